@@ -3,13 +3,30 @@
 //! Stores AI provider keys encrypted at rest. Validation and model discovery
 //! delegate to `tars_providers` for HTTP calls and to `tars_core::storage`
 //! for the cached model catalog.
+//!
+//! Provider ids are either a built-in `ProviderId` string or `custom:<slug>`
+//! for a user-defined OpenAI-compatible endpoint registered through
+//! [`save_custom_provider`]; [`resolve_provider`] turns either into a
+//! `Provider` impl.
 
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use tars_core::pricing::{load_effective_price, RequestTokens};
 use tars_core::storage::api_keys::{ApiKeyInput, ApiKeyRecord, ApiKeyStore};
+use tars_core::storage::balance_history::{
+    is_below_threshold, BalanceHistoryStore, BalanceSnapshot,
+};
+use tars_core::storage::custom_providers::{
+    CustomProvider, CustomProviderInput, CustomProviderStore,
+};
+use tars_core::storage::key_validations::KeyValidationStore;
 use tars_core::storage::model_cache::{CachedModel, ModelCache, ModelRow};
-use tars_providers::{all_metadata, provider_for, ProviderId};
+use tars_core::storage::Database;
+use tars_providers::{
+    all_metadata, custom_provider, custom_provider_slug, provider_for, CustomProviderConfig,
+    Provider, ProviderId, TokenUsage,
+};
 use tauri::State;
 
 use crate::state::AppState;
@@ -23,6 +40,12 @@ pub struct ApiKeySummaryResponse {
     pub last_validated_at: Option<String>,
     pub last_valid: Option<bool>,
     pub balance: Option<serde_json::Value>,
+    /// Normalised amount and currency from the latest balance snapshot
+    pub balance_amount: Option<f64>,
+    pub balance_currency: Option<String>,
+    pub balance_threshold: Option<f64>,
+    /// Latest amount is below `balance_threshold`
+    pub low_balance: bool,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -48,8 +71,12 @@ impl fmt::Debug for ApiKeyInputPayload {
     }
 }
 
-/// Static provider metadata surfaced to the UI
+/// Provider metadata surfaced to the UI.
+///
+/// `custom` is true for user-defined OpenAI-compatible providers, whose `id`
+/// is `custom:<slug>` and whose `docs_url` is the configured base URL.
 #[derive(Debug, Clone, Serialize)]
+#[allow(clippy::struct_excessive_bools)] // independent capability flags for the UI
 pub struct ProviderMetadataResponse {
     pub id: String,
     pub display_name: String,
@@ -57,6 +84,56 @@ pub struct ProviderMetadataResponse {
     pub key_format_hint: String,
     pub supports_models: bool,
     pub supports_balance: bool,
+    pub supports_completion: bool,
+    pub custom: bool,
+}
+
+impl From<&CustomProvider> for ProviderMetadataResponse {
+    fn from(p: &CustomProvider) -> Self {
+        let meta = tars_providers::metadata_for(ProviderId::Custom);
+        Self {
+            id: p.provider_id(),
+            display_name: p.display_name.clone(),
+            docs_url: p.base_url.clone(),
+            key_format_hint: meta.key_format_hint.to_string(),
+            supports_models: meta.supports_models,
+            supports_balance: meta.supports_balance,
+            supports_completion: meta.supports_completion,
+            custom: true,
+        }
+    }
+}
+
+/// Build the `tars_providers` connection settings for a stored custom
+/// provider.
+fn custom_config(p: &CustomProvider) -> CustomProviderConfig {
+    CustomProviderConfig {
+        display_name: p.display_name.clone(),
+        base_url: p.base_url.clone(),
+        auth: p.auth_style.clone(),
+        models_path: p.models_path.clone(),
+    }
+}
+
+/// Resolve a provider id string (built-in or `custom:<slug>`) to a
+/// `Provider` impl.
+pub(crate) fn resolve_provider(
+    db: &Database,
+    provider_id: &str,
+) -> Result<Box<dyn Provider>, String> {
+    if let Some(provider) = ProviderId::parse(provider_id).and_then(provider_for) {
+        return Ok(provider);
+    }
+    if custom_provider_slug(provider_id).is_some() {
+        let store = CustomProviderStore::new(db.connection());
+        if let Some(p) = store
+            .get_by_provider_id(provider_id)
+            .map_err(|e| format!("Failed to load custom provider: {e}"))?
+        {
+            return Ok(custom_provider(custom_config(&p)));
+        }
+    }
+    Err(format!("Unknown provider: {provider_id}"))
 }
 
 /// Result of a validation attempt.
@@ -72,12 +149,30 @@ pub struct ValidationResponse {
     pub message: Option<String>,
     #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub unverifiable: bool,
+    /// Set when the freshly fetched balance is below the key's threshold
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance_warning: Option<String>,
 }
 
-/// Static metadata for every supported provider
+/// Warning text for a balance below the key's threshold, if it is.
+fn low_balance_warning(
+    label: &str,
+    amount: f64,
+    currency: &str,
+    threshold: Option<f64>,
+) -> Option<String> {
+    let threshold = threshold?;
+    is_below_threshold(amount, Some(threshold)).then(|| {
+        format!("Balance for '{label}' is {amount:.2} {currency}, below the {threshold:.2} {currency} threshold")
+    })
+}
+
+/// Metadata for every built-in provider followed by the user-defined ones
 #[tauri::command]
-pub fn list_providers() -> Vec<ProviderMetadataResponse> {
-    all_metadata()
+pub async fn list_providers(
+    state: State<'_, AppState>,
+) -> Result<Vec<ProviderMetadataResponse>, String> {
+    let mut providers: Vec<ProviderMetadataResponse> = all_metadata()
         .into_iter()
         .map(|m| ProviderMetadataResponse {
             id: m.id.as_str().to_string(),
@@ -86,8 +181,62 @@ pub fn list_providers() -> Vec<ProviderMetadataResponse> {
             key_format_hint: m.key_format_hint.to_string(),
             supports_models: m.supports_models,
             supports_balance: m.supports_balance,
+            supports_completion: m.supports_completion,
+            custom: false,
         })
-        .collect()
+        .collect();
+    let custom = state.with_db(|db| {
+        CustomProviderStore::new(db.connection())
+            .list()
+            .map_err(|e| format!("Failed to list custom providers: {e}"))
+    })?;
+    providers.extend(custom.iter().map(ProviderMetadataResponse::from));
+    Ok(providers)
+}
+
+/// List user-defined OpenAI-compatible providers with their full settings.
+#[tauri::command]
+pub async fn list_custom_providers(
+    state: State<'_, AppState>,
+) -> Result<Vec<CustomProvider>, String> {
+    state.with_db(|db| {
+        CustomProviderStore::new(db.connection())
+            .list()
+            .map_err(|e| format!("Failed to list custom providers: {e}"))
+    })
+}
+
+/// Create a custom provider, or update the one with the same slug.
+#[tauri::command]
+pub async fn save_custom_provider(
+    input: CustomProviderInput,
+    state: State<'_, AppState>,
+) -> Result<CustomProvider, String> {
+    state.with_db(|db| {
+        let store = CustomProviderStore::new(db.connection());
+        let updated = store
+            .update(&input)
+            .map_err(|e| format!("Failed to save custom provider: {e}"))?;
+        match updated {
+            Some(p) => Ok(p),
+            None => store
+                .create(&input)
+                .map_err(|e| format!("Failed to save custom provider: {e}")),
+        }
+    })
+}
+
+/// Delete a custom provider along with its stored keys and cached models.
+#[tauri::command]
+pub async fn delete_custom_provider(
+    slug: String,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    state.with_db(|db| {
+        CustomProviderStore::new(db.connection())
+            .delete(&slug)
+            .map_err(|e| format!("Failed to delete custom provider: {e}"))
+    })
 }
 
 /// Add a new API key (encrypted before storage). Returns the new row id.
@@ -96,10 +245,6 @@ pub async fn add_api_key(
     input: ApiKeyInputPayload,
     state: State<'_, AppState>,
 ) -> Result<i64, String> {
-    if ProviderId::parse(&input.provider_id).is_none() {
-        return Err(format!("Unknown provider: {}", input.provider_id));
-    }
-
     let store_input = ApiKeyInput {
         provider_id: input.provider_id,
         label: input.label,
@@ -107,6 +252,7 @@ pub async fn add_api_key(
     };
 
     state.with_db(|db| {
+        resolve_provider(db, &store_input.provider_id)?;
         let store = ApiKeyStore::new(db.connection());
         store
             .save(&store_input)
@@ -121,22 +267,34 @@ pub async fn list_api_keys(
 ) -> Result<Vec<ApiKeySummaryResponse>, String> {
     state.with_db(|db| {
         let store = ApiKeyStore::new(db.connection());
+        let history = BalanceHistoryStore::new(db.connection());
         let keys = store
             .list()
             .map_err(|e| format!("Failed to list api keys: {e}"))?;
-        Ok(keys
-            .into_iter()
-            .map(|k| ApiKeySummaryResponse {
-                id: k.id,
-                provider_id: k.provider_id,
-                label: k.label,
-                last_validated_at: k.last_validated_at,
-                last_valid: k.last_valid,
-                balance: k.balance,
-                created_at: k.created_at,
-                updated_at: k.updated_at,
+        keys.into_iter()
+            .map(|k| {
+                let latest = history
+                    .latest(k.id)
+                    .map_err(|e| format!("Failed to load balance history: {e}"))?;
+                let low_balance = latest
+                    .as_ref()
+                    .is_some_and(|s| is_below_threshold(s.amount, k.balance_threshold));
+                Ok(ApiKeySummaryResponse {
+                    id: k.id,
+                    provider_id: k.provider_id,
+                    label: k.label,
+                    last_validated_at: k.last_validated_at,
+                    last_valid: k.last_valid,
+                    balance: k.balance,
+                    balance_amount: latest.as_ref().map(|s| s.amount),
+                    balance_currency: latest.map(|s| s.currency),
+                    balance_threshold: k.balance_threshold,
+                    low_balance,
+                    created_at: k.created_at,
+                    updated_at: k.updated_at,
+                })
             })
-            .collect())
+            .collect()
     })
 }
 
@@ -154,32 +312,37 @@ pub async fn delete_api_key(id: i64, state: State<'_, AppState>) -> Result<bool,
 /// Re-validate the stored key against the provider.
 ///
 /// Decrypts the stored key, calls the provider's `validate_key` endpoint,
-/// and — for providers that support it (currently `DeepSeek`) — also fetches
-/// the account balance on success. The validation outcome and balance are
-/// persisted via `ApiKeyStore::update_validation` so the UI sees a fresh
-/// `last_valid` and `balance`.
+/// and — for providers that support it (`DeepSeek`, `OpenRouter`,
+/// `ElevenLabs`) — also fetches the account balance on success. The
+/// validation outcome and balance are persisted via
+/// `ApiKeyStore::update_validation` so the UI sees a fresh `last_valid` and
+/// `balance`; each fetched balance is also appended to the key's snapshot
+/// history and checked against its low-balance threshold. The verdict and
+/// its latency are appended to the key's validation history.
 #[tauri::command]
 pub async fn validate_api_key(
     id: i64,
     state: State<'_, AppState>,
 ) -> Result<ValidationResponse, String> {
-    let record: ApiKeyRecord = state.with_db(|db| {
+    let (record, provider) = state.with_db(|db| {
         let store = ApiKeyStore::new(db.connection());
-        store
+        let record: ApiKeyRecord = store
             .get(id)
             .map_err(|e| format!("Failed to load api key: {e}"))?
-            .ok_or_else(|| format!("API key {id} not found"))
+            .ok_or_else(|| format!("API key {id} not found"))?;
+        let provider = resolve_provider(db, &record.provider_id).map_err(|_| {
+            format!(
+                "Unknown provider stored for key {id}: {}",
+                record.provider_id
+            )
+        })?;
+        Ok((record, provider))
     })?;
 
-    let provider_id = ProviderId::parse(&record.provider_id).ok_or_else(|| {
-        format!(
-            "Unknown provider stored for key {id}: {}",
-            record.provider_id
-        )
-    })?;
-    let provider = provider_for(provider_id);
-
-    let result = match provider.validate_key(&record.key).await {
+    let started = std::time::Instant::now();
+    let outcome = provider.validate_key(&record.key).await;
+    let latency_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+    let result = match outcome {
         Ok(r) => r,
         // Providers that expose no auth-check endpoint (e.g. Perplexity) short
         // circuit here: we preserve the existing `last_valid` state (no
@@ -193,6 +356,7 @@ pub async fn validate_api_key(
                         .to_string(),
                 ),
                 unverifiable: true,
+                balance_warning: None,
             });
         }
         Err(e) => return Err(format!("Validation failed: {e}")),
@@ -206,10 +370,15 @@ pub async fn validate_api_key(
     //     so the prior value is no longer trustworthy even though `validate`
     //     just succeeded (likely a race with revocation).
     //   - `Ok(None)`: provider explicitly reported no balance; clear.
+    let mut fetched = None;
     let balance_value = if result.valid {
         if provider.metadata().supports_balance {
             match provider.get_balance(&record.key).await {
-                Ok(Some(b)) => Some(b.raw),
+                Ok(Some(b)) => {
+                    let raw = b.raw.clone();
+                    fetched = Some(b);
+                    Some(raw)
+                }
                 Ok(None) | Err(tars_providers::ProviderError::Unauthorized { .. }) => None,
                 Err(_) => record.balance.clone(),
             }
@@ -225,13 +394,156 @@ pub async fn validate_api_key(
         store
             .update_validation(id, result.valid, balance_value.as_ref())
             .map(|_| ())
-            .map_err(|e| format!("Failed to persist validation: {e}"))
+            .map_err(|e| format!("Failed to persist validation: {e}"))?;
+        KeyValidationStore::new(db.connection())
+            .record(
+                id,
+                &record.provider_id,
+                result.valid,
+                result.message.as_deref(),
+                latency_ms,
+                Utc::now(),
+            )
+            .map(|_| ())
+            .map_err(|e| format!("Failed to record validation history: {e}"))?;
+        if let Some(b) = &fetched {
+            BalanceHistoryStore::new(db.connection())
+                .record(id, &record.provider_id, &b.currency, b.amount, Utc::now())
+                .map(|_| ())
+                .map_err(|e| format!("Failed to record balance snapshot: {e}"))?;
+        }
+        Ok(())
     })?;
 
     Ok(ValidationResponse {
         valid: result.valid,
         message: result.message,
         unverifiable: false,
+        balance_warning: fetched.and_then(|b| {
+            low_balance_warning(
+                &record.label,
+                b.amount,
+                &b.currency,
+                record.balance_threshold,
+            )
+        }),
+    })
+}
+
+/// Prompt sent by [`test_api_key_completion`] when the caller gives none
+const DEFAULT_TEST_PROMPT: &str = "Reply with the single word: pong";
+
+/// Output cap for [`test_api_key_completion`]; small enough that a test
+/// costs a fraction of a cent on any model
+const DEFAULT_TEST_MAX_TOKENS: u32 = 16;
+
+/// Result of a one-shot completion against a key and model.
+#[derive(Debug, Clone, Serialize)]
+pub struct CompletionTestResponse {
+    /// Model that answered, as reported by the provider
+    pub model: String,
+    pub text: String,
+    pub usage: TokenUsage,
+    pub latency_ms: u64,
+    /// USD cost from the pricing cache; `None` when the model has no cached
+    /// input and output price
+    pub cost_usd: Option<f64>,
+    /// True when the price came from a user override
+    pub price_overridden: bool,
+}
+
+/// Send a short prompt to `model` with the stored key to verify the pair end
+/// to end, and price the reply's actual token usage.
+#[tauri::command]
+pub async fn test_api_key_completion(
+    id: i64,
+    model: String,
+    prompt: Option<String>,
+    max_tokens: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<CompletionTestResponse, String> {
+    let (record, provider) = state.with_db(|db| {
+        let record: ApiKeyRecord = ApiKeyStore::new(db.connection())
+            .get(id)
+            .map_err(|e| format!("Failed to load api key: {e}"))?
+            .ok_or_else(|| format!("API key {id} not found"))?;
+        let provider = resolve_provider(db, &record.provider_id)?;
+        Ok((record, provider))
+    })?;
+
+    let prompt = prompt.unwrap_or_else(|| DEFAULT_TEST_PROMPT.to_string());
+    let completion = match provider
+        .complete(
+            &record.key,
+            &model,
+            &prompt,
+            max_tokens.unwrap_or(DEFAULT_TEST_MAX_TOKENS),
+        )
+        .await
+    {
+        Ok(c) => c,
+        Err(tars_providers::ProviderError::Unsupported) => {
+            return Err("This provider does not support test completions".to_string());
+        }
+        Err(e) => return Err(format!("Completion failed: {e}")),
+    };
+
+    // The pricing cache is keyed by the ids `list_models` returned, which
+    // may be the alias that was requested or the dated snapshot that
+    // answered; try both.
+    let price = state.with_db(|db| {
+        for candidate in [model.as_str(), completion.model.as_str()] {
+            if let Some(p) = load_effective_price(db.connection(), &record.provider_id, candidate)
+                .map_err(|e| format!("Failed to load price: {e}"))?
+            {
+                return Ok(Some(p));
+            }
+        }
+        Ok(None)
+    })?;
+    let usage = completion.usage;
+    let tokens = RequestTokens {
+        input: usage.input_tokens,
+        output: usage.output_tokens,
+        cache_read: usage.cache_read_tokens,
+        cache_write: usage.cache_write_tokens,
+    };
+
+    Ok(CompletionTestResponse {
+        model: completion.model,
+        text: completion.text,
+        usage,
+        latency_ms: completion.latency_ms,
+        cost_usd: price.and_then(|p| p.request_cost(tokens)),
+        price_overridden: price.is_some_and(|p| p.is_overridden),
+    })
+}
+
+/// Balance snapshots for one key, oldest first, for the spend chart.
+#[tauri::command]
+pub async fn get_balance_history(
+    id: i64,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<BalanceSnapshot>, String> {
+    state.with_db(|db| {
+        BalanceHistoryStore::new(db.connection())
+            .list_for_key(id, limit.unwrap_or(90))
+            .map_err(|e| format!("Failed to load balance history: {e}"))
+    })
+}
+
+/// Set or clear (`None`) the low-balance warning threshold for a key.
+#[tauri::command]
+pub async fn set_balance_threshold(
+    id: i64,
+    threshold: Option<f64>,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    state.with_db(|db| {
+        ApiKeyStore::new(db.connection())
+            .set_balance_threshold(id, threshold)
+            .map_err(|e| format!("Failed to set balance threshold: {e}"))
     })
 }
 
@@ -292,10 +604,8 @@ pub async fn list_provider_models(
     provider_id: String,
     state: State<'_, AppState>,
 ) -> Result<Vec<CachedModelResponse>, String> {
-    if ProviderId::parse(&provider_id).is_none() {
-        return Err(format!("Unknown provider: {provider_id}"));
-    }
     state.with_db(|db| {
+        resolve_provider(db, &provider_id)?;
         let cache = ModelCache::new(db.connection());
         let rows = cache
             .list_for_provider(&provider_id)
@@ -317,12 +627,10 @@ pub async fn refresh_models(
     provider_id: String,
     state: State<'_, AppState>,
 ) -> Result<usize, String> {
-    let pid = ProviderId::parse(&provider_id)
-        .ok_or_else(|| format!("Unknown provider: {provider_id}"))?;
-
     // Pull all keys for this provider, then pick one: prefer last_valid=true,
     // otherwise fall back to the first key stored.
-    let record: ApiKeyRecord = state.with_db(|db| {
+    let (record, provider) = state.with_db(|db| {
+        let provider = resolve_provider(db, &provider_id)?;
         let store = ApiKeyStore::new(db.connection());
         let summaries = store
             .list_by_provider(&provider_id)
@@ -336,13 +644,13 @@ pub async fn refresh_models(
             .iter()
             .find(|s| s.last_valid == Some(true))
             .unwrap_or(&summaries[0]);
-        store
+        let record: ApiKeyRecord = store
             .get(chosen.id)
             .map_err(|e| format!("Failed to load api key: {e}"))?
-            .ok_or_else(|| format!("API key {} vanished between list and get", chosen.id))
+            .ok_or_else(|| format!("API key {} vanished between list and get", chosen.id))?;
+        Ok((record, provider))
     })?;
 
-    let provider = provider_for(pid);
     let models = provider
         .list_models(&record.key)
         .await
@@ -360,11 +668,10 @@ pub async fn refresh_models(
         .collect();
 
     let now = Utc::now();
-    let provider_key = pid.as_str().to_string();
     let written = state.with_db(|db| {
         let cache = ModelCache::new(db.connection());
         cache
-            .upsert_all(&provider_key, &rows, now)
+            .upsert_all(&provider_id, &rows, now)
             .map_err(|e| format!("Failed to cache models: {e}"))
     })?;
 
@@ -381,6 +688,7 @@ pub async fn refresh_models(
 mod tests {
     use super::*;
     use chrono::TimeZone;
+    use tars_providers::AuthStyle;
 
     #[test]
     fn cached_model_response_from_cached_model_preserves_fields() {
@@ -431,8 +739,10 @@ mod tests {
             valid: true,
             message: None,
             unverifiable: false,
+            balance_warning: None,
         };
         let json = serde_json::to_value(&resp).unwrap();
+        assert!(json.get("balance_warning").is_none());
         assert!(
             json.get("unverifiable").is_none(),
             "unverifiable=false must be omitted so old clients ignore it"
@@ -446,6 +756,7 @@ mod tests {
             valid: false,
             message: Some("not checked".into()),
             unverifiable: true,
+            balance_warning: None,
         };
         let json = serde_json::to_value(&resp).unwrap();
         assert_eq!(json["unverifiable"], true);
@@ -453,6 +764,16 @@ mod tests {
         assert_eq!(json["message"], "not checked");
     }
 
+    #[test]
+    fn low_balance_warning_only_below_threshold() {
+        assert!(low_balance_warning("work", 2.0, "USD", None).is_none());
+        assert!(low_balance_warning("work", 12.0, "USD", Some(5.0)).is_none());
+        let msg = low_balance_warning("work", 2.5, "USD", Some(5.0)).unwrap();
+        assert!(msg.contains("'work'"));
+        assert!(msg.contains("2.50 USD"));
+        assert!(msg.contains("5.00 USD"));
+    }
+
     #[test]
     fn cached_model_response_serializes_to_camel_case_compatible_json() {
         // The frontend expects snake_case on the wire (matches the existing
@@ -472,4 +793,69 @@ mod tests {
         assert_eq!(json["context_window"], 8000);
         assert!(json["display_name"].is_null());
     }
+
+    fn gateway() -> CustomProvider {
+        let at = Utc.with_ymd_and_hms(2026, 10, 1, 9, 0, 0).unwrap();
+        CustomProvider {
+            id: 1,
+            slug: "gateway".into(),
+            display_name: "Team Gateway".into(),
+            base_url: "https://llm.internal/v1".into(),
+            auth_style: AuthStyle::Header {
+                name: "x-api-key".into(),
+            },
+            models_path: Some("/v1/models".into()),
+            created_at: at,
+            updated_at: at,
+        }
+    }
+
+    #[test]
+    fn custom_provider_metadata_uses_prefixed_id() {
+        let meta = ProviderMetadataResponse::from(&gateway());
+        assert_eq!(meta.id, "custom:gateway");
+        assert_eq!(meta.display_name, "Team Gateway");
+        assert_eq!(meta.docs_url, "https://llm.internal/v1");
+        assert!(meta.custom);
+        assert!(meta.supports_models);
+        assert!(!meta.supports_balance);
+    }
+
+    #[test]
+    fn custom_config_carries_auth_and_models_path() {
+        let config = custom_config(&gateway());
+        assert_eq!(
+            config.auth,
+            AuthStyle::Header {
+                name: "x-api-key".into()
+            }
+        );
+        assert_eq!(config.models_path.as_deref(), Some("/v1/models"));
+        assert_eq!(config.base_url, "https://llm.internal/v1");
+    }
+
+    #[test]
+    fn resolve_provider_handles_builtin_custom_and_unknown() {
+        let db = Database::in_memory().unwrap();
+        CustomProviderStore::new(db.connection())
+            .create(&CustomProviderInput {
+                slug: "ollama".into(),
+                display_name: "Ollama".into(),
+                base_url: "http://localhost:11434/v1".into(),
+                auth_style: AuthStyle::None,
+                models_path: None,
+            })
+            .unwrap();
+
+        assert_eq!(
+            resolve_provider(&db, "groq").unwrap().id(),
+            ProviderId::Groq
+        );
+        assert_eq!(
+            resolve_provider(&db, "custom:ollama").unwrap().id(),
+            ProviderId::Custom
+        );
+        assert!(resolve_provider(&db, "custom:missing").is_err());
+        assert!(resolve_provider(&db, "custom").is_err());
+    }
 }
@@ -4,11 +4,14 @@
 
 use crate::state::AppState;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tars_core::backup::restore::{restore_from_backup, verify_backup_integrity};
 use tars_core::diff::display::{format_plan_terminal, DiffSummary};
-use tars_core::diff::plan::generate_plan;
-use tars_core::storage::{BackupStore, ProfileStore, ProjectStore};
+use tars_core::diff::plan::generate_plan_with_context;
+use tars_core::profile::{
+    lock_plan, preview_lock_plan, resolve_profile, TemplateContext, LOCK_FILE,
+};
+use tars_core::storage::{BackupStore, MetadataStore, ProfileStore, ProjectStore};
 use tars_core::{apply::apply_operations, Backup};
 use tauri::State;
 
@@ -59,6 +62,7 @@ impl From<tars_core::storage::backups::BackupSummary> for BackupInfo {
 pub async fn preview_apply(
     profile_id: String,
     project_path: String,
+    write_lock: Option<bool>,
     state: State<'_, AppState>,
 ) -> Result<DiffPreview, String> {
     let uuid = uuid::Uuid::parse_str(&profile_id).map_err(|e| format!("Invalid UUID: {e}"))?;
@@ -78,13 +82,20 @@ pub async fn preview_apply(
             .ok_or_else(|| "Bundle not found".to_string())?;
 
         // Get or create project ID
-        let project_id = match projects.get_by_path(&path) {
-            Ok(Some(p)) => p.id,
-            _ => uuid::Uuid::new_v4(), // Temporary ID for preview
+        let (project_id, context) = match projects.get_by_path(&path) {
+            Ok(Some(p)) => (p.id, template_context(db.connection(), &p)?),
+            _ => (uuid::Uuid::new_v4(), TemplateContext::new(&path)), // Temporary ID for preview
         };
 
-        let plan = generate_plan(project_id, &path, &profile)
+        let resolved = resolve_profile(&profiles, &profile)
+            .map_err(|e| format!("Failed to resolve bundle: {e}"))?;
+        let mut plan = generate_plan_with_context(project_id, &path, &resolved.profile, &context)
             .map_err(|e| format!("Failed to generate plan: {e}"))?;
+        // A preview names the revision without recording it
+        if wants_lock(&path, write_lock) {
+            preview_lock_plan(db.connection(), &path, &resolved, &mut plan)
+                .map_err(|e| format!("Failed to plan lock: {e}"))?;
+        }
 
         let operations: Vec<OperationPreview> = plan
             .operations
@@ -129,6 +140,7 @@ pub async fn preview_apply(
 pub async fn apply_profile(
     profile_id: String,
     project_path: String,
+    write_lock: Option<bool>,
     state: State<'_, AppState>,
 ) -> Result<BackupInfo, String> {
     let uuid = uuid::Uuid::parse_str(&profile_id).map_err(|e| format!("Invalid UUID: {e}"))?;
@@ -166,8 +178,15 @@ pub async fn apply_profile(
             p
         };
 
-        let plan = generate_plan(project.id, &path, &profile)
+        let resolved = resolve_profile(&profiles, &profile)
+            .map_err(|e| format!("Failed to resolve bundle: {e}"))?;
+        let context = template_context(db.connection(), &project)?;
+        let mut plan = generate_plan_with_context(project.id, &path, &resolved.profile, &context)
             .map_err(|e| format!("Failed to generate plan: {e}"))?;
+        if wants_lock(&path, write_lock) {
+            lock_plan(db.connection(), &path, &resolved, &mut plan)
+                .map_err(|e| format!("Failed to write lock: {e}"))?;
+        }
 
         if plan.is_empty() {
             return Err("No changes needed - project already matches bundle.".to_string());
@@ -256,3 +275,20 @@ pub async fn rollback(
         Ok(files_count)
     })
 }
+
+/// Template variables for a registered project, including its stored metadata
+fn template_context(
+    conn: &rusqlite::Connection,
+    project: &tars_core::Project,
+) -> Result<TemplateContext, String> {
+    let metadata = MetadataStore::new(conn)
+        .get(project.id)
+        .map_err(|e| format!("Database error: {e}"))?;
+    Ok(TemplateContext::for_project(project, metadata))
+}
+
+/// Whether to add `.claude/tars.lock` to the plan: when asked, or when the
+/// project already has one
+fn wants_lock(path: &Path, write_lock: Option<bool>) -> bool {
+    write_lock.unwrap_or(false) || path.join(LOCK_FILE).exists()
+}
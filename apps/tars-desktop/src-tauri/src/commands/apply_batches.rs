@@ -0,0 +1,111 @@
+//! Batch apply Tauri commands
+//!
+//! Commands for applying a bundle to every project assigned to it and for
+//! rolling a whole batch back.
+
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use tars_core::diff::display::DiffSummary;
+use tars_core::profile::{apply_batch, plan_batch, rollback_batch, BatchPlan, BatchRollback};
+use tars_core::storage::{ApplyBatch, ApplyBatchStore};
+use tauri::State;
+
+/// What a batch apply would do to one project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchProjectPreview {
+    pub project_id: String,
+    pub project_name: String,
+    pub project_path: String,
+    pub summary: DiffSummary,
+    pub has_changes: bool,
+    /// Why no plan could be generated for the project
+    pub error: Option<String>,
+}
+
+/// What a batch apply would do across all assigned projects
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchPreview {
+    pub profile_id: String,
+    pub profile_name: String,
+    /// Changes across all projects
+    pub summary: DiffSummary,
+    /// Projects that would change
+    pub changed: usize,
+    /// Projects that could not be planned
+    pub failed: usize,
+    pub projects: Vec<BatchProjectPreview>,
+}
+
+impl From<&BatchPlan> for BatchPreview {
+    fn from(plan: &BatchPlan) -> Self {
+        Self {
+            profile_id: plan.profile_id.to_string(),
+            profile_name: plan.profile_name.clone(),
+            summary: plan.summary,
+            changed: plan.changed(),
+            failed: plan.failed(),
+            projects: plan
+                .projects
+                .iter()
+                .map(|p| BatchProjectPreview {
+                    project_id: p.project.id.to_string(),
+                    project_name: p.project.name.clone(),
+                    project_path: p.project.path.display().to_string(),
+                    summary: p.summary,
+                    has_changes: p.has_changes(),
+                    error: p.error.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Preview applying a bundle to every project assigned to it
+#[tauri::command]
+pub async fn preview_batch_apply(
+    profile_id: String,
+    state: State<'_, AppState>,
+) -> Result<BatchPreview, String> {
+    let uuid = uuid::Uuid::parse_str(&profile_id).map_err(|e| format!("Invalid UUID: {e}"))?;
+    state.with_db(|db| {
+        let plan = plan_batch(db.connection(), uuid).map_err(|e| e.to_string())?;
+        Ok(BatchPreview::from(&plan))
+    })
+}
+
+/// Apply a bundle to every project assigned to it, backing each one up
+#[tauri::command]
+pub async fn apply_bundle_batch(
+    profile_id: String,
+    state: State<'_, AppState>,
+) -> Result<ApplyBatch, String> {
+    let uuid = uuid::Uuid::parse_str(&profile_id).map_err(|e| format!("Invalid UUID: {e}"))?;
+    let backup_dir = state.data_dir().join("backups");
+    state.with_db(|db| {
+        let plan = plan_batch(db.connection(), uuid).map_err(|e| e.to_string())?;
+        apply_batch(db.connection(), &plan, &backup_dir)
+            .map_err(|e| format!("Failed to apply batch: {e}"))
+    })
+}
+
+/// List batch applies, newest first
+#[tauri::command]
+pub async fn list_apply_batches(state: State<'_, AppState>) -> Result<Vec<ApplyBatch>, String> {
+    state.with_db(|db| {
+        ApplyBatchStore::new(db.connection())
+            .list()
+            .map_err(|e| format!("Database error: {e}"))
+    })
+}
+
+/// Restore every project a batch changed
+#[tauri::command]
+pub async fn rollback_apply_batch(
+    batch_id: String,
+    state: State<'_, AppState>,
+) -> Result<BatchRollback, String> {
+    let uuid = uuid::Uuid::parse_str(&batch_id).map_err(|e| format!("Invalid batch ID: {e}"))?;
+    state.with_db(|db| {
+        rollback_batch(db.connection(), uuid).map_err(|e| format!("Failed to roll back: {e}"))
+    })
+}
@@ -0,0 +1,254 @@
+//! Bundle assignment rule commands
+//!
+//! Commands for managing the rules that pick a bundle for newly discovered
+//! or added projects, and for applying the bundle a rule proposes.
+
+use super::utils::find_claude_binary;
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tars_core::diff::display::DiffSummary;
+use tars_core::profile::{
+    apply_proposal, matching_rules, propose_assignment, validate_rule, AppliedProposal,
+    AssignmentProposal, ProjectFacts, RuleMatch,
+};
+use tars_core::storage::{
+    AssignmentRule, AssignmentRuleStore, Database, ProfileStore, ProjectStore, RuleConditions,
+};
+use tars_core::Project;
+use tauri::State;
+
+/// A bundle a rule proposes for a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleSuggestion {
+    pub rule_id: String,
+    pub rule_name: String,
+    pub profile_id: String,
+    pub profile_name: String,
+    pub auto_apply: bool,
+    /// Satisfied conditions, e.g. "language Rust"
+    pub reasons: Vec<String>,
+}
+
+/// Outcome of evaluating the rules for a registered project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleAssignment {
+    pub suggestion: RuleSuggestion,
+    /// One-line summary of the changes the bundle makes
+    pub summary: String,
+    /// Whether the bundle was applied and assigned
+    pub applied: bool,
+    pub backup_id: Option<String>,
+    /// Why auto-apply failed, if it did
+    pub error: Option<String>,
+    /// Plugins of the bundle that failed to install, as `id: error`
+    pub plugin_errors: Vec<String>,
+}
+
+impl From<&AssignmentProposal> for RuleSuggestion {
+    fn from(proposal: &AssignmentProposal) -> Self {
+        Self {
+            rule_id: proposal.rule.id.to_string(),
+            rule_name: proposal.rule.name.clone(),
+            profile_id: proposal.profile_id.to_string(),
+            profile_name: proposal.profile_name.clone(),
+            auto_apply: proposal.rule.auto_apply,
+            reasons: proposal.reasons.clone(),
+        }
+    }
+}
+
+/// List assignment rules, highest priority first
+#[tauri::command]
+pub async fn list_assignment_rules(
+    state: State<'_, AppState>,
+) -> Result<Vec<AssignmentRule>, String> {
+    state.with_db(|db| {
+        AssignmentRuleStore::new(db.connection())
+            .list()
+            .map_err(|e| format!("Database error: {e}"))
+    })
+}
+
+/// Create an assignment rule
+#[tauri::command]
+pub async fn create_assignment_rule(
+    name: String,
+    profile_id: String,
+    conditions: RuleConditions,
+    priority: Option<i64>,
+    auto_apply: bool,
+    state: State<'_, AppState>,
+) -> Result<AssignmentRule, String> {
+    let profile_uuid =
+        uuid::Uuid::parse_str(&profile_id).map_err(|e| format!("Invalid bundle ID: {e}"))?;
+    let mut rule = AssignmentRule::new(name, profile_uuid, conditions);
+    rule.priority = priority.unwrap_or(0);
+    rule.auto_apply = auto_apply;
+    validate_rule(&rule).map_err(|e| e.to_string())?;
+
+    state.with_db(|db| {
+        AssignmentRuleStore::new(db.connection())
+            .create(&rule)
+            .map_err(|e| format!("Failed to create rule: {e}"))?;
+        Ok(rule)
+    })
+}
+
+/// Update an assignment rule
+#[tauri::command]
+pub async fn update_assignment_rule(
+    rule: AssignmentRule,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    validate_rule(&rule).map_err(|e| e.to_string())?;
+    state.with_db(|db| {
+        AssignmentRuleStore::new(db.connection())
+            .update(&rule)
+            .map_err(|e| format!("Failed to update rule: {e}"))
+    })
+}
+
+/// Delete an assignment rule
+#[tauri::command]
+pub async fn delete_assignment_rule(
+    rule_id: String,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    let uuid = uuid::Uuid::parse_str(&rule_id).map_err(|e| format!("Invalid rule ID: {e}"))?;
+    state.with_db(|db| {
+        AssignmentRuleStore::new(db.connection())
+            .delete(uuid)
+            .map_err(|e| format!("Failed to delete rule: {e}"))
+    })
+}
+
+/// Show the bundle the rules propose for a registered project
+#[tauri::command]
+pub async fn propose_project_assignment(
+    project_id: String,
+    state: State<'_, AppState>,
+) -> Result<Option<RuleAssignment>, String> {
+    let uuid = uuid::Uuid::parse_str(&project_id).map_err(|e| format!("Invalid UUID: {e}"))?;
+    state.with_db(|db| {
+        let project = find_project(db, uuid)?;
+        let proposal = propose_assignment(db.connection(), &project)
+            .map_err(|e| format!("Failed to evaluate rules: {e}"))?;
+        Ok(proposal.map(|p| rule_assignment(&p, false, None, None)))
+    })
+}
+
+/// Apply and assign the bundle the rules propose for a project
+#[tauri::command]
+pub async fn apply_project_assignment(
+    project_id: String,
+    state: State<'_, AppState>,
+) -> Result<RuleAssignment, String> {
+    let uuid = uuid::Uuid::parse_str(&project_id).map_err(|e| format!("Invalid UUID: {e}"))?;
+    let backup_dir = state.data_dir().join("backups");
+    let claude = find_claude_binary().ok();
+    state.with_db(|db| {
+        let mut project = find_project(db, uuid)?;
+        let proposal = propose_assignment(db.connection(), &project)
+            .map_err(|e| format!("Failed to evaluate rules: {e}"))?
+            .ok_or_else(|| "No rule matches this project".to_string())?;
+        let applied = apply_proposal(
+            db.connection(),
+            &mut project,
+            &proposal,
+            &backup_dir,
+            claude.as_deref(),
+        )
+        .map_err(|e| format!("Failed to apply bundle: {e}"))?;
+        Ok(applied_assignment(&proposal, &applied))
+    })
+}
+
+/// Apply the bundle a rule proposes for a newly added project if the rule
+/// says so
+///
+/// Apply failures are reported in the result rather than failing the add.
+pub(crate) fn assign_new_project(
+    db: &Database,
+    project: &mut Project,
+    proposal: &AssignmentProposal,
+    backup_dir: &Path,
+) -> RuleAssignment {
+    if !proposal.rule.auto_apply {
+        return rule_assignment(proposal, false, None, None);
+    }
+    let claude = find_claude_binary().ok();
+    match apply_proposal(
+        db.connection(),
+        project,
+        proposal,
+        backup_dir,
+        claude.as_deref(),
+    ) {
+        Ok(applied) => applied_assignment(proposal, &applied),
+        Err(e) => rule_assignment(proposal, false, None, Some(e.to_string())),
+    }
+}
+
+/// The bundle the rules would propose for an unregistered project
+pub(crate) fn suggest_for_path(db: &Database, path: &Path) -> Option<RuleSuggestion> {
+    let rules = AssignmentRuleStore::new(db.connection()).list().ok()?;
+    if !rules.iter().any(|r| r.enabled) {
+        return None;
+    }
+    let facts = ProjectFacts::detect(path, None);
+    let profiles = ProfileStore::new(db.connection());
+    matching_rules(&rules, &facts)
+        .into_iter()
+        .find_map(|RuleMatch { rule, reasons }| {
+            let profile = profiles.get(rule.profile_id).ok()??;
+            Some(RuleSuggestion {
+                rule_id: rule.id.to_string(),
+                rule_name: rule.name,
+                profile_id: profile.id.to_string(),
+                profile_name: profile.name,
+                auto_apply: rule.auto_apply,
+                reasons,
+            })
+        })
+}
+
+fn applied_assignment(proposal: &AssignmentProposal, applied: &AppliedProposal) -> RuleAssignment {
+    RuleAssignment {
+        plugin_errors: applied
+            .assignment
+            .plugin_errors
+            .iter()
+            .map(|(id, e)| format!("{id}: {e}"))
+            .collect(),
+        ..rule_assignment(
+            proposal,
+            true,
+            applied.backup.as_ref().map(|b| b.id.to_string()),
+            None,
+        )
+    }
+}
+
+fn rule_assignment(
+    proposal: &AssignmentProposal,
+    applied: bool,
+    backup_id: Option<String>,
+    error: Option<String>,
+) -> RuleAssignment {
+    RuleAssignment {
+        suggestion: RuleSuggestion::from(proposal),
+        summary: DiffSummary::from_plan(&proposal.plan).one_line(),
+        applied,
+        backup_id,
+        error,
+        plugin_errors: Vec::new(),
+    }
+}
+
+fn find_project(db: &Database, id: uuid::Uuid) -> Result<Project, String> {
+    ProjectStore::new(db.connection())
+        .get(id)
+        .map_err(|e| format!("Database error: {e}"))?
+        .ok_or_else(|| "Project not found".to_string())
+}
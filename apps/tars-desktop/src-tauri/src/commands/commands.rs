@@ -169,8 +169,8 @@ Add your command instructions here.
                 .update(&profile)
                 .map_err(|e| format!("Failed to update bundle: {e}"))?;
 
-            regenerate_profile_plugin(&profile).map_err(|e| e.to_string())?;
-            sync_profile_marketplace(&profile).map_err(|e| e.to_string())?;
+            regenerate_profile_plugin(db.connection(), &profile).map_err(|e| e.to_string())?;
+            sync_profile_marketplace(db.connection(), &profile).map_err(|e| e.to_string())?;
 
             Ok(())
         })?;
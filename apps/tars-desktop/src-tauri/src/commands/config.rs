@@ -9,8 +9,8 @@ use tauri::State;
 
 use tars_core::backup::restore::restore_from_backup;
 use tars_core::config::{
-    ConfigError, ConfigItemData, ConfigScope, McpOps, McpServerConfig, McpServerUpdate,
-    McpTransport,
+    ConfigError, ConfigItemData, ConfigScope, McpOps, McpRefresher, McpServerConfig,
+    McpServerUpdate, McpTransport,
 };
 use tars_core::storage::BackupStore;
 use uuid::Uuid;
@@ -83,14 +83,23 @@ pub struct McpRefreshResult {
     pub success: bool,
     #[serde(rename = "serverName")]
     pub server_name: String,
+    /// Strategy id (`npm_install`, `docker_pull`, `cargo_install`, ...);
+    /// `unknown` when no strategy matched.
     #[serde(rename = "refreshType")]
-    pub refresh_type: String, // "npm_install", "git_pull", "npx_skip", "unknown"
+    pub refresh_type: String,
+    /// What the strategy did (`git_pull`, `uv_cache_clean`, ...). Actions
+    /// that have nothing to run end in `_skip`; `unknown` when no strategy
+    /// matched.
+    #[serde(rename = "refreshAction")]
+    pub refresh_action: String,
     #[serde(rename = "commandRun", skip_serializing_if = "Option::is_none")]
     pub command_run: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub output: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    #[serde(rename = "dryRun")]
+    pub dry_run: bool,
 }
 
 /// List all MCP servers
@@ -526,91 +535,17 @@ pub async fn config_rollback(
 // MCP Refresh Command
 // ============================================================================
 
-/// Strategy for refreshing an MCP server
-#[derive(Debug)]
-enum McpRefreshStrategy {
-    /// npx always fetches the latest version
-    NpxSkip,
-    /// Run `npm install` in the given directory
-    NpmInstall(PathBuf),
-    /// Run `git pull` then `npm install` in the given directory
-    GitPullNpmInstall(PathBuf),
-    /// Unknown server type - cannot refresh
-    Unknown,
-}
-
-/// Detect the refresh strategy based on command and args
-fn detect_refresh_strategy(command: Option<&str>, args: &[String]) -> McpRefreshStrategy {
-    let cmd = match command {
-        Some(c) => c,
-        None => return McpRefreshStrategy::Unknown,
-    };
-
-    // Check for npx command
-    if cmd == "npx" || cmd.ends_with("/npx") {
-        return McpRefreshStrategy::NpxSkip;
-    }
-
-    // Check for node running a local file
-    if cmd == "node" || cmd.ends_with("/node") {
-        if let Some(script_path) = args.first() {
-            let path = PathBuf::from(script_path);
-            if path.is_absolute() {
-                // Find the directory containing package.json
-                if let Some(project_dir) = find_npm_project_dir(&path) {
-                    // Check if it's a git repo
-                    if project_dir.join(".git").exists() {
-                        return McpRefreshStrategy::GitPullNpmInstall(project_dir);
-                    }
-                    return McpRefreshStrategy::NpmInstall(project_dir);
-                }
-            }
-        }
-        return McpRefreshStrategy::Unknown;
-    }
-
-    // Check for absolute path to a binary
-    let cmd_path = PathBuf::from(cmd);
-    if cmd_path.is_absolute() && cmd_path.exists() {
-        // Find the directory containing package.json
-        if let Some(project_dir) = find_npm_project_dir(&cmd_path) {
-            // Check if it's a git repo
-            if project_dir.join(".git").exists() {
-                return McpRefreshStrategy::GitPullNpmInstall(project_dir);
-            }
-            return McpRefreshStrategy::NpmInstall(project_dir);
-        }
-    }
-
-    McpRefreshStrategy::Unknown
-}
-
-/// Find the nearest directory containing package.json by walking up from the given path
-fn find_npm_project_dir(start_path: &PathBuf) -> Option<PathBuf> {
-    let mut current = if start_path.is_file() {
-        start_path.parent()?.to_path_buf()
-    } else {
-        start_path.clone()
-    };
-
-    // Walk up to 5 levels to find package.json
-    for _ in 0..5 {
-        if current.join("package.json").exists() {
-            return Some(current);
-        }
-        current = current.parent()?.to_path_buf();
-    }
-
-    None
-}
-
-/// Refresh an MCP server by running the appropriate update command
+/// Refresh an MCP server by running the appropriate update command.
+///
+/// With `dry_run`, only reports the commands that would run.
 #[tauri::command]
 pub async fn mcp_refresh(
     name: String,
     project_path: Option<String>,
+    dry_run: Option<bool>,
 ) -> Result<McpRefreshResult, String> {
     let ops = McpOps::new(project_path.map(PathBuf::from));
+    let dry_run = dry_run.unwrap_or(false);
 
     // Find the server by name
     let items = ops.list().map_err(|e| e.to_string())?;
@@ -619,166 +554,54 @@ pub async fn mcp_refresh(
         .find(|item| item.name == name)
         .ok_or_else(|| format!("MCP server '{name}' not found"))?;
 
-    // Extract command and args from the config
-    let (command, args) = if let ConfigItemData::McpServer(config) = &server.config {
-        (config.command.clone(), config.args.clone())
-    } else {
+    let ConfigItemData::McpServer(config) = &server.config else {
         return Err("Invalid server configuration".to_string());
     };
 
-    // Detect refresh strategy
-    let strategy = detect_refresh_strategy(command.as_deref(), &args);
-
-    match strategy {
-        McpRefreshStrategy::NpxSkip => Ok(McpRefreshResult {
-            success: true,
-            server_name: name,
-            refresh_type: "npx_skip".to_string(),
-            command_run: None,
-            output: Some("npx always fetches the latest version - no refresh needed".to_string()),
-            error: None,
-        }),
-
-        McpRefreshStrategy::NpmInstall(dir) => {
-            let output = run_command_in_dir("npm", &["install"], &dir)?;
-            Ok(McpRefreshResult {
-                success: true,
-                server_name: name,
-                refresh_type: "npm_install".to_string(),
-                command_run: Some(format!("npm install (in {})", dir.display())),
-                output: Some(output),
-                error: None,
-            })
-        }
-
-        McpRefreshStrategy::GitPullNpmInstall(dir) => {
-            // First try git pull
-            let git_output = match run_command_in_dir("git", &["pull"], &dir) {
-                Ok(out) => out,
-                Err(e) => format!("git pull failed: {e}"),
-            };
-
-            // Then run npm install regardless of git pull result
-            let npm_output = run_command_in_dir("npm", &["install"], &dir)?;
-
-            let combined_output = format!("=== git pull ===\n{git_output}\n\n=== npm install ===\n{npm_output}");
-
-            Ok(McpRefreshResult {
-                success: true,
-                server_name: name,
-                refresh_type: "git_pull".to_string(),
-                command_run: Some(format!("git pull && npm install (in {})", dir.display())),
-                output: Some(combined_output),
-                error: None,
-            })
-        }
-
-        McpRefreshStrategy::Unknown => Ok(McpRefreshResult {
+    let Some(plan) = McpRefresher::new().plan(config) else {
+        return Ok(McpRefreshResult {
             success: false,
             server_name: name,
             refresh_type: "unknown".to_string(),
+            refresh_action: "unknown".to_string(),
             command_run: None,
             output: None,
-            error: Some("Cannot determine how to refresh this MCP server. Only local Node.js projects are supported.".to_string()),
-        }),
-    }
-}
-
-/// Resolve the full path to a command by checking common locations
-/// This is needed because GUI apps on macOS don't inherit shell PATH
-fn resolve_command(cmd: &str) -> String {
-    // If it's already an absolute path, use it directly
-    if cmd.starts_with('/') {
-        return cmd.to_string();
-    }
-
-    // Common locations for npm, node, git on macOS/Linux
-    let common_paths = [
-        // Homebrew (Apple Silicon)
-        "/opt/homebrew/bin",
-        // Homebrew (Intel)
-        "/usr/local/bin",
-        // System paths
-        "/usr/bin",
-        // Common nvm locations
-        &format!(
-            "{}/.nvm/versions/node",
-            std::env::var("HOME").unwrap_or_default()
-        ),
-        // fnm
-        &format!(
-            "{}/.local/share/fnm/aliases/default/bin",
-            std::env::var("HOME").unwrap_or_default()
-        ),
-        // volta
-        &format!("{}/.volta/bin", std::env::var("HOME").unwrap_or_default()),
-        // asdf
-        &format!("{}/.asdf/shims", std::env::var("HOME").unwrap_or_default()),
-        // n (node version manager)
-        "/usr/local/n/versions/node",
-    ];
-
-    // For nvm, we need to find the active version directory
-    if let Ok(home) = std::env::var("HOME") {
-        let nvm_dir = format!("{home}/.nvm/versions/node");
-        if let Ok(entries) = std::fs::read_dir(&nvm_dir) {
-            // Find the most recent node version (they're named like v20.10.0)
-            let mut versions: Vec<_> = entries
-                .filter_map(std::result::Result::ok)
-                .filter(|e| e.path().is_dir())
-                .collect();
-            versions.sort_by_key(|b| std::cmp::Reverse(b.file_name()));
-            if let Some(latest) = versions.first() {
-                let bin_path = latest.path().join("bin").join(cmd);
-                if bin_path.exists() {
-                    return bin_path.to_string_lossy().to_string();
-                }
-            }
-        }
-    }
+            error: Some(UNKNOWN_REFRESH_MESSAGE.to_string()),
+            dry_run,
+        });
+    };
 
-    // Check common paths
-    for base in &common_paths {
-        let full_path = format!("{base}/{cmd}");
-        if std::path::Path::new(&full_path).exists() {
-            return full_path;
-        }
+    if plan.is_skip() || dry_run {
+        return Ok(McpRefreshResult {
+            success: true,
+            server_name: name,
+            refresh_type: plan.strategy.clone(),
+            refresh_action: plan.action.clone(),
+            command_run: plan.describe(),
+            output: plan.note.clone(),
+            error: None,
+            dry_run,
+        });
     }
 
-    // Fall back to just the command name (will use PATH)
-    cmd.to_string()
+    // Commands like npm install and cargo install can take a while
+    let run_plan = plan.clone();
+    let outcome = tokio::task::spawn_blocking(move || run_plan.execute())
+        .await
+        .map_err(|e| format!("Refresh task failed: {e}"))?;
+
+    Ok(McpRefreshResult {
+        success: outcome.success,
+        server_name: name,
+        refresh_type: plan.strategy.clone(),
+        refresh_action: plan.action.clone(),
+        command_run: plan.describe(),
+        output: Some(outcome.combined_output()),
+        error: outcome.error(),
+        dry_run,
+    })
 }
 
-/// Run a command in the specified directory and return the combined stdout/stderr
-fn run_command_in_dir(cmd: &str, args: &[&str], dir: &PathBuf) -> Result<String, String> {
-    use std::process::Command;
-
-    let resolved_cmd = resolve_command(cmd);
-    let output = Command::new(&resolved_cmd)
-        .args(args)
-        .current_dir(dir)
-        .output()
-        .map_err(|e| format!("Failed to execute {cmd} (tried {resolved_cmd}): {e}"))?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-
-    let combined = if stderr.is_empty() {
-        stdout.to_string()
-    } else if stdout.is_empty() {
-        stderr.to_string()
-    } else {
-        format!("{stdout}\n{stderr}")
-    };
-
-    if output.status.success() {
-        Ok(combined)
-    } else {
-        Err(format!(
-            "{} failed with exit code {:?}:\n{}",
-            cmd,
-            output.status.code(),
-            combined
-        ))
-    }
-}
+const UNKNOWN_REFRESH_MESSAGE: &str = "Cannot determine how to refresh this MCP server. \
+Supported: npx, uvx, pipx, docker/podman images, cargo install, go install, \
+local Node.js projects and git checkouts with a Makefile.";
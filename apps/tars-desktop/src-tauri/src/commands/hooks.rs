@@ -366,9 +366,9 @@ pub async fn save_profile_hooks(
             .update(&profile)
             .map_err(|e| format!("Failed to update bundle: {e}"))?;
 
-        tars_core::profile::regenerate_profile_plugin(&profile)
+        tars_core::profile::regenerate_profile_plugin(db.connection(), &profile)
             .map_err(|e| format!("Failed to regenerate plugin: {e}"))?;
-        tars_core::profile::sync_profile_marketplace(&profile)
+        tars_core::profile::sync_profile_marketplace(db.connection(), &profile)
             .map_err(|e| format!("Failed to sync bundle marketplace: {e}"))?;
 
         Ok(())
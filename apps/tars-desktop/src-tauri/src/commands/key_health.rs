@@ -0,0 +1,199 @@
+//! Scheduled API key validation and key health history.
+//!
+//! A background loop (see `spawn_key_validation_loop` in `lib.rs`) calls
+//! [`run_key_validation`] whenever the saved [`ValidationSchedule`] is due.
+//! Keys are validated concurrently through `tars_providers::validate_keys`
+//! with the schedule's per-provider limit; every verdict is written to the
+//! `key_validations` history and to `api_keys.last_valid`. Keys that worked
+//! before and are now rejected — usually revoked or expired, and the most
+//! common reason an MCP server stops working — are surfaced through
+//! [`list_failing_api_keys`].
+
+use chrono::Utc;
+use serde::Serialize;
+use tars_core::storage::api_keys::ApiKeyStore;
+use tars_core::storage::key_validations::{
+    FailingKey, KeyValidation, KeyValidationStore, ValidationSchedule,
+};
+use tars_providers::{validate_keys, ProviderError, ValidationJob};
+use tauri::State;
+
+use super::api_keys::resolve_provider;
+use crate::state::AppState;
+
+/// Totals for one validation run.
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyValidationRunSummary {
+    pub checked: usize,
+    pub valid: usize,
+    pub invalid: usize,
+    /// Keys whose provider has no auth-check endpoint
+    pub unverifiable: usize,
+    /// Network errors, rate limits and 5xx; no verdict recorded
+    pub errors: usize,
+    /// Keys whose first failure after a success happened in this run
+    pub newly_failing: Vec<FailingKey>,
+}
+
+/// Validate every stored key once, honouring the saved per-provider
+/// concurrency limit.
+pub async fn run_key_validation(state: &AppState) -> Result<KeyValidationRunSummary, String> {
+    let (jobs, balances, limit) = state.with_db(|db| {
+        let store = ApiKeyStore::new(db.connection());
+        let limit = KeyValidationStore::new(db.connection())
+            .schedule()
+            .map_err(|e| format!("Failed to load validation schedule: {e}"))?
+            .per_provider_concurrency;
+        let summaries = store
+            .list()
+            .map_err(|e| format!("Failed to list api keys: {e}"))?;
+
+        let mut jobs = Vec::new();
+        let mut balances = std::collections::HashMap::new();
+        for summary in summaries {
+            let Some(record) = store
+                .get(summary.id)
+                .map_err(|e| format!("Failed to load api key: {e}"))?
+            else {
+                continue;
+            };
+            // Keys of providers that no longer exist (deleted custom
+            // provider) cannot be checked; leave them alone.
+            let Ok(provider) = resolve_provider(db, &record.provider_id) else {
+                continue;
+            };
+            balances.insert(record.id, record.balance);
+            jobs.push(ValidationJob {
+                id: record.id,
+                provider_id: record.provider_id,
+                key: record.key,
+                provider,
+            });
+        }
+        Ok((jobs, balances, limit))
+    })?;
+
+    let outcomes = validate_keys(jobs, limit).await;
+
+    state.with_db(|db| {
+        let keys = ApiKeyStore::new(db.connection());
+        let history = KeyValidationStore::new(db.connection());
+        let now = Utc::now();
+        let mut summary = KeyValidationRunSummary {
+            checked: outcomes.len(),
+            valid: 0,
+            invalid: 0,
+            unverifiable: 0,
+            errors: 0,
+            newly_failing: Vec::new(),
+        };
+
+        for outcome in &outcomes {
+            let result = match &outcome.result {
+                Ok(r) => r,
+                Err(ProviderError::Unsupported) => {
+                    summary.unverifiable += 1;
+                    continue;
+                }
+                Err(_) => {
+                    summary.errors += 1;
+                    continue;
+                }
+            };
+            if result.valid {
+                summary.valid += 1;
+            } else {
+                summary.invalid += 1;
+            }
+            // Same balance policy as `validate_api_key`: a rejected key's
+            // balance is no longer trustworthy.
+            let balance = if result.valid {
+                balances.get(&outcome.id).cloned().flatten()
+            } else {
+                None
+            };
+            keys.update_validation(outcome.id, result.valid, balance.as_ref())
+                .map_err(|e| format!("Failed to persist validation: {e}"))?;
+            let latency_ms = u64::try_from(outcome.latency.as_millis()).unwrap_or(u64::MAX);
+            history
+                .record(
+                    outcome.id,
+                    &outcome.provider_id,
+                    result.valid,
+                    result.message.as_deref(),
+                    latency_ms,
+                    now,
+                )
+                .map_err(|e| format!("Failed to record validation history: {e}"))?;
+        }
+
+        summary.newly_failing = history
+            .failing_keys()
+            .map_err(|e| format!("Failed to load failing keys: {e}"))?
+            .into_iter()
+            .filter(|f| f.failing_since == now)
+            .collect();
+        Ok(summary)
+    })
+}
+
+/// Validate all stored keys now, outside the schedule.
+#[tauri::command]
+pub async fn validate_all_api_keys(
+    state: State<'_, AppState>,
+) -> Result<KeyValidationRunSummary, String> {
+    run_key_validation(&state).await
+}
+
+/// Validation verdicts for one key, newest first.
+#[tauri::command]
+pub async fn get_key_validation_history(
+    id: i64,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<KeyValidation>, String> {
+    state.with_db(|db| {
+        KeyValidationStore::new(db.connection())
+            .list_for_key(id, limit.unwrap_or(50))
+            .map_err(|e| format!("Failed to load validation history: {e}"))
+    })
+}
+
+/// Keys that used to validate and are now rejected.
+#[tauri::command]
+pub async fn list_failing_api_keys(state: State<'_, AppState>) -> Result<Vec<FailingKey>, String> {
+    state.with_db(|db| {
+        KeyValidationStore::new(db.connection())
+            .failing_keys()
+            .map_err(|e| format!("Failed to load failing keys: {e}"))
+    })
+}
+
+#[tauri::command]
+pub async fn get_key_validation_schedule(
+    state: State<'_, AppState>,
+) -> Result<ValidationSchedule, String> {
+    state.with_db(|db| {
+        KeyValidationStore::new(db.connection())
+            .schedule()
+            .map_err(|e| format!("Failed to load validation schedule: {e}"))
+    })
+}
+
+#[tauri::command]
+pub async fn set_key_validation_schedule(
+    schedule: ValidationSchedule,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    if schedule.interval_hours == 0 {
+        return Err("Validation interval must be at least one hour".to_string());
+    }
+    if schedule.per_provider_concurrency == 0 {
+        return Err("Concurrency limit must be at least one".to_string());
+    }
+    state.with_db(|db| {
+        KeyValidationStore::new(db.connection())
+            .set_schedule(&schedule)
+            .map_err(|e| format!("Failed to save validation schedule: {e}"))
+    })
+}
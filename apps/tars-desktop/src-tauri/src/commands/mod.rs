@@ -6,14 +6,18 @@ pub mod agents;
 pub mod api_keys;
 pub mod app_data_backup;
 pub mod apply;
+pub mod apply_batches;
+pub mod assignment_rules;
 pub mod beacons;
 pub mod commands;
 pub mod config;
 pub mod developer;
 pub mod hooks;
+pub mod key_health;
 pub mod metadata;
 pub mod plugins;
 pub mod pricing;
+pub mod profile_repos;
 pub mod profiles;
 pub mod projects;
 pub mod prompts;
@@ -32,14 +36,18 @@ pub use agents::*;
 pub use api_keys::*;
 pub use app_data_backup::*;
 pub use apply::*;
+pub use apply_batches::*;
+pub use assignment_rules::*;
 pub use beacons::*;
 pub use commands::*;
 pub use config::*;
 pub use developer::*;
 pub use hooks::*;
+pub use key_health::*;
 pub use metadata::*;
 pub use plugins::*;
 pub use pricing::*;
+pub use profile_repos::*;
 pub use profiles::*;
 pub use projects::*;
 pub use prompts::*;
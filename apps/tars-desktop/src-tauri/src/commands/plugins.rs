@@ -11,6 +11,7 @@ use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use tars_core::skills::plugin_skills_root;
 use tars_core::storage::{PluginSubscription, PluginSubscriptionInput, PluginSubscriptionStore};
 use tars_scanner::parser::parse_command;
 use tars_scanner::plugins::{InstalledPlugin, PluginInventory};
@@ -468,13 +469,6 @@ fn collect_plugin_command_paths(plugin: &InstalledPlugin) -> Result<Vec<PathBuf>
     Ok(command_paths)
 }
 
-pub(crate) fn plugin_skills_root(plugin: &InstalledPlugin) -> PathBuf {
-    plugin.manifest.skills.as_ref().map_or_else(
-        || plugin.path.join("skills"),
-        |path| resolve_plugin_relative_path(&plugin.path, path),
-    )
-}
-
 fn yaml_string(value: &str) -> String {
     serde_json::to_string(value).unwrap_or_else(|_| "\"\"".to_string())
 }
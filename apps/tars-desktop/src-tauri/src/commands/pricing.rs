@@ -136,6 +136,12 @@ fn parsed_to_update(p: ParsedPrice) -> PriceUpdateRow {
         input_price: p.input_price,
         output_price: p.output_price,
         context_window: p.context_window,
+        cache_read_price: p.cache_read_price,
+        cache_write_price: p.cache_write_price,
+        input_price_above_200k: p.input_price_above_200k,
+        output_price_above_200k: p.output_price_above_200k,
+        cache_read_price_above_200k: p.cache_read_price_above_200k,
+        cache_write_price_above_200k: p.cache_write_price_above_200k,
     }
 }
 
@@ -151,6 +157,12 @@ mod tests {
             input_price: 2.5,
             output_price: 10.0,
             context_window: Some(128_000),
+            cache_read_price: Some(1.25),
+            cache_write_price: None,
+            input_price_above_200k: None,
+            output_price_above_200k: None,
+            cache_read_price_above_200k: None,
+            cache_write_price_above_200k: None,
         };
         let r = parsed_to_update(p);
         assert_eq!(r.provider_id, "openai");
@@ -158,6 +170,8 @@ mod tests {
         assert!((r.input_price - 2.5).abs() < 1e-9);
         assert!((r.output_price - 10.0).abs() < 1e-9);
         assert_eq!(r.context_window, Some(128_000));
+        assert_eq!(r.cache_read_price, Some(1.25));
+        assert_eq!(r.cache_write_price, None);
     }
 
     #[test]
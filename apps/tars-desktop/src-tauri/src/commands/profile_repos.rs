@@ -0,0 +1,120 @@
+//! Git profile repository commands
+//!
+//! Commands for sharing bundles through git repositories: register a
+//! repository, check it for upstream and local changes, pull and push.
+
+use crate::state::AppState;
+use tars_core::profile::{PullResult, PushResult, RepoStatus, RepoSync, RepoWorkspace};
+use tars_core::storage::{Database, ProfileRepo, ProfileRepoStore, ProfileStore};
+use tauri::State;
+
+/// List registered profile repositories
+#[tauri::command]
+pub async fn list_profile_repos(state: State<'_, AppState>) -> Result<Vec<ProfileRepo>, String> {
+    state.with_db(|db| {
+        ProfileRepoStore::new(db.connection())
+            .list()
+            .map_err(|e| format!("Database error: {e}"))
+    })
+}
+
+/// Register and clone a profile repository
+#[tauri::command]
+pub async fn add_profile_repo(
+    name: String,
+    url: String,
+    branch: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<ProfileRepo, String> {
+    state.with_db(|db| {
+        repo_sync(db)?
+            .add(&name, &url, branch.as_deref())
+            .map_err(|e| format!("Failed to add repository: {e}"))
+    })
+}
+
+/// Unregister a profile repository; bundles pulled from it are kept
+#[tauri::command]
+pub async fn remove_profile_repo(
+    repo_id: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.with_db(|db| {
+        let repo = find_repo(db, &repo_id)?;
+        repo_sync(db)?
+            .remove(&repo)
+            .map_err(|e| format!("Failed to remove repository: {e}"))
+    })
+}
+
+/// Fetch a profile repository and compare it with local bundles
+#[tauri::command]
+pub async fn get_profile_repo_status(
+    repo_id: String,
+    state: State<'_, AppState>,
+) -> Result<RepoStatus, String> {
+    state.with_db(|db| {
+        let repo = find_repo(db, &repo_id)?;
+        repo_sync(db)?
+            .status(&repo)
+            .map_err(|e| format!("Failed to check repository: {e}"))
+    })
+}
+
+/// Bring upstream changes into local bundles
+#[tauri::command]
+pub async fn pull_profile_repo(
+    repo_id: String,
+    force: bool,
+    state: State<'_, AppState>,
+) -> Result<PullResult, String> {
+    state.with_db(|db| {
+        let repo = find_repo(db, &repo_id)?;
+        repo_sync(db)?
+            .pull(&repo, force)
+            .map_err(|e| format!("Failed to pull repository: {e}"))
+    })
+}
+
+/// Commit local bundle edits on a branch and push it
+#[tauri::command]
+pub async fn push_profile_repo(
+    repo_id: String,
+    profile_ids: Vec<String>,
+    branch: String,
+    message: String,
+    state: State<'_, AppState>,
+) -> Result<PushResult, String> {
+    state.with_db(|db| {
+        let repo = find_repo(db, &repo_id)?;
+        let store = ProfileStore::new(db.connection());
+        let profiles = profile_ids
+            .iter()
+            .map(|id| {
+                let uuid =
+                    uuid::Uuid::parse_str(id).map_err(|e| format!("Invalid bundle ID: {e}"))?;
+                store
+                    .get(uuid)
+                    .map_err(|e| format!("Database error: {e}"))?
+                    .ok_or_else(|| format!("Bundle not found: {id}"))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        repo_sync(db)?
+            .push(&repo, &profiles, &branch, &message)
+            .map_err(|e| format!("Failed to push repository: {e}"))
+    })
+}
+
+fn repo_sync(db: &Database) -> Result<RepoSync<'_>, String> {
+    let workspace = RepoWorkspace::default_dirs().map_err(|e| e.to_string())?;
+    Ok(RepoSync::new(db.connection(), workspace))
+}
+
+fn find_repo(db: &Database, repo_id: &str) -> Result<ProfileRepo, String> {
+    let uuid = uuid::Uuid::parse_str(repo_id).map_err(|e| format!("Invalid repository ID: {e}"))?;
+    ProfileRepoStore::new(db.connection())
+        .get(uuid)
+        .map_err(|e| format!("Database error: {e}"))?
+        .ok_or_else(|| "Repository not found".to_string())
+}
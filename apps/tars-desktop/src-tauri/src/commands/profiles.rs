@@ -11,18 +11,17 @@ use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::process::Command;
 use tars_core::config::McpServerConfig;
 use tars_core::export::export_as_plugin_zip;
 use tars_core::profile::snapshot::snapshot_from_project;
-use tars_core::profile::storage::{list_plugin_manifests, save_project_state, ProjectProfileState};
 use tars_core::profile::sync::{
-    apply_profile_to_project, convert_profile_to_local_overrides, sync_profile_marketplace,
+    assign_profile_to_project, convert_profile_to_local_overrides, sync_profile_marketplace,
     sync_profile_to_projects,
 };
-use tars_core::profile::updates::create_source_ref;
+use tars_core::profile::updates::{create_mcp_source_ref, create_source_ref};
 use tars_core::profile::{
-    PluginRef, SourceMode, SourceRef, ToolPermissions, ToolRef, ToolType, PROFILE_MARKETPLACE,
+    compare_stored_profiles, resolve_profile, PluginRef, ProfileComparison, ResolvedProfile,
+    SourceMode, ToolPermissions, ToolRef, ToolType, PROFILE_MARKETPLACE,
 };
 use tars_core::storage::projects::ProjectStore;
 use tars_core::storage::ProfileStore;
@@ -61,6 +60,8 @@ pub struct SourceRefInfo {
     pub source_hash: String,
     pub mode: String, // "pin" or "track"
     pub copied_at: String,
+    /// Entry within the source file, e.g. the MCP server's key
+    pub entry: Option<String>,
 }
 
 impl From<&tars_core::profile::SourceRef> for SourceRefInfo {
@@ -68,15 +69,20 @@ impl From<&tars_core::profile::SourceRef> for SourceRefInfo {
         Self {
             source_path: s.source_path.display().to_string(),
             source_hash: s.source_hash.clone(),
-            mode: match s.mode {
-                tars_core::profile::SourceMode::Pin => "pin".to_string(),
-                tars_core::profile::SourceMode::Track => "track".to_string(),
-            },
+            mode: source_mode_name(s.mode),
             copied_at: s.copied_at.clone(),
+            entry: s.entry.clone(),
         }
     }
 }
 
+fn source_mode_name(mode: SourceMode) -> String {
+    match mode {
+        SourceMode::Pin => "pin".to_string(),
+        SourceMode::Track => "track".to_string(),
+    }
+}
+
 /// Tool reference for frontend display
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolRefInfo {
@@ -139,6 +145,10 @@ pub struct PluginRefInfo {
     pub marketplace: Option<String>,
     pub scope: String,
     pub enabled: bool,
+    /// Recorded installed version, if the entry follows or pins one
+    pub version: Option<String>,
+    /// "track" or "pin" when a version is recorded
+    pub version_mode: Option<String>,
 }
 
 impl From<&tars_core::profile::PluginRef> for PluginRefInfo {
@@ -148,6 +158,8 @@ impl From<&tars_core::profile::PluginRef> for PluginRefInfo {
             marketplace: r.marketplace.clone(),
             scope: format!("{:?}", r.scope).to_lowercase(),
             enabled: r.enabled,
+            version: r.version_ref.as_ref().map(|v| v.version.clone()),
+            version_mode: r.version_ref.as_ref().map(|v| source_mode_name(v.mode)),
         }
     }
 }
@@ -158,6 +170,7 @@ pub struct ProfileDetails {
     pub id: String,
     pub name: String,
     pub description: Option<String>,
+    pub extends: Vec<String>,
     pub tool_refs: Vec<ToolRefInfo>,
     pub plugin_refs: Vec<PluginRefInfo>,
     pub assigned_projects: Vec<ProjectRef>,
@@ -196,7 +209,7 @@ impl From<&Profile> for ProfileDetails {
             .count();
 
         // Combine overlay counts with tool_ref counts
-        let mcp_count = mcp_refs;
+        let mcp_count = p.repo_overlays.mcp_servers.len() + mcp_refs;
         let skills_count = p.repo_overlays.skills.len() + p.user_overlays.skills.len() + skill_refs;
         let commands_count =
             p.repo_overlays.commands.len() + p.user_overlays.commands.len() + command_refs;
@@ -207,6 +220,7 @@ impl From<&Profile> for ProfileDetails {
             id: p.id.to_string(),
             name: p.name.clone(),
             description: p.description.clone(),
+            extends: p.extends.iter().map(ToString::to_string).collect(),
             tool_refs: p.tool_refs.iter().map(ToolRefInfo::from).collect(),
             plugin_refs: p
                 .plugin_set
@@ -283,11 +297,11 @@ pub async fn create_profile(
             .map_err(|e| format!("Failed to save bundle: {e}"))?;
 
         // Regenerate the plugin after profile creation
-        tars_core::profile::regenerate_profile_plugin(&profile)
+        tars_core::profile::regenerate_profile_plugin(db.connection(), &profile)
             .map_err(|e| format!("Failed to generate plugin: {e}"))?;
-        tars_core::profile::sync_profile_marketplace(&profile)
+        tars_core::profile::sync_profile_marketplace(db.connection(), &profile)
             .map_err(|e| format!("Failed to sync bundle marketplace: {e}"))?;
-        tars_core::profile::sync_profile_marketplace(&profile)
+        tars_core::profile::sync_profile_marketplace(db.connection(), &profile)
             .map_err(|e| format!("Failed to sync bundle marketplace: {e}"))?;
 
         Ok(ProfileInfo {
@@ -313,6 +327,22 @@ fn validate_profile_name(name: &str) -> Result<String, String> {
     Ok(trimmed.to_string())
 }
 
+/// Refuse to delete a profile that other profiles still extend
+fn ensure_not_extended(store: &ProfileStore, id: uuid::Uuid) -> Result<(), String> {
+    let children = store
+        .list_extending(id)
+        .map_err(|e| format!("Database error: {e}"))?;
+    if children.is_empty() {
+        return Ok(());
+    }
+    let names = children
+        .iter()
+        .map(|c| c.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    Err(format!("Bundle is extended by: {names}"))
+}
+
 fn ensure_unique_plugin_slug(
     store: &ProfileStore,
     name: &str,
@@ -363,7 +393,7 @@ pub async fn create_empty_profile(
             .map_err(|e| format!("Failed to save bundle: {e}"))?;
 
         // Regenerate the plugin after profile creation
-        tars_core::profile::regenerate_profile_plugin(&profile)
+        tars_core::profile::regenerate_profile_plugin(db.connection(), &profile)
             .map_err(|e| format!("Failed to generate plugin: {e}"))?;
 
         Ok(ProfileInfo {
@@ -410,6 +440,82 @@ pub async fn get_profile(id: String, state: State<'_, AppState>) -> Result<Profi
     })
 }
 
+/// Set the parent profiles a profile extends, rejecting cycles
+#[tauri::command]
+pub async fn set_profile_extends(
+    profile_id: String,
+    parent_ids: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<ResolvedProfile, String> {
+    let uuid = uuid::Uuid::parse_str(&profile_id).map_err(|e| format!("Invalid UUID: {e}"))?;
+    let parents = parent_ids
+        .iter()
+        .map(|id| uuid::Uuid::parse_str(id).map_err(|e| format!("Invalid UUID: {e}")))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    state.with_db(|db| {
+        let store = ProfileStore::new(db.connection());
+        let mut profile = store
+            .get(uuid)
+            .map_err(|e| format!("Database error: {e}"))?
+            .ok_or_else(|| "Bundle not found".to_string())?;
+
+        profile.extends = parents;
+        let resolved = resolve_profile(&store, &profile).map_err(|e| e.to_string())?;
+        profile.updated_at = Utc::now();
+        store
+            .update(&profile)
+            .map_err(|e| format!("Failed to update bundle: {e}"))?;
+
+        Ok(resolved)
+    })
+}
+
+/// Get the resolved view of a profile with the origin of every artifact
+#[tauri::command]
+pub async fn get_resolved_profile(
+    profile_id: String,
+    state: State<'_, AppState>,
+) -> Result<ResolvedProfile, String> {
+    let uuid = uuid::Uuid::parse_str(&profile_id).map_err(|e| format!("Invalid UUID: {e}"))?;
+
+    state.with_db(|db| {
+        let store = ProfileStore::new(db.connection());
+        let profile = store
+            .get(uuid)
+            .map_err(|e| format!("Database error: {e}"))?
+            .ok_or_else(|| "Bundle not found".to_string())?;
+
+        resolve_profile(&store, &profile).map_err(|e| e.to_string())
+    })
+}
+
+/// Compare two profiles: tools only in one of them or with different content,
+/// plus plugin, adapter and overlay differences
+#[tauri::command]
+pub async fn compare_profiles(
+    profile_a: String,
+    profile_b: String,
+    state: State<'_, AppState>,
+) -> Result<ProfileComparison, String> {
+    let uuid_a = uuid::Uuid::parse_str(&profile_a).map_err(|e| format!("Invalid UUID: {e}"))?;
+    let uuid_b = uuid::Uuid::parse_str(&profile_b).map_err(|e| format!("Invalid UUID: {e}"))?;
+
+    state.with_db(|db| {
+        let store = ProfileStore::new(db.connection());
+        let get = |uuid| {
+            store
+                .get(uuid)
+                .map_err(|e| format!("Database error: {e}"))?
+                .ok_or_else(|| "Bundle not found".to_string())
+        };
+        let a = get(uuid_a)?;
+        let b = get(uuid_b)?;
+
+        compare_stored_profiles(&a, &b).map_err(|e| format!("Failed to read bundle: {e}"))
+    })
+}
+
 /// Response for profile deletion
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeleteProfileResponse {
@@ -439,6 +545,7 @@ pub async fn delete_profile(
             .get(uuid)
             .map_err(|e| format!("Database error: {e}"))?
             .ok_or_else(|| "Bundle not found".to_string())?;
+        ensure_not_extended(&store, uuid)?;
 
         // First, convert profile tools to local overrides for all assigned projects
         let converted = convert_profile_to_local_overrides(db.connection(), uuid)
@@ -500,6 +607,7 @@ pub async fn delete_profile_cleanup(
                 .get(uuid)
                 .map_err(|e| format!("Database error: {e}"))?
                 .ok_or_else(|| "Bundle not found".to_string())?;
+            ensure_not_extended(&store, uuid)?;
 
             let projects = project_store
                 .list_by_profile(uuid)
@@ -671,6 +779,7 @@ impl TryFrom<PluginRefInput> for PluginRef {
             marketplace: input.marketplace,
             scope,
             enabled: input.enabled,
+            version_ref: None,
         })
     }
 }
@@ -829,10 +938,20 @@ pub async fn update_profile(
 
         // Update plugin_refs if provided
         if let Some(refs) = plugin_refs {
-            profile.plugin_set.plugins = refs
+            let mut plugins = refs
                 .into_iter()
                 .map(PluginRef::try_from)
                 .collect::<Result<Vec<_>, _>>()?;
+            // The frontend does not send recorded versions; keep them
+            for plugin in &mut plugins {
+                plugin.version_ref = profile
+                    .plugin_set
+                    .plugins
+                    .iter()
+                    .find(|p| p.id == plugin.id && p.marketplace == plugin.marketplace)
+                    .and_then(|p| p.version_ref.clone());
+            }
+            profile.plugin_set.plugins = plugins;
         }
 
         profile.updated_at = Utc::now();
@@ -842,9 +961,9 @@ pub async fn update_profile(
             .map_err(|e| format!("Failed to update bundle: {e}"))?;
 
         // Regenerate the plugin after profile update
-        tars_core::profile::regenerate_profile_plugin(&profile)
+        tars_core::profile::regenerate_profile_plugin(db.connection(), &profile)
             .map_err(|e| format!("Failed to regenerate plugin: {e}"))?;
-        tars_core::profile::sync_profile_marketplace(&profile)
+        tars_core::profile::sync_profile_marketplace(db.connection(), &profile)
             .map_err(|e| format!("Failed to sync bundle marketplace: {e}"))?;
         if previous_name != profile.name {
             tars_core::profile::remove_profile_from_marketplace(&previous_name)
@@ -975,8 +1094,15 @@ pub async fn export_profile_as_plugin(
             .map_err(|e| format!("Database error: {e}"))?
             .ok_or_else(|| "Bundle not found".to_string())?;
 
-        export_as_plugin_zip(&profile, &validated_output, &options.name, &options.version)
-            .map_err(|e| format!("Export failed: {e}"))?;
+        let resolved = resolve_profile(&store, &profile)
+            .map_err(|e| format!("Failed to resolve bundle: {e}"))?;
+        export_as_plugin_zip(
+            &resolved,
+            &validated_output,
+            &options.name,
+            &options.version,
+        )
+        .map_err(|e| format!("Export failed: {e}"))?;
 
         Ok(validated_output.display().to_string())
     })
@@ -1010,120 +1136,28 @@ pub async fn assign_profile(
     let profile_uuid =
         uuid::Uuid::parse_str(&profile_id).map_err(|e| format!("Invalid bundle ID: {e}"))?;
 
-    // Get profile and project info from database
-    let (_profile, project_path) = state.with_db(|db| {
-        let profile_store = ProfileStore::new(db.connection());
-        let project_store = ProjectStore::new(db.connection());
-
-        // Get the profile (we need its content for applying)
-        let profile = profile_store
-            .get(profile_uuid)
-            .map_err(|e| format!("Database error: {e}"))?
-            .ok_or_else(|| "Bundle not found".to_string())?;
-
-        // Get the project
-        let mut project = project_store
+    let claude = find_claude_binary().ok();
+    let result = state.with_db(|db| {
+        let mut project = ProjectStore::new(db.connection())
             .get(project_uuid)
             .map_err(|e| format!("Database error: {e}"))?
             .ok_or_else(|| "Project not found".to_string())?;
 
-        // Apply the profile overlays to the project directory
-        let _apply_result = apply_profile_to_project(&profile, &project.path)
-            .map_err(|e| format!("Failed to apply bundle: {e}"))?;
-
-        // Assign the profile in the database
-        project.assigned_profile_id = Some(profile_uuid);
-        project.updated_at = Utc::now();
-
-        project_store
-            .update(&project)
-            .map_err(|e| format!("Failed to update project: {e}"))?;
-
-        Ok((profile, project.path.clone()))
+        assign_profile_to_project(
+            db.connection(),
+            &mut project,
+            profile_uuid,
+            claude.as_deref(),
+        )
+        .map_err(|e| format!("Failed to assign bundle: {e}"))
     })?;
 
-    // Install plugins from the profile's central storage
-    let mut plugins_installed = 0usize;
-    let mut plugin_errors: Vec<(String, String)> = Vec::new();
-    let mut project_state = ProjectProfileState::new(profile_uuid);
-
-    // Get plugins from profile storage
-    let plugin_manifests = list_plugin_manifests(profile_uuid).unwrap_or_default();
-
-    if !plugin_manifests.is_empty() {
-        // Find Claude binary for plugin installation
-        let claude_binary = find_claude_binary();
-
-        for manifest in &plugin_manifests {
-            if !manifest.enabled {
-                continue; // Skip disabled plugins
-            }
-
-            // Build plugin identifier (plugin@marketplace format if marketplace is known)
-            let plugin_identifier = match &manifest.marketplace {
-                Some(marketplace) => format!("{}@{}", manifest.id, marketplace),
-                None => manifest.id.clone(),
-            };
-
-            match &claude_binary {
-                Ok(claude) => {
-                    // Install plugin with project scope
-                    let output = Command::new(claude)
-                        .args(["plugin", "install", "--scope=project", &plugin_identifier])
-                        .current_dir(&project_path)
-                        .output();
-
-                    match output {
-                        Ok(result) if result.status.success() => {
-                            plugins_installed += 1;
-                            project_state.add_installed_plugin(manifest.id.clone());
-                        }
-                        Ok(result) => {
-                            let stderr = String::from_utf8_lossy(&result.stderr);
-                            let stdout = String::from_utf8_lossy(&result.stdout);
-                            let error_msg = if !stderr.is_empty() {
-                                stderr.to_string()
-                            } else if !stdout.is_empty() {
-                                stdout.to_string()
-                            } else {
-                                "Unknown error".to_string()
-                            };
-
-                            // Check if already installed (not an error)
-                            if error_msg.contains("already installed") {
-                                // Plugin already exists, not an error
-                                project_state.add_installed_plugin(manifest.id.clone());
-                            } else {
-                                plugin_errors.push((manifest.id.clone(), error_msg));
-                            }
-                        }
-                        Err(e) => {
-                            plugin_errors
-                                .push((manifest.id.clone(), format!("Failed to run CLI: {e}")));
-                        }
-                    }
-                }
-                Err(e) => {
-                    plugin_errors.push((manifest.id.clone(), format!("Claude CLI not found: {e}")));
-                }
-            }
-        }
-    }
-
-    // Save project state for cleanup tracking
-    if let Err(e) = save_project_state(project_uuid, &project_state) {
-        // Log warning but don't fail the assignment
-        eprintln!("Warning: Failed to save project state: {e}");
-    }
-
-    let assigned_at = Utc::now();
-
     Ok(AssignProfileResponse {
         project_id: project_uuid.to_string(),
         profile_id: profile_uuid.to_string(),
-        assigned_at: assigned_at.to_rfc3339(),
-        plugins_installed,
-        plugin_errors,
+        assigned_at: Utc::now().to_rfc3339(),
+        plugins_installed: result.plugins_installed,
+        plugin_errors: result.plugin_errors,
     })
 }
 
@@ -1547,24 +1581,17 @@ fn collect_tools_from_project(
             storage::store_mcp_server(profile_id, &server.name, &serde_json::Value::Object(config))
                 .map_err(|e| format!("Failed to store MCP server: {e}"))?;
 
-            let source_ref = if mcp_config.path.exists() {
-                create_source_ref(mcp_config.path.clone(), ToolType::Mcp, source_mode)
-                    .map_err(|e| format!("Failed to create source ref: {e}"))?
-            } else {
-                SourceRef {
-                    source_path: project_path.to_path_buf(),
-                    source_hash: "mcp-config".to_string(),
-                    mode: source_mode,
-                    copied_at: chrono::Utc::now().to_rfc3339(),
-                }
-            };
+            // Track the server's own entry; servers without one in the file
+            // (e.g. from plugins) cannot be tracked
+            let source_ref =
+                create_mcp_source_ref(mcp_config.path.clone(), &server.name, source_mode).ok();
 
             tool_refs.push(ToolRef {
                 name: server.name.clone(),
                 tool_type: ToolType::Mcp,
                 source_scope: Some(Scope::Project),
                 permissions: None,
-                source_ref: Some(source_ref),
+                source_ref,
             });
         }
     }
@@ -1707,17 +1734,6 @@ pub async fn add_tools_from_source(
     let mut agents_added = 0usize;
     let mut commands_added = 0usize;
     let mut tool_refs: Vec<ToolRef> = Vec::new();
-    let mcp_source_path = if scope_for_refs == Scope::User {
-        let home =
-            dirs::home_dir().ok_or_else(|| "Could not determine home directory".to_string())?;
-        home.join(".claude.json")
-    } else {
-        source_path
-            .as_ref()
-            .ok_or_else(|| "Source project path is required for project scope".to_string())?
-            .clone()
-    };
-
     for tool in &input.tools {
         let tool_type = match tool.tool_type.to_lowercase().as_str() {
             "mcp" => ToolType::Mcp,
@@ -1766,20 +1782,21 @@ pub async fn add_tools_from_source(
                         )
                         .map_err(|e| format!("Failed to store MCP server: {e}"))?;
 
-                        // MCP servers come from config files; use scope-appropriate source path
-                        let mcp_source_ref = SourceRef {
-                            source_path: mcp_source_path.clone(),
-                            source_hash: "mcp-config".to_string(), // MCP configs are stored inline
-                            mode: source_mode,
-                            copied_at: chrono::Utc::now().to_rfc3339(),
-                        };
+                        // MCP servers come from shared config files (~/.claude.json or
+                        // .mcp.json); track only this server's entry
+                        let mcp_source_ref = create_mcp_source_ref(
+                            mcp_config.path.clone(),
+                            &server.name,
+                            source_mode,
+                        )
+                        .ok();
 
                         tool_refs.push(ToolRef {
                             name: tool.name.clone(),
                             tool_type: ToolType::Mcp,
                             source_scope: Some(scope_for_refs.clone()),
                             permissions: None,
-                            source_ref: Some(mcp_source_ref),
+                            source_ref: mcp_source_ref,
                         });
                         mcp_servers_added += 1;
                     }
@@ -1890,9 +1907,9 @@ pub async fn add_tools_from_source(
             .map_err(|e| format!("Failed to update bundle: {e}"))?;
 
         // Regenerate the plugin after adding tools
-        tars_core::profile::regenerate_profile_plugin(&profile)
+        tars_core::profile::regenerate_profile_plugin(db.connection(), &profile)
             .map_err(|e| format!("Failed to regenerate plugin: {e}"))?;
-        tars_core::profile::sync_profile_marketplace(&profile)
+        tars_core::profile::sync_profile_marketplace(db.connection(), &profile)
             .map_err(|e| format!("Failed to sync bundle marketplace: {e}"))?;
 
         Ok(AddToolsFromSourceResponse {
@@ -2003,9 +2020,9 @@ pub async fn create_profile_mcp_server(
             .update(&profile)
             .map_err(|e| format!("Failed to update bundle: {e}"))?;
 
-        tars_core::profile::regenerate_profile_plugin(&profile)
+        tars_core::profile::regenerate_profile_plugin(db.connection(), &profile)
             .map_err(|e| format!("Failed to regenerate plugin: {e}"))?;
-        sync_profile_marketplace(&profile)
+        sync_profile_marketplace(db.connection(), &profile)
             .map_err(|e| format!("Failed to sync bundle marketplace: {e}"))?;
 
         Ok(())
@@ -2132,9 +2149,9 @@ pub async fn remove_profile_mcp_server(
                 .update(&profile)
                 .map_err(|e| format!("Failed to update bundle: {e}"))?;
 
-            tars_core::profile::regenerate_profile_plugin(&profile)
+            tars_core::profile::regenerate_profile_plugin(db.connection(), &profile)
                 .map_err(|e| format!("Failed to regenerate plugin: {e}"))?;
-            sync_profile_marketplace(&profile)
+            sync_profile_marketplace(db.connection(), &profile)
                 .map_err(|e| format!("Failed to sync bundle marketplace: {e}"))?;
         }
 
@@ -2275,8 +2292,10 @@ pub struct PluginManifestInfo {
 // ============================================================================
 
 use tars_core::profile::export::{
-    export_profile as core_export, import_profile as core_import, preview_import as core_preview,
+    export_bundle as core_export_bundle, export_profile as core_export,
+    import_profile as core_import, preview_import_with_conflicts as core_preview,
 };
+use tars_core::profile::ImportConflict;
 
 /// Response for exporting a profile
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -2321,6 +2340,40 @@ pub async fn export_profile_json(
     })
 }
 
+/// Export a profile as a self-contained `.tars-profile.zip` bundle that
+/// embeds its skills, agents, commands, MCP servers, hooks and plugin
+/// manifests
+#[tauri::command]
+pub async fn export_profile_bundle(
+    profile_id: String,
+    output_path: String,
+    state: State<'_, AppState>,
+) -> Result<ExportProfileResponse, String> {
+    let uuid = uuid::Uuid::parse_str(&profile_id).map_err(|e| format!("Invalid UUID: {e}"))?;
+    let output = PathBuf::from(&output_path);
+    let validated_output = validate_export_path(&output)?;
+
+    let profile = state.with_db(|db| {
+        ProfileStore::new(db.connection())
+            .get(uuid)
+            .map_err(|e| format!("Database error: {e}"))?
+            .ok_or_else(|| "Bundle not found".to_string())
+    })?;
+    let storage_dir = tars_core::profile::storage::profile_dir(uuid)
+        .map_err(|e| format!("Failed to locate profile storage: {e}"))?;
+
+    let export = core_export_bundle(&profile, &storage_dir, &validated_output)
+        .map_err(|e| format!("Export failed: {e}"))?;
+    let metadata = std::fs::metadata(&validated_output)
+        .map_err(|e| format!("Failed to read exported file: {e}"))?;
+
+    Ok(ExportProfileResponse {
+        path: validated_output.display().to_string(),
+        size_bytes: metadata.len(),
+        exported_at: export.exported_at.to_rfc3339(),
+    })
+}
+
 /// Preview for importing a profile
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImportPreviewResponse {
@@ -2330,6 +2383,10 @@ pub struct ImportPreviewResponse {
     pub has_name_collision: bool,
     pub existing_profile_id: Option<String>,
     pub version: u32,
+    /// Files embedded in a bundle (0 for `.tars-profile.json`)
+    pub file_count: usize,
+    /// Bundled tools whose names already exist in the user's Claude config
+    pub conflicts: Vec<ImportConflict>,
 }
 
 /// Validate an import file path for safety
@@ -2361,12 +2418,12 @@ fn validate_import_path(path: &Path) -> Result<PathBuf, String> {
 
     // Verify it has the expected extension
     // Case-insensitive extension check
-    let is_json = path
+    let has_import_extension = path
         .extension()
         .and_then(|e| e.to_str())
-        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
-    if !is_json {
-        return Err("Import file must be a .json file".to_string());
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json") || ext.eq_ignore_ascii_case("zip"));
+    if !has_import_extension {
+        return Err("Import file must be a .json or .zip file".to_string());
     }
 
     // Canonicalize to resolve any symlinks and get absolute path
@@ -2383,8 +2440,9 @@ pub async fn preview_profile_import(
     let path = PathBuf::from(&file_path);
     let validated_path = validate_import_path(&path)?;
 
-    let preview =
-        core_preview(&validated_path).map_err(|e| format!("Failed to preview import: {e}"))?;
+    let home = dirs::home_dir().ok_or("Cannot find home directory")?;
+    let preview = core_preview(&validated_path, &home)
+        .map_err(|e| format!("Failed to preview import: {e}"))?;
 
     // Check for name collision
     let (has_collision, existing_id) = state.with_db(|db| {
@@ -2403,6 +2461,8 @@ pub async fn preview_profile_import(
         has_name_collision: has_collision,
         existing_profile_id: existing_id,
         version: preview.version,
+        file_count: preview.file_count,
+        conflicts: preview.conflicts,
     })
 }
 
@@ -2414,7 +2474,10 @@ pub struct ImportProfileResponse {
     pub collision_resolved: bool,
 }
 
-/// Import a profile from .tars-profile.json
+/// Import a profile from .tars-profile.json or a .tars-profile.zip bundle
+///
+/// Bundle files are unpacked into the new profile's storage before the
+/// database row exists; they are removed again if saving fails.
 #[tauri::command]
 pub async fn import_profile_json(
     file_path: String,
@@ -2426,51 +2489,63 @@ pub async fn import_profile_json(
 
     // Import the profile
     let mut profile = core_import(&validated_path).map_err(|e| format!("Failed to import: {e}"))?;
+    let discard_files = |id: uuid::Uuid| {
+        if let Ok(dir) = tars_core::profile::storage::profile_dir(id) {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    };
 
     // Validate the imported profile name
-    validate_profile_name(&profile.name)?;
+    if let Err(e) = validate_profile_name(&profile.name) {
+        discard_files(profile.id);
+        return Err(e);
+    }
 
     // Handle rename if provided
     let collision_resolved = if let Some(new_name) = rename_to {
-        let validated = validate_profile_name(&new_name)?;
+        let validated =
+            validate_profile_name(&new_name).inspect_err(|_| discard_files(profile.id))?;
         profile.name = validated;
         true
     } else {
         false
     };
 
-    state.with_db(|db| {
-        let store = ProfileStore::new(db.connection());
-
-        // Check for name collision
-        if store
-            .get_by_name(&profile.name)
-            .map_err(|e| format!("Database error: {e}"))?
-            .is_some()
-        {
-            return Err(format!(
-                "Bundle '{}' already exists. Provide rename_to to resolve.",
-                profile.name
-            ));
-        }
+    let profile_id = profile.id;
+    state
+        .with_db(|db| {
+            let store = ProfileStore::new(db.connection());
 
-        store
-            .create(&profile)
-            .map_err(|e| format!("Failed to save bundle: {e}"))?;
+            // Check for name collision
+            if store
+                .get_by_name(&profile.name)
+                .map_err(|e| format!("Database error: {e}"))?
+                .is_some()
+            {
+                return Err(format!(
+                    "Bundle '{}' already exists. Provide rename_to to resolve.",
+                    profile.name
+                ));
+            }
 
-        Ok(ImportProfileResponse {
-            profile: ProfileInfo {
-                id: profile.id.to_string(),
-                name: profile.name,
-                description: profile.description,
-                tool_count: profile.tool_refs.len(),
-                created_at: profile.created_at.to_rfc3339(),
-                updated_at: profile.updated_at.to_rfc3339(),
-            },
-            imported_from: file_path,
-            collision_resolved,
+            store
+                .create(&profile)
+                .map_err(|e| format!("Failed to save bundle: {e}"))?;
+
+            Ok(ImportProfileResponse {
+                profile: ProfileInfo {
+                    id: profile.id.to_string(),
+                    name: profile.name,
+                    description: profile.description,
+                    tool_count: profile.tool_refs.len(),
+                    created_at: profile.created_at.to_rfc3339(),
+                    updated_at: profile.updated_at.to_rfc3339(),
+                },
+                imported_from: file_path,
+                collision_resolved,
+            })
         })
-    })
+        .inspect_err(|_| discard_files(profile_id))
 }
 
 // ============================================================================
@@ -2488,12 +2563,23 @@ pub struct ToolUpdateInfoResponse {
     pub mode: String,
 }
 
+/// Plugin version update info for frontend display
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginUpdateInfoResponse {
+    pub plugin_id: String,
+    pub marketplace: Option<String>,
+    pub recorded_version: String,
+    pub installed_version: String,
+}
+
 /// Profile update check response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProfileUpdateCheckResponse {
     pub updates: Vec<ToolUpdateInfoResponse>,
     pub missing_sources: Vec<String>,
     pub total_checked: usize,
+    /// Tracked plugins with a newer installed version
+    pub plugin_updates: Vec<PluginUpdateInfoResponse>,
 }
 
 /// Check a profile for available updates to tracked tools
@@ -2515,6 +2601,8 @@ pub async fn check_profile_updates(
 
         let check = tars_core::profile::check_profile_updates(&profile)
             .map_err(|e| format!("Failed to check updates: {e}"))?;
+        let plugin_updates = tars_core::profile::check_plugin_updates(db.connection(), &profile)
+            .map_err(|e| format!("Failed to check plugin versions: {e}"))?;
 
         Ok(ProfileUpdateCheckResponse {
             updates: check
@@ -2526,14 +2614,20 @@ pub async fn check_profile_updates(
                     source_path: u.source_path.display().to_string(),
                     old_hash: u.old_hash.clone(),
                     new_hash: u.new_hash.clone(),
-                    mode: match u.mode {
-                        tars_core::profile::SourceMode::Pin => "pin".to_string(),
-                        tars_core::profile::SourceMode::Track => "track".to_string(),
-                    },
+                    mode: source_mode_name(u.mode),
                 })
                 .collect(),
             missing_sources: check.missing_sources,
             total_checked: check.total_checked,
+            plugin_updates: plugin_updates
+                .into_iter()
+                .map(|u| PluginUpdateInfoResponse {
+                    plugin_id: u.plugin_id,
+                    marketplace: u.marketplace,
+                    recorded_version: u.recorded_version,
+                    installed_version: u.installed_version,
+                })
+                .collect(),
         })
     })
 }
@@ -2588,6 +2682,10 @@ pub async fn pull_tool_update(
                 storage::compute_file_hash(&source_ref.source_path)
                     .map_err(|e| format!("Failed to compute hash: {e}"))?
             }
+            ToolType::Mcp if source_ref.entry.is_some() => {
+                tars_core::profile::pull_mcp_server(profile_uuid, &tool.name, source_ref)
+                    .map_err(|e| format!("Failed to copy MCP server: {e}"))?
+            }
             _ => return Err("Unsupported tool type for update".to_string()),
         };
 
@@ -2653,6 +2751,102 @@ pub async fn set_tool_source_mode(
     })
 }
 
+/// Follow or pin the installed version of a plugin in a profile
+///
+/// `mode` is "track" or "pin"; `None` stops recording a version.
+#[tauri::command]
+pub async fn set_plugin_version_mode(
+    state: State<'_, AppState>,
+    profile_id: String,
+    plugin_id: String,
+    mode: Option<String>,
+) -> Result<(), String> {
+    let profile_uuid =
+        uuid::Uuid::parse_str(&profile_id).map_err(|e| format!("Invalid bundle ID: {e}"))?;
+    let source_mode = match mode.as_deref() {
+        None => None,
+        Some("pin") => Some(SourceMode::Pin),
+        Some("track") => Some(SourceMode::Track),
+        Some(_) => return Err("Invalid mode. Use 'pin' or 'track'.".to_string()),
+    };
+
+    state.with_db(|db| {
+        let store = ProfileStore::new(db.connection());
+        let mut profile = store
+            .get(profile_uuid)
+            .map_err(|e| format!("Database error: {e}"))?
+            .ok_or("Bundle not found")?;
+        let plugin = profile
+            .plugin_set
+            .plugins
+            .iter_mut()
+            .find(|p| p.id == plugin_id)
+            .ok_or("Plugin not found in bundle")?;
+
+        match source_mode {
+            None => plugin.version_ref = None,
+            // Switching between track and pin keeps the recorded version
+            Some(mode) if plugin.version_ref.is_some() => {
+                if let Some(version_ref) = plugin.version_ref.as_mut() {
+                    version_ref.mode = mode;
+                }
+            }
+            Some(mode) => {
+                let recorded =
+                    tars_core::profile::record_plugin_version(db.connection(), plugin, mode)
+                        .map_err(|e| format!("Database error: {e}"))?;
+                if !recorded {
+                    return Err("No installed version is known for this plugin".to_string());
+                }
+            }
+        }
+
+        store
+            .update(&profile)
+            .map_err(|e| format!("Failed to update bundle: {e}"))
+    })
+}
+
+/// Record the currently installed version of a tracked plugin in a profile
+#[tauri::command]
+pub async fn pull_plugin_update(
+    state: State<'_, AppState>,
+    profile_id: String,
+    plugin_id: String,
+) -> Result<(), String> {
+    let profile_uuid =
+        uuid::Uuid::parse_str(&profile_id).map_err(|e| format!("Invalid bundle ID: {e}"))?;
+
+    state.with_db(|db| {
+        let store = ProfileStore::new(db.connection());
+        let mut profile = store
+            .get(profile_uuid)
+            .map_err(|e| format!("Database error: {e}"))?
+            .ok_or("Bundle not found")?;
+        let plugin = profile
+            .plugin_set
+            .plugins
+            .iter_mut()
+            .find(|p| p.id == plugin_id)
+            .ok_or("Plugin not found in bundle")?;
+        let mode = plugin
+            .version_ref
+            .as_ref()
+            .map(|v| v.mode)
+            .ok_or("Plugin does not follow a version")?;
+
+        let recorded = tars_core::profile::record_plugin_version(db.connection(), plugin, mode)
+            .map_err(|e| format!("Database error: {e}"))?;
+        if !recorded {
+            return Err("No installed version is known for this plugin".to_string());
+        }
+
+        store
+            .update(&profile)
+            .map_err(|e| format!("Failed to update bundle: {e}"))
+    })
+}
+
 /// Assign a profile to a project as a plugin
 #[tauri::command]
 pub async fn assign_profile_as_plugin(
@@ -2710,8 +2904,10 @@ pub async fn assign_profile_as_plugin(
         .await;
     }
 
-    let marketplace_sync = sync_profile_marketplace(&profile)
-        .map_err(|e| format!("Failed to sync bundle marketplace: {e}"))?;
+    let marketplace_sync = state.with_db(|db| {
+        sync_profile_marketplace(db.connection(), &profile)
+            .map_err(|e| format!("Failed to sync bundle marketplace: {e}"))
+    })?;
 
     ensure_profile_marketplace(&marketplace_sync.marketplace_path)
         .await
@@ -2871,8 +3067,10 @@ pub async fn install_profile_to_project(
         Ok((profile, project))
     })?;
 
-    let marketplace_sync = sync_profile_marketplace(&profile)
-        .map_err(|e| format!("Failed to sync bundle marketplace: {e}"))?;
+    let marketplace_sync = state.with_db(|db| {
+        sync_profile_marketplace(db.connection(), &profile)
+            .map_err(|e| format!("Failed to sync bundle marketplace: {e}"))
+    })?;
 
     ensure_profile_marketplace(&marketplace_sync.marketplace_path)
         .await
@@ -2909,8 +3107,10 @@ pub async fn install_profile_to_user(
             .ok_or("Bundle not found".to_string())
     })?;
 
-    let marketplace_sync = sync_profile_marketplace(&profile)
-        .map_err(|e| format!("Failed to sync bundle marketplace: {e}"))?;
+    let marketplace_sync = state.with_db(|db| {
+        sync_profile_marketplace(db.connection(), &profile)
+            .map_err(|e| format!("Failed to sync bundle marketplace: {e}"))
+    })?;
 
     ensure_profile_marketplace(&marketplace_sync.marketplace_path)
         .await
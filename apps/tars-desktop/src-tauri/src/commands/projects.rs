@@ -2,10 +2,12 @@
 //!
 //! Commands for managing tracked projects.
 
+use crate::commands::assignment_rules::{assign_new_project, RuleAssignment};
 use crate::state::AppState;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::process::Command;
+use tars_core::profile::propose_assignment;
 use tars_core::storage::ProjectStore;
 use tars_core::Project;
 use tauri::State;
@@ -18,6 +20,9 @@ pub struct ProjectInfo {
     pub path: String,
     pub created_at: String,
     pub updated_at: String,
+    /// Bundle proposed or applied by an assignment rule when the project was added
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule_assignment: Option<RuleAssignment>,
 }
 
 impl From<tars_core::storage::projects::ProjectSummary> for ProjectInfo {
@@ -28,6 +33,7 @@ impl From<tars_core::storage::projects::ProjectSummary> for ProjectInfo {
             path: p.path.display().to_string(),
             created_at: p.created_at.to_rfc3339(),
             updated_at: p.updated_at.to_rfc3339(),
+            rule_assignment: None,
         }
     }
 }
@@ -60,7 +66,8 @@ pub async fn add_project(path: String, state: State<'_, AppState>) -> Result<Pro
         .unwrap_or("Unknown")
         .to_string();
 
-    let project = Project::new(project_path).with_name(name);
+    let mut project = Project::new(project_path).with_name(name);
+    let backup_dir = state.data_dir().join("backups");
 
     state.with_db(|db| {
         let store = ProjectStore::new(db.connection());
@@ -74,16 +81,24 @@ pub async fn add_project(path: String, state: State<'_, AppState>) -> Result<Pro
             return Err("Project already tracked".to_string());
         }
 
+        // Evaluate the rules first so a failure leaves nothing registered
+        let proposal = propose_assignment(db.connection(), &project)
+            .map_err(|e| format!("Failed to evaluate assignment rules: {e}"))?;
+
         store
             .create(&project)
             .map_err(|e| format!("Failed to add project: {e}"))?;
 
+        let rule_assignment =
+            proposal.map(|proposal| assign_new_project(db, &mut project, &proposal, &backup_dir));
+
         Ok(ProjectInfo {
             id: project.id.to_string(),
             name: project.name,
             path: project.path.display().to_string(),
             created_at: project.created_at.to_rfc3339(),
             updated_at: project.updated_at.to_rfc3339(),
+            rule_assignment,
         })
     })
 }
@@ -106,6 +121,7 @@ pub async fn get_project(id: String, state: State<'_, AppState>) -> Result<Proje
             path: project.path.display().to_string(),
             created_at: project.created_at.to_rfc3339(),
             updated_at: project.updated_at.to_rfc3339(),
+            rule_assignment: None,
         })
     })
 }
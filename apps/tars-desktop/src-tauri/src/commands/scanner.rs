@@ -2,11 +2,12 @@
 //!
 //! Commands for scanning Claude Code configuration.
 
+use crate::commands::assignment_rules::{suggest_for_path, RuleSuggestion};
 use crate::state::AppState;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
-use tars_core::storage::{PluginVersionStore, ProfileStore};
+use tars_core::storage::{PluginVersionStore, ProfileStore, ProjectStore};
 use tars_scanner::artifacts::{AgentInfo, CommandInfo, SkillInfo};
 use tars_scanner::scope::user::{
     scan_agents_directory, scan_commands_directory, scan_skills_directory,
@@ -124,13 +125,15 @@ pub struct DiscoveredProject {
     pub has_claude_md: bool,
     /// Whether it has an .mcp.json file
     pub has_mcp_json: bool,
+    /// Bundle an assignment rule proposes for it
+    pub suggested_bundle: Option<RuleSuggestion>,
 }
 
 /// Discover all Claude projects in a directory (non-recursive first level only)
 #[tauri::command]
 pub async fn discover_claude_projects(
     folder: String,
-    _state: State<'_, AppState>,
+    state: State<'_, AppState>,
 ) -> Result<Vec<DiscoveredProject>, String> {
     let folder_path = PathBuf::from(&folder);
 
@@ -180,12 +183,24 @@ pub async fn discover_claude_projects(
                 .unwrap_or("Unknown")
                 .to_string();
 
+            // Rules only propose bundles for projects that are not tracked yet
+            let suggested_bundle = state.with_db(|db| {
+                let tracked = ProjectStore::new(db.connection())
+                    .get_by_path(&path)
+                    .map_err(|e| format!("Database error: {e}"))?;
+                Ok(match tracked {
+                    Some(_) => None,
+                    None => suggest_for_path(db, &path),
+                })
+            })?;
+
             projects.push(DiscoveredProject {
                 path: path.to_string_lossy().to_string(),
                 name,
                 has_claude_dir,
                 has_claude_md,
                 has_mcp_json,
+                suggested_bundle,
             });
         }
     }
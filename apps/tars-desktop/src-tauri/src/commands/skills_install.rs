@@ -7,11 +7,16 @@
 
 use std::path::PathBuf;
 
+use tauri::State;
+
 use tars_core::skills::{
-    adopt_resident_skill, external_skills_dir, install_bundles, parse_git_skill_url,
-    SkillInstallReport,
+    adopt_resident_skill, apply_skill_update, check_skill_updates as check_skills,
+    external_skills_dir, install_bundles, install_from_git, parse_git_skill_url,
+    prepare_skill_update, SkillInstallReport, SkillUpdateCheck, SkillUpdatePreview, UpdateTarget,
 };
 
+use crate::state::AppState;
+
 /// Copy the skill bundle(s) found in a user-picked folder into
 /// `~/.agents/skills`. Never overwrites an existing skill.
 #[tauri::command]
@@ -38,42 +43,80 @@ pub async fn adopt_skill(source_dir: String) -> Result<String, String> {
     Ok(dest.display().to_string())
 }
 
-/// Shallow-clone an https git repo (optionally a `/tree/<ref>/<subpath>` URL)
-/// and install every skill bundle found into `~/.agents/skills`.
+/// Clone an https git repo (optionally a `/tree/<ref>/<subpath>` URL) and
+/// install every skill bundle found into `~/.agents/skills`, recording each
+/// bundle's origin. With `pin`, that commit is installed and pinned.
 #[tauri::command]
-pub async fn install_skill_from_git(url: String) -> Result<SkillInstallReport, String> {
+pub async fn install_skill_from_git(
+    url: String,
+    pin: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<SkillInstallReport, String> {
     let source = parse_git_skill_url(&url).map_err(|e| e.to_string())?;
     let home = dirs::home_dir().ok_or("Cannot find home directory")?;
+    state.with_db(|db| {
+        install_from_git(
+            db.connection(),
+            &source,
+            pin.as_deref(),
+            &external_skills_dir(&home),
+        )
+        .map_err(|e| e.to_string())
+    })
+}
 
-    let tmp = tempfile::tempdir().map_err(|e| format!("Failed to create temp dir: {e}"))?;
-    let checkout = tmp.path().join("repo");
+/// Compare every git-installed skill with its remote's current commit.
+#[tauri::command]
+pub async fn check_skill_updates(
+    state: State<'_, AppState>,
+) -> Result<Vec<SkillUpdateCheck>, String> {
+    state.with_db(|db| check_skills(db.connection()).map_err(|e| e.to_string()))
+}
 
-    let mut cmd = tokio::process::Command::new("git");
-    cmd.arg("clone").arg("--depth").arg("1").arg("--quiet");
-    if let Some(reference) = &source.reference {
-        cmd.arg("--branch").arg(reference);
-    }
-    cmd.arg("--").arg(&source.repo_url).arg(&checkout);
+/// Diff a git-installed skill against the commit an update would move it to.
+#[tauri::command]
+pub async fn preview_skill_update(
+    skill_name: String,
+    pin: Option<String>,
+    unpin: bool,
+    state: State<'_, AppState>,
+) -> Result<SkillUpdatePreview, String> {
+    let target = update_target(pin, unpin);
+    state.with_db(|db| {
+        prepare_skill_update(db.connection(), &skill_name, &target)
+            .map(|update| update.preview)
+            .map_err(|e| e.to_string())
+    })
+}
 
-    let output = cmd
-        .output()
-        .await
-        .map_err(|e| format!("Failed to run git: {e}"))?;
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("git clone failed: {}", stderr.trim()));
-    }
+/// Move a git-installed skill to another commit. `expected_sha` is the
+/// previewed commit; the update is refused if upstream moved since.
+#[tauri::command]
+pub async fn update_skill(
+    skill_name: String,
+    pin: Option<String>,
+    unpin: bool,
+    expected_sha: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<SkillUpdatePreview, String> {
+    let target = update_target(pin, unpin);
+    state.with_db(|db| {
+        let update = prepare_skill_update(db.connection(), &skill_name, &target)
+            .map_err(|e| e.to_string())?;
+        if expected_sha
+            .as_deref()
+            .is_some_and(|sha| sha != update.preview.to_sha)
+        {
+            return Err("Upstream moved since the preview; preview the update again".to_string());
+        }
+        apply_skill_update(db.connection(), update).map_err(|e| e.to_string())
+    })
+}
 
-    let root = match &source.subpath {
-        Some(sub) => checkout.join(sub),
-        None => checkout.clone(),
-    };
-    if !root.is_dir() {
-        return Err(format!(
-            "Path not found in repository: {}",
-            source.subpath.as_deref().unwrap_or("")
-        ));
+fn update_target(pin: Option<String>, unpin: bool) -> UpdateTarget {
+    match pin {
+        Some(sha) => UpdateTarget::Pin(sha),
+        None if unpin => UpdateTarget::Unpin,
+        None => UpdateTarget::Latest,
     }
-
-    install_bundles(&root, &external_skills_dir(&home)).map_err(|e| e.to_string())
 }
@@ -100,12 +100,32 @@ pub fn run() {
             commands::list_profile_plugins,
             // Profile export/import commands
             commands::export_profile_json,
+            commands::export_profile_bundle,
+            commands::set_profile_extends,
+            commands::get_resolved_profile,
+            commands::compare_profiles,
             commands::preview_profile_import,
             commands::import_profile_json,
             // Profile update detection commands
             commands::check_profile_updates,
             commands::pull_tool_update,
             commands::set_tool_source_mode,
+            commands::set_plugin_version_mode,
+            commands::pull_plugin_update,
+            // Profile repository commands
+            commands::list_profile_repos,
+            commands::add_profile_repo,
+            commands::remove_profile_repo,
+            commands::get_profile_repo_status,
+            commands::pull_profile_repo,
+            commands::push_profile_repo,
+            // Bundle assignment rule commands
+            commands::list_assignment_rules,
+            commands::create_assignment_rule,
+            commands::update_assignment_rule,
+            commands::delete_assignment_rule,
+            commands::propose_project_assignment,
+            commands::apply_project_assignment,
             commands::assign_profile_as_plugin,
             commands::unassign_profile_plugin,
             // Profile install commands
@@ -117,6 +137,11 @@ pub fn run() {
             commands::apply_profile,
             commands::list_backups,
             commands::rollback,
+            // Batch apply commands
+            commands::preview_batch_apply,
+            commands::apply_bundle_batch,
+            commands::list_apply_batches,
+            commands::rollback_apply_batch,
             // Skill commands
             commands::read_skill,
             commands::read_supporting_file,
@@ -133,14 +158,20 @@ pub fn run() {
             commands::add_skill_source,
             commands::remove_skill_source,
             commands::scan_skill_library,
+            commands::list_skill_agents,
             commands::deploy_skill,
             commands::undeploy_skill,
             commands::get_project_skill_matrix,
             commands::set_skill_mute,
             commands::resync_skill_deployment,
+            commands::get_skill_drift,
+            commands::resolve_skill_drift,
             commands::set_project_plugin_enabled,
             commands::import_skill_folder,
             commands::install_skill_from_git,
+            commands::check_skill_updates,
+            commands::preview_skill_update,
+            commands::update_skill,
             commands::adopt_skill,
             // Agent commands
             commands::read_agent,
@@ -221,6 +252,7 @@ pub fn run() {
             commands::get_app_version,
             commands::get_platform_info,
             commands::get_claude_usage_stats,
+            commands::get_claude_usage_cost,
             // Runtime commands
             commands::get_runtime_statuses,
             // Settings commands
@@ -257,10 +289,16 @@ pub fn run() {
             commands::delete_project_secret,
             // API keys vault commands
             commands::list_providers,
+            commands::list_custom_providers,
+            commands::save_custom_provider,
+            commands::delete_custom_provider,
             commands::add_api_key,
             commands::list_api_keys,
             commands::delete_api_key,
             commands::validate_api_key,
+            commands::test_api_key_completion,
+            commands::get_balance_history,
+            commands::set_balance_threshold,
             commands::refresh_models,
             commands::reveal_api_key,
             commands::list_provider_models,
@@ -284,12 +322,19 @@ pub fn run() {
             commands::add_developer_command,
             commands::update_developer_command,
             commands::delete_developer_command,
+            // Key health commands
+            commands::validate_all_api_keys,
+            commands::get_key_validation_history,
+            commands::list_failing_api_keys,
+            commands::get_key_validation_schedule,
+            commands::set_key_validation_schedule,
             // Pricing commands
             commands::refresh_pricing,
             commands::get_pricing_metadata,
         ])
         .setup(|app| {
             spawn_pricing_refresh_loop(app.handle().clone());
+            spawn_key_validation_loop(app.handle().clone());
             Ok(())
         })
         .run(tauri::generate_context!())
@@ -342,3 +387,55 @@ fn spawn_pricing_refresh_loop(app_handle: tauri::AppHandle) {
         }
     });
 }
+
+/// Spawn a background task that re-validates stored API keys whenever the
+/// saved `ValidationSchedule` is due. The schedule is re-read on every tick,
+/// so changes from the settings UI apply without a restart.
+fn spawn_key_validation_loop(app_handle: tauri::AppHandle) {
+    use std::time::Duration;
+    use tars_core::storage::key_validations::KeyValidationStore;
+
+    const TICK: Duration = Duration::from_mins(15);
+    // Run after the pricing refresh's startup delay so the two network
+    // bursts do not coincide.
+    const STARTUP_DELAY: Duration = Duration::from_mins(1);
+
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(STARTUP_DELAY).await;
+
+        loop {
+            let state: tauri::State<'_, AppState> = app_handle.state();
+            let due = state
+                .with_db(|db: &Database| {
+                    let store = KeyValidationStore::new(db.connection());
+                    let schedule = store.schedule().map_err(|e| e.to_string())?;
+                    let last_run = store.last_run().map_err(|e| e.to_string())?;
+                    Ok(schedule.is_due(last_run, chrono::Utc::now()))
+                })
+                .unwrap_or(false);
+
+            if due {
+                match commands::key_health::run_key_validation(&state).await {
+                    Ok(summary) => {
+                        for key in &summary.newly_failing {
+                            eprintln!(
+                                "API key '{}' ({}) started failing: {}",
+                                key.label,
+                                key.provider_id,
+                                key.last_message.as_deref().unwrap_or("rejected")
+                            );
+                        }
+                        let _ = state.with_db(|db: &Database| {
+                            KeyValidationStore::new(db.connection())
+                                .set_last_run(chrono::Utc::now())
+                                .map_err(|e| e.to_string())
+                        });
+                    }
+                    Err(e) => eprintln!("Background key validation failed: {e}"),
+                }
+            }
+
+            tokio::time::sleep(TICK).await;
+        }
+    });
+}
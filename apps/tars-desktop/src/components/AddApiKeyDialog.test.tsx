@@ -12,6 +12,8 @@ const openai: ProviderMetadata = {
   key_format_hint: 'sk-...',
   supports_models: true,
   supports_balance: false,
+  supports_completion: false,
+  custom: false,
 };
 
 const invokeMock = vi.mocked(invoke);
@@ -0,0 +1,197 @@
+import { useEffect, useState } from 'react';
+import { toast } from 'sonner';
+import { useMutation, useQueryClient } from '@tanstack/react-query';
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from './ui/dialog';
+import { saveCustomProvider, type CustomAuthStyle } from '../lib/ipc';
+
+export interface AddCustomProviderDialogProps {
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+}
+
+type AuthKind = CustomAuthStyle['kind'];
+
+// Slug derived from the display name; mirrors the backend rule
+// (lowercase letters, digits, '-', '_', '.').
+function slugify(name: string): string {
+  return name
+    .toLowerCase()
+    .replace(/[^a-z0-9._-]+/g, '-')
+    .replace(/^-+|-+$/g, '')
+    .slice(0, 64);
+}
+
+const inputClass =
+  'w-full px-3 py-2 text-sm rounded-md border border-border bg-background focus:outline-none focus:ring-2 focus:ring-ring';
+
+export function AddCustomProviderDialog({ open, onOpenChange }: AddCustomProviderDialogProps) {
+  const [displayName, setDisplayName] = useState('');
+  const [baseUrl, setBaseUrl] = useState('');
+  const [authKind, setAuthKind] = useState<AuthKind>('bearer');
+  const [headerName, setHeaderName] = useState('');
+  const [modelsPath, setModelsPath] = useState('');
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    setDisplayName('');
+    setBaseUrl('');
+    setAuthKind('bearer');
+    setHeaderName('');
+    setModelsPath('');
+  }, [open]);
+
+  const mutation = useMutation({
+    mutationFn: () => {
+      const auth_style: CustomAuthStyle =
+        authKind === 'header' ? { kind: 'header', name: headerName.trim() } : { kind: authKind };
+      return saveCustomProvider({
+        slug: slugify(displayName),
+        display_name: displayName.trim(),
+        base_url: baseUrl.trim(),
+        auth_style,
+        models_path: modelsPath.trim() || null,
+      });
+    },
+    onSuccess: (p) => {
+      queryClient.invalidateQueries({ queryKey: ['providers'] });
+      toast.success(`Added ${p.display_name}`);
+      onOpenChange(false);
+    },
+    onError: (err) => {
+      toast.error(`Failed to add provider: ${String(err)}`);
+    },
+  });
+
+  const canSubmit =
+    slugify(displayName).length > 0 &&
+    /^https?:\/\/.+/.test(baseUrl.trim()) &&
+    (authKind !== 'header' || headerName.trim().length > 0) &&
+    !mutation.isPending;
+
+  return (
+    <Dialog open={open} onOpenChange={onOpenChange}>
+      <DialogContent>
+        <DialogHeader>
+          <DialogTitle>Add custom provider</DialogTitle>
+          <DialogDescription>
+            Any OpenAI-compatible endpoint: Ollama, LM Studio, vLLM or a self-hosted gateway.
+          </DialogDescription>
+        </DialogHeader>
+
+        <form
+          onSubmit={(e) => {
+            e.preventDefault();
+            if (!canSubmit) return;
+            mutation.mutate();
+          }}
+          className="space-y-4"
+        >
+          <div className="space-y-1">
+            <label htmlFor="custom-provider-name" className="text-sm font-medium">
+              Name
+            </label>
+            <input
+              id="custom-provider-name"
+              type="text"
+              value={displayName}
+              onChange={(e) => setDisplayName(e.target.value)}
+              placeholder="e.g. Local Ollama"
+              autoComplete="off"
+              className={inputClass}
+            />
+          </div>
+
+          <div className="space-y-1">
+            <label htmlFor="custom-provider-url" className="text-sm font-medium">
+              Base URL
+            </label>
+            <input
+              id="custom-provider-url"
+              type="text"
+              value={baseUrl}
+              onChange={(e) => setBaseUrl(e.target.value)}
+              placeholder="http://localhost:11434/v1"
+              autoComplete="off"
+              spellCheck={false}
+              className={`${inputClass} font-mono`}
+            />
+          </div>
+
+          <div className="space-y-1">
+            <label htmlFor="custom-provider-auth" className="text-sm font-medium">
+              Authentication
+            </label>
+            <select
+              id="custom-provider-auth"
+              value={authKind}
+              onChange={(e) => setAuthKind(e.target.value as AuthKind)}
+              className={inputClass}
+            >
+              <option value="bearer">Authorization: Bearer</option>
+              <option value="header">Custom header</option>
+              <option value="none">None</option>
+            </select>
+          </div>
+
+          {authKind === 'header' && (
+            <div className="space-y-1">
+              <label htmlFor="custom-provider-header" className="text-sm font-medium">
+                Header name
+              </label>
+              <input
+                id="custom-provider-header"
+                type="text"
+                value={headerName}
+                onChange={(e) => setHeaderName(e.target.value)}
+                placeholder="api-key"
+                autoComplete="off"
+                spellCheck={false}
+                className={`${inputClass} font-mono`}
+              />
+            </div>
+          )}
+
+          <div className="space-y-1">
+            <label htmlFor="custom-provider-models-path" className="text-sm font-medium">
+              Models path <span className="text-muted-foreground font-normal">(optional)</span>
+            </label>
+            <input
+              id="custom-provider-models-path"
+              type="text"
+              value={modelsPath}
+              onChange={(e) => setModelsPath(e.target.value)}
+              placeholder="/models"
+              autoComplete="off"
+              spellCheck={false}
+              className={`${inputClass} font-mono`}
+            />
+          </div>
+
+          <DialogFooter>
+            <button
+              type="button"
+              onClick={() => onOpenChange(false)}
+              className="px-4 py-2 text-sm rounded-md border border-border hover:bg-muted/50 transition-colors"
+            >
+              Cancel
+            </button>
+            <button
+              type="submit"
+              disabled={!canSubmit}
+              className="px-4 py-2 text-sm rounded-md bg-primary text-primary-foreground hover:bg-primary/90 disabled:opacity-50 disabled:cursor-not-allowed transition-colors"
+            >
+              {mutation.isPending ? 'Saving…' : 'Save'}
+            </button>
+          </DialogFooter>
+        </form>
+      </DialogContent>
+    </Dialog>
+  );
+}
@@ -13,6 +13,8 @@ const openaiMeta: ProviderMetadata = {
   key_format_hint: 'sk-...',
   supports_models: true,
   supports_balance: false,
+  supports_completion: false,
+  custom: false,
 };
 
 const deepseekMeta: ProviderMetadata = {
@@ -22,6 +24,8 @@ const deepseekMeta: ProviderMetadata = {
   key_format_hint: 'sk-...',
   supports_models: true,
   supports_balance: true,
+  supports_completion: false,
+  custom: false,
 };
 
 function makeKey(overrides: Partial<ApiKeySummary> = {}): ApiKeySummary {
@@ -32,6 +36,10 @@ function makeKey(overrides: Partial<ApiKeySummary> = {}): ApiKeySummary {
     last_validated_at: null,
     last_valid: null,
     balance: null,
+    balance_amount: null,
+    balance_currency: null,
+    balance_threshold: null,
+    low_balance: false,
     created_at: '2026-04-01T00:00:00Z',
     updated_at: '2026-04-01T00:00:00Z',
     ...overrides,
@@ -98,6 +106,28 @@ describe('ApiKeyProviderCard — shell', () => {
     );
     expect(screen.queryByTestId('balance-badge')).not.toBeInTheDocument();
   });
+
+  it('prefers the normalised snapshot amount and flags low balances', () => {
+    invokeMock.mockResolvedValue([]);
+    render(
+      <ApiKeyProviderCard
+        provider={deepseekMeta}
+        keys={[
+          makeKey({
+            provider_id: 'deepseek',
+            balance: { total_balance: '99.00' },
+            balance_amount: 2.5,
+            balance_currency: 'USD',
+            balance_threshold: 5,
+            low_balance: true,
+          }),
+        ]}
+        onAddKey={vi.fn()}
+      />
+    );
+    expect(screen.getByTestId('balance-badge')).toHaveTextContent('$2.50');
+    expect(screen.getByTestId('low-balance-badge')).toBeInTheDocument();
+  });
 });
 
 import userEvent from '@testing-library/user-event';
@@ -314,6 +344,8 @@ describe('ApiKeyProviderCard — interactions', () => {
       key_format_hint: 'pplx-...',
       supports_models: false,
       supports_balance: false,
+      supports_completion: false,
+      custom: false,
     };
     // last_valid is left null because the backend skips update_validation for
     // unverifiable providers. The badge column must stay empty in that case —
@@ -369,6 +401,80 @@ describe('ApiKeyProviderCard — models', () => {
     expect(screen.getByText(/128k/i)).toBeInTheDocument();
   });
 
+  it('sends a test completion for a model with the first usable key', async () => {
+    invokeMock.mockImplementation(async (cmd: string) => {
+      if (cmd === 'list_provider_models') {
+        return [
+          {
+            provider_id: 'openai',
+            model_id: 'gpt-4o-mini',
+            display_name: null,
+            context_window: null,
+            input_price: 0.15,
+            output_price: 0.6,
+            fetched_at: '2026-04-17T00:00:00Z',
+          },
+        ];
+      }
+      if (cmd === 'get_pricing_metadata') return noPricingMeta;
+      if (cmd === 'test_api_key_completion') {
+        return {
+          model: 'gpt-4o-mini-2024-07-18',
+          text: 'pong',
+          usage: {
+            input_tokens: 12,
+            output_tokens: 1,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
+          },
+          latency_ms: 420,
+          cost_usd: 0.0000024,
+          price_overridden: false,
+        };
+      }
+      throw new Error(`unexpected ${cmd}`);
+    });
+    const { user } = renderWithUser(
+      <ApiKeyProviderCard
+        provider={{ ...openaiMeta, supports_completion: true }}
+        keys={[makeKey({ id: 1, last_valid: false }), makeKey({ id: 2, label: 'ci' })]}
+        onAddKey={vi.fn()}
+      />
+    );
+    await user.click(await screen.findByRole('button', { name: 'Test gpt-4o-mini' }));
+    await waitFor(() =>
+      expect(invokeMock).toHaveBeenCalledWith('test_api_key_completion', {
+        id: 2,
+        model: 'gpt-4o-mini',
+        prompt: undefined,
+        maxTokens: undefined,
+      })
+    );
+  });
+
+  it('hides the test column for providers without completion support', async () => {
+    invokeMock.mockImplementation(async (cmd: string) => {
+      if (cmd === 'list_provider_models') {
+        return [
+          {
+            provider_id: 'openai',
+            model_id: 'gpt-4o',
+            display_name: null,
+            context_window: null,
+            input_price: null,
+            output_price: null,
+            fetched_at: '2026-04-17T00:00:00Z',
+          },
+        ];
+      }
+      if (cmd === 'get_pricing_metadata') return noPricingMeta;
+      throw new Error(`unexpected ${cmd}`);
+    });
+    render(<ApiKeyProviderCard provider={openaiMeta} keys={[makeKey()]} onAddKey={vi.fn()} />);
+    expect(await screen.findByText('gpt-4o')).toBeInTheDocument();
+    expect(screen.queryByRole('button', { name: 'Test gpt-4o' })).not.toBeInTheDocument();
+  });
+
   it('shows an empty model state when listProviderModels returns []', async () => {
     invokeMock.mockImplementation(async (cmd: string) => {
       if (cmd === 'list_provider_models') return [];
@@ -8,16 +8,22 @@ import {
   ShieldCheck,
   ChevronDown,
   ChevronRight,
+  BellRing,
+  Play,
 } from 'lucide-react';
 import { useEffect, useRef, useState } from 'react';
 import { toast } from 'sonner';
 import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
 import {
   deleteApiKey,
+  deleteCustomProvider,
+  getBalanceHistory,
   getPricingMetadata,
   listProviderModels,
   refreshModels,
   revealApiKey,
+  setBalanceThreshold,
+  testApiKeyCompletion,
   validateApiKey,
   type ApiKeySummary,
   type ProviderMetadata,
@@ -32,7 +38,20 @@ export interface ApiKeyProviderCardProps {
 
 const REVEAL_TIMEOUT_MS = 10_000;
 
-function formatBalance(balance: unknown): string | null {
+function formatAmount(amount: number, currency: string): string {
+  if (currency === 'USD') return `$${amount.toFixed(2)}`;
+  if (currency === 'characters') return `${Math.round(amount).toLocaleString()} chars`;
+  return `${amount.toFixed(2)} ${currency}`;
+}
+
+// Prefers the normalised amount from the latest snapshot; falls back to
+// provider-specific fields in the raw payload for keys validated before
+// snapshots existed.
+function formatBalance(k: ApiKeySummary): string | null {
+  if (k.balance_amount != null && k.balance_currency) {
+    return formatAmount(k.balance_amount, k.balance_currency);
+  }
+  const balance = k.balance;
   if (balance == null) return null;
   if (typeof balance === 'number') return `$${balance.toFixed(2)}`;
   if (typeof balance === 'object') {
@@ -76,11 +95,52 @@ function formatRelativeTime(iso: string | null): string | null {
   return `${days}d ago`;
 }
 
+const SPARKLINE_WIDTH = 80;
+const SPARKLINE_HEIGHT = 18;
+
+function BalanceSparkline({ keyId }: { keyId: number }) {
+  const historyQuery = useQuery({
+    queryKey: ['balance-history', keyId],
+    queryFn: () => getBalanceHistory(keyId),
+  });
+  const points = historyQuery.data ?? [];
+  if (points.length < 2) return null;
+
+  const amounts = points.map((p) => p.amount);
+  const min = Math.min(...amounts);
+  const range = Math.max(...amounts) - min || 1;
+  const step = SPARKLINE_WIDTH / (points.length - 1);
+  const path = amounts
+    .map((a, i) => {
+      const x = (i * step).toFixed(1);
+      const y = (SPARKLINE_HEIGHT - ((a - min) / range) * SPARKLINE_HEIGHT).toFixed(1);
+      return `${i === 0 ? 'M' : 'L'}${x},${y}`;
+    })
+    .join(' ');
+  const first = points[0];
+  const last = points[points.length - 1];
+
+  return (
+    <svg
+      data-testid="balance-sparkline"
+      width={SPARKLINE_WIDTH}
+      height={SPARKLINE_HEIGHT}
+      viewBox={`0 0 ${SPARKLINE_WIDTH} ${SPARKLINE_HEIGHT}`}
+      className="text-emerald-500 overflow-visible"
+      role="img"
+      aria-label={`Balance from ${formatAmount(first.amount, first.currency)} to ${formatAmount(last.amount, last.currency)}`}
+    >
+      <path d={path} fill="none" stroke="currentColor" strokeWidth={1.5} />
+    </svg>
+  );
+}
+
 interface ApiKeyRowProps {
   k: ApiKeySummary;
+  supportsBalance: boolean;
 }
 
-function ApiKeyRow({ k }: ApiKeyRowProps) {
+function ApiKeyRow({ k, supportsBalance }: ApiKeyRowProps) {
   const [revealedValue, setRevealedValue] = useState<string | null>(null);
   const hideTimerRef = useRef<ReturnType<typeof setTimeout> | null>(null);
   const mountedRef = useRef(true);
@@ -159,11 +219,43 @@ function ApiKeyRow({ k }: ApiKeyRowProps) {
       } else {
         toast.error(res.message ? `Invalid: ${res.message}` : 'Key is invalid');
       }
+      if (res.balance_warning) toast.warning(res.balance_warning);
       queryClient.invalidateQueries({ queryKey: ['api-keys'] });
+      queryClient.invalidateQueries({ queryKey: ['balance-history', k.id] });
+      queryClient.invalidateQueries({ queryKey: ['failing-api-keys'] });
     },
     onError: (err) => toast.error(`Validation failed: ${String(err)}`),
   });
 
+  const threshold = useMutation({
+    mutationFn: (value: number | null) => setBalanceThreshold(k.id, value),
+    onSuccess: (_, value) => {
+      toast.success(value == null ? 'Balance alert removed' : 'Balance alert saved');
+      queryClient.invalidateQueries({ queryKey: ['api-keys'] });
+    },
+    onError: (err) => toast.error(`Failed to set alert: ${String(err)}`),
+  });
+
+  const handleSetThreshold = () => {
+    const unit = k.balance_currency ?? 'USD';
+    const input = window.prompt(
+      `Warn when the balance for "${k.label}" drops below (${unit}). Leave empty to disable.`,
+      k.balance_threshold != null ? String(k.balance_threshold) : ''
+    );
+    if (input == null) return;
+    const trimmed = input.trim();
+    if (trimmed === '') {
+      threshold.mutate(null);
+      return;
+    }
+    const value = Number(trimmed);
+    if (!Number.isFinite(value) || value < 0) {
+      toast.error('Threshold must be a non-negative number');
+      return;
+    }
+    threshold.mutate(value);
+  };
+
   const handleToggleReveal = () => {
     if (revealedValue) {
       clearAutoHide();
@@ -214,6 +306,19 @@ function ApiKeyRow({ k }: ApiKeyRowProps) {
               Invalid
             </span>
           )}
+          {k.low_balance && (
+            <span
+              data-testid="low-balance-badge"
+              className="text-[10px] uppercase tracking-wide px-1.5 py-0.5 rounded bg-amber-500/15 text-amber-600 dark:text-amber-400 border border-amber-500/30"
+              title={
+                k.balance_threshold != null
+                  ? `Below ${formatAmount(k.balance_threshold, k.balance_currency ?? 'USD')}`
+                  : undefined
+              }
+            >
+              Low balance
+            </span>
+          )}
         </div>
         <div className="text-xs text-muted-foreground font-mono select-none break-all">
           {revealedValue ?? '•'.repeat(20)}
@@ -221,6 +326,11 @@ function ApiKeyRow({ k }: ApiKeyRowProps) {
         {validatedRel && (
           <div className="text-[10px] text-muted-foreground mt-0.5">Validated {validatedRel}</div>
         )}
+        {supportsBalance && k.balance_amount != null && (
+          <div className="mt-1">
+            <BalanceSparkline keyId={k.id} />
+          </div>
+        )}
       </div>
       <div className="flex items-center gap-1 shrink-0">
         <button
@@ -254,6 +364,24 @@ function ApiKeyRow({ k }: ApiKeyRowProps) {
           <ShieldCheck className="h-3 w-3" />
           Validate
         </button>
+        {supportsBalance && (
+          <button
+            type="button"
+            onClick={handleSetThreshold}
+            disabled={threshold.isPending}
+            aria-label="Set balance alert"
+            title={
+              k.balance_threshold != null
+                ? `Alert below ${formatAmount(k.balance_threshold, k.balance_currency ?? 'USD')}`
+                : 'Set a low-balance alert'
+            }
+            className="p-1.5 rounded hover:bg-muted/50 transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+          >
+            <BellRing
+              className={`h-3.5 w-3.5 ${k.balance_threshold != null ? 'text-amber-500' : ''}`}
+            />
+          </button>
+        )}
         <button
           type="button"
           onClick={handleDelete}
@@ -324,7 +452,30 @@ function ModelIdCell({ modelId, displayName }: ModelIdCellProps) {
   );
 }
 
-function ModelTable({ provider }: { provider: ProviderMetadata }) {
+function formatCost(usd: number | null): string {
+  if (usd == null) return 'unpriced';
+  return usd < 0.01 ? `$${usd.toFixed(6)}` : `$${usd.toFixed(4)}`;
+}
+
+interface ModelTableProps {
+  provider: ProviderMetadata;
+  /** Key used for "Test" completions; the column is hidden without one. */
+  testKeyId: number | null;
+}
+
+function ModelTable({ provider, testKeyId }: ModelTableProps) {
+  const testCompletion = useMutation({
+    mutationFn: (model: string) => testApiKeyCompletion(testKeyId as number, model),
+    onSuccess: (r) => {
+      const tokens = r.usage.input_tokens + r.usage.cache_read_tokens + r.usage.cache_write_tokens;
+      toast.success(`${r.model} replied "${r.text.trim()}"`, {
+        description: `${r.latency_ms} ms · ${tokens} in / ${r.usage.output_tokens} out · ${formatCost(r.cost_usd)}`,
+      });
+    },
+    onError: (err, model) => toast.error(`${model}: ${String(err)}`),
+  });
+  const canTest = provider.supports_completion && testKeyId != null;
+
   const modelsQuery = useQuery({
     queryKey: ['provider-models', provider.id],
     queryFn: () => listProviderModels(provider.id),
@@ -373,6 +524,7 @@ function ModelTable({ provider }: { provider: ProviderMetadata }) {
                 <th className="text-right px-2 py-1.5 font-medium">Context</th>
                 <th className="text-right px-2 py-1.5 font-medium">In $/1M</th>
                 <th className="text-right px-2 py-1.5 font-medium">Out $/1M</th>
+                {canTest && <th className="px-2 py-1.5" />}
               </tr>
             </thead>
             <tbody>
@@ -388,6 +540,20 @@ function ModelTable({ provider }: { provider: ProviderMetadata }) {
                   <td className="px-2 py-1.5 text-right font-mono">
                     {formatPrice(m.output_price)}
                   </td>
+                  {canTest && (
+                    <td className="px-2 py-1.5 text-right">
+                      <button
+                        type="button"
+                        onClick={() => testCompletion.mutate(m.model_id)}
+                        disabled={testCompletion.isPending}
+                        aria-label={`Test ${m.model_id}`}
+                        title="Send a short prompt with this key and show usage and cost"
+                        className="p-1 rounded hover:bg-muted/50 text-muted-foreground disabled:opacity-50"
+                      >
+                        <Play className="h-3 w-3" />
+                      </button>
+                    </td>
+                  )}
                 </tr>
               ))}
             </tbody>
@@ -421,8 +587,9 @@ function writeModelsExpanded(providerId: string, expanded: boolean): void {
 export function ApiKeyProviderCard({ provider, keys, onAddKey }: ApiKeyProviderCardProps) {
   const queryClient = useQueryClient();
   const balanceText = provider.supports_balance
-    ? (keys.map((k) => formatBalance(k.balance)).find((b) => b != null) ?? null)
+    ? (keys.map(formatBalance).find((b) => b != null) ?? null)
     : null;
+  const anyLowBalance = provider.supports_balance && keys.some((k) => k.low_balance);
 
   const [modelsExpanded, setModelsExpanded] = useState<boolean>(() =>
     readModelsExpanded(provider.id)
@@ -457,6 +624,26 @@ export function ApiKeyProviderCard({ provider, keys, onAddKey }: ApiKeyProviderC
     onError: (err) => toast.error(`Refresh failed: ${String(err)}`),
   });
 
+  const removeProvider = useMutation({
+    mutationFn: () => deleteCustomProvider(provider.id.replace(/^custom:/, '')),
+    onSuccess: () => {
+      toast.success(`Removed ${provider.display_name}`);
+      queryClient.invalidateQueries({ queryKey: ['providers'] });
+      queryClient.invalidateQueries({ queryKey: ['api-keys'] });
+    },
+    onError: (err) => toast.error(`Failed to remove provider: ${String(err)}`),
+  });
+
+  const handleRemoveProvider = () => {
+    if (
+      !window.confirm(
+        `Remove ${provider.display_name}? Its ${keys.length} stored key(s) and cached models are deleted too.`
+      )
+    )
+      return;
+    removeProvider.mutate();
+  };
+
   return (
     <div className="rounded-lg border border-border bg-card p-4 flex flex-col gap-4">
       <div className="flex items-start justify-between gap-2">
@@ -468,16 +655,26 @@ export function ApiKeyProviderCard({ provider, keys, onAddKey }: ApiKeyProviderC
           />
           <div className="min-w-0">
             <h2 className="font-semibold truncate">{provider.display_name}</h2>
-            <p className="text-xs text-muted-foreground truncate" title={provider.key_format_hint}>
-              Format: {provider.key_format_hint}
-            </p>
+            {provider.custom ? (
+              <p className="text-xs text-muted-foreground truncate font-mono" title={provider.docs_url}>
+                {provider.docs_url}
+              </p>
+            ) : (
+              <p className="text-xs text-muted-foreground truncate" title={provider.key_format_hint}>
+                Format: {provider.key_format_hint}
+              </p>
+            )}
           </div>
         </div>
         {provider.supports_balance && (
           <span
             data-testid="balance-badge"
-            className="shrink-0 text-xs font-mono px-2 py-0.5 rounded-full bg-emerald-500/15 text-emerald-600 dark:text-emerald-400 border border-emerald-500/30"
-            title="Account balance"
+            className={`shrink-0 text-xs font-mono px-2 py-0.5 rounded-full border ${
+              anyLowBalance
+                ? 'bg-amber-500/15 text-amber-600 dark:text-amber-400 border-amber-500/30'
+                : 'bg-emerald-500/15 text-emerald-600 dark:text-emerald-400 border-emerald-500/30'
+            }`}
+            title={anyLowBalance ? 'Account balance is below the alert threshold' : 'Account balance'}
           >
             {balanceText ?? '—'}
           </span>
@@ -491,7 +688,7 @@ export function ApiKeyProviderCard({ provider, keys, onAddKey }: ApiKeyProviderC
       ) : (
         <ul className="space-y-2">
           {keys.map((k) => (
-            <ApiKeyRow key={k.id} k={k} />
+            <ApiKeyRow key={k.id} k={k} supportsBalance={provider.supports_balance} />
           ))}
         </ul>
       )}
@@ -519,7 +716,10 @@ export function ApiKeyProviderCard({ provider, keys, onAddKey }: ApiKeyProviderC
           </button>
           {modelsExpanded && (
             <div id={`models-panel-${provider.id}`}>
-              <ModelTable provider={provider} />
+              <ModelTable
+                provider={provider}
+                testKeyId={keys.find((k) => k.last_valid !== false)?.id ?? null}
+              />
             </div>
           )}
         </div>
@@ -550,6 +750,18 @@ export function ApiKeyProviderCard({ provider, keys, onAddKey }: ApiKeyProviderC
             Refresh Models
           </button>
         )}
+        {provider.custom && (
+          <button
+            type="button"
+            onClick={handleRemoveProvider}
+            disabled={removeProvider.isPending}
+            title="Remove this custom provider"
+            className="ml-auto flex items-center gap-1.5 px-3 py-1.5 text-sm rounded-md border border-border text-destructive hover:bg-destructive/10 transition-colors disabled:opacity-50"
+          >
+            <Trash2 className="h-3.5 w-3.5" />
+            Remove
+          </button>
+        )}
       </div>
     </div>
   );
@@ -34,7 +34,7 @@ export function ImportProfileDialog({
   const handleSelectFile = async () => {
     try {
       const path = await open({
-        filters: [{ name: 'TARS Bundle', extensions: ['tars-profile.json', 'json'] }],
+        filters: [{ name: 'TARS Bundle', extensions: ['json', 'zip'] }],
         multiple: false,
       });
 
@@ -108,7 +108,7 @@ export function ImportProfileDialog({
             >
               <FileUp className="h-8 w-8 text-muted-foreground mx-auto mb-2" />
               <p className="text-sm font-medium">Select a bundle file</p>
-              <p className="text-xs text-muted-foreground mt-1">.tars-profile.json files</p>
+              <p className="text-xs text-muted-foreground mt-1">.tars-profile.json or .tars-profile.zip files</p>
             </button>
           ) : loading ? (
             <div className="flex items-center justify-center py-8">
@@ -123,10 +123,38 @@ export function ImportProfileDialog({
                 )}
                 <div className="flex items-center gap-4 mt-2 text-xs text-muted-foreground">
                   <span>{preview.tool_count} tools</span>
+                  {preview.file_count > 0 && <span>{preview.file_count} files</span>}
                   <span>v{preview.version}</span>
                 </div>
               </div>
 
+              {preview.conflicts.length > 0 && (
+                <div className="space-y-1">
+                  <div className="text-xs font-medium text-muted-foreground">
+                    Already installed locally
+                  </div>
+                  <ul className="space-y-1 text-xs">
+                    {preview.conflicts.map((conflict) => (
+                      <li
+                        key={`${conflict.kind}:${conflict.name}`}
+                        className="flex items-center justify-between gap-2"
+                        title={conflict.local_path}
+                      >
+                        <span className="truncate">
+                          {conflict.name}{' '}
+                          <span className="text-muted-foreground">({conflict.kind})</span>
+                        </span>
+                        {conflict.identical ? (
+                          <span className="text-muted-foreground shrink-0">identical</span>
+                        ) : (
+                          <span className="text-amber-500 shrink-0">differs</span>
+                        )}
+                      </li>
+                    ))}
+                  </ul>
+                </div>
+              )}
+
               {preview.has_name_collision && (
                 <div className="space-y-2">
                   <div className="flex items-start gap-2 text-amber-500 text-sm">
@@ -0,0 +1,55 @@
+import { describe, it, expect, vi, beforeEach } from 'vitest';
+import { render, screen } from '../test/test-utils';
+import { KeyHealthPanel } from './KeyHealthPanel';
+import type { FailingKey, ValidationSchedule } from '../lib/ipc';
+import { invoke } from '@tauri-apps/api/core';
+
+const invokeMock = vi.mocked(invoke);
+
+const schedule: ValidationSchedule = {
+  enabled: true,
+  interval_hours: 24,
+  per_provider_concurrency: 2,
+};
+
+function mockCommands(failing: FailingKey[]) {
+  invokeMock.mockImplementation(async (cmd: string) => {
+    if (cmd === 'list_failing_api_keys') return failing;
+    if (cmd === 'get_key_validation_schedule') return schedule;
+    return undefined;
+  });
+}
+
+describe('KeyHealthPanel', () => {
+  beforeEach(() => {
+    invokeMock.mockReset();
+  });
+
+  it('lists keys that stopped working', async () => {
+    mockCommands([
+      {
+        api_key_id: 3,
+        provider_id: 'openai',
+        label: 'ci',
+        last_valid_at: '2026-05-01T00:00:00Z',
+        failing_since: '2026-05-02T00:00:00Z',
+        consecutive_failures: 2,
+        last_message: 'Key rejected by OpenAI (HTTP 401)',
+      },
+    ]);
+    render(<KeyHealthPanel />);
+
+    expect(await screen.findByRole('alert')).toHaveTextContent('1 key stopped working');
+    expect(screen.getByText('ci')).toBeInTheDocument();
+    expect(screen.getByRole('alert')).toHaveTextContent('HTTP 401');
+  });
+
+  it('shows schedule controls and no banner when all keys are healthy', async () => {
+    mockCommands([]);
+    render(<KeyHealthPanel />);
+
+    expect(await screen.findByLabelText('Validation interval')).toHaveValue('24');
+    expect(screen.getByRole('button', { name: /validate all/i })).toBeInTheDocument();
+    expect(screen.queryByRole('alert')).not.toBeInTheDocument();
+  });
+});
@@ -0,0 +1,149 @@
+import { AlertTriangle, RefreshCw } from 'lucide-react';
+import { toast } from 'sonner';
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import {
+  getKeyValidationSchedule,
+  listFailingApiKeys,
+  setKeyValidationSchedule,
+  validateAllApiKeys,
+  type ValidationSchedule,
+} from '../lib/ipc';
+
+const INTERVAL_OPTIONS = [
+  { hours: 6, label: 'Every 6 hours' },
+  { hours: 12, label: 'Every 12 hours' },
+  { hours: 24, label: 'Daily' },
+  { hours: 168, label: 'Weekly' },
+];
+
+function formatSince(iso: string): string {
+  const d = new Date(iso);
+  return Number.isNaN(d.getTime()) ? iso : d.toLocaleString();
+}
+
+/**
+ * Background validation controls plus a banner for keys that used to work
+ * and are now rejected (revoked or expired keys break MCP servers silently).
+ */
+export function KeyHealthPanel() {
+  const queryClient = useQueryClient();
+
+  const failingQuery = useQuery({
+    queryKey: ['failing-api-keys'],
+    queryFn: listFailingApiKeys,
+  });
+
+  const scheduleQuery = useQuery({
+    queryKey: ['key-validation-schedule'],
+    queryFn: getKeyValidationSchedule,
+  });
+
+  const validateAll = useMutation({
+    mutationFn: validateAllApiKeys,
+    onSuccess: (summary) => {
+      const parts = [`${summary.valid} valid`, `${summary.invalid} invalid`];
+      if (summary.errors > 0) parts.push(`${summary.errors} unreachable`);
+      toast.success(`Checked ${summary.checked} keys: ${parts.join(', ')}`);
+      for (const key of summary.newly_failing) {
+        toast.error(`"${key.label}" stopped working`);
+      }
+      queryClient.invalidateQueries({ queryKey: ['api-keys'] });
+      queryClient.invalidateQueries({ queryKey: ['failing-api-keys'] });
+    },
+    onError: (err) => toast.error(`Validation failed: ${String(err)}`),
+  });
+
+  const saveSchedule = useMutation({
+    mutationFn: setKeyValidationSchedule,
+    onSuccess: () => queryClient.invalidateQueries({ queryKey: ['key-validation-schedule'] }),
+    onError: (err) => toast.error(`Failed to save schedule: ${String(err)}`),
+  });
+
+  const schedule = scheduleQuery.data;
+  const update = (patch: Partial<ValidationSchedule>) => {
+    if (schedule) saveSchedule.mutate({ ...schedule, ...patch });
+  };
+  const failing = failingQuery.data ?? [];
+
+  return (
+    <div className="space-y-3 mb-4">
+      {failing.length > 0 && (
+        <div
+          role="alert"
+          className="rounded-md border border-destructive/40 bg-destructive/10 px-4 py-3 text-sm"
+        >
+          <p className="flex items-center gap-2 font-medium text-destructive">
+            <AlertTriangle className="h-4 w-4" />
+            {failing.length === 1 ? '1 key stopped working' : `${failing.length} keys stopped working`}
+          </p>
+          <ul className="mt-2 space-y-1 text-muted-foreground">
+            {failing.map((k) => (
+              <li key={k.api_key_id}>
+                <span className="font-medium text-foreground">{k.label}</span> ({k.provider_id})
+                — failing since {formatSince(k.failing_since)}
+                {k.last_message ? `: ${k.last_message}` : ''}
+              </li>
+            ))}
+          </ul>
+        </div>
+      )}
+
+      <div className="flex flex-wrap items-center gap-3 text-sm">
+        <button
+          type="button"
+          onClick={() => validateAll.mutate()}
+          disabled={validateAll.isPending}
+          className="flex items-center gap-1.5 px-3 py-1.5 rounded-md border border-border hover:bg-muted/50 transition-colors disabled:opacity-50"
+        >
+          <RefreshCw className={validateAll.isPending ? 'h-3.5 w-3.5 animate-spin' : 'h-3.5 w-3.5'} />
+          Validate all
+        </button>
+        {schedule && (
+          <>
+            <label className="flex items-center gap-1.5 text-muted-foreground">
+              <input
+                type="checkbox"
+                checked={schedule.enabled}
+                onChange={(e) => update({ enabled: e.target.checked })}
+              />
+              Re-validate in background
+            </label>
+            <select
+              aria-label="Validation interval"
+              value={schedule.interval_hours}
+              disabled={!schedule.enabled}
+              onChange={(e) => update({ interval_hours: Number(e.target.value) })}
+              className="rounded-md border border-border bg-background px-2 py-1"
+            >
+              {INTERVAL_OPTIONS.map((o) => (
+                <option key={o.hours} value={o.hours}>
+                  {o.label}
+                </option>
+              ))}
+              {!INTERVAL_OPTIONS.some((o) => o.hours === schedule.interval_hours) && (
+                <option value={schedule.interval_hours}>
+                  Every {schedule.interval_hours} hours
+                </option>
+              )}
+            </select>
+            <label className="flex items-center gap-1.5 text-muted-foreground">
+              Per provider
+              <input
+                type="number"
+                min={1}
+                max={16}
+                aria-label="Concurrent checks per provider"
+                value={schedule.per_provider_concurrency}
+                onChange={(e) => {
+                  const n = Number(e.target.value);
+                  if (Number.isInteger(n) && n >= 1) update({ per_provider_concurrency: n });
+                }}
+                className="w-14 rounded-md border border-border bg-background px-2 py-1"
+              />
+            </label>
+          </>
+        )}
+      </div>
+    </div>
+  );
+}
@@ -1,6 +1,7 @@
 import { useState } from 'react';
 import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query';
 import { toast } from 'sonner';
+import { save } from '@tauri-apps/plugin-dialog';
 import {
   Sparkles,
   Terminal,
@@ -21,6 +22,7 @@ import {
   ArrowDownToLine,
   Globe,
   Loader2,
+  Package,
 } from 'lucide-react';
 import { Button } from './ui/button';
 import { ProfileToolPicker } from './ProfileToolPicker';
@@ -29,6 +31,7 @@ import { ProjectSelectorDialog } from './ProjectSelectorDialog';
 import {
   assignProfileAsPlugin,
   checkProfileUpdates,
+  exportProfileBundle,
   getProfileHooks,
   installProfileToUser,
   pullToolUpdate,
@@ -97,6 +100,32 @@ export function ProfileDetail({
     },
   });
 
+  // Mutation for exporting a self-contained bundle with tool content
+  const exportBundleMutation = useMutation({
+    mutationFn: (outputPath: string) => exportProfileBundle(profile.id, outputPath),
+    onSuccess: (result) => {
+      toast.success(`Exported bundle to ${result.path}`);
+    },
+    onError: (err) => {
+      toast.error(`Failed to export bundle: ${err}`);
+    },
+  });
+
+  async function handleExportBundle() {
+    try {
+      const outputPath = await save({
+        title: 'Export Bundle',
+        defaultPath: `${profile.name}.tars-profile.zip`,
+        filters: [{ name: 'ZIP Archive', extensions: ['zip'] }],
+      });
+      if (outputPath) {
+        exportBundleMutation.mutate(outputPath);
+      }
+    } catch (err) {
+      toast.error(`Failed to open save dialog: ${err}`);
+    }
+  }
+
   // Mutation for installing profile globally
   const installToUserMutation = useMutation({
     mutationFn: () => installProfileToUser(profile.id),
@@ -432,6 +461,14 @@ export function ProfileDetail({
           <Download className="h-4 w-4 mr-2" />
           Export Plugin
         </Button>
+        <Button
+          variant="outline"
+          onClick={handleExportBundle}
+          disabled={exportBundleMutation.isPending}
+        >
+          <Package className="h-4 w-4 mr-2" />
+          Export Bundle
+        </Button>
       </div>
 
       {/* Project Selector Dialog */}
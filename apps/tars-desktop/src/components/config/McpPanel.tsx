@@ -199,9 +199,9 @@ export function McpPanel() {
       const result = await mcpRefresh(server.name, selectedProjectPath);
 
       if (result.success) {
-        if (result.refreshType === 'npx_skip') {
-          toast.info(`"${server.name}" uses npx`, {
-            description: 'npx always fetches the latest version - no refresh needed',
+        if (result.refreshAction.endsWith('_skip')) {
+          toast.info(`"${server.name}" is always up to date`, {
+            description: result.output || 'No refresh needed',
           });
         } else {
           toast.success(`Refreshed "${server.name}"`, {
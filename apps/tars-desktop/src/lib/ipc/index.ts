@@ -6,6 +6,7 @@ import { invoke } from '@tauri-apps/api/core';
 import type {
   Inventory,
   ProjectInfo,
+  RuleSuggestion,
   ProfileInfo,
   ProfileDetails,
   DiffPreview,
@@ -92,6 +93,7 @@ export interface DiscoveredProject {
   has_claude_dir: boolean;
   has_claude_md: boolean;
   has_mcp_json: boolean;
+  suggested_bundle: RuleSuggestion | null;
 }
 
 export async function discoverClaudeProjects(folder: string): Promise<DiscoveredProject[]> {
@@ -182,6 +184,7 @@ export interface CoverageInfo {
 
 export interface DependencyInfo {
   source: string;
+  manifest: string;
   production: number;
   development: number;
 }
@@ -540,19 +543,23 @@ export async function removeProfileMcpServer(profileId: string, name: string): P
 export interface McpRefreshResult {
   success: boolean;
   serverName: string;
-  refreshType: string; // "npm_install", "git_pull", "npx_skip", "unknown"
+  refreshType: string; // strategy id, e.g. "npm_install", "docker_pull"; "unknown"
+  refreshAction: string; // e.g. "git_pull", "uv_cache_clean"; "*_skip" = nothing to run; "unknown"
   commandRun?: string;
   output?: string;
   error?: string;
+  dryRun: boolean;
 }
 
 export async function mcpRefresh(
   name: string,
-  projectPath?: string | null
+  projectPath?: string | null,
+  dryRun?: boolean
 ): Promise<McpRefreshResult> {
   return invoke('mcp_refresh', {
     name,
     projectPath: projectPath ?? null,
+    dryRun: dryRun ?? null,
   });
 }
 
@@ -620,7 +627,13 @@ export async function exportProfileAsPlugin(
 }
 
 // Profile export/import commands
-import type { ExportProfileResponse, ImportProfileResponse, PreviewImportResponse } from '../types';
+import type {
+  ExportProfileResponse,
+  ImportProfileResponse,
+  PreviewImportResponse,
+  ProfileComparison,
+  ResolvedProfile,
+} from '../types';
 
 export async function exportProfileJson(
   profileId: string,
@@ -632,6 +645,34 @@ export async function exportProfileJson(
   });
 }
 
+export async function setProfileExtends(
+  profileId: string,
+  parentIds: string[]
+): Promise<ResolvedProfile> {
+  return invoke('set_profile_extends', { profileId, parentIds });
+}
+
+export async function getResolvedProfile(profileId: string): Promise<ResolvedProfile> {
+  return invoke('get_resolved_profile', { profileId });
+}
+
+export async function compareProfiles(
+  profileA: string,
+  profileB: string
+): Promise<ProfileComparison> {
+  return invoke('compare_profiles', { profileA, profileB });
+}
+
+export async function exportProfileBundle(
+  profileId: string,
+  outputPath: string
+): Promise<ExportProfileResponse> {
+  return invoke('export_profile_bundle', {
+    profileId,
+    outputPath,
+  });
+}
+
 export async function previewProfileImport(filePath: string): Promise<PreviewImportResponse> {
   return invoke('preview_profile_import', {
     filePath,
@@ -649,17 +690,27 @@ export async function importProfileJson(
 }
 
 // Apply commands
-export async function previewApply(profileId: string, projectPath: string): Promise<DiffPreview> {
+export async function previewApply(
+  profileId: string,
+  projectPath: string,
+  writeLock?: boolean
+): Promise<DiffPreview> {
   return invoke('preview_apply', {
     profileId,
     projectPath,
+    writeLock,
   });
 }
 
-export async function applyProfile(profileId: string, projectPath: string): Promise<BackupInfo> {
+export async function applyProfile(
+  profileId: string,
+  projectPath: string,
+  writeLock?: boolean
+): Promise<BackupInfo> {
   return invoke('apply_profile', {
     profileId,
     projectPath,
+    writeLock,
   });
 }
 
@@ -674,6 +725,25 @@ export async function rollback(backupId: string, projectPath: string): Promise<n
   });
 }
 
+// Batch apply commands
+import type { ApplyBatch, BatchPreview, BatchRollback } from '../types';
+
+export async function previewBatchApply(profileId: string): Promise<BatchPreview> {
+  return invoke('preview_batch_apply', { profileId });
+}
+
+export async function applyBundleBatch(profileId: string): Promise<ApplyBatch> {
+  return invoke('apply_bundle_batch', { profileId });
+}
+
+export async function listApplyBatches(): Promise<ApplyBatch[]> {
+  return invoke('list_apply_batches');
+}
+
+export async function rollbackApplyBatch(batchId: string): Promise<BatchRollback> {
+  return invoke('rollback_apply_batch', { batchId });
+}
+
 // Skill commands
 export async function readSkill(path: string): Promise<SkillDetails> {
   return invoke('read_skill', { path });
@@ -1133,6 +1203,7 @@ export async function getPlatformInfo(): Promise<PlatformInfo> {
 // Claude Code usage stats
 import type {
   ClaudeUsageStats,
+  CostReport,
   ProfileUpdateCheck,
   PluginAssignResult,
   SourceMode,
@@ -1146,6 +1217,10 @@ export async function getClaudeUsageStats(): Promise<ClaudeUsageStats> {
   return invoke('get_claude_usage_stats');
 }
 
+export async function getClaudeUsageCost(): Promise<CostReport> {
+  return invoke('get_claude_usage_cost');
+}
+
 // Profile update detection
 export async function checkProfileUpdates(profileId: string): Promise<ProfileUpdateCheck> {
   return invoke('check_profile_updates', { profileId });
@@ -1163,6 +1238,97 @@ export async function setToolSourceMode(
   return invoke('set_tool_source_mode', { profileId, toolName, mode });
 }
 
+export async function setPluginVersionMode(
+  profileId: string,
+  pluginId: string,
+  mode: SourceMode | null
+): Promise<void> {
+  return invoke('set_plugin_version_mode', { profileId, pluginId, mode });
+}
+
+export async function pullPluginUpdate(profileId: string, pluginId: string): Promise<void> {
+  return invoke('pull_plugin_update', { profileId, pluginId });
+}
+
+// Git profile repositories
+import type {
+  ProfileRepo,
+  ProfileRepoStatus,
+  ProfileRepoPullResult,
+  ProfileRepoPushResult,
+} from '../types';
+
+export async function listProfileRepos(): Promise<ProfileRepo[]> {
+  return invoke('list_profile_repos');
+}
+
+export async function addProfileRepo(
+  name: string,
+  url: string,
+  branch?: string
+): Promise<ProfileRepo> {
+  return invoke('add_profile_repo', { name, url, branch });
+}
+
+export async function removeProfileRepo(repoId: string): Promise<void> {
+  return invoke('remove_profile_repo', { repoId });
+}
+
+export async function getProfileRepoStatus(repoId: string): Promise<ProfileRepoStatus> {
+  return invoke('get_profile_repo_status', { repoId });
+}
+
+export async function pullProfileRepo(
+  repoId: string,
+  force = false
+): Promise<ProfileRepoPullResult> {
+  return invoke('pull_profile_repo', { repoId, force });
+}
+
+export async function pushProfileRepo(
+  repoId: string,
+  profileIds: string[],
+  branch: string,
+  message: string
+): Promise<ProfileRepoPushResult> {
+  return invoke('push_profile_repo', { repoId, profileIds, branch, message });
+}
+
+// Bundle assignment rules
+import type { AssignmentRule, RuleAssignment, RuleConditions } from '../types';
+
+export async function listAssignmentRules(): Promise<AssignmentRule[]> {
+  return invoke('list_assignment_rules');
+}
+
+export async function createAssignmentRule(
+  name: string,
+  profileId: string,
+  conditions: RuleConditions,
+  autoApply: boolean,
+  priority?: number
+): Promise<AssignmentRule> {
+  return invoke('create_assignment_rule', { name, profileId, conditions, priority, autoApply });
+}
+
+export async function updateAssignmentRule(rule: AssignmentRule): Promise<void> {
+  return invoke('update_assignment_rule', { rule });
+}
+
+export async function deleteAssignmentRule(ruleId: string): Promise<boolean> {
+  return invoke('delete_assignment_rule', { ruleId });
+}
+
+export async function proposeProjectAssignment(
+  projectId: string
+): Promise<RuleAssignment | null> {
+  return invoke('propose_project_assignment', { projectId });
+}
+
+export async function applyProjectAssignment(projectId: string): Promise<RuleAssignment> {
+  return invoke('apply_project_assignment', { projectId });
+}
+
 // Plugin-based profile assignment
 export async function assignProfileAsPlugin(
   projectId: string,
@@ -1250,7 +1416,9 @@ export type ProviderId =
   | 'mistral'
   | 'xai'
   | 'openrouter'
-  | 'perplexity';
+  | 'perplexity'
+  // User-defined OpenAI-compatible provider (see `saveCustomProvider`).
+  | `custom:${string}`;
 
 export interface ProviderMetadata {
   id: ProviderId;
@@ -1259,6 +1427,34 @@ export interface ProviderMetadata {
   key_format_hint: string;
   supports_models: boolean;
   supports_balance: boolean;
+  /** Supports `testApiKeyCompletion`. */
+  supports_completion: boolean;
+  /** True for user-defined providers; `docs_url` then holds the base URL. */
+  custom: boolean;
+}
+
+export type CustomAuthStyle =
+  | { kind: 'bearer' }
+  | { kind: 'header'; name: string }
+  | { kind: 'none' };
+
+export interface CustomProvider {
+  id: number;
+  slug: string;
+  display_name: string;
+  base_url: string;
+  auth_style: CustomAuthStyle;
+  models_path: string | null;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface CustomProviderInput {
+  slug: string;
+  display_name: string;
+  base_url: string;
+  auth_style: CustomAuthStyle;
+  models_path?: string | null;
 }
 
 export interface ApiKeySummary {
@@ -1268,10 +1464,25 @@ export interface ApiKeySummary {
   last_validated_at: string | null;
   last_valid: boolean | null;
   balance: unknown | null;
+  /** Normalised amount and currency from the latest balance snapshot. */
+  balance_amount: number | null;
+  balance_currency: string | null;
+  /** Low-balance warning level in `balance_currency`; null disables it. */
+  balance_threshold: number | null;
+  low_balance: boolean;
   created_at: string;
   updated_at: string;
 }
 
+export interface BalanceSnapshot {
+  id: number;
+  api_key_id: number;
+  provider_id: ProviderId;
+  currency: string;
+  amount: number;
+  recorded_at: string;
+}
+
 export interface ApiKeyInput {
   provider_id: ProviderId;
   label: string;
@@ -1287,12 +1498,26 @@ export interface ValidationResult {
    * touched. Absent (undefined) on the wire when false.
    */
   unverifiable?: boolean;
+  /** Present when the freshly fetched balance is below the key's threshold. */
+  balance_warning?: string;
 }
 
 export async function listProviders(): Promise<ProviderMetadata[]> {
   return invoke('list_providers');
 }
 
+export async function listCustomProviders(): Promise<CustomProvider[]> {
+  return invoke('list_custom_providers');
+}
+
+export async function saveCustomProvider(input: CustomProviderInput): Promise<CustomProvider> {
+  return invoke('save_custom_provider', { input });
+}
+
+export async function deleteCustomProvider(slug: string): Promise<boolean> {
+  return invoke('delete_custom_provider', { slug });
+}
+
 export async function addApiKey(input: ApiKeyInput): Promise<number> {
   return invoke('add_api_key', { input });
 }
@@ -1309,6 +1534,101 @@ export async function validateApiKey(id: number): Promise<ValidationResult> {
   return invoke('validate_api_key', { id });
 }
 
+export interface TokenUsage {
+  /** Prompt tokens not read from or written to the prompt cache. */
+  input_tokens: number;
+  output_tokens: number;
+  cache_read_tokens: number;
+  cache_write_tokens: number;
+}
+
+export interface CompletionTestResult {
+  /** Model that answered, as reported by the provider. */
+  model: string;
+  text: string;
+  usage: TokenUsage;
+  latency_ms: number;
+  /** USD cost from the pricing cache; null when the model is unpriced. */
+  cost_usd: number | null;
+  price_overridden: boolean;
+}
+
+export async function testApiKeyCompletion(
+  id: number,
+  model: string,
+  prompt?: string,
+  maxTokens?: number
+): Promise<CompletionTestResult> {
+  return invoke('test_api_key_completion', { id, model, prompt, maxTokens });
+}
+
+export async function getBalanceHistory(id: number, limit?: number): Promise<BalanceSnapshot[]> {
+  return invoke('get_balance_history', { id, limit });
+}
+
+export async function setBalanceThreshold(id: number, threshold: number | null): Promise<boolean> {
+  return invoke('set_balance_threshold', { id, threshold });
+}
+
+export interface KeyValidation {
+  id: number;
+  api_key_id: number;
+  provider_id: ProviderId;
+  validated_at: string;
+  valid: boolean;
+  message: string | null;
+  latency_ms: number;
+}
+
+/** A key that validated before and is now rejected. */
+export interface FailingKey {
+  api_key_id: number;
+  provider_id: ProviderId;
+  label: string;
+  last_valid_at: string;
+  failing_since: string;
+  consecutive_failures: number;
+  last_message: string | null;
+}
+
+export interface ValidationSchedule {
+  enabled: boolean;
+  interval_hours: number;
+  per_provider_concurrency: number;
+}
+
+export interface KeyValidationRunSummary {
+  checked: number;
+  valid: number;
+  invalid: number;
+  unverifiable: number;
+  errors: number;
+  newly_failing: FailingKey[];
+}
+
+export async function validateAllApiKeys(): Promise<KeyValidationRunSummary> {
+  return invoke('validate_all_api_keys');
+}
+
+export async function getKeyValidationHistory(
+  id: number,
+  limit?: number
+): Promise<KeyValidation[]> {
+  return invoke('get_key_validation_history', { id, limit });
+}
+
+export async function listFailingApiKeys(): Promise<FailingKey[]> {
+  return invoke('list_failing_api_keys');
+}
+
+export async function getKeyValidationSchedule(): Promise<ValidationSchedule> {
+  return invoke('get_key_validation_schedule');
+}
+
+export async function setKeyValidationSchedule(schedule: ValidationSchedule): Promise<void> {
+  return invoke('set_key_validation_schedule', { schedule });
+}
+
 export async function refreshModels(providerId: ProviderId): Promise<number> {
   return invoke('refresh_models', { providerId });
 }
@@ -1475,7 +1795,7 @@ export interface CatalogSkill {
 export type SkillCellStatus = 'on' | 'off' | 'adopted' | 'collision' | 'plugin' | 'resident';
 
 // Muting middle-state. null == fully visible ('on'); the others mirror Claude
-// `skillOverrides`. Only settable on Claude standalone skills where supported.
+// `skillOverrides`. Only settable where the cell reports muteSupported.
 export type SkillMuteState = 'name-only' | 'user-invocable-only' | 'off';
 
 export interface SkillCell {
@@ -1484,10 +1804,11 @@ export interface SkillCell {
   tracked: boolean;
   linkKind: string | null;
   deploymentId: number | null;
+  // Empty when the agent has no skills dir in this scope.
   linkPath: string;
   // Providing plugin id when status === 'plugin'.
   pluginId: string | null;
-  // Copy deploy whose on-disk source no longer matches the deploy-time hash.
+  // Copy deploy whose source or deployed copy no longer matches the deploy-time hash.
   drifted: boolean;
   // null/'on' = visible; otherwise a mute state written to settings.json.
   muteState: SkillMuteState | null;
@@ -1499,8 +1820,8 @@ export interface SkillMatrixRow {
   name: string;
   description: string;
   sourceDir: string;
-  claude: SkillCell;
-  codex: SkillCell;
+  // One cell per agent.
+  cells: Record<SkillAgent, SkillCell>;
 }
 
 export interface SkillGroup {
@@ -1517,10 +1838,53 @@ export interface SkillGroup {
   skills: SkillMatrixRow[];
 }
 
-export type SkillAgent = 'claude' | 'codex';
+export type FileDriftKind = 'source_changed' | 'target_changed' | 'both_changed';
+
+export interface FileDrift {
+  path: string;
+  kind: FileDriftKind;
+}
+
+export interface DeploymentDrift {
+  deploymentId: number;
+  skillName: string;
+  sourcePath: string;
+  linkPath: string;
+  // False for copies deployed before per-file bases were recorded; files
+  // changed on both sides then merge only as whole-file conflicts.
+  hasBaseline: boolean;
+  files: FileDrift[];
+}
+
+export type DriftResolution = 'pull_back' | 'overwrite' | 'merge';
+
+export interface DriftReport {
+  backups: string[];
+  sourceUpdated: string[];
+  targetUpdated: string[];
+  // Files left with conflict markers in the deployed copy.
+  conflicts: string[];
+}
+
+// Every agent a skill can be deployed to; each has a column in the skill matrix.
+export type SkillAgent = 'claude' | 'codex' | 'gemini' | 'cursor' | 'opencode';
 export type SkillScope = 'user' | 'project';
 export type SkillLinkKind = 'symlink' | 'copy';
 
+// What an agent's adapter declares about deploying skills to it.
+export interface SkillAgentInfo {
+  agent: SkillAgent;
+  label: string;
+  // Null when the agent has no user scope.
+  userDir: string | null;
+  // Relative to the project root; null when the agent has no project scope.
+  projectDir: string | null;
+  symlinks: boolean;
+  mute: boolean;
+  // Whether bundles are converted to the agent's own format on deploy.
+  converts: boolean;
+}
+
 export interface DeploySkillInput {
   skillName: string;
   sourceDir: string;
@@ -1546,6 +1910,10 @@ export async function scanSkillLibrary(): Promise<CatalogSkill[]> {
   return invoke('scan_skill_library');
 }
 
+export async function listSkillAgents(): Promise<SkillAgentInfo[]> {
+  return invoke('list_skill_agents');
+}
+
 export async function deploySkill(input: DeploySkillInput): Promise<SkillDeployment> {
   return invoke('deploy_skill', { input });
 }
@@ -1570,10 +1938,72 @@ export async function importSkillFolder(path: string): Promise<SkillInstallRepor
   return invoke('import_skill_folder', { path });
 }
 
-// Shallow-clone an https git repo (optionally /tree/<ref>/<subpath>) and
-// install every skill bundle found into ~/.agents/skills.
-export async function installSkillFromGit(url: string): Promise<SkillInstallReport> {
-  return invoke('install_skill_from_git', { url });
+// Clone an https git repo (optionally /tree/<ref>/<subpath>) and install every
+// skill bundle found into ~/.agents/skills, recording its origin. With `pin`,
+// that commit is installed and pinned.
+export async function installSkillFromGit(
+  url: string,
+  pin: string | null = null
+): Promise<SkillInstallReport> {
+  return invoke('install_skill_from_git', { url, pin });
+}
+
+export interface SkillOrigin {
+  id: number;
+  skill_name: string;
+  install_path: string;
+  repo_url: string;
+  // Branch or tag followed by updates; null follows the remote HEAD.
+  git_ref: string | null;
+  commit_sha: string;
+  // Bundle directory within the repository ('' for the root).
+  subpath: string;
+  pinned: boolean;
+  created_at: string;
+  updated_at: string;
+}
+
+export type SkillUpdateStatus = 'up_to_date' | 'outdated' | 'pinned' | 'unreachable';
+
+export interface SkillUpdateCheck {
+  origin: SkillOrigin;
+  latestSha: string | null;
+  status: SkillUpdateStatus;
+  error: string | null;
+}
+
+export interface SkillUpdatePreview {
+  skillName: string;
+  installPath: string;
+  fromSha: string;
+  toSha: string;
+  pinned: boolean;
+  // Diff from the installed bundle to the target commit's; empty if unchanged.
+  diff: string;
+}
+
+// Ask each git-installed skill's remote for its current commit (no clone).
+export async function checkSkillUpdates(): Promise<SkillUpdateCheck[]> {
+  return invoke('check_skill_updates');
+}
+
+// Diff a git-installed skill against the latest commit, or against `pin`.
+export async function previewSkillUpdate(
+  skillName: string,
+  pin: string | null,
+  unpin: boolean
+): Promise<SkillUpdatePreview> {
+  return invoke('preview_skill_update', { skillName, pin, unpin });
+}
+
+// Move a git-installed skill to the previewed commit (`expectedSha`).
+export async function updateSkill(
+  skillName: string,
+  pin: string | null,
+  unpin: boolean,
+  expectedSha: string | null
+): Promise<SkillUpdatePreview> {
+  return invoke('update_skill', { skillName, pin, unpin, expectedSha });
 }
 
 // Move a resident skill from an agent's own dir into ~/.agents/skills,
@@ -1591,11 +2021,25 @@ export async function setSkillMute(
   return invoke('set_skill_mute', { deploymentId, muteState });
 }
 
-// Re-copy a drifted copy deployment from its source, refreshing the hash.
+// Overwrite a drifted copy deployment from its source (backing up the copy first).
 export async function resyncSkillDeployment(deploymentId: number): Promise<boolean> {
   return invoke('resync_skill_deployment', { deploymentId });
 }
 
+// Per-file drift of a copy deployment: source-changed, target-changed or both.
+export async function getSkillDrift(deploymentId: number): Promise<DeploymentDrift> {
+  return invoke('get_skill_drift', { deploymentId });
+}
+
+// Pull target edits back, overwrite the target, or three-way merge per file.
+// Every side written to is backed up first.
+export async function resolveSkillDrift(
+  deploymentId: number,
+  resolution: DriftResolution
+): Promise<DriftReport> {
+  return invoke('resolve_skill_drift', { deploymentId, resolution });
+}
+
 // Enable/disable a whole plugin for a project via enabledPlugins (the only
 // per-project lever for plugin-provided skills).
 export async function setProjectPluginEnabled(
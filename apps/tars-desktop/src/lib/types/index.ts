@@ -281,6 +281,8 @@ export interface ProjectInfo {
   path: string;
   created_at: string;
   updated_at: string;
+  /** Bundle proposed or applied by an assignment rule when the project was added */
+  rule_assignment?: RuleAssignment;
 }
 
 export interface ProjectGitStatus {
@@ -306,12 +308,16 @@ export interface ProfilePluginRef {
   marketplace: string | null;
   scope: string;
   enabled: boolean;
+  /** Installed version the entry follows or is pinned to */
+  version?: string | null;
+  version_mode?: SourceMode | null;
 }
 
 export interface ProfileDetails {
   id: string;
   name: string;
   description: string | null;
+  extends: string[];
   tool_refs: ToolRef[];
   plugin_refs: ProfilePluginRef[];
   assigned_projects: ProjectRef[];
@@ -356,6 +362,8 @@ export interface SourceRef {
   source_hash: string;
   mode: SourceMode;
   copied_at: string;
+  /** Entry within the source file, e.g. the MCP server's key */
+  entry: string | null;
 }
 
 export interface ToolRef {
@@ -449,6 +457,64 @@ export interface PreviewImportResponse {
   has_name_collision: boolean;
   existing_profile_id: string | null;
   version: number;
+  file_count: number;
+  conflicts: ImportConflict[];
+}
+
+export interface ImportConflict {
+  kind: 'skill' | 'agent' | 'command' | 'mcp-server';
+  name: string;
+  local_path: string;
+  identical: boolean;
+}
+
+export interface ProfileLayerRef {
+  id: string;
+  name: string;
+}
+
+export interface ArtifactProvenance {
+  kind: string;
+  tool_type: string | null;
+  name: string;
+  source: ProfileLayerRef;
+  earlier: ProfileLayerRef[];
+}
+
+export interface ResolvedProfile {
+  layers: ProfileLayerRef[];
+  provenance: ArtifactProvenance[];
+}
+
+export type CompareToolKind = 'mcp' | 'skill' | 'agent' | 'command' | 'user_skill' | 'user_command';
+
+export interface ToolKey {
+  kind: CompareToolKind;
+  name: string;
+}
+
+export interface ToolDifference {
+  kind: CompareToolKind;
+  name: string;
+  diff: string;
+}
+
+export interface SettingDifference {
+  setting: string;
+  a: string | null;
+  b: string | null;
+  diff: string | null;
+}
+
+export interface ProfileComparison {
+  a: string;
+  b: string;
+  only_in_a: ToolKey[];
+  only_in_b: ToolKey[];
+  differing: ToolDifference[];
+  plugins: SettingDifference[];
+  adapters: SettingDifference[];
+  overlays: SettingDifference[];
 }
 
 // Apply types
@@ -844,6 +910,45 @@ export interface ClaudeUsageStats {
   hourCounts: Record<string, number>;
 }
 
+// Cost estimate joined from usage stats and cached Anthropic prices (USD)
+export interface ModelPrice {
+  /** USD per million input tokens */
+  input: number;
+  /** USD per million output tokens */
+  output: number;
+  /** USD per million prompt-cache read tokens */
+  cacheRead: number;
+  /** USD per million prompt-cache write tokens */
+  cacheWrite: number;
+  /** True when cache rates were derived from the input rate */
+  cacheRatesEstimated: boolean;
+  isOverridden: boolean;
+}
+
+export interface ModelCost {
+  modelId: string;
+  usage: ModelUsage;
+  price: ModelPrice | null;
+  inputCost: number;
+  outputCost: number;
+  cacheReadCost: number;
+  cacheCreationCost: number;
+  totalCost: number;
+}
+
+export interface DailyCost {
+  date: string;
+  totalCost: number;
+  costByModel: Record<string, number>;
+}
+
+export interface CostReport {
+  totalCost: number;
+  models: ModelCost[];
+  daily: DailyCost[];
+  unpricedModels: string[];
+}
+
 // Profile update detection types
 export interface ToolUpdateInfo {
   name: string;
@@ -854,10 +959,159 @@ export interface ToolUpdateInfo {
   mode: SourceMode;
 }
 
+export interface PluginUpdateInfo {
+  plugin_id: string;
+  marketplace: string | null;
+  recorded_version: string;
+  installed_version: string;
+}
+
 export interface ProfileUpdateCheck {
   updates: ToolUpdateInfo[];
   missing_sources: string[];
   total_checked: number;
+  plugin_updates: PluginUpdateInfo[];
+}
+
+export interface ProfileRepo {
+  id: string;
+  name: string;
+  url: string;
+  branch: string;
+  head_commit: string | null;
+  last_synced_at: string | null;
+  created_at: string;
+}
+
+export type ProfileRepoState =
+  | 'up_to_date'
+  | 'new'
+  | 'upstream_changed'
+  | 'local_changed'
+  | 'diverged'
+  | 'unpublished'
+  | 'removed_upstream';
+
+export interface ProfileRepoProfileStatus {
+  path: string;
+  name: string;
+  profile_id: string | null;
+  state: ProfileRepoState;
+}
+
+export interface ProfileRepoStatus {
+  repo: ProfileRepo;
+  upstream: string | null;
+  profiles: ProfileRepoProfileStatus[];
+}
+
+export interface ProfileRepoPullResult {
+  upstream: string | null;
+  created: string[];
+  updated: string[];
+  skipped: { name: string; reason: string }[];
+  unlinked: string[];
+}
+
+export interface ProfileRepoPushResult {
+  branch: string;
+  commit: string | null;
+  profiles: string[];
+}
+
+export interface RuleConditions {
+  path_globs?: string[];
+  languages?: string[];
+  dependency_files?: string[];
+  git_hosts?: string[];
+  git_orgs?: string[];
+  platforms?: string[];
+}
+
+export interface AssignmentRule {
+  id: string;
+  name: string;
+  profile_id: string;
+  priority: number;
+  conditions: RuleConditions;
+  auto_apply: boolean;
+  enabled: boolean;
+  created_at: string;
+}
+
+export interface RuleSuggestion {
+  rule_id: string;
+  rule_name: string;
+  profile_id: string;
+  profile_name: string;
+  auto_apply: boolean;
+  reasons: string[];
+}
+
+export interface RuleAssignment {
+  suggestion: RuleSuggestion;
+  summary: string;
+  applied: boolean;
+  backup_id: string | null;
+  error: string | null;
+  plugin_errors: string[];
+}
+
+export interface DiffSummary {
+  creates: number;
+  modifies: number;
+  deletes: number;
+  total_bytes: number;
+}
+
+export interface BatchProjectPreview {
+  project_id: string;
+  project_name: string;
+  project_path: string;
+  summary: DiffSummary;
+  has_changes: boolean;
+  error: string | null;
+}
+
+export interface BatchPreview {
+  profile_id: string;
+  profile_name: string;
+  summary: DiffSummary;
+  changed: number;
+  failed: number;
+  projects: BatchProjectPreview[];
+}
+
+export type BatchProjectStatus = 'applied' | 'unchanged' | 'failed';
+
+export interface ApplyBatchEntry {
+  project_id: string;
+  project_name: string;
+  status: BatchProjectStatus;
+  operations: number;
+  backup_id: string | null;
+  error: string | null;
+}
+
+export interface ApplyBatch {
+  id: string;
+  profile_id: string;
+  profile_name: string;
+  created_at: string;
+  rolled_back_at: string | null;
+  entries: ApplyBatchEntry[];
+}
+
+export interface RollbackFailure {
+  project_id: string;
+  project_name: string;
+  error: string;
+}
+
+export interface BatchRollback {
+  batch_id: string;
+  restored: string[];
+  failures: RollbackFailure[];
 }
 
 export interface PluginAssignResult {
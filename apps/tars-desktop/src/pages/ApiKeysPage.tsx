@@ -1,12 +1,15 @@
-import { Key } from 'lucide-react';
+import { Key, Plus } from 'lucide-react';
 import { useQuery } from '@tanstack/react-query';
 import { useMemo, useState } from 'react';
 import { listApiKeys, listProviders, type ApiKeySummary, type ProviderMetadata } from '../lib/ipc';
 import { ApiKeyProviderCard } from '../components/ApiKeyProviderCard';
 import { AddApiKeyDialog } from '../components/AddApiKeyDialog';
+import { AddCustomProviderDialog } from '../components/AddCustomProviderDialog';
+import { KeyHealthPanel } from '../components/KeyHealthPanel';
 
 export function ApiKeysPage() {
   const [addingFor, setAddingFor] = useState<ProviderMetadata | null>(null);
+  const [addingCustom, setAddingCustom] = useState(false);
 
   const providersQuery = useQuery({
     queryKey: ['providers'],
@@ -32,16 +35,27 @@ export function ApiKeysPage() {
 
   return (
     <div className="h-full flex flex-col">
-      <div className="shrink-0 border-b border-border bg-card/50 px-6 py-4">
-        <h1 className="text-xl font-semibold flex items-center gap-2">
-          <Key className="h-5 w-5" />
-          AI Keys
-        </h1>
-        <p className="text-sm text-muted-foreground mt-1">
-          Manage AI provider API keys. Stored encrypted at rest.
-        </p>
+      <div className="shrink-0 border-b border-border bg-card/50 px-6 py-4 flex items-start justify-between gap-4">
+        <div>
+          <h1 className="text-xl font-semibold flex items-center gap-2">
+            <Key className="h-5 w-5" />
+            AI Keys
+          </h1>
+          <p className="text-sm text-muted-foreground mt-1">
+            Manage AI provider API keys. Stored encrypted at rest.
+          </p>
+        </div>
+        <button
+          type="button"
+          onClick={() => setAddingCustom(true)}
+          className="shrink-0 flex items-center gap-1.5 px-3 py-1.5 text-sm rounded-md border border-border hover:bg-muted/50 transition-colors"
+        >
+          <Plus className="h-3.5 w-3.5" />
+          Custom Provider
+        </button>
       </div>
       <div className="flex-1 overflow-y-auto p-6">
+        <KeyHealthPanel />
         {providersQuery.isLoading ? (
           <p className="text-muted-foreground">Loading providers…</p>
         ) : providersQuery.isError ? (
@@ -68,6 +82,7 @@ export function ApiKeysPage() {
           if (!open) setAddingFor(null);
         }}
       />
+      <AddCustomProviderDialog open={addingCustom} onOpenChange={setAddingCustom} />
     </div>
   );
 }
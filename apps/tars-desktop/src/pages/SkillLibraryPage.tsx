@@ -49,6 +49,9 @@ import { cn } from '../lib/utils';
 const AGENTS: { key: SkillAgent; label: string }[] = [
   { key: 'claude', label: 'Claude' },
   { key: 'codex', label: 'Codex' },
+  { key: 'gemini', label: 'Gemini' },
+  { key: 'cursor', label: 'Cursor' },
+  { key: 'opencode', label: 'OpenCode' },
 ];
 
 export function SkillLibraryPage() {
@@ -228,7 +231,7 @@ export function SkillLibraryPage() {
     setBusyGroup(`${groupKey(group)}:${agent}`);
     try {
       for (const row of s.eligible) {
-        await applyCell(row, agent, row[agent], turnOn);
+        await applyCell(row, agent, row.cells[agent], turnOn);
       }
       invalidateMatrix();
     } catch (e) {
@@ -240,7 +243,7 @@ export function SkillLibraryPage() {
 
   // Set a Claude standalone skill's mute state (null = fully visible).
   async function muteCell(row: SkillMatrixRow, agent: SkillAgent, next: SkillMuteState | null) {
-    const cell = row[agent];
+    const cell = row.cells[agent];
     if (cell.deploymentId == null) return;
     const key = `${row.name}:${agent}`;
     setBusyCell(key);
@@ -256,7 +259,7 @@ export function SkillLibraryPage() {
 
   // Re-copy a drifted copy deployment from its source.
   async function resyncCell(row: SkillMatrixRow, agent: SkillAgent) {
-    const cell = row[agent];
+    const cell = row.cells[agent];
     if (cell.deploymentId == null) return;
     const key = `${row.name}:${agent}`;
     setBusyCell(key);
@@ -302,7 +305,7 @@ export function SkillLibraryPage() {
     });
 
   const allRows = useMemo(() => groups.flatMap((g) => g.skills), [groups]);
-  const deployedCount = allRows.filter((r) => r.claude.deployed || r.codex.deployed).length;
+  const deployedCount = allRows.filter((r) => AGENTS.some((a) => r.cells[a.key].deployed)).length;
 
   return (
     <div className="flex flex-col h-full">
@@ -526,7 +529,7 @@ export function SkillLibraryPage() {
                                   row={row}
                                   agent={a.key}
                                   busy={busyCell === `${row.name}:${a.key}`}
-                                  onToggle={() => toggleCell(row, a.key, row[a.key])}
+                                  onToggle={() => toggleCell(row, a.key, row.cells[a.key])}
                                   onBadgeClick={() => navigate('/plugins')}
                                   onMute={(next) => muteCell(row, a.key, next)}
                                   onResync={() => resyncCell(row, a.key)}
@@ -649,7 +652,7 @@ export function SkillLibraryPage() {
                                       row={row}
                                       agent={a.key}
                                       busy={busyCell === `${row.name}:${a.key}`}
-                                      onToggle={() => toggleCell(row, a.key, row[a.key])}
+                                      onToggle={() => toggleCell(row, a.key, row.cells[a.key])}
                                       onBadgeClick={() => navigate('/plugins')}
                                       onMute={(next) => muteCell(row, a.key, next)}
                                       onResync={() => resyncCell(row, a.key)}
@@ -681,21 +684,27 @@ interface AgentSummary {
   partial: boolean;
 }
 
+/** Whether the agent has no skills dir at the selected scope (e.g. Cursor at user scope). */
+function unavailable(cell: SkillCell): boolean {
+  return cell.status === 'off' && cell.linkPath === '';
+}
+
 /** Summarize a group's state for one agent (using each cell's own status). */
 function agentSummary(rows: SkillMatrixRow[], agent: SkillAgent): AgentSummary {
   const pluginIds = rows
-    .map((r) => (r[agent].status === 'plugin' ? r[agent].pluginId : null))
+    .map((r) => (r.cells[agent].status === 'plugin' ? r.cells[agent].pluginId : null))
     .filter(Boolean) as string[];
   const allPlugin = rows.length > 0 && pluginIds.length === rows.length;
   // Eligible = deployable here (not plugin-provided, not a name collision,
-  // not living at the target itself).
+  // not living at the target itself, and the agent has a dir in this scope).
   const eligible = rows.filter(
     (r) =>
-      r[agent].status !== 'plugin' &&
-      r[agent].status !== 'collision' &&
-      r[agent].status !== 'resident'
+      r.cells[agent].status !== 'plugin' &&
+      r.cells[agent].status !== 'collision' &&
+      r.cells[agent].status !== 'resident' &&
+      !unavailable(r.cells[agent])
   );
-  const deployedCount = eligible.filter((r) => r[agent].deployed).length;
+  const deployedCount = eligible.filter((r) => r.cells[agent].deployed).length;
   return {
     allPlugin,
     pluginId: allPlugin ? pluginIds[0] : null,
@@ -749,7 +758,7 @@ function SkillCellControl({
   onMute: (next: SkillMuteState | null) => void;
   onResync: () => void;
 }) {
-  const cell = row[agent];
+  const cell = row.cells[agent];
   if (cell.status === 'plugin' && cell.pluginId) {
     return <PluginBadge pluginId={cell.pluginId} onClick={onBadgeClick} />;
   }
@@ -859,6 +868,13 @@ function CellToggle({
       </span>
     );
   }
+  if (unavailable(cell)) {
+    return (
+      <span className="text-xs text-muted-foreground" title={`${agent} has no skills dir here`}>
+        —
+      </span>
+    );
+  }
   if (cell.status === 'resident') {
     return (
       <input
@@ -870,8 +886,8 @@ function CellToggle({
       />
     );
   }
-  // Muting only applies to a deployed Claude skill on a build that honors it.
-  const canMute = agent === 'claude' && cell.deployed && cell.muteSupported;
+  // Muting only applies to a deployed skill where the agent and build honor it.
+  const canMute = cell.deployed && cell.muteSupported;
   const muted = cell.muteState != null;
   return (
     <div className="inline-flex flex-col items-center gap-1">
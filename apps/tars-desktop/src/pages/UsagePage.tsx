@@ -7,11 +7,12 @@ import {
   Zap,
   Calendar,
   TrendingUp,
+  DollarSign,
 } from 'lucide-react';
 import { useQuery } from '@tanstack/react-query';
 import { openUrl } from '@tauri-apps/plugin-opener';
 import { cn } from '../lib/utils';
-import { getClaudeUsageStats } from '../lib/ipc';
+import { getClaudeUsageCost, getClaudeUsageStats } from '../lib/ipc';
 
 // Format large numbers with commas
 function formatNumber(num: number): string {
@@ -32,6 +33,15 @@ function formatTokens(tokens: number): string {
   return tokens.toString();
 }
 
+// Format a USD amount; sub-cent values keep more precision
+function formatCost(usd: number): string {
+  if (usd > 0 && usd < 0.01) return `$${usd.toFixed(4)}`;
+  return `$${usd.toLocaleString(undefined, {
+    minimumFractionDigits: 2,
+    maximumFractionDigits: 2,
+  })}`;
+}
+
 // Get friendly model name
 function getModelName(modelId: string): string {
   if (modelId.includes('opus')) return 'Opus';
@@ -54,9 +64,11 @@ const ACTIVITY_CHART_HEIGHT = 128; // h-32 = 128px
 function ActivityChart({
   data,
   maxValue,
+  formatValue = (value) => `${formatNumber(value)} messages`,
 }: {
   data: { date: string; value: number }[];
   maxValue: number;
+  formatValue?: (value: number) => string;
 }) {
   if (data.length === 0) return null;
 
@@ -78,7 +90,7 @@ function ActivityChart({
             <div className="absolute bottom-full mb-2 hidden group-hover:block z-10">
               <div className="bg-popover text-popover-foreground text-xs rounded px-2 py-1 shadow-lg border whitespace-nowrap">
                 <div className="font-medium">{date.toLocaleDateString()}</div>
-                <div>{formatNumber(item.value)} messages</div>
+                <div>{formatValue(item.value)}</div>
               </div>
             </div>
 
@@ -152,6 +164,13 @@ export function UsagePage() {
     staleTime: 60_000,
   });
 
+  const { data: costReport } = useQuery({
+    queryKey: ['claude-usage-cost'],
+    queryFn: getClaudeUsageCost,
+    staleTime: 60_000,
+    enabled: !!usageStats,
+  });
+
   const handleOpenClaudeSettings = async () => {
     try {
       await openUrl('https://claude.ai/settings/usage');
@@ -174,6 +193,17 @@ export function UsagePage() {
     return { data, max };
   }, [usageStats?.dailyActivity]);
 
+  // Daily cost chart data (last 30 days)
+  const costChartData = useMemo(() => {
+    if (!costReport?.daily) return { data: [], max: 0 };
+    const data = costReport.daily.slice(-30).map((d) => ({
+      date: d.date,
+      value: d.totalCost,
+    }));
+    const max = Math.max(...data.map((d) => d.value), 0.01);
+    return { data, max };
+  }, [costReport?.daily]);
+
   // Calculate total tool calls
   const totalToolCalls = useMemo(() => {
     if (!usageStats?.dailyActivity) return 0;
@@ -269,6 +299,74 @@ export function UsagePage() {
               </div>
             </div>
 
+            {/* Estimated Cost */}
+            {costReport && (
+              <div className="p-4 rounded-lg border border-border bg-card">
+                <div className="flex items-center justify-between mb-4">
+                  <h3 className="font-medium flex items-center gap-2">
+                    <DollarSign className="h-4 w-4" />
+                    Estimated Cost
+                  </h3>
+                  <span className="text-2xl font-semibold font-mono">
+                    {formatCost(costReport.totalCost)}
+                  </span>
+                </div>
+                {costChartData.data.length > 0 && (
+                  <div className="mb-8">
+                    <ActivityChart
+                      data={costChartData.data}
+                      maxValue={costChartData.max}
+                      formatValue={formatCost}
+                    />
+                  </div>
+                )}
+                <div className="space-y-2">
+                  {costReport.models.map((m) => {
+                    const colors = getModelColor(m.modelId);
+                    return (
+                      <div
+                        key={m.modelId}
+                        className="flex items-center justify-between text-sm"
+                        title={m.modelId}
+                      >
+                        <span className={cn('font-medium', colors.text)}>
+                          {getModelName(m.modelId)}
+                          {m.price?.isOverridden && (
+                            <span className="ml-2 text-[10px] text-muted-foreground">
+                              (price override)
+                            </span>
+                          )}
+                        </span>
+                        <span className="flex items-center gap-4 text-xs text-muted-foreground">
+                          <span>In {formatCost(m.inputCost)}</span>
+                          <span>Out {formatCost(m.outputCost)}</span>
+                          <span
+                            title={
+                              m.price?.cacheRatesEstimated
+                                ? 'No cached cache rates; estimated from the input price'
+                                : undefined
+                            }
+                          >
+                            Cache {formatCost(m.cacheReadCost + m.cacheCreationCost)}
+                            {m.price?.cacheRatesEstimated && '*'}
+                          </span>
+                          <span className="font-mono text-sm text-foreground">
+                            {m.price ? formatCost(m.totalCost) : '—'}
+                          </span>
+                        </span>
+                      </div>
+                    );
+                  })}
+                </div>
+                {costReport.unpricedModels.length > 0 && (
+                  <p className="text-xs text-muted-foreground mt-4 pt-3 border-t border-border">
+                    No cached price for {costReport.unpricedModels.join(', ')}. Add an Anthropic
+                    key and refresh its models and pricing on the API Keys page.
+                  </p>
+                )}
+              </div>
+            )}
+
             <div className="grid grid-cols-2 gap-4">
               {/* Model Usage */}
               <div className="p-4 rounded-lg border border-border bg-card">
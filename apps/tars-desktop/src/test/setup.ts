@@ -14,6 +14,7 @@ vi.mock('@tauri-apps/api/core', () => ({
 
 vi.mock('@tauri-apps/plugin-dialog', () => ({
   open: vi.fn(),
+  save: vi.fn(),
   message: vi.fn(),
   confirm: vi.fn(),
 }));
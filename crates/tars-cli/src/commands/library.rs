@@ -0,0 +1,579 @@
+//! Skill library CLI commands
+//!
+//! Handles: tars library source add/rm/ls, scan, install, outdated, update, deploy,
+//! undeploy, matrix, drift, resolve, agents
+
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use clap::{Subcommand, ValueEnum};
+
+use tars_core::skills::{
+    add_source, agent_infos, apply_skill_update, check_skill_updates, deploy_skill,
+    deployment_drift, external_skills_dir, find_library_skill, install_from_git,
+    parse_git_skill_url, prepare_skill_update, resolve_drift, scan_library, short_sha,
+    skill_matrix, undeploy_skill, Agent, DeployRequest, DriftResolution, FileDriftKind, LinkKind,
+    MatrixEnv, Scope, SkillCell, UpdateStatus, UpdateTarget,
+};
+use tars_core::storage::skill_library::{SkillDeployment, SkillDeploymentStore, SkillSourceStore};
+use tars_core::storage::{Database, ProjectStore};
+use tars_core::Project;
+
+/// Skill library commands
+#[derive(Subcommand)]
+pub enum LibraryCommands {
+    /// Manage library source directories
+    Source {
+        #[command(subcommand)]
+        action: SourceCommands,
+    },
+    /// List the skills in all registered sources
+    Scan {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Install skills from an https git repository into ~/.agents/skills
+    Install {
+        /// Repository URL, optionally with /tree/<ref>[/<subpath>]
+        url: String,
+        /// Install this commit and pin it
+        #[arg(long)]
+        pin: Option<String>,
+    },
+    /// Check git-installed skills for new upstream commits
+    Outdated {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Move a git-installed skill to the latest commit, or pin it to one
+    Update {
+        /// Skill name
+        skill: String,
+        /// Move to this commit and pin it
+        #[arg(long, conflicts_with = "unpin")]
+        pin: Option<String>,
+        /// Drop the pin and follow the recorded ref again
+        #[arg(long)]
+        unpin: bool,
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Deploy a library skill to an agent
+    Deploy {
+        /// Skill name
+        skill: String,
+        #[command(flatten)]
+        target: TargetArgs,
+        /// Copy the skill instead of symlinking it
+        #[arg(long)]
+        copy: bool,
+    },
+    /// Remove a skill deployed by TARS
+    Undeploy {
+        /// Skill name
+        skill: String,
+        #[command(flatten)]
+        target: TargetArgs,
+    },
+    /// Show which files of a copy deployment changed in the source, the copy, or both
+    Drift {
+        /// Skill name
+        skill: String,
+        #[command(flatten)]
+        target: TargetArgs,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Bring a drifted copy deployment back in sync (backs up each side first)
+    Resolve {
+        /// Skill name
+        skill: String,
+        #[command(flatten)]
+        target: TargetArgs,
+        /// How to resolve the drift
+        #[arg(long, value_enum)]
+        using: ResolutionArg,
+    },
+    /// Show every skill with its Claude and Codex state
+    Matrix {
+        /// Show project scope for this project directory instead of user scope
+        #[arg(long)]
+        project: Option<PathBuf>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// List the agents skills can be deployed to, with their directories
+    Agents {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// Library source commands
+#[derive(Subcommand)]
+pub enum SourceCommands {
+    /// Register a directory of skills
+    Add {
+        /// Source directory
+        path: PathBuf,
+        /// Display label
+        #[arg(long)]
+        label: Option<String>,
+    },
+    /// Unregister a source by ID or path
+    Rm {
+        /// Source ID or path
+        source: String,
+    },
+    /// List registered sources
+    Ls {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// Where a skill is deployed
+#[derive(clap::Args)]
+pub struct TargetArgs {
+    /// Target agent
+    #[arg(long, value_enum)]
+    agent: AgentArg,
+    /// Target scope
+    #[arg(long, value_enum)]
+    scope: ScopeArg,
+    /// Project directory for project scope (defaults to current directory)
+    #[arg(long)]
+    project: Option<PathBuf>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum AgentArg {
+    Claude,
+    Codex,
+    Gemini,
+    Cursor,
+    Opencode,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum ScopeArg {
+    User,
+    Project,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum ResolutionArg {
+    /// Pull edits made to the deployed copy back into the library source
+    PullBack,
+    /// Overwrite the deployed copy with the library source
+    Overwrite,
+    /// Three-way merge each file, leaving conflict markers in the copy
+    Merge,
+}
+
+impl From<AgentArg> for Agent {
+    fn from(agent: AgentArg) -> Self {
+        match agent {
+            AgentArg::Claude => Agent::Claude,
+            AgentArg::Codex => Agent::Codex,
+            AgentArg::Gemini => Agent::Gemini,
+            AgentArg::Cursor => Agent::Cursor,
+            AgentArg::Opencode => Agent::OpenCode,
+        }
+    }
+}
+
+impl From<ScopeArg> for Scope {
+    fn from(scope: ScopeArg) -> Self {
+        match scope {
+            ScopeArg::User => Scope::User,
+            ScopeArg::Project => Scope::Project,
+        }
+    }
+}
+
+impl From<ResolutionArg> for DriftResolution {
+    fn from(resolution: ResolutionArg) -> Self {
+        match resolution {
+            ResolutionArg::PullBack => DriftResolution::PullBack,
+            ResolutionArg::Overwrite => DriftResolution::Overwrite,
+            ResolutionArg::Merge => DriftResolution::Merge,
+        }
+    }
+}
+
+/// Execute a library command
+pub fn execute(cmd: LibraryCommands) -> Result<(), Box<dyn std::error::Error>> {
+    let data_dir = crate::get_data_dir()?;
+    std::fs::create_dir_all(&data_dir)?;
+    let db = Database::open(&data_dir.join("tars.db"))?;
+    let home = crate::get_home_dir().ok_or("Cannot find home directory")?;
+
+    match cmd {
+        LibraryCommands::Source { action } => execute_source(&db, action)?,
+        LibraryCommands::Scan { json } => {
+            let catalog = scan_library(db.connection())?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&catalog)?);
+                return Ok(());
+            }
+            if catalog.is_empty() {
+                println!("No skills found. Register a source with `tars library source add`.");
+                return Ok(());
+            }
+            for skill in catalog {
+                println!("  {}  {}", skill.name, skill.source_dir.display());
+                if !skill.description.is_empty() {
+                    println!("      {}", skill.description);
+                }
+            }
+        }
+        LibraryCommands::Install { url, pin } => {
+            let source = parse_git_skill_url(&url)?;
+            let report = install_from_git(
+                db.connection(),
+                &source,
+                pin.as_deref(),
+                &external_skills_dir(&home),
+            )?;
+            for name in &report.installed {
+                println!("Installed '{name}'");
+            }
+            for name in &report.skipped {
+                println!("Skipped '{name}' (already exists)");
+            }
+        }
+        LibraryCommands::Outdated { json } => {
+            let checks = check_skill_updates(db.connection())?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&checks)?);
+                return Ok(());
+            }
+            if checks.is_empty() {
+                println!("No skills installed from git.");
+                return Ok(());
+            }
+            for check in checks {
+                let current = short_sha(&check.origin.commit_sha);
+                let latest = check.latest_sha.as_deref().map_or("?", short_sha);
+                let status = match check.status {
+                    UpdateStatus::UpToDate => "up to date".to_string(),
+                    UpdateStatus::Outdated => format!("{current} -> {latest}"),
+                    UpdateStatus::Pinned if latest == current => format!("pinned at {current}"),
+                    UpdateStatus::Pinned => format!("pinned at {current} (latest {latest})"),
+                    UpdateStatus::Unreachable => {
+                        format!("unreachable: {}", check.error.unwrap_or_default())
+                    }
+                };
+                println!("  {:<30} {status}", check.origin.skill_name);
+            }
+        }
+        LibraryCommands::Update {
+            skill,
+            pin,
+            unpin,
+            force,
+        } => {
+            let target = match pin {
+                Some(sha) => UpdateTarget::Pin(sha),
+                None if unpin => UpdateTarget::Unpin,
+                None => UpdateTarget::Latest,
+            };
+            let update = prepare_skill_update(db.connection(), &skill, &target)?;
+            let preview = &update.preview;
+            let from = short_sha(&preview.from_sha).to_string();
+            let to = short_sha(&preview.to_sha).to_string();
+            if preview.diff.is_empty() && preview.from_sha == preview.to_sha {
+                if matches!(target, UpdateTarget::Latest) {
+                    println!("'{skill}' is up to date at {from}");
+                    return Ok(());
+                }
+            } else {
+                print!("{}", preview.diff);
+            }
+
+            if !force {
+                print!("Update '{skill}' from {from} to {to}? [y/N] ");
+                io::stdout().flush()?;
+                let mut input = String::new();
+                io::stdin().read_line(&mut input)?;
+                if !input.trim().eq_ignore_ascii_case("y") {
+                    println!("Cancelled.");
+                    return Ok(());
+                }
+            }
+            let applied = apply_skill_update(db.connection(), update)?;
+            if applied.pinned {
+                println!("Updated '{skill}' to {to} (pinned)");
+            } else {
+                println!("Updated '{skill}' to {to}");
+            }
+        }
+        LibraryCommands::Deploy {
+            skill,
+            target,
+            copy,
+        } => {
+            let found = find_library_skill(db.connection(), &home, &skill)?
+                .ok_or_else(|| format!("Skill not found in the library: {skill}"))?;
+            let scope = Scope::from(target.scope);
+            let project_id = match scope {
+                Scope::Project => Some(project_id_for(&db, target.project.as_deref(), true)?),
+                Scope::User => None,
+            };
+            let request = DeployRequest {
+                skill_name: found.name,
+                source_dir: found.source_dir,
+                agent: target.agent.into(),
+                scope,
+                project_id,
+                link_kind: if copy {
+                    LinkKind::Copy
+                } else {
+                    LinkKind::Symlink
+                },
+            };
+            let deployment = deploy_skill(db.connection(), &request, &home)?;
+            println!(
+                "Deployed '{}' to {} ({})",
+                deployment.skill_name, deployment.link_path, deployment.link_kind
+            );
+        }
+        LibraryCommands::Undeploy { skill, target } => {
+            let deployment = tracked_deployment(&db, &skill, &target)?;
+            undeploy_skill(db.connection(), deployment.id, &home)?;
+            println!("Removed '{skill}' from {}", deployment.link_path);
+        }
+        LibraryCommands::Drift {
+            skill,
+            target,
+            json,
+        } => {
+            let deployment = tracked_deployment(&db, &skill, &target)?;
+            let drift = deployment_drift(db.connection(), deployment.id)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&drift)?);
+                return Ok(());
+            }
+            if drift.files.is_empty() {
+                println!("'{skill}' is in sync with {}", drift.source_path);
+                return Ok(());
+            }
+            println!("{} -> {}", drift.source_path, drift.link_path);
+            for file in &drift.files {
+                let kind = match file.kind {
+                    FileDriftKind::SourceChanged => "source changed",
+                    FileDriftKind::TargetChanged => "target changed",
+                    FileDriftKind::BothChanged => "both changed",
+                };
+                println!("  {kind:<16} {}", file.path);
+            }
+            if !drift.has_baseline {
+                println!("No per-file base was recorded for this deployment; files changed on both sides can only be merged as conflicts.");
+            }
+        }
+        LibraryCommands::Resolve {
+            skill,
+            target,
+            using,
+        } => {
+            let deployment = tracked_deployment(&db, &skill, &target)?;
+            let report = resolve_drift(
+                db.connection(),
+                deployment.id,
+                using.into(),
+                &data_dir.join("backups"),
+            )?;
+            for backup in &report.backups {
+                println!("Backed up to {}", backup.display());
+            }
+            for path in &report.source_updated {
+                println!("  source  {path}");
+            }
+            for path in &report.target_updated {
+                println!("  target  {path}");
+            }
+            if report.conflicts.is_empty() {
+                println!("'{skill}' is in sync");
+            } else {
+                println!("Conflicts left in {}:", deployment.link_path);
+                for path in &report.conflicts {
+                    println!("  {path}");
+                }
+            }
+        }
+        LibraryCommands::Matrix { project, json } => {
+            let project_id = match project {
+                Some(path) => Some(project_id_for(&db, Some(&path), false)?),
+                None => None,
+            };
+            let env = MatrixEnv::detect(home)?;
+            let groups = skill_matrix(db.connection(), project_id.as_deref(), &env)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&groups)?);
+                return Ok(());
+            }
+            if groups.is_empty() {
+                println!("No skills found. Register a source with `tars library source add`.");
+                return Ok(());
+            }
+            for group in groups {
+                println!("{} ({})", group.label, group.kind);
+                for row in group.skills {
+                    let cells: Vec<_> = row
+                        .cells
+                        .iter()
+                        .map(|(agent, cell)| {
+                            format!("{}: {:<12}", agent.as_str(), cell_label(cell))
+                        })
+                        .collect();
+                    println!("  {:<30} {}", row.name, cells.join(" ").trim_end());
+                }
+            }
+        }
+        LibraryCommands::Agents { json } => {
+            let infos = agent_infos(&home);
+            if json {
+                println!("{}", serde_json::to_string_pretty(&infos)?);
+                return Ok(());
+            }
+            let dir = |dir: &Option<PathBuf>| {
+                dir.as_ref()
+                    .map_or_else(|| "-".to_string(), |dir| dir.display().to_string())
+            };
+            for info in infos {
+                let mut notes = Vec::new();
+                if !info.symlinks {
+                    notes.push("copies only");
+                }
+                if info.converts {
+                    notes.push("converted");
+                }
+                if info.mute {
+                    notes.push("mutable");
+                }
+                let line = format!(
+                    "  {:<10} {:<12} user: {:<32} project: {:<18} {}",
+                    info.agent.as_str(),
+                    info.label,
+                    dir(&info.user_dir),
+                    dir(&info.project_dir),
+                    notes.join(", ")
+                );
+                println!("{}", line.trim_end());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn execute_source(db: &Database, cmd: SourceCommands) -> Result<(), Box<dyn std::error::Error>> {
+    let sources = SkillSourceStore::new(db.connection());
+    match cmd {
+        SourceCommands::Add { path, label } => {
+            let source = add_source(db.connection(), &path, label.as_deref())?;
+            println!("Source {}: {}", source.id, source.path);
+        }
+        SourceCommands::Rm { source } => {
+            let found = if let Ok(id) = source.parse::<i64>() {
+                sources.get(id)?
+            } else {
+                let path = PathBuf::from(&source);
+                let path = path.canonicalize().unwrap_or(path);
+                sources.get_by_path(&path.display().to_string())?
+            };
+            let found = found.ok_or_else(|| format!("Source not found: {source}"))?;
+            sources.delete(found.id)?;
+            println!("Removed source {}: {}", found.id, found.path);
+        }
+        SourceCommands::Ls { json } => {
+            let list = sources.list()?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&list)?);
+                return Ok(());
+            }
+            if list.is_empty() {
+                println!("No sources. Add one with `tars library source add <path>`.");
+                return Ok(());
+            }
+            for source in list {
+                match &source.label {
+                    Some(label) => println!("  {}  {}  ({label})", source.id, source.path),
+                    None => println!("  {}  {}", source.id, source.path),
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Find the tracked deployment of `skill` at a target.
+fn tracked_deployment(
+    db: &Database,
+    skill: &str,
+    target: &TargetArgs,
+) -> Result<SkillDeployment, Box<dyn std::error::Error>> {
+    let agent = Agent::from(target.agent);
+    let scope = Scope::from(target.scope);
+    let project_id = match scope {
+        Scope::Project => Some(project_id_for(db, target.project.as_deref(), false)?),
+        Scope::User => None,
+    };
+    let deployment = SkillDeploymentStore::new(db.connection())
+        .get_target(agent.as_str(), scope.as_str(), project_id.as_deref(), skill)?
+        .ok_or_else(|| {
+            format!(
+                "'{skill}' is not deployed by TARS to {} {} scope",
+                agent.as_str(),
+                scope.as_str()
+            )
+        })?;
+    Ok(deployment)
+}
+
+/// Look up the project at `path` (default: current directory), registering
+/// it first when `create` is set.
+fn project_id_for(
+    db: &Database,
+    path: Option<&Path>,
+    create: bool,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let path = match path {
+        Some(path) => path.to_path_buf(),
+        None => std::env::current_dir()?,
+    };
+    let path = path.canonicalize()?;
+    let projects = ProjectStore::new(db.connection());
+    if let Some(project) = projects.get_by_path(&path)? {
+        return Ok(project.id.to_string());
+    }
+    if !create {
+        return Err(format!("Project not found: {}", path.display()).into());
+    }
+    let project = Project::new(path);
+    projects.create(&project)?;
+    Ok(project.id.to_string())
+}
+
+fn cell_label(cell: &SkillCell) -> String {
+    // The agent has no skills dir at this scope
+    if cell.status == "off" && cell.link_path.is_empty() {
+        return "-".to_string();
+    }
+    let mut label = cell.status.clone();
+    if cell.drifted {
+        label.push_str(" (drifted)");
+    }
+    if let Some(plugin) = &cell.plugin_id {
+        label = format!("{label} ({plugin})");
+    }
+    label
+}
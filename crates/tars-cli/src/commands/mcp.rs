@@ -1,15 +1,19 @@
 //! MCP server CLI commands
 //!
-//! Handles: tars mcp add/remove/update/move/list
+//! Handles: tars mcp add/remove/update/move/list/catalog/refresh
 
 use clap::{Args, Subcommand};
 use serde_json::json;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use tars_core::config::{
-    ConfigItemData, ConfigScope, McpOps, McpServerConfig, McpServerUpdate, McpTransport,
+    mcp_catalog_dir, provider_key, ConfigItemData, ConfigScope, McpCatalog, McpCatalogError,
+    McpOps, McpRefresher, McpServerConfig, McpServerUpdate, McpTransport, ParamKind, RefreshStep,
+    RefreshStrategy, TemplateInstance,
 };
+use tars_core::storage::{ApiKeyStore, Database};
+use tars_providers::{custom_provider_slug, ProviderId};
 
 /// MCP server commands
 #[derive(Subcommand)]
@@ -61,21 +65,42 @@ pub enum McpCommands {
         #[arg(long)]
         json: bool,
     },
+    /// List MCP server templates usable with `tars mcp add --from-template`
+    Catalog {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Update the package, image or checkout behind an MCP server
+    Refresh {
+        /// Server name
+        name: String,
+        /// Scope to look in (auto-detect if not specified)
+        #[arg(long)]
+        scope: Option<String>,
+        /// Show the commands that would run without running them
+        #[arg(long)]
+        dry_run: bool,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 /// Arguments for `tars mcp add`
 #[derive(Args)]
 pub struct McpAddArgs {
-    /// Server name (must be unique in scope)
-    pub name: String,
+    /// Server name (must be unique in scope; defaults to the template id)
+    #[arg(required_unless_present = "from_template")]
+    pub name: Option<String>,
 
     /// Target scope (user, project)
     #[arg(long, default_value = "project")]
     pub scope: String,
 
-    /// Transport type (stdio, http, sse)
-    #[arg(long, value_name = "TYPE", default_value = "stdio")]
-    pub r#type: String,
+    /// Transport type (stdio, http, sse) [default: stdio, or the template's default]
+    #[arg(long, value_name = "TYPE")]
+    pub r#type: Option<String>,
 
     /// Command for stdio transport
     #[arg(long)]
@@ -93,6 +118,14 @@ pub struct McpAddArgs {
     #[arg(long)]
     pub url: Option<String>,
 
+    /// Fill in the server from an MCP catalog template (see `tars mcp catalog`)
+    #[arg(long, value_name = "ID", conflicts_with_all = ["command", "args", "url"])]
+    pub from_template: Option<String>,
+
+    /// Template parameter (NAME=value, can specify multiple times)
+    #[arg(long = "param", value_name = "NAME=VALUE", requires = "from_template")]
+    pub params: Vec<String>,
+
     /// Preview changes without applying
     #[arg(long)]
     pub dry_run: bool,
@@ -105,20 +138,8 @@ pub struct McpAddArgs {
 impl McpAddArgs {
     /// Convert to `McpServerConfig`
     pub fn to_config(&self) -> Result<McpServerConfig, String> {
-        let transport = match self.r#type.as_str() {
-            "stdio" => McpTransport::Stdio,
-            "http" => McpTransport::Http,
-            "sse" => McpTransport::Sse,
-            other => return Err(format!("Invalid transport type: {other}")),
-        };
-
-        let mut env = HashMap::new();
-        for e in &self.env {
-            let (key, value) = e
-                .split_once('=')
-                .ok_or_else(|| format!("Invalid env format: {e} (expected KEY=value)"))?;
-            env.insert(key.to_string(), value.to_string());
-        }
+        let transport = self.parse_transport()?.unwrap_or(McpTransport::Stdio);
+        let env = parse_key_values(&self.env, "env")?;
 
         let config = McpServerConfig {
             transport,
@@ -137,6 +158,85 @@ impl McpAddArgs {
     pub fn parse_scope(&self) -> Result<ConfigScope, String> {
         self.scope.parse().map_err(|e| format!("{e}"))
     }
+
+    /// Parse `--type`, if given
+    fn parse_transport(&self) -> Result<Option<McpTransport>, String> {
+        self.r#type
+            .as_deref()
+            .map(|t| match t {
+                "stdio" => Ok(McpTransport::Stdio),
+                "http" => Ok(McpTransport::Http),
+                "sse" => Ok(McpTransport::Sse),
+                other => Err(format!("Invalid transport type: {other}")),
+            })
+            .transpose()
+    }
+
+    /// Build the config from a catalog template, filling provider-linked
+    /// parameters from the API key vault
+    fn template_config(
+        &self,
+        template_id: &str,
+    ) -> Result<(McpServerConfig, TemplateInstance), Box<dyn std::error::Error>> {
+        let home = dirs::home_dir().ok_or("Cannot find home directory")?;
+        let catalog = load_catalog(&home)?;
+        let template = catalog.require(template_id)?;
+        let values = parse_key_values(&self.params, "param")?;
+
+        // Only touch the key vault when a provider-linked parameter still
+        // needs a value.
+        let needs_vault = template
+            .params
+            .iter()
+            .any(|p| p.provider.is_some() && !values.contains_key(&p.name));
+        let db = if needs_vault {
+            let db_path = home.join(".tars").join("tars.db");
+            db_path
+                .exists()
+                .then(|| Database::open(&db_path))
+                .transpose()?
+        } else {
+            None
+        };
+        let store = db.as_ref().map(|db| ApiKeyStore::new(db.connection()));
+
+        let mut vault_errors = Vec::new();
+        let instance = template.instantiate(self.parse_transport()?, &values, |provider| {
+            if ProviderId::parse(provider).is_none() {
+                vault_errors.push(format!("unknown provider '{provider}'"));
+                return None;
+            }
+            let store = store.as_ref()?;
+            provider_key(store, provider)
+                .map_err(|e| vault_errors.push(e.to_string()))
+                .ok()
+                .flatten()
+        });
+        let instance = instance.map_err(|e| {
+            if vault_errors.is_empty() {
+                e.to_string()
+            } else {
+                format!("{e} (key vault: {})", vault_errors.join("; "))
+            }
+        })?;
+
+        let mut config = instance.config.clone();
+        config.env.extend(parse_key_values(&self.env, "env")?);
+        config.validate()?;
+        Ok((config, instance))
+    }
+}
+
+/// Parse repeated `KEY=value` flags
+fn parse_key_values(pairs: &[String], flag: &str) -> Result<HashMap<String, String>, String> {
+    pairs
+        .iter()
+        .map(|pair| {
+            pair.split_once('=')
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .ok_or_else(|| format!("Invalid {flag} format: {pair} (expected KEY=value)"))
+        })
+        .collect()
 }
 
 /// Arguments for `tars mcp update`
@@ -209,6 +309,13 @@ pub fn execute(
             dry_run,
             json,
         } => execute_move(&name, from, &to, force, dry_run, json, project_path),
+        McpCommands::Catalog { json } => execute_catalog(json),
+        McpCommands::Refresh {
+            name,
+            scope,
+            dry_run,
+            json,
+        } => execute_refresh(&name, scope, dry_run, json, project_path),
     }
 }
 
@@ -292,33 +399,56 @@ fn execute_add(
     project_path: Option<&PathBuf>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let ops = McpOps::new(project_path.cloned());
-    let config = args.to_config()?;
+    let (config, instance) = match &args.from_template {
+        Some(id) => {
+            let (config, instance) = args.template_config(id)?;
+            (config, Some(instance))
+        }
+        None => (args.to_config()?, None),
+    };
     let scope = args.parse_scope()?;
+    let name = args
+        .name
+        .clone()
+        .or_else(|| args.from_template.clone())
+        .ok_or("Server name is required")?;
 
-    let result = ops.add(&args.name, scope, config, args.dry_run)?;
+    let result = ops.add(&name, scope, config.clone(), args.dry_run)?;
 
     if args.json {
         let output = json!({
             "success": result.success,
             "operation": "add",
-            "server": args.name,
+            "server": name,
             "scope": scope.to_string(),
             "dry_run": args.dry_run,
+            "template": args.from_template,
+            "from_key_vault": instance.as_ref().map(|i| &i.from_key_vault),
             "message": result.error,
             "backup_id": result.backup_id,
             "files_modified": result.files_modified.iter().map(|p| p.display().to_string()).collect::<Vec<_>>(),
         });
         println!("{}", serde_json::to_string_pretty(&output)?);
     } else if args.dry_run {
-        println!(
-            "Dry run: Would add MCP server '{}' to {} scope",
-            args.name, scope
-        );
+        println!("Dry run: Would add MCP server '{name}' to {scope} scope");
+        if args.from_template.is_some() {
+            println!("  {}", config.display());
+        }
+        if let Some(instance) = &instance {
+            for param in &instance.from_key_vault {
+                println!("  {param}: from API key vault");
+            }
+        }
         if !result.files_modified.is_empty() {
             println!("Would modify: {}", result.files_modified[0].display());
         }
     } else if result.success {
-        println!("Added MCP server '{}' to {} scope", args.name, scope);
+        println!("Added MCP server '{name}' to {scope} scope");
+        if let Some(instance) = &instance {
+            for param in &instance.from_key_vault {
+                println!("  {param}: filled from API key vault");
+            }
+        }
         if let Some(backup_id) = &result.backup_id {
             println!("Backup created: {backup_id}");
         }
@@ -560,3 +690,152 @@ fn execute_move(
 
     Ok(())
 }
+
+fn execute_refresh(
+    name: &str,
+    scope: Option<String>,
+    dry_run: bool,
+    json_output: bool,
+    project_path: Option<&PathBuf>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let ops = McpOps::new(project_path.cloned());
+
+    let items = if let Some(scope_str) = &scope {
+        let scope: ConfigScope = scope_str.parse()?;
+        ops.list_scope(scope)?
+    } else {
+        ops.list()?
+    };
+    let server = items
+        .into_iter()
+        .find(|item| item.name == name)
+        .ok_or_else(|| format!("MCP server '{name}' not found"))?;
+    let ConfigItemData::McpServer(config) = &server.config else {
+        return Err("Invalid server configuration".into());
+    };
+
+    let refresher = McpRefresher::new();
+    let Some(plan) = refresher.plan(config) else {
+        let supported: Vec<&str> = refresher
+            .strategies()
+            .map(RefreshStrategy::description)
+            .collect();
+        return Err(format!(
+            "Cannot determine how to refresh MCP server '{name}'. Supported:\n  {}",
+            supported.join("\n  ")
+        )
+        .into());
+    };
+
+    let outcome = if plan.is_skip() || dry_run {
+        None
+    } else {
+        Some(plan.execute())
+    };
+
+    if json_output {
+        let output = json!({
+            "success": outcome.as_ref().map_or(true, |o| o.success),
+            "operation": "refresh",
+            "server": name,
+            "scope": server.scope.to_string(),
+            "dry_run": dry_run,
+            "strategy": plan.strategy,
+            "action": plan.action,
+            "note": plan.note,
+            "commands": plan.steps.iter().map(RefreshStep::command_line).collect::<Vec<_>>(),
+            "steps": outcome.as_ref().map(|o| &o.steps),
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else if plan.is_skip() {
+        println!(
+            "MCP server '{name}': {}",
+            plan.note.as_deref().unwrap_or("no refresh needed")
+        );
+    } else if dry_run {
+        println!(
+            "Dry run: Would refresh MCP server '{name}' ({})",
+            plan.action
+        );
+        for step in &plan.steps {
+            match &step.cwd {
+                Some(dir) => println!("  {} (in {})", step.command_line(), dir.display()),
+                None => println!("  {}", step.command_line()),
+            }
+        }
+    } else if let Some(outcome) = &outcome {
+        for step in &outcome.steps {
+            println!("=== {} ===", step.command);
+            println!("{}", step.output.trim_end());
+        }
+        if outcome.success {
+            println!("Refreshed MCP server '{name}' ({})", plan.action);
+        }
+    }
+
+    if outcome.is_some_and(|o| !o.success) {
+        eprintln!("Failed to refresh MCP server '{name}'");
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Load the MCP catalog, checking provider links against known providers
+fn load_catalog(home: &Path) -> Result<McpCatalog, McpCatalogError> {
+    McpCatalog::load(&mcp_catalog_dir(home), |provider| {
+        ProviderId::parse(provider).is_some() || custom_provider_slug(provider).is_some()
+    })
+}
+
+fn execute_catalog(json_output: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let home = dirs::home_dir().ok_or("Cannot find home directory")?;
+    let catalog = load_catalog(&home)?;
+
+    if json_output {
+        let output = json!({
+            "count": catalog.entries().len(),
+            "templates": catalog.entries(),
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    for entry in catalog.entries() {
+        let template = &entry.template;
+        let transports: Vec<String> = template
+            .transports
+            .iter()
+            .map(|t| format!("{t:?}").to_lowercase())
+            .collect();
+        println!(
+            "{} - {} [{}]",
+            template.id,
+            template.name,
+            transports.join(", ")
+        );
+        if let Some(desc) = &template.description {
+            println!("    {desc}");
+        }
+        for param in &template.params {
+            let kind = match param.kind {
+                ParamKind::Env => "env",
+                ParamKind::Arg => "arg",
+            };
+            let mut notes = Vec::new();
+            if !param.required {
+                notes.push("optional".to_string());
+            }
+            if let Some(provider) = &param.provider {
+                notes.push(format!("key vault: {provider}"));
+            }
+            let notes = if notes.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", notes.join(", "))
+            };
+            println!("    --param {}=<{kind}>{notes}", param.name);
+        }
+    }
+    Ok(())
+}
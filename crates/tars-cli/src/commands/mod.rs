@@ -6,5 +6,10 @@
 pub mod agent;
 pub mod command;
 pub mod hook;
+pub mod library;
 pub mod mcp;
+pub mod profile_batch;
+pub mod profile_lock;
+pub mod profile_repo;
+pub mod profile_rule;
 pub mod skill;
@@ -0,0 +1,176 @@
+//! Batch apply CLI commands
+//!
+//! Handles: tars profile batch apply/rollback/ls
+
+use std::path::Path;
+
+use clap::Subcommand;
+use uuid::Uuid;
+
+use tars_core::profile::{apply_batch, plan_batch, rollback_batch, BatchPlan};
+use tars_core::storage::{ApplyBatch, ApplyBatchStore, BatchProjectStatus, Database, ProfileStore};
+
+/// Batch apply commands
+#[derive(Subcommand)]
+pub enum ProfileBatchCommands {
+    /// Apply a profile to every project assigned to it
+    Apply {
+        /// Profile name or ID
+        profile: String,
+        /// Show the plans without applying them
+        #[arg(long)]
+        dry_run: bool,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Restore every project a batch changed
+    Rollback {
+        /// Batch ID
+        batch_id: String,
+    },
+    /// List batches, newest first
+    Ls {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// Execute a batch command
+pub fn execute(
+    db: &Database,
+    data_dir: &Path,
+    cmd: ProfileBatchCommands,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match cmd {
+        ProfileBatchCommands::Apply {
+            profile,
+            dry_run,
+            json,
+        } => {
+            let profile = crate::find_profile(&ProfileStore::new(db.connection()), &profile)?;
+            let plan = plan_batch(db.connection(), profile.id)?;
+
+            if dry_run {
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&plan)?);
+                } else {
+                    print_plan(&plan);
+                    println!("\nDry run - no changes made.");
+                }
+                return Ok(());
+            }
+            if plan.changed() == 0 {
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&plan)?);
+                } else {
+                    print_plan(&plan);
+                    println!("\nNo changes needed.");
+                }
+                return Ok(());
+            }
+
+            let batch = apply_batch(db.connection(), &plan, &data_dir.join("backups"))?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&batch)?);
+                return Ok(());
+            }
+            print_plan(&plan);
+            println!();
+            print_batch(&batch);
+            println!("\nRoll back with: tars profile batch rollback {}", batch.id);
+        }
+        ProfileBatchCommands::Rollback { batch_id } => {
+            let id = Uuid::parse_str(&batch_id)?;
+            let rollback = rollback_batch(db.connection(), id)?;
+            for name in &rollback.restored {
+                println!("  restored  {name}");
+            }
+            for failure in &rollback.failures {
+                println!("  failed    {}: {}", failure.project_name, failure.error);
+            }
+            if rollback.failures.is_empty() {
+                println!(
+                    "Rolled back batch {id} ({} project(s) restored).",
+                    rollback.restored.len()
+                );
+            } else {
+                return Err(format!(
+                    "{} project(s) could not be restored; fix them and run the rollback again",
+                    rollback.failures.len()
+                )
+                .into());
+            }
+        }
+        ProfileBatchCommands::Ls { json } => {
+            let batches = ApplyBatchStore::new(db.connection()).list()?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&batches)?);
+                return Ok(());
+            }
+            if batches.is_empty() {
+                println!("No batches. Run one with `tars profile batch apply`.");
+                return Ok(());
+            }
+            for batch in batches {
+                let state = if batch.rolled_back_at.is_some() {
+                    " (rolled back)"
+                } else {
+                    ""
+                };
+                println!(
+                    "{}  {}  '{}': {} applied, {} failed{state}",
+                    batch.id,
+                    batch.created_at.format("%Y-%m-%d %H:%M:%S"),
+                    batch.profile_name,
+                    batch.count(BatchProjectStatus::Applied),
+                    batch.count(BatchProjectStatus::Failed),
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn print_plan(plan: &BatchPlan) {
+    println!(
+        "Profile '{}' is assigned to {} project(s):",
+        plan.profile_name,
+        plan.projects.len()
+    );
+    for project in &plan.projects {
+        let detail = match &project.error {
+            Some(error) => format!("error: {error}"),
+            None if project.has_changes() => project.summary.one_line(),
+            None => "up to date".to_string(),
+        };
+        println!("  {}  {detail}", project.project.name);
+    }
+    println!(
+        "\nSummary: {} project(s) to change - {}",
+        plan.changed(),
+        plan.summary.one_line()
+    );
+}
+
+fn print_batch(batch: &ApplyBatch) {
+    println!("Batch {}:", batch.id);
+    for entry in &batch.entries {
+        match entry.status {
+            BatchProjectStatus::Applied => println!(
+                "  applied    {} ({} operations, backup {})",
+                entry.project_name,
+                entry.operations,
+                entry.backup_id.map(|id| id.to_string()).unwrap_or_default()
+            ),
+            BatchProjectStatus::Unchanged => println!("  unchanged  {}", entry.project_name),
+            BatchProjectStatus::Failed => println!(
+                "  failed     {}: {}",
+                entry.project_name,
+                entry.error.as_deref().unwrap_or("unknown error")
+            ),
+        }
+    }
+}
@@ -0,0 +1,158 @@
+//! Profile lock CLI commands
+//!
+//! Handles: tars profile verify, tars profile sync --from-lock
+
+use std::path::Path;
+
+use tars_core::diff::display::{format_plan_terminal, DiffSummary};
+use tars_core::diff::plan::generate_plan_with_context;
+use tars_core::profile::{
+    locked_profile, read_lock, verify_lock, LockedSource, TemplateContext, LOCK_FILE,
+};
+use tars_core::storage::{BackupStore, Database, MetadataStore, ProjectStore};
+use tars_core::Project;
+
+/// Check a project's files against its lock; fails if any file differs
+pub fn verify(db: &Database, target: &Path, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let verification = verify_lock(target)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&verification)?);
+    } else {
+        let lock = &verification.lock;
+        println!(
+            "Locked to profile '{}' at revision {}",
+            lock.profile,
+            short(lock.revision.as_deref().unwrap_or("unknown"))
+        );
+        for mismatch in &verification.mismatches {
+            match &mismatch.actual {
+                Some(_) => println!("  modified  {}", mismatch.path),
+                None => println!("  missing   {}", mismatch.path),
+            }
+        }
+        if verification.is_clean() {
+            println!("All {} locked files match.", lock.files.len());
+        }
+
+        // Only a hint: a teammate may not have the profile at all
+        if let Ok(locked) = locked_profile(db.connection(), lock) {
+            if locked.source != LockedSource::Current {
+                println!(
+                    "Note: profile '{}' has changed since the lock was written.",
+                    lock.profile
+                );
+            }
+        }
+    }
+
+    if verification.is_clean() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} of {} locked files do not match {LOCK_FILE}",
+            verification.mismatches.len(),
+            verification.lock.files.len()
+        )
+        .into())
+    }
+}
+
+/// Apply the profile revision a project's lock records
+pub fn sync(
+    db: &Database,
+    data_dir: &Path,
+    target: &Path,
+    dry_run: bool,
+    force: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Projects are registered by absolute path
+    let target_path = target.canonicalize()?;
+    let target = target_path.as_path();
+    let lock = read_lock(target)?;
+    let locked = locked_profile(db.connection(), &lock)?;
+    match locked.source {
+        LockedSource::Current => {}
+        LockedSource::Revision => println!(
+            "Profile '{}' has changed; using revision {} from its history.",
+            lock.profile,
+            short(lock.revision.as_deref().unwrap_or_default())
+        ),
+        LockedSource::Changed if force => println!(
+            "Profile '{}' has changed and the locked revision is not available; \
+             applying the current profile.",
+            lock.profile
+        ),
+        LockedSource::Changed => {
+            return Err(format!(
+                "Profile '{}' has changed since the lock was written and revision {} is not \
+                 available locally. Use --force to apply the current profile.",
+                lock.profile,
+                short(lock.revision.as_deref().unwrap_or("unknown"))
+            )
+            .into())
+        }
+    }
+
+    // An unregistered project is only recorded once the plan is applied
+    let projects = ProjectStore::new(db.connection());
+    let registered = projects.get_by_path(&target_path)?;
+    let project = registered
+        .clone()
+        .unwrap_or_else(|| Project::new(target_path.clone()));
+    let metadata = MetadataStore::new(db.connection()).get(project.id)?;
+    let context = TemplateContext::for_project(&project, metadata);
+    let resolved = &locked.resolved;
+    let mut plan = generate_plan_with_context(project.id, target, &resolved.profile, &context)?;
+
+    // The rewritten lock keeps the locked identity; only file hashes can move
+    let mut relocked = lock.clone();
+    relocked.files.clear();
+    relocked.record_plan(target, &plan)?;
+    relocked.add_to_plan(target, &mut plan)?;
+
+    let differing: Vec<_> = relocked
+        .files
+        .iter()
+        .filter(|(path, hash)| lock.files.get(*path) != Some(*hash))
+        .map(|(path, _)| path.as_str())
+        .chain(
+            lock.files
+                .keys()
+                .filter(|path| !relocked.files.contains_key(*path))
+                .map(String::as_str),
+        )
+        .collect();
+
+    if plan.is_empty() {
+        println!("Project already matches {LOCK_FILE}.");
+        return Ok(());
+    }
+
+    println!("{}", format_plan_terminal(&plan));
+    println!("Summary: {}", DiffSummary::from_plan(&plan).one_line());
+    if !differing.is_empty() {
+        println!("\nThese files will not match the lock exactly:");
+        for path in &differing {
+            println!("  {path}");
+        }
+    }
+
+    if dry_run {
+        println!("\nDry run - no changes made.");
+        return Ok(());
+    }
+
+    if registered.is_none() {
+        projects.create(&project)?;
+    }
+
+    let backups = BackupStore::new(db.connection());
+    let backup = crate::apply_with_backup(data_dir, &backups, &plan, target, &resolved.profile)?;
+    println!("\nApplied {} operations.", plan.operations.len());
+    println!("Backup created: {}", backup.id);
+    Ok(())
+}
+
+fn short(hash: &str) -> &str {
+    &hash[..hash.len().min(12)]
+}
@@ -0,0 +1,239 @@
+//! Profile repository CLI commands
+//!
+//! Handles: tars profile repo add/rm/ls/status/pull/push
+
+use clap::Subcommand;
+use uuid::Uuid;
+
+use tars_core::profile::{RepoProfileState, RepoSync, RepoWorkspace};
+use tars_core::storage::{Database, ProfileRepo, ProfileRepoStore, ProfileStore};
+
+/// Profile repository commands
+#[derive(Subcommand)]
+pub enum ProfileRepoCommands {
+    /// Register a git repository (local path or remote URL) as a profile source
+    Add {
+        /// Name for the repository
+        name: String,
+        /// Path or URL passed to `git clone`
+        url: String,
+        /// Branch to pull profiles from (defaults to the remote's default branch)
+        #[arg(short, long)]
+        branch: Option<String>,
+    },
+    /// Unregister a repository; profiles pulled from it are kept
+    Rm {
+        /// Repository name or ID
+        repo: String,
+    },
+    /// List registered repositories
+    Ls {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Fetch a repository and show upstream and local changes
+    Status {
+        /// Repository name or ID
+        repo: String,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Bring upstream changes into local profiles
+    Pull {
+        /// Repository name or ID
+        repo: String,
+        /// Take the upstream version of profiles edited on both sides
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Commit local profile edits on a branch and push it
+    Push {
+        /// Repository name or ID
+        repo: String,
+        /// Profiles to push (defaults to linked profiles with local edits)
+        profiles: Vec<String>,
+        /// Branch to push to (defaults to a new `tars/update-<timestamp>` branch)
+        #[arg(short, long)]
+        branch: Option<String>,
+        /// Commit message
+        #[arg(short, long)]
+        message: Option<String>,
+    },
+}
+
+/// Execute a profile repository command
+pub fn execute(db: &Database, cmd: ProfileRepoCommands) -> Result<(), Box<dyn std::error::Error>> {
+    let sync = RepoSync::new(db.connection(), RepoWorkspace::default_dirs()?);
+    let repos = ProfileRepoStore::new(db.connection());
+
+    match cmd {
+        ProfileRepoCommands::Add { name, url, branch } => {
+            println!("Cloning {url}...");
+            let repo = sync.add(&name, &url, branch.as_deref())?;
+            println!(
+                "Added profile repository '{}' tracking {}.",
+                repo.name, repo.branch
+            );
+            println!(
+                "Run `tars profile repo pull {}` to import its profiles.",
+                repo.name
+            );
+        }
+        ProfileRepoCommands::Rm { repo } => {
+            let repo = find_repo(&repos, &repo)?;
+            sync.remove(&repo)?;
+            println!("Removed profile repository '{}'.", repo.name);
+        }
+        ProfileRepoCommands::Ls { json } => {
+            let list = repos.list()?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&list)?);
+                return Ok(());
+            }
+            if list.is_empty() {
+                println!("No profile repositories. Add one with `tars profile repo add`.");
+                return Ok(());
+            }
+            println!("Profile repositories:");
+            for repo in list {
+                let synced = repo.last_synced_at.map_or_else(
+                    || "never pulled".to_string(),
+                    |t| format!("pulled {}", t.format("%Y-%m-%d %H:%M")),
+                );
+                println!("  {} - {} ({}, {synced})", repo.name, repo.url, repo.branch);
+            }
+        }
+        ProfileRepoCommands::Status { repo, json } => {
+            let repo = find_repo(&repos, &repo)?;
+            let status = sync.status(&repo)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&status)?);
+                return Ok(());
+            }
+
+            let upstream = status
+                .upstream
+                .as_deref()
+                .map_or("no commits yet", |c| &c[..c.len().min(12)]);
+            println!("{} ({} at {upstream})", repo.name, repo.branch);
+            if status.profiles.is_empty() {
+                println!("\nNo profiles in this repository.");
+            }
+            for profile in &status.profiles {
+                println!("  {:<20} {}", profile.name, describe_state(profile.state));
+            }
+        }
+        ProfileRepoCommands::Pull { repo, force } => {
+            let repo = find_repo(&repos, &repo)?;
+            let result = sync.pull(&repo, force)?;
+
+            for name in &result.created {
+                println!("Created profile '{name}'");
+            }
+            for name in &result.updated {
+                println!("Updated profile '{name}'");
+            }
+            for name in &result.unlinked {
+                println!("Profile '{name}' was removed upstream; kept locally");
+            }
+            for skipped in &result.skipped {
+                println!("Skipped '{}': {}", skipped.name, skipped.reason);
+            }
+            if result.created.is_empty() && result.updated.is_empty() {
+                println!("Local profiles are up to date with {}.", repo.name);
+            }
+            if result.skipped.iter().any(|s| s.reason.contains("both")) {
+                println!("Use --force to take the upstream version.");
+            }
+        }
+        ProfileRepoCommands::Push {
+            repo,
+            profiles,
+            branch,
+            message,
+        } => {
+            let repo = find_repo(&repos, &repo)?;
+            let store = ProfileStore::new(db.connection());
+
+            let to_push = if profiles.is_empty() {
+                let status = sync.status(&repo)?;
+                status
+                    .profiles
+                    .iter()
+                    .filter(|p| {
+                        matches!(
+                            p.state,
+                            RepoProfileState::LocalChanged | RepoProfileState::Diverged
+                        )
+                    })
+                    .filter_map(|p| p.profile_id)
+                    .map(|id| store.get(id))
+                    .filter_map(Result::transpose)
+                    .collect::<Result<Vec<_>, _>>()?
+            } else {
+                profiles
+                    .iter()
+                    .map(|p| crate::find_profile(&store, p))
+                    .collect::<Result<Vec<_>, _>>()?
+            };
+            if to_push.is_empty() {
+                println!("No local edits to push to {}.", repo.name);
+                return Ok(());
+            }
+
+            let branch = branch.unwrap_or_else(|| {
+                format!("tars/update-{}", chrono::Utc::now().format("%Y%m%d-%H%M%S"))
+            });
+            let names: Vec<_> = to_push.iter().map(|p| p.name.as_str()).collect();
+            let message =
+                message.unwrap_or_else(|| format!("Update profiles: {}", names.join(", ")));
+
+            let result = sync.push(&repo, &to_push, &branch, &message)?;
+            match result.commit {
+                Some(commit) => {
+                    println!(
+                        "Pushed {} to {} ({}) on branch {}.",
+                        names.join(", "),
+                        repo.name,
+                        &commit[..commit.len().min(12)],
+                        result.branch
+                    );
+                    if result.branch != repo.branch {
+                        println!("Merge it into {} to share the changes.", repo.branch);
+                    }
+                }
+                None => println!("{} already matches the local profiles.", repo.name),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Look up a repository by name or UUID
+fn find_repo(
+    repos: &ProfileRepoStore,
+    identifier: &str,
+) -> Result<ProfileRepo, Box<dyn std::error::Error>> {
+    let repo = if let Ok(id) = Uuid::parse_str(identifier) {
+        repos.get(id)?
+    } else {
+        repos.get_by_name(identifier)?
+    }
+    .ok_or_else(|| format!("Profile repository not found: {identifier}"))?;
+    Ok(repo)
+}
+
+fn describe_state(state: RepoProfileState) -> &'static str {
+    match state {
+        RepoProfileState::UpToDate => "up to date",
+        RepoProfileState::New => "new upstream",
+        RepoProfileState::UpstreamChanged => "changed upstream",
+        RepoProfileState::LocalChanged => "edited locally",
+        RepoProfileState::Diverged => "changed upstream and locally",
+        RepoProfileState::Unpublished => "pushed, not merged yet",
+        RepoProfileState::RemovedUpstream => "removed upstream",
+    }
+}
@@ -0,0 +1,245 @@
+//! Profile assignment rule CLI commands
+//!
+//! Handles: tars profile rule add/rm/ls/match
+
+use std::path::{Path, PathBuf};
+
+use clap::Subcommand;
+use uuid::Uuid;
+
+use tars_core::diff::display::{format_plan_terminal, DiffSummary};
+use tars_core::profile::{apply_proposal, propose_assignment, validate_rule};
+use tars_core::storage::{
+    AssignmentRule, AssignmentRuleStore, Database, ProfileStore, ProjectStore, RuleConditions,
+};
+use tars_core::Project;
+
+/// Profile assignment rule commands
+#[derive(Subcommand)]
+pub enum ProfileRuleCommands {
+    /// Add a rule assigning a profile to matching projects
+    Add {
+        /// Name for the rule
+        name: String,
+        /// Profile name or ID to assign
+        #[arg(short, long)]
+        profile: String,
+        /// Glob matched against the project path (e.g. "~/work/**")
+        #[arg(long = "path")]
+        paths: Vec<String>,
+        /// Language with code in the project (e.g. Rust)
+        #[arg(long = "language")]
+        languages: Vec<String>,
+        /// Dependency manifest in the project (e.g. Cargo.toml)
+        #[arg(long = "dep-file")]
+        dependency_files: Vec<String>,
+        /// Host of the project's git origin (e.g. github.com)
+        #[arg(long = "git-host")]
+        git_hosts: Vec<String>,
+        /// Owner or organization of the project's git origin
+        #[arg(long = "git-org")]
+        git_orgs: Vec<String>,
+        /// Platform from the project's metadata (e.g. web)
+        #[arg(long = "platform")]
+        platforms: Vec<String>,
+        /// Rules with a higher priority win when several match
+        #[arg(long, default_value_t = 0)]
+        priority: i64,
+        /// Apply the profile on a match instead of only proposing it
+        #[arg(long)]
+        auto_apply: bool,
+    },
+    /// Remove a rule
+    Rm {
+        /// Rule name or ID
+        rule: String,
+    },
+    /// List rules, highest priority first
+    Ls {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show the profile the rules propose for a project
+    Match {
+        /// Project path
+        #[arg(default_value = ".")]
+        target: String,
+        /// Apply the proposed profile (with a backup) and assign it
+        #[arg(long)]
+        apply: bool,
+    },
+}
+
+/// Execute a profile rule command
+pub fn execute(
+    db: &Database,
+    data_dir: &Path,
+    cmd: ProfileRuleCommands,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let rules = AssignmentRuleStore::new(db.connection());
+
+    match cmd {
+        ProfileRuleCommands::Add {
+            name,
+            profile,
+            paths,
+            languages,
+            dependency_files,
+            git_hosts,
+            git_orgs,
+            platforms,
+            priority,
+            auto_apply,
+        } => {
+            let profile = crate::find_profile(&ProfileStore::new(db.connection()), &profile)?;
+            let conditions = RuleConditions {
+                path_globs: paths,
+                languages,
+                dependency_files,
+                git_hosts,
+                git_orgs,
+                platforms,
+            };
+            let mut rule = AssignmentRule::new(name, profile.id, conditions);
+            rule.priority = priority;
+            rule.auto_apply = auto_apply;
+            validate_rule(&rule)?;
+            rules.create(&rule)?;
+            println!(
+                "Added rule '{}' {} profile '{}'.",
+                rule.name,
+                if auto_apply { "applying" } else { "proposing" },
+                profile.name
+            );
+        }
+        ProfileRuleCommands::Rm { rule } => {
+            let rule = find_rule(&rules, &rule)?;
+            rules.delete(rule.id)?;
+            println!("Removed rule '{}'.", rule.name);
+        }
+        ProfileRuleCommands::Ls { json } => {
+            let list = rules.list()?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&list)?);
+                return Ok(());
+            }
+            if list.is_empty() {
+                println!("No assignment rules. Add one with `tars profile rule add`.");
+                return Ok(());
+            }
+            let profiles = ProfileStore::new(db.connection());
+            println!("Assignment rules:");
+            for rule in list {
+                let profile = profiles
+                    .get(rule.profile_id)?
+                    .map_or_else(|| rule.profile_id.to_string(), |p| p.name);
+                let mode = match (rule.enabled, rule.auto_apply) {
+                    (false, _) => "disabled",
+                    (true, true) => "auto-apply",
+                    (true, false) => "propose",
+                };
+                println!(
+                    "  {} -> {profile} (priority {}, {mode}): {}",
+                    rule.name,
+                    rule.priority,
+                    describe_conditions(&rule.conditions)
+                );
+            }
+        }
+        ProfileRuleCommands::Match { target, apply } => {
+            let path = PathBuf::from(&target).canonicalize()?;
+            let projects = ProjectStore::new(db.connection());
+            let registered = projects.get_by_path(&path)?;
+            let mut project = registered
+                .clone()
+                .unwrap_or_else(|| Project::new(path.clone()));
+
+            let Some(proposal) = propose_assignment(db.connection(), &project)? else {
+                if project.assigned_profile_id.is_some() {
+                    println!("Project already has a profile assigned.");
+                } else {
+                    println!("No rule matches {}.", path.display());
+                }
+                return Ok(());
+            };
+
+            println!(
+                "Rule '{}' proposes profile '{}' ({}).",
+                proposal.rule.name,
+                proposal.profile_name,
+                proposal.reasons.join(", ")
+            );
+            if !proposal.plan.is_empty() {
+                println!("\n{}", format_plan_terminal(&proposal.plan));
+                println!(
+                    "Summary: {}",
+                    DiffSummary::from_plan(&proposal.plan).one_line()
+                );
+            }
+            if !apply {
+                println!("\nRun with --apply to apply and assign it.");
+                return Ok(());
+            }
+
+            if registered.is_none() {
+                projects.create(&project)?;
+            }
+            let applied = apply_proposal(
+                db.connection(),
+                &mut project,
+                &proposal,
+                &data_dir.join("backups"),
+                Some(Path::new("claude")),
+            )?;
+            println!("\nAssigned profile '{}'.", proposal.profile_name);
+            if let Some(backup) = applied.backup {
+                println!("Applied {} operations.", proposal.plan.operations.len());
+                println!("Backup created: {}", backup.id);
+            }
+            for (plugin, error) in &applied.assignment.plugin_errors {
+                println!("Failed to install plugin '{plugin}': {error}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Look up a rule by UUID or unique name
+fn find_rule(
+    rules: &AssignmentRuleStore,
+    identifier: &str,
+) -> Result<AssignmentRule, Box<dyn std::error::Error>> {
+    if let Ok(id) = Uuid::parse_str(identifier) {
+        return Ok(rules
+            .get(id)?
+            .ok_or_else(|| format!("Rule not found: {identifier}"))?);
+    }
+    let mut matches: Vec<_> = rules
+        .list()?
+        .into_iter()
+        .filter(|r| r.name == identifier)
+        .collect();
+    match matches.len() {
+        0 => Err(format!("Rule not found: {identifier}").into()),
+        1 => Ok(matches.remove(0)),
+        _ => Err(format!("Several rules are named '{identifier}'; use the rule ID").into()),
+    }
+}
+
+fn describe_conditions(conditions: &RuleConditions) -> String {
+    [
+        ("path", &conditions.path_globs),
+        ("language", &conditions.languages),
+        ("dep-file", &conditions.dependency_files),
+        ("git-host", &conditions.git_hosts),
+        ("git-org", &conditions.git_orgs),
+        ("platform", &conditions.platforms),
+    ]
+    .into_iter()
+    .filter(|(_, values)| !values.is_empty())
+    .map(|(label, values)| format!("{label}={}", values.join("|")))
+    .collect::<Vec<_>>()
+    .join(" ")
+}
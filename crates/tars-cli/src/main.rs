@@ -1,6 +1,6 @@
 //! TARS CLI - Command-line interface for TARS
 //!
-//! Provides `tars scan`, `tars profile`, `tars mcp`, and other commands.
+//! Provides `tars scan`, `tars profile`, `tars mcp`, `tars library`, `tars usage`, and other commands.
 
 #![allow(
     clippy::cast_precision_loss,
@@ -22,16 +22,27 @@ use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use tars_core::backup::restore::restore_from_backup;
 use tars_core::diff::display::{format_plan_terminal, DiffSummary};
-use tars_core::diff::plan::generate_plan;
+use tars_core::diff::plan::generate_plan_with_context;
 use tars_core::export::export_as_plugin;
 use tars_core::profile::snapshot::snapshot_from_project;
-use tars_core::storage::{BackupStore, Database, ProfileStore, ProjectStore};
+use tars_core::profile::{
+    compare_stored_profiles, diff_revisions, find_revision, lock_plan, preview_lock_plan,
+    resolve_profile, revert_profile, FileChangeKind, TemplateContext, LOCK_FILE,
+};
+use tars_core::storage::{
+    BackupStore, Database, MetadataStore, ProfileRevisionStore, ProfileStore, ProjectStore,
+};
+use tars_core::usage::{compute_cost, load_anthropic_prices, load_stats_cache};
 use tars_core::{Backup, Project};
 use tars_scanner::output::{json::to_json, markdown::to_markdown};
 use tars_scanner::{CacheCleanupReport, Scanner};
 use uuid::Uuid;
 
+use commands::library::LibraryCommands;
 use commands::mcp::McpCommands;
+use commands::profile_batch::ProfileBatchCommands;
+use commands::profile_repo::ProfileRepoCommands;
+use commands::profile_rule::ProfileRuleCommands;
 
 #[derive(Parser)]
 #[command(name = "tars")]
@@ -75,11 +86,25 @@ enum Commands {
         #[arg(short, long, global = true)]
         project: Option<PathBuf>,
     },
+    /// Manage the cross-agent skill library
+    Library {
+        #[command(subcommand)]
+        action: LibraryCommands,
+    },
     /// Manage plugin cache
     Cache {
         #[command(subcommand)]
         action: CacheCommands,
     },
+    /// Show Claude Code usage from ~/.claude/stats-cache.json
+    Usage {
+        /// Estimate spend using cached Anthropic model prices
+        #[arg(long)]
+        cost: bool,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 #[derive(ValueEnum, Clone, Copy, Debug)]
@@ -113,6 +138,33 @@ enum ProfileCommands {
         /// Preview changes without applying
         #[arg(long)]
         dry_run: bool,
+        /// Write `.claude/tars.lock` (kept up to date once the project has one)
+        #[arg(long)]
+        lock: bool,
+    },
+    /// Check that a project's files match its `.claude/tars.lock`
+    Verify {
+        /// Project path
+        #[arg(default_value = ".")]
+        target: String,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Reproduce a project's profile files from its `.claude/tars.lock`
+    Sync {
+        /// Apply the profile and revision recorded in the lock file
+        #[arg(long, required = true)]
+        from_lock: bool,
+        /// Project path
+        #[arg(default_value = ".")]
+        target: String,
+        /// Preview changes without applying
+        #[arg(long)]
+        dry_run: bool,
+        /// Apply the current profile even if it changed since the lock was written
+        #[arg(short, long)]
+        force: bool,
     },
     /// Rollback to a previous state
     Rollback {
@@ -126,6 +178,57 @@ enum ProfileCommands {
         /// Profile name or ID
         profile: String,
     },
+    /// Set the profiles a profile extends (no parents clears inheritance)
+    Extends {
+        /// Profile name or ID
+        profile: String,
+        /// Parent profile names or IDs, base first
+        parents: Vec<String>,
+    },
+    /// Show a profile with its parents layered in, and where each tool came from
+    Resolve {
+        /// Profile name or ID
+        profile: String,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// List recorded revisions of a profile, newest first
+    History {
+        /// Profile name or ID
+        profile: String,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show what changed between two profile revisions
+    Diff {
+        /// Older revision hash (or unique prefix)
+        rev_a: String,
+        /// Newer revision hash (or unique prefix)
+        rev_b: String,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show the differences between two profiles
+    Compare {
+        /// First profile name or ID
+        a: String,
+        /// Second profile name or ID
+        b: String,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Restore a profile and its stored files to an earlier revision
+    Revert {
+        /// Revision hash (or unique prefix)
+        rev: String,
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        force: bool,
+    },
     /// Delete a profile
     Delete {
         /// Profile name or ID
@@ -148,6 +251,21 @@ enum ProfileCommands {
         #[arg(short, long, default_value = "1.0.0")]
         version: String,
     },
+    /// Share profiles through git repositories
+    Repo {
+        #[command(subcommand)]
+        action: ProfileRepoCommands,
+    },
+    /// Apply a profile to all of its projects at once, or roll a batch back
+    Batch {
+        #[command(subcommand)]
+        action: ProfileBatchCommands,
+    },
+    /// Assign profiles to projects automatically by rule
+    Rule {
+        #[command(subcommand)]
+        action: ProfileRuleCommands,
+    },
     /// List all backups
     Backups {
         /// Filter by project path
@@ -202,12 +320,24 @@ fn main() {
                 std::process::exit(1);
             }
         }
+        Commands::Library { action } => {
+            if let Err(e) = commands::library::execute(action) {
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+        }
         Commands::Cache { action } => {
             if let Err(e) = run_cache_command(action) {
                 eprintln!("Error: {e}");
                 std::process::exit(1);
             }
         }
+        Commands::Usage { cost, json } => {
+            if let Err(e) = run_usage_command(cost, json) {
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+        }
     }
 }
 
@@ -271,6 +401,36 @@ fn find_profile(
     Ok(profile)
 }
 
+/// Back up the files `plan` touches, apply it and store the backup
+fn apply_with_backup(
+    data_dir: &Path,
+    backups: &BackupStore,
+    plan: &tars_core::diff::DiffPlan,
+    target_path: &Path,
+    profile: &tars_core::Profile,
+) -> Result<Backup, Box<dyn std::error::Error>> {
+    // Create backup and apply
+    let backup_dir = data_dir.join("backups");
+    std::fs::create_dir_all(&backup_dir)?;
+
+    let archive_path = backup_dir.join(format!(
+        "backup-{}.json",
+        chrono::Utc::now().format("%Y%m%d-%H%M%S")
+    ));
+    let mut backup = Backup::new(plan.project_id, archive_path.clone())
+        .with_profile(profile.id)
+        .with_description(format!("Before applying profile '{}'", profile.name));
+
+    tars_core::apply::apply_operations(plan, target_path, &mut backup)?;
+
+    // Save backup
+    let backup_json = serde_json::to_string_pretty(&backup)?;
+    std::fs::write(&archive_path, backup_json)?;
+    backups.create(&backup)?;
+
+    Ok(backup)
+}
+
 fn run_profile_command(action: ProfileCommands) -> Result<(), Box<dyn std::error::Error>> {
     let data_dir = get_data_dir()?;
     std::fs::create_dir_all(&data_dir)?;
@@ -280,6 +440,7 @@ fn run_profile_command(action: ProfileCommands) -> Result<(), Box<dyn std::error
     let profiles = ProfileStore::new(db.connection());
     let projects = ProjectStore::new(db.connection());
     let backups = BackupStore::new(db.connection());
+    let revisions = ProfileRevisionStore::new(db.connection());
 
     match action {
         ProfileCommands::List => {
@@ -330,6 +491,7 @@ fn run_profile_command(action: ProfileCommands) -> Result<(), Box<dyn std::error
             profile,
             target,
             dry_run,
+            lock,
         } => {
             let target_path = PathBuf::from(&target);
 
@@ -351,8 +513,19 @@ fn run_profile_command(action: ProfileCommands) -> Result<(), Box<dyn std::error
                 p
             };
 
-            // Generate diff plan
-            let plan = generate_plan(proj.id, &target_path, &prof)?;
+            // Generate diff plan from the profile with its parents layered in
+            let resolved = resolve_profile(&profiles, &prof)?;
+            let metadata = MetadataStore::new(db.connection()).get(proj.id)?;
+            let context = TemplateContext::for_project(&proj, metadata);
+            let mut plan =
+                generate_plan_with_context(proj.id, &target_path, &resolved.profile, &context)?;
+            if lock || target_path.join(LOCK_FILE).exists() {
+                if dry_run {
+                    preview_lock_plan(db.connection(), &target_path, &resolved, &mut plan)?;
+                } else {
+                    lock_plan(db.connection(), &target_path, &resolved, &mut plan)?;
+                }
+            }
 
             if plan.is_empty() {
                 println!("No changes needed - project already matches profile.");
@@ -369,28 +542,22 @@ fn run_profile_command(action: ProfileCommands) -> Result<(), Box<dyn std::error
                 return Ok(());
             }
 
-            // Create backup and apply
-            let backup_dir = data_dir.join("backups");
-            std::fs::create_dir_all(&backup_dir)?;
-
-            let archive_path = backup_dir.join(format!(
-                "backup-{}.json",
-                chrono::Utc::now().format("%Y%m%d-%H%M%S")
-            ));
-            let mut backup = Backup::new(proj.id, archive_path.clone())
-                .with_profile(prof.id)
-                .with_description(format!("Before applying profile '{}'", prof.name));
-
-            tars_core::apply::apply_operations(&plan, &target_path, &mut backup)?;
-
-            // Save backup
-            let backup_json = serde_json::to_string_pretty(&backup)?;
-            std::fs::write(&archive_path, backup_json)?;
-            backups.create(&backup)?;
+            let backup = apply_with_backup(&data_dir, &backups, &plan, &target_path, &prof)?;
 
             println!("\nApplied {} operations.", plan.operations.len());
             println!("Backup created: {}", backup.id);
         }
+        ProfileCommands::Verify { target, json } => {
+            commands::profile_lock::verify(&db, Path::new(&target), json)?;
+        }
+        ProfileCommands::Sync {
+            from_lock: _,
+            target,
+            dry_run,
+            force,
+        } => {
+            commands::profile_lock::sync(&db, &data_dir, Path::new(&target), dry_run, force)?;
+        }
         ProfileCommands::Rollback { backup_id, target } => {
             let target_path = PathBuf::from(&target);
 
@@ -415,18 +582,259 @@ fn run_profile_command(action: ProfileCommands) -> Result<(), Box<dyn std::error
             }
             println!("Created: {}", prof.created_at);
             println!("Updated: {}", prof.updated_at);
+            if !prof.extends.is_empty() {
+                let parents = prof
+                    .extends
+                    .iter()
+                    .map(|id| {
+                        profiles
+                            .get(*id)
+                            .ok()
+                            .flatten()
+                            .map_or_else(|| format!("{id} (missing)"), |p| p.name)
+                    })
+                    .collect::<Vec<_>>();
+                println!("Extends: {}", parents.join(", "));
+            }
             println!("\nRepo Overlays:");
             println!("  Skills: {}", prof.repo_overlays.skills.len());
             println!("  Commands: {}", prof.repo_overlays.commands.len());
             println!("  Agents: {}", prof.repo_overlays.agents.len());
+            println!("  MCP servers: {}", prof.repo_overlays.mcp_servers.len());
+            println!(
+                "  Hook events: {}",
+                prof.repo_overlays
+                    .hooks
+                    .as_ref()
+                    .map_or(0, |h| h.events.len())
+            );
+            println!(
+                "  Permissions: {}",
+                prof.repo_overlays.permissions.is_some()
+            );
             println!("  CLAUDE.md: {}", prof.repo_overlays.claude_md.is_some());
             println!("\nUser Overlays:");
             println!("  Skills: {}", prof.user_overlays.skills.len());
             println!("  Commands: {}", prof.user_overlays.commands.len());
         }
+        ProfileCommands::Extends { profile, parents } => {
+            let mut prof = find_profile(&profiles, &profile)?;
+            let mut parent_ids = Vec::new();
+            for parent in &parents {
+                parent_ids.push(find_profile(&profiles, parent)?.id);
+            }
+
+            prof.extends = parent_ids;
+            // Resolving the unsaved profile rejects cycles before anything is stored
+            let resolved = resolve_profile(&profiles, &prof)?;
+            prof.updated_at = chrono::Utc::now();
+            profiles.update(&prof)?;
+
+            if prof.extends.is_empty() {
+                println!("Profile '{}' no longer extends other profiles.", prof.name);
+            } else {
+                let layers: Vec<_> = resolved.layers.iter().map(|l| l.name.as_str()).collect();
+                println!(
+                    "Profile '{}' now resolves as: {}",
+                    prof.name,
+                    layers.join(" -> ")
+                );
+            }
+        }
+        ProfileCommands::Resolve { profile, json } => {
+            let prof = find_profile(&profiles, &profile)?;
+            let resolved = resolve_profile(&profiles, &prof)?;
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&resolved)?);
+                return Ok(());
+            }
+
+            let layers: Vec<_> = resolved.layers.iter().map(|l| l.name.as_str()).collect();
+            println!("Profile: {}", prof.name);
+            println!("Layers: {}", layers.join(" -> "));
+            if resolved.provenance.is_empty() {
+                println!("\nNo tools or overlays.");
+                return Ok(());
+            }
+            println!();
+            for origin in &resolved.provenance {
+                let kind = origin
+                    .tool_type
+                    .map_or_else(|| origin.kind.to_string(), |t| format!("tool:{t}"));
+                let earlier: Vec<_> = origin.earlier.iter().map(|l| l.name.as_str()).collect();
+                let over = if earlier.is_empty() {
+                    String::new()
+                } else {
+                    format!(" (over {})", earlier.join(", "))
+                };
+                println!(
+                    "  {kind:<14} {:<30} from {}{over}",
+                    origin.name, origin.source.name
+                );
+            }
+        }
+        ProfileCommands::History { profile, json } => {
+            let prof = find_profile(&profiles, &profile)?;
+            // Pick up stored file edits made without a profile update
+            profiles.record_revision(&prof)?;
+            let history = revisions.list(prof.id)?;
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&history)?);
+                return Ok(());
+            }
+
+            println!("History of {}:", prof.name);
+            for (index, revision) in history.iter().enumerate() {
+                let marker = if index == 0 { " (current)" } else { "" };
+                println!(
+                    "  {}  {}  {} files{marker}",
+                    revision.short_hash(),
+                    revision.created_at.format("%Y-%m-%d %H:%M:%S"),
+                    revision.file_count
+                );
+            }
+        }
+        ProfileCommands::Diff { rev_a, rev_b, json } => {
+            let from = revisions.snapshot(&find_revision(&revisions, &rev_a)?)?;
+            let to = revisions.snapshot(&find_revision(&revisions, &rev_b)?)?;
+            let diff = diff_revisions(&from, &to);
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&diff)?);
+                return Ok(());
+            }
+
+            println!(
+                "Diff {} -> {}",
+                diff.from.short_hash(),
+                diff.to.short_hash()
+            );
+            if diff.is_empty() {
+                println!("\nNo differences.");
+                return Ok(());
+            }
+            if let Some(profile_diff) = &diff.profile {
+                println!("\nprofile.json:");
+                print!("{profile_diff}");
+            }
+            for file in &diff.files {
+                let kind = match file.kind {
+                    FileChangeKind::Added => "added",
+                    FileChangeKind::Removed => "removed",
+                    FileChangeKind::Modified => "modified",
+                };
+                println!("\n{} ({kind})", file.path);
+                if let Some(file_diff) = &file.diff {
+                    print!("{file_diff}");
+                }
+            }
+        }
+        ProfileCommands::Compare { a, b, json } => {
+            let prof_a = find_profile(&profiles, &a)?;
+            let prof_b = find_profile(&profiles, &b)?;
+            let comparison = compare_stored_profiles(&prof_a, &prof_b)?;
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&comparison)?);
+                return Ok(());
+            }
+
+            println!("Compare '{}' -> '{}'", comparison.a, comparison.b);
+            if comparison.is_identical() {
+                println!("\nNo differences.");
+                return Ok(());
+            }
+            if !comparison.only_in_a.is_empty() {
+                println!("\nOnly in '{}':", comparison.a);
+                for tool in &comparison.only_in_a {
+                    println!("  {} {}", tool.kind, tool.name);
+                }
+            }
+            if !comparison.only_in_b.is_empty() {
+                println!("\nOnly in '{}':", comparison.b);
+                for tool in &comparison.only_in_b {
+                    println!("  {} {}", tool.kind, tool.name);
+                }
+            }
+            for tool in &comparison.differing {
+                println!("\n{} {} (differs)", tool.kind, tool.name);
+                print!("{}", tool.diff);
+            }
+            for (title, settings) in [
+                ("Plugins", &comparison.plugins),
+                ("Adapters", &comparison.adapters),
+                ("Overlays", &comparison.overlays),
+            ] {
+                if settings.is_empty() {
+                    continue;
+                }
+                println!("\n{title}:");
+                for setting in settings {
+                    println!(
+                        "  {}: {} -> {}",
+                        setting.setting,
+                        setting.a.as_deref().unwrap_or("(none)"),
+                        setting.b.as_deref().unwrap_or("(none)")
+                    );
+                    if let Some(diff) = &setting.diff {
+                        print!("{diff}");
+                    }
+                }
+            }
+        }
+        ProfileCommands::Revert { rev, force } => {
+            let target = find_revision(&revisions, &rev)?;
+            let prof = profiles
+                .get(target.profile_id)?
+                .ok_or_else(|| format!("Profile not found: {}", target.profile_id))?;
+
+            if !force {
+                print!(
+                    "Revert profile '{}' to revision {} from {}? [y/N] ",
+                    prof.name,
+                    target.short_hash(),
+                    target.created_at.format("%Y-%m-%d %H:%M:%S")
+                );
+                io::stdout().flush()?;
+                let mut input = String::new();
+                io::stdin().read_line(&mut input)?;
+                if !input.trim().eq_ignore_ascii_case("y") {
+                    println!("Cancelled.");
+                    return Ok(());
+                }
+            }
+
+            let result = revert_profile(db.connection(), &target.hash)?;
+            if result.recorded.is_some() {
+                println!(
+                    "Reverted profile '{}' to revision {}.",
+                    result.profile.name,
+                    result.target.short_hash()
+                );
+            } else {
+                println!(
+                    "Profile '{}' already matches revision {}.",
+                    result.profile.name,
+                    result.target.short_hash()
+                );
+            }
+        }
         ProfileCommands::Delete { profile, force } => {
             let prof = find_profile(&profiles, &profile)?;
 
+            let children = profiles.list_extending(prof.id)?;
+            if !children.is_empty() {
+                let names: Vec<_> = children.iter().map(|c| c.name.as_str()).collect();
+                return Err(format!(
+                    "Profile '{}' is extended by: {}. Update their parents first.",
+                    prof.name,
+                    names.join(", ")
+                )
+                .into());
+            }
+
             if !force {
                 print!("Delete profile '{}' (ID: {})? [y/N] ", prof.name, prof.id);
                 io::stdout().flush()?;
@@ -471,10 +879,18 @@ fn run_profile_command(action: ProfileCommands) -> Result<(), Box<dyn std::error
                 prof.name, plugin_name, version
             );
 
-            export_as_plugin(&prof, &output_dir, &plugin_name, &version)?;
+            let resolved = resolve_profile(&profiles, &prof)?;
+            export_as_plugin(&resolved, &output_dir, &plugin_name, &version)?;
             let output_path = output_dir.join(format!("{plugin_name}-{version}"));
             println!("Created plugin: {}", output_path.display());
         }
+        ProfileCommands::Repo { action } => commands::profile_repo::execute(&db, action)?,
+        ProfileCommands::Batch { action } => {
+            commands::profile_batch::execute(&db, &data_dir, action)?;
+        }
+        ProfileCommands::Rule { action } => {
+            commands::profile_rule::execute(&db, &data_dir, action)?;
+        }
         ProfileCommands::Backups { project } => {
             let backup_list = backups.list_all()?;
             let filtered: Vec<_> = if let Some(proj_path) = project {
@@ -602,6 +1018,93 @@ fn run_cache_command(action: CacheCommands) -> Result<(), Box<dyn std::error::Er
     Ok(())
 }
 
+fn run_usage_command(cost: bool, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let home = get_home_dir().ok_or("Cannot find home directory")?;
+    let stats = load_stats_cache(&home)?;
+
+    if !cost {
+        if json {
+            println!("{}", serde_json::to_string_pretty(&stats)?);
+            return Ok(());
+        }
+        println!("Claude Code Usage");
+        println!("=================\n");
+        println!("Sessions: {}", stats.total_sessions);
+        println!("Messages: {}", stats.total_messages);
+        if let Some(date) = &stats.last_computed_date {
+            println!("Computed: {date}");
+        }
+        let mut models: Vec<_> = stats.model_usage.iter().collect();
+        models.sort_by(|a, b| a.0.cmp(b.0));
+        if !models.is_empty() {
+            println!("\nTokens by model:");
+            for (model, u) in models {
+                println!(
+                    "  {model}: {} in, {} out, {} cache read, {} cache write",
+                    u.input_tokens,
+                    u.output_tokens,
+                    u.cache_read_input_tokens,
+                    u.cache_creation_input_tokens
+                );
+            }
+        }
+        println!("\nRun 'tars usage --cost' for a spend estimate.");
+        return Ok(());
+    }
+
+    let data_dir = get_data_dir()?;
+    std::fs::create_dir_all(&data_dir)?;
+    let db = Database::open(&data_dir.join("tars.db"))?;
+    let prices = load_anthropic_prices(db.connection())?;
+    let report = compute_cost(&stats, &prices);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!("Claude Code Cost Estimate");
+    println!("=========================\n");
+    println!("Total: ${:.2}", report.total_cost);
+
+    if !report.models.is_empty() {
+        println!("\nBy model:");
+        for m in &report.models {
+            let note = match m.price {
+                Some(p) if p.is_overridden => " (price override)",
+                Some(_) => "",
+                None => " (no price)",
+            };
+            println!(
+                "  {}: ${:.2} (input ${:.2}, output ${:.2}, cache read ${:.2}, cache write ${:.2}){note}",
+                m.model_id,
+                m.total_cost,
+                m.input_cost,
+                m.output_cost,
+                m.cache_read_cost,
+                m.cache_creation_cost
+            );
+        }
+    }
+
+    if !report.daily.is_empty() {
+        println!("\nBy day:");
+        for d in &report.daily {
+            println!("  {}: ${:.2}", d.date, d.total_cost);
+        }
+    }
+
+    if !report.unpriced_models.is_empty() {
+        println!(
+            "\nNo cached price for: {}",
+            report.unpriced_models.join(", ")
+        );
+        println!("Add an Anthropic key and refresh models and pricing in the desktop app.");
+    }
+
+    Ok(())
+}
+
 /// Format bytes as human-readable string
 fn format_entry_size(bytes: u64) -> String {
     const KB: u64 = 1024;
@@ -455,3 +455,225 @@ fn test_scan_with_include_managed() {
         .assert()
         .success();
 }
+
+#[test]
+fn test_mcp_refresh_dry_run_docker() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let project_dir = TempDir::new().expect("Failed to create project dir");
+
+    fs::write(
+        project_dir.path().join(".mcp.json"),
+        r#"{"mcpServers": {"github": {"command": "docker", "args": ["run", "-i", "--rm", "-e", "GITHUB_TOKEN", "ghcr.io/github/github-mcp-server"]}}}"#,
+    )
+    .expect("Failed to write .mcp.json");
+
+    let mut cmd = tars_cmd();
+    set_home_env(&mut cmd, temp_dir.path())
+        .arg("mcp")
+        .arg("--project")
+        .arg(project_dir.path())
+        .arg("refresh")
+        .arg("github")
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "docker pull ghcr.io/github/github-mcp-server",
+        ));
+}
+
+#[test]
+fn test_mcp_refresh_unknown_server_type() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let project_dir = TempDir::new().expect("Failed to create project dir");
+
+    fs::write(
+        project_dir.path().join(".mcp.json"),
+        r#"{"mcpServers": {"remote": {"type": "http", "url": "https://example.com/mcp"}}}"#,
+    )
+    .expect("Failed to write .mcp.json");
+
+    let mut cmd = tars_cmd();
+    set_home_env(&mut cmd, temp_dir.path())
+        .arg("mcp")
+        .arg("--project")
+        .arg(project_dir.path())
+        .arg("refresh")
+        .arg("remote")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Cannot determine how to refresh"));
+}
+
+#[test]
+fn test_mcp_add_from_template() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let project_dir = TempDir::new().expect("Failed to create project dir");
+
+    let mut cmd = tars_cmd();
+    set_home_env(&mut cmd, temp_dir.path())
+        .arg("mcp")
+        .arg("--project")
+        .arg(project_dir.path())
+        .arg("add")
+        .arg("--from-template")
+        .arg("filesystem")
+        .arg("--param")
+        .arg("path=/srv/docs")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Added MCP server 'filesystem'"));
+
+    let mcp_json =
+        fs::read_to_string(project_dir.path().join(".mcp.json")).expect("Failed to read .mcp.json");
+    let value: serde_json::Value = serde_json::from_str(&mcp_json).expect("Invalid JSON");
+    let server = &value["mcpServers"]["filesystem"];
+    assert_eq!(server["command"], "npx");
+    assert_eq!(server["args"][2], "/srv/docs");
+}
+
+#[test]
+fn test_mcp_add_from_user_template_missing_param() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let project_dir = TempDir::new().expect("Failed to create project dir");
+
+    let catalog_dir = temp_dir.path().join(".tars").join("mcp-catalog");
+    fs::create_dir_all(&catalog_dir).expect("Failed to create catalog dir");
+    fs::write(
+        catalog_dir.join("internal.toml"),
+        "id = \"internal\"\nname = \"Internal\"\ntransports = [\"stdio\"]\ncommand = \"internal-mcp\"\n\n[[params]]\nname = \"INTERNAL_TOKEN\"\nkind = \"env\"\n",
+    )
+    .expect("Failed to write template");
+
+    let mut cmd = tars_cmd();
+    set_home_env(&mut cmd, temp_dir.path())
+        .arg("mcp")
+        .arg("catalog")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("internal - Internal"));
+
+    let mut cmd = tars_cmd();
+    set_home_env(&mut cmd, temp_dir.path())
+        .arg("mcp")
+        .arg("--project")
+        .arg(project_dir.path())
+        .arg("add")
+        .arg("--from-template")
+        .arg("internal")
+        .arg("--dry-run")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("INTERNAL_TOKEN"));
+}
+
+const USAGE_STATS: &str = r#"{
+    "totalSessions": 3,
+    "totalMessages": 40,
+    "lastComputedDate": "2026-05-02",
+    "dailyModelTokens": [
+        {"date": "2026-05-01", "tokensByModel": {"claude-sonnet-4-5-20250929": 1000000}}
+    ],
+    "modelUsage": {
+        "claude-sonnet-4-5-20250929": {
+            "inputTokens": 500000,
+            "outputTokens": 500000,
+            "cacheReadInputTokens": 0,
+            "cacheCreationInputTokens": 0
+        }
+    }
+}"#;
+
+fn write_usage_stats(home: &Path) {
+    fs::create_dir_all(home.join(".claude")).expect("Failed to create .claude");
+    fs::write(home.join(".claude/stats-cache.json"), USAGE_STATS)
+        .expect("Failed to write stats cache");
+}
+
+#[test]
+fn test_usage_missing_stats_fails() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+
+    let mut cmd = tars_cmd();
+    set_home_env(&mut cmd, temp_dir.path())
+        .arg("usage")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("stats file not found"));
+}
+
+#[test]
+fn test_usage_summary() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    write_usage_stats(temp_dir.path());
+
+    let mut cmd = tars_cmd();
+    set_home_env(&mut cmd, temp_dir.path())
+        .arg("usage")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Sessions: 3"))
+        .stdout(predicate::str::contains("claude-sonnet-4-5-20250929"));
+}
+
+#[test]
+fn test_usage_cost_uses_cached_prices() {
+    use tars_core::storage::{Database, ModelCache, ModelRow};
+
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    write_usage_stats(temp_dir.path());
+
+    let data_dir = temp_dir.path().join(".tars");
+    fs::create_dir_all(&data_dir).expect("Failed to create data dir");
+    let db = Database::open(&data_dir.join("tars.db")).expect("Failed to open db");
+    ModelCache::new(db.connection())
+        .upsert_all(
+            "anthropic",
+            &[ModelRow {
+                model_id: "claude-sonnet-4-5-20250929".into(),
+                display_name: None,
+                context_window: None,
+                input_price: Some(3.0),
+                output_price: Some(15.0),
+            }],
+            chrono::Utc::now(),
+        )
+        .expect("Failed to seed prices");
+    drop(db);
+
+    let mut cmd = tars_cmd();
+    set_home_env(&mut cmd, temp_dir.path())
+        .arg("usage")
+        .arg("--cost")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Total: $9.00"))
+        .stdout(predicate::str::contains("2026-05-01: $9.00"));
+
+    let mut cmd = tars_cmd();
+    let output = set_home_env(&mut cmd, temp_dir.path())
+        .args(["usage", "--cost", "--json"])
+        .output()
+        .expect("Failed to run");
+    assert!(output.status.success());
+    let report: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("valid JSON output");
+    assert_eq!(report["totalCost"], 9.0);
+    assert_eq!(report["models"][0]["modelId"], "claude-sonnet-4-5-20250929");
+}
+
+#[test]
+fn test_usage_cost_reports_unpriced_models() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    write_usage_stats(temp_dir.path());
+
+    let mut cmd = tars_cmd();
+    set_home_env(&mut cmd, temp_dir.path())
+        .args(["usage", "--cost"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Total: $0.00"))
+        .stdout(predicate::str::contains(
+            "No cached price for: claude-sonnet-4-5-20250929",
+        ));
+}
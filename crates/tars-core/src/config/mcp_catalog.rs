@@ -0,0 +1,743 @@
+//! MCP server catalog: reusable templates for adding servers
+//!
+//! A template declares everything needed to add an MCP server — command,
+//! args, URL, supported transports — plus the parameters a user has to
+//! supply. Parameters are either environment variables or values spliced
+//! into args/URL via `{{name}}` placeholders. An env parameter may name a
+//! provider id (as stored in the API key vault) so its value can be filled
+//! from a stored key instead of being typed in.
+//!
+//! Templates come from a bundled default catalog plus any `.json` / `.toml`
+//! files under `~/.tars/mcp-catalog`. A user file may hold a single template
+//! or a `templates` list; user templates replace bundled ones with the same
+//! id.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::item::validate_name;
+use super::mcp::{McpServerConfig, McpTransport};
+use crate::storage::db::DatabaseError;
+use crate::storage::ApiKeyStore;
+
+/// The catalog shipped with TARS
+const BUNDLED_CATALOG: &str = include_str!("mcp_catalog.json");
+
+/// Directory holding user catalog files
+pub fn mcp_catalog_dir(home: &Path) -> PathBuf {
+    home.join(".tars").join("mcp-catalog")
+}
+
+#[derive(Debug, Error)]
+pub enum McpCatalogError {
+    #[error("failed to parse catalog file {path}: {message}")]
+    Parse { path: PathBuf, message: String },
+    #[error("invalid template '{id}': {message}")]
+    InvalidTemplate { id: String, message: String },
+    #[error("template '{id}' links parameter '{param}' to unknown provider '{provider}'")]
+    UnknownProvider {
+        id: String,
+        param: String,
+        provider: String,
+    },
+    #[error("template '{0}' not found in the MCP catalog")]
+    NotFound(String),
+    #[error("template '{id}' does not support the {transport} transport")]
+    UnsupportedTransport { id: String, transport: String },
+    #[error("missing required parameters for template '{id}': {}", .names.join(", "))]
+    MissingParams { id: String, names: Vec<String> },
+    #[error("unknown parameters for template '{id}': {}", .names.join(", "))]
+    UnknownParams { id: String, names: Vec<String> },
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Where a template parameter ends up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParamKind {
+    /// Set as an environment variable named after the parameter
+    Env,
+    /// Substituted into `args` / `url` / `command` via `{{name}}`
+    Arg,
+}
+
+/// A value a template needs from the user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateParam {
+    pub name: String,
+    pub kind: ParamKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default = "default_true")]
+    pub required: bool,
+    /// Mask the value when displaying it
+    #[serde(default)]
+    pub secret: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    /// Provider id whose stored API key fills this parameter
+    /// (e.g. `brave-search`; see `tars_providers::ProviderId`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// A reusable MCP server definition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpTemplate {
+    pub id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub docs_url: Option<String>,
+    /// Supported transports; the first one is the default
+    pub transports: Vec<McpTransport>,
+    /// Command for stdio transport
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// Fixed environment variables
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env: HashMap<String, String>,
+    /// URL for http/sse transport
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub params: Vec<TemplateParam>,
+}
+
+/// Result of filling in a template
+#[derive(Debug, Clone)]
+pub struct TemplateInstance {
+    pub config: McpServerConfig,
+    /// Parameters filled from a stored provider key
+    pub from_key_vault: Vec<String>,
+    /// Parameters that fell back to their default value
+    pub from_defaults: Vec<String>,
+}
+
+impl McpTemplate {
+    /// The transport used when none is requested
+    pub fn default_transport(&self) -> Option<&McpTransport> {
+        self.transports.first()
+    }
+
+    /// Check that the template is internally consistent
+    pub fn validate(&self) -> Result<(), McpCatalogError> {
+        let invalid = |message: String| McpCatalogError::InvalidTemplate {
+            id: self.id.clone(),
+            message,
+        };
+
+        validate_name(&self.id).map_err(|e| invalid(e.to_string()))?;
+        if self.transports.is_empty() {
+            return Err(invalid("no transports declared".into()));
+        }
+        if self.transports.contains(&McpTransport::Stdio) && self.command.is_none() {
+            return Err(invalid("stdio transport requires 'command'".into()));
+        }
+        if self
+            .transports
+            .iter()
+            .any(|t| matches!(t, McpTransport::Http | McpTransport::Sse))
+            && self.url.is_none()
+        {
+            return Err(invalid("http/sse transport requires 'url'".into()));
+        }
+
+        let mut seen = BTreeSet::new();
+        for param in &self.params {
+            if param.name.is_empty() || !seen.insert(param.name.as_str()) {
+                return Err(invalid(format!(
+                    "duplicate or empty parameter name '{}'",
+                    param.name
+                )));
+            }
+        }
+
+        let referenced = self.placeholders();
+        for name in &referenced {
+            match self.param(name) {
+                Some(p) if p.kind == ParamKind::Arg => {}
+                Some(_) => {
+                    return Err(invalid(format!(
+                        "placeholder {{{{{name}}}}} refers to an env parameter"
+                    )))
+                }
+                None => return Err(invalid(format!("unknown placeholder {{{{{name}}}}}"))),
+            }
+        }
+        if let Some(p) = self
+            .params
+            .iter()
+            .find(|p| p.kind == ParamKind::Arg && !referenced.contains(&p.name))
+        {
+            return Err(invalid(format!(
+                "arg parameter '{}' is not used in command, args or url",
+                p.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Look up a parameter by name
+    pub fn param(&self, name: &str) -> Option<&TemplateParam> {
+        self.params.iter().find(|p| p.name == name)
+    }
+
+    /// Names of all `{{placeholders}}` used in command, args, url and env
+    fn placeholders(&self) -> BTreeSet<String> {
+        let mut out = BTreeSet::new();
+        let fields = self
+            .command
+            .iter()
+            .chain(&self.args)
+            .chain(&self.url)
+            .chain(self.env.values());
+        for field in fields {
+            out.extend(placeholders_in(field));
+        }
+        out
+    }
+
+    /// Fill in the template.
+    ///
+    /// Each parameter takes its value from `values`, then from `key_for`
+    /// (called with the parameter's provider id), then from its default.
+    /// Optional parameters left without a value drop out: their env var is
+    /// not set, and any arg containing their placeholder is removed.
+    pub fn instantiate(
+        &self,
+        transport: Option<McpTransport>,
+        values: &HashMap<String, String>,
+        mut key_for: impl FnMut(&str) -> Option<String>,
+    ) -> Result<TemplateInstance, McpCatalogError> {
+        let transport = match transport {
+            Some(t) if self.transports.contains(&t) => t,
+            Some(t) => {
+                return Err(McpCatalogError::UnsupportedTransport {
+                    id: self.id.clone(),
+                    transport: format!("{t:?}").to_lowercase(),
+                })
+            }
+            None => self
+                .default_transport()
+                .cloned()
+                .unwrap_or(McpTransport::Stdio),
+        };
+
+        let unknown: Vec<String> = values
+            .keys()
+            .filter(|k| self.param(k).is_none())
+            .cloned()
+            .collect();
+        if !unknown.is_empty() {
+            return Err(McpCatalogError::UnknownParams {
+                id: self.id.clone(),
+                names: unknown,
+            });
+        }
+
+        let mut resolved: HashMap<&str, String> = HashMap::new();
+        let mut from_key_vault = Vec::new();
+        let mut from_defaults = Vec::new();
+        let mut missing = Vec::new();
+        for param in &self.params {
+            if let Some(v) = values.get(&param.name) {
+                resolved.insert(&param.name, v.clone());
+            } else if let Some(v) = param.provider.as_deref().and_then(&mut key_for) {
+                resolved.insert(&param.name, v);
+                from_key_vault.push(param.name.clone());
+            } else if let Some(v) = &param.default {
+                resolved.insert(&param.name, v.clone());
+                from_defaults.push(param.name.clone());
+            } else if param.required && self.param_used_by(param, &transport) {
+                missing.push(param.name.clone());
+            }
+        }
+        if !missing.is_empty() {
+            return Err(McpCatalogError::MissingParams {
+                id: self.id.clone(),
+                names: missing,
+            });
+        }
+
+        let mut env: HashMap<String, String> = self
+            .env
+            .iter()
+            .filter_map(|(k, v)| Some((k.clone(), substitute(v, &resolved)?)))
+            .collect();
+        for param in self.params.iter().filter(|p| p.kind == ParamKind::Env) {
+            if let Some(v) = resolved.get(param.name.as_str()) {
+                env.insert(param.name.clone(), v.clone());
+            }
+        }
+
+        let config = match transport {
+            McpTransport::Stdio => McpServerConfig {
+                transport,
+                command: self
+                    .command
+                    .as_deref()
+                    .and_then(|c| substitute(c, &resolved)),
+                args: self
+                    .args
+                    .iter()
+                    .filter_map(|a| substitute(a, &resolved))
+                    .collect(),
+                env,
+                url: None,
+                docs_url: self.docs_url.clone(),
+            },
+            McpTransport::Http | McpTransport::Sse => McpServerConfig {
+                transport,
+                command: None,
+                args: Vec::new(),
+                env,
+                url: self.url.as_deref().and_then(|u| substitute(u, &resolved)),
+                docs_url: self.docs_url.clone(),
+            },
+        };
+        config
+            .validate()
+            .map_err(|message| McpCatalogError::InvalidTemplate {
+                id: self.id.clone(),
+                message,
+            })?;
+
+        Ok(TemplateInstance {
+            config,
+            from_key_vault,
+            from_defaults,
+        })
+    }
+
+    /// Whether a parameter affects the config for the given transport.
+    /// Env parameters always apply; arg parameters only where referenced.
+    fn param_used_by(&self, param: &TemplateParam, transport: &McpTransport) -> bool {
+        if param.kind == ParamKind::Env {
+            return true;
+        }
+        let mut fields: Vec<&String> = self.env.values().collect();
+        match transport {
+            McpTransport::Stdio => fields.extend(self.command.iter().chain(&self.args)),
+            McpTransport::Http | McpTransport::Sse => fields.extend(&self.url),
+        }
+        fields
+            .iter()
+            .any(|f| placeholders_in(f).any(|n| n == param.name))
+    }
+}
+
+/// Iterate the `{{name}}` placeholders in a string
+fn placeholders_in(s: &str) -> impl Iterator<Item = String> + '_ {
+    s.match_indices("{{").filter_map(move |(start, _)| {
+        let rest = &s[start + 2..];
+        let end = rest.find("}}")?;
+        Some(rest[..end].trim().to_string())
+    })
+}
+
+/// Replace placeholders with their values. Returns `None` if any placeholder
+/// has no value, so the caller can drop the field.
+fn substitute(s: &str, values: &HashMap<&str, String>) -> Option<String> {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + 2 + end].trim();
+        out.push_str(&rest[..start]);
+        out.push_str(values.get(name)?);
+        rest = &rest[start + 2 + end + 2..];
+    }
+    out.push_str(rest);
+    Some(out)
+}
+
+/// Where a catalog entry was loaded from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase", tag = "type", content = "path")]
+pub enum CatalogSource {
+    Bundled,
+    User(PathBuf),
+}
+
+/// A template together with its origin
+#[derive(Debug, Clone, Serialize)]
+pub struct CatalogEntry {
+    #[serde(flatten)]
+    pub template: McpTemplate,
+    pub source: CatalogSource,
+}
+
+/// On-disk shape of a catalog file: a single template or a list
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CatalogFile {
+    Many { templates: Vec<McpTemplate> },
+    One(Box<McpTemplate>),
+}
+
+impl CatalogFile {
+    fn into_templates(self) -> Vec<McpTemplate> {
+        match self {
+            Self::Many { templates } => templates,
+            Self::One(t) => vec![*t],
+        }
+    }
+}
+
+/// The merged set of bundled and user templates, sorted by id
+#[derive(Debug, Clone, Default)]
+pub struct McpCatalog {
+    entries: Vec<CatalogEntry>,
+}
+
+impl McpCatalog {
+    /// Only the bundled templates
+    ///
+    /// # Panics
+    /// Panics if the bundled catalog is malformed (a build-time bug caught by
+    /// the tests).
+    pub fn bundled() -> Self {
+        let file: CatalogFile =
+            serde_json::from_str(BUNDLED_CATALOG).expect("bundled MCP catalog is valid JSON");
+        let mut catalog = Self::default();
+        for template in file.into_templates() {
+            catalog.insert(template, CatalogSource::Bundled);
+        }
+        catalog
+    }
+
+    /// Bundled templates overlaid with every `.json` / `.toml` file in `dir`.
+    /// A missing directory is not an error.
+    ///
+    /// Every provider-linked parameter must name a provider for which
+    /// `is_known_provider` returns true, so a typo surfaces here rather than
+    /// as a key that is never found when the template is used.
+    pub fn load(
+        dir: &Path,
+        is_known_provider: impl Fn(&str) -> bool,
+    ) -> Result<Self, McpCatalogError> {
+        let mut catalog = Self::bundled();
+        if dir.is_dir() {
+            catalog.overlay(dir)?;
+        }
+        for entry in &catalog.entries {
+            let template = &entry.template;
+            let unknown = template.params.iter().find_map(|p| {
+                let provider = p.provider.as_deref()?;
+                (!is_known_provider(provider)).then_some((p, provider))
+            });
+            if let Some((param, provider)) = unknown {
+                return Err(McpCatalogError::UnknownProvider {
+                    id: template.id.clone(),
+                    param: param.name.clone(),
+                    provider: provider.to_string(),
+                });
+            }
+        }
+        Ok(catalog)
+    }
+
+    /// Add every template from the `.json` / `.toml` files in `dir`
+    fn overlay(&mut self, dir: &Path) -> Result<(), McpCatalogError> {
+        let mut files: Vec<PathBuf> = fs::read_dir(dir)?
+            .filter_map(Result::ok)
+            .map(|e| e.path())
+            .filter(|p| {
+                p.is_file()
+                    && matches!(
+                        p.extension().and_then(|e| e.to_str()),
+                        Some("json" | "toml")
+                    )
+            })
+            .collect();
+        files.sort();
+
+        for path in files {
+            for template in parse_catalog_file(&path)? {
+                template.validate()?;
+                self.insert(template, CatalogSource::User(path.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    fn insert(&mut self, template: McpTemplate, source: CatalogSource) {
+        self.entries.retain(|e| e.template.id != template.id);
+        self.entries.push(CatalogEntry { template, source });
+        self.entries
+            .sort_by(|a, b| a.template.id.cmp(&b.template.id));
+    }
+
+    /// All entries, sorted by id
+    pub fn entries(&self) -> &[CatalogEntry] {
+        &self.entries
+    }
+
+    /// Look up a template by id
+    pub fn get(&self, id: &str) -> Option<&McpTemplate> {
+        self.entries
+            .iter()
+            .find(|e| e.template.id == id)
+            .map(|e| &e.template)
+    }
+
+    /// Look up a template by id, erroring if absent
+    pub fn require(&self, id: &str) -> Result<&McpTemplate, McpCatalogError> {
+        self.get(id)
+            .ok_or_else(|| McpCatalogError::NotFound(id.to_string()))
+    }
+}
+
+fn parse_catalog_file(path: &Path) -> Result<Vec<McpTemplate>, McpCatalogError> {
+    let content = fs::read_to_string(path)?;
+    let parse_err = |message: String| McpCatalogError::Parse {
+        path: path.to_path_buf(),
+        message,
+    };
+    let file: CatalogFile = if path.extension().is_some_and(|e| e == "toml") {
+        toml::from_str(&content).map_err(|e| parse_err(e.to_string()))?
+    } else {
+        serde_json::from_str(&content).map_err(|e| parse_err(e.to_string()))?
+    };
+    Ok(file.into_templates())
+}
+
+/// The stored key to use for a provider: the first one (by label) that has
+/// not failed its last validation.
+pub fn provider_key(
+    store: &ApiKeyStore,
+    provider_id: &str,
+) -> Result<Option<String>, McpCatalogError> {
+    let summaries = store.list_by_provider(provider_id)?;
+    let Some(summary) = summaries.iter().find(|s| s.last_valid != Some(false)) else {
+        return Ok(None);
+    };
+    Ok(store.get(summary.id)?.map(|r| r.key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_bundled_catalog_is_valid() {
+        let catalog = McpCatalog::bundled();
+        assert!(!catalog.entries().is_empty());
+        for entry in catalog.entries() {
+            entry.template.validate().unwrap();
+            assert_eq!(entry.source, CatalogSource::Bundled);
+        }
+        assert!(catalog.get("filesystem").is_some());
+    }
+
+    #[test]
+    fn test_instantiate_args_and_env() {
+        let catalog = McpCatalog::bundled();
+        let fs_template = catalog.require("filesystem").unwrap();
+        let instance = fs_template
+            .instantiate(None, &values(&[("path", "/srv/docs")]), |_| None)
+            .unwrap();
+        assert_eq!(instance.config.command.as_deref(), Some("npx"));
+        assert_eq!(
+            instance.config.args,
+            ["-y", "@modelcontextprotocol/server-filesystem", "/srv/docs"]
+        );
+        assert!(instance.config.docs_url.is_some());
+
+        let err = fs_template
+            .instantiate(None, &HashMap::new(), |_| None)
+            .unwrap_err();
+        assert!(matches!(err, McpCatalogError::MissingParams { names, .. } if names == ["path"]));
+    }
+
+    #[test]
+    fn test_optional_arg_dropped_when_missing() {
+        let catalog = McpCatalog::bundled();
+        let git = catalog.require("git").unwrap();
+        let instance = git.instantiate(None, &HashMap::new(), |_| None).unwrap();
+        assert_eq!(instance.config.args, ["mcp-server-git"]);
+
+        let instance = git
+            .instantiate(None, &values(&[("repository", "/src/app")]), |_| None)
+            .unwrap();
+        assert_eq!(
+            instance.config.args,
+            ["mcp-server-git", "--repository=/src/app"]
+        );
+    }
+
+    #[test]
+    fn test_provider_key_fills_env() {
+        let catalog = McpCatalog::bundled();
+        let brave = catalog.require("brave-search").unwrap();
+        let instance = brave
+            .instantiate(None, &HashMap::new(), |provider| {
+                (provider == "brave-search").then(|| "bsk-123".to_string())
+            })
+            .unwrap();
+        assert_eq!(
+            instance.config.env.get("BRAVE_API_KEY").map(String::as_str),
+            Some("bsk-123")
+        );
+        assert_eq!(instance.from_key_vault, ["BRAVE_API_KEY"]);
+
+        // Explicit values win over the vault
+        let instance = brave
+            .instantiate(None, &values(&[("BRAVE_API_KEY", "explicit")]), |_| {
+                Some("vault".into())
+            })
+            .unwrap();
+        assert_eq!(instance.config.env["BRAVE_API_KEY"], "explicit");
+        assert!(instance.from_key_vault.is_empty());
+    }
+
+    #[test]
+    fn test_transport_selection() {
+        let catalog = McpCatalog::bundled();
+        let context7 = catalog.require("context7").unwrap();
+
+        let http = context7
+            .instantiate(None, &HashMap::new(), |_| None)
+            .unwrap();
+        assert_eq!(http.config.transport, McpTransport::Http);
+        assert_eq!(
+            http.config.url.as_deref(),
+            Some("https://mcp.context7.com/mcp")
+        );
+        assert!(http.config.command.is_none());
+
+        let stdio = context7
+            .instantiate(Some(McpTransport::Stdio), &HashMap::new(), |_| None)
+            .unwrap();
+        assert_eq!(stdio.config.command.as_deref(), Some("npx"));
+
+        let err = context7
+            .instantiate(Some(McpTransport::Sse), &HashMap::new(), |_| None)
+            .unwrap_err();
+        assert!(matches!(err, McpCatalogError::UnsupportedTransport { .. }));
+    }
+
+    #[test]
+    fn test_unknown_params_rejected() {
+        let catalog = McpCatalog::bundled();
+        let err = catalog
+            .require("memory")
+            .unwrap()
+            .instantiate(None, &values(&[("nope", "1")]), |_| None)
+            .unwrap_err();
+        assert!(matches!(err, McpCatalogError::UnknownParams { .. }));
+    }
+
+    #[test]
+    fn test_user_catalog_overrides_and_toml() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("internal.toml"),
+            r#"
+id = "internal-search"
+name = "Internal Search"
+transports = ["sse"]
+url = "https://search.internal/{{tenant}}/sse"
+
+[[params]]
+name = "tenant"
+kind = "arg"
+
+[[params]]
+name = "SEARCH_TOKEN"
+kind = "env"
+secret = true
+"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("overrides.json"),
+            r#"{"templates": [{"id": "memory", "name": "Memory (pinned)", "transports": ["stdio"],
+                "command": "npx", "args": ["-y", "@modelcontextprotocol/server-memory@0.6.0"]}]}"#,
+        )
+        .unwrap();
+        fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let catalog = McpCatalog::load(dir.path(), |_| true).unwrap();
+        let memory = catalog
+            .entries()
+            .iter()
+            .find(|e| e.template.id == "memory")
+            .unwrap();
+        assert_eq!(memory.template.name, "Memory (pinned)");
+        assert!(matches!(memory.source, CatalogSource::User(_)));
+
+        let internal = catalog.require("internal-search").unwrap();
+        let instance = internal
+            .instantiate(
+                None,
+                &values(&[("tenant", "acme"), ("SEARCH_TOKEN", "t0k")]),
+                |_| None,
+            )
+            .unwrap();
+        assert_eq!(
+            instance.config.url.as_deref(),
+            Some("https://search.internal/acme/sse")
+        );
+        assert_eq!(instance.config.env["SEARCH_TOKEN"], "t0k");
+    }
+
+    #[test]
+    fn test_invalid_user_template_rejected() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("bad.json"),
+            r#"{"id": "bad", "name": "Bad", "transports": ["stdio"], "command": "x", "args": ["{{missing}}"]}"#,
+        )
+        .unwrap();
+        let err = McpCatalog::load(dir.path(), |_| true).unwrap_err();
+        assert!(matches!(err, McpCatalogError::InvalidTemplate { .. }));
+        assert!(err.to_string().contains("{{missing}}"));
+    }
+
+    #[test]
+    fn test_unknown_provider_rejected_at_load() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("typo.json"),
+            r#"{"id": "search", "name": "Search", "transports": ["stdio"], "command": "x",
+                "params": [{"name": "KEY", "kind": "env", "provider": "brave-serach"}]}"#,
+        )
+        .unwrap();
+        let known = |p: &str| p != "brave-serach";
+        let err = McpCatalog::load(dir.path(), known).unwrap_err();
+        assert!(
+            matches!(err, McpCatalogError::UnknownProvider { ref provider, .. } if provider == "brave-serach")
+        );
+
+        fs::remove_file(dir.path().join("typo.json")).unwrap();
+        assert!(McpCatalog::load(dir.path(), known).is_ok());
+    }
+}
@@ -0,0 +1,1372 @@
+//! MCP server refresh strategies
+//!
+//! Works out how to update the code behind a stdio MCP server — `npm install`
+//! for a local Node project, `docker pull` for a container image, `cargo
+//! install` for a Cargo-built binary, and so on — and runs it.
+//!
+//! Detection is an ordered list of [`RefreshStrategy`] implementations; the
+//! first one that recognises the server's command produces a [`RefreshPlan`].
+//! Plans are plain data, so a dry-run can show exactly which commands would
+//! run without executing anything.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::Serialize;
+
+use super::mcp::{McpServerConfig, McpTransport};
+
+/// How many parent directories to walk when looking for a project root.
+const PROJECT_ROOT_MAX_DEPTH: usize = 5;
+
+/// A single external command in a refresh plan
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshStep {
+    /// Program to run (resolved against common install locations at run time)
+    pub program: String,
+    /// Arguments passed to the program
+    pub args: Vec<String>,
+    /// Working directory, if the command must run inside a project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<PathBuf>,
+    /// Continue with later steps even if this one fails
+    pub allow_failure: bool,
+}
+
+impl RefreshStep {
+    /// Create a step that runs `program` with `args`
+    pub fn new<I, S>(program: impl Into<String>, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            program: program.into(),
+            args: args.into_iter().map(Into::into).collect(),
+            cwd: None,
+            allow_failure: false,
+        }
+    }
+
+    /// Run the step inside `dir`
+    #[must_use]
+    pub fn in_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cwd = Some(dir.into());
+        self
+    }
+
+    /// Let later steps run even if this one fails
+    #[must_use]
+    pub fn allow_failure(mut self) -> Self {
+        self.allow_failure = true;
+        self
+    }
+
+    /// Shell-like rendering of the command line, e.g. `npm install`
+    pub fn command_line(&self) -> String {
+        std::iter::once(self.program.as_str())
+            .chain(self.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn run(&self) -> Result<String, String> {
+        let resolved = resolve_program(&self.program);
+        let mut cmd = Command::new(&resolved);
+        cmd.args(&self.args);
+        if let Some(dir) = &self.cwd {
+            cmd.current_dir(dir);
+        }
+        let output = cmd
+            .output()
+            .map_err(|e| format!("Failed to execute {} (tried {resolved}): {e}", self.program))?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let combined = if stderr.is_empty() {
+            stdout.to_string()
+        } else if stdout.is_empty() {
+            stderr.to_string()
+        } else {
+            format!("{stdout}\n{stderr}")
+        };
+
+        if output.status.success() {
+            Ok(combined)
+        } else {
+            Err(format!(
+                "{} failed with exit code {:?}:\n{combined}",
+                self.program,
+                output.status.code()
+            ))
+        }
+    }
+}
+
+/// The commands a strategy wants to run to refresh one MCP server
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshPlan {
+    /// [`RefreshStrategy::id`] of the strategy that produced the plan
+    pub strategy: String,
+    /// What the strategy decided to do (`git_pull`, `uv_cache_clean`, ...).
+    /// Actions with nothing to run end in `_skip`.
+    pub action: String,
+    /// Commands to run, in order
+    pub steps: Vec<RefreshStep>,
+    /// Human-readable explanation, used when there is nothing to run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+impl RefreshPlan {
+    /// A plan that runs `steps`
+    pub fn new(
+        strategy: impl Into<String>,
+        action: impl Into<String>,
+        steps: Vec<RefreshStep>,
+    ) -> Self {
+        Self {
+            strategy: strategy.into(),
+            action: action.into(),
+            steps,
+            note: None,
+        }
+    }
+
+    /// A plan for servers that always launch the latest version
+    pub fn skip(
+        strategy: impl Into<String>,
+        action: impl Into<String>,
+        note: impl Into<String>,
+    ) -> Self {
+        Self {
+            strategy: strategy.into(),
+            action: action.into(),
+            steps: Vec::new(),
+            note: Some(note.into()),
+        }
+    }
+
+    /// Whether the plan has nothing to run
+    pub fn is_skip(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Describe the commands joined with `&&`, e.g.
+    /// `git pull && npm install (in /path/to/server)`
+    pub fn describe(&self) -> Option<String> {
+        if self.steps.is_empty() {
+            return None;
+        }
+        let commands = self
+            .steps
+            .iter()
+            .map(RefreshStep::command_line)
+            .collect::<Vec<_>>()
+            .join(" && ");
+        let dir = self.steps.iter().find_map(|s| s.cwd.as_ref());
+        Some(match dir {
+            Some(dir) => format!("{commands} (in {})", dir.display()),
+            None => commands,
+        })
+    }
+
+    /// Run every step in order, stopping at the first failure unless the
+    /// step allows it
+    pub fn execute(&self) -> RefreshOutcome {
+        let mut outcome = RefreshOutcome {
+            success: true,
+            steps: Vec::new(),
+        };
+        for step in &self.steps {
+            let result = step.run();
+            let ok = result.is_ok();
+            outcome.steps.push(RefreshStepOutput {
+                command: step.command_line(),
+                success: ok,
+                output: result.unwrap_or_else(|e| e),
+            });
+            if !ok && !step.allow_failure {
+                outcome.success = false;
+                break;
+            }
+        }
+        outcome
+    }
+}
+
+/// Output of one executed refresh step
+#[derive(Debug, Clone, Serialize)]
+pub struct RefreshStepOutput {
+    pub command: String,
+    pub success: bool,
+    pub output: String,
+}
+
+/// Result of executing a [`RefreshPlan`]
+#[derive(Debug, Clone, Serialize)]
+pub struct RefreshOutcome {
+    pub success: bool,
+    pub steps: Vec<RefreshStepOutput>,
+}
+
+impl RefreshOutcome {
+    /// Output of every step, each under a `=== command ===` header
+    pub fn combined_output(&self) -> String {
+        if let [only] = self.steps.as_slice() {
+            return only.output.clone();
+        }
+        self.steps
+            .iter()
+            .map(|s| format!("=== {} ===\n{}", s.command, s.output))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// Output of the step that stopped the plan, if any
+    pub fn error(&self) -> Option<String> {
+        if self.success {
+            return None;
+        }
+        self.steps
+            .iter()
+            .rev()
+            .find(|s| !s.success)
+            .map(|s| s.output.clone())
+    }
+}
+
+/// What a strategy gets to inspect when deciding whether it applies
+#[derive(Debug, Clone)]
+pub struct RefreshContext<'a> {
+    /// The server's `command` as written in the config
+    pub command: &'a str,
+    /// The server's `args`
+    pub args: &'a [String],
+    /// `command` located on disk (absolute path, `PATH`, or common user bin
+    /// directories). Symlinks are not followed.
+    pub executable: Option<PathBuf>,
+    /// The user's home directory
+    pub home: Option<PathBuf>,
+}
+
+impl<'a> RefreshContext<'a> {
+    /// Build a context, locating `command` on disk
+    pub fn new(command: &'a str, args: &'a [String], home: Option<PathBuf>) -> Self {
+        let executable = locate_executable(command, home.as_deref());
+        Self {
+            command,
+            args,
+            executable,
+            home,
+        }
+    }
+
+    /// The command's file name without directory or Windows extension, so
+    /// `/usr/local/bin/npx` and `npx.cmd` both yield `npx`
+    pub fn program_name(&self) -> &str {
+        let name = Path::new(self.command)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or(self.command);
+        [".exe", ".cmd"]
+            .iter()
+            .find_map(|ext| name.strip_suffix(ext))
+            .unwrap_or(name)
+    }
+
+    /// The executable with symlinks resolved
+    pub fn canonical_executable(&self) -> Option<PathBuf> {
+        self.executable
+            .as_ref()
+            .and_then(|p| std::fs::canonicalize(p).ok())
+    }
+
+    /// The first argument, when it is an absolute path to an existing file
+    /// (e.g. the script in `node /srv/server/index.js`)
+    pub fn script_arg(&self) -> Option<PathBuf> {
+        let path = PathBuf::from(self.args.first()?);
+        (path.is_absolute() && path.exists()).then_some(path)
+    }
+}
+
+/// A way of refreshing one kind of MCP server
+pub trait RefreshStrategy: Send + Sync {
+    /// Stable identifier, used for listing strategies
+    fn id(&self) -> &'static str;
+
+    /// One-line description of what the strategy handles
+    fn description(&self) -> &'static str;
+
+    /// Return a plan if this strategy recognises the server
+    fn detect(&self, ctx: &RefreshContext<'_>) -> Option<RefreshPlan>;
+}
+
+/// An ordered set of refresh strategies
+pub struct McpRefresher {
+    strategies: Vec<Box<dyn RefreshStrategy>>,
+}
+
+impl McpRefresher {
+    /// The built-in strategies, most specific first
+    pub fn new() -> Self {
+        Self {
+            strategies: vec![
+                Box::new(NpxStrategy),
+                Box::new(UvStrategy),
+                Box::new(PipxStrategy),
+                Box::new(DockerStrategy),
+                Box::new(GoStrategy),
+                Box::new(CargoStrategy),
+                Box::new(NodeProjectStrategy),
+                Box::new(GitMakeStrategy),
+            ],
+        }
+    }
+
+    /// A refresher with no strategies at all
+    pub fn empty() -> Self {
+        Self {
+            strategies: Vec::new(),
+        }
+    }
+
+    /// Add a strategy that is tried before all existing ones
+    #[must_use]
+    pub fn with_strategy(mut self, strategy: impl RefreshStrategy + 'static) -> Self {
+        self.strategies.insert(0, Box::new(strategy));
+        self
+    }
+
+    /// Strategies in the order they are tried
+    pub fn strategies(&self) -> impl Iterator<Item = &dyn RefreshStrategy> {
+        self.strategies.iter().map(AsRef::as_ref)
+    }
+
+    /// Plan a refresh for a server config, using the real home directory.
+    ///
+    /// Returns `None` for http/sse servers and for commands no strategy
+    /// recognises.
+    pub fn plan(&self, config: &McpServerConfig) -> Option<RefreshPlan> {
+        if config.transport != McpTransport::Stdio {
+            return None;
+        }
+        let command = config.command.as_deref()?;
+        let ctx = RefreshContext::new(command, &config.args, dirs::home_dir());
+        self.detect(&ctx)
+    }
+
+    /// Ask each strategy in turn; the first match wins
+    pub fn detect(&self, ctx: &RefreshContext<'_>) -> Option<RefreshPlan> {
+        self.strategies.iter().find_map(|s| s.detect(ctx))
+    }
+}
+
+impl Default for McpRefresher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ============================================================================
+// Built-in strategies
+// ============================================================================
+
+/// `npx <package>` — npx resolves the package on every launch
+pub struct NpxStrategy;
+
+impl RefreshStrategy for NpxStrategy {
+    fn id(&self) -> &'static str {
+        "npx_skip"
+    }
+
+    fn description(&self) -> &'static str {
+        "npx packages (always fetched at launch)"
+    }
+
+    fn detect(&self, ctx: &RefreshContext<'_>) -> Option<RefreshPlan> {
+        (ctx.program_name() == "npx").then(|| {
+            RefreshPlan::skip(
+                self.id(),
+                "npx_skip",
+                "npx always fetches the latest version - no refresh needed",
+            )
+        })
+    }
+}
+
+/// `uvx <package>` / `uv tool run <package>` (clear the cached environment)
+/// and binaries installed with `uv tool install` (`uv tool upgrade`)
+pub struct UvStrategy;
+
+/// uv flags that consume the following argument
+const UV_VALUE_FLAGS: &[&str] = &[
+    "--from",
+    "--with",
+    "--with-editable",
+    "--with-requirements",
+    "--python",
+    "-p",
+    "--index",
+    "--index-url",
+    "--extra-index-url",
+    "--default-index",
+    "--find-links",
+    "-f",
+    "--constraints",
+    "-c",
+    "--overrides",
+    "--directory",
+    "--project",
+    "--cache-dir",
+    "--config-file",
+];
+
+impl RefreshStrategy for UvStrategy {
+    fn id(&self) -> &'static str {
+        "uv"
+    }
+
+    fn description(&self) -> &'static str {
+        "uvx / uv tool run packages and uv tool installs"
+    }
+
+    fn detect(&self, ctx: &RefreshContext<'_>) -> Option<RefreshPlan> {
+        let run_args = match ctx.program_name() {
+            "uvx" => Some(ctx.args),
+            "uv" if ctx.args.len() >= 2 && ctx.args[0] == "tool" && ctx.args[1] == "run" => {
+                Some(&ctx.args[2..])
+            }
+            _ => None,
+        };
+
+        if let Some(run_args) = run_args {
+            let spec = flag_value(run_args, "--from")
+                .or_else(|| first_positional(run_args, UV_VALUE_FLAGS))?;
+            if spec.ends_with("@latest") {
+                return Some(RefreshPlan::skip(
+                    self.id(),
+                    "uvx_skip",
+                    "uvx resolves @latest on every launch - no refresh needed",
+                ));
+            }
+            let package = python_package_name(spec)?;
+            return Some(RefreshPlan::new(
+                self.id(),
+                "uv_cache_clean",
+                vec![RefreshStep::new("uv", ["cache", "clean", package])],
+            ));
+        }
+
+        let package = venv_package(&ctx.canonical_executable()?, &["uv", "tools"])?;
+        Some(RefreshPlan::new(
+            self.id(),
+            "uv_tool_upgrade",
+            vec![RefreshStep::new(
+                "uv",
+                ["tool", "upgrade", package.as_str()],
+            )],
+        ))
+    }
+}
+
+/// `pipx run <package>` and binaries installed with `pipx install`
+pub struct PipxStrategy;
+
+impl RefreshStrategy for PipxStrategy {
+    fn id(&self) -> &'static str {
+        "pipx"
+    }
+
+    fn description(&self) -> &'static str {
+        "pipx run packages and pipx installs"
+    }
+
+    fn detect(&self, ctx: &RefreshContext<'_>) -> Option<RefreshPlan> {
+        if ctx.program_name() == "pipx" && ctx.args.first().map(String::as_str) == Some("run") {
+            return Some(RefreshPlan::skip(
+                self.id(),
+                "pipx_run_skip",
+                "pipx run re-resolves its cached environment after a few days - no refresh needed",
+            ));
+        }
+
+        let exe = ctx.canonical_executable()?;
+        let package = venv_package(&exe, &["pipx", "venvs"])
+            .or_else(|| venv_package(&exe, &[".pipx", "venvs"]))?;
+        Some(RefreshPlan::new(
+            self.id(),
+            "pipx_upgrade",
+            vec![RefreshStep::new("pipx", ["upgrade", package.as_str()])],
+        ))
+    }
+}
+
+/// `docker run <image>` / `podman run <image>` — pull the image again
+pub struct DockerStrategy;
+
+/// `docker run` flags that consume the following argument
+const DOCKER_VALUE_FLAGS: &[&str] = &[
+    "-a",
+    "--attach",
+    "--add-host",
+    "--cap-add",
+    "--cap-drop",
+    "--cidfile",
+    "--cpus",
+    "--device",
+    "--dns",
+    "-e",
+    "--env",
+    "--env-file",
+    "--entrypoint",
+    "--expose",
+    "--gpus",
+    "--group-add",
+    "-h",
+    "--hostname",
+    "--ipc",
+    "-l",
+    "--label",
+    "--label-file",
+    "--link",
+    "--log-driver",
+    "--log-opt",
+    "-m",
+    "--memory",
+    "--mount",
+    "--name",
+    "--network",
+    "--net",
+    "-p",
+    "--publish",
+    "--pid",
+    "--platform",
+    "--pull",
+    "--restart",
+    "--runtime",
+    "--security-opt",
+    "--shm-size",
+    "--stop-signal",
+    "--stop-timeout",
+    "--tmpfs",
+    "-u",
+    "--user",
+    "--ulimit",
+    "-v",
+    "--volume",
+    "--volumes-from",
+    "-w",
+    "--workdir",
+];
+
+impl RefreshStrategy for DockerStrategy {
+    fn id(&self) -> &'static str {
+        "docker_pull"
+    }
+
+    fn description(&self) -> &'static str {
+        "docker / podman run images"
+    }
+
+    fn detect(&self, ctx: &RefreshContext<'_>) -> Option<RefreshPlan> {
+        let program = ctx.program_name();
+        if program != "docker" && program != "podman" {
+            return None;
+        }
+        let run_args = match ctx.args {
+            [run, rest @ ..] if run == "run" => rest,
+            [container, run, rest @ ..] if container == "container" && run == "run" => rest,
+            _ => return None,
+        };
+        let image = first_positional(run_args, DOCKER_VALUE_FLAGS)?;
+        Some(RefreshPlan::new(
+            self.id(),
+            "docker_pull",
+            vec![RefreshStep::new(program, ["pull", image])],
+        ))
+    }
+}
+
+/// `go run <module>@latest` and binaries installed with `go install`
+pub struct GoStrategy;
+
+impl RefreshStrategy for GoStrategy {
+    fn id(&self) -> &'static str {
+        "go_install"
+    }
+
+    fn description(&self) -> &'static str {
+        "go run @latest and go install binaries"
+    }
+
+    fn detect(&self, ctx: &RefreshContext<'_>) -> Option<RefreshPlan> {
+        if ctx.program_name() == "go" {
+            let target = match ctx.args {
+                [run, rest @ ..] if run == "run" => first_positional(rest, &[]),
+                _ => None,
+            }?;
+            return target.ends_with("@latest").then(|| {
+                RefreshPlan::skip(
+                    self.id(),
+                    "go_run_skip",
+                    "go run resolves @latest on every launch - no refresh needed",
+                )
+            });
+        }
+
+        let exe = ctx.executable.as_ref()?;
+        let bin_dir = exe.parent()?;
+        if !go_bin_dirs(ctx.home.as_deref())
+            .iter()
+            .any(|d| d == bin_dir)
+        {
+            return None;
+        }
+        let package = go_main_package(exe)?;
+        Some(RefreshPlan::new(
+            self.id(),
+            "go_install",
+            vec![RefreshStep::new(
+                "go",
+                ["install".to_string(), format!("{package}@latest")],
+            )],
+        ))
+    }
+}
+
+/// Binaries installed with `cargo install`, re-installed from the source
+/// recorded in `.crates2.json`
+pub struct CargoStrategy;
+
+impl RefreshStrategy for CargoStrategy {
+    fn id(&self) -> &'static str {
+        "cargo_install"
+    }
+
+    fn description(&self) -> &'static str {
+        "cargo install binaries"
+    }
+
+    fn detect(&self, ctx: &RefreshContext<'_>) -> Option<RefreshPlan> {
+        let exe = ctx.executable.as_ref()?;
+        let bin_dir = exe.parent()?;
+        if bin_dir.file_name()? != "bin" {
+            return None;
+        }
+        let root = bin_dir.parent()?;
+        let bin_name = exe.file_stem()?.to_str()?;
+        let default_root = ctx.home.as_ref().map(|h| h.join(".cargo"));
+        let is_default_root = default_root.as_deref() == Some(root)
+            || std::env::var_os("CARGO_HOME").is_some_and(|h| Path::new(&h) == root);
+
+        let install = find_cargo_install(root, bin_name);
+        if install.is_none() && !is_default_root {
+            return None;
+        }
+
+        let mut args = vec!["install".to_string()];
+        let mut steps = Vec::new();
+        match install {
+            Some(CargoInstall { name, source }) => match source {
+                CargoSource::Registry => args.push(name),
+                CargoSource::Git { url, branch } => {
+                    args.extend(["--git".to_string(), url]);
+                    if let Some(branch) = branch {
+                        args.extend(["--branch".to_string(), branch]);
+                    }
+                    args.push(name);
+                }
+                CargoSource::Path(path) => {
+                    if path.join(".git").exists() {
+                        steps.push(
+                            RefreshStep::new("git", ["pull"])
+                                .in_dir(&path)
+                                .allow_failure(),
+                        );
+                    }
+                    args.extend(["--path".to_string(), path.display().to_string()]);
+                }
+            },
+            None => args.push(bin_name.to_string()),
+        }
+        if !is_default_root {
+            args.extend(["--root".to_string(), root.display().to_string()]);
+        }
+        steps.push(RefreshStep::new("cargo", args));
+        Some(RefreshPlan::new(self.id(), "cargo_install", steps))
+    }
+}
+
+/// `node /abs/path/index.js` or a binary inside a local npm project
+pub struct NodeProjectStrategy;
+
+impl RefreshStrategy for NodeProjectStrategy {
+    fn id(&self) -> &'static str {
+        "npm_install"
+    }
+
+    fn description(&self) -> &'static str {
+        "local Node.js projects (git pull + npm install)"
+    }
+
+    fn detect(&self, ctx: &RefreshContext<'_>) -> Option<RefreshPlan> {
+        let start = if ctx.program_name() == "node" {
+            ctx.script_arg()?
+        } else {
+            let exe = PathBuf::from(ctx.command);
+            if !exe.is_absolute() || !exe.exists() {
+                return None;
+            }
+            exe
+        };
+        let dir = find_project_root(&start, |d| d.join("package.json").exists())?;
+        let npm_install = RefreshStep::new("npm", ["install"]).in_dir(&dir);
+        if dir.join(".git").exists() {
+            return Some(RefreshPlan::new(
+                self.id(),
+                "git_pull",
+                vec![
+                    RefreshStep::new("git", ["pull"])
+                        .in_dir(&dir)
+                        .allow_failure(),
+                    npm_install,
+                ],
+            ));
+        }
+        Some(RefreshPlan::new(
+            self.id(),
+            "npm_install",
+            vec![npm_install],
+        ))
+    }
+}
+
+/// A binary or script inside a git checkout that builds with `make`
+pub struct GitMakeStrategy;
+
+impl RefreshStrategy for GitMakeStrategy {
+    fn id(&self) -> &'static str {
+        "git_make"
+    }
+
+    fn description(&self) -> &'static str {
+        "git checkouts with a Makefile (git pull + make)"
+    }
+
+    fn detect(&self, ctx: &RefreshContext<'_>) -> Option<RefreshPlan> {
+        let is_root = |d: &Path| {
+            d.join(".git").exists()
+                && ["Makefile", "makefile", "GNUmakefile"]
+                    .iter()
+                    .any(|m| d.join(m).is_file())
+        };
+        // Either the command itself lives in the checkout, or it is an
+        // interpreter running a script that does.
+        let exe = PathBuf::from(ctx.command);
+        let dir = (exe.is_absolute() && exe.exists())
+            .then(|| find_project_root(&exe, is_root))
+            .flatten()
+            .or_else(|| find_project_root(&ctx.script_arg()?, is_root))?;
+        Some(RefreshPlan::new(
+            self.id(),
+            "git_make",
+            vec![
+                RefreshStep::new("git", ["pull"]).in_dir(&dir),
+                RefreshStep::new("make", std::iter::empty::<String>()).in_dir(&dir),
+            ],
+        ))
+    }
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+/// Locate `command` on disk: absolute paths as-is, bare names via `PATH`
+/// and the usual per-user bin directories. Relative paths are left alone,
+/// since they depend on the MCP client's working directory.
+fn locate_executable(command: &str, home: Option<&Path>) -> Option<PathBuf> {
+    let path = Path::new(command);
+    if path.is_absolute() {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+    if command.contains('/') || command.contains('\\') {
+        return None;
+    }
+    let mut dirs: Vec<PathBuf> = std::env::var_os("PATH")
+        .map(|p| std::env::split_paths(&p).collect())
+        .unwrap_or_default();
+    if let Some(home) = home {
+        dirs.extend([".cargo/bin", "go/bin", ".local/bin"].map(|d| home.join(d)));
+    }
+    dirs.into_iter()
+        .map(|d| d.join(command))
+        .find(|c| c.is_file())
+}
+
+/// Walk up from `start` (a file or directory) looking for a directory
+/// matching `is_root`
+fn find_project_root(start: &Path, is_root: impl Fn(&Path) -> bool) -> Option<PathBuf> {
+    let mut current = if start.is_file() {
+        start.parent()?.to_path_buf()
+    } else {
+        start.to_path_buf()
+    };
+    for _ in 0..PROJECT_ROOT_MAX_DEPTH {
+        if is_root(&current) {
+            return Some(current);
+        }
+        current = current.parent()?.to_path_buf();
+    }
+    None
+}
+
+/// The value of `--flag value` or `--flag=value`
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == flag {
+            return iter.next().map(String::as_str);
+        }
+        if let Some(value) = arg.strip_prefix(flag).and_then(|r| r.strip_prefix('=')) {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// The first argument that is neither a flag nor a flag's value
+fn first_positional<'a>(args: &'a [String], value_flags: &[&str]) -> Option<&'a str> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--" {
+            return iter.next().map(String::as_str);
+        }
+        if !arg.starts_with('-') {
+            return Some(arg);
+        }
+        if !arg.contains('=') && value_flags.contains(&arg.as_str()) {
+            iter.next();
+        }
+    }
+    None
+}
+
+/// Strip version specifiers and extras from a Python requirement:
+/// `mcp-server-git==1.2`, `pkg[cli]>=2`, `pkg@1.0` all yield the bare name
+fn python_package_name(spec: &str) -> Option<&str> {
+    let end = spec
+        .find(['=', '<', '>', '~', '!', '@', '[', ';', ' '])
+        .unwrap_or(spec.len());
+    let name = spec[..end].trim();
+    (!name.is_empty()).then_some(name)
+}
+
+/// For an executable living in `<...>/<marker[0]>/<marker[1]>/<package>/...`
+/// (e.g. `~/.local/share/uv/tools/<package>/bin/x`), return `<package>`
+fn venv_package(exe: &Path, marker: &[&str; 2]) -> Option<String> {
+    let parts: Vec<&str> = exe
+        .components()
+        .filter_map(|c| c.as_os_str().to_str())
+        .collect();
+    parts
+        .windows(3)
+        .find(|w| w[0] == marker[0] && w[1] == marker[1])
+        .map(|w| w[2].to_string())
+}
+
+/// Directories `go install` writes binaries to
+fn go_bin_dirs(home: Option<&Path>) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(gobin) = std::env::var_os("GOBIN").filter(|v| !v.is_empty()) {
+        dirs.push(PathBuf::from(gobin));
+    }
+    if let Some(gopath) = std::env::var_os("GOPATH") {
+        dirs.extend(std::env::split_paths(&gopath).map(|p| p.join("bin")));
+    }
+    if let Some(home) = home {
+        dirs.push(home.join("go").join("bin"));
+    }
+    dirs
+}
+
+/// Read the main package path from a Go binary's embedded build info
+/// (Go 1.18+ inline format), e.g. `github.com/org/server/cmd/server`
+fn go_main_package(exe: &Path) -> Option<String> {
+    const MAGIC: &[u8] = b"\xff Go buildinf:";
+    const HEADER_LEN: usize = 32;
+    const SENTINEL_LEN: usize = 16;
+
+    let data = std::fs::read(exe).ok()?;
+    let start = data.windows(MAGIC.len()).position(|w| w == MAGIC)?;
+    let header = data.get(start..start + HEADER_LEN)?;
+    // Flag bit 2 marks inline (varint-prefixed) strings.
+    if header[MAGIC.len() + 1] & 0x2 == 0 {
+        return None;
+    }
+    let mut rest = &data[start + HEADER_LEN..];
+    let (version_len, n) = read_uvarint(rest)?;
+    rest = rest.get(n + usize::try_from(version_len).ok()?..)?;
+    let (modinfo_len, n) = read_uvarint(rest)?;
+    let modinfo = rest.get(n..n + usize::try_from(modinfo_len).ok()?)?;
+    let modinfo = modinfo.get(SENTINEL_LEN..modinfo.len().checked_sub(SENTINEL_LEN)?)?;
+    let modinfo = std::str::from_utf8(modinfo).ok()?;
+
+    modinfo
+        .lines()
+        .find_map(|line| line.strip_prefix("path\t"))
+        .filter(|p| *p != "command-line-arguments")
+        .map(str::to_string)
+}
+
+fn read_uvarint(buf: &[u8]) -> Option<(u64, usize)> {
+    let mut value = 0u64;
+    for (i, byte) in buf.iter().enumerate().take(10) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
+
+/// Where a `cargo install`ed crate came from
+#[derive(Debug, PartialEq, Eq)]
+enum CargoSource {
+    Registry,
+    Git { url: String, branch: Option<String> },
+    Path(PathBuf),
+}
+
+#[derive(Debug)]
+struct CargoInstall {
+    name: String,
+    source: CargoSource,
+}
+
+/// Look `bin_name` up in `<root>/.crates2.json`
+fn find_cargo_install(root: &Path, bin_name: &str) -> Option<CargoInstall> {
+    let content = std::fs::read_to_string(root.join(".crates2.json")).ok()?;
+    let value: serde_json::Value = serde_json::from_str(&content).ok()?;
+    let installs = value.get("installs")?.as_object()?;
+
+    installs.iter().find_map(|(key, info)| {
+        let bins = info.get("bins")?.as_array()?;
+        if !bins.iter().any(|b| {
+            b.as_str()
+                .is_some_and(|b| b.strip_suffix(".exe").unwrap_or(b) == bin_name)
+        }) {
+            return None;
+        }
+        // Key format: "<name> <version> (<source-id>)"
+        let (name, rest) = key.split_once(' ')?;
+        let source_id = rest.split_once('(')?.1.strip_suffix(')')?;
+        Some(CargoInstall {
+            name: name.to_string(),
+            source: parse_cargo_source(source_id)?,
+        })
+    })
+}
+
+fn parse_cargo_source(source_id: &str) -> Option<CargoSource> {
+    if source_id.starts_with("registry+") || source_id.starts_with("sparse+") {
+        return Some(CargoSource::Registry);
+    }
+    if let Some(git) = source_id.strip_prefix("git+") {
+        let without_rev = git.split('#').next()?;
+        let (url, query) = match without_rev.split_once('?') {
+            Some((url, query)) => (url, Some(query)),
+            None => (without_rev, None),
+        };
+        let branch = query.and_then(|q| {
+            q.split('&')
+                .find_map(|kv| kv.strip_prefix("branch="))
+                .map(str::to_string)
+        });
+        return Some(CargoSource::Git {
+            url: url.to_string(),
+            branch,
+        });
+    }
+    let path = source_id.strip_prefix("path+file://")?;
+    Some(CargoSource::Path(PathBuf::from(path)))
+}
+
+/// Resolve a program name to a full path by checking common install
+/// locations. GUI apps on macOS don't inherit the shell `PATH`, so a bare
+/// `npm` or `cargo` would otherwise not be found.
+pub fn resolve_program(cmd: &str) -> String {
+    if Path::new(cmd).is_absolute() {
+        return cmd.to_string();
+    }
+    let home = std::env::var("HOME").unwrap_or_default();
+
+    // nvm: use the most recent installed node version
+    if let Ok(entries) = std::fs::read_dir(format!("{home}/.nvm/versions/node")) {
+        let mut versions: Vec<_> = entries
+            .filter_map(std::result::Result::ok)
+            .filter(|e| e.path().is_dir())
+            .collect();
+        versions.sort_by_key(|b| std::cmp::Reverse(b.file_name()));
+        if let Some(latest) = versions.first() {
+            let bin_path = latest.path().join("bin").join(cmd);
+            if bin_path.exists() {
+                return bin_path.to_string_lossy().to_string();
+            }
+        }
+    }
+
+    let common_paths = [
+        // Homebrew (Apple Silicon)
+        "/opt/homebrew/bin".to_string(),
+        // Homebrew (Intel)
+        "/usr/local/bin".to_string(),
+        // System paths
+        "/usr/bin".to_string(),
+        // fnm
+        format!("{home}/.local/share/fnm/aliases/default/bin"),
+        // volta
+        format!("{home}/.volta/bin"),
+        // asdf
+        format!("{home}/.asdf/shims"),
+        // n (node version manager)
+        "/usr/local/n/versions/node".to_string(),
+        // rustup / cargo
+        format!("{home}/.cargo/bin"),
+        // go
+        "/usr/local/go/bin".to_string(),
+        format!("{home}/go/bin"),
+        // uv, pipx
+        format!("{home}/.local/bin"),
+    ];
+    for base in &common_paths {
+        let full_path = format!("{base}/{cmd}");
+        if Path::new(&full_path).exists() {
+            return full_path;
+        }
+    }
+
+    // Fall back to the bare name (resolved via PATH)
+    cmd.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(ToString::to_string).collect()
+    }
+
+    fn detect(command: &str, list: &[&str], home: Option<&Path>) -> Option<RefreshPlan> {
+        let args = args(list);
+        let ctx = RefreshContext::new(command, &args, home.map(Path::to_path_buf));
+        let refresher = McpRefresher::new();
+        let plan = refresher.detect(&ctx)?;
+        // Plans always name the strategy that produced them.
+        assert!(refresher.strategies().any(|s| s.id() == plan.strategy));
+        Some(plan)
+    }
+
+    fn commands(plan: &RefreshPlan) -> Vec<String> {
+        plan.steps.iter().map(RefreshStep::command_line).collect()
+    }
+
+    #[test]
+    fn test_npx_is_skipped() {
+        let plan = detect("npx", &["-y", "@modelcontextprotocol/server-github"], None).unwrap();
+        assert_eq!(plan.action, "npx_skip");
+        assert!(plan.is_skip());
+        assert!(plan.describe().is_none());
+    }
+
+    #[test]
+    fn test_uvx_cleans_package_cache() {
+        let plan = detect(
+            "uvx",
+            &[
+                "--python",
+                "3.12",
+                "mcp-server-git==0.6.2",
+                "--repository",
+                ".",
+            ],
+            None,
+        )
+        .unwrap();
+        assert_eq!(plan.action, "uv_cache_clean");
+        assert_eq!(commands(&plan), ["uv cache clean mcp-server-git"]);
+
+        let plan = detect(
+            "uvx",
+            &["--from", "mcp-fetch[cli]", "mcp-server-fetch"],
+            None,
+        )
+        .unwrap();
+        assert_eq!(commands(&plan), ["uv cache clean mcp-fetch"]);
+
+        let plan = detect("uv", &["tool", "run", "mcp-server-time"], None).unwrap();
+        assert_eq!(commands(&plan), ["uv cache clean mcp-server-time"]);
+
+        let plan = detect("uvx", &["mcp-server-time@latest"], None).unwrap();
+        assert_eq!(plan.action, "uvx_skip");
+    }
+
+    #[test]
+    fn test_uv_and_pipx_installed_tools_upgrade() {
+        let home = TempDir::new().unwrap();
+        let uv_bin = home.path().join(".local/share/uv/tools/mcp-server-git/bin");
+        let pipx_bin = home.path().join(".local/pipx/venvs/mcp-obsidian/bin");
+        fs::create_dir_all(&uv_bin).unwrap();
+        fs::create_dir_all(&pipx_bin).unwrap();
+        fs::write(uv_bin.join("mcp-server-git"), "").unwrap();
+        fs::write(pipx_bin.join("mcp-obsidian"), "").unwrap();
+
+        let plan = detect(uv_bin.join("mcp-server-git").to_str().unwrap(), &[], None).unwrap();
+        assert_eq!(commands(&plan), ["uv tool upgrade mcp-server-git"]);
+
+        let plan = detect(pipx_bin.join("mcp-obsidian").to_str().unwrap(), &[], None).unwrap();
+        assert_eq!(commands(&plan), ["pipx upgrade mcp-obsidian"]);
+
+        let plan = detect("pipx", &["run", "mcp-obsidian"], None).unwrap();
+        assert_eq!(plan.action, "pipx_run_skip");
+    }
+
+    #[test]
+    fn test_docker_run_pulls_image() {
+        let plan = detect(
+            "docker",
+            &[
+                "run",
+                "-i",
+                "--rm",
+                "-e",
+                "GITHUB_TOKEN",
+                "--env=FOO=bar",
+                "-v",
+                "/tmp:/data",
+                "ghcr.io/github/github-mcp-server:latest",
+                "stdio",
+            ],
+            None,
+        )
+        .unwrap();
+        assert_eq!(plan.action, "docker_pull");
+        assert_eq!(
+            commands(&plan),
+            ["docker pull ghcr.io/github/github-mcp-server:latest"]
+        );
+
+        let plan = detect("podman", &["container", "run", "mcp/fetch"], None).unwrap();
+        assert_eq!(commands(&plan), ["podman pull mcp/fetch"]);
+
+        assert!(detect("docker", &["exec", "-i", "mcp", "server"], None).is_none());
+    }
+
+    #[test]
+    fn test_cargo_install_uses_recorded_source() {
+        let home = TempDir::new().unwrap();
+        let bin = home.path().join(".cargo/bin");
+        fs::create_dir_all(&bin).unwrap();
+        fs::write(bin.join("mcp-registry"), "").unwrap();
+        fs::write(bin.join("mcp-git"), "").unwrap();
+        fs::write(
+            home.path().join(".cargo/.crates2.json"),
+            r#"{"installs": {
+                "mcp-registry-server 0.3.1 (registry+https://github.com/rust-lang/crates.io-index)": {"bins": ["mcp-registry"]},
+                "mcp-git 0.1.0 (git+https://github.com/acme/mcp-git?branch=main#0123abcd)": {"bins": ["mcp-git"]}
+            }}"#,
+        )
+        .unwrap();
+
+        let plan = detect(
+            bin.join("mcp-registry").to_str().unwrap(),
+            &[],
+            Some(home.path()),
+        )
+        .unwrap();
+        assert_eq!(plan.action, "cargo_install");
+        assert_eq!(commands(&plan), ["cargo install mcp-registry-server"]);
+
+        let plan = detect(
+            bin.join("mcp-git").to_str().unwrap(),
+            &[],
+            Some(home.path()),
+        )
+        .unwrap();
+        assert_eq!(
+            commands(&plan),
+            ["cargo install --git https://github.com/acme/mcp-git --branch main mcp-git"]
+        );
+    }
+
+    #[test]
+    fn test_cargo_install_custom_root_and_path_source() {
+        let root = TempDir::new().unwrap();
+        let src = TempDir::new().unwrap();
+        fs::create_dir_all(src.path().join(".git")).unwrap();
+        fs::create_dir_all(root.path().join("bin")).unwrap();
+        fs::write(root.path().join("bin/local-mcp"), "").unwrap();
+        fs::write(
+            root.path().join(".crates2.json"),
+            format!(
+                r#"{{"installs": {{"local-mcp 0.1.0 (path+file://{})": {{"bins": ["local-mcp"]}}}}}}"#,
+                src.path().display()
+            ),
+        )
+        .unwrap();
+
+        let plan = detect(
+            root.path().join("bin/local-mcp").to_str().unwrap(),
+            &[],
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            commands(&plan),
+            [
+                "git pull".to_string(),
+                format!(
+                    "cargo install --path {} --root {}",
+                    src.path().display(),
+                    root.path().display()
+                )
+            ]
+        );
+        assert!(plan.steps[0].allow_failure);
+    }
+
+    #[test]
+    fn test_go_binary_reinstalls_main_package() {
+        let home = TempDir::new().unwrap();
+        let bin = home.path().join("go/bin");
+        fs::create_dir_all(&bin).unwrap();
+
+        let modinfo = format!(
+            "{}path\tgithub.com/acme/mcp-go/cmd/server\nmod\tgithub.com/acme/mcp-go\tv1.2.0\th1:abc=\n{}",
+            "s".repeat(16),
+            "e".repeat(16)
+        );
+        let mut data = b"\x7fELF junk".to_vec();
+        data.extend_from_slice(b"\xff Go buildinf:");
+        data.extend_from_slice(&[8, 0x2]);
+        data.resize(data.len() + 16, 0);
+        data.push(8);
+        data.extend_from_slice(b"go1.22.1");
+        data.push(u8::try_from(modinfo.len()).unwrap());
+        data.extend_from_slice(modinfo.as_bytes());
+        fs::write(bin.join("mcp-go"), data).unwrap();
+
+        let plan = detect(bin.join("mcp-go").to_str().unwrap(), &[], Some(home.path())).unwrap();
+        assert_eq!(plan.action, "go_install");
+        assert_eq!(
+            commands(&plan),
+            ["go install github.com/acme/mcp-go/cmd/server@latest"]
+        );
+
+        let plan = detect(
+            "go",
+            &["run", "github.com/acme/mcp-go/cmd/server@latest"],
+            None,
+        )
+        .unwrap();
+        assert_eq!(plan.action, "go_run_skip");
+    }
+
+    #[test]
+    fn test_node_project_with_git() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("dist")).unwrap();
+        fs::create_dir_all(dir.path().join(".git")).unwrap();
+        fs::write(dir.path().join("package.json"), "{}").unwrap();
+        fs::write(dir.path().join("dist/index.js"), "").unwrap();
+
+        let script = dir.path().join("dist/index.js");
+        let plan = detect("node", &[script.to_str().unwrap()], None).unwrap();
+        assert_eq!(plan.action, "git_pull");
+        assert_eq!(commands(&plan), ["git pull", "npm install"]);
+        assert_eq!(
+            plan.describe().unwrap(),
+            format!("git pull && npm install (in {})", dir.path().display())
+        );
+    }
+
+    #[test]
+    fn test_git_make_checkout() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join(".git")).unwrap();
+        fs::create_dir_all(dir.path().join("build")).unwrap();
+        fs::write(dir.path().join("Makefile"), "all:\n").unwrap();
+        fs::write(dir.path().join("build/server"), "").unwrap();
+
+        let plan = detect(dir.path().join("build/server").to_str().unwrap(), &[], None).unwrap();
+        assert_eq!(plan.action, "git_make");
+        assert_eq!(commands(&plan), ["git pull", "make"]);
+
+        let script = dir.path().join("build/server");
+        let plan = detect("python3", &[script.to_str().unwrap()], None).unwrap();
+        assert_eq!(plan.action, "git_make");
+    }
+
+    #[test]
+    fn test_unknown_and_remote_servers() {
+        assert!(detect("some-unknown-binary-xyz", &[], None).is_none());
+        assert!(McpRefresher::new()
+            .plan(&McpServerConfig::http("https://example.com/mcp"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_custom_strategy_takes_priority() {
+        struct Always;
+        impl RefreshStrategy for Always {
+            fn id(&self) -> &'static str {
+                "always"
+            }
+            fn description(&self) -> &'static str {
+                "test"
+            }
+            fn detect(&self, _ctx: &RefreshContext<'_>) -> Option<RefreshPlan> {
+                Some(RefreshPlan::new(
+                    self.id(),
+                    "always",
+                    vec![RefreshStep::new("true", Vec::<String>::new())],
+                ))
+            }
+        }
+
+        let refresher = McpRefresher::new().with_strategy(Always);
+        assert_eq!(refresher.strategies().next().unwrap().id(), "always");
+        let args = args(&["-y", "pkg"]);
+        let ctx = RefreshContext::new("npx", &args, None);
+        assert_eq!(refresher.detect(&ctx).unwrap().strategy, "always");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_execute_stops_on_failure() {
+        let dir = TempDir::new().unwrap();
+        let plan = RefreshPlan::new(
+            "test",
+            "test",
+            vec![
+                RefreshStep::new("sh", ["-c", "exit 1"])
+                    .in_dir(dir.path())
+                    .allow_failure(),
+                RefreshStep::new("sh", ["-c", "echo second"]).in_dir(dir.path()),
+                RefreshStep::new("sh", ["-c", "exit 3"]).in_dir(dir.path()),
+                RefreshStep::new("sh", ["-c", "echo never"]).in_dir(dir.path()),
+            ],
+        );
+        let outcome = plan.execute();
+        assert!(!outcome.success);
+        assert_eq!(outcome.steps.len(), 3);
+        assert!(outcome.steps[1].output.contains("second"));
+        assert!(outcome.error().unwrap().contains("exit code Some(3)"));
+    }
+}
@@ -21,7 +21,9 @@ mod agent;
 mod command;
 mod hook;
 mod mcp;
+mod mcp_catalog;
 mod mcp_ops;
+mod mcp_refresh;
 mod skill;
 
 // Re-exports
@@ -35,5 +37,13 @@ pub use agent::AgentConfig;
 pub use command::CommandConfig;
 pub use hook::{HookConfig, HookDefinition, HookTrigger};
 pub use mcp::{McpServerConfig, McpServerUpdate, McpTransport};
+pub use mcp_catalog::{
+    mcp_catalog_dir, provider_key, CatalogEntry, CatalogSource, McpCatalog, McpCatalogError,
+    McpTemplate, ParamKind, TemplateInstance, TemplateParam,
+};
 pub use mcp_ops::McpOps;
+pub use mcp_refresh::{
+    resolve_program, McpRefresher, RefreshContext, RefreshOutcome, RefreshPlan, RefreshStep,
+    RefreshStepOutput, RefreshStrategy,
+};
 pub use skill::SkillConfig;
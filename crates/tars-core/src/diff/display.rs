@@ -1,6 +1,7 @@
 //! Diff display formatting for user review
 
 use crate::diff::{DiffPlan, FileOperation, WarningSeverity};
+use serde::{Deserialize, Serialize};
 use std::fmt::Write;
 
 /// Format a diff plan for terminal display
@@ -105,7 +106,7 @@ pub fn format_plan_markdown(plan: &DiffPlan) -> String {
 }
 
 /// Summary statistics for a diff plan
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DiffSummary {
     /// Files to create
     pub creates: usize,
@@ -142,6 +143,14 @@ impl DiffSummary {
         summary
     }
 
+    /// Add another summary's counts to this one, e.g. to total several plans
+    pub fn merge(&mut self, other: &Self) {
+        self.creates += other.creates;
+        self.modifies += other.modifies;
+        self.deletes += other.deletes;
+        self.total_bytes += other.total_bytes;
+    }
+
     /// Format as a one-line summary
     #[must_use]
     pub fn one_line(&self) -> String {
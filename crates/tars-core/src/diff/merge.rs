@@ -0,0 +1,158 @@
+//! Line-based three-way merge
+//!
+//! Merges two descendants of a common base the way `diff3` does: lines that
+//! are unchanged on both sides anchor the merge, and each unstable chunk in
+//! between takes whichever side changed it. When both sides changed a chunk
+//! differently, git-style conflict markers are written in its place.
+
+use similar::{capture_diff_slices, Algorithm, DiffOp};
+
+/// Result of a three-way merge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeOutcome {
+    /// Merged text, including conflict markers for unresolved chunks
+    pub content: String,
+    /// Number of conflicting chunks
+    pub conflicts: usize,
+}
+
+impl MergeOutcome {
+    /// Whether every chunk merged cleanly
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.conflicts == 0
+    }
+}
+
+/// Merge `ours` and `theirs`, both derived from `base`
+///
+/// Conflicting chunks are wrapped in `<<<<<<< {ours_label}` /
+/// `=======` / `>>>>>>> {theirs_label}` markers.
+#[must_use]
+pub fn merge3(
+    base: &str,
+    ours: &str,
+    theirs: &str,
+    ours_label: &str,
+    theirs_label: &str,
+) -> MergeOutcome {
+    let base: Vec<&str> = base.split_inclusive('\n').collect();
+    let ours: Vec<&str> = ours.split_inclusive('\n').collect();
+    let theirs: Vec<&str> = theirs.split_inclusive('\n').collect();
+
+    let ours_at = matching_lines(&base, &ours);
+    let theirs_at = matching_lines(&base, &theirs);
+
+    let mut outcome = MergeOutcome {
+        content: String::new(),
+        conflicts: 0,
+    };
+    let (mut b, mut o, mut t) = (0, 0, 0);
+    while b < base.len() || o < ours.len() || t < theirs.len() {
+        // The next base line both sides kept is a stable anchor.
+        let anchor = (b..base.len()).find_map(|i| Some((i, ours_at[i]?, theirs_at[i]?)));
+        let (end_b, end_o, end_t) = anchor.unwrap_or((base.len(), ours.len(), theirs.len()));
+
+        if (end_b, end_o, end_t) == (b, o, t) {
+            outcome.content.push_str(base[b]);
+            b += 1;
+            o += 1;
+            t += 1;
+            continue;
+        }
+
+        let chunk_base = &base[b..end_b];
+        let chunk_ours = &ours[o..end_o];
+        let chunk_theirs = &theirs[t..end_t];
+        if chunk_ours == chunk_base || chunk_ours == chunk_theirs {
+            push_lines(&mut outcome.content, chunk_theirs);
+        } else if chunk_theirs == chunk_base {
+            push_lines(&mut outcome.content, chunk_ours);
+        } else {
+            outcome.conflicts += 1;
+            outcome.content.push_str("<<<<<<< ");
+            outcome.content.push_str(ours_label);
+            outcome.content.push('\n');
+            push_lines(&mut outcome.content, chunk_ours);
+            end_line(&mut outcome.content);
+            outcome.content.push_str("=======\n");
+            push_lines(&mut outcome.content, chunk_theirs);
+            end_line(&mut outcome.content);
+            outcome.content.push_str(">>>>>>> ");
+            outcome.content.push_str(theirs_label);
+            outcome.content.push('\n');
+        }
+        (b, o, t) = (end_b, end_o, end_t);
+    }
+    outcome
+}
+
+/// For each base line, the index of the line it is unchanged as in `other`
+fn matching_lines(base: &[&str], other: &[&str]) -> Vec<Option<usize>> {
+    let mut at = vec![None; base.len()];
+    for op in capture_diff_slices(Algorithm::Myers, base, other) {
+        if let DiffOp::Equal {
+            old_index,
+            new_index,
+            len,
+        } = op
+        {
+            for i in 0..len {
+                at[old_index + i] = Some(new_index + i);
+            }
+        }
+    }
+    at
+}
+
+fn push_lines(out: &mut String, lines: &[&str]) {
+    for line in lines {
+        out.push_str(line);
+    }
+}
+
+/// Terminate a chunk that ended without a trailing newline before a marker
+fn end_line(out: &mut String) {
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge3_combines_disjoint_edits() {
+        let base = "one\ntwo\nthree\nfour\n";
+        let ours = "ONE\ntwo\nthree\nfour\n";
+        let theirs = "one\ntwo\nthree\nFOUR\nfive\n";
+        let merged = merge3(base, ours, theirs, "ours", "theirs");
+        assert!(merged.is_clean());
+        assert_eq!(merged.content, "ONE\ntwo\nthree\nFOUR\nfive\n");
+    }
+
+    #[test]
+    fn test_merge3_identical_edits_are_clean() {
+        let merged = merge3("a\nb\n", "a\nB\n", "a\nB\n", "ours", "theirs");
+        assert!(merged.is_clean());
+        assert_eq!(merged.content, "a\nB\n");
+    }
+
+    #[test]
+    fn test_merge3_marks_conflicts() {
+        let base = "keep\nold\nend";
+        let merged = merge3(
+            base,
+            "keep\nmine\nend",
+            "keep\nyours\nend",
+            "ours",
+            "theirs",
+        );
+        assert_eq!(merged.conflicts, 1);
+        assert_eq!(
+            merged.content,
+            "keep\n<<<<<<< ours\nmine\n=======\nyours\n>>>>>>> theirs\nend"
+        );
+    }
+}
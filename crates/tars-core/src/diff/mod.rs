@@ -1,6 +1,7 @@
 //! Diff generation for profile application
 
 pub mod display;
+pub mod merge;
 pub mod plan;
 mod types;
 
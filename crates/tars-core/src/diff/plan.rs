@@ -2,12 +2,14 @@
 
 use crate::diff::{DiffPlan, FileOperation, Warning, WarningSeverity};
 use crate::profile::{
-    AgentOverlay, ClaudeMdOverlay, CommandOverlay, OverlayMode, Profile, SkillOverlay,
+    AgentOverlay, ClaudeMdOverlay, CommandOverlay, HooksOverlay, McpLocation, McpServerOverlay,
+    OverlayMode, PermissionsOverlay, Profile, SkillOverlay, TemplateContext,
 };
-use crate::util::{validate_name, PathError};
+use crate::util::{safe_join, validate_name, PathError};
+use serde_json::{Map, Value};
 use similar::{ChangeTag, TextDiff};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 use uuid::Uuid;
 
@@ -22,42 +24,126 @@ pub enum PlanError {
 
     #[error("Invalid overlay name: {0}")]
     InvalidName(#[from] PathError),
+
+    #[error("Invalid file in skill {skill}: {path}")]
+    InvalidSkillFile { skill: String, path: String },
+
+    #[error("Invalid JSON in {path}: {source}")]
+    Json {
+        path: String,
+        source: serde_json::Error,
+    },
 }
 
 /// Generate a diff plan for applying a profile to a project
 ///
+/// Template variables in overlays are rendered from what can be detected in
+/// the project directory; use [`generate_plan_with_context`] to add the
+/// project's stored metadata.
+///
 /// # Errors
 /// Returns an error if plan generation fails
 pub fn generate_plan(
     project_id: Uuid,
     project_path: &Path,
     profile: &Profile,
+) -> Result<DiffPlan, PlanError> {
+    generate_plan_with_context(
+        project_id,
+        project_path,
+        profile,
+        &TemplateContext::new(project_path),
+    )
+}
+
+/// Generate a diff plan, rendering overlay templates with `context`
+///
+/// Variables an overlay uses but the project does not set become warnings.
+///
+/// # Errors
+/// Returns an error if plan generation fails
+pub fn generate_plan_with_context(
+    project_id: Uuid,
+    project_path: &Path,
+    profile: &Profile,
+    context: &TemplateContext,
 ) -> Result<DiffPlan, PlanError> {
     let mut plan = DiffPlan::new(project_id, profile.id);
 
     // Process CLAUDE.md overlay
     if let Some(claude_md) = &profile.repo_overlays.claude_md {
-        plan_claude_md(project_path, claude_md, &mut plan)?;
+        let claude_md = ClaudeMdOverlay {
+            content: render_overlay(context, "CLAUDE.md", &claude_md.content, &mut plan),
+            ..claude_md.clone()
+        };
+        plan_claude_md(project_path, &claude_md, &mut plan)?;
     }
 
     // Process repo skill overlays
     for skill in &profile.repo_overlays.skills {
-        plan_skill(project_path, skill, &mut plan)?;
+        let source = format!("skill '{}'", skill.name);
+        let skill = SkillOverlay {
+            content: render_overlay(context, &source, &skill.content, &mut plan),
+            ..skill.clone()
+        };
+        plan_skill(project_path, &skill, &mut plan)?;
     }
 
     // Process repo command overlays
     for cmd in &profile.repo_overlays.commands {
-        plan_command(project_path, cmd, &mut plan)?;
+        let source = format!("command '{}'", cmd.name);
+        let cmd = CommandOverlay {
+            content: render_overlay(context, &source, &cmd.content, &mut plan),
+            ..cmd.clone()
+        };
+        plan_command(project_path, &cmd, &mut plan)?;
     }
 
     // Process repo agent overlays
     for agent in &profile.repo_overlays.agents {
-        plan_agent(project_path, agent, &mut plan)?;
+        let source = format!("agent '{}'", agent.name);
+        let agent = AgentOverlay {
+            content: render_overlay(context, &source, &agent.content, &mut plan),
+            ..agent.clone()
+        };
+        plan_agent(project_path, &agent, &mut plan)?;
+    }
+
+    // Process MCP server overlays
+    if !profile.repo_overlays.mcp_servers.is_empty() {
+        let mcp_path = match profile.adapters.mcp_location {
+            McpLocation::ProjectRoot => project_path.join(".mcp.json"),
+            McpLocation::ClaudeDir => project_path.join(".claude").join("mcp.json"),
+        };
+        plan_mcp_servers(&mcp_path, &profile.repo_overlays.mcp_servers, &mut plan)?;
+    }
+
+    // Process hook and permission overlays
+    let hooks = profile.repo_overlays.hooks.as_ref();
+    let permissions = profile.repo_overlays.permissions.as_ref();
+    if hooks.is_some() || permissions.is_some() {
+        plan_settings(project_path, hooks, permissions, &mut plan)?;
     }
 
     Ok(plan)
 }
 
+fn render_overlay(
+    context: &TemplateContext,
+    source: &str,
+    content: &str,
+    plan: &mut DiffPlan,
+) -> String {
+    let rendered = context.render(content);
+    for name in rendered.missing {
+        plan.warnings.push(Warning {
+            severity: WarningSeverity::Warning,
+            message: format!("{source}: template variable '{name}' is not set for this project"),
+        });
+    }
+    rendered.text
+}
+
 fn plan_claude_md(
     project_path: &Path,
     overlay: &ClaudeMdOverlay,
@@ -76,22 +162,7 @@ fn plan_claude_md(
         overlay.content.clone()
     };
 
-    if claude_md_path.exists() {
-        let existing = fs::read_to_string(&claude_md_path)?;
-        if existing != new_content {
-            let diff = generate_text_diff(&existing, &new_content);
-            plan.operations.push(FileOperation::Modify {
-                path: claude_md_path,
-                diff,
-                new_content: new_content.into_bytes(),
-            });
-        }
-    } else {
-        plan.operations.push(FileOperation::Create {
-            path: claude_md_path,
-            content: new_content.into_bytes(),
-        });
-    }
+    plan_file(claude_md_path, new_content.into_bytes(), plan)?;
 
     Ok(())
 }
@@ -110,22 +181,20 @@ fn plan_skill(
         .join(&skill.name);
     let skill_file = skill_dir.join("SKILL.md");
 
-    if skill_file.exists() {
-        let existing = fs::read_to_string(&skill_file)?;
-        if existing != skill.content {
-            let diff = generate_text_diff(&existing, &skill.content);
-            plan.operations.push(FileOperation::Modify {
-                path: skill_file,
-                diff,
-                new_content: skill.content.clone().into_bytes(),
-            });
+    plan_file(skill_file, skill.content.clone().into_bytes(), plan)?;
+
+    // Supporting files travel with the skill
+    for file in &skill.files {
+        let invalid = || PlanError::InvalidSkillFile {
+            skill: skill.name.clone(),
+            path: file.path.clone(),
+        };
+        if file.path == "SKILL.md" {
+            return Err(invalid());
         }
-    } else {
-        // Need to create directory and file
-        plan.operations.push(FileOperation::Create {
-            path: skill_file,
-            content: skill.content.clone().into_bytes(),
-        });
+        let path = safe_join(&skill_dir, Path::new(&file.path))?;
+        let content = file.bytes().map_err(|_| invalid())?;
+        plan_file(path, content, plan)?;
     }
 
     Ok(())
@@ -144,22 +213,7 @@ fn plan_command(
         .join("commands")
         .join(format!("{}.md", cmd.name));
 
-    if cmd_path.exists() {
-        let existing = fs::read_to_string(&cmd_path)?;
-        if existing != cmd.content {
-            let diff = generate_text_diff(&existing, &cmd.content);
-            plan.operations.push(FileOperation::Modify {
-                path: cmd_path,
-                diff,
-                new_content: cmd.content.clone().into_bytes(),
-            });
-        }
-    } else {
-        plan.operations.push(FileOperation::Create {
-            path: cmd_path,
-            content: cmd.content.clone().into_bytes(),
-        });
-    }
+    plan_file(cmd_path, cmd.content.clone().into_bytes(), plan)?;
 
     Ok(())
 }
@@ -177,21 +231,195 @@ fn plan_agent(
         .join("agents")
         .join(format!("{}.md", agent.name));
 
-    if agent_path.exists() {
-        let existing = fs::read_to_string(&agent_path)?;
-        if existing != agent.content {
-            let diff = generate_text_diff(&existing, &agent.content);
+    plan_file(agent_path, agent.content.clone().into_bytes(), plan)?;
+
+    Ok(())
+}
+
+fn plan_mcp_servers(
+    mcp_path: &Path,
+    servers: &[McpServerOverlay],
+    plan: &mut DiffPlan,
+) -> Result<(), PlanError> {
+    let mut config = read_json_object(mcp_path)?;
+    let entries = config
+        .entry("mcpServers")
+        .or_insert_with(|| Value::Object(Map::new()));
+    if !entries.is_object() {
+        *entries = Value::Object(Map::new());
+    }
+    if let Value::Object(entries) = entries {
+        for server in servers {
+            entries.insert(server.name.clone(), mcp_server_json(server));
+        }
+    }
+
+    plan_file(mcp_path.to_path_buf(), json_bytes(config), plan)
+}
+
+fn mcp_server_json(server: &McpServerOverlay) -> Value {
+    let mut entry = Map::new();
+    if server.transport != "stdio" {
+        entry.insert("type".into(), Value::from(server.transport.clone()));
+    }
+    if let Some(command) = &server.command {
+        entry.insert("command".into(), Value::from(command.clone()));
+    }
+    if !server.args.is_empty() {
+        entry.insert("args".into(), Value::from(server.args.clone()));
+    }
+    if !server.env.is_empty() {
+        entry.insert("env".into(), sorted_string_map(&server.env));
+    }
+    if let Some(url) = &server.url {
+        entry.insert("url".into(), Value::from(url.clone()));
+    }
+    if !server.headers.is_empty() {
+        entry.insert("headers".into(), sorted_string_map(&server.headers));
+    }
+    Value::Object(entry)
+}
+
+fn sorted_string_map(map: &std::collections::HashMap<String, String>) -> Value {
+    let sorted: std::collections::BTreeMap<_, _> = map.iter().collect();
+    serde_json::to_value(sorted).unwrap_or_default()
+}
+
+/// Merge hooks and permission rules into `.claude/settings.json`.
+///
+/// Hook groups already present for an event are not duplicated, and
+/// permission lists are unioned so local rules are never dropped.
+fn plan_settings(
+    project_path: &Path,
+    hooks: Option<&HooksOverlay>,
+    permissions: Option<&PermissionsOverlay>,
+    plan: &mut DiffPlan,
+) -> Result<(), PlanError> {
+    let settings_path = project_path.join(".claude").join("settings.json");
+    let mut settings = read_json_object(&settings_path)?;
+
+    if let Some(hooks) = hooks {
+        let existing = object_entry(&mut settings, "hooks");
+        for (event, groups) in &hooks.events {
+            let current = existing
+                .entry(event.clone())
+                .or_insert_with(|| Value::Array(Vec::new()));
+            if !current.is_array() {
+                *current = Value::Array(Vec::new());
+            }
+            if let Value::Array(current) = current {
+                for group in groups {
+                    if !current.contains(group) {
+                        current.push(group.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(permissions) = permissions {
+        let existing = object_entry(&mut settings, "permissions");
+        union_strings(existing, "allow", &permissions.allow);
+        union_strings(existing, "deny", &permissions.deny);
+        union_strings(existing, "ask", &permissions.ask);
+        union_strings(
+            existing,
+            "additionalDirectories",
+            &permissions.additional_directories,
+        );
+        if let Some(mode) = &permissions.default_mode {
+            existing.insert("defaultMode".into(), Value::from(mode.clone()));
+        }
+    }
+
+    plan_file(settings_path, json_bytes(settings), plan)
+}
+
+fn object_entry<'a>(object: &'a mut Map<String, Value>, key: &str) -> &'a mut Map<String, Value> {
+    let entry = object
+        .entry(key)
+        .or_insert_with(|| Value::Object(Map::new()));
+    if !entry.is_object() {
+        *entry = Value::Object(Map::new());
+    }
+    match entry {
+        Value::Object(map) => map,
+        _ => unreachable!("entry was just made an object"),
+    }
+}
+
+fn union_strings(object: &mut Map<String, Value>, key: &str, additions: &[String]) {
+    if additions.is_empty() {
+        return;
+    }
+    let entry = object
+        .entry(key)
+        .or_insert_with(|| Value::Array(Vec::new()));
+    if !entry.is_array() {
+        *entry = Value::Array(Vec::new());
+    }
+    if let Value::Array(items) = entry {
+        for addition in additions {
+            let value = Value::from(addition.clone());
+            if !items.contains(&value) {
+                items.push(value);
+            }
+        }
+    }
+}
+
+fn read_json_object(path: &Path) -> Result<Map<String, Value>, PlanError> {
+    if !path.exists() {
+        return Ok(Map::new());
+    }
+    let content = fs::read_to_string(path)?;
+    if content.trim().is_empty() {
+        return Ok(Map::new());
+    }
+    match serde_json::from_str(&content) {
+        Ok(Value::Object(map)) => Ok(map),
+        Ok(_) => Ok(Map::new()),
+        Err(source) => Err(PlanError::Json {
+            path: path.display().to_string(),
+            source,
+        }),
+    }
+}
+
+fn json_bytes(object: Map<String, Value>) -> Vec<u8> {
+    let mut content = serde_json::to_string_pretty(&Value::Object(object)).unwrap_or_default();
+    content.push('\n');
+    content.into_bytes()
+}
+
+/// Plan a create or modify for `path`, skipping files that already match
+///
+/// The path is recorded as a plan target either way.
+pub(crate) fn plan_file(
+    path: PathBuf,
+    content: Vec<u8>,
+    plan: &mut DiffPlan,
+) -> Result<(), PlanError> {
+    plan.targets.push(path.clone());
+    if path.exists() {
+        let existing = fs::read(&path)?;
+        if existing != content {
+            let diff = match (
+                std::str::from_utf8(&existing),
+                std::str::from_utf8(&content),
+            ) {
+                (Ok(old), Ok(new)) => generate_text_diff(old, new),
+                _ => "Binary files differ\n".to_string(),
+            };
             plan.operations.push(FileOperation::Modify {
-                path: agent_path,
+                path,
                 diff,
-                new_content: agent.content.clone().into_bytes(),
+                new_content: content,
             });
         }
     } else {
-        plan.operations.push(FileOperation::Create {
-            path: agent_path,
-            content: agent.content.clone().into_bytes(),
-        });
+        plan.operations
+            .push(FileOperation::Create { path, content });
     }
 
     Ok(())
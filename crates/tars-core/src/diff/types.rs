@@ -15,6 +15,9 @@ pub struct DiffPlan {
     pub operations: Vec<FileOperation>,
     /// Warnings generated
     pub warnings: Vec<Warning>,
+    /// Every file the profile writes, including ones already up to date
+    #[serde(default)]
+    pub targets: Vec<PathBuf>,
 }
 
 impl DiffPlan {
@@ -26,6 +29,7 @@ impl DiffPlan {
             profile_id,
             operations: Vec::new(),
             warnings: Vec::new(),
+            targets: Vec::new(),
         }
     }
 
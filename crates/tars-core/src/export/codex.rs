@@ -2,7 +2,7 @@
 
 use crate::config::{HookConfig, HookDefinition};
 use crate::profile::storage::{get_mcp_server_config, profile_dir, sanitize_tool_name};
-use crate::profile::{McpServerOverlay, Profile, ToolRef, ToolType};
+use crate::profile::{McpServerOverlay, Profile, ResolvedProfile, ToolRef, ToolType};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::{BTreeMap, HashMap};
@@ -88,17 +88,18 @@ struct ExportMcpServer {
 /// # Errors
 /// Returns an error if any file cannot be written.
 pub fn export_as_codex_bundle(
-    profile: &Profile,
+    resolved: &ResolvedProfile,
     output_dir: &Path,
     plugin_name: &str,
     version: &str,
     marketplace_name: &str,
 ) -> Result<CodexExportResult, ExportError> {
-    let skills = collect_skills(profile)?;
-    let commands = collect_commands(profile)?;
-    let agents = collect_agents(profile)?;
-    let mcp_servers = collect_mcp_servers(profile)?;
-    let hook_configs = collect_hooks(profile, &skills, &agents)?;
+    let profile = &resolved.profile;
+    let skills = collect_skills(resolved)?;
+    let commands = collect_commands(resolved)?;
+    let agents = collect_agents(resolved)?;
+    let mcp_servers = collect_mcp_servers(resolved)?;
+    let hook_configs = collect_hooks(resolved, &skills, &agents)?;
 
     let mut report = build_codex_compatibility_report(&skills, &commands, &agents, &mcp_servers);
     append_hook_findings(&mut report, &hook_configs);
@@ -331,7 +332,8 @@ fn write_codex_agents(
     Ok(Some(agents_dir))
 }
 
-fn collect_skills(profile: &Profile) -> Result<Vec<ExportSkill>, ExportError> {
+fn collect_skills(resolved: &ResolvedProfile) -> Result<Vec<ExportSkill>, ExportError> {
+    let profile = &resolved.profile;
     let mut skills = BTreeMap::new();
 
     for skill in &profile.user_overlays.skills {
@@ -354,13 +356,12 @@ fn collect_skills(profile: &Profile) -> Result<Vec<ExportSkill>, ExportError> {
         );
     }
 
-    let profile_storage = profile_dir(profile.id)?;
     for tool in profile
         .tool_refs
         .iter()
         .filter(|tool| tool.tool_type == ToolType::Skill)
     {
-        if let Some(skill) = read_stored_skill(&profile_storage, tool)? {
+        if let Some(skill) = read_stored_skill(&profile_dir(resolved.tool_owner(tool))?, tool)? {
             skills.insert(skill.name.clone(), skill);
         }
     }
@@ -368,7 +369,8 @@ fn collect_skills(profile: &Profile) -> Result<Vec<ExportSkill>, ExportError> {
     Ok(skills.into_values().collect())
 }
 
-fn collect_commands(profile: &Profile) -> Result<Vec<ExportCommand>, ExportError> {
+fn collect_commands(resolved: &ResolvedProfile) -> Result<Vec<ExportCommand>, ExportError> {
+    let profile = &resolved.profile;
     let mut commands = BTreeMap::new();
 
     for command in &profile.user_overlays.commands {
@@ -391,13 +393,13 @@ fn collect_commands(profile: &Profile) -> Result<Vec<ExportCommand>, ExportError
         );
     }
 
-    let profile_storage = profile_dir(profile.id)?;
     for tool in profile
         .tool_refs
         .iter()
         .filter(|tool| tool.tool_type == ToolType::Hook)
     {
-        if let Some(command) = read_stored_command(&profile_storage, tool)? {
+        if let Some(command) = read_stored_command(&profile_dir(resolved.tool_owner(tool))?, tool)?
+        {
             commands.insert(command.name.clone(), command);
         }
     }
@@ -405,7 +407,8 @@ fn collect_commands(profile: &Profile) -> Result<Vec<ExportCommand>, ExportError
     Ok(commands.into_values().collect())
 }
 
-fn collect_agents(profile: &Profile) -> Result<Vec<ExportAgent>, ExportError> {
+fn collect_agents(resolved: &ResolvedProfile) -> Result<Vec<ExportAgent>, ExportError> {
+    let profile = &resolved.profile;
     let mut agents = BTreeMap::new();
 
     for agent in &profile.repo_overlays.agents {
@@ -418,13 +421,12 @@ fn collect_agents(profile: &Profile) -> Result<Vec<ExportAgent>, ExportError> {
         );
     }
 
-    let profile_storage = profile_dir(profile.id)?;
     for tool in profile
         .tool_refs
         .iter()
         .filter(|tool| tool.tool_type == ToolType::Agent)
     {
-        if let Some(agent) = read_stored_agent(&profile_storage, tool)? {
+        if let Some(agent) = read_stored_agent(&profile_dir(resolved.tool_owner(tool))?, tool)? {
             agents.insert(agent.name.clone(), agent);
         }
     }
@@ -432,7 +434,8 @@ fn collect_agents(profile: &Profile) -> Result<Vec<ExportAgent>, ExportError> {
     Ok(agents.into_values().collect())
 }
 
-fn collect_mcp_servers(profile: &Profile) -> Result<Vec<ExportMcpServer>, ExportError> {
+fn collect_mcp_servers(resolved: &ResolvedProfile) -> Result<Vec<ExportMcpServer>, ExportError> {
+    let profile = &resolved.profile;
     let mut servers = BTreeMap::new();
 
     for server in &profile.repo_overlays.mcp_servers {
@@ -444,7 +447,7 @@ fn collect_mcp_servers(profile: &Profile) -> Result<Vec<ExportMcpServer>, Export
         .iter()
         .filter(|tool| tool.tool_type == ToolType::Mcp)
     {
-        let value = get_mcp_server_config(profile.id, &tool.name)?;
+        let value = get_mcp_server_config(resolved.tool_owner(tool), &tool.name)?;
         servers.insert(tool.name.clone(), parse_mcp_value(&tool.name, &value));
     }
 
@@ -452,19 +455,13 @@ fn collect_mcp_servers(profile: &Profile) -> Result<Vec<ExportMcpServer>, Export
 }
 
 fn collect_hooks(
-    profile: &Profile,
+    resolved: &ResolvedProfile,
     skills: &[ExportSkill],
     agents: &[ExportAgent],
 ) -> Result<Vec<HookConfig>, ExportError> {
-    let mut hooks = Vec::new();
-
-    let profile_storage = profile_dir(profile.id)?;
-    let hooks_path = profile_storage.join("hooks.json");
-    if hooks_path.exists() {
-        let content = fs::read_to_string(hooks_path)?;
-        let mut stored_hooks: Vec<HookConfig> = serde_json::from_str(&content)?;
-        hooks.append(&mut stored_hooks);
-    }
+    let mut hooks = resolved
+        .stored_hooks()?
+        .map_or_else(Vec::new, |stored| plugin_hook_configs(&stored));
 
     hooks.extend(collect_skill_embedded_hooks(skills));
     hooks.extend(collect_agent_embedded_hooks(agents));
@@ -472,6 +469,42 @@ fn collect_hooks(
     Ok(hooks)
 }
 
+/// Flatten plugin-format hooks (`{"hooks": {Event: [{matcher, hooks}]}}`)
+/// into one [`HookConfig`] per handler. Unknown events and handler types are
+/// skipped.
+fn plugin_hook_configs(stored: &Value) -> Vec<HookConfig> {
+    #[derive(Deserialize)]
+    struct MatcherGroup {
+        #[serde(default)]
+        matcher: Option<String>,
+        #[serde(default)]
+        hooks: Vec<Value>,
+    }
+
+    let mut hooks = Vec::new();
+    let Some(events) = stored.get("hooks").and_then(Value::as_object) else {
+        return hooks;
+    };
+    for (event, groups) in events {
+        let Ok(trigger) = event.parse() else {
+            continue;
+        };
+        let groups: Vec<MatcherGroup> = serde_json::from_value(groups.clone()).unwrap_or_default();
+        for group in groups {
+            let matcher = group.matcher.filter(|m| !m.is_empty());
+            hooks.extend(group.hooks.into_iter().filter_map(|handler| {
+                let definition = serde_json::from_value::<HookDefinition>(handler).ok()?;
+                Some(HookConfig {
+                    trigger,
+                    matcher: matcher.clone(),
+                    definition,
+                })
+            }));
+        }
+    }
+    hooks
+}
+
 fn collect_skill_embedded_hooks(skills: &[ExportSkill]) -> Vec<HookConfig> {
     let mut hooks = Vec::new();
     for skill in skills {
@@ -762,7 +795,8 @@ fn toml_table_key(value: &str) -> String {
     toml_string(value)
 }
 
-fn split_frontmatter(content: &str) -> Option<(String, String)> {
+/// Split a `---`-fenced YAML frontmatter block from the body that follows it.
+pub(crate) fn split_frontmatter(content: &str) -> Option<(String, String)> {
     let mut parts = content.split_inclusive('\n');
     let first = parts.next()?;
     let trimmed = first.trim_end_matches(['\n', '\r']);
@@ -1,10 +1,9 @@
 //! Profile to plugin conversion
 
 use crate::profile::storage::{
-    compute_profile_content_hash, copy_dir_recursive, get_mcp_server_config, profile_dir,
-    sanitize_tool_name, StorageError,
+    copy_dir_recursive, get_mcp_server_config, profile_dir, sanitize_tool_name, StorageError,
 };
-use crate::profile::{Profile, ToolType};
+use crate::profile::{ResolvedProfile, ToolType};
 use std::fs;
 use std::path::{Path, PathBuf};
 use tempfile::TempDir;
@@ -42,21 +41,23 @@ impl From<StorageError> for ExportError {
 /// Export a profile as a Claude Code plugin
 ///
 /// Creates a complete plugin directory structure with all tools from the profile's
-/// central storage. The plugin can be installed via `claude plugin install`.
+/// central storage. Inherited tools are read from the storage of the layer that
+/// defines them. The plugin can be installed via `claude plugin install`.
 ///
 /// # Errors
 /// Returns an error if export fails
 pub fn export_as_plugin(
-    profile: &Profile,
+    resolved: &ResolvedProfile,
     output_dir: &Path,
     plugin_name: &str,
     version: &str,
 ) -> Result<PathBuf, ExportError> {
+    let profile = &resolved.profile;
+
     // Create directory structure
     create_plugin_structure(output_dir)?;
 
     let plugin_dir = output_dir.join(".claude-plugin");
-    let profile_storage = profile_dir(profile.id)?;
 
     // Copy skills from profile storage
     for tool in profile
@@ -65,7 +66,9 @@ pub fn export_as_plugin(
         .filter(|t| t.tool_type == ToolType::Skill)
     {
         let safe_name = sanitize_tool_name(&tool.name)?;
-        let src = profile_storage.join("skills").join(&safe_name);
+        let src = profile_dir(resolved.tool_owner(tool))?
+            .join("skills")
+            .join(&safe_name);
         if src.exists() {
             let dst = output_dir.join("skills").join(&safe_name);
             copy_dir_recursive(&src, &dst)?;
@@ -79,7 +82,7 @@ pub fn export_as_plugin(
         .filter(|t| t.tool_type == ToolType::Agent)
     {
         let safe_name = sanitize_tool_name(&tool.name)?;
-        let src = profile_storage
+        let src = profile_dir(resolved.tool_owner(tool))?
             .join("agents")
             .join(format!("{safe_name}.md"));
         if src.exists() {
@@ -98,7 +101,7 @@ pub fn export_as_plugin(
         // In Claude Code, "commands" and "hooks" are related
         // Commands are the MD files, hooks are in settings
         let safe_name = sanitize_tool_name(&tool.name)?;
-        let src = profile_storage
+        let src = profile_dir(resolved.tool_owner(tool))?
             .join("commands")
             .join(format!("{safe_name}.md"));
         if src.exists() {
@@ -107,11 +110,10 @@ pub fn export_as_plugin(
         }
     }
 
-    // Copy hooks configuration if present
-    let hooks_path = profile_storage.join("hooks.json");
-    if hooks_path.exists() {
+    // Write hooks configuration, layered across inherited profiles
+    if let Some(hooks) = resolved.stored_hooks()? {
         let dest_path = output_dir.join("hooks.json");
-        fs::copy(&hooks_path, &dest_path)?;
+        fs::write(dest_path, serde_json::to_string_pretty(&hooks)?)?;
     }
 
     // Generate MCP config in FLAT format (no mcpServers wrapper)
@@ -126,7 +128,7 @@ pub fn export_as_plugin(
         let mut mcp_config = serde_json::Map::new();
 
         for tool in mcp_tools {
-            if let Ok(config) = get_mcp_server_config(profile.id, &tool.name) {
+            if let Ok(config) = get_mcp_server_config(resolved.tool_owner(tool), &tool.name) {
                 mcp_config.insert(tool.name.clone(), config);
             }
         }
@@ -161,14 +163,14 @@ pub fn export_as_plugin(
 /// # Errors
 /// Returns an error if export fails
 pub fn export_as_plugin_with_hash(
-    profile: &Profile,
+    resolved: &ResolvedProfile,
     output_dir: &Path,
     plugin_name: &str,
 ) -> Result<PathBuf, ExportError> {
-    let content_hash = compute_profile_content_hash(profile.id)?;
+    let content_hash = resolved.content_hash()?;
     // Use 16 chars of hash for better uniqueness (reduces collision probability)
     let version = format!("1.0.0+{}", &content_hash[..16.min(content_hash.len())]);
-    export_as_plugin(profile, output_dir, plugin_name, &version)
+    export_as_plugin(resolved, output_dir, plugin_name, &version)
 }
 
 /// Export a profile as a ZIP archive containing a Claude Code plugin
@@ -178,7 +180,7 @@ pub fn export_as_plugin_with_hash(
 /// # Errors
 /// Returns an error if export or archive creation fails
 pub fn export_as_plugin_zip(
-    profile: &Profile,
+    resolved: &ResolvedProfile,
     output_path: &Path,
     plugin_name: &str,
     version: &str,
@@ -187,7 +189,7 @@ pub fn export_as_plugin_zip(
     let plugin_root = temp_dir.path().join(plugin_name);
     fs::create_dir_all(&plugin_root)?;
 
-    export_as_plugin(profile, &plugin_root, plugin_name, version)?;
+    export_as_plugin(resolved, &plugin_root, plugin_name, version)?;
     create_archive(&plugin_root, output_path)?;
 
     Ok(output_path.to_path_buf())
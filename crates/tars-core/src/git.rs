@@ -0,0 +1,50 @@
+//! Running the `git` CLI
+//!
+//! Profile repositories and git-installed skills both shell out to git. The
+//! runners here never wait on a credential prompt and return trimmed stdout,
+//! or a message naming the failed subcommand with git's stderr. Callers wrap
+//! the message in their own error type.
+
+use std::ffi::OsString;
+use std::path::Path;
+use std::process::Command;
+
+/// Run git, scoped to the repository whose work tree is `dir` when given
+///
+/// The repository is named explicitly rather than discovered, so a command
+/// never reaches a repository that encloses `dir`. Without `dir`, git runs
+/// outside any repository, as for `clone` or `ls-remote`.
+pub(crate) fn git_in(dir: Option<&Path>, args: &[&str]) -> Result<String, String> {
+    let mut cmd = Command::new("git");
+    if let Some(dir) = dir {
+        let mut git_dir = OsString::from("--git-dir=");
+        git_dir.push(dir.join(".git"));
+        let mut work_tree = OsString::from("--work-tree=");
+        work_tree.push(dir);
+        cmd.current_dir(dir).arg(git_dir).arg(work_tree);
+    }
+    run(cmd, args)
+}
+
+/// Run git from `dir`, letting it discover the repository `dir` belongs to
+pub(crate) fn git_discover(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let mut cmd = Command::new("git");
+    cmd.current_dir(dir);
+    run(cmd, args)
+}
+
+fn run(mut cmd: Command, args: &[&str]) -> Result<String, String> {
+    cmd.args(args)
+        // Never wait on a credential prompt
+        .env("GIT_TERMINAL_PROMPT", "0");
+    let output = cmd.output().map_err(|e| format!("could not be run: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "{} failed: {}",
+            args.first().unwrap_or(&""),
+            stderr.trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
@@ -28,11 +28,14 @@ pub mod config;
 pub mod crypto;
 pub mod diff;
 pub mod export;
+pub(crate) mod git;
 pub mod pricing;
 pub mod profile;
 pub mod project;
 pub mod skills;
+pub mod stats;
 pub mod storage;
+pub mod usage;
 pub mod util;
 
 pub use tars_scanner;
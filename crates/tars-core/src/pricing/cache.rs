@@ -10,6 +10,10 @@ use serde::Deserialize;
 
 use crate::storage::db::DatabaseError;
 
+/// Prompt size (input plus cache tokens) above which long-context tier
+/// prices apply, matching `LiteLLM`'s `*_above_200k_tokens` fields.
+pub const LONG_CONTEXT_THRESHOLD_TOKENS: u64 = 200_000;
+
 /// Sentinel keys used in the `pricing_metadata` table.
 pub const METADATA_KEY_LAST_REFRESH: &str = "last_refresh";
 pub const METADATA_KEY_LAST_ERROR: &str = "last_error";
@@ -25,6 +29,50 @@ pub struct PriceUpdateRow {
     /// untouched so provider-reported context windows (when available) are
     /// not clobbered by a `LiteLLM` entry that happens to omit it.
     pub context_window: Option<u32>,
+    pub cache_read_price: Option<f64>,
+    pub cache_write_price: Option<f64>,
+    pub input_price_above_200k: Option<f64>,
+    pub output_price_above_200k: Option<f64>,
+    pub cache_read_price_above_200k: Option<f64>,
+    pub cache_write_price_above_200k: Option<f64>,
+}
+
+/// Per-1M-token prices for one model, one field per priced dimension.
+///
+/// Used both for the fetched columns of a `provider_models` row and for the
+/// shape of `price_override_json`, so an override may set any subset of
+/// these keys (e.g. `{"cache_read": 0.3}`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct PriceColumns {
+    pub input: Option<f64>,
+    pub output: Option<f64>,
+    pub cache_read: Option<f64>,
+    pub cache_write: Option<f64>,
+    pub input_above_200k: Option<f64>,
+    pub output_above_200k: Option<f64>,
+    pub cache_read_above_200k: Option<f64>,
+    pub cache_write_above_200k: Option<f64>,
+}
+
+impl PriceColumns {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Field-wise merge: values set in `over` win, the rest come from `self`.
+    fn overlay(self, over: Self) -> Self {
+        Self {
+            input: over.input.or(self.input),
+            output: over.output.or(self.output),
+            cache_read: over.cache_read.or(self.cache_read),
+            cache_write: over.cache_write.or(self.cache_write),
+            input_above_200k: over.input_above_200k.or(self.input_above_200k),
+            output_above_200k: over.output_above_200k.or(self.output_above_200k),
+            cache_read_above_200k: over.cache_read_above_200k.or(self.cache_read_above_200k),
+            cache_write_above_200k: over.cache_write_above_200k.or(self.cache_write_above_200k),
+        }
+    }
 }
 
 /// Effective per-1M-token price displayed to the user.
@@ -32,9 +80,92 @@ pub struct PriceUpdateRow {
 pub struct EffectivePrice {
     pub input: Option<f64>,
     pub output: Option<f64>,
+    pub cache_read: Option<f64>,
+    pub cache_write: Option<f64>,
+    pub input_above_200k: Option<f64>,
+    pub output_above_200k: Option<f64>,
+    pub cache_read_above_200k: Option<f64>,
+    pub cache_write_above_200k: Option<f64>,
     pub is_overridden: bool,
 }
 
+impl EffectivePrice {
+    fn from_columns(c: PriceColumns, is_overridden: bool) -> Self {
+        Self {
+            input: c.input,
+            output: c.output,
+            cache_read: c.cache_read,
+            cache_write: c.cache_write,
+            input_above_200k: c.input_above_200k,
+            output_above_200k: c.output_above_200k,
+            cache_read_above_200k: c.cache_read_above_200k,
+            cache_write_above_200k: c.cache_write_above_200k,
+            is_overridden,
+        }
+    }
+
+    /// Base-tier prices for a request whose prompt (input plus cache read
+    /// and write tokens) is `prompt_tokens` long.
+    ///
+    /// Above [`LONG_CONTEXT_THRESHOLD_TOKENS`] each published long-context
+    /// rate replaces its base rate; dimensions without a tier rate keep the
+    /// base one.
+    #[must_use]
+    pub fn for_prompt_tokens(self, prompt_tokens: u64) -> Self {
+        if prompt_tokens <= LONG_CONTEXT_THRESHOLD_TOKENS {
+            return self;
+        }
+        Self {
+            input: self.input_above_200k.or(self.input),
+            output: self.output_above_200k.or(self.output),
+            cache_read: self.cache_read_above_200k.or(self.cache_read),
+            cache_write: self.cache_write_above_200k.or(self.cache_write),
+            ..self
+        }
+    }
+}
+
+/// Token counts of a single priced request, one field per priced dimension.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RequestTokens {
+    /// Prompt tokens not read from or written to the cache
+    pub input: u64,
+    pub output: u64,
+    pub cache_read: u64,
+    pub cache_write: u64,
+}
+
+impl RequestTokens {
+    fn prompt(self) -> u64 {
+        self.input + self.cache_read + self.cache_write
+    }
+}
+
+impl EffectivePrice {
+    /// USD cost of one request, with the long-context tier applied when its
+    /// prompt crosses [`LONG_CONTEXT_THRESHOLD_TOKENS`].
+    ///
+    /// Returns `None` without both input and output rates. Cache dimensions
+    /// without a published rate are billed at the input rate — an upper
+    /// bound for reads, close enough for writes on a verification request.
+    #[must_use]
+    pub fn request_cost(self, tokens: RequestTokens) -> Option<f64> {
+        let price = self.for_prompt_tokens(tokens.prompt());
+        let (input, output) = (price.input?, price.output?);
+        let per_token = |count: u64, rate: f64| {
+            #[allow(clippy::cast_precision_loss)]
+            let count = count as f64;
+            count * rate / 1_000_000.0
+        };
+        Some(
+            per_token(tokens.input, input)
+                + per_token(tokens.output, output)
+                + per_token(tokens.cache_read, price.cache_read.unwrap_or(input))
+                + per_token(tokens.cache_write, price.cache_write.unwrap_or(input)),
+        )
+    }
+}
+
 /// One row from `pricing_metadata`.
 #[derive(Debug, Clone, PartialEq)]
 pub struct PricingMetadata {
@@ -43,17 +174,9 @@ pub struct PricingMetadata {
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize)]
-struct PriceOverride {
-    #[serde(default)]
-    input: Option<f64>,
-    #[serde(default)]
-    output: Option<f64>,
-}
-
 /// Update fetched prices for the supplied rows.
 ///
-/// Only `input_price` and `output_price` are written; rows missing from
+/// Only the price columns are written; rows missing from
 /// `provider_models` are skipped (model isn't currently cached for that
 /// provider — likely not yet refreshed via the model-list discovery flow).
 /// `price_override_json` is never touched, so user overrides survive.
@@ -75,12 +198,24 @@ pub fn update_prices(conn: &Connection, rows: &[PriceUpdateRow]) -> Result<usize
             "UPDATE provider_models
              SET input_price = ?1,
                  output_price = ?2,
-                 context_window = COALESCE(?3, context_window)
-             WHERE provider_id = ?4 AND model_id = ?5",
+                 context_window = COALESCE(?3, context_window),
+                 cache_read_price = ?4,
+                 cache_write_price = ?5,
+                 input_price_above_200k = ?6,
+                 output_price_above_200k = ?7,
+                 cache_read_price_above_200k = ?8,
+                 cache_write_price_above_200k = ?9
+             WHERE provider_id = ?10 AND model_id = ?11",
             params![
                 row.input_price,
                 row.output_price,
                 row.context_window,
+                row.cache_read_price,
+                row.cache_write_price,
+                row.input_price_above_200k,
+                row.output_price_above_200k,
+                row.cache_read_price_above_200k,
+                row.cache_write_price_above_200k,
                 row.provider_id,
                 row.model_id
             ],
@@ -155,30 +290,59 @@ pub fn set_metadata(
 
 /// Resolve the price the UI should display for one cached row.
 ///
-/// When `override_json` is `Some` and parses to a `{"input":?, "output":?}`
-/// shape, those values win — partial overrides (only one side set) fall back
-/// to the fetched value for the other side. When parsing fails or both
-/// override values are missing, the fetched values pass through unchanged.
-pub fn effective_price_for(
-    input_price: Option<f64>,
-    output_price: Option<f64>,
-    override_json: Option<&str>,
-) -> EffectivePrice {
-    let override_parsed = override_json.and_then(|s| serde_json::from_str::<PriceOverride>(s).ok());
+/// When `override_json` is `Some` and parses to a [`PriceColumns`] shape
+/// (e.g. `{"input":?, "output":?, "cache_read":?}`), those values win —
+/// partial overrides fall back to the fetched value for every dimension they
+/// leave unset. When parsing fails or no override value is set, the fetched
+/// values pass through unchanged.
+pub fn effective_price_for(fetched: PriceColumns, override_json: Option<&str>) -> EffectivePrice {
+    let override_parsed = override_json.and_then(|s| serde_json::from_str::<PriceColumns>(s).ok());
     match override_parsed {
-        Some(o) if o.input.is_some() || o.output.is_some() => EffectivePrice {
-            input: o.input.or(input_price),
-            output: o.output.or(output_price),
-            is_overridden: true,
-        },
-        _ => EffectivePrice {
-            input: input_price,
-            output: output_price,
-            is_overridden: false,
-        },
+        Some(o) if !o.is_empty() => EffectivePrice::from_columns(fetched.overlay(o), true),
+        _ => EffectivePrice::from_columns(fetched, false),
     }
 }
 
+/// Effective price of one cached `(provider_id, model_id)` row.
+///
+/// Returns `Ok(None)` when the model has never been cached for the provider.
+///
+/// # Errors
+/// Returns `DatabaseError` on query failure.
+pub fn load_effective_price(
+    conn: &Connection,
+    provider_id: &str,
+    model_id: &str,
+) -> Result<Option<EffectivePrice>, DatabaseError> {
+    let row = conn
+        .query_row(
+            r"
+            SELECT input_price, output_price, cache_read_price, cache_write_price,
+                   input_price_above_200k, output_price_above_200k,
+                   cache_read_price_above_200k, cache_write_price_above_200k,
+                   price_override_json
+            FROM provider_models
+            WHERE provider_id = ?1 AND model_id = ?2
+            ",
+            params![provider_id, model_id],
+            |row| {
+                let fetched = PriceColumns {
+                    input: row.get(0)?,
+                    output: row.get(1)?,
+                    cache_read: row.get(2)?,
+                    cache_write: row.get(3)?,
+                    input_above_200k: row.get(4)?,
+                    output_above_200k: row.get(5)?,
+                    cache_read_above_200k: row.get(6)?,
+                    cache_write_above_200k: row.get(7)?,
+                };
+                Ok((fetched, row.get::<_, Option<String>>(8)?))
+            },
+        )
+        .optional()?;
+    Ok(row.map(|(fetched, override_json)| effective_price_for(fetched, override_json.as_deref())))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -194,6 +358,30 @@ mod tests {
         .unwrap();
     }
 
+    fn row(provider: &str, model: &str) -> PriceUpdateRow {
+        PriceUpdateRow {
+            provider_id: provider.into(),
+            model_id: model.into(),
+            input_price: 0.0,
+            output_price: 0.0,
+            context_window: None,
+            cache_read_price: None,
+            cache_write_price: None,
+            input_price_above_200k: None,
+            output_price_above_200k: None,
+            cache_read_price_above_200k: None,
+            cache_write_price_above_200k: None,
+        }
+    }
+
+    fn fetched(input: f64, output: f64) -> PriceColumns {
+        PriceColumns {
+            input: Some(input),
+            output: Some(output),
+            ..PriceColumns::default()
+        }
+    }
+
     fn read_prices(conn: &Connection, provider: &str, model: &str) -> (Option<f64>, Option<f64>) {
         conn.query_row(
             "SELECT input_price, output_price FROM provider_models
@@ -218,6 +406,7 @@ mod tests {
                 input_price: 2.5,
                 output_price: 10.0,
                 context_window: Some(128_000),
+                ..row("openai", "gpt-4o")
             }],
         )
         .unwrap();
@@ -243,6 +432,7 @@ mod tests {
                     input_price: 2.5,
                     output_price: 10.0,
                     context_window: None,
+                    ..row("openai", "gpt-4o")
                 },
                 PriceUpdateRow {
                     provider_id: "openai".into(),
@@ -250,6 +440,7 @@ mod tests {
                     input_price: 1.0,
                     output_price: 2.0,
                     context_window: None,
+                    ..row("openai", "gpt-4o")
                 },
             ],
         )
@@ -279,6 +470,7 @@ mod tests {
                 input_price: 2.5,
                 output_price: 10.0,
                 context_window: None,
+                ..row("openai", "gpt-4o")
             }],
         )
         .unwrap();
@@ -333,7 +525,7 @@ mod tests {
 
     #[test]
     fn effective_price_returns_fetched_when_no_override() {
-        let r = effective_price_for(Some(2.5), Some(10.0), None);
+        let r = effective_price_for(fetched(2.5, 10.0), None);
         assert_eq!(r.input, Some(2.5));
         assert_eq!(r.output, Some(10.0));
         assert!(!r.is_overridden);
@@ -341,7 +533,7 @@ mod tests {
 
     #[test]
     fn effective_price_full_override_wins() {
-        let r = effective_price_for(Some(2.5), Some(10.0), Some(r#"{"input":1.0,"output":3.0}"#));
+        let r = effective_price_for(fetched(2.5, 10.0), Some(r#"{"input":1.0,"output":3.0}"#));
         assert_eq!(r.input, Some(1.0));
         assert_eq!(r.output, Some(3.0));
         assert!(r.is_overridden);
@@ -349,7 +541,7 @@ mod tests {
 
     #[test]
     fn effective_price_partial_override_falls_back_to_fetched() {
-        let r = effective_price_for(Some(2.5), Some(10.0), Some(r#"{"input":1.0}"#));
+        let r = effective_price_for(fetched(2.5, 10.0), Some(r#"{"input":1.0}"#));
         assert_eq!(r.input, Some(1.0));
         assert_eq!(r.output, Some(10.0));
         assert!(r.is_overridden);
@@ -357,7 +549,7 @@ mod tests {
 
     #[test]
     fn effective_price_invalid_json_falls_back() {
-        let r = effective_price_for(Some(2.5), Some(10.0), Some("not json"));
+        let r = effective_price_for(fetched(2.5, 10.0), Some("not json"));
         assert_eq!(r.input, Some(2.5));
         assert_eq!(r.output, Some(10.0));
         assert!(!r.is_overridden);
@@ -365,12 +557,139 @@ mod tests {
 
     #[test]
     fn effective_price_empty_override_object_is_not_overridden() {
-        let r = effective_price_for(Some(2.5), Some(10.0), Some("{}"));
+        let r = effective_price_for(fetched(2.5, 10.0), Some("{}"));
         assert_eq!(r.input, Some(2.5));
         assert_eq!(r.output, Some(10.0));
         assert!(!r.is_overridden);
     }
 
+    #[test]
+    fn update_prices_writes_cache_and_tier_columns() {
+        let db = Database::in_memory().unwrap();
+        let conn = db.connection();
+        seed_model(conn, "anthropic", "claude-sonnet-4-5");
+
+        update_prices(
+            conn,
+            &[PriceUpdateRow {
+                input_price: 3.0,
+                output_price: 15.0,
+                cache_read_price: Some(0.3),
+                cache_write_price: Some(3.75),
+                input_price_above_200k: Some(6.0),
+                ..row("anthropic", "claude-sonnet-4-5")
+            }],
+        )
+        .unwrap();
+
+        let (read, write, tier): (Option<f64>, Option<f64>, Option<f64>) = conn
+            .query_row(
+                "SELECT cache_read_price, cache_write_price, input_price_above_200k
+                 FROM provider_models WHERE model_id = 'claude-sonnet-4-5'",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(read, Some(0.3));
+        assert_eq!(write, Some(3.75));
+        assert_eq!(tier, Some(6.0));
+    }
+
+    #[test]
+    fn effective_price_overrides_cache_dimension_only() {
+        let base = PriceColumns {
+            cache_read: Some(0.3),
+            ..fetched(3.0, 15.0)
+        };
+        let r = effective_price_for(base, Some(r#"{"cache_read":0.1}"#));
+        assert_eq!(r.input, Some(3.0));
+        assert_eq!(r.cache_read, Some(0.1));
+        assert!(r.is_overridden);
+    }
+
+    #[test]
+    fn for_prompt_tokens_switches_to_long_context_tier() {
+        let r = effective_price_for(
+            PriceColumns {
+                input_above_200k: Some(6.0),
+                cache_read: Some(0.3),
+                ..fetched(3.0, 15.0)
+            },
+            None,
+        );
+        let short = r.for_prompt_tokens(LONG_CONTEXT_THRESHOLD_TOKENS);
+        assert_eq!(short.input, Some(3.0));
+
+        let long = r.for_prompt_tokens(LONG_CONTEXT_THRESHOLD_TOKENS + 1);
+        assert_eq!(long.input, Some(6.0));
+        // No published tier rate: the base rate carries over.
+        assert_eq!(long.output, Some(15.0));
+        assert_eq!(long.cache_read, Some(0.3));
+    }
+
+    #[test]
+    fn request_cost_prices_each_dimension_and_applies_tier() {
+        let price = effective_price_for(
+            PriceColumns {
+                cache_read: Some(0.3),
+                input_above_200k: Some(6.0),
+                ..fetched(3.0, 15.0)
+            },
+            None,
+        );
+        let short = RequestTokens {
+            input: 100_000,
+            output: 10_000,
+            cache_read: 50_000,
+            // No cache-write rate: billed at the input rate.
+            cache_write: 10_000,
+        };
+        let cost = price.request_cost(short).unwrap();
+        assert!((cost - (0.3 + 0.15 + 0.015 + 0.03)).abs() < 1e-9, "{cost}");
+
+        let long = RequestTokens {
+            input: 250_000,
+            ..RequestTokens::default()
+        };
+        let cost = price.request_cost(long).unwrap();
+        assert!((cost - 1.5).abs() < 1e-9, "long-context input rate: {cost}");
+
+        let unpriced = effective_price_for(PriceColumns::default(), None);
+        assert!(unpriced.request_cost(short).is_none());
+    }
+
+    #[test]
+    fn load_effective_price_applies_override() {
+        let db = Database::in_memory().unwrap();
+        let conn = db.connection();
+        seed_model(conn, "openai", "gpt-4o-mini");
+        update_prices(
+            conn,
+            &[PriceUpdateRow {
+                input_price: 0.15,
+                output_price: 0.6,
+                ..row("openai", "gpt-4o-mini")
+            }],
+        )
+        .unwrap();
+        conn.execute(
+            "UPDATE provider_models SET price_override_json = '{\"output\": 1.0}'
+             WHERE model_id = 'gpt-4o-mini'",
+            [],
+        )
+        .unwrap();
+
+        let price = load_effective_price(conn, "openai", "gpt-4o-mini")
+            .unwrap()
+            .unwrap();
+        assert_eq!(price.input, Some(0.15));
+        assert_eq!(price.output, Some(1.0));
+        assert!(price.is_overridden);
+        assert!(load_effective_price(conn, "openai", "gpt-5")
+            .unwrap()
+            .is_none());
+    }
+
     #[test]
     fn delete_metadata_removes_row() {
         let db = Database::in_memory().unwrap();
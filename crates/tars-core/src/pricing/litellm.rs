@@ -7,6 +7,12 @@
 //! `provider_id` strings (matching `tars_providers::ProviderId::as_str`) and
 //! convert per-token prices to per-1M-token to match the existing UI columns.
 //!
+//! Besides the base input/output rates, entries may carry prompt-caching
+//! rates (`cache_read_input_token_cost`, `cache_creation_input_token_cost`)
+//! and long-context tiers (`*_above_200k_tokens`). These are optional: a
+//! missing or malformed value leaves the dimension `None` without dropping
+//! the entry.
+//!
 //! The parser is defensive: malformed entries, unknown providers, and the
 //! `sample_spec` placeholder key are all silently skipped instead of failing
 //! the whole import.
@@ -33,6 +39,18 @@ pub struct ParsedPrice {
     /// `max_input_tokens` (preferred) or `max_tokens` (fallback). `None` when
     /// both are missing or not representable as `u32`.
     pub context_window: Option<u32>,
+    /// USD per 1M prompt-cache read tokens.
+    pub cache_read_price: Option<f64>,
+    /// USD per 1M prompt-cache write (creation) tokens.
+    pub cache_write_price: Option<f64>,
+    /// USD per 1M input tokens once the prompt exceeds 200k tokens.
+    pub input_price_above_200k: Option<f64>,
+    /// USD per 1M output tokens once the prompt exceeds 200k tokens.
+    pub output_price_above_200k: Option<f64>,
+    /// USD per 1M cache read tokens once the prompt exceeds 200k tokens.
+    pub cache_read_price_above_200k: Option<f64>,
+    /// USD per 1M cache write tokens once the prompt exceeds 200k tokens.
+    pub cache_write_price_above_200k: Option<f64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -47,6 +65,33 @@ struct RawEntry {
     max_input_tokens: Option<u64>,
     #[serde(default)]
     max_tokens: Option<u64>,
+    #[serde(default, deserialize_with = "lenient_cost")]
+    cache_read_input_token_cost: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_cost")]
+    cache_creation_input_token_cost: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_cost")]
+    input_cost_per_token_above_200k_tokens: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_cost")]
+    output_cost_per_token_above_200k_tokens: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_cost")]
+    cache_read_input_token_cost_above_200k_tokens: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_cost")]
+    cache_creation_input_token_cost_above_200k_tokens: Option<f64>,
+}
+
+/// Decode an optional per-token cost, treating anything that is not a finite
+/// number as absent so one odd field never drops the whole entry.
+fn lenient_cost<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = Option::<serde_json::Value>::deserialize(deserializer)?;
+    Ok(value.and_then(|v| v.as_f64()).filter(|n| n.is_finite()))
+}
+
+/// Convert an optional USD-per-token cost into USD per 1M tokens.
+fn per_million(cost: Option<f64>) -> Option<f64> {
+    cost.map(|c| c * 1_000_000.0)
 }
 
 /// Map a `LiteLLM` `litellm_provider` value to our internal provider id.
@@ -63,6 +108,11 @@ fn map_provider(litellm_provider: &str) -> Option<&'static str> {
         // provider id.
         "gemini" => Some("gemini"),
         "deepseek" => Some("deepseek"),
+        "groq" => Some("groq"),
+        "mistral" => Some("mistral"),
+        "xai" => Some("xai"),
+        "openrouter" => Some("openrouter"),
+        "perplexity" => Some("perplexity"),
         _ => None,
     }
 }
@@ -73,12 +123,21 @@ fn map_provider(litellm_provider: &str) -> Option<&'static str> {
 /// (`openai/gpt-4o`, `anthropic/claude-3-5-sonnet-latest`). The provider
 /// model lists fetched by `tars_providers` always store the bare id, so we
 /// normalise to that form here.
+///
+/// `OpenRouter` ids are themselves namespaced (`anthropic/claude-sonnet-4`),
+/// and `LiteLLM` keys them as `openrouter/anthropic/claude-sonnet-4`; only
+/// the leading `openrouter/` is stripped so the vendor segment survives.
 fn normalise_model_id<'a>(provider_id: &str, raw_key: &'a str) -> &'a str {
     let prefix_with_slash = match provider_id {
         "openai" => "openai/",
         "anthropic" => "anthropic/",
         "gemini" => "gemini/",
         "deepseek" => "deepseek/",
+        "groq" => "groq/",
+        "mistral" => "mistral/",
+        "xai" => "xai/",
+        "openrouter" => "openrouter/",
+        "perplexity" => "perplexity/",
         _ => return raw_key,
     };
     raw_key.strip_prefix(prefix_with_slash).unwrap_or(raw_key)
@@ -141,6 +200,16 @@ pub fn parse_litellm_prices(raw: &str) -> Result<Vec<ParsedPrice>, serde_json::E
                 input_price: input_per_token * 1_000_000.0,
                 output_price: output_per_token * 1_000_000.0,
                 context_window,
+                cache_read_price: per_million(entry.cache_read_input_token_cost),
+                cache_write_price: per_million(entry.cache_creation_input_token_cost),
+                input_price_above_200k: per_million(entry.input_cost_per_token_above_200k_tokens),
+                output_price_above_200k: per_million(entry.output_cost_per_token_above_200k_tokens),
+                cache_read_price_above_200k: per_million(
+                    entry.cache_read_input_token_cost_above_200k_tokens,
+                ),
+                cache_write_price_above_200k: per_million(
+                    entry.cache_creation_input_token_cost_above_200k_tokens,
+                ),
             },
         );
     }
@@ -176,6 +245,17 @@ mod tests {
                 "input_cost_per_token": 0.000003,
                 "output_cost_per_token": 0.000015
             },
+            "claude-sonnet-4-5": {
+                "litellm_provider": "anthropic",
+                "input_cost_per_token": 0.000003,
+                "output_cost_per_token": 0.000015,
+                "cache_read_input_token_cost": 0.0000003,
+                "cache_creation_input_token_cost": 0.00000375,
+                "input_cost_per_token_above_200k_tokens": 0.000006,
+                "output_cost_per_token_above_200k_tokens": 0.0000225,
+                "cache_read_input_token_cost_above_200k_tokens": 0.0000006,
+                "cache_creation_input_token_cost_above_200k_tokens": "n/a"
+            },
             "gemini-1.5-pro": {
                 "litellm_provider": "gemini",
                 "input_cost_per_token": 0.00000125,
@@ -186,6 +266,33 @@ mod tests {
                 "input_cost_per_token": 0.00000027,
                 "output_cost_per_token": 0.0000011
             },
+            "groq/llama-3.3-70b-versatile": {
+                "litellm_provider": "groq",
+                "input_cost_per_token": 0.00000059,
+                "output_cost_per_token": 0.00000079,
+                "max_tokens": 8192,
+                "max_input_tokens": 128000
+            },
+            "mistral/mistral-large-latest": {
+                "litellm_provider": "mistral",
+                "input_cost_per_token": 0.000002,
+                "output_cost_per_token": 0.000006
+            },
+            "xai/grok-3-mini": {
+                "litellm_provider": "xai",
+                "input_cost_per_token": 0.0000003,
+                "output_cost_per_token": 0.0000005
+            },
+            "openrouter/anthropic/claude-3.5-sonnet": {
+                "litellm_provider": "openrouter",
+                "input_cost_per_token": 0.000003,
+                "output_cost_per_token": 0.000015
+            },
+            "perplexity/sonar-pro": {
+                "litellm_provider": "perplexity",
+                "input_cost_per_token": 0.000003,
+                "output_cost_per_token": 0.000015
+            },
             "vertex_ai/gemini-1.5-pro": {
                 "litellm_provider": "vertex_ai-gemini",
                 "input_cost_per_token": 0.00000125,
@@ -214,6 +321,25 @@ mod tests {
         assert!(providers.contains(&"anthropic"));
         assert!(providers.contains(&"gemini"));
         assert!(providers.contains(&"deepseek"));
+        for extra in ["groq", "mistral", "xai", "openrouter", "perplexity"] {
+            assert!(providers.contains(&extra), "{extra} should be mapped");
+        }
+    }
+
+    #[test]
+    fn strips_prefix_for_openai_compatible_providers() {
+        let prices = parse_litellm_prices(fixture()).unwrap();
+        let has = |provider: &str, model: &str| {
+            prices
+                .iter()
+                .any(|p| p.provider_id == provider && p.model_id == model)
+        };
+        assert!(has("groq", "llama-3.3-70b-versatile"));
+        assert!(has("mistral", "mistral-large-latest"));
+        assert!(has("xai", "grok-3-mini"));
+        assert!(has("perplexity", "sonar-pro"));
+        // Only the `openrouter/` namespace goes; the vendor segment stays.
+        assert!(has("openrouter", "anthropic/claude-3.5-sonnet"));
     }
 
     #[test]
@@ -254,6 +380,27 @@ mod tests {
         assert!((gpt4o.output_price - 10.0).abs() < 1e-9);
     }
 
+    #[test]
+    fn parses_cache_and_long_context_prices() {
+        let prices = parse_litellm_prices(fixture()).unwrap();
+        let sonnet = prices
+            .iter()
+            .find(|p| p.provider_id == "anthropic" && p.model_id == "claude-sonnet-4-5")
+            .expect("claude-sonnet-4-5 present");
+        let close = |v: Option<f64>, want: f64| (v.unwrap() - want).abs() < 1e-9;
+        assert!(close(sonnet.cache_read_price, 0.3));
+        assert!(close(sonnet.cache_write_price, 3.75));
+        assert!(close(sonnet.input_price_above_200k, 6.0));
+        assert!(close(sonnet.output_price_above_200k, 22.5));
+        assert!(close(sonnet.cache_read_price_above_200k, 0.6));
+        // A malformed optional rate is dropped without losing the entry.
+        assert_eq!(sonnet.cache_write_price_above_200k, None);
+
+        let gpt4o = prices.iter().find(|p| p.model_id == "gpt-4o").unwrap();
+        assert_eq!(gpt4o.cache_read_price, None);
+        assert_eq!(gpt4o.input_price_above_200k, None);
+    }
+
     #[test]
     fn strips_provider_prefix_in_keys() {
         let prices = parse_litellm_prices(fixture()).unwrap();
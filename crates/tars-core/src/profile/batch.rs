@@ -0,0 +1,409 @@
+//! Applying a profile to every project assigned to it
+//!
+//! [`plan_batch`] resolves the profile once and generates a diff plan for
+//! each project from [`ProjectStore::list_by_profile`], with an aggregate
+//! [`DiffSummary`] for review. [`apply_batch`] applies the plans in parallel,
+//! backing each project up first, and records the outcome as an
+//! [`ApplyBatch`]. A project that fails is restored from its partial backup
+//! and does not stop the others; if that restore fails too, the entry says so
+//! and keeps the backup, so rolling the batch back retries the restore.
+//!
+//! [`rollback_batch`] restores every project the batch changed from the
+//! backups it recorded.
+
+use crate::apply::apply_operations;
+use crate::backup::restore::{restore_from_backup, verify_backup_integrity};
+use crate::backup::Backup;
+use crate::diff::display::DiffSummary;
+use crate::diff::plan::generate_plan_with_context;
+use crate::diff::DiffPlan;
+use crate::profile::lock::{preview_lock_plan, LOCK_FILE};
+use crate::profile::resolve::{resolve_profile, ResolveError};
+use crate::profile::TemplateContext;
+use crate::project::Project;
+use crate::storage::apply_batches::{
+    ApplyBatch, ApplyBatchEntry, ApplyBatchStore, BatchProjectStatus,
+};
+use crate::storage::backups::BackupStore;
+use crate::storage::db::DatabaseError;
+use crate::storage::metadata::MetadataStore;
+use crate::storage::profiles::ProfileStore;
+use crate::storage::projects::ProjectStore;
+use chrono::Utc;
+use rayon::prelude::*;
+use rusqlite::Connection;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Errors while planning, applying or rolling back a batch
+#[derive(Error, Debug)]
+pub enum BatchError {
+    #[error("Profile not found: {0}")]
+    ProfileNotFound(Uuid),
+
+    #[error("Batch not found: {0}")]
+    NotFound(Uuid),
+
+    #[error("Batch {0} was already rolled back")]
+    AlreadyRolledBack(Uuid),
+
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+
+    #[error("Resolve error: {0}")]
+    Resolve(#[from] ResolveError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// The plan for one project in a batch
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectPlan {
+    pub project: Project,
+    /// `None` when the plan could not be generated
+    pub plan: Option<DiffPlan>,
+    pub summary: DiffSummary,
+    /// Why the plan could not be generated
+    pub error: Option<String>,
+}
+
+impl ProjectPlan {
+    /// Whether applying this project would change files
+    #[must_use]
+    pub fn has_changes(&self) -> bool {
+        self.plan.as_ref().is_some_and(|p| !p.is_empty())
+    }
+}
+
+/// Plans for every project assigned to a profile
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchPlan {
+    pub profile_id: Uuid,
+    pub profile_name: String,
+    /// Projects ordered by name
+    pub projects: Vec<ProjectPlan>,
+    /// Changes across all projects
+    pub summary: DiffSummary,
+}
+
+impl BatchPlan {
+    /// Projects the batch would change
+    #[must_use]
+    pub fn changed(&self) -> usize {
+        self.projects.iter().filter(|p| p.has_changes()).count()
+    }
+
+    /// Projects whose plan could not be generated
+    #[must_use]
+    pub fn failed(&self) -> usize {
+        self.projects.iter().filter(|p| p.error.is_some()).count()
+    }
+}
+
+/// A project a rollback could not restore
+#[derive(Debug, Clone, Serialize)]
+pub struct RollbackFailure {
+    pub project_id: Uuid,
+    pub project_name: String,
+    pub error: String,
+}
+
+/// Outcome of rolling back a batch
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchRollback {
+    pub batch_id: Uuid,
+    /// Names of the projects restored
+    pub restored: Vec<String>,
+    pub failures: Vec<RollbackFailure>,
+}
+
+/// Generate plans for every project assigned to a profile
+///
+/// The profile is resolved once; each project gets its own template context,
+/// and projects with a lock file get their lock refreshed as for a single
+/// apply. A project whose plan cannot be generated is reported rather than
+/// failing the batch.
+///
+/// # Errors
+/// Returns an error if the profile does not exist or cannot be resolved
+pub fn plan_batch(conn: &Connection, profile_id: Uuid) -> Result<BatchPlan, BatchError> {
+    let profiles = ProfileStore::new(conn);
+    let profile = profiles
+        .get(profile_id)?
+        .ok_or(BatchError::ProfileNotFound(profile_id))?;
+    let resolved = resolve_profile(&profiles, &profile)?;
+    let metadata = MetadataStore::new(conn);
+
+    let mut projects = ProjectStore::new(conn).list_by_profile(profile_id)?;
+    projects.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut summary = DiffSummary::default();
+    let mut plans = Vec::with_capacity(projects.len());
+    for project in projects {
+        let planned = if project.path.is_dir() {
+            metadata
+                .get(project.id)
+                .map_err(|e| e.to_string())
+                .and_then(|meta| {
+                    let context = TemplateContext::for_project(&project, meta);
+                    let mut plan = generate_plan_with_context(
+                        project.id,
+                        &project.path,
+                        &resolved.profile,
+                        &context,
+                    )
+                    .map_err(|e| e.to_string())?;
+                    if project.path.join(LOCK_FILE).exists() {
+                        preview_lock_plan(conn, &project.path, &resolved, &mut plan)
+                            .map_err(|e| e.to_string())?;
+                    }
+                    Ok(plan)
+                })
+        } else {
+            Err(format!(
+                "Project directory does not exist: {}",
+                project.path.display()
+            ))
+        };
+
+        let entry = match planned {
+            Ok(plan) => {
+                let project_summary = DiffSummary::from_plan(&plan);
+                summary.merge(&project_summary);
+                ProjectPlan {
+                    project,
+                    plan: Some(plan),
+                    summary: project_summary,
+                    error: None,
+                }
+            }
+            Err(error) => ProjectPlan {
+                project,
+                plan: None,
+                summary: DiffSummary::default(),
+                error: Some(error),
+            },
+        };
+        plans.push(entry);
+    }
+
+    Ok(BatchPlan {
+        profile_id,
+        profile_name: profile.name,
+        projects: plans,
+        summary,
+    })
+}
+
+/// Apply a batch plan to its projects in parallel and record the batch
+///
+/// Every project that changes is backed up to its own archive in
+/// `backup_dir`. The returned batch lists each project's outcome; its ID
+/// rolls back all of them with [`rollback_batch`].
+///
+/// # Errors
+/// Returns an error if the backup directory cannot be created or the batch
+/// cannot be recorded
+pub fn apply_batch(
+    conn: &Connection,
+    plan: &BatchPlan,
+    backup_dir: &Path,
+) -> Result<ApplyBatch, BatchError> {
+    fs::create_dir_all(backup_dir)?;
+    // Planned locks name the profile's current revision; record it now
+    let profiles = ProfileStore::new(conn);
+    if let Some(profile) = profiles.get(plan.profile_id)? {
+        profiles.record_revision(&profile)?;
+    }
+    let mut batch = ApplyBatch::new(plan.profile_id, plan.profile_name.clone());
+
+    let outcomes: Vec<_> = plan
+        .projects
+        .par_iter()
+        .map(|project| apply_project(project, &batch, backup_dir))
+        .collect();
+
+    // Writes to the database stay on this thread
+    let backups = BackupStore::new(conn);
+    for (item, (mut entry, backup)) in plan.projects.iter().zip(outcomes) {
+        if let Some(backup) = backup {
+            if let Err(e) = backups.create(&backup) {
+                let error = format!("Failed to record backup: {e}");
+                entry.status = BatchProjectStatus::Failed;
+                entry.backup_id = None;
+                entry.error = Some(match undo(&item.project.path, &backup) {
+                    Ok(()) => error,
+                    Err(restore) => restore_failed(&error, &backup, &restore),
+                });
+            }
+        }
+        batch.entries.push(entry);
+    }
+
+    ApplyBatchStore::new(conn).create(&batch)?;
+    Ok(batch)
+}
+
+/// Restore every project a batch changed to its state before the batch
+///
+/// Projects are restored from the backups the batch recorded, including
+/// projects whose failed apply could not be undone at the time. The batch is
+/// marked rolled back only if every project was restored, so a rollback
+/// that partly failed can be retried.
+///
+/// # Errors
+/// Returns an error if the batch does not exist or was already rolled back
+pub fn rollback_batch(conn: &Connection, batch_id: Uuid) -> Result<BatchRollback, BatchError> {
+    let store = ApplyBatchStore::new(conn);
+    let batch = store.get(batch_id)?.ok_or(BatchError::NotFound(batch_id))?;
+    if batch.rolled_back_at.is_some() {
+        return Err(BatchError::AlreadyRolledBack(batch_id));
+    }
+
+    let backups = BackupStore::new(conn);
+    let projects = ProjectStore::new(conn);
+    let mut failures = Vec::new();
+    let mut restores = Vec::new();
+    for entry in &batch.entries {
+        let Some(backup_id) = entry.backup_id else {
+            continue;
+        };
+        let found = backups
+            .get(backup_id)?
+            .ok_or_else(|| format!("Backup not found: {backup_id}"))
+            .and_then(|backup| {
+                let project = projects
+                    .get(entry.project_id)
+                    .map_err(|e| e.to_string())?
+                    .ok_or_else(|| "Project no longer registered".to_string())?;
+                Ok((project.path, backup))
+            });
+        match found {
+            Ok((path, backup)) => restores.push((entry, path, backup)),
+            Err(error) => failures.push(RollbackFailure {
+                project_id: entry.project_id,
+                project_name: entry.project_name.clone(),
+                error,
+            }),
+        }
+    }
+
+    let results: Vec<_> = restores
+        .par_iter()
+        .map(|(entry, path, backup)| {
+            let result = verify_backup_integrity(backup)
+                .and_then(|()| restore_from_backup(path, backup))
+                .map_err(|e| e.to_string());
+            (*entry, result)
+        })
+        .collect();
+
+    let mut restored = Vec::new();
+    for (entry, result) in results {
+        match result {
+            Ok(()) => restored.push(entry.project_name.clone()),
+            Err(error) => failures.push(RollbackFailure {
+                project_id: entry.project_id,
+                project_name: entry.project_name.clone(),
+                error,
+            }),
+        }
+    }
+
+    if failures.is_empty() {
+        store.set_rolled_back(batch_id, Utc::now())?;
+    }
+    Ok(BatchRollback {
+        batch_id,
+        restored,
+        failures,
+    })
+}
+
+/// Apply one project's plan, returning its entry and the backup to record
+fn apply_project(
+    item: &ProjectPlan,
+    batch: &ApplyBatch,
+    backup_dir: &Path,
+) -> (ApplyBatchEntry, Option<Backup>) {
+    let mut entry = ApplyBatchEntry {
+        project_id: item.project.id,
+        project_name: item.project.name.clone(),
+        status: BatchProjectStatus::Unchanged,
+        operations: 0,
+        backup_id: None,
+        error: None,
+    };
+    if let Some(error) = &item.error {
+        entry.status = BatchProjectStatus::Failed;
+        entry.error = Some(error.clone());
+        return (entry, None);
+    }
+    let Some(plan) = item.plan.as_ref().filter(|p| !p.is_empty()) else {
+        return (entry, None);
+    };
+
+    // Projects are applied concurrently, so the timestamp alone is not unique
+    let archive_path = backup_dir.join(format!(
+        "backup-{}-{}.json",
+        Utc::now().format("%Y%m%d-%H%M%S"),
+        item.project.id
+    ));
+    let mut backup = Backup::new(item.project.id, archive_path.clone())
+        .with_profile(batch.profile_id)
+        .with_description(format!(
+            "Before applying profile '{}' (batch {})",
+            batch.profile_name, batch.id
+        ));
+
+    let result = apply_operations(plan, &item.project.path, &mut backup)
+        .map_err(|e| e.to_string())
+        .and_then(|()| serde_json::to_string_pretty(&backup).map_err(|e| e.to_string()))
+        .and_then(|json| fs::write(&archive_path, json).map_err(|e| e.to_string()));
+
+    match result {
+        Ok(()) => {
+            entry.status = BatchProjectStatus::Applied;
+            entry.operations = plan.operations.len();
+            entry.backup_id = Some(backup.id);
+            (entry, Some(backup))
+        }
+        Err(error) => {
+            entry.status = BatchProjectStatus::Failed;
+            let Err(restore) = undo(&item.project.path, &backup) else {
+                entry.error = Some(error);
+                return (entry, None);
+            };
+            entry.error = Some(restore_failed(&error, &backup, &restore));
+            // Keep the backup so rolling back the batch retries the restore
+            let kept = serde_json::to_string_pretty(&backup)
+                .map_err(|e| e.to_string())
+                .and_then(|json| fs::write(&archive_path, json).map_err(|e| e.to_string()));
+            if kept.is_err() {
+                return (entry, None);
+            }
+            entry.backup_id = Some(backup.id);
+            (entry, Some(backup))
+        }
+    }
+}
+
+/// Restore a project after a failed apply, discarding the partial backup
+/// once the project is restored
+fn undo(project_path: &Path, backup: &Backup) -> Result<(), String> {
+    restore_from_backup(project_path, backup).map_err(|e| e.to_string())?;
+    let _ = fs::remove_file(&backup.archive_path);
+    Ok(())
+}
+
+/// The entry error for an apply whose undo also failed
+fn restore_failed(error: &str, backup: &Backup, restore: &str) -> String {
+    format!(
+        "{error}; restoring the project also failed: {restore} (backup kept at {})",
+        backup.archive_path.display()
+    )
+}
@@ -0,0 +1,495 @@
+//! Structured comparison of two profiles
+//!
+//! Tools are gathered from a profile's overlays and from the files in its
+//! storage directory (see [`crate::profile::storage`]) and matched by kind and
+//! name. Tools present in both profiles with different content get a line
+//! diff. Plugins, marketplaces, adapter settings, the settings-file overlays
+//! and the stored `hooks.json` are compared as settings, each side described
+//! in a short string.
+
+use crate::diff::plan::generate_text_diff;
+use crate::profile::history::read_profile_files;
+use crate::profile::storage::{PluginManifest, StorageError};
+use crate::profile::types::{
+    Adapters, MarketplaceSourceRef, McpLocation, OverlayMode, PluginRef, Profile, SkillOverlay,
+};
+use crate::storage::profile_revisions::RevisionFiles;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+
+/// Kind of tool compared between profiles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompareToolKind {
+    Mcp,
+    Skill,
+    Agent,
+    Command,
+    UserSkill,
+    UserCommand,
+}
+
+impl fmt::Display for CompareToolKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Mcp => "mcp",
+            Self::Skill => "skill",
+            Self::Agent => "agent",
+            Self::Command => "command",
+            Self::UserSkill => "user skill",
+            Self::UserCommand => "user command",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A tool identified by kind and name
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ToolKey {
+    pub kind: CompareToolKind,
+    pub name: String,
+}
+
+/// A tool in both profiles with different content
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolDifference {
+    pub kind: CompareToolKind,
+    pub name: String,
+    /// Line diff from profile A to profile B
+    pub diff: String,
+}
+
+/// A setting that differs between the profiles
+///
+/// `a` and `b` describe the setting in each profile; `None` means the
+/// profile does not have it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SettingDifference {
+    pub setting: String,
+    pub a: Option<String>,
+    pub b: Option<String>,
+    /// Line diff for settings with longer content
+    pub diff: Option<String>,
+}
+
+/// Differences between two profiles
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileComparison {
+    pub a: String,
+    pub b: String,
+    pub only_in_a: Vec<ToolKey>,
+    pub only_in_b: Vec<ToolKey>,
+    pub differing: Vec<ToolDifference>,
+    /// Plugins and marketplaces
+    pub plugins: Vec<SettingDifference>,
+    pub adapters: Vec<SettingDifference>,
+    /// CLAUDE.md, hooks and permissions overlays, and stored hooks
+    pub overlays: Vec<SettingDifference>,
+}
+
+impl ProfileComparison {
+    /// Whether the profiles have no differences
+    #[must_use]
+    pub fn is_identical(&self) -> bool {
+        self.only_in_a.is_empty()
+            && self.only_in_b.is_empty()
+            && self.differing.is_empty()
+            && self.plugins.is_empty()
+            && self.adapters.is_empty()
+            && self.overlays.is_empty()
+    }
+}
+
+/// Compare two stored profiles, reading their storage directories
+///
+/// # Errors
+/// Returns an error if a storage directory cannot be read
+pub fn compare_stored_profiles(
+    a: &Profile,
+    b: &Profile,
+) -> Result<ProfileComparison, StorageError> {
+    let a_files = read_profile_files(a.id)?;
+    let b_files = read_profile_files(b.id)?;
+    Ok(compare_profiles(a, &a_files, b, &b_files))
+}
+
+/// Compare two profiles given the files of their storage directories
+#[must_use]
+pub fn compare_profiles(
+    a: &Profile,
+    a_files: &RevisionFiles,
+    b: &Profile,
+    b_files: &RevisionFiles,
+) -> ProfileComparison {
+    let a_tools = collect_tools(a, a_files);
+    let b_tools = collect_tools(b, b_files);
+
+    let mut only_in_a = Vec::new();
+    let mut differing = Vec::new();
+    for (key, a_content) in &a_tools {
+        match b_tools.get(key) {
+            None => only_in_a.push(key.clone()),
+            Some(b_content) if b_content != a_content => differing.push(ToolDifference {
+                kind: key.kind,
+                name: key.name.clone(),
+                diff: generate_text_diff(a_content, b_content),
+            }),
+            Some(_) => {}
+        }
+    }
+    let only_in_b = b_tools
+        .keys()
+        .filter(|key| !a_tools.contains_key(key))
+        .cloned()
+        .collect();
+
+    let mut plugins = compare_settings(&plugin_settings(a, a_files), &plugin_settings(b, b_files));
+    plugins.extend(compare_settings(
+        &marketplace_settings(a),
+        &marketplace_settings(b),
+    ));
+
+    ProfileComparison {
+        a: a.name.clone(),
+        b: b.name.clone(),
+        only_in_a,
+        only_in_b,
+        differing,
+        plugins,
+        adapters: compare_settings(
+            &adapter_settings(&a.adapters),
+            &adapter_settings(&b.adapters),
+        ),
+        overlays: compare_overlays(a, a_files, b, b_files),
+    }
+}
+
+/// Every tool in a profile with its content rendered as text
+fn collect_tools(profile: &Profile, files: &RevisionFiles) -> BTreeMap<ToolKey, String> {
+    // Tools made of several files are collected per file, then rendered
+    let mut tools: BTreeMap<ToolKey, BTreeMap<String, String>> = BTreeMap::new();
+    let mut add = |kind, name: &str, path: &str, content: String| {
+        let key = ToolKey {
+            kind,
+            name: name.to_string(),
+        };
+        tools
+            .entry(key)
+            .or_default()
+            .insert(path.to_string(), content);
+    };
+
+    let overlays = &profile.repo_overlays;
+    for server in &overlays.mcp_servers {
+        let config = serde_json::to_value(server).unwrap_or_default();
+        add(CompareToolKind::Mcp, &server.name, "", pretty_json(&config));
+    }
+    for skill in &overlays.skills {
+        add_skill(&mut add, CompareToolKind::Skill, skill);
+    }
+    for skill in &profile.user_overlays.skills {
+        add_skill(&mut add, CompareToolKind::UserSkill, skill);
+    }
+    for command in &overlays.commands {
+        add(
+            CompareToolKind::Command,
+            &command.name,
+            "",
+            command.content.clone(),
+        );
+    }
+    for command in &profile.user_overlays.commands {
+        add(
+            CompareToolKind::UserCommand,
+            &command.name,
+            "",
+            command.content.clone(),
+        );
+    }
+    for agent in &overlays.agents {
+        add(
+            CompareToolKind::Agent,
+            &agent.name,
+            "",
+            agent.content.clone(),
+        );
+    }
+
+    for (path, bytes) in files {
+        let Some((dir, rest)) = path.split_once('/') else {
+            continue;
+        };
+        let content = file_text(bytes);
+        match dir {
+            "mcp-servers" => {
+                if let Some(name) = rest.strip_suffix(".json") {
+                    let content = serde_json::from_slice(bytes)
+                        .map_or(content, |config| pretty_json(&config));
+                    add(CompareToolKind::Mcp, name, "", content);
+                }
+            }
+            "skills" => {
+                if let Some((name, file)) = rest.split_once('/') {
+                    add(CompareToolKind::Skill, name, file, content);
+                }
+            }
+            "agents" => {
+                if let Some(name) = rest.strip_suffix(".md") {
+                    add(CompareToolKind::Agent, name, "", content);
+                }
+            }
+            "commands" => {
+                if let Some(name) = rest.strip_suffix(".md") {
+                    add(CompareToolKind::Command, name, "", content);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    tools
+        .into_iter()
+        .map(|(key, files)| (key, render_files(files)))
+        .collect()
+}
+
+fn add_skill(
+    add: &mut impl FnMut(CompareToolKind, &str, &str, String),
+    kind: CompareToolKind,
+    skill: &SkillOverlay,
+) {
+    add(kind, &skill.name, "SKILL.md", skill.content.clone());
+    for file in &skill.files {
+        let content = file
+            .bytes()
+            .map_or_else(|_| file.content.clone(), |bytes| file_text(&bytes));
+        add(kind, &skill.name, &file.path, content);
+    }
+}
+
+/// Render a tool's files as one text, with a header per file when there are
+/// several
+fn render_files(mut files: BTreeMap<String, String>) -> String {
+    if files.len() == 1 {
+        return files
+            .pop_first()
+            .map(|(_, content)| content)
+            .unwrap_or_default();
+    }
+    let mut output = String::new();
+    for (path, content) in files {
+        let _ = writeln!(output, "==> {path} <==");
+        output.push_str(&content);
+        if !content.ends_with('\n') {
+            output.push('\n');
+        }
+    }
+    output
+}
+
+fn file_text(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => format!("<binary, {} bytes>\n", bytes.len()),
+    }
+}
+
+fn pretty_json(value: &serde_json::Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
+
+/// Compare settings keyed by name, described as short strings
+fn compare_settings(
+    a: &BTreeMap<String, String>,
+    b: &BTreeMap<String, String>,
+) -> Vec<SettingDifference> {
+    let mut differences = Vec::new();
+    for (setting, a_value) in a {
+        if b.get(setting) != Some(a_value) {
+            differences.push(SettingDifference {
+                setting: setting.clone(),
+                a: Some(a_value.clone()),
+                b: b.get(setting).cloned(),
+                diff: None,
+            });
+        }
+    }
+    for (setting, b_value) in b {
+        if !a.contains_key(setting) {
+            differences.push(SettingDifference {
+                setting: setting.clone(),
+                a: None,
+                b: Some(b_value.clone()),
+                diff: None,
+            });
+        }
+    }
+    differences
+}
+
+fn plugin_settings(profile: &Profile, files: &RevisionFiles) -> BTreeMap<String, String> {
+    let mut settings: BTreeMap<String, String> = profile
+        .plugin_set
+        .plugins
+        .iter()
+        .map(|plugin| {
+            (
+                format!("plugin {}", plugin.version_key()),
+                describe_plugin(plugin),
+            )
+        })
+        .collect();
+    for (path, bytes) in files {
+        if !path.starts_with("plugins/") {
+            continue;
+        }
+        let Ok(manifest) = serde_json::from_slice::<PluginManifest>(bytes) else {
+            continue;
+        };
+        let key = match &manifest.marketplace {
+            Some(marketplace) => format!("plugin manifest {}@{marketplace}", manifest.id),
+            None => format!("plugin manifest {}", manifest.id),
+        };
+        let version = manifest.version.as_deref().unwrap_or("any version");
+        let state = if manifest.enabled {
+            "enabled"
+        } else {
+            "disabled"
+        };
+        settings.insert(key, format!("{version}, {state}"));
+    }
+    settings
+}
+
+fn describe_plugin(plugin: &PluginRef) -> String {
+    let mut description = format!(
+        "{} scope, {}",
+        format!("{:?}", plugin.scope).to_lowercase(),
+        if plugin.enabled {
+            "enabled"
+        } else {
+            "disabled"
+        }
+    );
+    if let Some(version_ref) = &plugin.version_ref {
+        let mode = format!("{:?}", version_ref.mode).to_lowercase();
+        let _ = write!(description, ", {} ({mode})", version_ref.version);
+    }
+    description
+}
+
+fn marketplace_settings(profile: &Profile) -> BTreeMap<String, String> {
+    profile
+        .plugin_set
+        .marketplaces
+        .iter()
+        .map(|marketplace| {
+            let source = match &marketplace.source {
+                MarketplaceSourceRef::GitHub { owner, repo } => format!("github {owner}/{repo}"),
+                MarketplaceSourceRef::Url { url } => format!("url {url}"),
+                MarketplaceSourceRef::Local { path } => format!("local {}", path.display()),
+            };
+            (format!("marketplace {}", marketplace.name), source)
+        })
+        .collect()
+}
+
+fn adapter_settings(adapters: &Adapters) -> BTreeMap<String, String> {
+    let mut settings = BTreeMap::new();
+    let location = match adapters.mcp_location {
+        McpLocation::ProjectRoot => ".mcp.json",
+        McpLocation::ClaudeDir => ".claude/mcp.json",
+    };
+    settings.insert("mcp location".to_string(), location.to_string());
+    for (artifact, strategy) in &adapters.merge_strategies {
+        settings.insert(
+            format!("merge strategy {artifact}"),
+            format!("{strategy:?}").to_lowercase(),
+        );
+    }
+    settings
+}
+
+fn compare_overlays(
+    a: &Profile,
+    a_files: &RevisionFiles,
+    b: &Profile,
+    b_files: &RevisionFiles,
+) -> Vec<SettingDifference> {
+    let a_md = a.repo_overlays.claude_md.as_ref();
+    let b_md = b.repo_overlays.claude_md.as_ref();
+    let mode = |mode: OverlayMode| format!("{mode:?}").to_lowercase();
+
+    let mut differences = compare_settings(
+        &a_md
+            .map(|md| ("CLAUDE.md mode".to_string(), mode(md.mode)))
+            .into_iter()
+            .collect(),
+        &b_md
+            .map(|md| ("CLAUDE.md mode".to_string(), mode(md.mode)))
+            .into_iter()
+            .collect(),
+    );
+    differences.extend(content_difference(
+        "CLAUDE.md content",
+        a_md.map(|md| md.content.clone()),
+        b_md.map(|md| md.content.clone()),
+    ));
+    differences.extend(content_difference(
+        "hooks",
+        a.repo_overlays
+            .hooks
+            .as_ref()
+            .map(|h| pretty_json(&serde_json::to_value(&h.events).unwrap_or_default())),
+        b.repo_overlays
+            .hooks
+            .as_ref()
+            .map(|h| pretty_json(&serde_json::to_value(&h.events).unwrap_or_default())),
+    ));
+    differences.extend(content_difference(
+        "permissions",
+        a.repo_overlays
+            .permissions
+            .as_ref()
+            .map(|p| pretty_json(&serde_json::to_value(p).unwrap_or_default())),
+        b.repo_overlays
+            .permissions
+            .as_ref()
+            .map(|p| pretty_json(&serde_json::to_value(p).unwrap_or_default())),
+    ));
+    differences.extend(content_difference(
+        "stored hooks",
+        stored_hooks(a_files),
+        stored_hooks(b_files),
+    ));
+    differences
+}
+
+/// The `hooks.json` at the root of a profile's storage, as text
+fn stored_hooks(files: &RevisionFiles) -> Option<String> {
+    files.get("hooks.json").map(|bytes| {
+        serde_json::from_slice(bytes).map_or_else(|_| file_text(bytes), |hooks| pretty_json(&hooks))
+    })
+}
+
+/// A setting with longer content, described by line count and diffed
+fn content_difference(
+    setting: &str,
+    a: Option<String>,
+    b: Option<String>,
+) -> Option<SettingDifference> {
+    if a == b {
+        return None;
+    }
+    let lines = |content: &String| format!("{} line(s)", content.lines().count());
+    Some(SettingDifference {
+        setting: setting.to_string(),
+        a: a.as_ref().map(lines),
+        b: b.as_ref().map(lines),
+        diff: Some(generate_text_diff(
+            a.as_deref().unwrap_or_default(),
+            b.as_deref().unwrap_or_default(),
+        )),
+    })
+}
@@ -1,16 +1,54 @@
 //! Profile export/import operations
 //!
-//! This module handles exporting profiles to JSON and importing them back.
+//! Two formats are supported:
+//!
+//! - Version 1, `.tars-profile.json`: tool names, types and permissions
+//!   only. Importing it on another machine yields references to tools that
+//!   must already exist there.
+//! - Version 2, `.tars-profile.zip`: a self-contained bundle. Besides
+//!   `profile.json` (a [`ProfileExport`] that also carries plugin set,
+//!   overlays and adapters) it embeds the profile's stored tool content and
+//!   `hooks.json` from `~/.tars/profiles/<id>/` under `files/`, plus a
+//!   `manifest.json` listing each file's SHA-256 and a hash over the whole
+//!   set. Imports verify every
+//!   hash before anything is written.
 
-use crate::profile::{Profile, ToolRef};
+use crate::profile::storage::{
+    compute_dir_hash, compute_file_hash, profiles_base_dir, StorageError, MAX_FILE_SIZE,
+};
+use crate::profile::{Adapters, PluginSet, Profile, RepoOverlays, ToolRef, UserOverlays};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 use std::fs;
-use std::io;
-use std::path::Path;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+use zip::write::FileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+/// Version written by [`export_bundle`]
+pub const EXPORT_FORMAT_VERSION: u32 = 2;
+
+/// Version written by [`export_profile`] (reference-only JSON)
+const JSON_FORMAT_VERSION: u32 = 1;
+
+/// Subdirectories of a profile's storage carried in a bundle. The generated
+/// `plugin/` output is left out; it is rebuilt from these on demand.
+pub const BUNDLED_DIRS: &[&str] = &["skills", "agents", "commands", "mcp-servers", "plugins"];
 
-/// Version of the export format
-const EXPORT_FORMAT_VERSION: u32 = 1;
+/// Files at the root of a profile's storage carried in a bundle: the
+/// profile's stored hooks.
+pub const BUNDLED_FILES: &[&str] = &["hooks.json"];
+
+const BUNDLE_PROFILE_ENTRY: &str = "profile.json";
+const BUNDLE_MANIFEST_ENTRY: &str = "manifest.json";
+const BUNDLE_FILES_PREFIX: &str = "files/";
+
+/// Upper bounds on what an import will unpack
+const MAX_BUNDLE_FILES: usize = 10_000;
+const MAX_BUNDLE_BYTES: u64 = 256 * 1024 * 1024;
 
 /// Exported profile format (.tars-profile.json)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,6 +65,40 @@ pub struct ProfileExport {
     pub created_at: DateTime<Utc>,
     /// When exported
     pub exported_at: DateTime<Utc>,
+    /// Plugin configuration (bundles only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plugin_set: Option<PluginSet>,
+    /// Repository-level overlays (bundles only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo_overlays: Option<RepoOverlays>,
+    /// User-level overlays (bundles only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_overlays: Option<UserOverlays>,
+    /// Adapter settings (bundles only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adapters: Option<Adapters>,
+}
+
+/// Content manifest stored as `manifest.json` in a bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    /// Format version, equal to the embedded profile's
+    pub version: u32,
+    /// SHA-256 over every file's path and hash, in path order
+    pub content_hash: String,
+    /// Every embedded file, in path order
+    pub files: Vec<BundleFile>,
+}
+
+/// One file embedded in a bundle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleFile {
+    /// Path relative to the profile storage directory, `/`-separated
+    pub path: String,
+    /// SHA-256 of the contents
+    pub sha256: String,
+    /// Size in bytes
+    pub size: u64,
 }
 
 /// Exported tool reference (simplified for portability)
@@ -64,6 +136,21 @@ pub enum ExportError {
     /// Invalid format version
     #[error("Unsupported export format version: {0}")]
     UnsupportedVersion(u32),
+    /// ZIP read/write error
+    #[error("ZIP error: {0}")]
+    Zip(#[from] zip::result::ZipError),
+    /// Directory walk error
+    #[error("Walk error: {0}")]
+    Walk(#[from] walkdir::Error),
+    /// Profile storage error
+    #[error("Storage error: {0}")]
+    Storage(#[from] StorageError),
+    /// Malformed bundle (missing entries, unsafe paths, limits exceeded)
+    #[error("Invalid bundle: {0}")]
+    InvalidBundle(String),
+    /// A file does not match the hash recorded in the manifest
+    #[error("Bundle integrity check failed: {0}")]
+    Integrity(String),
 }
 
 /// Export a profile to JSON format
@@ -71,8 +158,17 @@ pub enum ExportError {
 /// # Errors
 /// Returns an error if the file cannot be written
 pub fn export_profile(profile: &Profile, output_path: &Path) -> Result<ProfileExport, ExportError> {
-    let export = ProfileExport {
-        version: EXPORT_FORMAT_VERSION,
+    let export = export_header(profile);
+    let json = serde_json::to_string_pretty(&export)?;
+    fs::write(output_path, json)?;
+
+    Ok(export)
+}
+
+/// The reference-only part of an export, shared by both formats
+fn export_header(profile: &Profile) -> ProfileExport {
+    ProfileExport {
+        version: JSON_FORMAT_VERSION,
         name: profile.name.clone(),
         description: profile.description.clone(),
         tool_refs: profile
@@ -89,19 +185,284 @@ pub fn export_profile(profile: &Profile, output_path: &Path) -> Result<ProfileEx
             .collect(),
         created_at: profile.created_at,
         exported_at: Utc::now(),
+        plugin_set: None,
+        repo_overlays: None,
+        user_overlays: None,
+        adapters: None,
+    }
+}
+
+/// Export a profile as a self-contained bundle (format version 2)
+///
+/// `profile_dir` is the profile's storage directory, normally
+/// [`crate::profile::storage::profile_dir`]. Only [`BUNDLED_DIRS`] and
+/// [`BUNDLED_FILES`] are embedded; symlinks are skipped. A missing directory yields a bundle with
+/// no files.
+///
+/// # Errors
+/// Returns an error if the storage directory cannot be read or the archive
+/// cannot be written
+pub fn export_bundle(
+    profile: &Profile,
+    profile_dir: &Path,
+    output_path: &Path,
+) -> Result<ProfileExport, ExportError> {
+    let export = bundle_export(profile);
+    let sources = collect_profile_files(profile_dir)?;
+
+    let mut zip = ZipWriter::new(fs::File::create(output_path)?);
+    let options = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut files = Vec::with_capacity(sources.len());
+    for (rel, source) in &sources {
+        let content = fs::read(source)?;
+        files.push(BundleFile {
+            path: rel.clone(),
+            sha256: sha256_hex(&content),
+            size: u64::try_from(content.len()).unwrap_or(u64::MAX),
+        });
+        zip.start_file(format!("{BUNDLE_FILES_PREFIX}{rel}"), options)?;
+        zip.write_all(&content)?;
+    }
+    let manifest = BundleManifest {
+        version: EXPORT_FORMAT_VERSION,
+        content_hash: manifest_hash(&files),
+        files,
     };
 
-    let json = serde_json::to_string_pretty(&export)?;
-    fs::write(output_path, json)?;
+    zip.start_file(BUNDLE_PROFILE_ENTRY, options)?;
+    zip.write_all(serde_json::to_string_pretty(&export)?.as_bytes())?;
+    zip.start_file(BUNDLE_MANIFEST_ENTRY, options)?;
+    zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
+    zip.finish()?;
 
     Ok(export)
 }
 
+/// The bundle form of a profile: header plus plugin set, overlays and adapters
+pub(crate) fn bundle_export(profile: &Profile) -> ProfileExport {
+    ProfileExport {
+        version: EXPORT_FORMAT_VERSION,
+        plugin_set: Some(profile.plugin_set.clone()),
+        repo_overlays: Some(profile.repo_overlays.clone()),
+        user_overlays: Some(profile.user_overlays.clone()),
+        adapters: Some(profile.adapters.clone()),
+        ..export_header(profile)
+    }
+}
+
+/// Contents of the files a bundle of `profile_dir` would embed
+pub(crate) fn read_bundled_files(
+    profile_dir: &Path,
+) -> Result<BTreeMap<String, Vec<u8>>, ExportError> {
+    collect_profile_files(profile_dir)?
+        .into_iter()
+        .map(|(rel, source)| Ok((rel, fs::read(source)?)))
+        .collect()
+}
+
+/// [`BUNDLED_FILES`] and the files under [`BUNDLED_DIRS`], keyed by their
+/// `/`-separated path relative to `profile_dir`.
+fn collect_profile_files(profile_dir: &Path) -> Result<BTreeMap<String, PathBuf>, ExportError> {
+    let mut files = BTreeMap::new();
+    for name in BUNDLED_FILES {
+        let path = profile_dir.join(name);
+        // Symlinks are skipped, as inside the bundled directories
+        if fs::symlink_metadata(&path).is_ok_and(|m| m.file_type().is_file()) {
+            files.insert((*name).to_string(), path);
+        }
+    }
+    for dir in BUNDLED_DIRS {
+        let root = profile_dir.join(dir);
+        if !root.is_dir() {
+            continue;
+        }
+        for entry in WalkDir::new(&root).follow_links(false) {
+            let entry = entry?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let relative = entry
+                .path()
+                .strip_prefix(profile_dir)
+                .map_err(io::Error::other)?;
+            let parts = relative
+                .components()
+                .map(|c| c.as_os_str().to_str())
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(|| {
+                    ExportError::InvalidBundle(format!(
+                        "non UTF-8 file name: {}",
+                        relative.display()
+                    ))
+                })?;
+            files.insert(parts.join("/"), entry.into_path());
+            if files.len() > MAX_BUNDLE_FILES {
+                return Err(ExportError::InvalidBundle(format!(
+                    "profile has more than {MAX_BUNDLE_FILES} files"
+                )));
+            }
+        }
+    }
+    Ok(files)
+}
+
+fn sha256_hex(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+/// Hash over every file's path and hash; `files` must be in path order.
+fn manifest_hash(files: &[BundleFile]) -> String {
+    let mut hasher = Sha256::new();
+    for f in files {
+        hasher.update(f.path.as_bytes());
+        hasher.update([0]);
+        hasher.update(f.sha256.as_bytes());
+        hasher.update(b"\n");
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Whether `path` starts with a ZIP signature (bundle) rather than JSON.
+fn is_bundle(path: &Path) -> Result<bool, ExportError> {
+    let mut magic = [0u8; 4];
+    let mut file = fs::File::open(path)?;
+    let read = file.read(&mut magic)?;
+    Ok(read == 4 && (magic == *b"PK\x03\x04" || magic == *b"PK\x05\x06"))
+}
+
+/// Whether `rel`, a `/`-separated path relative to a profile's storage, is
+/// one a bundle carries: a [`BUNDLED_FILES`] entry or a file under
+/// [`BUNDLED_DIRS`]
+pub(crate) fn is_bundled_path(rel: &str) -> bool {
+    match rel.split_once('/') {
+        Some((top, rest)) => BUNDLED_DIRS.contains(&top) && !rest.is_empty(),
+        None => BUNDLED_FILES.contains(&rel),
+    }
+}
+
+/// Reject bundle paths that would escape the profile directory or that a
+/// bundle does not carry.
+fn validate_bundle_path(rel: &str) -> Result<(), ExportError> {
+    let unsafe_part =
+        |p: &str| p.is_empty() || p == "." || p == ".." || p.contains(['\\', ':', '\0']);
+    if !is_bundled_path(rel) || rel.split('/').any(unsafe_part) {
+        return Err(ExportError::InvalidBundle(format!(
+            "unexpected file path: {rel}"
+        )));
+    }
+    Ok(())
+}
+
+/// A bundle read fully into memory and verified against its manifest
+struct VerifiedBundle {
+    export: ProfileExport,
+    manifest: BundleManifest,
+    /// Contents keyed by path relative to the profile directory
+    files: BTreeMap<String, Vec<u8>>,
+}
+
+fn read_entry(archive: &mut ZipArchive<fs::File>, name: &str) -> Result<Vec<u8>, ExportError> {
+    let mut entry = archive
+        .by_name(name)
+        .map_err(|_| ExportError::InvalidBundle(format!("missing {name}")))?;
+    let mut content = Vec::new();
+    (&mut entry).take(MAX_FILE_SIZE).read_to_end(&mut content)?;
+    Ok(content)
+}
+
+fn read_bundle(path: &Path) -> Result<VerifiedBundle, ExportError> {
+    let mut archive = ZipArchive::new(fs::File::open(path)?)?;
+    if archive.len() > MAX_BUNDLE_FILES + 2 {
+        return Err(ExportError::InvalidBundle(format!(
+            "more than {MAX_BUNDLE_FILES} files"
+        )));
+    }
+
+    let export: ProfileExport =
+        serde_json::from_slice(&read_entry(&mut archive, BUNDLE_PROFILE_ENTRY)?)?;
+    let manifest: BundleManifest =
+        serde_json::from_slice(&read_entry(&mut archive, BUNDLE_MANIFEST_ENTRY)?)?;
+    for version in [export.version, manifest.version] {
+        if version > EXPORT_FORMAT_VERSION {
+            return Err(ExportError::UnsupportedVersion(version));
+        }
+    }
+
+    let mut files = BTreeMap::new();
+    let mut total: u64 = 0;
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+        let name = entry.name().to_string();
+        if entry.is_dir() || name == BUNDLE_PROFILE_ENTRY || name == BUNDLE_MANIFEST_ENTRY {
+            continue;
+        }
+        let rel = name
+            .strip_prefix(BUNDLE_FILES_PREFIX)
+            .ok_or_else(|| ExportError::InvalidBundle(format!("unexpected entry: {name}")))?;
+        validate_bundle_path(rel)?;
+
+        // Bound the read by what is left of the budget rather than trusting
+        // the size recorded in the archive.
+        let remaining = MAX_BUNDLE_BYTES - total;
+        let mut content = Vec::new();
+        (&mut entry).take(remaining + 1).read_to_end(&mut content)?;
+        let size = u64::try_from(content.len()).unwrap_or(u64::MAX);
+        if size > remaining {
+            return Err(ExportError::InvalidBundle(format!(
+                "contents exceed {MAX_BUNDLE_BYTES} bytes"
+            )));
+        }
+        total += size;
+        files.insert(rel.to_string(), content);
+    }
+
+    for f in &manifest.files {
+        let content = files
+            .get(&f.path)
+            .ok_or_else(|| ExportError::Integrity(format!("{} is missing", f.path)))?;
+        if sha256_hex(content) != f.sha256 {
+            return Err(ExportError::Integrity(format!(
+                "{} does not match its recorded hash",
+                f.path
+            )));
+        }
+    }
+    if let Some(extra) = files
+        .keys()
+        .find(|p| !manifest.files.iter().any(|f| &f.path == *p))
+    {
+        return Err(ExportError::Integrity(format!(
+            "{extra} is not listed in the manifest"
+        )));
+    }
+    if manifest_hash(&manifest.files) != manifest.content_hash {
+        return Err(ExportError::Integrity(
+            "manifest content hash does not match its file list".to_string(),
+        ));
+    }
+
+    Ok(VerifiedBundle {
+        export,
+        manifest,
+        files,
+    })
+}
+
 /// Preview what would be imported from a file
 ///
+/// Accepts both a version 1 `.tars-profile.json` and a version 2 bundle;
+/// bundles are fully verified. `conflicts` is left empty, see
+/// [`preview_import_with_conflicts`].
+///
 /// # Errors
-/// Returns an error if the file cannot be read or parsed
+/// Returns an error if the file cannot be read or parsed, or a bundle fails
+/// its integrity check
 pub fn preview_import(path: &Path) -> Result<ImportPreview, ExportError> {
+    if is_bundle(path)? {
+        let bundle = read_bundle(path)?;
+        return Ok(preview_from(bundle.export, Some(&bundle.manifest)));
+    }
+
     let content = fs::read_to_string(path)?;
     let export: ProfileExport = serde_json::from_str(&content)?;
 
@@ -109,14 +470,43 @@ pub fn preview_import(path: &Path) -> Result<ImportPreview, ExportError> {
         return Err(ExportError::UnsupportedVersion(export.version));
     }
 
-    Ok(ImportPreview {
+    Ok(preview_from(export, None))
+}
+
+/// Like [`preview_import`], and also lists bundled skills, agents, commands
+/// and MCP servers whose names are already taken in the user's Claude
+/// config under `home` (`~/.claude/{skills,agents,commands}` and the
+/// `mcpServers` of `~/.claude.json`).
+///
+/// Version 1 files carry no content, so they never report conflicts.
+///
+/// # Errors
+/// Same as [`preview_import`]
+pub fn preview_import_with_conflicts(
+    path: &Path,
+    home: &Path,
+) -> Result<ImportPreview, ExportError> {
+    if !is_bundle(path)? {
+        return preview_import(path);
+    }
+    let bundle = read_bundle(path)?;
+    let mut preview = preview_from(bundle.export, Some(&bundle.manifest));
+    preview.conflicts = find_conflicts(&bundle.files, home);
+    Ok(preview)
+}
+
+fn preview_from(export: ProfileExport, manifest: Option<&BundleManifest>) -> ImportPreview {
+    ImportPreview {
         name: export.name,
         description: export.description,
         tool_count: export.tool_refs.len(),
         version: export.version,
         created_at: export.created_at,
         exported_at: export.exported_at,
-    })
+        file_count: manifest.map_or(0, |m| m.files.len()),
+        content_hash: manifest.map(|m| m.content_hash.clone()),
+        conflicts: Vec::new(),
+    }
 }
 
 /// Preview of what would be imported
@@ -134,16 +524,141 @@ pub struct ImportPreview {
     pub created_at: DateTime<Utc>,
     /// When exported
     pub exported_at: DateTime<Utc>,
+    /// Number of embedded files (0 for version 1)
+    #[serde(default)]
+    pub file_count: usize,
+    /// Manifest content hash (bundles only)
+    #[serde(default)]
+    pub content_hash: Option<String>,
+    /// Bundled tools whose names already exist locally
+    #[serde(default)]
+    pub conflicts: Vec<ImportConflict>,
+}
+
+/// Kind of tool embedded in a bundle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BundledToolKind {
+    Skill,
+    Agent,
+    Command,
+    McpServer,
+}
+
+/// A bundled tool whose name is already used by a local tool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportConflict {
+    pub kind: BundledToolKind,
+    pub name: String,
+    /// The existing local file or directory (`~/.claude.json` for MCP servers)
+    pub local_path: PathBuf,
+    /// The local content equals the bundled content
+    pub identical: bool,
+}
+
+/// Hash of bundled files the way [`compute_dir_hash`] hashes a directory,
+/// so a bundled skill can be compared with an installed one.
+fn tree_hash(mut entries: Vec<(&str, &[u8])>) -> String {
+    entries.sort_by(|a, b| Path::new(a.0).cmp(Path::new(b.0)));
+    let mut hasher = Sha256::new();
+    for (rel, content) in entries {
+        hasher.update(rel.as_bytes());
+        if u64::try_from(content.len()).unwrap_or(u64::MAX) > MAX_FILE_SIZE {
+            hasher.update(sha256_hex(content).as_bytes());
+        } else {
+            hasher.update(content);
+        }
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+fn find_conflicts(files: &BTreeMap<String, Vec<u8>>, home: &Path) -> Vec<ImportConflict> {
+    let claude_dir = home.join(".claude");
+    let mut skills: BTreeMap<&str, Vec<(&str, &[u8])>> = BTreeMap::new();
+    let mut conflicts = Vec::new();
+    let mut local_mcp: Option<serde_json::Value> = None;
+
+    for (path, content) in files {
+        let Some((dir, rest)) = path.split_once('/') else {
+            continue;
+        };
+        match dir {
+            "skills" => {
+                if let Some((name, inner)) = rest.split_once('/') {
+                    skills.entry(name).or_default().push((inner, content));
+                }
+            }
+            "agents" | "commands" => {
+                let Some(name) = rest.strip_suffix(".md").filter(|n| !n.contains('/')) else {
+                    continue;
+                };
+                let local = claude_dir.join(dir).join(rest);
+                if local.is_file() {
+                    conflicts.push(ImportConflict {
+                        kind: if dir == "agents" {
+                            BundledToolKind::Agent
+                        } else {
+                            BundledToolKind::Command
+                        },
+                        name: name.to_string(),
+                        identical: compute_file_hash(&local)
+                            .is_ok_and(|h| h == sha256_hex(content)),
+                        local_path: local,
+                    });
+                }
+            }
+            "mcp-servers" => {
+                let Some(name) = rest.strip_suffix(".json").filter(|n| !n.contains('/')) else {
+                    continue;
+                };
+                let config_path = home.join(".claude.json");
+                let config = local_mcp.get_or_insert_with(|| {
+                    fs::read_to_string(&config_path)
+                        .ok()
+                        .and_then(|c| serde_json::from_str(&c).ok())
+                        .unwrap_or(serde_json::Value::Null)
+                });
+                if let Some(existing) = config.get("mcpServers").and_then(|m| m.get(name)) {
+                    let bundled: Option<serde_json::Value> = serde_json::from_slice(content).ok();
+                    conflicts.push(ImportConflict {
+                        kind: BundledToolKind::McpServer,
+                        name: name.to_string(),
+                        identical: bundled.as_ref() == Some(existing),
+                        local_path: config_path,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for (name, entries) in skills {
+        let local = claude_dir.join("skills").join(name);
+        if local.is_dir() {
+            let bundled = tree_hash(entries);
+            conflicts.push(ImportConflict {
+                kind: BundledToolKind::Skill,
+                name: name.to_string(),
+                identical: compute_dir_hash(&local).is_ok_and(|h| h == bundled),
+                local_path: local,
+            });
+        }
+    }
+    conflicts
 }
 
-/// Import a profile from JSON file
+/// Import a profile from a `.tars-profile.json` or a bundle
 ///
-/// Creates a new profile with a new ID.
+/// Creates a new profile with a new ID. A bundle's files are unpacked into
+/// the new profile's storage directory under `~/.tars/profiles/`.
 ///
 /// # Errors
-/// Returns an error if the file cannot be read or parsed
+/// Returns an error if the file cannot be read or parsed, a bundle fails its
+/// integrity check, or its files cannot be written
 pub fn import_profile(path: &Path) -> Result<Profile, ExportError> {
-    use crate::profile::{ToolPermissions, ToolType};
+    if is_bundle(path)? {
+        return import_bundle(path, &profiles_base_dir()?);
+    }
 
     let content = fs::read_to_string(path)?;
     let export: ProfileExport = serde_json::from_str(&content)?;
@@ -152,6 +667,39 @@ pub fn import_profile(path: &Path) -> Result<Profile, ExportError> {
         return Err(ExportError::UnsupportedVersion(export.version));
     }
 
+    Ok(profile_from_export(export))
+}
+
+/// Import a bundle, unpacking its files into `profiles_base/<new-id>/`
+///
+/// Every file is verified against the manifest before anything is written;
+/// a failed write removes the partially unpacked directory.
+///
+/// # Errors
+/// Returns an error if the bundle is invalid or its files cannot be written
+pub fn import_bundle(path: &Path, profiles_base: &Path) -> Result<Profile, ExportError> {
+    let bundle = read_bundle(path)?;
+    let profile = profile_from_export(bundle.export);
+    let dest = profiles_base.join(profile.id.to_string());
+
+    let written = bundle.files.iter().try_for_each(|(rel, content)| {
+        let target = dest.join(rel);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&target, content)
+    });
+    if let Err(e) = written {
+        let _ = fs::remove_dir_all(&dest);
+        return Err(e.into());
+    }
+
+    Ok(profile)
+}
+
+pub(crate) fn profile_from_export(export: ProfileExport) -> Profile {
+    use crate::profile::{ToolPermissions, ToolType};
+
     // Convert exported tools back to ToolRef
     let tool_refs: Vec<ToolRef> = export
         .tool_refs
@@ -184,7 +732,19 @@ pub fn import_profile(path: &Path) -> Result<Profile, ExportError> {
     let mut profile = Profile::new(export.name);
     profile.description = export.description;
     profile.tool_refs = tool_refs;
+    if let Some(plugin_set) = export.plugin_set {
+        profile.plugin_set = plugin_set;
+    }
+    if let Some(overlays) = export.repo_overlays {
+        profile.repo_overlays = overlays;
+    }
+    if let Some(overlays) = export.user_overlays {
+        profile.user_overlays = overlays;
+    }
+    if let Some(adapters) = export.adapters {
+        profile.adapters = adapters;
+    }
     // Note: Keep the new created_at for the imported profile, not the original
 
-    Ok(profile)
+    profile
 }
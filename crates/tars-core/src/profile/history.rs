@@ -0,0 +1,293 @@
+//! Profile revision history: reading, diffing and reverting revisions
+//!
+//! [`ProfileStore`] records a revision whenever a profile is created or
+//! updated (see [`crate::storage::profile_revisions`]). A revision covers the
+//! profile row and every file under `~/.tars/profiles/<id>/` except the
+//! generated `plugin/` directory.
+
+use crate::diff::plan::generate_text_diff;
+use crate::profile::storage::{ensure_profile_dir, profile_dir, StorageError};
+use crate::profile::Profile;
+use crate::storage::db::DatabaseError;
+use crate::storage::profile_revisions::{
+    ProfileRevision, ProfileRevisionStore, RevisionFiles, RevisionSnapshot,
+};
+use crate::storage::profiles::ProfileStore;
+use chrono::Utc;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path};
+use thiserror::Error;
+use walkdir::WalkDir;
+
+/// Generated from the profile on sync, so not part of its history
+const GENERATED_DIR: &str = "plugin";
+
+/// Errors while working with profile history
+#[derive(Error, Debug)]
+pub enum HistoryError {
+    #[error("Revision not found: {0}")]
+    NotFound(String),
+
+    #[error("Revision '{0}' is ambiguous; use more characters")]
+    Ambiguous(String),
+
+    #[error("Profile not found: {0}")]
+    ProfileNotFound(String),
+
+    #[error("Invalid path in revision: {0}")]
+    InvalidPath(String),
+
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+
+    #[error("Storage error: {0}")]
+    Storage(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl From<StorageError> for HistoryError {
+    fn from(e: StorageError) -> Self {
+        Self::Storage(e.to_string())
+    }
+}
+
+/// How a file differs between two revisions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// A file that differs between two revisions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileChange {
+    pub path: String,
+    pub kind: FileChangeKind,
+    /// Line diff, when both sides are text
+    pub diff: Option<String>,
+}
+
+/// Differences between two revisions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevisionDiff {
+    pub from: ProfileRevision,
+    pub to: ProfileRevision,
+    /// Line diff of the profile JSON, if it changed
+    pub profile: Option<String>,
+    pub files: Vec<FileChange>,
+}
+
+impl RevisionDiff {
+    /// Whether the revisions are identical
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.profile.is_none() && self.files.is_empty()
+    }
+}
+
+/// Result of reverting a profile
+#[derive(Debug, Clone)]
+pub struct RevertResult {
+    /// The profile as now stored
+    pub profile: Profile,
+    /// Revision the profile was reverted to
+    pub target: ProfileRevision,
+    /// Revision recorded for the revert, if the state changed
+    pub recorded: Option<ProfileRevision>,
+}
+
+/// Read the files of a profile's storage directory
+///
+/// # Errors
+/// Returns an error if the directory cannot be read
+pub fn read_profile_files(profile_id: uuid::Uuid) -> Result<RevisionFiles, StorageError> {
+    read_revision_files(&profile_dir(profile_id)?)
+}
+
+/// Read the files under `dir`, skipping the generated `plugin/` directory
+///
+/// # Errors
+/// Returns an error if the directory cannot be read
+pub fn read_revision_files(dir: &Path) -> Result<RevisionFiles, StorageError> {
+    let mut files = RevisionFiles::new();
+    if !dir.exists() {
+        return Ok(files);
+    }
+
+    let walker = WalkDir::new(dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| !(e.depth() == 1 && e.file_name() == GENERATED_DIR));
+    for entry in walker {
+        let entry = entry.map_err(|e| StorageError::Io(e.to_string()))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Ok(relative) = entry.path().strip_prefix(dir) else {
+            continue;
+        };
+        let path = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let content = fs::read(entry.path()).map_err(|e| {
+            StorageError::Io(format!("Failed to read {}: {e}", entry.path().display()))
+        })?;
+        files.insert(path, content);
+    }
+
+    Ok(files)
+}
+
+/// Find a revision by hash or unique hash prefix
+///
+/// # Errors
+/// Returns an error if no revision or more than one revision matches
+pub fn find_revision(
+    store: &ProfileRevisionStore,
+    rev: &str,
+) -> Result<ProfileRevision, HistoryError> {
+    let mut matches = store.find(rev)?;
+    match matches.len() {
+        0 => Err(HistoryError::NotFound(rev.to_string())),
+        1 => Ok(matches.remove(0)),
+        _ => Err(HistoryError::Ambiguous(rev.to_string())),
+    }
+}
+
+/// Compare two revisions
+#[must_use]
+pub fn diff_revisions(from: &RevisionSnapshot, to: &RevisionSnapshot) -> RevisionDiff {
+    let old_json = profile_json(&from.profile);
+    let new_json = profile_json(&to.profile);
+    let profile = (old_json != new_json).then(|| generate_text_diff(&old_json, &new_json));
+
+    let mut files = Vec::new();
+    for (path, old) in &from.files {
+        match to.files.get(path) {
+            None => files.push(FileChange {
+                path: path.clone(),
+                kind: FileChangeKind::Removed,
+                diff: None,
+            }),
+            Some(new) if new != old => files.push(FileChange {
+                path: path.clone(),
+                kind: FileChangeKind::Modified,
+                diff: match (std::str::from_utf8(old), std::str::from_utf8(new)) {
+                    (Ok(old), Ok(new)) => Some(generate_text_diff(old, new)),
+                    _ => None,
+                },
+            }),
+            Some(_) => {}
+        }
+    }
+    for path in to.files.keys() {
+        if !from.files.contains_key(path) {
+            files.push(FileChange {
+                path: path.clone(),
+                kind: FileChangeKind::Added,
+                diff: None,
+            });
+        }
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    RevisionDiff {
+        from: from.revision.clone(),
+        to: to.revision.clone(),
+        profile,
+        files,
+    }
+}
+
+/// Replace the files under `dir` with a revision's files
+///
+/// The generated `plugin/` directory is left alone.
+///
+/// # Errors
+/// Returns an error if a path is unsafe or the files cannot be written
+pub fn restore_revision_files(snapshot: &RevisionSnapshot, dir: &Path) -> Result<(), HistoryError> {
+    for path in snapshot.files.keys() {
+        let safe = !path.is_empty()
+            && Path::new(path)
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)));
+        if !safe {
+            return Err(HistoryError::InvalidPath(path.clone()));
+        }
+    }
+
+    fs::create_dir_all(dir)?;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_name() == GENERATED_DIR {
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            fs::remove_dir_all(entry.path())?;
+        } else {
+            fs::remove_file(entry.path())?;
+        }
+    }
+
+    for (path, content) in &snapshot.files {
+        let target = dir.join(path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(target, content)?;
+    }
+
+    Ok(())
+}
+
+/// Revert a profile and its stored files to an earlier revision
+///
+/// The current state is recorded first, so the revert can itself be undone.
+///
+/// # Errors
+/// Returns an error if the revision or profile cannot be found, or the
+/// files or row cannot be restored
+pub fn revert_profile(conn: &Connection, rev: &str) -> Result<RevertResult, HistoryError> {
+    let revisions = ProfileRevisionStore::new(conn);
+    let profiles = ProfileStore::new(conn);
+
+    let target = find_revision(&revisions, rev)?;
+    let snapshot = revisions.snapshot(&target)?;
+    let current = profiles
+        .get(target.profile_id)?
+        .ok_or_else(|| HistoryError::ProfileNotFound(target.profile_id.to_string()))?;
+    profiles.record_revision(&current)?;
+    let before = revisions.latest(target.profile_id)?.map(|r| r.id);
+
+    restore_revision_files(&snapshot, &ensure_profile_dir(target.profile_id)?)?;
+
+    let mut profile = snapshot.profile;
+    profile.created_at = current.created_at;
+    profile.updated_at = Utc::now();
+    profiles.update(&profile)?;
+
+    Ok(RevertResult {
+        recorded: revisions
+            .latest(profile.id)?
+            .filter(|r| Some(r.id) != before),
+        profile,
+        target,
+    })
+}
+
+/// Pretty profile JSON for diffing, without the update timestamp
+fn profile_json(profile: &Profile) -> String {
+    let mut value = serde_json::to_value(profile).unwrap_or_default();
+    if let Some(object) = value.as_object_mut() {
+        object.remove("updated_at");
+    }
+    serde_json::to_string_pretty(&value).unwrap_or_default()
+}
@@ -0,0 +1,374 @@
+//! Profile lock files
+//!
+//! An optional `.claude/tars.lock` records which profile a project was
+//! applied from: the profile name, its revision, its storage content hash
+//! (see [`compute_profile_content_hash`]) and the SHA256 of every file the
+//! profile wrote. Checked in next to those files, it lets a teammate or CI
+//! verify the project still matches, and reproduce it from the same revision.
+//!
+//! The lock has no timestamps, so re-applying an unchanged profile leaves it
+//! byte-for-byte identical.
+//!
+//! [`compute_profile_content_hash`]: crate::profile::storage::compute_profile_content_hash
+
+use crate::diff::plan::{plan_file, PlanError};
+use crate::diff::{DiffPlan, FileOperation};
+use crate::profile::history::read_profile_files;
+use crate::profile::resolve::{resolve_profile, ResolveError, ResolvedProfile};
+use crate::profile::storage::StorageError;
+use crate::profile::Profile;
+use crate::storage::db::DatabaseError;
+use crate::storage::profile_revisions::{revision_hash, ProfileRevisionStore};
+use crate::storage::profiles::ProfileStore;
+use crate::storage::RevisionFiles;
+use crate::util::{safe_join, PathError};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Location of the lock file, relative to the project root
+pub const LOCK_FILE: &str = ".claude/tars.lock";
+
+/// Lock file format version
+pub const LOCK_FORMAT_VERSION: u32 = 1;
+
+/// Errors while reading, writing or verifying a lock file
+#[derive(Error, Debug)]
+pub enum LockError {
+    #[error("No lock file at {0}")]
+    NotFound(String),
+
+    #[error("Invalid lock file: {0}")]
+    Invalid(String),
+
+    #[error("Unsupported lock file version {0}")]
+    UnsupportedVersion(u32),
+
+    #[error("Profile not found: {0}")]
+    ProfileNotFound(String),
+
+    #[error("Invalid path in lock file: {0}")]
+    InvalidPath(#[from] PathError),
+
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+
+    #[error("Resolve error: {0}")]
+    Resolve(#[from] ResolveError),
+
+    #[error("Plan error: {0}")]
+    Plan(#[from] PlanError),
+
+    #[error("Storage error: {0}")]
+    Storage(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl From<StorageError> for LockError {
+    fn from(e: StorageError) -> Self {
+        Self::Storage(e.to_string())
+    }
+}
+
+/// Contents of `.claude/tars.lock`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileLock {
+    pub version: u32,
+    /// Name of the applied profile
+    pub profile: String,
+    pub profile_id: Uuid,
+    /// Revision hash of the profile when it was applied
+    pub revision: Option<String>,
+    /// Storage content hash of the profile and its parents
+    pub content_hash: String,
+    /// SHA256 of every file the profile wrote, keyed by `/`-separated path
+    /// relative to the project root
+    pub files: BTreeMap<String, String>,
+}
+
+impl ProfileLock {
+    /// A lock for `profile` with no files yet
+    #[must_use]
+    pub fn new(profile: &Profile, revision: Option<String>, content_hash: String) -> Self {
+        Self {
+            version: LOCK_FORMAT_VERSION,
+            profile: profile.name.clone(),
+            profile_id: profile.id,
+            revision,
+            content_hash,
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Record the hash every target of `plan` will have once it is applied
+    ///
+    /// # Errors
+    /// Returns an error if an unchanged target cannot be read
+    pub fn record_plan(&mut self, project_path: &Path, plan: &DiffPlan) -> Result<(), LockError> {
+        for target in &plan.targets {
+            let Some(path) = relative_path(project_path, target) else {
+                continue;
+            };
+            if path == LOCK_FILE {
+                continue;
+            }
+            let planned = plan.operations.iter().find_map(|op| match op {
+                FileOperation::Create { path, content } if path == target => Some(content),
+                FileOperation::Modify {
+                    path, new_content, ..
+                } if path == target => Some(new_content),
+                _ => None,
+            });
+            let hash = match planned {
+                Some(content) => sha256(content),
+                None => sha256(&fs::read(target)?),
+            };
+            self.files.insert(path, hash);
+        }
+        Ok(())
+    }
+
+    /// Add writing this lock to `plan`, so it is previewed and backed up
+    /// with the files it describes
+    ///
+    /// # Errors
+    /// Returns an error if the existing lock file cannot be read
+    pub fn add_to_plan(&self, project_path: &Path, plan: &mut DiffPlan) -> Result<(), LockError> {
+        plan_file(project_path.join(LOCK_FILE), self.to_bytes(), plan)?;
+        Ok(())
+    }
+
+    /// Serialized lock file content
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut content = serde_json::to_string_pretty(self).unwrap_or_default();
+        content.push('\n');
+        content.into_bytes()
+    }
+}
+
+/// A locked file whose content no longer matches
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockMismatch {
+    pub path: String,
+    pub expected: String,
+    /// Current hash, or `None` if the file is missing
+    pub actual: Option<String>,
+}
+
+/// Result of checking a project against its lock
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockVerification {
+    pub lock: ProfileLock,
+    pub mismatches: Vec<LockMismatch>,
+}
+
+impl LockVerification {
+    /// Whether every locked file matches
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// How the profile a lock names relates to the local profile
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LockedSource {
+    /// The local profile is still at the locked revision or content
+    Current,
+    /// The local profile has changed; the locked revision came from history
+    Revision,
+    /// The local profile has changed and the locked revision is not known
+    /// locally; the current profile was used
+    Changed,
+}
+
+/// The profile to reproduce a lock from
+#[derive(Debug, Clone)]
+pub struct LockedProfile {
+    pub resolved: ResolvedProfile,
+    pub source: LockedSource,
+}
+
+/// Add the lock for `resolved` to `plan`
+///
+/// Records the profile's current revision, hashes its storage and every
+/// planned target, and adds writing `.claude/tars.lock` to the plan.
+///
+/// # Errors
+/// Returns an error if the revision cannot be recorded or a file cannot be read
+pub fn lock_plan(
+    conn: &Connection,
+    project_path: &Path,
+    resolved: &ResolvedProfile,
+    plan: &mut DiffPlan,
+) -> Result<ProfileLock, LockError> {
+    let store = ProfileStore::new(conn);
+    let stored = stored_profile(&store, resolved)?;
+    store.record_revision(&stored)?;
+    let revision = ProfileRevisionStore::new(conn)
+        .latest(stored.id)?
+        .map(|r| r.hash);
+    add_lock(project_path, resolved, plan, &stored, revision)
+}
+
+/// Add the lock for `resolved` to `plan` without touching the history
+///
+/// The lock names the revision [`lock_plan`] would record, so previews show
+/// the lock an apply writes without recording anything.
+///
+/// # Errors
+/// Returns an error if the profile or a file cannot be read
+pub fn preview_lock_plan(
+    conn: &Connection,
+    project_path: &Path,
+    resolved: &ResolvedProfile,
+    plan: &mut DiffPlan,
+) -> Result<ProfileLock, LockError> {
+    let stored = stored_profile(&ProfileStore::new(conn), resolved)?;
+    let files = match read_profile_files(stored.id) {
+        Ok(files) => files,
+        Err(StorageError::NoHomeDir) => RevisionFiles::new(),
+        Err(e) => return Err(e.into()),
+    };
+    let revision = revision_hash(&stored, &files)?;
+    add_lock(project_path, resolved, plan, &stored, Some(revision))
+}
+
+fn stored_profile(store: &ProfileStore, resolved: &ResolvedProfile) -> Result<Profile, LockError> {
+    store
+        .get(resolved.profile.id)?
+        .ok_or_else(|| LockError::ProfileNotFound(resolved.profile.name.clone()))
+}
+
+fn add_lock(
+    project_path: &Path,
+    resolved: &ResolvedProfile,
+    plan: &mut DiffPlan,
+    stored: &Profile,
+    revision: Option<String>,
+) -> Result<ProfileLock, LockError> {
+    let mut lock = ProfileLock::new(stored, revision, resolved.content_hash()?);
+    lock.record_plan(project_path, plan)?;
+    lock.add_to_plan(project_path, plan)?;
+    Ok(lock)
+}
+
+/// Read a project's lock file
+///
+/// # Errors
+/// Returns an error if there is no lock, it cannot be parsed, or it lists a
+/// path outside the project
+pub fn read_lock(project_path: &Path) -> Result<ProfileLock, LockError> {
+    let path = project_path.join(LOCK_FILE);
+    if !path.exists() {
+        return Err(LockError::NotFound(path.display().to_string()));
+    }
+    let content = fs::read_to_string(&path)?;
+    let lock: ProfileLock =
+        serde_json::from_str(&content).map_err(|e| LockError::Invalid(e.to_string()))?;
+    if lock.version > LOCK_FORMAT_VERSION {
+        return Err(LockError::UnsupportedVersion(lock.version));
+    }
+    for file in lock.files.keys() {
+        safe_join(project_path, Path::new(file))?;
+    }
+    Ok(lock)
+}
+
+/// Check the files of a project against its lock
+///
+/// # Errors
+/// Returns an error if the lock cannot be read
+pub fn verify_lock(project_path: &Path) -> Result<LockVerification, LockError> {
+    let lock = read_lock(project_path)?;
+    let mut mismatches = Vec::new();
+    for (file, expected) in &lock.files {
+        let path = safe_join(project_path, Path::new(file))?;
+        let actual = if path.is_file() {
+            Some(sha256(&fs::read(&path)?))
+        } else {
+            None
+        };
+        if actual.as_ref() != Some(expected) {
+            mismatches.push(LockMismatch {
+                path: file.clone(),
+                expected: expected.clone(),
+                actual,
+            });
+        }
+    }
+    Ok(LockVerification { lock, mismatches })
+}
+
+/// Find the profile a lock was written from
+///
+/// The profile is looked up by ID, then by name. If it has changed since the
+/// lock was written, the locked revision is loaded from its history when
+/// available; otherwise the current profile is returned as
+/// [`LockedSource::Changed`].
+///
+/// # Errors
+/// Returns an error if no such profile exists or it cannot be resolved
+pub fn locked_profile(conn: &Connection, lock: &ProfileLock) -> Result<LockedProfile, LockError> {
+    let store = ProfileStore::new(conn);
+    let profile = match store.get(lock.profile_id)? {
+        Some(profile) => profile,
+        None => store
+            .get_by_name(&lock.profile)?
+            .ok_or_else(|| LockError::ProfileNotFound(lock.profile.clone()))?,
+    };
+
+    let revisions = ProfileRevisionStore::new(conn);
+    store.record_revision(&profile)?;
+    let latest = revisions.latest(profile.id)?.map(|r| r.hash);
+
+    let resolved = resolve_profile(&store, &profile)?;
+    if lock.revision.is_none() || lock.revision == latest {
+        return Ok(LockedProfile {
+            resolved,
+            source: LockedSource::Current,
+        });
+    }
+    if let Some(revision) = revisions
+        .list(profile.id)?
+        .into_iter()
+        .find(|r| lock.revision.as_ref() == Some(&r.hash))
+    {
+        let snapshot = revisions.snapshot(&revision)?;
+        return Ok(LockedProfile {
+            resolved: resolve_profile(&store, &snapshot.profile)?,
+            source: LockedSource::Revision,
+        });
+    }
+    let source = if resolved.content_hash()? == lock.content_hash {
+        LockedSource::Current
+    } else {
+        LockedSource::Changed
+    };
+    Ok(LockedProfile { resolved, source })
+}
+
+fn sha256(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+fn relative_path(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    let parts = relative
+        .components()
+        .map(|c| match c {
+            Component::Normal(part) => Some(part.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(parts.join("/"))
+}
@@ -1,23 +1,67 @@
 //! Profile types and operations
 
+pub mod batch;
+pub mod compare;
 pub mod export;
+pub mod history;
+pub mod lock;
+pub mod repo;
+pub mod resolve;
+pub mod rules;
 pub mod snapshot;
 pub mod storage;
 pub mod sync;
+pub mod template;
 mod types;
 pub mod updates;
 
-pub use export::{ExportError, ExportedTool, ImportPreview, ProfileExport};
+pub use batch::{
+    apply_batch, plan_batch, rollback_batch, BatchError, BatchPlan, BatchRollback, ProjectPlan,
+    RollbackFailure,
+};
+pub use compare::{
+    compare_profiles, compare_stored_profiles, CompareToolKind, ProfileComparison,
+    SettingDifference, ToolDifference, ToolKey,
+};
+pub use export::{
+    BundleFile, BundleManifest, BundledToolKind, ExportError, ExportedTool, ImportConflict,
+    ImportPreview, ProfileExport, EXPORT_FORMAT_VERSION,
+};
+pub use history::{
+    diff_revisions, find_revision, revert_profile, FileChange, FileChangeKind, HistoryError,
+    RevertResult, RevisionDiff,
+};
+pub use lock::{
+    lock_plan, locked_profile, preview_lock_plan, read_lock, verify_lock, LockError, LockMismatch,
+    LockVerification, LockedProfile, LockedSource, ProfileLock, LOCK_FILE,
+};
+pub use repo::{
+    PullResult, PushResult, RepoError, RepoProfileState, RepoProfileStatus, RepoStatus, RepoSync,
+    RepoWorkspace,
+};
+pub use resolve::{
+    resolve_profile, resolve_with, ArtifactKind, LayerRef, Provenance, ResolveError,
+    ResolvedProfile,
+};
+pub use rules::{
+    apply_proposal, match_conditions, matching_rules, parse_git_remote, propose_assignment,
+    validate_rule, AppliedProposal, AssignmentProposal, GitRemote, ProjectFacts, RuleError,
+    RuleMatch,
+};
 pub use storage::{PluginManifest, ProfileTools, ProjectProfileState, StorageError};
 pub use sync::{
-    assign_profile_as_plugin, install_profile_plugin_to_project, install_profile_plugin_to_user,
-    regenerate_profile_plugin, reinstall_profile_plugin, remove_profile_from_marketplace,
-    sync_profile_marketplace, unassign_profile_plugin, uninstall_profile_plugin_from_project,
-    uninstall_profile_plugin_from_user, ApplyError, ApplyResult, MarketplaceSyncResult,
-    PluginAssignResult, SyncResult, PROFILE_MARKETPLACE,
+    assign_profile_as_plugin, assign_profile_to_project, install_profile_plugin_to_project,
+    install_profile_plugin_to_user, regenerate_profile_plugin, reinstall_profile_plugin,
+    remove_profile_from_marketplace, sync_profile_marketplace, unassign_profile_plugin,
+    uninstall_profile_plugin_from_project, uninstall_profile_plugin_from_user, ApplyError,
+    ApplyResult, AssignResult, MarketplaceSyncResult, PluginAssignResult, SyncResult,
+    PROFILE_MARKETPLACE,
 };
+pub use template::{Rendered, TemplateContext};
 pub use types::*;
 pub use updates::{
-    check_profile_updates, create_source_ref, migrate_legacy_profile, needs_migration,
-    set_source_mode, update_source_hash, ProfileUpdateCheck, ToolUpdateInfo,
+    check_plugin_updates, check_profile_updates, compute_mcp_entry_hash, create_mcp_source_ref,
+    create_source_ref, migrate_legacy_profile, needs_migration, pull_mcp_server,
+    read_mcp_server_entry, record_plugin_version, set_source_mode, update_source_hash,
+    PluginUpdateInfo, ProfileUpdateCheck, ToolUpdateInfo,
 };
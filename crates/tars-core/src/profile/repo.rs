@@ -0,0 +1,839 @@
+//! Git-backed profile repositories
+//!
+//! A profile repository is any git repository, a local path or a remote,
+//! whose top-level directories each hold one profile in bundle form:
+//!
+//! ```text
+//! <dir>/profile.json   a version 2 ProfileExport
+//! <dir>/files/...      the profile's stored tool content
+//! ```
+//!
+//! TARS keeps a clone under `~/.tars/profile-repos/<id>/` that follows the
+//! registered branch and never carries local commits on it. Pulling resets
+//! the clone to the fetched branch and imports the directories that changed;
+//! pushing commits local edits on a branch of their own and pushes it, so
+//! they can be reviewed before they land. Each link records the content hash
+//! both sides last agreed on, which tells upstream edits from local ones the
+//! way [`check_profile_updates`](super::check_profile_updates) does for
+//! tracked tools. All git work goes through the `git` CLI.
+
+use crate::git::git_discover;
+use crate::profile::export::{
+    bundle_export, is_bundled_path, profile_from_export, read_bundled_files, ExportError,
+    ProfileExport, BUNDLED_DIRS, BUNDLED_FILES, EXPORT_FORMAT_VERSION,
+};
+use crate::profile::history::read_revision_files;
+use crate::profile::storage::{profiles_base_dir, sanitize_tool_name, StorageError};
+use crate::profile::Profile;
+use crate::storage::db::DatabaseError;
+use crate::storage::profile_repos::{ProfileRepo, ProfileRepoLink, ProfileRepoStore};
+use crate::storage::profiles::ProfileStore;
+use crate::storage::RevisionFiles;
+use chrono::Utc;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use thiserror::Error;
+use uuid::Uuid;
+
+const PROFILE_ENTRY: &str = "profile.json";
+const FILES_DIR: &str = "files";
+
+/// Errors while working with profile repositories
+#[derive(Error, Debug)]
+pub enum RepoError {
+    #[error("git {0}")]
+    Git(String),
+
+    #[error("Invalid name: {0}")]
+    InvalidName(String),
+
+    #[error("Invalid profile repository: {0}")]
+    InvalidRepo(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+
+    #[error("Storage error: {0}")]
+    Storage(String),
+
+    #[error("Export error: {0}")]
+    Export(#[from] ExportError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl From<StorageError> for RepoError {
+    fn from(e: StorageError) -> Self {
+        Self::Storage(e.to_string())
+    }
+}
+
+/// How a profile in a repository compares with its local copy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RepoProfileState {
+    /// Same content on both sides
+    UpToDate,
+    /// Upstream only; a pull creates it locally
+    New,
+    /// Changed upstream since the last sync
+    UpstreamChanged,
+    /// Edited locally since the last sync
+    LocalChanged,
+    /// Changed on both sides since the last sync
+    Diverged,
+    /// Pushed on a branch that has not landed upstream yet
+    Unpublished,
+    /// Linked locally but no longer in the repository
+    RemovedUpstream,
+}
+
+/// One profile of a repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepoProfileStatus {
+    /// Directory in the repository
+    pub path: String,
+    pub name: String,
+    /// Linked local profile, if any
+    pub profile_id: Option<Uuid>,
+    pub state: RepoProfileState,
+}
+
+/// Result of checking a repository against the local profiles
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepoStatus {
+    pub repo: ProfileRepo,
+    /// Fetched commit of the tracked branch; `None` while it has no commits
+    pub upstream: Option<String>,
+    pub profiles: Vec<RepoProfileStatus>,
+}
+
+impl RepoStatus {
+    /// Whether a pull would change local profiles
+    #[must_use]
+    pub fn has_upstream_changes(&self) -> bool {
+        self.profiles.iter().any(|p| {
+            matches!(
+                p.state,
+                RepoProfileState::New
+                    | RepoProfileState::UpstreamChanged
+                    | RepoProfileState::Diverged
+                    | RepoProfileState::RemovedUpstream
+            )
+        })
+    }
+
+    /// Whether local edits are waiting to be pushed
+    #[must_use]
+    pub fn has_local_changes(&self) -> bool {
+        self.profiles.iter().any(|p| {
+            matches!(
+                p.state,
+                RepoProfileState::LocalChanged | RepoProfileState::Diverged
+            )
+        })
+    }
+}
+
+/// A profile a pull left alone
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedProfile {
+    pub name: String,
+    pub reason: String,
+}
+
+/// Result of pulling a repository
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PullResult {
+    pub upstream: Option<String>,
+    /// Profiles created locally
+    pub created: Vec<String>,
+    /// Local profiles replaced with the upstream version
+    pub updated: Vec<String>,
+    pub skipped: Vec<SkippedProfile>,
+    /// Profiles removed upstream; the local copies are kept but unlinked
+    pub unlinked: Vec<String>,
+}
+
+/// Result of pushing profiles to a repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushResult {
+    pub branch: String,
+    /// The pushed commit, or `None` when the repository already matched
+    pub commit: Option<String>,
+    pub profiles: Vec<String>,
+}
+
+/// Base directory of repository clones, `~/.tars/profile-repos`
+///
+/// # Errors
+/// Returns an error if the home directory cannot be found
+pub fn repos_base_dir() -> Result<PathBuf, StorageError> {
+    let home = dirs::home_dir().ok_or(StorageError::NoHomeDir)?;
+    Ok(home.join(".tars").join("profile-repos"))
+}
+
+/// Where repository clones and the profiles they feed are stored
+#[derive(Debug, Clone)]
+pub struct RepoWorkspace {
+    repos_base: PathBuf,
+    profiles_base: PathBuf,
+}
+
+impl RepoWorkspace {
+    /// Workspace with explicit directories
+    #[must_use]
+    pub fn new(repos_base: PathBuf, profiles_base: PathBuf) -> Self {
+        Self {
+            repos_base,
+            profiles_base,
+        }
+    }
+
+    /// Workspace under `~/.tars`
+    ///
+    /// # Errors
+    /// Returns an error if the home directory cannot be found
+    pub fn default_dirs() -> Result<Self, RepoError> {
+        Ok(Self::new(repos_base_dir()?, profiles_base_dir()?))
+    }
+
+    /// The clone of a repository
+    #[must_use]
+    pub fn checkout(&self, repo: &ProfileRepo) -> PathBuf {
+        self.repos_base.join(repo.id.to_string())
+    }
+
+    fn profile_dir(&self, profile_id: Uuid) -> PathBuf {
+        self.profiles_base.join(profile_id.to_string())
+    }
+}
+
+/// A profile directory read from a repository
+struct RepoEntry {
+    export: ProfileExport,
+    files: RevisionFiles,
+    hash: String,
+}
+
+/// A classified profile and, when it exists upstream, its repository entry
+type InspectedProfile = (RepoProfileStatus, Option<RepoEntry>);
+
+/// Profile repository operations against the database and a workspace
+pub struct RepoSync<'a> {
+    conn: &'a Connection,
+    workspace: RepoWorkspace,
+}
+
+impl<'a> RepoSync<'a> {
+    /// Create a new repository sync
+    #[must_use]
+    pub fn new(conn: &'a Connection, workspace: RepoWorkspace) -> Self {
+        Self { conn, workspace }
+    }
+
+    /// Register and clone a repository
+    ///
+    /// Without `branch`, the remote's default branch is followed.
+    ///
+    /// # Errors
+    /// Returns an error if the name is taken or invalid, or the clone fails
+    pub fn add(
+        &self,
+        name: &str,
+        url: &str,
+        branch: Option<&str>,
+    ) -> Result<ProfileRepo, RepoError> {
+        let store = ProfileRepoStore::new(self.conn);
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(RepoError::InvalidName(
+                "repository name cannot be empty".to_string(),
+            ));
+        }
+        if store.get_by_name(name)?.is_some() {
+            return Err(RepoError::Conflict(format!(
+                "a profile repository named '{name}' already exists"
+            )));
+        }
+        if let Some(branch) = branch {
+            check_branch_name(branch)?;
+        }
+
+        let mut repo = ProfileRepo::new(name.to_string(), url.to_string(), String::new());
+        let checkout = self.workspace.checkout(&repo);
+        if checkout.exists() {
+            fs::remove_dir_all(&checkout)?;
+        }
+        fs::create_dir_all(&self.workspace.repos_base)?;
+
+        let cloned = clone_repo(url, &checkout).and_then(|default_branch| {
+            repo.branch = branch.map_or(default_branch, str::to_string);
+            sync_checkout(&checkout, &repo.branch)?;
+            store.create(&repo)?;
+            Ok(())
+        });
+        if let Err(e) = cloned {
+            let _ = fs::remove_dir_all(&checkout);
+            return Err(e);
+        }
+
+        Ok(repo)
+    }
+
+    /// Unregister a repository and delete its clone; local profiles are kept
+    ///
+    /// # Errors
+    /// Returns an error if the repository cannot be removed
+    pub fn remove(&self, repo: &ProfileRepo) -> Result<(), RepoError> {
+        ProfileRepoStore::new(self.conn).delete(repo.id)?;
+        let checkout = self.workspace.checkout(repo);
+        if checkout.exists() {
+            fs::remove_dir_all(checkout)?;
+        }
+        Ok(())
+    }
+
+    /// Fetch the repository and compare it with the local profiles
+    ///
+    /// # Errors
+    /// Returns an error if the fetch fails or a profile cannot be read
+    pub fn status(&self, repo: &ProfileRepo) -> Result<RepoStatus, RepoError> {
+        let (upstream, items) = self.inspect(repo)?;
+        Ok(RepoStatus {
+            repo: repo.clone(),
+            upstream,
+            profiles: items.into_iter().map(|(status, _)| status).collect(),
+        })
+    }
+
+    /// Fetch the repository and bring upstream changes into local profiles
+    ///
+    /// Profiles edited on both sides are skipped unless `force` is set, in
+    /// which case the upstream version wins.
+    ///
+    /// # Errors
+    /// Returns an error if the fetch fails or a profile cannot be written
+    pub fn pull(&self, repo: &ProfileRepo, force: bool) -> Result<PullResult, RepoError> {
+        let store = ProfileRepoStore::new(self.conn);
+        let profiles = ProfileStore::new(self.conn);
+        let (upstream, items) = self.inspect(repo)?;
+        let mut result = PullResult {
+            upstream: upstream.clone(),
+            ..PullResult::default()
+        };
+
+        for (status, entry) in items {
+            let skip = |reason: &str| SkippedProfile {
+                name: status.name.clone(),
+                reason: reason.to_string(),
+            };
+            match (status.state, entry) {
+                (RepoProfileState::UpToDate, Some(entry)) => {
+                    if let Some(profile_id) = status.profile_id {
+                        self.link(repo, &status.path, profile_id, Some(entry.hash))?;
+                    }
+                }
+                (RepoProfileState::New, Some(entry)) => {
+                    if profiles.get_by_name(&status.name)?.is_some() {
+                        result
+                            .skipped
+                            .push(skip("a local profile with this name already exists"));
+                        continue;
+                    }
+                    let profile = profile_from_export(entry.export);
+                    write_bundled_files(&self.workspace.profile_dir(profile.id), &entry.files)?;
+                    profiles.create(&profile)?;
+                    self.link(repo, &status.path, profile.id, Some(entry.hash))?;
+                    result.created.push(profile.name);
+                }
+                (RepoProfileState::Diverged, Some(_)) if !force => {
+                    result
+                        .skipped
+                        .push(skip("changed both locally and upstream"));
+                }
+                (RepoProfileState::UpstreamChanged | RepoProfileState::Diverged, Some(entry)) => {
+                    let Some(profile_id) = status.profile_id else {
+                        continue;
+                    };
+                    let Some(mut profile) = profiles.get(profile_id)? else {
+                        continue;
+                    };
+                    let taken = profiles
+                        .get_by_name(&entry.export.name)?
+                        .is_some_and(|other| other.id != profile.id);
+                    if taken {
+                        result
+                            .skipped
+                            .push(skip("another local profile has the upstream name"));
+                        continue;
+                    }
+                    replace_from_export(&mut profile, entry.export);
+                    write_bundled_files(&self.workspace.profile_dir(profile.id), &entry.files)?;
+                    profiles.update(&profile)?;
+                    self.link(repo, &status.path, profile.id, Some(entry.hash))?;
+                    result.updated.push(profile.name);
+                }
+                (RepoProfileState::RemovedUpstream, _) => {
+                    store.unlink(repo.id, &status.path)?;
+                    result.unlinked.push(status.name);
+                }
+                _ => {}
+            }
+        }
+
+        store.set_head(repo.id, upstream.as_deref())?;
+        Ok(result)
+    }
+
+    /// Commit local profiles to `branch` and push it
+    ///
+    /// Profiles not yet in the repository are added under a directory named
+    /// after them. Pushing to the tracked branch itself publishes directly.
+    ///
+    /// # Errors
+    /// Returns an error if a directory belongs to another profile, or a git
+    /// command fails
+    pub fn push(
+        &self,
+        repo: &ProfileRepo,
+        to_push: &[Profile],
+        branch: &str,
+        message: &str,
+    ) -> Result<PushResult, RepoError> {
+        check_branch_name(branch)?;
+        let checkout = self.workspace.checkout(repo);
+        ensure_checkout_root(&checkout)?;
+        let upstream = sync_checkout(&checkout, &repo.branch)?;
+
+        let pushed = self.commit_and_push(
+            repo,
+            &checkout,
+            upstream.as_deref(),
+            to_push,
+            branch,
+            message,
+        );
+        // Leave the clone on the tracked branch whatever happened
+        let restored = sync_checkout(&checkout, &repo.branch);
+        let (result, hashes) = pushed?;
+        restored?;
+
+        let store = ProfileRepoStore::new(self.conn);
+        let publishing = branch == repo.branch;
+        for (profile, (path, hash)) in to_push.iter().zip(hashes) {
+            let synced_hash = if publishing {
+                Some(hash)
+            } else {
+                store
+                    .link_for_profile(profile.id)?
+                    .and_then(|link| link.synced_hash)
+            };
+            self.link(repo, &path, profile.id, synced_hash)?;
+        }
+        if publishing && result.commit.is_some() {
+            store.set_head(repo.id, result.commit.as_deref())?;
+        }
+
+        Ok(result)
+    }
+
+    fn commit_and_push(
+        &self,
+        repo: &ProfileRepo,
+        checkout: &Path,
+        upstream: Option<&str>,
+        to_push: &[Profile],
+        branch: &str,
+        message: &str,
+    ) -> Result<(PushResult, Vec<(String, String)>), RepoError> {
+        let links = ProfileRepoStore::new(self.conn).links(repo.id)?;
+        let entries = read_entries(checkout)?;
+
+        match upstream {
+            Some(commit) => {
+                git(checkout, &["checkout", "-f", "-B", branch, commit])?;
+            }
+            None => {
+                git(
+                    checkout,
+                    &["symbolic-ref", "HEAD", &format!("refs/heads/{branch}")],
+                )?;
+            }
+        }
+
+        let mut written = Vec::with_capacity(to_push.len());
+        for profile in to_push {
+            let path = if let Some(link) = links.iter().find(|l| l.profile_id == profile.id) {
+                link.path.clone()
+            } else {
+                let path = profile_dir_name(&profile.name)?;
+                let owner = links.iter().find(|l| l.path == path);
+                if owner.is_some() || entries.contains_key(&path) {
+                    return Err(RepoError::Conflict(format!(
+                        "'{path}' in {} belongs to another profile; pull it or rename '{}'",
+                        repo.name, profile.name
+                    )));
+                }
+                path
+            };
+
+            let export = bundle_export(profile);
+            let files = read_bundled_files(&self.workspace.profile_dir(profile.id))?;
+            let hash = content_hash(&export, &files)?;
+            if !entries.get(&path).is_some_and(|entry| entry.hash == hash) {
+                write_entry(&checkout.join(&path), &export, &files)?;
+            }
+            written.push((path, hash));
+        }
+
+        let paths: Vec<&str> = written.iter().map(|(path, _)| path.as_str()).collect();
+        let mut add = vec!["add", "-A", "--"];
+        add.extend(&paths);
+        git(checkout, &add)?;
+        let staged = git(checkout, &["status", "--porcelain", "--"])?;
+
+        let mut result = PushResult {
+            branch: branch.to_string(),
+            commit: None,
+            profiles: to_push.iter().map(|p| p.name.clone()).collect(),
+        };
+        if staged.is_empty() {
+            return Ok((result, written));
+        }
+
+        let mut commit = Vec::new();
+        if git_opt(checkout, &["config", "user.email"]).is_none() {
+            commit.extend(["-c", "user.name=TARS", "-c", "user.email=tars@localhost"]);
+        }
+        commit.extend(["commit", "-q", "-m", message]);
+        git(checkout, &commit)?;
+        let head = git(checkout, &["rev-parse", "HEAD"])?;
+
+        let refspec = format!("HEAD:refs/heads/{branch}");
+        if branch == repo.branch {
+            git(checkout, &["push", "-q", "origin", &refspec])?;
+        } else {
+            git(
+                checkout,
+                &["push", "-q", "--force-with-lease", "origin", &refspec],
+            )?;
+        }
+
+        result.commit = Some(head);
+        Ok((result, written))
+    }
+
+    /// Sync the clone and classify every repository and linked profile
+    fn inspect(
+        &self,
+        repo: &ProfileRepo,
+    ) -> Result<(Option<String>, Vec<InspectedProfile>), RepoError> {
+        let checkout = self.workspace.checkout(repo);
+        if !checkout.join(".git").exists() {
+            clone_repo(&repo.url, &checkout)?;
+        }
+        let upstream = sync_checkout(&checkout, &repo.branch)?;
+        let mut entries = read_entries(&checkout)?;
+        let mut links: HashMap<String, ProfileRepoLink> = ProfileRepoStore::new(self.conn)
+            .links(repo.id)?
+            .into_iter()
+            .map(|link| (link.path.clone(), link))
+            .collect();
+        let profiles = ProfileStore::new(self.conn);
+
+        let mut items = Vec::new();
+        let paths: Vec<String> = entries.keys().chain(links.keys()).cloned().collect();
+        for path in paths {
+            let entry = entries.remove(&path);
+            let link = links.remove(&path);
+            if entry.is_none() && link.is_none() {
+                continue; // listed twice
+            }
+            let local = match &link {
+                Some(link) => profiles.get(link.profile_id)?,
+                None => None,
+            };
+
+            let (name, state) = match (&entry, &link, &local) {
+                (Some(entry), Some(link), Some(profile)) => {
+                    let local_hash = content_hash(
+                        &bundle_export(profile),
+                        &read_bundled_files(&self.workspace.profile_dir(profile.id))?,
+                    )?;
+                    let state = classify(link.synced_hash.as_deref(), &entry.hash, &local_hash);
+                    (profile.name.clone(), state)
+                }
+                (Some(entry), _, _) => (entry.export.name.clone(), RepoProfileState::New),
+                (None, Some(link), Some(profile)) => {
+                    let state = if link.synced_hash.is_some() {
+                        RepoProfileState::RemovedUpstream
+                    } else {
+                        RepoProfileState::Unpublished
+                    };
+                    (profile.name.clone(), state)
+                }
+                (None, _, _) => continue,
+            };
+
+            items.push((
+                RepoProfileStatus {
+                    path,
+                    name,
+                    profile_id: local.map(|p| p.id),
+                    state,
+                },
+                entry,
+            ));
+        }
+        items.sort_by(|(a, _), (b, _)| a.path.cmp(&b.path));
+
+        Ok((upstream, items))
+    }
+
+    fn link(
+        &self,
+        repo: &ProfileRepo,
+        path: &str,
+        profile_id: Uuid,
+        synced_hash: Option<String>,
+    ) -> Result<(), RepoError> {
+        ProfileRepoStore::new(self.conn).set_link(&ProfileRepoLink {
+            repo_id: repo.id,
+            path: path.to_string(),
+            profile_id,
+            synced_hash,
+        })?;
+        Ok(())
+    }
+}
+
+/// Compare both sides against the hash they last agreed on
+fn classify(synced: Option<&str>, upstream: &str, local: &str) -> RepoProfileState {
+    if upstream == local {
+        return RepoProfileState::UpToDate;
+    }
+    match synced {
+        Some(synced) if synced == local => RepoProfileState::UpstreamChanged,
+        Some(synced) if synced == upstream => RepoProfileState::LocalChanged,
+        _ => RepoProfileState::Diverged,
+    }
+}
+
+/// Content hash of a profile in repository form
+///
+/// Covers the export without its version and timestamps, then each file's
+/// path and SHA256 in path order.
+fn content_hash(export: &ProfileExport, files: &RevisionFiles) -> Result<String, RepoError> {
+    let mut value = serde_json::to_value(export).map_err(ExportError::from)?;
+    if let Some(object) = value.as_object_mut() {
+        for key in ["version", "created_at", "exported_at"] {
+            object.remove(key);
+        }
+    }
+
+    let mut hasher = Sha256::new();
+    hasher.update(value.to_string().as_bytes());
+    for (path, content) in files {
+        hasher.update(b"\n");
+        hasher.update(path.as_bytes());
+        hasher.update(b"\0");
+        hasher.update(format!("{:x}", Sha256::digest(content)).as_bytes());
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Take everything shared through the repository from `export`
+fn replace_from_export(profile: &mut Profile, export: ProfileExport) {
+    let incoming = profile_from_export(export);
+    profile.name = incoming.name;
+    profile.description = incoming.description;
+    profile.tool_refs = incoming.tool_refs;
+    profile.plugin_set = incoming.plugin_set;
+    profile.repo_overlays = incoming.repo_overlays;
+    profile.user_overlays = incoming.user_overlays;
+    profile.adapters = incoming.adapters;
+    profile.updated_at = Utc::now();
+}
+
+/// Directory name for a profile added to a repository
+fn profile_dir_name(name: &str) -> Result<String, RepoError> {
+    let safe = sanitize_tool_name(name)
+        .map_err(|e| RepoError::InvalidName(format!("{name}: {e}")))?
+        .trim_start_matches('.')
+        .to_string();
+    if safe.is_empty() {
+        return Err(RepoError::InvalidName(format!(
+            "{name}: no usable characters for a directory name"
+        )));
+    }
+    Ok(safe)
+}
+
+/// Read every profile directory of a checkout
+fn read_entries(checkout: &Path) -> Result<BTreeMap<String, RepoEntry>, RepoError> {
+    let mut entries = BTreeMap::new();
+    for dir in fs::read_dir(checkout)? {
+        let dir = dir?;
+        let name = dir.file_name().to_string_lossy().into_owned();
+        let manifest = dir.path().join(PROFILE_ENTRY);
+        if name.starts_with('.') || !dir.file_type()?.is_dir() || !manifest.is_file() {
+            continue;
+        }
+
+        let export: ProfileExport = serde_json::from_str(&fs::read_to_string(&manifest)?)
+            .map_err(|e| RepoError::InvalidRepo(format!("{name}/{PROFILE_ENTRY}: {e}")))?;
+        if export.version > EXPORT_FORMAT_VERSION {
+            return Err(ExportError::UnsupportedVersion(export.version).into());
+        }
+        let files: RevisionFiles = read_revision_files(&dir.path().join(FILES_DIR))?
+            .into_iter()
+            .filter(|(path, _)| is_bundled_path(path))
+            .collect();
+        let hash = content_hash(&export, &files)?;
+        entries.insert(
+            name,
+            RepoEntry {
+                export,
+                files,
+                hash,
+            },
+        );
+    }
+    Ok(entries)
+}
+
+/// Replace a repository directory with a profile's export and files
+fn write_entry(dir: &Path, export: &ProfileExport, files: &RevisionFiles) -> Result<(), RepoError> {
+    if dir.exists() {
+        fs::remove_dir_all(dir)?;
+    }
+    fs::create_dir_all(dir)?;
+    fs::write(
+        dir.join(PROFILE_ENTRY),
+        serde_json::to_string_pretty(export).map_err(ExportError::from)? + "\n",
+    )?;
+    write_files(&dir.join(FILES_DIR), files)
+}
+
+/// Replace the bundled directories and files of a profile's storage with
+/// `files`
+fn write_bundled_files(profile_dir: &Path, files: &RevisionFiles) -> Result<(), RepoError> {
+    for dir in BUNDLED_DIRS {
+        let path = profile_dir.join(dir);
+        if path.exists() {
+            fs::remove_dir_all(path)?;
+        }
+    }
+    for name in BUNDLED_FILES {
+        let path = profile_dir.join(name);
+        if fs::symlink_metadata(&path).is_ok() {
+            fs::remove_file(path)?;
+        }
+    }
+    fs::create_dir_all(profile_dir)?;
+    write_files(profile_dir, files)
+}
+
+fn write_files(root: &Path, files: &RevisionFiles) -> Result<(), RepoError> {
+    for (path, content) in files {
+        let safe = Path::new(path)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)));
+        if !safe || !is_bundled_path(path) {
+            return Err(RepoError::InvalidRepo(format!("unsafe file path: {path}")));
+        }
+        let target = root.join(path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(target, content)?;
+    }
+    Ok(())
+}
+
+/// Clone `url` without a checkout and return its default branch
+///
+/// The tracked branch is checked out by [`sync_checkout`], which also copes
+/// with a remote that has no commits yet.
+fn clone_repo(url: &str, checkout: &Path) -> Result<String, RepoError> {
+    let target = checkout.to_string_lossy();
+    git_in(None, &["clone", "-q", "--no-checkout", "--", url, &target])?;
+
+    let default_branch = git_opt(
+        checkout,
+        &["symbolic-ref", "--short", "refs/remotes/origin/HEAD"],
+    )
+    .and_then(|r| r.strip_prefix("origin/").map(str::to_string))
+    .or_else(|| git_opt(checkout, &["symbolic-ref", "--short", "HEAD"]))
+    .unwrap_or_else(|| "main".to_string());
+    Ok(default_branch)
+}
+
+/// Fetch and reset the clone to the tracked branch
+///
+/// Returns the branch's commit, or `None` while the remote has no commits
+/// on it.
+fn sync_checkout(checkout: &Path, branch: &str) -> Result<Option<String>, RepoError> {
+    ensure_checkout_root(checkout)?;
+    git(checkout, &["fetch", "-q", "--prune", "origin"])?;
+    let tracking = format!("refs/remotes/origin/{branch}");
+    let Some(upstream) = git_opt(checkout, &["rev-parse", "--verify", "--quiet", &tracking]) else {
+        git(
+            checkout,
+            &["symbolic-ref", "HEAD", &format!("refs/heads/{branch}")],
+        )?;
+        return Ok(None);
+    };
+    git(checkout, &["checkout", "-q", "-f", "-B", branch, &upstream])?;
+    git(checkout, &["clean", "-q", "-f", "-d", "-x"])?;
+    Ok(Some(upstream))
+}
+
+fn check_branch_name(branch: &str) -> Result<(), RepoError> {
+    if branch.starts_with('-') || git_in(None, &["check-ref-format", "--branch", branch]).is_err() {
+        return Err(RepoError::InvalidName(format!(
+            "not a valid branch name: {branch}"
+        )));
+    }
+    Ok(())
+}
+
+/// Make sure `checkout` is the top level of its own repository
+///
+/// A clone whose `.git` is gone would otherwise let git find an enclosing
+/// repository and fetch, reset and commit there.
+fn ensure_checkout_root(checkout: &Path) -> Result<(), RepoError> {
+    let toplevel = git_discover(checkout, &["rev-parse", "--show-toplevel"]).map_err(|_| {
+        RepoError::InvalidRepo(format!("{} is not a git clone", checkout.display()))
+    })?;
+    if fs::canonicalize(&toplevel)? != fs::canonicalize(checkout)? {
+        return Err(RepoError::InvalidRepo(format!(
+            "{} is not a git clone of its own (found {toplevel})",
+            checkout.display()
+        )));
+    }
+    Ok(())
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String, RepoError> {
+    git_in(Some(dir), args)
+}
+
+/// Run git and return its output, or `None` if it fails
+fn git_opt(dir: &Path, args: &[&str]) -> Option<String> {
+    git(dir, args).ok().filter(|out| !out.is_empty())
+}
+
+/// Run git, scoped to the repository at `dir` when given
+fn git_in(dir: Option<&Path>, args: &[&str]) -> Result<String, RepoError> {
+    crate::git::git_in(dir, args).map_err(RepoError::Git)
+}
@@ -0,0 +1,579 @@
+//! Profile inheritance: layering `extends` parents into one resolved profile
+//!
+//! Layers are linearized depth-first in `extends` order: every parent comes
+//! before the profiles that extend it, each profile appears once, and the
+//! requested profile is last. Each artifact kind is then folded layer by
+//! layer. A layer combines with what it inherits according to its own
+//! `adapters.merge_strategies` entry for that kind (keyed by
+//! [`ArtifactKind::as_str`], default [`MergeStrategy::Merge`]):
+//!
+//! - `Merge`: same-named artifacts are overridden and new ones added. Hook
+//!   groups, permission rules and CLAUDE.md are combined instead.
+//! - `Replace`: if the layer defines any artifact of the kind, everything
+//!   inherited of that kind is dropped.
+//! - `Skip`: inherited artifacts win; the layer only adds new names.
+
+use crate::profile::storage::{compute_profile_content_hash, profile_dir, StorageError};
+use crate::profile::{
+    ClaudeMdOverlay, HooksOverlay, MergeStrategy, OverlayMode, PermissionsOverlay, PluginSet,
+    Profile, RepoOverlays, ToolRef, ToolType, UserOverlays,
+};
+use crate::storage::db::DatabaseError;
+use crate::storage::profiles::ProfileStore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Errors during profile resolution
+#[derive(Error, Debug)]
+pub enum ResolveError {
+    #[error("Profile inheritance cycle: {0}")]
+    Cycle(String),
+
+    #[error("Parent profile not found: {0}")]
+    MissingParent(Uuid),
+
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+}
+
+/// Kinds of artifacts that are layered independently
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtifactKind {
+    /// Tool references backed by profile storage
+    Tools,
+    /// Repo skill overlays
+    Skills,
+    /// Repo command overlays
+    Commands,
+    /// Repo agent overlays
+    Agents,
+    /// Repo MCP server overlays
+    McpServers,
+    /// Hook events
+    Hooks,
+    /// Permission rules
+    Permissions,
+    /// CLAUDE.md overlay
+    ClaudeMd,
+    /// User skill overlays
+    UserSkills,
+    /// User command overlays
+    UserCommands,
+    /// Plugins to install
+    Plugins,
+    /// Marketplaces to add
+    Marketplaces,
+}
+
+impl ArtifactKind {
+    /// Key used in `Adapters::merge_strategies`
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Tools => "tools",
+            Self::Skills => "skills",
+            Self::Commands => "commands",
+            Self::Agents => "agents",
+            Self::McpServers => "mcp_servers",
+            Self::Hooks => "hooks",
+            Self::Permissions => "permissions",
+            Self::ClaudeMd => "claude_md",
+            Self::UserSkills => "user_skills",
+            Self::UserCommands => "user_commands",
+            Self::Plugins => "plugins",
+            Self::Marketplaces => "marketplaces",
+        }
+    }
+}
+
+impl std::fmt::Display for ArtifactKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A profile taking part in a resolution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LayerRef {
+    pub id: Uuid,
+    pub name: String,
+}
+
+impl LayerRef {
+    fn of(profile: &Profile) -> Self {
+        Self {
+            id: profile.id,
+            name: profile.name.clone(),
+        }
+    }
+}
+
+/// Where a resolved artifact came from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    pub kind: ArtifactKind,
+    /// Tool type, for [`ArtifactKind::Tools`]
+    pub tool_type: Option<ToolType>,
+    pub name: String,
+    /// Layer whose definition is in effect (the last contributor when combined)
+    pub source: LayerRef,
+    /// Earlier layers that also defined it, overridden or combined
+    pub earlier: Vec<LayerRef>,
+}
+
+/// A profile with its `extends` chain flattened
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolvedProfile {
+    /// The flattened profile; identity and adapters are the requested profile's
+    pub profile: Profile,
+    /// Layers in application order, base first
+    pub layers: Vec<LayerRef>,
+    /// Origin of every resolved artifact
+    pub provenance: Vec<Provenance>,
+    #[serde(skip)]
+    layer_profiles: Vec<Profile>,
+}
+
+impl ResolvedProfile {
+    /// Resolve a profile on its own, ignoring `extends`
+    #[must_use]
+    pub fn standalone(profile: Profile) -> Self {
+        fold_layers(&profile, vec![profile.clone()])
+    }
+
+    /// Find the provenance of an artifact
+    #[must_use]
+    pub fn origin(
+        &self,
+        kind: ArtifactKind,
+        tool_type: Option<ToolType>,
+        name: &str,
+    ) -> Option<&Provenance> {
+        self.provenance
+            .iter()
+            .find(|p| p.kind == kind && p.tool_type == tool_type && p.name == name)
+    }
+
+    /// Profile whose storage holds a resolved tool's files
+    #[must_use]
+    pub fn tool_owner(&self, tool: &ToolRef) -> Uuid {
+        self.origin(ArtifactKind::Tools, Some(tool.tool_type), &tool.name)
+            .map_or(self.profile.id, |p| p.source.id)
+    }
+
+    /// Hash of the storage content of every layer
+    ///
+    /// Equals [`compute_profile_content_hash`] for a profile without parents.
+    pub fn content_hash(&self) -> Result<String, StorageError> {
+        if let [layer] = self.layers.as_slice() {
+            return compute_profile_content_hash(layer.id);
+        }
+        let mut hasher = Sha256::new();
+        for layer in &self.layers {
+            hasher.update(layer.id.as_bytes());
+            hasher.update(compute_profile_content_hash(layer.id)?.as_bytes());
+        }
+        Ok(hex::encode(hasher.finalize()))
+    }
+
+    /// Layered `hooks.json` from profile storage, in plugin format
+    ///
+    /// Each layer's file is folded with its `hooks` merge strategy.
+    pub fn stored_hooks(&self) -> Result<Option<serde_json::Value>, StorageError> {
+        let mut hooks = None;
+        let mut provenance = Vec::new();
+        for layer in &self.layer_profiles {
+            let path = profile_dir(layer.id)?.join("hooks.json");
+            if !path.exists() {
+                continue;
+            }
+            let content = fs::read_to_string(&path).map_err(|e| StorageError::Io(e.to_string()))?;
+            let file: StoredHooks =
+                serde_json::from_str(&content).map_err(|e| StorageError::Io(e.to_string()))?;
+            let incoming = HooksOverlay { events: file.hooks };
+            fold_hooks(
+                &mut hooks,
+                Some(&incoming),
+                strategy(layer, ArtifactKind::Hooks),
+                &LayerRef::of(layer),
+                &mut provenance,
+            );
+        }
+        Ok(hooks
+            .filter(|h: &HooksOverlay| !h.events.is_empty())
+            .map(|h| serde_json::json!({ "hooks": h.events })))
+    }
+}
+
+#[derive(Deserialize)]
+struct StoredHooks {
+    #[serde(default)]
+    hooks: std::collections::BTreeMap<String, Vec<serde_json::Value>>,
+}
+
+/// Resolve a profile's `extends` chain from the database
+///
+/// `profile` itself is used as given, so unsaved edits (such as a new
+/// `extends` list) can be validated before they are stored.
+///
+/// # Errors
+/// Returns an error on a cycle, a missing parent, or a database failure
+pub fn resolve_profile(
+    store: &ProfileStore,
+    profile: &Profile,
+) -> Result<ResolvedProfile, ResolveError> {
+    resolve_with(profile, |id| Ok(store.get(id)?))
+}
+
+/// Resolve a profile's `extends` chain with a custom parent lookup
+///
+/// # Errors
+/// Returns an error on a cycle, a missing parent, or a lookup failure
+pub fn resolve_with<F>(profile: &Profile, mut lookup: F) -> Result<ResolvedProfile, ResolveError>
+where
+    F: FnMut(Uuid) -> Result<Option<Profile>, ResolveError>,
+{
+    let mut layers = Vec::new();
+    linearize(
+        profile,
+        &mut lookup,
+        &mut Vec::new(),
+        &mut HashSet::new(),
+        &mut layers,
+    )?;
+    Ok(fold_layers(profile, layers))
+}
+
+fn linearize<F>(
+    profile: &Profile,
+    lookup: &mut F,
+    stack: &mut Vec<LayerRef>,
+    done: &mut HashSet<Uuid>,
+    layers: &mut Vec<Profile>,
+) -> Result<(), ResolveError>
+where
+    F: FnMut(Uuid) -> Result<Option<Profile>, ResolveError>,
+{
+    if let Some(start) = stack.iter().position(|layer| layer.id == profile.id) {
+        let mut path: Vec<_> = stack[start..].iter().map(|l| l.name.as_str()).collect();
+        path.push(&profile.name);
+        return Err(ResolveError::Cycle(path.join(" -> ")));
+    }
+    if done.contains(&profile.id) {
+        return Ok(());
+    }
+
+    stack.push(LayerRef::of(profile));
+    for parent_id in &profile.extends {
+        let parent = lookup(*parent_id)?.ok_or(ResolveError::MissingParent(*parent_id))?;
+        linearize(&parent, lookup, stack, done, layers)?;
+    }
+    stack.pop();
+
+    done.insert(profile.id);
+    layers.push(profile.clone());
+    Ok(())
+}
+
+fn strategy(layer: &Profile, kind: ArtifactKind) -> MergeStrategy {
+    layer
+        .adapters
+        .merge_strategies
+        .get(kind.as_str())
+        .copied()
+        .unwrap_or(MergeStrategy::Merge)
+}
+
+fn fold_layers(leaf: &Profile, layers: Vec<Profile>) -> ResolvedProfile {
+    let mut resolved = Profile {
+        tool_refs: Vec::new(),
+        plugin_set: PluginSet::default(),
+        repo_overlays: RepoOverlays::default(),
+        user_overlays: UserOverlays::default(),
+        ..leaf.clone()
+    };
+    let mut provenance = Vec::new();
+
+    for layer in &layers {
+        let layer_ref = LayerRef::of(layer);
+        let prov = &mut provenance;
+        let at = |kind| strategy(layer, kind);
+
+        fold_named(
+            &mut resolved.tool_refs,
+            &layer.tool_refs,
+            at(ArtifactKind::Tools),
+            ArtifactKind::Tools,
+            |t| (Some(t.tool_type), t.name.clone()),
+            &layer_ref,
+            prov,
+        );
+
+        let repo = &mut resolved.repo_overlays;
+        let incoming = &layer.repo_overlays;
+        fold_named(
+            &mut repo.skills,
+            &incoming.skills,
+            at(ArtifactKind::Skills),
+            ArtifactKind::Skills,
+            |s| (None, s.name.clone()),
+            &layer_ref,
+            prov,
+        );
+        fold_named(
+            &mut repo.commands,
+            &incoming.commands,
+            at(ArtifactKind::Commands),
+            ArtifactKind::Commands,
+            |c| (None, c.name.clone()),
+            &layer_ref,
+            prov,
+        );
+        fold_named(
+            &mut repo.agents,
+            &incoming.agents,
+            at(ArtifactKind::Agents),
+            ArtifactKind::Agents,
+            |a| (None, a.name.clone()),
+            &layer_ref,
+            prov,
+        );
+        fold_named(
+            &mut repo.mcp_servers,
+            &incoming.mcp_servers,
+            at(ArtifactKind::McpServers),
+            ArtifactKind::McpServers,
+            |m| (None, m.name.clone()),
+            &layer_ref,
+            prov,
+        );
+        fold_hooks(
+            &mut repo.hooks,
+            incoming.hooks.as_ref(),
+            at(ArtifactKind::Hooks),
+            &layer_ref,
+            prov,
+        );
+        fold_permissions(
+            &mut repo.permissions,
+            incoming.permissions.as_ref(),
+            at(ArtifactKind::Permissions),
+            &layer_ref,
+            prov,
+        );
+        fold_claude_md(
+            &mut repo.claude_md,
+            incoming.claude_md.as_ref(),
+            at(ArtifactKind::ClaudeMd),
+            &layer_ref,
+            prov,
+        );
+
+        fold_named(
+            &mut resolved.user_overlays.skills,
+            &layer.user_overlays.skills,
+            at(ArtifactKind::UserSkills),
+            ArtifactKind::UserSkills,
+            |s| (None, s.name.clone()),
+            &layer_ref,
+            prov,
+        );
+        fold_named(
+            &mut resolved.user_overlays.commands,
+            &layer.user_overlays.commands,
+            at(ArtifactKind::UserCommands),
+            ArtifactKind::UserCommands,
+            |c| (None, c.name.clone()),
+            &layer_ref,
+            prov,
+        );
+
+        fold_named(
+            &mut resolved.plugin_set.plugins,
+            &layer.plugin_set.plugins,
+            at(ArtifactKind::Plugins),
+            ArtifactKind::Plugins,
+            |p| (None, p.id.clone()),
+            &layer_ref,
+            prov,
+        );
+        fold_named(
+            &mut resolved.plugin_set.marketplaces,
+            &layer.plugin_set.marketplaces,
+            at(ArtifactKind::Marketplaces),
+            ArtifactKind::Marketplaces,
+            |m| (None, m.name.clone()),
+            &layer_ref,
+            prov,
+        );
+    }
+
+    ResolvedProfile {
+        profile: resolved,
+        layers: layers.iter().map(LayerRef::of).collect(),
+        provenance,
+        layer_profiles: layers,
+    }
+}
+
+fn record(
+    provenance: &mut Vec<Provenance>,
+    kind: ArtifactKind,
+    tool_type: Option<ToolType>,
+    name: &str,
+    layer: &LayerRef,
+) {
+    if let Some(existing) = provenance
+        .iter_mut()
+        .find(|p| p.kind == kind && p.tool_type == tool_type && p.name == name)
+    {
+        let previous = std::mem::replace(&mut existing.source, layer.clone());
+        existing.earlier.push(previous);
+    } else {
+        provenance.push(Provenance {
+            kind,
+            tool_type,
+            name: name.to_string(),
+            source: layer.clone(),
+            earlier: Vec::new(),
+        });
+    }
+}
+
+fn fold_named<T: Clone>(
+    acc: &mut Vec<T>,
+    incoming: &[T],
+    strategy: MergeStrategy,
+    kind: ArtifactKind,
+    key: fn(&T) -> (Option<ToolType>, String),
+    layer: &LayerRef,
+    provenance: &mut Vec<Provenance>,
+) {
+    if incoming.is_empty() {
+        return;
+    }
+    if strategy == MergeStrategy::Replace {
+        acc.clear();
+        provenance.retain(|p| p.kind != kind);
+    }
+
+    for item in incoming {
+        let item_key = key(item);
+        match acc.iter().position(|existing| key(existing) == item_key) {
+            Some(_) if strategy == MergeStrategy::Skip => continue,
+            Some(index) => acc[index] = item.clone(),
+            None => acc.push(item.clone()),
+        }
+        record(provenance, kind, item_key.0, &item_key.1, layer);
+    }
+}
+
+fn fold_hooks(
+    acc: &mut Option<HooksOverlay>,
+    incoming: Option<&HooksOverlay>,
+    strategy: MergeStrategy,
+    layer: &LayerRef,
+    provenance: &mut Vec<Provenance>,
+) {
+    let Some(incoming) = incoming.filter(|h| !h.events.is_empty()) else {
+        return;
+    };
+    if strategy == MergeStrategy::Replace {
+        *acc = None;
+        provenance.retain(|p| p.kind != ArtifactKind::Hooks);
+    }
+
+    let acc = acc.get_or_insert_with(HooksOverlay::default);
+    for (event, groups) in &incoming.events {
+        match acc.events.get_mut(event) {
+            Some(_) if strategy == MergeStrategy::Skip => continue,
+            Some(existing) => {
+                for group in groups {
+                    if !existing.contains(group) {
+                        existing.push(group.clone());
+                    }
+                }
+            }
+            None => {
+                acc.events.insert(event.clone(), groups.clone());
+            }
+        }
+        record(provenance, ArtifactKind::Hooks, None, event, layer);
+    }
+}
+
+fn fold_permissions(
+    acc: &mut Option<PermissionsOverlay>,
+    incoming: Option<&PermissionsOverlay>,
+    strategy: MergeStrategy,
+    layer: &LayerRef,
+    provenance: &mut Vec<Provenance>,
+) {
+    let Some(incoming) = incoming else {
+        return;
+    };
+
+    match (acc.as_mut(), strategy) {
+        (Some(_), MergeStrategy::Skip) => return,
+        (Some(existing), MergeStrategy::Merge) => {
+            union(&mut existing.allow, &incoming.allow);
+            union(&mut existing.deny, &incoming.deny);
+            union(&mut existing.ask, &incoming.ask);
+            union(
+                &mut existing.additional_directories,
+                &incoming.additional_directories,
+            );
+            if incoming.default_mode.is_some() {
+                existing.default_mode.clone_from(&incoming.default_mode);
+            }
+        }
+        _ => *acc = Some(incoming.clone()),
+    }
+    record(
+        provenance,
+        ArtifactKind::Permissions,
+        None,
+        "permissions",
+        layer,
+    );
+}
+
+fn union(acc: &mut Vec<String>, incoming: &[String]) {
+    for item in incoming {
+        if !acc.contains(item) {
+            acc.push(item.clone());
+        }
+    }
+}
+
+fn fold_claude_md(
+    acc: &mut Option<ClaudeMdOverlay>,
+    incoming: Option<&ClaudeMdOverlay>,
+    strategy: MergeStrategy,
+    layer: &LayerRef,
+    provenance: &mut Vec<Provenance>,
+) {
+    let Some(incoming) = incoming else {
+        return;
+    };
+
+    match (acc.as_mut(), strategy) {
+        (Some(_), MergeStrategy::Skip) => return,
+        (Some(existing), MergeStrategy::Merge) => match incoming.mode {
+            OverlayMode::Replace => *existing = incoming.clone(),
+            OverlayMode::Prepend => {
+                existing.content = format!("{}\n\n{}", incoming.content, existing.content);
+            }
+            OverlayMode::Append => {
+                existing.content = format!("{}\n\n{}", existing.content, incoming.content);
+            }
+        },
+        _ => *acc = Some(incoming.clone()),
+    }
+    record(provenance, ArtifactKind::ClaudeMd, None, "CLAUDE.md", layer);
+}
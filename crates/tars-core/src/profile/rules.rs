@@ -0,0 +1,436 @@
+//! Rule-based profile assignment
+//!
+//! [`AssignmentRule`]s stored in the database pick a profile for projects
+//! when they are discovered or added. A project is described by
+//! [`ProjectFacts`]: its path, the languages and dependency manifests the
+//! [`crate::stats`] detectors find, the host and organization of its git
+//! `origin`, and the `platforms` of its metadata.
+//!
+//! The highest-priority enabled rule that matches becomes an
+//! [`AssignmentProposal`] carrying the diff plan for its profile. Callers show
+//! the proposal, or apply it right away with [`apply_proposal`] when the rule
+//! is set to auto-apply; either way the project is backed up first.
+
+use crate::apply::{apply_operations, write::ApplyError};
+use crate::backup::Backup;
+use crate::diff::plan::{generate_plan_with_context, PlanError};
+use crate::diff::DiffPlan;
+use crate::profile::resolve::{resolve_profile, ResolveError};
+use crate::profile::sync::{assign_profile_to_project, ApplyError as AssignError, AssignResult};
+use crate::profile::TemplateContext;
+use crate::project::Project;
+use crate::stats::collect_project_stats;
+use crate::storage::assignment_rules::{AssignmentRule, AssignmentRuleStore, RuleConditions};
+use crate::storage::backups::BackupStore;
+use crate::storage::db::DatabaseError;
+use crate::storage::metadata::{MetadataStore, ProjectMetadata};
+use crate::storage::profiles::ProfileStore;
+use chrono::Utc;
+use glob::{MatchOptions, Pattern};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Errors while evaluating or applying assignment rules
+#[derive(Error, Debug)]
+pub enum RuleError {
+    #[error("A rule needs at least one condition")]
+    NoConditions,
+
+    #[error("Invalid path glob '{glob}': {message}")]
+    InvalidGlob { glob: String, message: String },
+
+    #[error("Profile not found: {0}")]
+    ProfileNotFound(Uuid),
+
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+
+    #[error("Resolve error: {0}")]
+    Resolve(#[from] ResolveError),
+
+    #[error("Plan error: {0}")]
+    Plan(#[from] PlanError),
+
+    #[error("Apply error: {0}")]
+    Apply(#[from] ApplyError),
+
+    #[error("Assign error: {0}")]
+    Assign(#[from] AssignError),
+
+    #[error("Failed to write backup: {0}")]
+    Backup(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Host and owner of a git remote
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitRemote {
+    pub host: String,
+    /// First path segment, the user or organization on most forges
+    pub org: Option<String>,
+}
+
+/// What assignment rules can match a project on
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProjectFacts {
+    pub path: PathBuf,
+    /// Languages with code, largest first
+    pub languages: Vec<String>,
+    /// Dependency manifests found in the project, e.g. `Cargo.toml`
+    pub dependency_files: Vec<String>,
+    pub git_remote: Option<GitRemote>,
+    pub platforms: Vec<String>,
+}
+
+impl ProjectFacts {
+    /// Detect the facts of the project at `path`
+    #[must_use]
+    pub fn detect(path: &Path, metadata: Option<&ProjectMetadata>) -> Self {
+        let stats = collect_project_stats(path);
+        Self {
+            path: path.to_path_buf(),
+            languages: stats
+                .languages_by_code()
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            dependency_files: stats
+                .dependencies
+                .iter()
+                .map(|d| d.manifest.clone())
+                .collect(),
+            git_remote: origin_url(path).as_deref().and_then(parse_git_remote),
+            platforms: metadata.map(|m| m.platforms.clone()).unwrap_or_default(),
+        }
+    }
+}
+
+/// An enabled rule that matches a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleMatch {
+    pub rule: AssignmentRule,
+    /// One entry per satisfied condition, e.g. `language Rust`
+    pub reasons: Vec<String>,
+}
+
+/// A profile proposed for a project by a matching rule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssignmentProposal {
+    pub rule: AssignmentRule,
+    pub reasons: Vec<String>,
+    pub profile_id: Uuid,
+    pub profile_name: String,
+    /// Changes applying the profile would make
+    pub plan: DiffPlan,
+}
+
+/// Check that a rule can be stored
+///
+/// # Errors
+/// Returns an error if the rule has no conditions or an invalid glob
+pub fn validate_rule(rule: &AssignmentRule) -> Result<(), RuleError> {
+    if rule.conditions.is_empty() {
+        return Err(RuleError::NoConditions);
+    }
+    for glob in &rule.conditions.path_globs {
+        compile_glob(glob)?;
+    }
+    Ok(())
+}
+
+/// Reasons `conditions` match `facts`, or `None` if any condition fails
+#[must_use]
+pub fn match_conditions(conditions: &RuleConditions, facts: &ProjectFacts) -> Option<Vec<String>> {
+    if conditions.is_empty() {
+        return None;
+    }
+    let mut reasons = Vec::new();
+
+    if !conditions.path_globs.is_empty() {
+        let glob = conditions.path_globs.iter().find(|glob| {
+            compile_glob(glob)
+                .is_ok_and(|pattern| pattern.matches_path_with(&facts.path, path_match_options()))
+        })?;
+        reasons.push(format!("path matches {glob}"));
+    }
+
+    let checks: [(&str, &[String], Vec<&str>); 5] = [
+        (
+            "language",
+            &conditions.languages,
+            facts.languages.iter().map(String::as_str).collect(),
+        ),
+        (
+            "dependency file",
+            &conditions.dependency_files,
+            facts.dependency_files.iter().map(String::as_str).collect(),
+        ),
+        (
+            "git host",
+            &conditions.git_hosts,
+            facts.git_remote.iter().map(|r| r.host.as_str()).collect(),
+        ),
+        (
+            "git org",
+            &conditions.git_orgs,
+            facts
+                .git_remote
+                .iter()
+                .filter_map(|r| r.org.as_deref())
+                .collect(),
+        ),
+        (
+            "platform",
+            &conditions.platforms,
+            facts.platforms.iter().map(String::as_str).collect(),
+        ),
+    ];
+    for (label, wanted, found) in checks {
+        if wanted.is_empty() {
+            continue;
+        }
+        let hit = found
+            .into_iter()
+            .find(|value| wanted.iter().any(|w| w.eq_ignore_ascii_case(value)))?;
+        reasons.push(format!("{label} {hit}"));
+    }
+
+    Some(reasons)
+}
+
+/// Enabled rules matching `facts`, highest priority first
+#[must_use]
+pub fn matching_rules(rules: &[AssignmentRule], facts: &ProjectFacts) -> Vec<RuleMatch> {
+    let mut matches: Vec<_> = rules
+        .iter()
+        .filter(|rule| rule.enabled)
+        .filter_map(|rule| {
+            match_conditions(&rule.conditions, facts).map(|reasons| RuleMatch {
+                rule: rule.clone(),
+                reasons,
+            })
+        })
+        .collect();
+    matches.sort_by_key(|m| std::cmp::Reverse(m.rule.priority));
+    matches
+}
+
+/// Propose a profile for a project from the stored rules
+///
+/// Projects that already have a profile get no proposal.
+///
+/// # Errors
+/// Returns an error if the rules cannot be read or the plan cannot be generated
+pub fn propose_assignment(
+    conn: &Connection,
+    project: &Project,
+) -> Result<Option<AssignmentProposal>, RuleError> {
+    if project.assigned_profile_id.is_some() {
+        return Ok(None);
+    }
+    let rules = AssignmentRuleStore::new(conn).list()?;
+    if !rules.iter().any(|r| r.enabled) {
+        return Ok(None);
+    }
+
+    let metadata = MetadataStore::new(conn).get(project.id)?;
+    let facts = ProjectFacts::detect(&project.path, metadata.as_ref());
+    let Some(matched) = matching_rules(&rules, &facts).into_iter().next() else {
+        return Ok(None);
+    };
+
+    let profiles = ProfileStore::new(conn);
+    let profile = profiles
+        .get(matched.rule.profile_id)?
+        .ok_or(RuleError::ProfileNotFound(matched.rule.profile_id))?;
+    let resolved = resolve_profile(&profiles, &profile)?;
+    let context = TemplateContext::for_project(project, metadata);
+    let plan = generate_plan_with_context(project.id, &project.path, &resolved.profile, &context)?;
+
+    Ok(Some(AssignmentProposal {
+        rule: matched.rule,
+        reasons: matched.reasons,
+        profile_id: profile.id,
+        profile_name: profile.name,
+        plan,
+    }))
+}
+
+/// A proposal applied to a project
+#[derive(Debug, Clone)]
+pub struct AppliedProposal {
+    /// Backup of the files the plan touched, or `None` when the project
+    /// already matched
+    pub backup: Option<Backup>,
+    /// What assigning the profile did
+    pub assignment: AssignResult,
+}
+
+/// Apply a proposal and assign its profile to the project
+///
+/// The files the plan touches are backed up to an archive in `backup_dir`
+/// first, as for a manual apply. The profile is then assigned the same way
+/// as a manual assignment, with [`assign_profile_to_project`], installing
+/// its plugins through the Claude CLI at `claude`.
+///
+/// # Errors
+/// Returns an error if the plan cannot be applied or the profile assigned
+pub fn apply_proposal(
+    conn: &Connection,
+    project: &mut Project,
+    proposal: &AssignmentProposal,
+    backup_dir: &Path,
+    claude: Option<&Path>,
+) -> Result<AppliedProposal, RuleError> {
+    let backup = if proposal.plan.is_empty() {
+        None
+    } else {
+        fs::create_dir_all(backup_dir)?;
+        let archive_path = backup_dir.join(format!(
+            "backup-{}.json",
+            Utc::now().format("%Y%m%d-%H%M%S")
+        ));
+        let mut backup = Backup::new(project.id, archive_path.clone())
+            .with_profile(proposal.profile_id)
+            .with_description(format!(
+                "Before applying profile '{}' (rule '{}')",
+                proposal.profile_name, proposal.rule.name
+            ));
+        apply_operations(&proposal.plan, &project.path, &mut backup)?;
+
+        let backup_json =
+            serde_json::to_string_pretty(&backup).map_err(|e| RuleError::Backup(e.to_string()))?;
+        fs::write(&archive_path, backup_json)?;
+        BackupStore::new(conn).create(&backup)?;
+        Some(backup)
+    };
+
+    let assignment = assign_profile_to_project(conn, project, proposal.profile_id, claude)?;
+    Ok(AppliedProposal { backup, assignment })
+}
+
+/// Parse the host and owner out of a git remote URL
+///
+/// Handles `scheme://[user@]host[:port]/owner/repo` and scp-like
+/// `user@host:owner/repo`; local paths have no host and give `None`.
+#[must_use]
+pub fn parse_git_remote(url: &str) -> Option<GitRemote> {
+    let url = url.trim();
+    let (authority, path) = if let Some((scheme, rest)) = url.split_once("://") {
+        if scheme == "file" {
+            return None;
+        }
+        let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+        let authority = authority.rsplit('@').next().unwrap_or(authority);
+        (authority.split(':').next().unwrap_or(authority), path)
+    } else {
+        let (authority, path) = url.split_once(':')?;
+        if authority.contains('/') || authority.len() < 2 {
+            // A local path, or a Windows drive letter
+            return None;
+        }
+        (authority.rsplit('@').next().unwrap_or(authority), path)
+    };
+    if authority.is_empty() {
+        return None;
+    }
+
+    let org = path
+        .trim_start_matches('/')
+        .split('/')
+        .next()
+        .filter(|segment| !segment.is_empty() && path.contains('/'))
+        .map(String::from);
+    Some(GitRemote {
+        host: authority.to_ascii_lowercase(),
+        org,
+    })
+}
+
+fn origin_url(path: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["config", "--get", "remote.origin.url"])
+        .current_dir(path)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|url| !url.is_empty())
+}
+
+fn compile_glob(glob: &str) -> Result<Pattern, RuleError> {
+    let expanded = match glob.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir().map_or_else(
+            || glob.to_string(),
+            |home| home.join(rest).display().to_string(),
+        ),
+        None => glob.to_string(),
+    };
+    Pattern::new(&expanded).map_err(|e| RuleError::InvalidGlob {
+        glob: glob.to_string(),
+        message: e.to_string(),
+    })
+}
+
+fn path_match_options() -> MatchOptions {
+    MatchOptions {
+        case_sensitive: true,
+        require_literal_separator: true,
+        require_literal_leading_dot: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_remote_urls() {
+        let remote = |host: &str, org: &str| {
+            Some(GitRemote {
+                host: host.to_string(),
+                org: Some(org.to_string()),
+            })
+        };
+        assert_eq!(
+            parse_git_remote("git@github.com:acme/api.git"),
+            remote("github.com", "acme")
+        );
+        assert_eq!(
+            parse_git_remote("https://GitLab.com/acme/tools/api.git"),
+            remote("gitlab.com", "acme")
+        );
+        assert_eq!(
+            parse_git_remote("ssh://git@git.example.com:2222/team/api"),
+            remote("git.example.com", "team")
+        );
+        assert_eq!(parse_git_remote("/srv/git/api.git"), None);
+        assert_eq!(parse_git_remote("file:///srv/git/api.git"), None);
+        assert_eq!(parse_git_remote("C:\\repos\\api"), None);
+    }
+
+    #[test]
+    fn detects_dependency_manifests_by_file_name() {
+        let project = tempfile::TempDir::new().unwrap();
+        for dir in ["web", "api"] {
+            let dir = project.path().join(dir);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(
+                dir.join("package.json"),
+                r#"{"dependencies": {"left-pad": "1.0.0"}}"#,
+            )
+            .unwrap();
+        }
+        let facts = ProjectFacts::detect(project.path(), None);
+        assert_eq!(facts.dependency_files, vec!["package.json"]);
+    }
+}
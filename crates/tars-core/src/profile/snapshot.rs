@@ -1,11 +1,15 @@
 //! Profile snapshot creation from current state
 
 use crate::profile::{
-    AgentOverlay, ClaudeMdOverlay, CommandOverlay, OverlayMode, Profile, SkillOverlay,
+    AgentOverlay, ClaudeMdOverlay, CommandOverlay, HooksOverlay, McpLocation, McpServerOverlay,
+    OverlayMode, PermissionsOverlay, Profile, SkillFile, SkillOverlay,
 };
+use serde_json::Value;
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 use thiserror::Error;
+use walkdir::WalkDir;
 
 /// Errors during snapshot creation
 #[derive(Error, Debug)]
@@ -15,6 +19,15 @@ pub enum SnapshotError {
 
     #[error("Path not found: {0}")]
     PathNotFound(String),
+
+    #[error("Invalid JSON in {path}: {source}")]
+    Json {
+        path: String,
+        source: serde_json::Error,
+    },
+
+    #[error("Failed to read skill files: {0}")]
+    Walk(#[from] walkdir::Error),
 }
 
 /// Create a profile snapshot from a project directory
@@ -45,6 +58,25 @@ pub fn snapshot_from_project(project_path: &Path, name: String) -> Result<Profil
     // Snapshot agents
     profile.repo_overlays.agents = snapshot_agents(&claude_dir.join("agents"))?;
 
+    // Snapshot MCP servers, remembering which of the two locations was used
+    let root_mcp = project_path.join(".mcp.json");
+    let claude_dir_mcp = claude_dir.join("mcp.json");
+    if root_mcp.exists() {
+        profile.repo_overlays.mcp_servers = snapshot_mcp_servers(&root_mcp)?;
+    } else if claude_dir_mcp.exists() {
+        profile.repo_overlays.mcp_servers = snapshot_mcp_servers(&claude_dir_mcp)?;
+        profile.adapters.mcp_location = McpLocation::ClaudeDir;
+    }
+
+    // Snapshot hooks and permissions from the shared settings file.
+    // settings.local.json holds personal overrides and is left out.
+    let settings_path = claude_dir.join("settings.json");
+    if settings_path.exists() {
+        let settings = read_json(&settings_path)?;
+        profile.repo_overlays.hooks = snapshot_hooks(&settings);
+        profile.repo_overlays.permissions = snapshot_permissions(&settings);
+    }
+
     Ok(profile)
 }
 
@@ -88,8 +120,13 @@ fn snapshot_skills(skills_dir: &Path) -> Result<Vec<SkillOverlay>, SnapshotError
                     .and_then(|n| n.to_str())
                     .unwrap_or("unknown")
                     .to_string();
+                let files = snapshot_skill_files(&path)?;
 
-                skills.push(SkillOverlay { name, content });
+                skills.push(SkillOverlay {
+                    name,
+                    content,
+                    files,
+                });
             }
         }
     }
@@ -97,6 +134,33 @@ fn snapshot_skills(skills_dir: &Path) -> Result<Vec<SkillOverlay>, SnapshotError
     Ok(skills)
 }
 
+/// Collect every file in a skill directory except the top-level SKILL.md
+fn snapshot_skill_files(skill_dir: &Path) -> Result<Vec<SkillFile>, SnapshotError> {
+    let mut files = Vec::new();
+
+    for entry in WalkDir::new(skill_dir).sort_by_file_name() {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Ok(relative) = entry.path().strip_prefix(skill_dir) else {
+            continue;
+        };
+        if relative == Path::new("SKILL.md") {
+            continue;
+        }
+
+        let path = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        files.push(SkillFile::from_bytes(path, fs::read(entry.path())?));
+    }
+
+    Ok(files)
+}
+
 fn snapshot_commands(commands_dir: &Path) -> Result<Vec<CommandOverlay>, SnapshotError> {
     let mut commands = Vec::new();
 
@@ -148,3 +212,105 @@ fn snapshot_agents(agents_dir: &Path) -> Result<Vec<AgentOverlay>, SnapshotError
 
     Ok(agents)
 }
+
+fn read_json(path: &Path) -> Result<Value, SnapshotError> {
+    let content = fs::read_to_string(path)?;
+    serde_json::from_str(&content).map_err(|source| SnapshotError::Json {
+        path: path.display().to_string(),
+        source,
+    })
+}
+
+fn snapshot_mcp_servers(mcp_path: &Path) -> Result<Vec<McpServerOverlay>, SnapshotError> {
+    let config = read_json(mcp_path)?;
+    let Some(servers) = config.get("mcpServers").and_then(Value::as_object) else {
+        return Ok(Vec::new());
+    };
+
+    Ok(servers
+        .iter()
+        .map(|(name, server)| {
+            let url = string_field(server, "url");
+            let transport = string_field(server, "type").unwrap_or_else(|| {
+                if url.is_some() {
+                    "http".to_string()
+                } else {
+                    "stdio".to_string()
+                }
+            });
+
+            McpServerOverlay {
+                name: name.clone(),
+                transport,
+                command: string_field(server, "command"),
+                args: server
+                    .get("args")
+                    .and_then(Value::as_array)
+                    .map(|args| {
+                        args.iter()
+                            .filter_map(Value::as_str)
+                            .map(str::to_string)
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                env: string_map(server, "env"),
+                url,
+                headers: string_map(server, "headers"),
+            }
+        })
+        .collect())
+}
+
+fn snapshot_hooks(settings: &Value) -> Option<HooksOverlay> {
+    let hooks = settings.get("hooks")?.as_object()?;
+    let events: std::collections::BTreeMap<_, _> = hooks
+        .iter()
+        .filter_map(|(event, groups)| Some((event.clone(), groups.as_array()?.clone())))
+        .filter(|(_, groups)| !groups.is_empty())
+        .collect();
+
+    (!events.is_empty()).then_some(HooksOverlay { events })
+}
+
+fn snapshot_permissions(settings: &Value) -> Option<PermissionsOverlay> {
+    let permissions = settings.get("permissions")?;
+    let overlay = PermissionsOverlay {
+        allow: string_list(permissions, "allow"),
+        deny: string_list(permissions, "deny"),
+        ask: string_list(permissions, "ask"),
+        additional_directories: string_list(permissions, "additionalDirectories"),
+        default_mode: string_field(permissions, "defaultMode"),
+    };
+
+    (overlay != PermissionsOverlay::default()).then_some(overlay)
+}
+
+fn string_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+fn string_list(value: &Value, key: &str) -> Vec<String> {
+    value
+        .get(key)
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn string_map(value: &Value, key: &str) -> HashMap<String, String> {
+    value
+        .get(key)
+        .and_then(Value::as_object)
+        .map(|map| {
+            map.iter()
+                .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -21,7 +21,7 @@ const MAX_DEPTH: usize = 50;
 const MAX_FILES: usize = 10_000;
 
 /// Maximum file size to read entirely into memory (10 MB)
-const MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+pub(crate) const MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
 
 // ============================================================================
 // Hash Utilities for Source Tracking
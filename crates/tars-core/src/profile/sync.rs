@@ -2,10 +2,12 @@
 //!
 //! This module handles syncing profile changes to all assigned projects.
 
+use crate::profile::resolve::{resolve_profile, ArtifactKind, ResolvedProfile};
 use crate::profile::storage::copy_dir_recursive;
-use crate::profile::types::Profile;
+use crate::profile::types::{Profile, ToolType};
 use crate::project::Project;
 use crate::storage::db::DatabaseError;
+use crate::storage::profiles::ProfileStore;
 use chrono::{DateTime, Utc};
 use rusqlite::Connection;
 use serde_json::{json, Value};
@@ -69,7 +71,6 @@ pub fn convert_profile_to_local_overrides(
     conn: &Connection,
     profile_id: Uuid,
 ) -> Result<Vec<Project>, DatabaseError> {
-    use crate::storage::profiles::ProfileStore;
     use crate::storage::projects::ProjectStore;
 
     let profile_store = ProfileStore::new(conn);
@@ -118,54 +119,77 @@ pub fn convert_profile_to_local_overrides(
 /// Apply a profile's tools to a project directory
 ///
 /// This copies tool files from central profile storage (~/.tars/profiles/<id>/)
-/// to the target project directory:
+/// of every layer of the resolved profile to the target project directory:
 /// - MCP servers → .mcp.json (merged)
 /// - Skills → .claude/skills/<name>/ (directory with SKILL.md)
 /// - Agents → .claude/agents/<name>.md
 /// - Commands → .claude/commands/<name>.md
 ///
+/// Layers are applied base first. A tool the resolved profile references is
+/// taken only from the layer whose definition is in effect.
+///
 /// # Errors
 /// Returns an error if file operations fail
 pub fn apply_profile_to_project(
-    profile: &Profile,
+    resolved: &ResolvedProfile,
     project_path: &Path,
 ) -> Result<ApplyResult, ApplyError> {
     use super::storage;
 
     let mut result = ApplyResult::default();
 
-    // List tools stored in the profile's central storage
-    let stored_tools =
-        storage::list_profile_tools(profile.id).map_err(|e| ApplyError::Storage(e.to_string()))?;
+    for layer in &resolved.layers {
+        let in_effect = |tool_type: ToolType, name: &str| {
+            resolved
+                .origin(ArtifactKind::Tools, Some(tool_type), name)
+                .map_or(true, |p| p.source.id == layer.id)
+        };
 
-    // Apply MCP servers to .mcp.json
-    for server_name in &stored_tools.mcp_servers {
-        let config = storage::get_mcp_server_config(profile.id, server_name)
+        // List tools stored in the layer's central storage
+        let stored_tools = storage::list_profile_tools(layer.id)
             .map_err(|e| ApplyError::Storage(e.to_string()))?;
 
-        apply_mcp_server_config(server_name, &config, project_path)?;
-        result.mcp_servers_applied += 1;
-    }
+        // Apply MCP servers to .mcp.json
+        for server_name in &stored_tools.mcp_servers {
+            if !in_effect(ToolType::Mcp, server_name) {
+                continue;
+            }
+            let config = storage::get_mcp_server_config(layer.id, server_name)
+                .map_err(|e| ApplyError::Storage(e.to_string()))?;
 
-    // Apply skills from central storage
-    for skill_name in &stored_tools.skills {
-        storage::apply_skill_to_project(profile.id, skill_name, project_path)
-            .map_err(|e| ApplyError::Storage(e.to_string()))?;
-        result.skills_applied += 1;
-    }
+            apply_mcp_server_config(server_name, &config, project_path)?;
+            result.mcp_servers_applied += 1;
+        }
 
-    // Apply agents from central storage
-    for agent_name in &stored_tools.agents {
-        storage::apply_agent_to_project(profile.id, agent_name, project_path)
-            .map_err(|e| ApplyError::Storage(e.to_string()))?;
-        result.agents_applied += 1;
-    }
+        // Apply skills from central storage
+        for skill_name in &stored_tools.skills {
+            if !in_effect(ToolType::Skill, skill_name) {
+                continue;
+            }
+            storage::apply_skill_to_project(layer.id, skill_name, project_path)
+                .map_err(|e| ApplyError::Storage(e.to_string()))?;
+            result.skills_applied += 1;
+        }
 
-    // Apply commands from central storage
-    for command_name in &stored_tools.commands {
-        storage::apply_command_to_project(profile.id, command_name, project_path)
-            .map_err(|e| ApplyError::Storage(e.to_string()))?;
-        result.commands_applied += 1;
+        // Apply agents from central storage
+        for agent_name in &stored_tools.agents {
+            if !in_effect(ToolType::Agent, agent_name) {
+                continue;
+            }
+            storage::apply_agent_to_project(layer.id, agent_name, project_path)
+                .map_err(|e| ApplyError::Storage(e.to_string()))?;
+            result.agents_applied += 1;
+        }
+
+        // Apply commands from central storage
+        for command_name in &stored_tools.commands {
+            if !in_effect(ToolType::Hook, command_name) {
+                continue;
+            }
+            storage::apply_command_to_project(layer.id, command_name, project_path)
+                .map_err(|e| ApplyError::Storage(e.to_string()))?;
+            result.commands_applied += 1;
+        }
     }
 
     Ok(result)
@@ -258,6 +282,115 @@ impl std::fmt::Display for ApplyError {
 
 impl std::error::Error for ApplyError {}
 
+/// What assigning a profile to a project did
+#[derive(Debug, Clone, Default)]
+pub struct AssignResult {
+    /// Stored tools copied into the project
+    pub applied: ApplyResult,
+    /// Number of plugins installed
+    pub plugins_installed: usize,
+    /// Plugins that failed to install (id, error message)
+    pub plugin_errors: Vec<(String, String)>,
+}
+
+/// Assign a profile to a project
+///
+/// Copies the stored tools of the profile and its `extends` parents into the
+/// project, records the assignment, installs the enabled plugins stored with
+/// them at project scope through the Claude CLI at `claude`, and saves which
+/// plugins TARS installed so unassigning can remove them. A plugin that
+/// cannot be installed, including when `claude` is `None`, is reported in
+/// the result rather than failing the assignment.
+///
+/// # Errors
+/// Returns an error if the profile cannot be resolved, its tools cannot be
+/// copied, or the assignment cannot be recorded
+pub fn assign_profile_to_project(
+    conn: &Connection,
+    project: &mut Project,
+    profile_id: Uuid,
+    claude: Option<&Path>,
+) -> Result<AssignResult, ApplyError> {
+    use super::storage::{list_plugin_manifests, save_project_state, ProjectProfileState};
+    use crate::storage::projects::ProjectStore;
+
+    let store = ProfileStore::new(conn);
+    let profile = store
+        .get(profile_id)
+        .map_err(|e| ApplyError::Storage(e.to_string()))?
+        .ok_or_else(|| ApplyError::Storage(format!("Profile not found: {profile_id}")))?;
+    let resolved =
+        resolve_profile(&store, &profile).map_err(|e| ApplyError::Storage(e.to_string()))?;
+
+    let mut result = AssignResult {
+        applied: apply_profile_to_project(&resolved, &project.path)?,
+        ..AssignResult::default()
+    };
+
+    project.assigned_profile_id = Some(profile_id);
+    project.updated_at = Utc::now();
+    ProjectStore::new(conn)
+        .update(project)
+        .map_err(|e| ApplyError::Storage(e.to_string()))?;
+
+    let mut state = ProjectProfileState::new(profile_id);
+    let mut seen = std::collections::HashSet::new();
+    for layer in &resolved.layers {
+        let manifests =
+            list_plugin_manifests(layer.id).map_err(|e| ApplyError::Storage(e.to_string()))?;
+        for manifest in manifests {
+            if !manifest.enabled || !seen.insert(manifest.id.clone()) {
+                continue;
+            }
+            // plugin@marketplace when the marketplace is known
+            let identifier = match &manifest.marketplace {
+                Some(marketplace) => format!("{}@{marketplace}", manifest.id),
+                None => manifest.id.clone(),
+            };
+            let Some(claude) = claude else {
+                result
+                    .plugin_errors
+                    .push((manifest.id, "Claude CLI not found".to_string()));
+                continue;
+            };
+            match install_project_plugin(claude, &project.path, &identifier) {
+                Ok(()) => {
+                    result.plugins_installed += 1;
+                    state.add_installed_plugin(manifest.id);
+                }
+                Err(e) if e.contains("already installed") => {
+                    state.add_installed_plugin(manifest.id);
+                }
+                Err(e) => result.plugin_errors.push((manifest.id, e)),
+            }
+        }
+    }
+
+    save_project_state(project.id, &state).map_err(|e| ApplyError::Storage(e.to_string()))?;
+    Ok(result)
+}
+
+/// Install a plugin at project scope with the Claude CLI
+fn install_project_plugin(claude: &Path, project_path: &Path, plugin: &str) -> Result<(), String> {
+    let output = std::process::Command::new(claude)
+        .args(["plugin", "install", "--scope=project", plugin])
+        .current_dir(project_path)
+        .output()
+        .map_err(|e| format!("Failed to run CLI: {e}"))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Err(if !stderr.is_empty() {
+        stderr.to_string()
+    } else if !stdout.is_empty() {
+        stdout.to_string()
+    } else {
+        "Unknown error".to_string()
+    })
+}
+
 // ============================================================================
 // Plugin-based Assignment
 // ============================================================================
@@ -286,10 +419,11 @@ pub struct PluginAssignResult {
 /// # Errors
 /// Returns an error if plugin generation or installation fails
 pub fn assign_profile_as_plugin(
+    conn: &Connection,
     profile: &Profile,
     _project_path: &Path,
 ) -> Result<PluginAssignResult, ApplyError> {
-    let sync_result = sync_profile_marketplace(profile)?;
+    let sync_result = sync_profile_marketplace(conn, profile)?;
 
     Ok(PluginAssignResult {
         plugin_path: sync_result.plugin_path.join(".claude-plugin"),
@@ -360,6 +494,7 @@ fn is_valid_plugin_id(id: &str) -> bool {
 /// # Errors
 /// Returns an error if reinstall fails
 pub fn reinstall_profile_plugin(
+    conn: &Connection,
     profile: &Profile,
     project_path: &Path,
 ) -> Result<PluginAssignResult, ApplyError> {
@@ -370,7 +505,7 @@ pub fn reinstall_profile_plugin(
     let _ = unassign_profile_plugin(project_path, &plugin_id);
 
     // Install fresh
-    assign_profile_as_plugin(profile, project_path)
+    assign_profile_as_plugin(conn, profile, project_path)
 }
 
 /// Generate the plugin for a profile into its storage directory
@@ -381,12 +516,46 @@ pub fn reinstall_profile_plugin(
 /// Uses atomic directory replacement to avoid race conditions where the plugin
 /// directory might be missing during regeneration.
 ///
+/// The plugin is exported from the profile resolved with its `extends`
+/// parents, so the plugins of profiles that extend this one embed its tools
+/// too; they are regenerated afterwards.
+///
 /// # Errors
-/// Returns an error if plugin generation fails
-pub fn regenerate_profile_plugin(profile: &Profile) -> Result<PathBuf, ApplyError> {
+/// Returns an error if the profile cannot be resolved or plugin generation
+/// fails
+pub fn regenerate_profile_plugin(
+    conn: &Connection,
+    profile: &Profile,
+) -> Result<PathBuf, ApplyError> {
+    let store = ProfileStore::new(conn);
+    let resolved =
+        resolve_profile(&store, profile).map_err(|e| ApplyError::Storage(e.to_string()))?;
+    let plugin_path = write_profile_plugin(&resolved)?;
+
+    let children = store
+        .list_extending(profile.id)
+        .map_err(|e| ApplyError::Storage(e.to_string()))?;
+    for child in children {
+        if let Some(child) = store
+            .get(child.id)
+            .map_err(|e| ApplyError::Storage(e.to_string()))?
+        {
+            regenerate_profile_plugin(conn, &child)?;
+            if in_profile_marketplace(&child.name) {
+                sync_profile_marketplace(conn, &child)?;
+            }
+        }
+    }
+
+    Ok(plugin_path)
+}
+
+/// Export a resolved profile into its storage's `plugin/` directory
+fn write_profile_plugin(resolved: &ResolvedProfile) -> Result<PathBuf, ApplyError> {
     use crate::export::export_as_plugin_with_hash;
     use crate::profile::storage::{profile_dir, profile_plugin_dir};
 
+    let profile = &resolved.profile;
     let profile_storage =
         profile_dir(profile.id).map_err(|e| ApplyError::Storage(e.to_string()))?;
 
@@ -419,8 +588,9 @@ pub fn regenerate_profile_plugin(profile: &Profile) -> Result<PathBuf, ApplyErro
     let plugin_name = format!("tars-profile-{}", sanitize_plugin_name(&profile.name));
 
     // Export profile as plugin with content-hash version to temp directory
-    let generated_plugin_path = export_as_plugin_with_hash(profile, &temp_plugin_dir, &plugin_name)
-        .map_err(|e| ApplyError::Storage(e.to_string()))?;
+    let generated_plugin_path =
+        export_as_plugin_with_hash(resolved, &temp_plugin_dir, &plugin_name)
+            .map_err(|e| ApplyError::Storage(e.to_string()))?;
 
     // Get the final plugin directory path
     let final_plugin_dir =
@@ -467,10 +637,11 @@ pub fn regenerate_profile_plugin(profile: &Profile) -> Result<PathBuf, ApplyErro
 /// # Errors
 /// Returns an error if installation fails
 pub fn install_profile_plugin_to_project(
+    conn: &Connection,
     profile: &Profile,
     _project_path: &Path,
 ) -> Result<PluginAssignResult, ApplyError> {
-    let sync_result = sync_profile_marketplace(profile)?;
+    let sync_result = sync_profile_marketplace(conn, profile)?;
 
     Ok(PluginAssignResult {
         plugin_path: sync_result.plugin_path.join(".claude-plugin"),
@@ -490,8 +661,11 @@ pub fn install_profile_plugin_to_project(
 ///
 /// # Errors
 /// Returns an error if installation fails
-pub fn install_profile_plugin_to_user(profile: &Profile) -> Result<PluginAssignResult, ApplyError> {
-    let sync_result = sync_profile_marketplace(profile)?;
+pub fn install_profile_plugin_to_user(
+    conn: &Connection,
+    profile: &Profile,
+) -> Result<PluginAssignResult, ApplyError> {
+    let sync_result = sync_profile_marketplace(conn, profile)?;
 
     Ok(PluginAssignResult {
         plugin_path: sync_result.plugin_path.join(".claude-plugin"),
@@ -657,14 +831,17 @@ pub struct MarketplaceSyncResult {
 /// Sync a profile plugin into the local marketplace directory.
 ///
 /// This prepares the marketplace on disk; installation is done via the Claude CLI.
-pub fn sync_profile_marketplace(profile: &Profile) -> Result<MarketplaceSyncResult, ApplyError> {
+pub fn sync_profile_marketplace(
+    conn: &Connection,
+    profile: &Profile,
+) -> Result<MarketplaceSyncResult, ApplyError> {
     use crate::profile::storage::profile_plugin_dir;
 
     let plugin_dir =
         profile_plugin_dir(profile.id).map_err(|e| ApplyError::Storage(e.to_string()))?;
 
     if !plugin_dir.exists() {
-        regenerate_profile_plugin(profile)?;
+        regenerate_profile_plugin(conn, profile)?;
     }
 
     let plugin_name = format!("tars-profile-{}", sanitize_plugin_name(&profile.name));
@@ -687,6 +864,16 @@ pub fn sync_profile_marketplace(profile: &Profile) -> Result<MarketplaceSyncResu
     })
 }
 
+/// Whether a profile's plugin has been published to the profile marketplace
+fn in_profile_marketplace(profile_name: &str) -> bool {
+    let plugin_name = format!("tars-profile-{}", sanitize_plugin_name(profile_name));
+    dirs::home_dir().is_some_and(|home| {
+        profile_marketplace_plugins_dir(&home)
+            .join(plugin_name)
+            .exists()
+    })
+}
+
 pub fn remove_profile_from_marketplace(profile_name: &str) -> Result<(), ApplyError> {
     let plugin_name = format!("tars-profile-{}", sanitize_plugin_name(profile_name));
     let home_dir = dirs::home_dir()
@@ -0,0 +1,366 @@
+//! Template variables in profile overlays
+//!
+//! CLAUDE.md, skill, command and agent overlay content may reference project
+//! variables as `{{ name }}`, optionally with a fallback after a pipe:
+//! `{{ test_command | make test }}`. `\{{` produces a literal `{{`. Only the
+//! names listed below are placeholders; other text between braces, such as
+//! GitHub Actions' `${{ github.ref }}` or a Handlebars block, is left alone.
+//! A variable that is unset and has no fallback stays in the output verbatim
+//! and is reported as missing.
+//!
+//! Variables, from the project's metadata and detected statistics:
+//!
+//! - `project_name`, `project_path`, `description`
+//! - `language` (by lines of code) and `languages`
+//! - `test_command` (inferred from the project's manifests), `start_command`,
+//!   `deploy_command`
+//! - `framework`, `platforms`, `github_url`, `production_url`, `staging_url`,
+//!   `domain`
+//! - `custom.<key>` for every custom metadata field; a custom field named
+//!   like a built-in variable overrides it
+
+use crate::project::Project;
+use crate::stats::collect_project_stats;
+use crate::storage::metadata::ProjectMetadata;
+use std::cell::OnceCell;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Project variables for rendering overlays, computed on first use
+#[derive(Debug, Clone)]
+pub struct TemplateContext {
+    project_path: PathBuf,
+    project_name: Option<String>,
+    metadata: Option<ProjectMetadata>,
+    variables: OnceCell<BTreeMap<String, String>>,
+}
+
+/// Result of rendering a template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rendered {
+    pub text: String,
+    /// Variables that were referenced but not set, in order of appearance
+    pub missing: Vec<String>,
+}
+
+impl TemplateContext {
+    /// Context with only what can be detected from the project directory
+    #[must_use]
+    pub fn new(project_path: &Path) -> Self {
+        Self {
+            project_path: project_path.to_path_buf(),
+            project_name: None,
+            metadata: None,
+            variables: OnceCell::new(),
+        }
+    }
+
+    /// Context for a registered project and its stored metadata
+    #[must_use]
+    pub fn for_project(project: &Project, metadata: Option<ProjectMetadata>) -> Self {
+        let context = Self::new(&project.path).with_name(project.name.clone());
+        match metadata {
+            Some(metadata) => context.with_metadata(metadata),
+            None => context,
+        }
+    }
+
+    /// Use the project's registered name instead of its directory name
+    #[must_use]
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.project_name = Some(name.into());
+        self
+    }
+
+    /// Add the project's stored metadata
+    #[must_use]
+    pub fn with_metadata(mut self, metadata: ProjectMetadata) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// All variables set for the project
+    pub fn variables(&self) -> &BTreeMap<String, String> {
+        self.variables.get_or_init(|| self.collect_variables())
+    }
+
+    /// Render a template against the project's variables
+    ///
+    /// Text without `{{` is returned as is, without detecting anything.
+    #[must_use]
+    pub fn render(&self, template: &str) -> Rendered {
+        if !template.contains("{{") {
+            return Rendered {
+                text: template.to_string(),
+                missing: Vec::new(),
+            };
+        }
+        render(template, self.variables())
+    }
+
+    fn collect_variables(&self) -> BTreeMap<String, String> {
+        let mut vars = BTreeMap::new();
+        let mut set = |key: &str, value: Option<String>| {
+            if let Some(value) = value.filter(|v| !v.trim().is_empty()) {
+                vars.insert(key.to_string(), value);
+            }
+        };
+
+        let dir_name = self
+            .project_path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned());
+        set("project_name", self.project_name.clone().or(dir_name));
+        set(
+            "project_path",
+            Some(self.project_path.display().to_string()),
+        );
+
+        let stats = collect_project_stats(&self.project_path);
+        set("language", stats.primary_language().map(str::to_string));
+        set("languages", Some(stats.languages_by_code().join(", ")));
+        set("test_command", infer_test_command(&self.project_path));
+
+        if let Some(meta) = &self.metadata {
+            set("description", meta.description.clone());
+            set("start_command", meta.start_command.clone());
+            set("deploy_command", deploy_command(meta));
+            set("framework", meta.app_framework.clone());
+            set("platforms", Some(meta.platforms.join(", ")));
+            set("github_url", meta.github_url.clone());
+            set("production_url", meta.production_url.clone());
+            set("staging_url", meta.staging_url.clone());
+            set("domain", meta.domain.clone());
+            for field in &meta.custom_fields {
+                let key = field.key.trim();
+                if key.is_empty() {
+                    continue;
+                }
+                set(&format!("custom.{key}"), Some(field.value.clone()));
+                if is_builtin(key) {
+                    set(key, Some(field.value.clone()));
+                }
+            }
+        }
+
+        vars
+    }
+}
+
+const BUILTIN_VARIABLES: &[&str] = &[
+    "project_name",
+    "project_path",
+    "description",
+    "language",
+    "languages",
+    "test_command",
+    "start_command",
+    "deploy_command",
+    "framework",
+    "platforms",
+    "github_url",
+    "production_url",
+    "staging_url",
+    "domain",
+];
+
+fn is_builtin(key: &str) -> bool {
+    BUILTIN_VARIABLES.contains(&key)
+}
+
+/// Render `{{ name }}` placeholders from a variable map
+#[must_use]
+pub fn render(template: &str, variables: &BTreeMap<String, String>) -> Rendered {
+    let mut text = String::with_capacity(template.len());
+    let mut missing: Vec<String> = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        if rest[..start].ends_with('\\') {
+            text.push_str(&rest[..start - 1]);
+            text.push_str("{{");
+            rest = &rest[start + 2..];
+            continue;
+        }
+        text.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        if rest[..start].ends_with('$') {
+            // `${{ ... }}` belongs to another template language
+            text.push_str("{{");
+            rest = after;
+            continue;
+        }
+
+        let Some(end) = after.find("}}") else {
+            text.push_str(&rest[start..]);
+            rest = "";
+            break;
+        };
+        let inner = &after[..end];
+        let (name, fallback) = match inner.split_once('|') {
+            Some((name, fallback)) => (name.trim(), Some(fallback.trim())),
+            None => (inner.trim(), None),
+        };
+
+        if !is_variable_name(name) {
+            // Not a placeholder; keep the braces and continue after them
+            text.push_str("{{");
+            rest = after;
+            continue;
+        }
+
+        match (variables.get(name), fallback) {
+            (Some(value), _) => text.push_str(value),
+            (None, Some(fallback)) => text.push_str(fallback),
+            (None, None) => {
+                text.push_str(&rest[start..start + 2 + end + 2]);
+                if !missing.iter().any(|m| m == name) {
+                    missing.push(name.to_string());
+                }
+            }
+        }
+        rest = &after[end + 2..];
+    }
+    text.push_str(rest);
+
+    Rendered { text, missing }
+}
+
+/// Whether `name` is a built-in variable or `custom.<key>`
+fn is_variable_name(name: &str) -> bool {
+    match name.strip_prefix("custom.") {
+        Some(key) => {
+            !key.is_empty()
+                && key
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        }
+        None => is_builtin(name),
+    }
+}
+
+/// First deploy command from the metadata's deploy fields
+fn deploy_command(meta: &ProjectMetadata) -> Option<String> {
+    meta.deploy_command
+        .clone()
+        .filter(|c| !c.trim().is_empty())
+        .or_else(|| meta.deploy_commands.first().cloned())
+        .or_else(|| {
+            meta.deploy_steps
+                .iter()
+                .find(|step| step.kind == "command")
+                .map(|step| step.text.clone())
+        })
+}
+
+/// Test command implied by the project's manifests
+fn infer_test_command(project: &Path) -> Option<String> {
+    let has = |name: &str| project.join(name).exists();
+
+    if has("Cargo.toml") {
+        return Some("cargo test".to_string());
+    }
+    if let Ok(content) = fs::read_to_string(project.join("package.json")) {
+        let has_test_script = serde_json::from_str::<serde_json::Value>(&content)
+            .ok()
+            .is_some_and(|json| json["scripts"]["test"].is_string());
+        if has_test_script {
+            let runner = if has("pnpm-lock.yaml") {
+                "pnpm"
+            } else if has("yarn.lock") {
+                "yarn"
+            } else if has("bun.lockb") || has("bun.lock") {
+                "bun run"
+            } else {
+                "npm"
+            };
+            return Some(format!("{runner} test"));
+        }
+    }
+    if let Ok(pubspec) = fs::read_to_string(project.join("pubspec.yaml")) {
+        let flutter = pubspec.lines().any(|line| line.trim_start() == "flutter:");
+        return Some(if flutter { "flutter test" } else { "dart test" }.to_string());
+    }
+    if has("go.mod") {
+        return Some("go test ./...".to_string());
+    }
+    if has("pyproject.toml") || has("requirements.txt") {
+        return Some("pytest".to_string());
+    }
+    if has("Gemfile") {
+        let command = if has("spec") {
+            "bundle exec rspec"
+        } else {
+            "bundle exec rake test"
+        };
+        return Some(command.to_string());
+    }
+    if has("Package.swift") {
+        return Some("swift test".to_string());
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn renders_variables_and_fallbacks() {
+        let rendered = render(
+            "# {{project_name}}\nTest: `{{ test_command }}`\nDeploy: {{ deploy_command | n/a }}",
+            &vars(&[("project_name", "tars"), ("test_command", "cargo test")]),
+        );
+        assert_eq!(rendered.text, "# tars\nTest: `cargo test`\nDeploy: n/a");
+        assert!(rendered.missing.is_empty());
+    }
+
+    #[test]
+    fn keeps_missing_variables_and_reports_them_once() {
+        let rendered = render("{{ language }} and {{language}}", &vars(&[]));
+        assert_eq!(rendered.text, "{{ language }} and {{language}}");
+        assert_eq!(rendered.missing, vec!["language"]);
+    }
+
+    #[test]
+    fn leaves_non_placeholders_alone() {
+        let template = "Use {{#each items}} or {{ }} or \\{{project_name}} or {{ unclosed";
+        let rendered = render(template, &vars(&[("project_name", "tars")]));
+        assert_eq!(
+            rendered.text,
+            "Use {{#each items}} or {{ }} or {{project_name}} or {{ unclosed"
+        );
+        assert!(rendered.missing.is_empty());
+
+        // GitHub Actions expressions, even naming a variable
+        let template = "ref: ${{ github.ref }} in ${{project_name}}";
+        let rendered = render(template, &vars(&[("project_name", "tars")]));
+        assert_eq!(rendered.text, template);
+        assert!(rendered.missing.is_empty());
+
+        // Names that are neither built in nor custom
+        let template = "Hi {{ user.name }} from {{ repo }} and {{ custom. }}";
+        let rendered = render(template, &vars(&[]));
+        assert_eq!(rendered.text, template);
+        assert!(rendered.missing.is_empty());
+    }
+
+    #[test]
+    fn custom_variables_are_placeholders() {
+        let rendered = render(
+            "{{ custom.team }} / {{ custom.owner }}",
+            &vars(&[("custom.team", "core")]),
+        );
+        assert_eq!(rendered.text, "core / {{ custom.owner }}");
+        assert_eq!(rendered.missing, vec!["custom.owner"]);
+    }
+}
@@ -1,8 +1,10 @@
 //! Profile types and operations
 
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 use tars_scanner::types::Scope;
 use uuid::Uuid;
@@ -29,6 +31,10 @@ pub struct SourceRef {
     pub mode: SourceMode,
     /// When the tool was copied to the profile
     pub copied_at: String,
+    /// Key of the tool's entry when the source is a shared config file, such
+    /// as an MCP server in `.mcp.json`; the hash then covers only that entry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entry: Option<String>,
 }
 
 /// Type of tool that can be referenced in a profile
@@ -97,6 +103,9 @@ pub struct Profile {
     pub name: String,
     /// Optional description
     pub description: Option<String>,
+    /// Parent profiles layered beneath this one, in order (see [`super::resolve`])
+    #[serde(default)]
+    pub extends: Vec<Uuid>,
     /// Tool references for this profile
     #[serde(default)]
     pub tool_refs: Vec<ToolRef>,
@@ -123,6 +132,7 @@ impl Profile {
             id: Uuid::new_v4(),
             name,
             description: None,
+            extends: Vec::new(),
             tool_refs: Vec::new(),
             plugin_set: PluginSet::default(),
             repo_overlays: RepoOverlays::default(),
@@ -174,6 +184,32 @@ pub struct PluginRef {
     pub scope: Scope,
     /// Whether to enable
     pub enabled: bool,
+    /// Installed version this entry follows or is pinned to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_ref: Option<PluginVersionRef>,
+}
+
+impl PluginRef {
+    /// Key the plugin's installed version is tracked under in
+    /// [`crate::storage::PluginVersionStore`], e.g. `name@marketplace`
+    #[must_use]
+    pub fn version_key(&self) -> String {
+        match &self.marketplace {
+            Some(marketplace) if !self.id.contains('@') => format!("{}@{marketplace}", self.id),
+            _ => self.id.clone(),
+        }
+    }
+}
+
+/// Installed plugin version recorded for a profile's plugin entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginVersionRef {
+    /// Version installed when the entry was recorded
+    pub version: String,
+    /// Whether to follow newer installed versions
+    pub mode: SourceMode,
+    /// When the version was recorded
+    pub recorded_at: String,
 }
 
 /// Repository-level overlays
@@ -193,6 +229,12 @@ pub struct RepoOverlays {
     pub agents: Vec<AgentOverlay>,
     /// CLAUDE.md overlay
     pub claude_md: Option<ClaudeMdOverlay>,
+    /// Hooks merged into `.claude/settings.json`
+    #[serde(default)]
+    pub hooks: Option<HooksOverlay>,
+    /// Permission rules merged into `.claude/settings.json`
+    #[serde(default)]
+    pub permissions: Option<PermissionsOverlay>,
 }
 
 /// User-level overlays
@@ -223,6 +265,9 @@ pub struct McpServerOverlay {
     pub env: std::collections::HashMap<String, String>,
     /// URL for http/sse transport
     pub url: Option<String>,
+    /// HTTP headers for http/sse transport
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
 }
 
 /// Skill overlay content
@@ -232,6 +277,62 @@ pub struct SkillOverlay {
     pub name: String,
     /// Full SKILL.md content
     pub content: String,
+    /// Supporting files (`scripts/`, `references/`, ...) next to SKILL.md
+    #[serde(default)]
+    pub files: Vec<SkillFile>,
+}
+
+/// A file bundled with a skill, relative to the skill directory
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkillFile {
+    /// Path relative to the skill directory, `/`-separated
+    pub path: String,
+    /// File content, encoded as described by `encoding`
+    pub content: String,
+    /// How `content` is encoded
+    #[serde(default)]
+    pub encoding: FileEncoding,
+}
+
+impl SkillFile {
+    /// Build a skill file from raw bytes, keeping UTF-8 text readable
+    #[must_use]
+    pub fn from_bytes(path: String, bytes: Vec<u8>) -> Self {
+        match String::from_utf8(bytes) {
+            Ok(content) => Self {
+                path,
+                content,
+                encoding: FileEncoding::Utf8,
+            },
+            Err(e) => Self {
+                path,
+                content: BASE64.encode(e.into_bytes()),
+                encoding: FileEncoding::Base64,
+            },
+        }
+    }
+
+    /// Decode the file content
+    ///
+    /// # Errors
+    /// Returns an error if base64 content is malformed
+    pub fn bytes(&self) -> Result<Vec<u8>, base64::DecodeError> {
+        match self.encoding {
+            FileEncoding::Utf8 => Ok(self.content.clone().into_bytes()),
+            FileEncoding::Base64 => BASE64.decode(&self.content),
+        }
+    }
+}
+
+/// Encoding of a [`SkillFile`]'s content
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileEncoding {
+    /// Plain UTF-8 text
+    #[default]
+    Utf8,
+    /// Binary content, base64-encoded
+    Base64,
 }
 
 /// Command overlay content
@@ -261,6 +362,34 @@ pub struct ClaudeMdOverlay {
     pub content: String,
 }
 
+/// Hooks overlay, in the format of the `hooks` key in `.claude/settings.json`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HooksOverlay {
+    /// Matcher groups keyed by hook event (`PreToolUse`, `Stop`, ...)
+    #[serde(default)]
+    pub events: BTreeMap<String, Vec<serde_json::Value>>,
+}
+
+/// Permission rules from the `permissions` key in `.claude/settings.json`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PermissionsOverlay {
+    /// Allowed tool patterns
+    #[serde(default)]
+    pub allow: Vec<String>,
+    /// Denied tool patterns
+    #[serde(default)]
+    pub deny: Vec<String>,
+    /// Patterns that always prompt
+    #[serde(default)]
+    pub ask: Vec<String>,
+    /// Extra directories Claude may access
+    #[serde(default)]
+    pub additional_directories: Vec<String>,
+    /// Default permission mode
+    #[serde(default)]
+    pub default_mode: Option<String>,
+}
+
 /// Overlay application mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OverlayMode {
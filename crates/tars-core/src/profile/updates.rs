@@ -2,11 +2,26 @@
 //!
 //! This module handles detecting when source files have changed for tools
 //! that are tracked (as opposed to pinned) in a profile.
+//!
+//! MCP servers are copied out of shared config files (`~/.claude.json`, a
+//! project's `.mcp.json`), so their source refs name the server's entry and
+//! hash only that entry. Plugin entries instead follow the installed version
+//! recorded in [`PluginVersionStore`].
 
-use crate::profile::storage::{compute_dir_hash, compute_file_hash, StorageError};
-use crate::profile::types::{Profile, SourceMode, SourceRef, ToolType};
+use crate::profile::storage::{
+    compute_dir_hash, compute_file_hash, store_mcp_server, StorageError,
+};
+use crate::profile::types::{
+    PluginRef, PluginVersionRef, Profile, SourceMode, SourceRef, ToolType,
+};
+use crate::storage::db::DatabaseError;
+use crate::storage::plugin_versions::PluginVersionStore;
 use chrono::Utc;
-use std::path::PathBuf;
+use rusqlite::Connection;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
 
 /// Information about an available update for a tool
 #[derive(Debug, Clone)]
@@ -25,6 +40,19 @@ pub struct ToolUpdateInfo {
     pub mode: SourceMode,
 }
 
+/// A tracked plugin whose installed version has changed
+#[derive(Debug, Clone)]
+pub struct PluginUpdateInfo {
+    /// Plugin identifier
+    pub plugin_id: String,
+    /// Marketplace it comes from
+    pub marketplace: Option<String>,
+    /// Version recorded in the profile
+    pub recorded_version: String,
+    /// Version currently installed
+    pub installed_version: String,
+}
+
 /// Result of checking for profile updates
 #[derive(Debug, Clone, Default)]
 pub struct ProfileUpdateCheck {
@@ -78,8 +106,20 @@ pub fn check_profile_updates(profile: &Profile) -> Result<ProfileUpdateCheck, St
         }
 
         // Compute current hash based on tool type
-        let current_hash = match tool.tool_type {
-            ToolType::Skill => {
+        let current_hash = match (tool.tool_type, &source_ref.entry) {
+            (ToolType::Mcp, Some(entry)) => {
+                // MCP servers hash only their entry in the shared config file
+                let Some(config) = read_mcp_server_entry(&source_ref.source_path, entry)? else {
+                    result.missing_sources.push(tool.name.clone());
+                    continue;
+                };
+                compute_mcp_entry_hash(&config)
+            }
+            (ToolType::Mcp, None) if source_ref.source_path.is_dir() => {
+                // Recorded before entry tracking, with no comparable hash
+                continue;
+            }
+            (ToolType::Skill, _) => {
                 // Skills are directories
                 compute_dir_hash(&source_ref.source_path)?
             }
@@ -124,9 +164,94 @@ pub fn create_source_ref(
         source_hash,
         mode,
         copied_at: Utc::now().to_rfc3339(),
+        entry: None,
+    })
+}
+
+/// Create a `SourceRef` for an MCP server copied from a config file
+///
+/// Only the server's own entry is hashed, so edits to other servers in the
+/// same file are not reported as updates.
+///
+/// # Errors
+/// Returns an error if the file cannot be read or has no such server
+pub fn create_mcp_source_ref(
+    source_path: PathBuf,
+    server_name: &str,
+    mode: SourceMode,
+) -> Result<SourceRef, StorageError> {
+    let config = read_mcp_server_entry(&source_path, server_name)?.ok_or_else(|| {
+        StorageError::NotFound(format!(
+            "MCP server '{server_name}' not found in {}",
+            source_path.display()
+        ))
+    })?;
+
+    Ok(SourceRef {
+        source_path,
+        source_hash: compute_mcp_entry_hash(&config),
+        mode,
+        copied_at: Utc::now().to_rfc3339(),
+        entry: Some(server_name.to_string()),
     })
 }
 
+/// Read one MCP server's config from a Claude config file
+///
+/// Servers are looked up under `mcpServers`, as in `~/.claude.json` and
+/// `.mcp.json`, or at the top level for plugin-style files without that key.
+///
+/// # Errors
+/// Returns an error if the file cannot be read or parsed
+pub fn read_mcp_server_entry(
+    source_path: &Path,
+    server_name: &str,
+) -> Result<Option<serde_json::Value>, StorageError> {
+    let content = fs::read_to_string(source_path).map_err(|e| StorageError::Io(e.to_string()))?;
+    let mut root: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| StorageError::Io(format!("Failed to parse {}: {e}", source_path.display())))?;
+
+    let servers = match root.get_mut("mcpServers") {
+        Some(servers) => servers.take(),
+        None => root,
+    };
+    Ok(servers
+        .get(server_name)
+        .filter(|config| config.is_object())
+        .cloned())
+}
+
+/// Hash an MCP server config independently of key order and formatting
+#[must_use]
+pub fn compute_mcp_entry_hash(config: &serde_json::Value) -> String {
+    // `serde_json::Value` keeps object keys sorted, so this is canonical
+    let canonical = serde_json::to_vec(config).unwrap_or_default();
+    hex::encode(Sha256::digest(canonical))
+}
+
+/// Copy the current source entry of a tracked MCP server into the profile
+///
+/// Returns the new hash for the tool's source ref.
+///
+/// # Errors
+/// Returns an error if the source has no entry for the server or the config
+/// cannot be stored
+pub fn pull_mcp_server(
+    profile_id: Uuid,
+    server_name: &str,
+    source_ref: &SourceRef,
+) -> Result<String, StorageError> {
+    let entry = source_ref.entry.as_deref().unwrap_or(server_name);
+    let config = read_mcp_server_entry(&source_ref.source_path, entry)?.ok_or_else(|| {
+        StorageError::NotFound(format!(
+            "MCP server '{entry}' not found in {}",
+            source_ref.source_path.display()
+        ))
+    })?;
+    store_mcp_server(profile_id, server_name, &config)?;
+    Ok(compute_mcp_entry_hash(&config))
+}
+
 /// Update the hash in a source ref after pulling changes
 pub fn update_source_hash(source_ref: &mut SourceRef, new_hash: String) {
     source_ref.source_hash = new_hash;
@@ -138,6 +263,67 @@ pub fn set_source_mode(source_ref: &mut SourceRef, mode: SourceMode) {
     source_ref.mode = mode;
 }
 
+// ============================================================================
+// Plugin versions
+// ============================================================================
+
+/// Record the installed version of a plugin on its profile entry
+///
+/// Returns `false` and leaves the entry unchanged when no installed version
+/// has been seen for the plugin.
+///
+/// # Errors
+/// Returns an error if the version store cannot be read
+pub fn record_plugin_version(
+    conn: &Connection,
+    plugin: &mut PluginRef,
+    mode: SourceMode,
+) -> Result<bool, DatabaseError> {
+    let Some(installed) = PluginVersionStore::new(conn).get(&plugin.version_key())? else {
+        return Ok(false);
+    };
+    plugin.version_ref = Some(PluginVersionRef {
+        version: installed.version,
+        mode,
+        recorded_at: Utc::now().to_rfc3339(),
+    });
+    Ok(true)
+}
+
+/// Find tracked plugins whose installed version differs from the profile's
+///
+/// Pinned plugins and plugins without a recorded version are skipped.
+///
+/// # Errors
+/// Returns an error if the version store cannot be read
+pub fn check_plugin_updates(
+    conn: &Connection,
+    profile: &Profile,
+) -> Result<Vec<PluginUpdateInfo>, DatabaseError> {
+    let store = PluginVersionStore::new(conn);
+    let mut updates = Vec::new();
+    for plugin in &profile.plugin_set.plugins {
+        let Some(version_ref) = &plugin.version_ref else {
+            continue;
+        };
+        if version_ref.mode == SourceMode::Pin {
+            continue;
+        }
+        let Some(installed) = store.get(&plugin.version_key())? else {
+            continue;
+        };
+        if installed.version != version_ref.version {
+            updates.push(PluginUpdateInfo {
+                plugin_id: plugin.id.clone(),
+                marketplace: plugin.marketplace.clone(),
+                recorded_version: version_ref.version.clone(),
+                installed_version: installed.version,
+            });
+        }
+    }
+    Ok(updates)
+}
+
 // ============================================================================
 // Migration
 // ============================================================================
@@ -154,6 +340,7 @@ pub fn migrate_legacy_profile(profile: &mut Profile) {
                 source_hash: "legacy".to_string(),
                 mode: SourceMode::Pin,
                 copied_at: Utc::now().to_rfc3339(),
+                entry: None,
             });
         }
     }
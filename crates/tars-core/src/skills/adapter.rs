@@ -0,0 +1,396 @@
+//! Agent adapters: everything agent-specific about deploying a skill.
+//!
+//! Each [`Agent`] has one adapter that declares where the agent reads skills
+//! from in each scope, whether it follows a symlinked skill directory, which
+//! settings file TARS mutes a deployed skill in and how, and — for agents
+//! that do not read `SKILL.md` bundles as-is — how to convert a bundle into
+//! the agent's own format. Supporting another agent means adding an [`Agent`] variant and an
+//! adapter here; the deploy engine, the library and the deployment records
+//! need no agent-specific code.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tars_scanner::parser::parse_skill;
+use tars_scanner::types::Scope as ScanScope;
+
+use super::deploy::{codex_user_skills_dir, Agent, BundleFiles, Scope};
+use crate::export::codex::split_frontmatter;
+
+/// Converts a skill bundle into the files an agent actually reads.
+///
+/// Receives the skill name and the bundle keyed by `/`-separated relative
+/// path, and returns the bundle to materialize in its place.
+pub type Transformer = fn(skill_name: &str, bundle: &BundleFiles) -> BundleFiles;
+
+/// The top-level object of a JSON settings file.
+pub type Settings = Map<String, Value>;
+
+/// What TARS needs to know to deploy skills to one agent.
+pub trait AgentAdapter: Send + Sync {
+    /// The agent this adapter serves.
+    fn agent(&self) -> Agent;
+
+    /// Display name.
+    fn label(&self) -> &'static str;
+
+    /// User-scope skills directory, or `None` if the agent has no user scope.
+    fn user_dir(&self, home: &Path) -> Option<PathBuf>;
+
+    /// Project-scope skills directory under `project_root`, or `None` if the
+    /// agent has no project scope.
+    fn project_dir(&self, project_root: &Path) -> Option<PathBuf>;
+
+    /// Whether the agent loads a skill directory that is a symlink. Agents
+    /// that do not always receive a copy.
+    fn honors_symlinks(&self) -> bool {
+        true
+    }
+
+    /// Whether the agent has a settings mechanism TARS can use to mute a
+    /// deployed skill.
+    fn supports_mute(&self) -> bool {
+        self.mute_settings_path(Scope::User, None, Path::new(""))
+            .is_some()
+    }
+
+    /// JSON settings file holding the mute state of skills deployed at
+    /// `scope`, or `None` if the agent cannot mute skills there.
+    fn mute_settings_path(
+        &self,
+        _scope: Scope,
+        _project_root: Option<&Path>,
+        _home: &Path,
+    ) -> Option<PathBuf> {
+        None
+    }
+
+    /// Record a skill's mute `state` in the settings object read from
+    /// [`mute_settings_path`](Self::mute_settings_path), or clear it with
+    /// `None`. Unrelated keys are left as they are.
+    fn set_mute(&self, _settings: &mut Settings, _skill: &str, _state: Option<&str>) {}
+
+    /// Conversion applied to a bundle before it is deployed. Converted
+    /// deployments are always copies.
+    fn transformer(&self) -> Option<Transformer> {
+        None
+    }
+}
+
+/// Return the adapter for an agent.
+#[must_use]
+pub fn adapter_for(agent: Agent) -> &'static dyn AgentAdapter {
+    match agent {
+        Agent::Claude => &ClaudeAdapter,
+        Agent::Codex => &CodexAdapter,
+        Agent::Gemini => &GeminiAdapter,
+        Agent::Cursor => &CursorAdapter,
+        Agent::OpenCode => &OpenCodeAdapter,
+    }
+}
+
+/// An adapter's declarations, for listing deploy targets.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentInfo {
+    pub agent: Agent,
+    pub label: String,
+    /// Resolved user-scope directory; `None` when there is no user scope.
+    pub user_dir: Option<PathBuf>,
+    /// Project-scope directory relative to the project root; `None` when
+    /// there is no project scope.
+    pub project_dir: Option<PathBuf>,
+    pub symlinks: bool,
+    pub mute: bool,
+    /// Whether bundles are converted to another format on deploy.
+    pub converts: bool,
+}
+
+/// Describe every agent's adapter, resolving user directories under `home`.
+#[must_use]
+pub fn agent_infos(home: &Path) -> Vec<AgentInfo> {
+    Agent::ALL
+        .iter()
+        .map(|&agent| {
+            let adapter = adapter_for(agent);
+            AgentInfo {
+                agent,
+                label: adapter.label().to_string(),
+                user_dir: adapter.user_dir(home),
+                project_dir: adapter.project_dir(Path::new("")),
+                symlinks: adapter.honors_symlinks(),
+                mute: adapter.supports_mute(),
+                converts: adapter.transformer().is_some(),
+            }
+        })
+        .collect()
+}
+
+/// Claude Code: `.claude/skills`, muted through `skillOverrides`.
+struct ClaudeAdapter;
+
+impl AgentAdapter for ClaudeAdapter {
+    fn agent(&self) -> Agent {
+        Agent::Claude
+    }
+
+    fn label(&self) -> &'static str {
+        "Claude Code"
+    }
+
+    fn user_dir(&self, home: &Path) -> Option<PathBuf> {
+        Some(home.join(".claude").join("skills"))
+    }
+
+    fn project_dir(&self, project_root: &Path) -> Option<PathBuf> {
+        Some(project_root.join(".claude").join("skills"))
+    }
+
+    fn mute_settings_path(
+        &self,
+        scope: Scope,
+        project_root: Option<&Path>,
+        home: &Path,
+    ) -> Option<PathBuf> {
+        let root = match scope {
+            Scope::User => home,
+            Scope::Project => project_root?,
+        };
+        Some(root.join(".claude").join("settings.json"))
+    }
+
+    fn set_mute(&self, settings: &mut Settings, skill: &str, state: Option<&str>) {
+        match state {
+            Some(state) => {
+                let overrides = settings
+                    .entry("skillOverrides")
+                    .or_insert_with(|| Value::Object(Map::new()));
+                if let Some(obj) = overrides.as_object_mut() {
+                    obj.insert(skill.to_string(), Value::String(state.to_string()));
+                }
+            }
+            None => {
+                if let Some(obj) = settings
+                    .get_mut("skillOverrides")
+                    .and_then(Value::as_object_mut)
+                {
+                    obj.remove(skill);
+                    if obj.is_empty() {
+                        settings.remove("skillOverrides");
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Codex: a detected user directory and the tool-neutral `.agents/skills`.
+struct CodexAdapter;
+
+impl AgentAdapter for CodexAdapter {
+    fn agent(&self) -> Agent {
+        Agent::Codex
+    }
+
+    fn label(&self) -> &'static str {
+        "Codex"
+    }
+
+    fn user_dir(&self, home: &Path) -> Option<PathBuf> {
+        Some(codex_user_skills_dir(home))
+    }
+
+    fn project_dir(&self, project_root: &Path) -> Option<PathBuf> {
+        Some(project_root.join(".agents").join("skills"))
+    }
+}
+
+/// Gemini CLI: `SKILL.md` bundles under `.gemini/skills`.
+struct GeminiAdapter;
+
+impl AgentAdapter for GeminiAdapter {
+    fn agent(&self) -> Agent {
+        Agent::Gemini
+    }
+
+    fn label(&self) -> &'static str {
+        "Gemini CLI"
+    }
+
+    fn user_dir(&self, home: &Path) -> Option<PathBuf> {
+        Some(home.join(".gemini").join("skills"))
+    }
+
+    fn project_dir(&self, project_root: &Path) -> Option<PathBuf> {
+        Some(project_root.join(".gemini").join("skills"))
+    }
+}
+
+/// `opencode`: `SKILL.md` bundles under `~/.config/opencode` and `.opencode`.
+struct OpenCodeAdapter;
+
+impl AgentAdapter for OpenCodeAdapter {
+    fn agent(&self) -> Agent {
+        Agent::OpenCode
+    }
+
+    fn label(&self) -> &'static str {
+        "OpenCode"
+    }
+
+    fn user_dir(&self, home: &Path) -> Option<PathBuf> {
+        Some(home.join(".config").join("opencode").join("skills"))
+    }
+
+    fn project_dir(&self, project_root: &Path) -> Option<PathBuf> {
+        Some(project_root.join(".opencode").join("skills"))
+    }
+}
+
+/// Cursor: project rules under `.cursor/rules`, rendered as `.mdc` files.
+/// Cursor keeps user rules in its own settings, so there is no user scope.
+struct CursorAdapter;
+
+impl AgentAdapter for CursorAdapter {
+    fn agent(&self) -> Agent {
+        Agent::Cursor
+    }
+
+    fn label(&self) -> &'static str {
+        "Cursor"
+    }
+
+    fn user_dir(&self, _home: &Path) -> Option<PathBuf> {
+        None
+    }
+
+    fn project_dir(&self, project_root: &Path) -> Option<PathBuf> {
+        Some(project_root.join(".cursor").join("rules"))
+    }
+
+    fn honors_symlinks(&self) -> bool {
+        false
+    }
+
+    fn transformer(&self) -> Option<Transformer> {
+        Some(skill_to_cursor_rule)
+    }
+}
+
+/// Render `SKILL.md` as an agent-requested Cursor rule, `<name>.mdc`.
+///
+/// The skill's description becomes the rule description Cursor uses to decide
+/// when to attach it; supporting files are kept alongside so relative links in
+/// the body still resolve.
+fn skill_to_cursor_rule(skill_name: &str, bundle: &BundleFiles) -> BundleFiles {
+    let mut out = bundle.clone();
+    let Some(skill_md) = out.remove("SKILL.md") else {
+        return out;
+    };
+    let content = String::from_utf8_lossy(&skill_md);
+    let description = parse_skill(Path::new("SKILL.md"), &content, ScanScope::User)
+        .map(|info| info.description)
+        .unwrap_or_default();
+    let body = split_frontmatter(&content).map_or_else(|| content.to_string(), |(_, body)| body);
+    let rule = format!(
+        "---\ndescription: {}\nglobs:\nalwaysApply: false\n---\n{}",
+        serde_json::to_string(&description).unwrap_or_default(),
+        body.trim_start_matches('\n')
+    );
+    out.insert(format!("{skill_name}.mdc"), rule.into_bytes());
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn every_agent_has_an_adapter_for_itself() {
+        for &agent in Agent::ALL {
+            let adapter = adapter_for(agent);
+            assert_eq!(adapter.agent(), agent);
+            assert_eq!(Agent::from_db_str(agent.as_str()), Some(agent));
+        }
+    }
+
+    #[test]
+    fn infos_declare_scopes_and_capabilities() {
+        let infos = agent_infos(Path::new("/home/u"));
+        let cursor = infos.iter().find(|i| i.agent == Agent::Cursor).unwrap();
+        assert_eq!(cursor.user_dir, None);
+        assert_eq!(cursor.project_dir, Some(PathBuf::from(".cursor/rules")));
+        assert!(cursor.converts && !cursor.symlinks && !cursor.mute);
+
+        let gemini = infos.iter().find(|i| i.agent == Agent::Gemini).unwrap();
+        assert_eq!(
+            gemini.user_dir,
+            Some(PathBuf::from("/home/u/.gemini/skills"))
+        );
+        assert!(gemini.symlinks && !gemini.converts);
+
+        let claude = infos.iter().find(|i| i.agent == Agent::Claude).unwrap();
+        assert!(claude.mute);
+    }
+
+    #[test]
+    fn claude_mutes_through_skill_overrides() {
+        let claude = adapter_for(Agent::Claude);
+        assert_eq!(
+            claude.mute_settings_path(
+                Scope::Project,
+                Some(Path::new("/repo")),
+                Path::new("/home/u")
+            ),
+            Some(PathBuf::from("/repo/.claude/settings.json"))
+        );
+        assert_eq!(
+            claude.mute_settings_path(Scope::Project, None, Path::new("/home/u")),
+            None
+        );
+
+        let mut settings = json!({ "model": "opus" }).as_object().unwrap().clone();
+        claude.set_mute(&mut settings, "deep-research", Some("off"));
+        assert_eq!(
+            settings["skillOverrides"],
+            json!({ "deep-research": "off" })
+        );
+
+        // Switching state overwrites in place.
+        claude.set_mute(&mut settings, "deep-research", Some("name-only"));
+        assert_eq!(
+            settings["skillOverrides"]["deep-research"],
+            json!("name-only")
+        );
+
+        // Clearing the last entry removes the whole skillOverrides object.
+        claude.set_mute(&mut settings, "deep-research", None);
+        assert!(!settings.contains_key("skillOverrides"));
+        assert_eq!(settings["model"], json!("opus"));
+    }
+
+    #[test]
+    fn cursor_rule_keeps_description_body_and_supporting_files() {
+        let bundle: BundleFiles = [
+            (
+                "SKILL.md".to_string(),
+                b"---\nname: review\ndescription: \"Review a diff: carefully\"\n---\n\nRead refs/guide.md\n"
+                    .to_vec(),
+            ),
+            ("refs/guide.md".to_string(), b"guide".to_vec()),
+        ]
+        .into_iter()
+        .collect();
+        let rule = skill_to_cursor_rule("review", &bundle);
+        assert_eq!(
+            rule.keys().collect::<Vec<_>>(),
+            vec!["refs/guide.md", "review.mdc"]
+        );
+        assert_eq!(
+            String::from_utf8(rule["review.mdc"].clone()).unwrap(),
+            "---\ndescription: \"Review a diff: carefully\"\nglobs:\nalwaysApply: false\n---\nRead refs/guide.md\n"
+        );
+    }
+}
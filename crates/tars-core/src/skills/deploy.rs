@@ -1,11 +1,14 @@
 //! Materialize a library skill into an agent's skills directory, and remove it.
 //!
-//! A skill is a directory containing a `SKILL.md`. Both Claude Code and Codex
-//! discover skills by directory presence and follow symlinks, so a deployment
-//! is just a symlink (default) — or a copy, for shared repos where a
-//! machine-local symlink would be useless to teammates — from the agent's
-//! skills directory to the canonical skill folder in the library.
-
+//! A skill is a directory containing a `SKILL.md`. Most agents discover skills
+//! by directory presence and follow symlinks, so a deployment is just a
+//! symlink (default) — or a copy, for shared repos where a machine-local
+//! symlink would be useless to teammates — from the agent's skills directory
+//! to the canonical skill folder in the library. Where each agent looks, and
+//! whether it needs a copy or a converted bundle instead, is declared by its
+//! [`AgentAdapter`](super::adapter::AgentAdapter).
+
+use std::collections::BTreeMap;
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
@@ -14,12 +17,20 @@ use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use walkdir::WalkDir;
 
+use super::adapter::{adapter_for, AgentAdapter};
+
+/// An in-memory skill bundle, keyed by `/`-separated relative path.
+pub type BundleFiles = BTreeMap<String, Vec<u8>>;
+
 /// Target agent for a deployment.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Agent {
     Claude,
     Codex,
+    Gemini,
+    Cursor,
+    OpenCode,
 }
 
 /// Target scope for a deployment.
@@ -39,11 +50,23 @@ pub enum LinkKind {
 }
 
 impl Agent {
+    /// Every agent TARS can deploy skills to.
+    pub const ALL: &'static [Agent] = &[
+        Agent::Claude,
+        Agent::Codex,
+        Agent::Gemini,
+        Agent::Cursor,
+        Agent::OpenCode,
+    ];
+
     /// String form stored in the `skill_deployments.agent` column.
     pub fn as_str(self) -> &'static str {
         match self {
             Agent::Claude => "claude",
             Agent::Codex => "codex",
+            Agent::Gemini => "gemini",
+            Agent::Cursor => "cursor",
+            Agent::OpenCode => "opencode",
         }
     }
 
@@ -51,9 +74,17 @@ impl Agent {
         match value {
             "claude" => Some(Agent::Claude),
             "codex" => Some(Agent::Codex),
+            "gemini" => Some(Agent::Gemini),
+            "cursor" => Some(Agent::Cursor),
+            "opencode" => Some(Agent::OpenCode),
             _ => None,
         }
     }
+
+    /// The adapter describing how skills deploy to this agent.
+    pub fn adapter(self) -> &'static dyn AgentAdapter {
+        adapter_for(self)
+    }
 }
 
 impl Scope {
@@ -103,6 +134,11 @@ pub enum SkillDeployError {
     TargetNotACopy(PathBuf),
     #[error("a project root is required for project scope")]
     ProjectRootRequired,
+    #[error("{agent} has no {scope}-scope skills directory")]
+    UnsupportedScope {
+        agent: &'static str,
+        scope: &'static str,
+    },
     #[error("io error: {0}")]
     Io(#[from] io::Error),
 }
@@ -120,28 +156,26 @@ pub struct DeployResult {
 
 /// Resolve the agent's skills directory for a target.
 ///
-/// Claude uses `.claude/skills`; Codex user scope is detected
-/// ([`codex_user_skills_dir`]) and Codex project scope is the tool-neutral
-/// `.agents/skills`. `home` is the user's home directory (injected for tests).
+/// The directory comes from the agent's adapter; an agent without the
+/// requested scope is an [`SkillDeployError::UnsupportedScope`]. `home` is the
+/// user's home directory (injected for tests).
 pub fn resolve_skills_dir(
     agent: Agent,
     scope: Scope,
     project_root: Option<&Path>,
     home: &Path,
 ) -> Result<PathBuf, SkillDeployError> {
-    match scope {
-        Scope::User => Ok(match agent {
-            Agent::Claude => home.join(".claude").join("skills"),
-            Agent::Codex => codex_user_skills_dir(home),
-        }),
+    let adapter = agent.adapter();
+    let dir = match scope {
+        Scope::User => adapter.user_dir(home),
         Scope::Project => {
-            let root = project_root.ok_or(SkillDeployError::ProjectRootRequired)?;
-            Ok(match agent {
-                Agent::Claude => root.join(".claude").join("skills"),
-                Agent::Codex => root.join(".agents").join("skills"),
-            })
+            adapter.project_dir(project_root.ok_or(SkillDeployError::ProjectRootRequired)?)
         }
-    }
+    };
+    dir.ok_or(SkillDeployError::UnsupportedScope {
+        agent: adapter.label(),
+        scope: scope.as_str(),
+    })
 }
 
 /// Detect the Codex user skills directory.
@@ -168,8 +202,10 @@ pub fn codex_user_skills_dir(home: &Path) -> PathBuf {
 /// `skill_name`.
 ///
 /// Refuses to overwrite an existing entry (so a pre-existing skill the user
-/// placed by hand is never clobbered). The caller is responsible for recording
-/// the returned [`DeployResult`] in the deployment store.
+/// placed by hand is never clobbered). Agents that do not follow symlinks, or
+/// whose adapter converts the bundle, always get a copy, whatever `link_kind`
+/// asks for. The caller is responsible for recording the returned
+/// [`DeployResult`] in the deployment store.
 pub fn deploy(
     source_skill_dir: &Path,
     skill_name: &str,
@@ -198,15 +234,29 @@ pub fn deploy(
         });
     }
 
-    match link_kind {
-        LinkKind::Symlink => make_symlink(source_skill_dir, &link_path)?,
-        LinkKind::Copy => copy_dir(source_skill_dir, &link_path)?,
-    }
+    let adapter = agent.adapter();
+    let transformer = adapter.transformer();
+    let link_kind = if transformer.is_some() || !adapter.honors_symlinks() {
+        LinkKind::Copy
+    } else {
+        link_kind
+    };
 
     // Only copies can drift from the source; symlinks are the source.
-    let sha256 = match link_kind {
-        LinkKind::Copy => hash_bundle(source_skill_dir),
-        LinkKind::Symlink => None,
+    let sha256 = match (link_kind, transformer) {
+        (LinkKind::Symlink, _) => {
+            make_symlink(source_skill_dir, &link_path)?;
+            None
+        }
+        (LinkKind::Copy, None) => {
+            copy_dir(source_skill_dir, &link_path)?;
+            hash_bundle(source_skill_dir)
+        }
+        (LinkKind::Copy, Some(transform)) => {
+            let files = transform(skill_name, &read_bundle(source_skill_dir)?);
+            write_bundle(&link_path, &files)?;
+            Some(hash_files(&files))
+        }
     };
 
     Ok(DeployResult {
@@ -310,57 +360,66 @@ pub(crate) fn copy_dir(src: &Path, dst: &Path) -> Result<(), SkillDeployError> {
 /// Used for drift detection on COPY deploys — a symlink deploy *is* the source
 /// and never drifts, so it carries no hash.
 pub fn hash_bundle(skill_dir: &Path) -> Option<String> {
-    let mut files: Vec<(String, PathBuf)> = Vec::new();
-    for entry in WalkDir::new(skill_dir).follow_links(false) {
-        let entry = entry.ok()?;
-        // Skip directories and nested symlinks; only real files are content.
-        if !entry.file_type().is_file() {
-            continue;
-        }
-        let rel = entry.path().strip_prefix(skill_dir).ok()?;
-        // Normalize separators so the hash is stable across platforms.
-        let rel = rel.to_string_lossy().replace('\\', "/");
-        files.push((rel, entry.path().to_path_buf()));
-    }
-    files.sort_by(|a, b| a.0.cmp(&b.0));
+    read_bundle(skill_dir).ok().map(|files| hash_files(&files))
+}
 
+/// Hash an in-memory bundle exactly as [`hash_bundle`] hashes one on disk.
+pub(crate) fn hash_files(files: &BundleFiles) -> String {
     let mut hasher = Sha256::new();
-    for (rel, path) in files {
-        let content = fs::read(&path).ok()?;
+    for (rel, content) in files {
         hasher.update(rel.as_bytes());
         hasher.update([0u8]); // path/content delimiter
         hasher.update((content.len() as u64).to_le_bytes());
-        hasher.update(&content);
+        hasher.update(content);
     }
-    Some(hex::encode(hasher.finalize()))
+    hex::encode(hasher.finalize())
 }
 
-/// Re-copy a COPY deployment from its source, refreshing a drifted bundle.
+/// Read a skill's bundle into memory, keyed by `/`-separated relative path.
 ///
-/// Removes the existing copy (only if it is a real directory — never a
-/// symlink) and re-materializes the whole folder, returning the new bundle
-/// hash. Refuses anything that is not a plain directory at `link_path`, so it
-/// can never clobber a symlink deploy or a foreign file.
-pub fn resync_copy(
-    source_skill_dir: &Path,
-    link_path: &Path,
-) -> Result<Option<String>, SkillDeployError> {
-    if !source_skill_dir.is_dir() {
-        return Err(SkillDeployError::SourceNotFound(
-            source_skill_dir.to_path_buf(),
-        ));
+/// Walks the same regular files as [`hash_bundle`], so the map describes
+/// exactly what a copy deploy materializes. Used to record the deploy-time
+/// base of a copy and to classify drift file by file.
+pub fn read_bundle(skill_dir: &Path) -> io::Result<BundleFiles> {
+    let mut files = BTreeMap::new();
+    for entry in WalkDir::new(skill_dir).follow_links(false) {
+        let entry = entry.map_err(io::Error::other)?;
+        // Skip directories and nested symlinks; only real files are content.
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let rel = entry
+            .path()
+            .strip_prefix(skill_dir)
+            .map_err(io::Error::other)?;
+        // Normalize separators so paths (and hashes) are stable across platforms.
+        let rel = rel.to_string_lossy().replace('\\', "/");
+        files.insert(rel, fs::read(entry.path())?);
     }
-    match link_path.symlink_metadata() {
-        Ok(meta) if meta.file_type().is_symlink() => {
-            return Err(SkillDeployError::TargetNotACopy(link_path.to_path_buf()))
+    Ok(files)
+}
+
+/// Read a library skill's bundle as `agent` receives it: converted by the
+/// agent's transformer, if it has one, and otherwise as-is.
+pub fn render_bundle(agent: Agent, skill_name: &str, skill_dir: &Path) -> io::Result<BundleFiles> {
+    let files = read_bundle(skill_dir)?;
+    Ok(match agent.adapter().transformer() {
+        Some(transform) => transform(skill_name, &files),
+        None => files,
+    })
+}
+
+/// Materialize an in-memory bundle as a new directory at `dir`.
+fn write_bundle(dir: &Path, files: &BundleFiles) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    for (rel, content) in files {
+        let path = dir.join(rel);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
         }
-        Ok(meta) if meta.is_dir() => fs::remove_dir_all(link_path)?,
-        Ok(_) => return Err(SkillDeployError::TargetNotACopy(link_path.to_path_buf())),
-        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
-        Err(e) => return Err(e.into()),
+        fs::write(&path, content)?;
     }
-    copy_dir(source_skill_dir, link_path)?;
-    Ok(hash_bundle(source_skill_dir))
+    Ok(())
 }
 
 #[cfg(test)]
@@ -512,7 +571,7 @@ mod tests {
     }
 
     #[test]
-    fn bundle_hash_reflects_supporting_files_and_resync_clears_drift() {
+    fn bundle_hash_reflects_supporting_files() {
         let tmp = TempDir::new().unwrap();
         let home = tmp.path().join("home");
         let lib = tmp.path().join("lib");
@@ -536,30 +595,6 @@ mod tests {
         fs::write(src.join("references").join("guide.md"), "# guide v2\n").unwrap();
         let current = hash_bundle(&src).unwrap();
         assert_ne!(deployed_hash, current, "supporting-file edit should drift");
-
-        // Re-sync re-copies the source and returns the fresh hash, clearing drift.
-        let refreshed = resync_copy(&src, &res.link_path).unwrap().unwrap();
-        assert_eq!(refreshed, current);
-        assert_eq!(
-            fs::read_to_string(res.link_path.join("references/guide.md")).unwrap(),
-            "# guide v2\n"
-        );
-
-        // Re-sync refuses to touch a symlink deploy.
-        let link = deploy(
-            &src,
-            "rich",
-            Agent::Claude,
-            Scope::User,
-            None,
-            LinkKind::Symlink,
-            &home,
-        )
-        .unwrap();
-        assert!(matches!(
-            resync_copy(&src, &link.link_path),
-            Err(SkillDeployError::TargetNotACopy(_))
-        ));
     }
 
     #[test]
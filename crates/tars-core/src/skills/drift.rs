@@ -0,0 +1,521 @@
+//! Bidirectional drift for copy deployments.
+//!
+//! A copy can drift from either end: the library source is edited after
+//! deploy, or someone edits the deployed copy in place. The bundle recorded at
+//! deploy time (see
+//! [`SkillDeploymentStore::base_files`](crate::storage::skill_library::SkillDeploymentStore::base_files))
+//! is the common base that tells the two apart file by file, and is what a
+//! three-way merge merges against. Deployments made before per-file bases were
+//! recorded fall back to the bundle hash, which can only say which side moved.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use super::deploy::{
+    copy_dir, hash_files, read_bundle, render_bundle, Agent, BundleFiles, LinkKind,
+    SkillDeployError,
+};
+use super::library::SkillLibraryError;
+use crate::diff::merge::merge3;
+use crate::storage::skill_library::{SkillDeployment, SkillDeploymentStore};
+
+/// Which side of a copy deployment changed a file since deploy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileDriftKind {
+    /// Only the library source changed.
+    SourceChanged,
+    /// Only the deployed copy changed.
+    TargetChanged,
+    /// Both sides changed, differently.
+    BothChanged,
+}
+
+/// One drifted file of a copy deployment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDrift {
+    pub path: String,
+    pub kind: FileDriftKind,
+}
+
+/// Per-file drift of a copy deployment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeploymentDrift {
+    pub deployment_id: i64,
+    pub skill_name: String,
+    pub source_path: String,
+    pub link_path: String,
+    /// Whether a per-file deploy-time base was recorded. Without one, a file
+    /// changed on both sides can only be resolved with conflict markers.
+    pub has_baseline: bool,
+    pub files: Vec<FileDrift>,
+}
+
+/// How to bring a drifted copy deployment back in sync.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DriftResolution {
+    /// Pull edits made to the deployed copy back into the library source,
+    /// three-way merging files changed on both sides. Files only the library
+    /// changed are left as they are.
+    PullBack,
+    /// Overwrite the deployed copy with the library source.
+    Overwrite,
+    /// Take each side's changes file by file, three-way merging files changed
+    /// on both sides. Unresolved conflicts are written to the deployed copy.
+    Merge,
+}
+
+/// What [`resolve_drift`] changed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DriftReport {
+    /// Directories holding the pre-resolution copies of each side written to.
+    pub backups: Vec<PathBuf>,
+    /// Files written to (or removed from) the library source.
+    pub source_updated: Vec<String>,
+    /// Files written to (or removed from) the deployed copy.
+    pub target_updated: Vec<String>,
+    /// Files left with conflict markers in the deployed copy, or untouched
+    /// when they could not be merged as text.
+    pub conflicts: Vec<String>,
+}
+
+/// Classify how `source` and `target` differ, file by file.
+///
+/// With a recorded `base`, each differing file is attributed to the side that
+/// no longer matches the base. Without one, `recorded_hash` (the bundle hash
+/// stored at deploy) tells which whole side moved; if neither matches, every
+/// differing file counts as changed on both sides. Files that differ from the
+/// base identically on both sides are not drift.
+#[must_use]
+pub fn classify_drift(
+    base: Option<&BundleFiles>,
+    recorded_hash: Option<&str>,
+    source: &BundleFiles,
+    target: &BundleFiles,
+) -> Vec<FileDrift> {
+    let fallback = if base.is_some() {
+        None
+    } else if recorded_hash == Some(hash_files(source).as_str()) {
+        Some(FileDriftKind::TargetChanged)
+    } else if recorded_hash == Some(hash_files(target).as_str()) {
+        Some(FileDriftKind::SourceChanged)
+    } else {
+        Some(FileDriftKind::BothChanged)
+    };
+
+    let paths: BTreeSet<&String> = source.keys().chain(target.keys()).collect();
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let in_source = source.get(path);
+            let in_target = target.get(path);
+            if in_source == in_target {
+                return None;
+            }
+            let kind = match base {
+                Some(base) => {
+                    let in_base = base.get(path);
+                    if in_source == in_base {
+                        FileDriftKind::TargetChanged
+                    } else if in_target == in_base {
+                        FileDriftKind::SourceChanged
+                    } else {
+                        FileDriftKind::BothChanged
+                    }
+                }
+                None => fallback.unwrap_or(FileDriftKind::BothChanged),
+            };
+            Some(FileDrift {
+                path: path.clone(),
+                kind,
+            })
+        })
+        .collect()
+}
+
+/// Report per-file drift for a copy deployment.
+pub fn deployment_drift(
+    conn: &Connection,
+    deployment_id: i64,
+) -> Result<DeploymentDrift, SkillLibraryError> {
+    let (dep, base, source, target) = load(conn, deployment_id)?;
+    let files = classify_drift(base.as_ref(), dep.sha256.as_deref(), &source, &target);
+    Ok(DeploymentDrift {
+        deployment_id,
+        skill_name: dep.skill_name,
+        source_path: dep.source_path,
+        link_path: dep.link_path,
+        has_baseline: base.is_some(),
+        files,
+    })
+}
+
+/// Bring a drifted copy deployment back in sync.
+///
+/// Before anything is written, the whole bundle of each side about to change
+/// is copied under `backup_dir/skills/`. Afterwards the source bundle becomes
+/// the new deploy-time base and hash, so a file left conflicted in the copy
+/// shows up as a target-side change until it is pulled back or overwritten.
+/// Files a pull-back leaves alone keep their old base and stay library-side
+/// drift.
+pub fn resolve_drift(
+    conn: &Connection,
+    deployment_id: i64,
+    resolution: DriftResolution,
+    backup_dir: &Path,
+) -> Result<DriftReport, SkillLibraryError> {
+    let (dep, base, source, target) = load(conn, deployment_id)?;
+    let agent = Agent::from_db_str(&dep.agent);
+    // A converted copy has no file-for-file counterpart in the library, so
+    // only the library side can win.
+    if resolution != DriftResolution::Overwrite
+        && agent.is_some_and(|agent| agent.adapter().transformer().is_some())
+    {
+        return Err(SkillLibraryError::ConvertedDeployment(dep.agent));
+    }
+    let drift = classify_drift(base.as_ref(), dep.sha256.as_deref(), &source, &target);
+
+    let mut merged: BTreeMap<&str, Vec<u8>> = BTreeMap::new();
+    // `None` removes the file from that side.
+    let mut source_writes: BTreeMap<&str, Option<&[u8]>> = BTreeMap::new();
+    let mut target_writes: BTreeMap<&str, Option<&[u8]>> = BTreeMap::new();
+    // Library-side changes a pull-back leaves undeployed.
+    let mut unsynced: Vec<&str> = Vec::new();
+    let mut report = DriftReport::default();
+
+    for file in &drift {
+        let path = file.path.as_str();
+        let in_source = source.get(path).map(Vec::as_slice);
+        let in_target = target.get(path).map(Vec::as_slice);
+        match (resolution, file.kind) {
+            (DriftResolution::PullBack | DriftResolution::Merge, FileDriftKind::TargetChanged) => {
+                source_writes.insert(path, in_target);
+            }
+            (DriftResolution::PullBack, FileDriftKind::SourceChanged) => {
+                unsynced.push(path);
+            }
+            (DriftResolution::Overwrite, _)
+            | (DriftResolution::Merge, FileDriftKind::SourceChanged) => {
+                target_writes.insert(path, in_source);
+            }
+            (DriftResolution::PullBack | DriftResolution::Merge, FileDriftKind::BothChanged) => {
+                let in_base = base
+                    .as_ref()
+                    .and_then(|base| base.get(path))
+                    .map_or(&[][..], Vec::as_slice);
+                let texts = (
+                    std::str::from_utf8(in_base),
+                    std::str::from_utf8(in_target.unwrap_or_default()),
+                    std::str::from_utf8(in_source.unwrap_or_default()),
+                );
+                let (Ok(in_base), Ok(ours), Ok(theirs)) = texts else {
+                    // Binary content cannot carry conflict markers.
+                    report.conflicts.push(file.path.clone());
+                    continue;
+                };
+                let outcome = merge3(in_base, ours, theirs, "deployed", "library");
+                if !outcome.is_clean() {
+                    report.conflicts.push(file.path.clone());
+                }
+                merged.insert(path, outcome.content.into_bytes());
+            }
+        }
+    }
+    for (path, content) in &merged {
+        let clean = !report.conflicts.iter().any(|c| c == path);
+        if clean {
+            source_writes.insert(path, Some(content.as_slice()));
+        }
+        target_writes.insert(path, Some(content.as_slice()));
+    }
+
+    let source_dir = Path::new(&dep.source_path);
+    let link_path = Path::new(&dep.link_path);
+    let stamp = Utc::now().format("%Y%m%dT%H%M%S%.3f");
+    for (side, dir, writes) in [
+        ("source", source_dir, &source_writes),
+        ("target", link_path, &target_writes),
+    ] {
+        if writes.is_empty() {
+            continue;
+        }
+        let backup = backup_dir
+            .join("skills")
+            .join(format!("{}-{}-{side}-{stamp}", dep.skill_name, dep.id));
+        if dir.is_dir() {
+            copy_dir(dir, &backup)?;
+            report.backups.push(backup);
+        }
+        apply_writes(dir, writes)?;
+    }
+    report.source_updated = source_writes.keys().map(ToString::to_string).collect();
+    report.target_updated = target_writes.keys().map(ToString::to_string).collect();
+
+    let mut new_base = source_bundle(&dep)?;
+    // The copy still holds the base version of files the library changed but
+    // a pull-back left alone, so they keep showing as library-side drift.
+    for path in unsynced {
+        match target.get(path) {
+            Some(content) => new_base.insert(path.to_string(), content.clone()),
+            None => new_base.remove(path),
+        };
+    }
+    let store = SkillDeploymentStore::new(conn);
+    store.set_base_files(deployment_id, &new_base)?;
+    store.update_sha256(deployment_id, Some(&hash_files(&new_base)))?;
+    Ok(report)
+}
+
+/// Load a copy deployment with its recorded base and both current bundles.
+fn load(
+    conn: &Connection,
+    deployment_id: i64,
+) -> Result<
+    (
+        SkillDeployment,
+        Option<BundleFiles>,
+        BundleFiles,
+        BundleFiles,
+    ),
+    SkillLibraryError,
+> {
+    let store = SkillDeploymentStore::new(conn);
+    let dep = store
+        .get(deployment_id)?
+        .ok_or(SkillLibraryError::DeploymentNotFound(deployment_id))?;
+    if dep.link_kind != LinkKind::Copy.as_str() {
+        return Err(SkillLibraryError::NotACopy);
+    }
+    let source_dir = Path::new(&dep.source_path);
+    if !source_dir.is_dir() {
+        return Err(SkillDeployError::SourceNotFound(source_dir.to_path_buf()).into());
+    }
+    let link_path = Path::new(&dep.link_path);
+    let target = match link_path.symlink_metadata() {
+        Ok(meta) if meta.is_dir() => read_bundle(link_path)?,
+        Ok(_) => return Err(SkillDeployError::TargetNotACopy(link_path.to_path_buf()).into()),
+        // A deleted copy is drift too: every file changed on the target side.
+        Err(e) if e.kind() == io::ErrorKind::NotFound => BundleFiles::new(),
+        Err(e) => return Err(e.into()),
+    };
+    let source = source_bundle(&dep)?;
+    let base = store.base_files(deployment_id)?;
+    Ok((dep, base, source, target))
+}
+
+/// The library side of a deployment, as its agent receives it.
+fn source_bundle(dep: &SkillDeployment) -> io::Result<BundleFiles> {
+    let source_dir = Path::new(&dep.source_path);
+    match Agent::from_db_str(&dep.agent) {
+        Some(agent) => render_bundle(agent, &dep.skill_name, source_dir),
+        None => read_bundle(source_dir),
+    }
+}
+
+/// Write (or, for `None`, remove) files under a bundle directory.
+fn apply_writes(dir: &Path, writes: &BTreeMap<&str, Option<&[u8]>>) -> io::Result<()> {
+    for (rel, content) in writes {
+        let path = dir.join(rel);
+        match content {
+            Some(content) => {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&path, content)?;
+            }
+            None => match fs::remove_file(&path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            },
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::skills::deploy::{Agent, Scope};
+    use crate::skills::library::{deploy_skill, DeployRequest};
+    use crate::storage::migrations::run_migrations;
+    use tempfile::TempDir;
+
+    fn bundle(entries: &[(&str, &str)]) -> BundleFiles {
+        entries
+            .iter()
+            .map(|(path, content)| ((*path).to_string(), content.as_bytes().to_vec()))
+            .collect()
+    }
+
+    fn kinds(drift: &[FileDrift]) -> Vec<(&str, FileDriftKind)> {
+        drift.iter().map(|f| (f.path.as_str(), f.kind)).collect()
+    }
+
+    #[test]
+    fn classify_attributes_each_file_to_a_side() {
+        let base = bundle(&[("SKILL.md", "v1"), ("a.md", "a"), ("b.md", "b")]);
+        let source = bundle(&[("SKILL.md", "v2"), ("a.md", "a"), ("b.md", "b2")]);
+        let target = bundle(&[("SKILL.md", "v1"), ("a.md", "a!"), ("b.md", "b3")]);
+        let drift = classify_drift(Some(&base), None, &source, &target);
+        assert_eq!(
+            kinds(&drift),
+            vec![
+                ("SKILL.md", FileDriftKind::SourceChanged),
+                ("a.md", FileDriftKind::TargetChanged),
+                ("b.md", FileDriftKind::BothChanged),
+            ]
+        );
+    }
+
+    #[test]
+    fn classify_without_base_uses_the_recorded_hash() {
+        let source = bundle(&[("SKILL.md", "v1")]);
+        let target = bundle(&[("SKILL.md", "v1"), ("new.md", "x")]);
+        let hash = hash_files(&source);
+        let drift = classify_drift(None, Some(&hash), &source, &target);
+        assert_eq!(
+            kinds(&drift),
+            vec![("new.md", FileDriftKind::TargetChanged)]
+        );
+
+        let drift = classify_drift(None, Some("stale"), &source, &target);
+        assert_eq!(kinds(&drift), vec![("new.md", FileDriftKind::BothChanged)]);
+    }
+
+    fn deploy_copy(tmp: &TempDir, conn: &Connection) -> (PathBuf, PathBuf, i64) {
+        let source = tmp.path().join("lib").join("notes");
+        fs::create_dir_all(&source).unwrap();
+        fs::write(source.join("SKILL.md"), "title\nbody\nend\n").unwrap();
+        fs::write(source.join("extra.md"), "extra\n").unwrap();
+        let home = tmp.path().join("home");
+        let request = DeployRequest {
+            skill_name: "notes".into(),
+            source_dir: source.clone(),
+            agent: Agent::Claude,
+            scope: Scope::User,
+            project_id: None,
+            link_kind: LinkKind::Copy,
+        };
+        let dep = deploy_skill(conn, &request, &home).unwrap();
+        (source, PathBuf::from(dep.link_path), dep.id)
+    }
+
+    fn conn() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "foreign_keys", "ON").unwrap();
+        run_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn merge_combines_both_sides_and_backs_up() {
+        let tmp = TempDir::new().unwrap();
+        let conn = conn();
+        let (source, target, id) = deploy_copy(&tmp, &conn);
+
+        fs::write(source.join("SKILL.md"), "title\nbody\nEND\n").unwrap();
+        fs::write(target.join("SKILL.md"), "TITLE\nbody\nend\n").unwrap();
+        fs::write(target.join("local.md"), "mine\n").unwrap();
+
+        let drift = deployment_drift(&conn, id).unwrap();
+        assert!(drift.has_baseline);
+        assert_eq!(
+            kinds(&drift.files),
+            vec![
+                ("SKILL.md", FileDriftKind::BothChanged),
+                ("local.md", FileDriftKind::TargetChanged),
+            ]
+        );
+
+        let backups = tmp.path().join("backups");
+        let report = resolve_drift(&conn, id, DriftResolution::Merge, &backups).unwrap();
+        assert!(report.conflicts.is_empty());
+        assert_eq!(report.backups.len(), 2);
+        assert_eq!(
+            fs::read_to_string(report.backups[0].join("SKILL.md")).unwrap(),
+            "title\nbody\nEND\n"
+        );
+        for dir in [&source, &target] {
+            assert_eq!(
+                fs::read_to_string(dir.join("SKILL.md")).unwrap(),
+                "TITLE\nbody\nEND\n"
+            );
+            assert!(dir.join("local.md").exists());
+        }
+        assert!(deployment_drift(&conn, id).unwrap().files.is_empty());
+    }
+
+    #[test]
+    fn pull_back_and_overwrite_pick_one_side() {
+        let tmp = TempDir::new().unwrap();
+        let conn = conn();
+        let (source, target, id) = deploy_copy(&tmp, &conn);
+        let backups = tmp.path().join("backups");
+
+        fs::remove_file(target.join("extra.md")).unwrap();
+        fs::write(source.join("SKILL.md"), "title\nbody\nEND\n").unwrap();
+        let report = resolve_drift(&conn, id, DriftResolution::PullBack, &backups).unwrap();
+        assert_eq!(report.source_updated, vec!["extra.md".to_string()]);
+        assert!(report.target_updated.is_empty());
+        assert!(!source.join("extra.md").exists());
+        assert!(report.backups[0].join("extra.md").exists());
+        // The library's own edit is neither reverted nor deployed.
+        assert_eq!(
+            fs::read_to_string(source.join("SKILL.md")).unwrap(),
+            "title\nbody\nEND\n"
+        );
+        assert_eq!(
+            kinds(&deployment_drift(&conn, id).unwrap().files),
+            vec![("SKILL.md", FileDriftKind::SourceChanged)]
+        );
+
+        fs::write(source.join("SKILL.md"), "library\n").unwrap();
+        fs::write(target.join("SKILL.md"), "deployed\n").unwrap();
+        let report = resolve_drift(&conn, id, DriftResolution::Overwrite, &backups).unwrap();
+        assert_eq!(report.target_updated, vec!["SKILL.md".to_string()]);
+        assert_eq!(
+            fs::read_to_string(target.join("SKILL.md")).unwrap(),
+            "library\n"
+        );
+        assert_eq!(
+            fs::read_to_string(report.backups[0].join("SKILL.md")).unwrap(),
+            "deployed\n"
+        );
+    }
+
+    #[test]
+    fn merge_conflicts_stay_in_the_target() {
+        let tmp = TempDir::new().unwrap();
+        let conn = conn();
+        let (source, target, id) = deploy_copy(&tmp, &conn);
+
+        fs::write(source.join("SKILL.md"), "title\nlibrary\nend\n").unwrap();
+        fs::write(target.join("SKILL.md"), "title\ndeployed\nend\n").unwrap();
+        let backups = tmp.path().join("backups");
+        let report = resolve_drift(&conn, id, DriftResolution::Merge, &backups).unwrap();
+        assert_eq!(report.conflicts, vec!["SKILL.md".to_string()]);
+        assert_eq!(
+            fs::read_to_string(source.join("SKILL.md")).unwrap(),
+            "title\nlibrary\nend\n"
+        );
+        let marked = fs::read_to_string(target.join("SKILL.md")).unwrap();
+        assert!(marked.contains("<<<<<<< deployed\ndeployed\n=======\nlibrary\n>>>>>>> library"));
+
+        // The source is the new base, so the marked-up copy is a target edit.
+        let drift = deployment_drift(&conn, id).unwrap();
+        assert_eq!(
+            kinds(&drift.files),
+            vec![("SKILL.md", FileDriftKind::TargetChanged)]
+        );
+    }
+}
@@ -2,8 +2,9 @@
 //! (`~/.agents/skills`) — the same destination `npx skills add` uses — from a
 //! local folder or a cloned git checkout.
 //!
-//! The command layer performs the clone; everything here is pure filesystem
-//! work plus URL parsing, so it can be exercised with tempdirs.
+//! Cloning lives in [`origin`](super::origin), which also records where each
+//! bundle came from; everything here is pure filesystem work plus URL
+//! parsing, so it can be exercised with tempdirs.
 
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -13,6 +14,7 @@ use thiserror::Error;
 
 use super::deploy::{copy_dir, make_symlink, SkillDeployError};
 use super::scan::scan_source;
+use crate::storage::db::DatabaseError;
 
 /// Where externally-installed standalone skills live.
 pub fn external_skills_dir(home: &Path) -> PathBuf {
@@ -31,6 +33,18 @@ pub enum SkillInstallError {
     InvalidName(String),
     #[error("unsupported url {0:?}: expected an https git repository url")]
     InvalidUrl(String),
+    #[error("invalid commit {0:?}: expected a commit sha")]
+    InvalidCommit(String),
+    #[error("path not found in repository: {0}")]
+    SubpathNotFound(String),
+    #[error("'{0}' was not installed from git")]
+    NotFromGit(String),
+    #[error("'{name}' is pinned to {sha}; unpin it or pin another commit")]
+    Pinned { name: String, sha: String },
+    #[error("git {0}")]
+    Git(String),
+    #[error("database error: {0}")]
+    Database(#[from] DatabaseError),
     #[error(transparent)]
     Deploy(#[from] SkillDeployError),
     #[error("io error: {0}")]
@@ -116,7 +130,7 @@ pub fn install_bundles(
 
 /// The bundle's frontmatter `name`, falling back to its directory name.
 /// Must be a plain single path component.
-fn bundle_name(bundle: &Path) -> Result<String, SkillInstallError> {
+pub(crate) fn bundle_name(bundle: &Path) -> Result<String, SkillInstallError> {
     let name = scan_source(bundle)
         .into_iter()
         .next()
@@ -0,0 +1,564 @@
+//! Library orchestration: sources, tracked deployments, and muting.
+//!
+//! [`deploy`](super::deploy) materializes a skill on disk; this module ties
+//! that to the `skill_sources` / `skill_deployments` tables and to the
+//! agents' settings files, so the desktop app and the CLI share one
+//! implementation.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use rusqlite::Connection;
+
+use super::adapter::Settings;
+use super::deploy::{
+    codex_user_skills_dir, deploy, read_bundle, resolve_skills_dir, undeploy, Agent, LinkKind,
+    Scope, SkillDeployError,
+};
+use super::install::external_skills_dir;
+use super::scan::{
+    probe_target, scan_external_dir, scan_source, scan_sources, CatalogSkill, TargetProbe,
+};
+use crate::storage::db::DatabaseError;
+use crate::storage::skill_library::{
+    SkillDeployment, SkillDeploymentInput, SkillDeploymentStore, SkillSource, SkillSourceStore,
+};
+use crate::storage::ProjectStore;
+
+/// Errors from library operations.
+#[derive(Debug, thiserror::Error)]
+pub enum SkillLibraryError {
+    #[error("not a directory: {0}")]
+    NotADirectory(PathBuf),
+    #[error("invalid project id: {0}")]
+    InvalidProjectId(String),
+    #[error("project not found: {0}")]
+    ProjectNotFound(String),
+    #[error("project scope requires a project id")]
+    ProjectRequired,
+    #[error("'{0}' is already deployed to this target")]
+    AlreadyDeployed(String),
+    #[error("deployment not found: {0}")]
+    DeploymentNotFound(i64),
+    #[error("invalid deployment scope: {0}")]
+    InvalidScope(String),
+    #[error("invalid mute state: {0}")]
+    InvalidMuteState(String),
+    #[error("muting is not supported for {0} skills")]
+    MuteUnsupported(String),
+    #[error("only copy deployments can drift")]
+    NotACopy,
+    #[error("{0} deployments are converted from the library bundle and can only be overwritten")]
+    ConvertedDeployment(String),
+    #[error("{path} is not a JSON object: {source}")]
+    InvalidSettings {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[error("failed to scan plugins: {0}")]
+    PluginScan(String),
+    #[error(transparent)]
+    Deploy(#[from] SkillDeployError),
+    #[error("database error: {0}")]
+    Database(#[from] DatabaseError),
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// Resolve a project's absolute root path from its UUID.
+pub fn project_root_for(conn: &Connection, project_id: &str) -> Result<PathBuf, SkillLibraryError> {
+    let uuid = uuid::Uuid::parse_str(project_id)
+        .map_err(|_| SkillLibraryError::InvalidProjectId(project_id.to_string()))?;
+    let project = ProjectStore::new(conn)
+        .get(uuid)?
+        .ok_or_else(|| SkillLibraryError::ProjectNotFound(project_id.to_string()))?;
+    Ok(project.path)
+}
+
+/// The project root a (scope, project id) target needs, if any.
+fn target_project_root(
+    conn: &Connection,
+    scope: Scope,
+    project_id: Option<&str>,
+) -> Result<Option<PathBuf>, SkillLibraryError> {
+    match (scope, project_id) {
+        (Scope::Project, Some(pid)) => Ok(Some(project_root_for(conn, pid)?)),
+        (Scope::Project, None) => Err(SkillLibraryError::ProjectRequired),
+        (Scope::User, _) => Ok(None),
+    }
+}
+
+/// Register a source directory, returning the existing row if the
+/// canonicalized path is already registered.
+pub fn add_source(
+    conn: &Connection,
+    path: &Path,
+    label: Option<&str>,
+) -> Result<SkillSource, SkillLibraryError> {
+    if !path.is_dir() {
+        return Err(SkillLibraryError::NotADirectory(path.to_path_buf()));
+    }
+    let canonical = path.canonicalize()?.display().to_string();
+
+    let store = SkillSourceStore::new(conn);
+    if let Some(existing) = store.get_by_path(&canonical)? {
+        return Ok(existing);
+    }
+    Ok(store.create(&canonical, label)?)
+}
+
+/// Load the registered source directories.
+pub fn source_dirs(conn: &Connection) -> Result<Vec<PathBuf>, SkillLibraryError> {
+    Ok(SkillSourceStore::new(conn)
+        .list()?
+        .into_iter()
+        .map(|s| PathBuf::from(s.path))
+        .collect())
+}
+
+/// Scan every registered source into one catalog, sorted by name.
+pub fn scan_library(conn: &Connection) -> Result<Vec<CatalogSkill>, SkillLibraryError> {
+    Ok(scan_sources(&source_dirs(conn)?))
+}
+
+/// Find a deployable skill by name.
+///
+/// Looks where the matrix lists standalone skills, in the same order: the
+/// library home (`~/.agents/skills`), registered sources, then skills resident
+/// in an agent's own user skills dir. Plugin-provided skills are not searched,
+/// and neither are copies TARS deployed itself.
+pub fn find_library_skill(
+    conn: &Connection,
+    home: &Path,
+    name: &str,
+) -> Result<Option<CatalogSkill>, SkillLibraryError> {
+    let deployed: Vec<PathBuf> = SkillDeploymentStore::new(conn)
+        .list()?
+        .into_iter()
+        .map(|d| PathBuf::from(d.link_path))
+        .collect();
+
+    let mut candidates = scan_external_dir(&external_skills_dir(home));
+    for dir in source_dirs(conn)? {
+        candidates.extend(scan_source(&dir));
+    }
+    candidates.extend(scan_external_dir(&home.join(".claude").join("skills")));
+    candidates.extend(scan_external_dir(&codex_user_skills_dir(home)));
+    Ok(candidates
+        .into_iter()
+        .find(|skill| skill.name == name && !deployed.contains(&skill.source_dir)))
+}
+
+/// A deployment to create.
+#[derive(Debug, Clone)]
+pub struct DeployRequest {
+    pub skill_name: String,
+    pub source_dir: PathBuf,
+    pub agent: Agent,
+    pub scope: Scope,
+    /// Required for project scope.
+    pub project_id: Option<String>,
+    pub link_kind: LinkKind,
+}
+
+/// Deploy a skill to a target and record the deployment.
+///
+/// Any existing symlink at the target (regardless of where it points — e.g. a
+/// hand-made link to a plugin's repo) is adopted instead of colliding;
+/// otherwise a fresh deployment is materialized.
+pub fn deploy_skill(
+    conn: &Connection,
+    request: &DeployRequest,
+    home: &Path,
+) -> Result<SkillDeployment, SkillLibraryError> {
+    let project_root = target_project_root(conn, request.scope, request.project_id.as_deref())?;
+
+    let store = SkillDeploymentStore::new(conn);
+    if store
+        .get_target(
+            request.agent.as_str(),
+            request.scope.as_str(),
+            request.project_id.as_deref(),
+            &request.skill_name,
+        )?
+        .is_some()
+    {
+        return Err(SkillLibraryError::AlreadyDeployed(
+            request.skill_name.clone(),
+        ));
+    }
+
+    let link_path =
+        resolve_skills_dir(request.agent, request.scope, project_root.as_deref(), home)?
+            .join(&request.skill_name);
+
+    let (final_link_path, link_kind, sha256) =
+        if let TargetProbe::Symlink { .. } = probe_target(&link_path) {
+            (link_path, LinkKind::Symlink, None)
+        } else {
+            let result = deploy(
+                &request.source_dir,
+                &request.skill_name,
+                request.agent,
+                request.scope,
+                project_root.as_deref(),
+                request.link_kind,
+                home,
+            )?;
+            (result.link_path, result.link_kind, result.sha256)
+        };
+
+    let record = SkillDeploymentInput {
+        skill_name: request.skill_name.clone(),
+        source_path: request.source_dir.display().to_string(),
+        agent: request.agent.as_str().to_string(),
+        scope: request.scope.as_str().to_string(),
+        project_id: request.project_id.clone(),
+        link_path: final_link_path.display().to_string(),
+        link_kind: link_kind.as_str().to_string(),
+        sha256,
+    };
+    let deployment = store.create(&record)?;
+    if link_kind == LinkKind::Copy {
+        // The deploy-time bundle is the base for classifying later drift.
+        store.set_base_files(deployment.id, &read_bundle(&final_link_path)?)?;
+    }
+    Ok(deployment)
+}
+
+/// Remove a tracked deployment from disk and delete its record.
+///
+/// Returns `false` if there is no such deployment.
+pub fn undeploy_skill(conn: &Connection, id: i64, home: &Path) -> Result<bool, SkillLibraryError> {
+    let store = SkillDeploymentStore::new(conn);
+    let Some(row) = store.get(id)? else {
+        return Ok(false);
+    };
+    let link_kind = LinkKind::from_db_str(&row.link_kind).unwrap_or(LinkKind::Symlink);
+    undeploy(Path::new(&row.link_path), link_kind)?;
+
+    // Clear any lingering mute entry so the agent's settings never reference
+    // a skill that no longer exists (best effort).
+    if row.mute_state.is_some() {
+        if let (Some(agent), Some(scope)) = (
+            Agent::from_db_str(&row.agent),
+            Scope::from_db_str(&row.scope),
+        ) {
+            let project_root = match (scope, row.project_id.as_deref()) {
+                (Scope::Project, Some(pid)) => project_root_for(conn, pid).ok(),
+                _ => None,
+            };
+            let adapter = agent.adapter();
+            if let Some(path) = adapter.mute_settings_path(scope, project_root.as_deref(), home) {
+                let _ = edit_settings(&path, |root| {
+                    adapter.set_mute(root, &row.skill_name, None);
+                });
+            }
+        }
+    }
+
+    Ok(store.delete(id)?)
+}
+
+/// Set (or clear, with `None`/`"on"`) the muting state for a deployment.
+///
+/// Writes the skill's mute entry to the settings file the agent's adapter
+/// names for the deployment's scope and records the state on the row. Only
+/// agents whose adapter supports muting qualify — today just Claude
+/// standalone skills, through `skillOverrides`; Codex has no working
+/// per-project file mute, and plugin skills are muted via
+/// [`set_project_plugin_enabled`] instead.
+pub fn set_skill_mute(
+    conn: &Connection,
+    deployment_id: i64,
+    mute_state: Option<&str>,
+    home: &Path,
+) -> Result<(), SkillLibraryError> {
+    let normalized = match mute_state {
+        None | Some("on" | "") => None,
+        Some(state @ ("name-only" | "user-invocable-only" | "off")) => Some(state),
+        Some(other) => return Err(SkillLibraryError::InvalidMuteState(other.to_string())),
+    };
+
+    let store = SkillDeploymentStore::new(conn);
+    let dep = store
+        .get(deployment_id)?
+        .ok_or(SkillLibraryError::DeploymentNotFound(deployment_id))?;
+    let adapter = Agent::from_db_str(&dep.agent)
+        .map(Agent::adapter)
+        .filter(|adapter| adapter.supports_mute())
+        .ok_or_else(|| SkillLibraryError::MuteUnsupported(dep.agent.clone()))?;
+    let scope = Scope::from_db_str(&dep.scope)
+        .ok_or_else(|| SkillLibraryError::InvalidScope(dep.scope.clone()))?;
+    let project_root = target_project_root(conn, scope, dep.project_id.as_deref())?;
+    let path = adapter
+        .mute_settings_path(scope, project_root.as_deref(), home)
+        .ok_or(SkillLibraryError::ProjectRequired)?;
+    edit_settings(&path, |root| {
+        adapter.set_mute(root, &dep.skill_name, normalized);
+    })?;
+    Ok(store.set_mute_state(deployment_id, normalized)?)
+}
+
+/// Enable or disable a whole plugin for a project via `enabledPlugins` in the
+/// project `.claude/settings.json`. This is the only per-project lever for
+/// plugin-provided skills (Claude ignores `skillOverrides` for them).
+pub fn set_project_plugin_enabled(
+    conn: &Connection,
+    project_id: &str,
+    plugin_key: &str,
+    enabled: bool,
+) -> Result<(), SkillLibraryError> {
+    let root = project_root_for(conn, project_id)?;
+    let path = root.join(".claude").join("settings.json");
+    edit_settings(&path, |settings| {
+        set_enabled_plugin(settings, plugin_key, enabled);
+    })
+}
+
+/// Read a JSON settings object, apply `mutate`, write it back.
+/// A missing or empty file starts from `{}`; unrelated keys are preserved.
+fn edit_settings<F>(path: &Path, mutate: F) -> Result<(), SkillLibraryError>
+where
+    F: FnOnce(&mut Settings),
+{
+    let mut root: Settings = if path.exists() {
+        let text = fs::read_to_string(path)?;
+        if text.trim().is_empty() {
+            serde_json::Map::new()
+        } else {
+            serde_json::from_str(&text).map_err(|source| SkillLibraryError::InvalidSettings {
+                path: path.to_path_buf(),
+                source,
+            })?
+        }
+    } else {
+        serde_json::Map::new()
+    };
+
+    mutate(&mut root);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let serialized =
+        serde_json::to_string_pretty(&serde_json::Value::Object(root)).map_err(|source| {
+            SkillLibraryError::InvalidSettings {
+                path: path.to_path_buf(),
+                source,
+            }
+        })?;
+    fs::write(path, format!("{serialized}\n"))?;
+    Ok(())
+}
+
+/// Set or clear a single plugin's `enabledPlugins` entry.
+fn set_enabled_plugin(root: &mut Settings, key: &str, enabled: bool) {
+    if enabled {
+        // Re-enabling = drop the explicit `false` (back to default-on).
+        if let Some(obj) = root
+            .get_mut("enabledPlugins")
+            .and_then(|v| v.as_object_mut())
+        {
+            obj.remove(key);
+            if obj.is_empty() {
+                root.remove("enabledPlugins");
+            }
+        }
+    } else {
+        let obj = root
+            .entry("enabledPlugins")
+            .or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+        if let Some(map) = obj.as_object_mut() {
+            map.insert(key.to_string(), serde_json::Value::Bool(false));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::db::Database;
+    use crate::Project;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn enabled_plugin_disable_then_reenable() {
+        let mut root = serde_json::Map::new();
+        set_enabled_plugin(&mut root, "pasiv@tars-profiles", false);
+        assert_eq!(
+            root["enabledPlugins"],
+            json!({ "pasiv@tars-profiles": false })
+        );
+
+        // Re-enabling drops the explicit false (back to default-on) and cleans up.
+        set_enabled_plugin(&mut root, "pasiv@tars-profiles", true);
+        assert!(!root.contains_key("enabledPlugins"));
+    }
+
+    #[test]
+    fn edit_settings_roundtrips_via_disk() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join(".claude").join("settings.json");
+
+        // Writing into a missing file creates it from `{}`.
+        edit_settings(&path, |root| {
+            Agent::Claude
+                .adapter()
+                .set_mute(root, "denoise", Some("off"));
+        })
+        .unwrap();
+        let read: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(read["skillOverrides"]["denoise"], json!("off"));
+
+        // A second edit merges, not clobbers.
+        edit_settings(&path, |root| {
+            set_enabled_plugin(root, "x@y", false);
+        })
+        .unwrap();
+        let read: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(read["skillOverrides"]["denoise"], json!("off"));
+        assert_eq!(read["enabledPlugins"]["x@y"], json!(false));
+    }
+
+    #[test]
+    fn deploy_mute_and_undeploy_a_project_skill() {
+        let tmp = TempDir::new().unwrap();
+        let home = tmp.path().join("home");
+        let lib = tmp.path().join("lib");
+        let root = tmp.path().join("repo");
+        fs::create_dir_all(lib.join("review")).unwrap();
+        fs::create_dir_all(&root).unwrap();
+        fs::write(
+            lib.join("review").join("SKILL.md"),
+            "---\nname: review\ndescription: Review code\n---\nbody\n",
+        )
+        .unwrap();
+
+        let db = Database::in_memory().unwrap();
+        let conn = db.connection();
+        let project = Project::new(root.clone());
+        ProjectStore::new(conn).create(&project).unwrap();
+        let source = add_source(conn, &lib, Some("team")).unwrap();
+        // Registering the same directory again returns the existing row.
+        assert_eq!(add_source(conn, &lib, None).unwrap().id, source.id);
+
+        let skill = find_library_skill(conn, &home, "review").unwrap().unwrap();
+        let mut request = DeployRequest {
+            skill_name: skill.name.clone(),
+            source_dir: skill.source_dir.clone(),
+            agent: Agent::Claude,
+            scope: Scope::Project,
+            project_id: None,
+            link_kind: LinkKind::Symlink,
+        };
+        assert!(matches!(
+            deploy_skill(conn, &request, &home),
+            Err(SkillLibraryError::ProjectRequired)
+        ));
+
+        request.project_id = Some(project.id.to_string());
+        let dep = deploy_skill(conn, &request, &home).unwrap();
+        let link = root.join(".claude").join("skills").join("review");
+        assert_eq!(Path::new(&dep.link_path), link);
+        assert!(link.join("SKILL.md").is_file());
+        assert!(matches!(
+            deploy_skill(conn, &request, &home),
+            Err(SkillLibraryError::AlreadyDeployed(_))
+        ));
+
+        let settings = root.join(".claude").join("settings.json");
+        set_skill_mute(conn, dep.id, Some("name-only"), &home).unwrap();
+        let read: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&settings).unwrap()).unwrap();
+        assert_eq!(read["skillOverrides"]["review"], json!("name-only"));
+
+        // Undeploying removes the link, the record and the override.
+        assert!(undeploy_skill(conn, dep.id, &home).unwrap());
+        assert!(probe_target(&link) == TargetProbe::Absent);
+        assert!(lib.join("review").join("SKILL.md").is_file());
+        let read: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&settings).unwrap()).unwrap();
+        assert!(read.get("skillOverrides").is_none());
+        assert!(!undeploy_skill(conn, dep.id, &home).unwrap());
+    }
+
+    #[test]
+    fn deployments_to_new_agents_are_recorded() {
+        use crate::skills::drift::{deployment_drift, resolve_drift, DriftResolution};
+
+        let tmp = TempDir::new().unwrap();
+        let home = tmp.path().join("home");
+        let lib = tmp.path().join("lib");
+        let root = tmp.path().join("repo");
+        fs::create_dir_all(lib.join("review")).unwrap();
+        fs::create_dir_all(&root).unwrap();
+        fs::write(
+            lib.join("review").join("SKILL.md"),
+            "---\nname: review\ndescription: Review code\n---\nbody\n",
+        )
+        .unwrap();
+
+        let db = Database::in_memory().unwrap();
+        let conn = db.connection();
+        let project = Project::new(root.clone());
+        ProjectStore::new(conn).create(&project).unwrap();
+        let mut request = DeployRequest {
+            skill_name: "review".to_string(),
+            source_dir: lib.join("review"),
+            agent: Agent::Gemini,
+            scope: Scope::User,
+            project_id: None,
+            link_kind: LinkKind::Symlink,
+        };
+        let gemini = deploy_skill(conn, &request, &home).unwrap();
+        assert_eq!(gemini.agent, "gemini");
+        assert_eq!(
+            Path::new(&gemini.link_path),
+            home.join(".gemini").join("skills").join("review")
+        );
+        assert!(matches!(
+            set_skill_mute(conn, gemini.id, Some("off"), &home),
+            Err(SkillLibraryError::MuteUnsupported(_))
+        ));
+
+        // Cursor has no user scope, and converts project deploys to a copy.
+        request.agent = Agent::Cursor;
+        assert!(matches!(
+            deploy_skill(conn, &request, &home),
+            Err(SkillLibraryError::Deploy(
+                SkillDeployError::UnsupportedScope { .. }
+            ))
+        ));
+        request.scope = Scope::Project;
+        request.project_id = Some(project.id.to_string());
+        let cursor = deploy_skill(conn, &request, &home).unwrap();
+        assert_eq!(cursor.agent, "cursor");
+        assert_eq!(cursor.link_kind, "copy");
+        let rule = root.join(".cursor/rules/review/review.mdc");
+        assert!(fs::read_to_string(&rule)
+            .unwrap()
+            .starts_with("---\ndescription: \"Review code\"\n"));
+        assert!(!rule.with_file_name("SKILL.md").exists());
+
+        let store = SkillDeploymentStore::new(conn);
+        assert_eq!(store.list().unwrap().len(), 2);
+
+        // Drift compares the copy with the converted source.
+        assert!(deployment_drift(conn, cursor.id).unwrap().files.is_empty());
+        fs::write(&rule, "edited\n").unwrap();
+        assert_eq!(deployment_drift(conn, cursor.id).unwrap().files.len(), 1);
+        let backups = tmp.path().join("backups");
+        assert!(matches!(
+            resolve_drift(conn, cursor.id, DriftResolution::PullBack, &backups),
+            Err(SkillLibraryError::ConvertedDeployment(_))
+        ));
+        resolve_drift(conn, cursor.id, DriftResolution::Overwrite, &backups).unwrap();
+        assert!(deployment_drift(conn, cursor.id).unwrap().files.is_empty());
+        assert!(fs::read_to_string(&rule).unwrap().ends_with("body\n"));
+    }
+}
@@ -0,0 +1,659 @@
+//! The skill matrix: every known skill with its per-agent state at one scope.
+//!
+//! Groups come from the library home (`~/.agents/skills`), installed plugins,
+//! skills resident in an agent's own user skills dir, and registered sources.
+//! Each cell reconciles TARS's deployment records against what is physically
+//! present at the target.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tars_scanner::plugins::{InstalledPlugin, PluginInventory};
+
+use super::deploy::{hash_bundle, repoint_symlink, resolve_skills_dir, Agent, Scope};
+use super::install::external_skills_dir;
+use super::library::{project_root_for, SkillLibraryError};
+use super::scan::{probe_target, scan_external_dir, scan_source, CatalogSkill, TargetProbe};
+use crate::storage::skill_library::{SkillDeployment, SkillDeploymentStore, SkillSourceStore};
+
+/// A skill's on/off state for one agent at the selected scope.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[allow(clippy::struct_excessive_bools)] // a UI cell DTO; each flag is an independent axis
+pub struct SkillCell {
+    /// `"on"` | `"off"` | `"adopted"` | `"collision"` | `"plugin"` | `"resident"`.
+    pub status: String,
+    pub deployed: bool,
+    /// Whether TARS has a deployment record (vs. a hand-made symlink).
+    pub tracked: bool,
+    pub link_kind: Option<String>,
+    pub deployment_id: Option<i64>,
+    pub link_path: String,
+    /// Set when this agent receives the skill from a plugin (status `"plugin"`).
+    pub plugin_id: Option<String>,
+    /// Copy deploy whose on-disk source bundle no longer matches the hash
+    /// captured at deploy time (the deployed copy is stale). Always false for
+    /// symlink deploys — they are the source.
+    pub drifted: bool,
+    /// Muting middle-state: `None`/`"on"` = fully visible; `"name-only"`,
+    /// `"user-invocable-only"`, `"off"` mirror Claude `skillOverrides`.
+    pub mute_state: Option<String>,
+    /// Whether this (agent, scope, kind) can actually be muted on the installed
+    /// agent build. The UI must not render a mute control when this is false.
+    pub mute_supported: bool,
+}
+
+/// One catalog skill with its per-agent state for the selected scope.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillMatrixRow {
+    pub name: String,
+    pub description: String,
+    pub source_dir: String,
+    /// One cell per agent in [`Agent::ALL`].
+    pub cells: BTreeMap<Agent, SkillCell>,
+}
+
+/// A group of skills in the Library: an installed plugin (auto-listed from the
+/// Marketplace) or a registered standalone source directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillGroup {
+    /// `"library"`, `"plugin"`, `"resident"` or `"source"`.
+    pub kind: String,
+    pub label: String,
+    pub plugin_id: Option<String>,
+    /// Marketplace the plugin came from, so the frontend can form the
+    /// `id@marketplace` key used by `enabledPlugins` (plugin groups only).
+    pub plugin_marketplace: Option<String>,
+    /// Whether this plugin is disabled for the current project scope via
+    /// `enabledPlugins` in the project settings (plugin groups only).
+    pub plugin_disabled_here: bool,
+    pub source_root: Option<String>,
+    /// True when the source directory itself is a single skill bundle (its
+    /// `SKILL.md` is at the root), rather than a folder that contains skills.
+    pub single_skill: bool,
+    pub skills: Vec<SkillMatrixRow>,
+}
+
+/// The skills an installed, enabled plugin provides.
+#[derive(Debug, Clone)]
+pub struct PluginSkills {
+    pub id: String,
+    pub marketplace: Option<String>,
+    pub label: String,
+    pub skills: Vec<CatalogSkill>,
+}
+
+/// What the matrix needs to know about the machine beyond the database.
+#[derive(Debug, Clone)]
+pub struct MatrixEnv {
+    /// The user's home directory (injected for tests).
+    pub home: PathBuf,
+    /// Installed, enabled plugins that provide skills.
+    pub plugins: Vec<PluginSkills>,
+    /// Whether the installed Claude Code honors `skillOverrides`.
+    pub claude_mute_supported: bool,
+}
+
+impl MatrixEnv {
+    /// Detect installed plugins and the Claude Code version for `home`.
+    pub fn detect(home: PathBuf) -> Result<Self, SkillLibraryError> {
+        Ok(Self {
+            home,
+            plugins: installed_plugin_skills()?,
+            claude_mute_supported: claude_supports_skill_overrides(),
+        })
+    }
+}
+
+/// The skills directory an installed plugin declares (default `skills/`).
+pub fn plugin_skills_root(plugin: &InstalledPlugin) -> PathBuf {
+    plugin.manifest.skills.as_ref().map_or_else(
+        || plugin.path.join("skills"),
+        |path| {
+            if path.is_absolute() {
+                return path.clone();
+            }
+            let relative = path.to_string_lossy();
+            plugin.path.join(relative.trim_start_matches("./"))
+        },
+    )
+}
+
+/// Scan installed, enabled plugins for the skills they provide.
+pub fn installed_plugin_skills() -> Result<Vec<PluginSkills>, SkillLibraryError> {
+    let inventory =
+        PluginInventory::scan().map_err(|e| SkillLibraryError::PluginScan(e.to_string()))?;
+    let mut out = Vec::new();
+    for plugin in inventory.installed {
+        if !plugin.enabled {
+            continue;
+        }
+        let skills = scan_source(&plugin_skills_root(&plugin));
+        if skills.is_empty() {
+            continue;
+        }
+        let label = if plugin.manifest.name.is_empty() {
+            plugin.id.clone()
+        } else {
+            plugin.manifest.name.clone()
+        };
+        out.push(PluginSkills {
+            id: plugin.id,
+            marketplace: plugin.marketplace,
+            label,
+            skills,
+        });
+    }
+    Ok(out)
+}
+
+/// Build the skill matrix for a project (or user scope when `project_id` is
+/// `None`).
+///
+/// Plugin-sourced symlinks orphaned by a plugin version bump are repaired on
+/// the way, so the matrix never reports a dangling link as deployed.
+pub fn skill_matrix(
+    conn: &Connection,
+    project_id: Option<&str>,
+    env: &MatrixEnv,
+) -> Result<Vec<SkillGroup>, SkillLibraryError> {
+    let home = env.home.as_path();
+    let scope = if project_id.is_some() {
+        Scope::Project
+    } else {
+        Scope::User
+    };
+
+    // The Claude column always badges a skill a plugin already provides (even
+    // one that also exists in a standalone source).
+    let mut claude_plugin_by_name: HashMap<String, String> = HashMap::new();
+    // skill name -> the plugin's CURRENT skills-root source dir, used to repair
+    // Codex symlinks that still point at a superseded version-pinned cache dir.
+    let mut plugin_skill_source: HashMap<String, PathBuf> = HashMap::new();
+    for plugin in &env.plugins {
+        for skill in &plugin.skills {
+            claude_plugin_by_name
+                .entry(skill.name.clone())
+                .or_insert_with(|| plugin.id.clone());
+            plugin_skill_source
+                .entry(skill.name.clone())
+                .or_insert_with(|| skill.source_dir.clone());
+        }
+    }
+
+    let sources = SkillSourceStore::new(conn).list()?;
+    let project_root = match project_id {
+        Some(pid) => Some(project_root_for(conn, pid)?),
+        None => None,
+    };
+    let store = SkillDeploymentStore::new(conn);
+    let mut deployments = match project_id {
+        Some(pid) => store.list_for_project(pid)?,
+        None => store.list_user_scope()?,
+    };
+    repair_plugin_deployments(&store, &mut deployments, &plugin_skill_source)?;
+
+    // Plugins disabled for this project via `enabledPlugins` (project scope only).
+    let disabled_plugins = match project_root.as_deref() {
+        Some(root) => read_disabled_plugins(&root.join(".claude").join("settings.json")),
+        None => HashSet::new(),
+    };
+
+    let build_row = |skill: &CatalogSkill| -> SkillMatrixRow {
+        let cells = Agent::ALL
+            .iter()
+            .map(|&agent| {
+                // Plugins are Claude's, so only its column badges them.
+                let plugin = (agent == Agent::Claude)
+                    .then(|| claude_plugin_by_name.get(&skill.name))
+                    .flatten();
+                // Muting is offered only where the adapter can write it and,
+                // for Claude, where the installed build honors it.
+                let mute_supported = agent.adapter().supports_mute()
+                    && (agent != Agent::Claude || env.claude_mute_supported);
+                let cell = match plugin {
+                    Some(pid) => plugin_cell(pid),
+                    None => cell_for(
+                        agent,
+                        scope,
+                        project_root.as_deref(),
+                        home,
+                        skill,
+                        &deployments,
+                        mute_supported,
+                    ),
+                };
+                (agent, cell)
+            })
+            .collect();
+        SkillMatrixRow {
+            name: skill.name.clone(),
+            description: skill.description.clone(),
+            source_dir: skill.source_dir.display().to_string(),
+            cells,
+        }
+    };
+    let group =
+        |kind: &str, label: String, source_root: Option<String>, skills: &[CatalogSkill]| {
+            SkillGroup {
+                kind: kind.to_string(),
+                label,
+                plugin_id: None,
+                plugin_marketplace: None,
+                plugin_disabled_here: false,
+                source_root,
+                single_skill: false,
+                skills: skills.iter().map(&build_row).collect(),
+            }
+        };
+
+    let mut groups: Vec<SkillGroup> = Vec::new();
+
+    // The library home (`~/.agents/skills` — where Add skill, `npx skills
+    // add`, and adoption land) leads the list, unless the user registered
+    // that directory as a source themselves. Symlinked entries are deploys
+    // pointing back into a library, not residents, and are skipped.
+    let external_dir = external_skills_dir(home);
+    let external_path = external_dir.display().to_string();
+    if !sources.iter().any(|s| s.path == external_path) {
+        let skills = scan_external_dir(&external_dir);
+        if !skills.is_empty() {
+            groups.push(group(
+                "library",
+                "Library (~/.agents/skills)".to_string(),
+                Some(external_path),
+                &skills,
+            ));
+        }
+    }
+
+    // Plugin groups next (auto, from the Marketplace).
+    for plugin in &env.plugins {
+        let key = plugin_key(&plugin.id, plugin.marketplace.as_deref());
+        groups.push(SkillGroup {
+            plugin_id: Some(plugin.id.clone()),
+            plugin_marketplace: plugin.marketplace.clone(),
+            plugin_disabled_here: disabled_plugins.contains(&key),
+            ..group("plugin", plugin.label.clone(), None, &plugin.skills)
+        });
+    }
+
+    // Skills that physically live in an agent's own user skills dir
+    // (hand-placed, pre-library). An agent's dir can resolve to the external
+    // dir itself, which is already listed above, or be shared with another
+    // agent. Symlinked entries are deploys, not residents, and are skipped by
+    // the scan.
+    let mut resident_dirs: Vec<(&str, PathBuf)> = Vec::new();
+    for &agent in Agent::ALL {
+        let adapter = agent.adapter();
+        let Some(dir) = adapter.user_dir(home) else {
+            continue;
+        };
+        if dir != external_dir && !resident_dirs.iter().any(|(_, d)| *d == dir) {
+            resident_dirs.push((adapter.label(), dir));
+        }
+    }
+    for (agent_label, dir) in resident_dirs {
+        let dir_path = dir.display().to_string();
+        if sources.iter().any(|s| s.path == dir_path) {
+            continue;
+        }
+        let skills = scan_external_dir(&dir);
+        if skills.is_empty() {
+            continue;
+        }
+        groups.push(group(
+            "resident",
+            format!("{agent_label} (resident)"),
+            Some(dir_path),
+            &skills,
+        ));
+    }
+
+    // Standalone source groups.
+    for source in &sources {
+        let dir = PathBuf::from(&source.path);
+        let skills = scan_source(&dir);
+        if skills.is_empty() {
+            continue;
+        }
+        let label = source
+            .label
+            .clone()
+            .unwrap_or_else(|| short_path(&source.path));
+        groups.push(SkillGroup {
+            // The source itself is a skill when its SKILL.md is at the root.
+            single_skill: dir.join("SKILL.md").is_file(),
+            ..group("source", label, Some(source.path.clone()), &skills)
+        });
+    }
+
+    Ok(groups)
+}
+
+/// A Claude cell for a skill provided by an installed plugin.
+fn plugin_cell(plugin_id: &str) -> SkillCell {
+    SkillCell {
+        status: "plugin".to_string(),
+        plugin_id: Some(plugin_id.to_string()),
+        ..off_cell(String::new())
+    }
+}
+
+fn cell_for(
+    agent: Agent,
+    scope: Scope,
+    project_root: Option<&Path>,
+    home: &Path,
+    skill: &CatalogSkill,
+    deployments: &[SkillDeployment],
+    mute_supported: bool,
+) -> SkillCell {
+    // A tracked deployment row wins outright.
+    if let Some(dep) = deployments
+        .iter()
+        .find(|d| d.agent == agent.as_str() && d.skill_name == skill.name)
+    {
+        // A copy deploy drifts when either the source bundle or the deployed
+        // copy changed since deploy.
+        let drifted = dep.link_kind == "copy"
+            && dep.sha256.as_deref().is_some_and(|stored| {
+                [&dep.source_path, &dep.link_path].into_iter().any(|dir| {
+                    hash_bundle(Path::new(dir)).map_or(true, |current| current != stored)
+                })
+            });
+        return SkillCell {
+            status: "on".to_string(),
+            deployed: true,
+            tracked: true,
+            link_kind: Some(dep.link_kind.clone()),
+            deployment_id: Some(dep.id),
+            link_path: dep.link_path.clone(),
+            plugin_id: None,
+            drifted,
+            mute_state: dep.mute_state.clone(),
+            mute_supported,
+        };
+    }
+
+    // No record: adopt any symlink at the target (by name), else off/collision.
+    let Ok(dir) = resolve_skills_dir(agent, scope, project_root, home) else {
+        return off_cell(String::new());
+    };
+    let link_path = dir.join(&skill.name);
+    let link_str = link_path.display().to_string();
+
+    // The skill physically lives at the target itself (its bundle IS the
+    // entry in this agent's skills dir) — it is on by residence, not by
+    // deployment, and can't be toggled off without deleting it.
+    if link_path == skill.source_dir {
+        return SkillCell {
+            status: "resident".to_string(),
+            deployed: true,
+            ..off_cell(link_str)
+        };
+    }
+
+    match probe_target(&link_path) {
+        TargetProbe::Symlink { .. } => SkillCell {
+            status: "adopted".to_string(),
+            deployed: true,
+            link_kind: Some("symlink".to_string()),
+            ..off_cell(link_str)
+        },
+        TargetProbe::Absent => off_cell(link_str),
+        _ => SkillCell {
+            status: "collision".to_string(),
+            ..off_cell(link_str)
+        },
+    }
+}
+
+fn off_cell(link_path: String) -> SkillCell {
+    SkillCell {
+        status: "off".to_string(),
+        deployed: false,
+        tracked: false,
+        link_kind: None,
+        deployment_id: None,
+        link_path,
+        plugin_id: None,
+        drifted: false,
+        mute_state: None,
+        mute_supported: false,
+    }
+}
+
+/// Last two path segments, for a compact source label.
+fn short_path(path: &str) -> String {
+    let parts: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    if parts.len() <= 2 {
+        path.to_string()
+    } else {
+        format!("…/{}", parts[parts.len() - 2..].join("/"))
+    }
+}
+
+/// Form the `id@marketplace` key `enabledPlugins` uses (bare id if no marketplace).
+fn plugin_key(id: &str, marketplace: Option<&str>) -> String {
+    match marketplace {
+        Some(mp) if !mp.is_empty() => format!("{id}@{mp}"),
+        _ => id.to_string(),
+    }
+}
+
+/// True if the installed Claude Code honors per-skill `skillOverrides` in
+/// user/project settings. This was a silent no-op until it was fixed in
+/// 2.1.129, so below that version we must not offer a mute control.
+pub fn claude_supports_skill_overrides() -> bool {
+    let Ok(output) = std::process::Command::new("claude")
+        .arg("--version")
+        .output()
+    else {
+        return false;
+    };
+    if !output.status.success() {
+        return false;
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    parse_semver(&text).is_some_and(|v| v >= (2, 1, 129))
+}
+
+/// Extract the first `major.minor.patch` triple from a version string,
+/// tolerating a leading `v` (e.g. `v1.2.3`).
+fn parse_semver(text: &str) -> Option<(u32, u32, u32)> {
+    let token = text.split_whitespace().find_map(|t| {
+        let t = t.strip_prefix('v').unwrap_or(t);
+        (t.split('.').count() >= 3 && t.starts_with(|c: char| c.is_ascii_digit())).then_some(t)
+    })?;
+    let mut parts = token.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    // The patch field may carry a trailing suffix (e.g. build metadata).
+    let patch: u32 = parts
+        .next()?
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect::<String>()
+        .parse()
+        .ok()?;
+    Some((major, minor, patch))
+}
+
+/// True if `path` lives under the Claude plugin cache (version-pinned dirs).
+fn is_plugin_cache_path(path: &str) -> bool {
+    path.replace('\\', "/").contains("/plugins/cache/")
+}
+
+/// Repoint plugin-sourced symlinks orphaned by a plugin version bump.
+///
+/// A plugin's skills live under a version-pinned cache dir; when the plugin
+/// updates, the old dir disappears and any deployed symlink dangles. For each
+/// tracked symlink deployment whose recorded source is under the plugin cache
+/// and either dangles or points at a superseded version, recreate the link
+/// against the plugin's current skills dir and update the row. Only ever
+/// touches symlinks (never real directories).
+fn repair_plugin_deployments(
+    store: &SkillDeploymentStore,
+    deployments: &mut [SkillDeployment],
+    plugin_skill_source: &HashMap<String, PathBuf>,
+) -> Result<(), SkillLibraryError> {
+    for dep in deployments.iter_mut() {
+        if dep.link_kind != "symlink" || !is_plugin_cache_path(&dep.source_path) {
+            continue;
+        }
+        // Only repair skills a currently-installed plugin still provides.
+        let Some(current) = plugin_skill_source.get(&dep.skill_name) else {
+            continue;
+        };
+        let current_str = current.display().to_string();
+        let link_resolves = Path::new(&dep.link_path).exists();
+        if current_str == dep.source_path && link_resolves {
+            continue;
+        }
+        repoint_symlink(current, Path::new(&dep.link_path))?;
+        store.update_source_path(dep.id, &current_str)?;
+        dep.source_path = current_str;
+    }
+    Ok(())
+}
+
+/// Plugin keys (`id@marketplace`) explicitly disabled via
+/// `enabledPlugins: { key: false }` in a settings file.
+fn read_disabled_plugins(settings_path: &Path) -> HashSet<String> {
+    let mut out = HashSet::new();
+    let Ok(text) = std::fs::read_to_string(settings_path) else {
+        return out;
+    };
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(&text) else {
+        return out;
+    };
+    if let Some(map) = value.get("enabledPlugins").and_then(|v| v.as_object()) {
+        for (key, enabled) in map {
+            if enabled.as_bool() == Some(false) {
+                out.insert(key.clone());
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::skills::library::{add_source, deploy_skill, DeployRequest};
+    use crate::skills::LinkKind;
+    use crate::storage::db::Database;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn semver_gate_matches_2_1_129_fix() {
+        assert_eq!(parse_semver("2.1.201 (Claude Code)"), Some((2, 1, 201)));
+        assert_eq!(parse_semver("v0.142.5"), Some((0, 142, 5)));
+        // The mute fix landed in 2.1.129.
+        assert!(parse_semver("2.1.201 (Claude Code)").unwrap() >= (2, 1, 129));
+        assert!(parse_semver("2.1.129").unwrap() >= (2, 1, 129));
+        assert!(parse_semver("2.1.128").unwrap() < (2, 1, 129));
+        assert!(parse_semver("2.0.999").unwrap() < (2, 1, 129));
+        assert!(parse_semver("3.0.0").unwrap() >= (2, 1, 129));
+    }
+
+    #[test]
+    fn detects_plugin_cache_paths() {
+        assert!(is_plugin_cache_path(
+            "/Users/j/.claude/plugins/cache/mkt/pasiv/1.2.0/skills/kick"
+        ));
+        assert!(!is_plugin_cache_path("/Users/j/skills-lib/deep-research"));
+    }
+
+    #[test]
+    fn matrix_reports_tracked_and_plugin_cells() {
+        let tmp = TempDir::new().unwrap();
+        let home = tmp.path().join("home");
+        let lib = tmp.path().join("lib");
+        for name in ["lint", "review"] {
+            fs::create_dir_all(lib.join(name)).unwrap();
+            fs::write(
+                lib.join(name).join("SKILL.md"),
+                format!("---\nname: {name}\ndescription: {name} skill\n---\nbody\n"),
+            )
+            .unwrap();
+        }
+
+        let db = Database::in_memory().unwrap();
+        let conn = db.connection();
+        add_source(conn, &lib, Some("team")).unwrap();
+        let review = lib.canonicalize().unwrap().join("review");
+        deploy_skill(
+            conn,
+            &DeployRequest {
+                skill_name: "review".to_string(),
+                source_dir: review.clone(),
+                agent: Agent::Claude,
+                scope: Scope::User,
+                project_id: None,
+                link_kind: LinkKind::Symlink,
+            },
+            &home,
+        )
+        .unwrap();
+        deploy_skill(
+            conn,
+            &DeployRequest {
+                skill_name: "review".to_string(),
+                source_dir: review.clone(),
+                agent: Agent::Gemini,
+                scope: Scope::User,
+                project_id: None,
+                link_kind: LinkKind::Symlink,
+            },
+            &home,
+        )
+        .unwrap();
+
+        let env = MatrixEnv {
+            home: home.clone(),
+            plugins: vec![PluginSkills {
+                id: "linter".to_string(),
+                marketplace: Some("tools".to_string()),
+                label: "Linter".to_string(),
+                skills: scan_source(&lib.join("lint")),
+            }],
+            claude_mute_supported: true,
+        };
+        let groups = skill_matrix(conn, None, &env).unwrap();
+        let kinds: Vec<_> = groups.iter().map(|g| g.kind.as_str()).collect();
+        assert_eq!(kinds, ["plugin", "source"]);
+
+        let source = &groups[1];
+        assert_eq!(source.label, "team");
+        let lint = &source.skills[0];
+        assert_eq!(
+            lint.cells.keys().copied().collect::<Vec<_>>(),
+            Agent::ALL.to_vec()
+        );
+        assert_eq!(lint.cells[&Agent::Claude].status, "plugin");
+        assert_eq!(
+            lint.cells[&Agent::Claude].plugin_id.as_deref(),
+            Some("linter")
+        );
+        assert_eq!(lint.cells[&Agent::Codex].status, "off");
+
+        let review_row = &source.skills[1];
+        let claude = &review_row.cells[&Agent::Claude];
+        assert_eq!(claude.status, "on");
+        assert!(claude.tracked && claude.mute_supported);
+        assert_eq!(review_row.cells[&Agent::Codex].status, "off");
+        assert!(!review_row.cells[&Agent::Codex].mute_supported);
+        let gemini = &review_row.cells[&Agent::Gemini];
+        assert_eq!(gemini.status, "on");
+        assert!(gemini.tracked && !gemini.mute_supported);
+        // Cursor has no user scope to deploy to.
+        assert_eq!(review_row.cells[&Agent::Cursor].link_path, "");
+    }
+}
@@ -6,18 +6,42 @@
 //! an agent's skills directory. Keeping the two separate means the engine is
 //! pure filesystem work and can be exercised with tempdirs.
 
+pub mod adapter;
 pub mod deploy;
+pub mod drift;
 pub mod install;
+pub mod library;
+pub mod matrix;
+pub mod origin;
 pub mod scan;
 
+pub use adapter::{adapter_for, agent_infos, AgentAdapter, AgentInfo, Settings, Transformer};
 pub use deploy::{
-    codex_user_skills_dir, deploy, hash_bundle, repoint_symlink, resolve_skills_dir, resync_copy,
-    undeploy, Agent, DeployResult, LinkKind, Scope, SkillDeployError,
+    codex_user_skills_dir, deploy, hash_bundle, read_bundle, render_bundle, repoint_symlink,
+    resolve_skills_dir, undeploy, Agent, BundleFiles, DeployResult, LinkKind, Scope,
+    SkillDeployError,
+};
+pub use drift::{
+    classify_drift, deployment_drift, resolve_drift, DeploymentDrift, DriftReport, DriftResolution,
+    FileDrift, FileDriftKind,
 };
 pub use install::{
     adopt_resident_skill, external_skills_dir, find_skill_bundles, install_bundles,
     parse_git_skill_url, GitSkillSource, SkillInstallError, SkillInstallReport,
 };
+pub use library::{
+    add_source, deploy_skill, find_library_skill, project_root_for, scan_library,
+    set_project_plugin_enabled, set_skill_mute, source_dirs, undeploy_skill, DeployRequest,
+    SkillLibraryError,
+};
+pub use matrix::{
+    claude_supports_skill_overrides, installed_plugin_skills, plugin_skills_root, skill_matrix,
+    MatrixEnv, PluginSkills, SkillCell, SkillGroup, SkillMatrixRow,
+};
+pub use origin::{
+    apply_skill_update, check_skill_updates, install_from_git, prepare_skill_update, short_sha,
+    SkillUpdate, SkillUpdateCheck, SkillUpdatePreview, UpdateStatus, UpdateTarget,
+};
 pub use scan::{
     probe_target, scan_external_dir, scan_source, scan_sources, symlink_points_to, CatalogSkill,
     TargetProbe,
@@ -0,0 +1,541 @@
+//! Git origins of installed skills: clone-and-install, upstream checks, and
+//! moving an install to another commit.
+//!
+//! Installing from git records, per bundle, the repository URL, the ref it
+//! follows, the resolved commit and the bundle's path in the repository
+//! ([`SkillOriginStore`]). Checking for updates asks the remote for the ref's
+//! current commit with `git ls-remote`, without cloning; updating clones the
+//! target commit, previews the change as a diff, then replaces the installed
+//! bundle. A pinned origin stays on its commit until moved explicitly. All git
+//! work goes through the `git` CLI.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tempfile::TempDir;
+
+use super::deploy::{copy_dir, read_bundle};
+use super::install::{
+    bundle_name, find_skill_bundles, install_bundles, GitSkillSource, SkillInstallError,
+    SkillInstallReport,
+};
+use crate::diff::plan::generate_text_diff;
+use crate::git::git_in;
+use crate::storage::skill_library::{SkillOrigin, SkillOriginInput, SkillOriginStore};
+
+/// Upstream state of an installed skill's origin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateStatus {
+    UpToDate,
+    Outdated,
+    /// Pinned to a commit; upstream is reported but not followed.
+    Pinned,
+    /// The remote or ref could not be reached.
+    Unreachable,
+}
+
+/// Result of checking one origin against its remote.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillUpdateCheck {
+    pub origin: SkillOrigin,
+    /// Current commit of the followed ref, when the remote answered.
+    pub latest_sha: Option<String>,
+    pub status: UpdateStatus,
+    pub error: Option<String>,
+}
+
+/// Which commit to move an installed skill to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpdateTarget {
+    /// The newest commit on the recorded ref. Refused for a pinned skill.
+    Latest,
+    /// The newest commit on the recorded ref, dropping the pin.
+    Unpin,
+    /// An exact commit (full or abbreviated SHA), pinned.
+    Pin(String),
+}
+
+/// What an update would change, for review before it is applied.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillUpdatePreview {
+    pub skill_name: String,
+    pub install_path: String,
+    pub from_sha: String,
+    pub to_sha: String,
+    pub pinned: bool,
+    /// Unified-style diff from the installed bundle to the target commit's.
+    /// Empty when the bundle content is unchanged.
+    pub diff: String,
+}
+
+/// A prepared update: the target commit is checked out in a temporary
+/// directory until [`apply_skill_update`] copies it into place.
+#[derive(Debug)]
+pub struct SkillUpdate {
+    pub preview: SkillUpdatePreview,
+    origin_id: i64,
+    bundle_dir: PathBuf,
+    _checkout: TempDir,
+}
+
+/// Clone `source` and install every bundle found into `dest_root`, recording
+/// each installed bundle's origin.
+///
+/// With `pin`, the given commit is checked out and the origins are pinned to
+/// it; otherwise the tip of `source.reference` (or the remote default branch)
+/// is installed and followed.
+pub fn install_from_git(
+    conn: &Connection,
+    source: &GitSkillSource,
+    pin: Option<&str>,
+    dest_root: &Path,
+) -> Result<SkillInstallReport, SkillInstallError> {
+    let tmp = TempDir::new()?;
+    let checkout = tmp.path().join("repo");
+    let commit_sha = checkout_commit(
+        &source.repo_url,
+        source.reference.as_deref(),
+        pin,
+        &checkout,
+    )?;
+
+    let root = match &source.subpath {
+        Some(sub) => checkout.join(sub),
+        None => checkout.clone(),
+    };
+    if !root.is_dir() {
+        return Err(SkillInstallError::SubpathNotFound(
+            source.subpath.clone().unwrap_or_default(),
+        ));
+    }
+
+    let report = install_bundles(&root, dest_root)?;
+    let store = SkillOriginStore::new(conn);
+    for bundle in find_skill_bundles(&root) {
+        let name = bundle_name(&bundle)?;
+        if !report.installed.contains(&name) {
+            continue;
+        }
+        let subpath = bundle
+            .strip_prefix(&checkout)
+            .unwrap_or(&bundle)
+            .to_string_lossy()
+            .replace('\\', "/");
+        store.upsert(&SkillOriginInput {
+            install_path: dest_root.join(&name).display().to_string(),
+            skill_name: name,
+            repo_url: source.repo_url.clone(),
+            git_ref: source.reference.clone(),
+            commit_sha: commit_sha.clone(),
+            subpath,
+            pinned: pin.is_some(),
+        })?;
+    }
+    Ok(report)
+}
+
+/// Compare every recorded origin with its remote's current commit.
+///
+/// Origins whose install directory is gone are skipped. Each remote and ref
+/// is asked once, however many skills share it.
+pub fn check_skill_updates(conn: &Connection) -> Result<Vec<SkillUpdateCheck>, SkillInstallError> {
+    let mut heads: HashMap<(String, Option<String>), Result<String, String>> = HashMap::new();
+    let mut checks = Vec::new();
+    for origin in SkillOriginStore::new(conn).list()? {
+        if !Path::new(&origin.install_path).is_dir() {
+            continue;
+        }
+        let head = heads
+            .entry((origin.repo_url.clone(), origin.git_ref.clone()))
+            .or_insert_with(|| {
+                remote_head(&origin.repo_url, origin.git_ref.as_deref()).map_err(|e| e.to_string())
+            })
+            .clone();
+        let check = match head {
+            Ok(latest) => SkillUpdateCheck {
+                status: if origin.pinned {
+                    UpdateStatus::Pinned
+                } else if latest == origin.commit_sha {
+                    UpdateStatus::UpToDate
+                } else {
+                    UpdateStatus::Outdated
+                },
+                latest_sha: Some(latest),
+                error: None,
+                origin,
+            },
+            Err(error) => SkillUpdateCheck {
+                origin,
+                latest_sha: None,
+                status: UpdateStatus::Unreachable,
+                error: Some(error),
+            },
+        };
+        checks.push(check);
+    }
+    Ok(checks)
+}
+
+/// Check out the commit an installed skill should move to and diff it
+/// against the installed bundle. Nothing on disk changes until the update is
+/// passed to [`apply_skill_update`].
+pub fn prepare_skill_update(
+    conn: &Connection,
+    skill_name: &str,
+    target: &UpdateTarget,
+) -> Result<SkillUpdate, SkillInstallError> {
+    let origin = SkillOriginStore::new(conn)
+        .list_by_name(skill_name)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| SkillInstallError::NotFromGit(skill_name.to_string()))?;
+    let pin = match target {
+        UpdateTarget::Latest if origin.pinned => {
+            return Err(SkillInstallError::Pinned {
+                name: origin.skill_name,
+                sha: short_sha(&origin.commit_sha).to_string(),
+            })
+        }
+        UpdateTarget::Latest | UpdateTarget::Unpin => None,
+        UpdateTarget::Pin(sha) => Some(sha.as_str()),
+    };
+
+    let tmp = TempDir::new()?;
+    let checkout = tmp.path().join("repo");
+    let to_sha = checkout_commit(&origin.repo_url, origin.git_ref.as_deref(), pin, &checkout)?;
+    let bundle_dir = checkout.join(&origin.subpath);
+    if !bundle_dir.join("SKILL.md").is_file() {
+        return Err(SkillInstallError::SubpathNotFound(origin.subpath));
+    }
+
+    let installed = read_bundle(Path::new(&origin.install_path))?;
+    let upstream = read_bundle(&bundle_dir)?;
+    Ok(SkillUpdate {
+        preview: SkillUpdatePreview {
+            skill_name: origin.skill_name,
+            install_path: origin.install_path,
+            from_sha: origin.commit_sha,
+            to_sha,
+            pinned: pin.is_some(),
+            diff: bundle_diff(&installed, &upstream),
+        },
+        origin_id: origin.id,
+        bundle_dir,
+        _checkout: tmp,
+    })
+}
+
+/// Replace the installed bundle with the prepared commit's and record it.
+///
+/// The new bundle is staged next to the installed one and renamed into
+/// place, so a failed copy leaves the installed bundle untouched.
+pub fn apply_skill_update(
+    conn: &Connection,
+    update: SkillUpdate,
+) -> Result<SkillUpdatePreview, SkillInstallError> {
+    let install_path = Path::new(&update.preview.install_path);
+    let parent = install_path.parent().unwrap_or_else(|| Path::new("."));
+    fs::create_dir_all(parent)?;
+    let staging = tempfile::Builder::new()
+        .prefix(".tars-update-")
+        .tempdir_in(parent)?;
+    let staged = staging.path().join("bundle");
+    copy_dir(&update.bundle_dir, &staged)?;
+    let previous = staging.path().join("previous");
+    if install_path.is_dir() {
+        fs::rename(install_path, &previous)?;
+    }
+    if let Err(e) = fs::rename(&staged, install_path) {
+        if previous.is_dir() {
+            let _ = fs::rename(&previous, install_path);
+        }
+        return Err(e.into());
+    }
+    SkillOriginStore::new(conn).update_commit(
+        update.origin_id,
+        &update.preview.to_sha,
+        update.preview.pinned,
+    )?;
+    Ok(update.preview)
+}
+
+/// The first seven characters of a commit SHA, for display.
+#[must_use]
+pub fn short_sha(sha: &str) -> &str {
+    sha.get(..7).unwrap_or(sha)
+}
+
+/// Clone `repo_url` into `dest` at `pin`, or at the tip of `reference` (the
+/// remote default branch when `None`), returning the full commit SHA.
+fn checkout_commit(
+    repo_url: &str,
+    reference: Option<&str>,
+    pin: Option<&str>,
+    dest: &Path,
+) -> Result<String, SkillInstallError> {
+    let dest_str = dest.to_string_lossy();
+    let Some(pin) = pin else {
+        let mut args = vec!["clone", "--depth", "1", "--quiet"];
+        if let Some(reference) = reference {
+            args.extend(["--branch", reference]);
+        }
+        args.extend(["--", repo_url, &dest_str]);
+        git(None, &args)?;
+        return git(Some(dest), &["rev-parse", "HEAD"]);
+    };
+    if pin.len() < 4 || pin.len() > 40 || !pin.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(SkillInstallError::InvalidCommit(pin.to_string()));
+    }
+    // A pinned commit need not be on any ref's tip, so take full history.
+    git(None, &["clone", "--quiet", "--", repo_url, &dest_str])?;
+    let sha = git(
+        Some(dest),
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{pin}^{{commit}}"),
+        ],
+    )
+    .map_err(|_| SkillInstallError::InvalidCommit(pin.to_string()))?;
+    git(Some(dest), &["checkout", "--quiet", "--detach", &sha])?;
+    Ok(sha)
+}
+
+/// The commit `reference` (or the default branch) points at on the remote.
+///
+/// A branch wins over a same-named tag, as with `git clone --branch`, and
+/// annotated tags resolve to the commit they tag.
+fn remote_head(repo_url: &str, reference: Option<&str>) -> Result<String, SkillInstallError> {
+    let patterns = match reference {
+        Some(r) => vec![
+            format!("refs/heads/{r}"),
+            format!("refs/tags/{r}^{{}}"),
+            format!("refs/tags/{r}"),
+        ],
+        None => vec!["HEAD".to_string()],
+    };
+    let mut args = vec!["ls-remote", "--", repo_url];
+    args.extend(patterns.iter().map(String::as_str));
+    let output = git(None, &args)?;
+    let refs: HashMap<&str, &str> = output
+        .lines()
+        .filter_map(|line| {
+            let (sha, name) = line.split_once('\t')?;
+            Some((name, sha))
+        })
+        .collect();
+    patterns
+        .iter()
+        .find_map(|p| refs.get(p.as_str()))
+        .map(|sha| (*sha).to_string())
+        .ok_or_else(|| {
+            SkillInstallError::Git(format!(
+                "ref not found on {repo_url}: {}",
+                reference.unwrap_or("HEAD")
+            ))
+        })
+}
+
+/// Diff two bundles file by file, with `--- a/path` / `+++ b/path` headers.
+fn bundle_diff(old: &BTreeMap<String, Vec<u8>>, new: &BTreeMap<String, Vec<u8>>) -> String {
+    let paths: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    let mut out = String::new();
+    for path in paths {
+        let before = old.get(path);
+        let after = new.get(path);
+        if before == after {
+            continue;
+        }
+        let _ = writeln!(
+            out,
+            "--- {}\n+++ {}",
+            before.map_or("/dev/null".to_string(), |_| format!("a/{path}")),
+            after.map_or("/dev/null".to_string(), |_| format!("b/{path}"))
+        );
+        let texts = (
+            std::str::from_utf8(before.map_or(&[][..], Vec::as_slice)),
+            std::str::from_utf8(after.map_or(&[][..], Vec::as_slice)),
+        );
+        match texts {
+            (Ok(before), Ok(after)) => out.push_str(&generate_text_diff(before, after)),
+            _ => out.push_str("Binary files differ\n"),
+        }
+    }
+    out
+}
+
+fn git(dir: Option<&Path>, args: &[&str]) -> Result<String, SkillInstallError> {
+    git_in(dir, args).map_err(SkillInstallError::Git)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::migrations::run_migrations;
+
+    fn conn() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "foreign_keys", "ON").unwrap();
+        run_migrations(&conn).unwrap();
+        conn
+    }
+
+    /// A bare repository plus a work tree that pushes to it.
+    struct Upstream {
+        bare: PathBuf,
+        work: PathBuf,
+    }
+
+    impl Upstream {
+        fn new(root: &Path) -> Self {
+            let bare = root.join("skills.git");
+            let work = root.join("work");
+            git(
+                None,
+                &["init", "--quiet", "--bare", &bare.to_string_lossy()],
+            )
+            .unwrap();
+            git(
+                None,
+                &[
+                    "clone",
+                    "--quiet",
+                    &bare.to_string_lossy(),
+                    &work.to_string_lossy(),
+                ],
+            )
+            .unwrap();
+            Self { bare, work }
+        }
+
+        fn commit(&self, body: &str) -> String {
+            let dir = self.work.join("skills").join("notes");
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(
+                dir.join("SKILL.md"),
+                format!("---\nname: notes\ndescription: D\n---\n{body}\n"),
+            )
+            .unwrap();
+            git(Some(&self.work), &["add", "-A"]).unwrap();
+            git(
+                Some(&self.work),
+                &[
+                    "-c",
+                    "user.name=T",
+                    "-c",
+                    "user.email=t@example.com",
+                    "commit",
+                    "--quiet",
+                    "-m",
+                    body,
+                ],
+            )
+            .unwrap();
+            git(Some(&self.work), &["push", "--quiet", "origin", "HEAD"]).unwrap();
+            git(Some(&self.work), &["rev-parse", "HEAD"]).unwrap()
+        }
+
+        fn source(&self) -> GitSkillSource {
+            GitSkillSource {
+                repo_url: self.bare.display().to_string(),
+                reference: None,
+                subpath: None,
+            }
+        }
+    }
+
+    fn statuses(conn: &Connection) -> Vec<UpdateStatus> {
+        check_skill_updates(conn)
+            .unwrap()
+            .into_iter()
+            .map(|c| c.status)
+            .collect()
+    }
+
+    #[test]
+    fn install_records_origin_and_update_follows_upstream() {
+        let tmp = TempDir::new().unwrap();
+        let conn = conn();
+        let upstream = Upstream::new(tmp.path());
+        let first = upstream.commit("v1");
+        let dest = tmp.path().join("agents-skills");
+
+        let report = install_from_git(&conn, &upstream.source(), None, &dest).unwrap();
+        assert_eq!(report.installed, vec!["notes"]);
+        let origin = &SkillOriginStore::new(&conn).list_by_name("notes").unwrap()[0];
+        assert_eq!(origin.commit_sha, first);
+        assert_eq!(origin.subpath, "skills/notes");
+        assert!(!origin.pinned);
+        assert_eq!(statuses(&conn), vec![UpdateStatus::UpToDate]);
+
+        let second = upstream.commit("v2");
+        let check = &check_skill_updates(&conn).unwrap()[0];
+        assert_eq!(check.status, UpdateStatus::Outdated);
+        assert_eq!(check.latest_sha.as_deref(), Some(second.as_str()));
+
+        let update = prepare_skill_update(&conn, "notes", &UpdateTarget::Latest).unwrap();
+        assert_eq!(update.preview.to_sha, second);
+        assert!(update.preview.diff.contains("--- a/SKILL.md"));
+        assert!(update.preview.diff.contains("-v1\n+v2\n"));
+        // Preparing leaves the installed bundle alone.
+        let installed = dest.join("notes").join("SKILL.md");
+        assert!(fs::read_to_string(&installed).unwrap().contains("v1"));
+
+        apply_skill_update(&conn, update).unwrap();
+        assert!(fs::read_to_string(&installed).unwrap().contains("v2"));
+        // The staged copy was renamed into place and nothing else is left.
+        let entries: Vec<_> = fs::read_dir(&dest)
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(entries, vec!["notes"]);
+        assert_eq!(statuses(&conn), vec![UpdateStatus::UpToDate]);
+    }
+
+    #[test]
+    fn pinned_skills_stay_put_until_unpinned() {
+        let tmp = TempDir::new().unwrap();
+        let conn = conn();
+        let upstream = Upstream::new(tmp.path());
+        let first = upstream.commit("v1");
+        let second = upstream.commit("v2");
+        let dest = tmp.path().join("agents-skills");
+
+        install_from_git(&conn, &upstream.source(), Some(&first[..10]), &dest).unwrap();
+        let installed = dest.join("notes").join("SKILL.md");
+        assert!(fs::read_to_string(&installed).unwrap().contains("v1"));
+        let check = &check_skill_updates(&conn).unwrap()[0];
+        assert_eq!(check.status, UpdateStatus::Pinned);
+        assert_eq!(check.origin.commit_sha, first);
+        assert_eq!(check.latest_sha.as_deref(), Some(second.as_str()));
+
+        assert!(matches!(
+            prepare_skill_update(&conn, "notes", &UpdateTarget::Latest),
+            Err(SkillInstallError::Pinned { .. })
+        ));
+        assert!(matches!(
+            prepare_skill_update(&conn, "notes", &UpdateTarget::Pin("--force".into())),
+            Err(SkillInstallError::InvalidCommit(_))
+        ));
+
+        let update = prepare_skill_update(&conn, "notes", &UpdateTarget::Unpin).unwrap();
+        let applied = apply_skill_update(&conn, update).unwrap();
+        assert_eq!(applied.to_sha, second);
+        assert!(!applied.pinned);
+        assert_eq!(statuses(&conn), vec![UpdateStatus::UpToDate]);
+
+        let update =
+            prepare_skill_update(&conn, "notes", &UpdateTarget::Pin(first.clone())).unwrap();
+        apply_skill_update(&conn, update).unwrap();
+        assert!(fs::read_to_string(&installed).unwrap().contains("v1"));
+        assert_eq!(statuses(&conn), vec![UpdateStatus::Pinned]);
+    }
+}
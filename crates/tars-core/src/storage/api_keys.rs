@@ -99,6 +99,7 @@ pub struct ApiKeyRecord {
     pub last_validated_at: Option<String>,
     pub last_valid: Option<bool>,
     pub balance: Option<serde_json::Value>,
+    pub balance_threshold: Option<f64>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -113,6 +114,7 @@ impl fmt::Debug for ApiKeyRecord {
             .field("last_validated_at", &self.last_validated_at)
             .field("last_valid", &self.last_valid)
             .field("balance", &self.balance)
+            .field("balance_threshold", &self.balance_threshold)
             .field("created_at", &self.created_at)
             .field("updated_at", &self.updated_at)
             .finish()
@@ -128,6 +130,8 @@ pub struct ApiKeySummary {
     pub last_validated_at: Option<String>,
     pub last_valid: Option<bool>,
     pub balance: Option<serde_json::Value>,
+    /// Low-balance warning level in the key's balance currency
+    pub balance_threshold: Option<f64>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -173,7 +177,7 @@ impl<'a> ApiKeyStore<'a> {
         let mut stmt = self.conn.prepare(
             r"
             SELECT id, provider_id, label, last_validated_at, last_valid,
-                   balance_json, created_at, updated_at
+                   balance_json, balance_threshold, created_at, updated_at
             FROM api_keys
             ORDER BY provider_id, label
             ",
@@ -190,7 +194,7 @@ impl<'a> ApiKeyStore<'a> {
         let mut stmt = self.conn.prepare(
             r"
             SELECT id, provider_id, label, last_validated_at, last_valid,
-                   balance_json, created_at, updated_at
+                   balance_json, balance_threshold, created_at, updated_at
             FROM api_keys
             WHERE provider_id = ?1
             ORDER BY label
@@ -210,7 +214,7 @@ impl<'a> ApiKeyStore<'a> {
             r"
             SELECT id, provider_id, label, encrypted_key, nonce,
                    last_validated_at, last_valid, balance_json,
-                   created_at, updated_at
+                   balance_threshold, created_at, updated_at
             FROM api_keys
             WHERE id = ?1
             ",
@@ -227,8 +231,9 @@ impl<'a> ApiKeyStore<'a> {
                     row.get::<_, Option<String>>(5)?,
                     row.get::<_, Option<i64>>(6)?,
                     row.get::<_, Option<String>>(7)?,
-                    row.get::<_, String>(8)?,
+                    row.get::<_, Option<f64>>(8)?,
                     row.get::<_, String>(9)?,
+                    row.get::<_, String>(10)?,
                 ))
             })
             .optional()?;
@@ -242,6 +247,7 @@ impl<'a> ApiKeyStore<'a> {
             last_validated_at,
             last_valid,
             balance_json,
+            balance_threshold,
             created_at,
             updated_at,
         )) = row
@@ -261,6 +267,7 @@ impl<'a> ApiKeyStore<'a> {
             last_validated_at,
             last_valid: last_valid.map(|v| v != 0),
             balance,
+            balance_threshold,
             created_at,
             updated_at,
         }))
@@ -343,6 +350,29 @@ impl<'a> ApiKeyStore<'a> {
         Ok(updated > 0)
     }
 
+    /// Set or clear the low-balance warning threshold for a key.
+    ///
+    /// # Errors
+    /// Returns an error if the threshold is negative or not finite, or if the
+    /// update fails.
+    pub fn set_balance_threshold(
+        &self,
+        id: i64,
+        threshold: Option<f64>,
+    ) -> Result<bool, DatabaseError> {
+        if threshold.is_some_and(|t| !t.is_finite() || t < 0.0) {
+            return Err(DatabaseError::Migration(
+                "Balance threshold must be a non-negative number".to_string(),
+            ));
+        }
+        let now = Utc::now().to_rfc3339();
+        let updated = self.conn.execute(
+            "UPDATE api_keys SET balance_threshold = ?1, updated_at = ?2 WHERE id = ?3",
+            params![threshold, now, id],
+        )?;
+        Ok(updated > 0)
+    }
+
     /// Delete a key by id.
     ///
     /// # Errors
@@ -367,8 +397,9 @@ fn row_to_summary(row: &rusqlite::Row) -> rusqlite::Result<ApiKeySummary> {
         last_validated_at: row.get(3)?,
         last_valid: row.get::<_, Option<i64>>(4)?.map(|v| v != 0),
         balance,
-        created_at: row.get(6)?,
-        updated_at: row.get(7)?,
+        balance_threshold: row.get(6)?,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
     })
 }
 
@@ -566,6 +597,35 @@ mod tests {
         assert_eq!(summary.balance, None);
     }
 
+    #[test]
+    fn balance_threshold_roundtrip() {
+        let db = Database::in_memory().unwrap();
+        let store = ApiKeyStore::new(db.connection());
+
+        let input = ApiKeyInput {
+            provider_id: "openrouter".into(),
+            label: "work".into(),
+            key: "sk-or-test".into(),
+        };
+        let id = match store.save(&input) {
+            Ok(id) => id,
+            Err(e) if is_keychain_error(&e) => return,
+            Err(e) => panic!("unexpected: {e}"),
+        };
+
+        assert_eq!(store.list().unwrap()[0].balance_threshold, None);
+        assert!(store.set_balance_threshold(id, Some(5.0)).unwrap());
+        assert_eq!(store.list().unwrap()[0].balance_threshold, Some(5.0));
+        assert_eq!(store.get(id).unwrap().unwrap().balance_threshold, Some(5.0));
+
+        assert!(store.set_balance_threshold(id, Some(-1.0)).is_err());
+        assert!(store.set_balance_threshold(id, Some(f64::NAN)).is_err());
+
+        assert!(store.set_balance_threshold(id, None).unwrap());
+        assert_eq!(store.list().unwrap()[0].balance_threshold, None);
+        assert!(!store.set_balance_threshold(999, Some(1.0)).unwrap());
+    }
+
     #[test]
     fn delete_nonexistent_returns_false() {
         let db = Database::in_memory().unwrap();
@@ -614,6 +674,7 @@ mod tests {
             last_validated_at: None,
             last_valid: None,
             balance: None,
+            balance_threshold: None,
             created_at: "now".into(),
             updated_at: "now".into(),
         };
@@ -0,0 +1,261 @@
+//! Batch apply storage
+//!
+//! Backs the tables created in migration v20. A batch records one profile
+//! being applied to every project assigned to it, with the backup taken for
+//! each project so the whole batch can be rolled back at once; see
+//! [`crate::profile::batch`].
+
+use crate::storage::db::DatabaseError;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// What happened to one project in a batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchProjectStatus {
+    /// The plan was applied and a backup recorded
+    Applied,
+    /// The project already matched the profile
+    Unchanged,
+    /// Planning or applying failed; any partial writes were undone, or the
+    /// entry keeps the backup that undoes them
+    Failed,
+}
+
+impl BatchProjectStatus {
+    /// The value stored in the database
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Applied => "applied",
+            Self::Unchanged => "unchanged",
+            Self::Failed => "failed",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "applied" => Some(Self::Applied),
+            "unchanged" => Some(Self::Unchanged),
+            "failed" => Some(Self::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// One project's outcome in a batch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApplyBatchEntry {
+    pub project_id: Uuid,
+    pub project_name: String,
+    pub status: BatchProjectStatus,
+    /// Number of file operations applied
+    pub operations: usize,
+    /// Backup restoring the project to its state before the batch
+    pub backup_id: Option<Uuid>,
+    pub error: Option<String>,
+}
+
+/// A profile applied to all of its projects in one go
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApplyBatch {
+    pub id: Uuid,
+    pub profile_id: Uuid,
+    pub profile_name: String,
+    pub created_at: DateTime<Utc>,
+    /// When the batch was rolled back, if it was
+    pub rolled_back_at: Option<DateTime<Utc>>,
+    pub entries: Vec<ApplyBatchEntry>,
+}
+
+impl ApplyBatch {
+    /// A new batch for `profile_id` with no entries yet
+    #[must_use]
+    pub fn new(profile_id: Uuid, profile_name: String) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            profile_id,
+            profile_name,
+            created_at: Utc::now(),
+            rolled_back_at: None,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Number of entries with `status`
+    #[must_use]
+    pub fn count(&self, status: BatchProjectStatus) -> usize {
+        self.entries.iter().filter(|e| e.status == status).count()
+    }
+}
+
+/// Batch apply storage operations
+pub struct ApplyBatchStore<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> ApplyBatchStore<'a> {
+    /// Create a new batch store
+    #[must_use]
+    pub fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    /// Store a batch with its entries
+    ///
+    /// # Errors
+    /// Returns an error if the batch cannot be stored
+    pub fn create(&self, batch: &ApplyBatch) -> Result<(), DatabaseError> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            r"
+            INSERT INTO apply_batches (id, profile_id, profile_name, created_at, rolled_back_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            ",
+            params![
+                batch.id.to_string(),
+                batch.profile_id.to_string(),
+                batch.profile_name,
+                batch.created_at.to_rfc3339(),
+                batch.rolled_back_at.map(|t| t.to_rfc3339()),
+            ],
+        )?;
+        for entry in &batch.entries {
+            tx.execute(
+                r"
+                INSERT INTO apply_batch_projects
+                    (batch_id, project_id, project_name, status, operations, backup_id, error)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                ",
+                params![
+                    batch.id.to_string(),
+                    entry.project_id.to_string(),
+                    entry.project_name,
+                    entry.status.as_str(),
+                    i64::try_from(entry.operations).unwrap_or(i64::MAX),
+                    entry.backup_id.map(|id| id.to_string()),
+                    entry.error,
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Get a batch with its entries
+    ///
+    /// # Errors
+    /// Returns an error if the query fails
+    pub fn get(&self, id: Uuid) -> Result<Option<ApplyBatch>, DatabaseError> {
+        let batch = self
+            .conn
+            .query_row(
+                r"
+                SELECT id, profile_id, profile_name, created_at, rolled_back_at
+                FROM apply_batches WHERE id = ?1
+                ",
+                params![id.to_string()],
+                row_to_batch,
+            )
+            .optional()?;
+        match batch {
+            Some(mut batch) => {
+                batch.entries = self.entries(batch.id)?;
+                Ok(Some(batch))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// List batches with their entries, newest first
+    ///
+    /// # Errors
+    /// Returns an error if the query fails
+    pub fn list(&self) -> Result<Vec<ApplyBatch>, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            r"
+            SELECT id, profile_id, profile_name, created_at, rolled_back_at
+            FROM apply_batches ORDER BY created_at DESC
+            ",
+        )?;
+        let mut batches = stmt
+            .query_map([], row_to_batch)?
+            .collect::<Result<Vec<_>, _>>()?;
+        for batch in &mut batches {
+            batch.entries = self.entries(batch.id)?;
+        }
+        Ok(batches)
+    }
+
+    /// Record that a batch was rolled back
+    ///
+    /// # Errors
+    /// Returns an error if the update fails
+    pub fn set_rolled_back(&self, id: Uuid, at: DateTime<Utc>) -> Result<bool, DatabaseError> {
+        let updated = self.conn.execute(
+            "UPDATE apply_batches SET rolled_back_at = ?1 WHERE id = ?2",
+            params![at.to_rfc3339(), id.to_string()],
+        )?;
+        Ok(updated > 0)
+    }
+
+    fn entries(&self, batch_id: Uuid) -> Result<Vec<ApplyBatchEntry>, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            r"
+            SELECT project_id, project_name, status, operations, backup_id, error
+            FROM apply_batch_projects WHERE batch_id = ?1 ORDER BY project_name
+            ",
+        )?;
+        let rows = stmt.query_map(params![batch_id.to_string()], row_to_entry)?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(DatabaseError::from)
+    }
+}
+
+fn to_sql_error(e: impl std::error::Error + Send + Sync + 'static) -> rusqlite::Error {
+    rusqlite::Error::ToSqlConversionFailure(Box::new(e))
+}
+
+fn parse_time(value: &str) -> Result<DateTime<Utc>, rusqlite::Error> {
+    Ok(DateTime::parse_from_rfc3339(value)
+        .map_err(to_sql_error)?
+        .with_timezone(&Utc))
+}
+
+fn row_to_batch(row: &rusqlite::Row<'_>) -> Result<ApplyBatch, rusqlite::Error> {
+    let id: String = row.get(0)?;
+    let profile_id: String = row.get(1)?;
+    let created_at: String = row.get(3)?;
+    let rolled_back_at: Option<String> = row.get(4)?;
+    Ok(ApplyBatch {
+        id: Uuid::parse_str(&id).map_err(to_sql_error)?,
+        profile_id: Uuid::parse_str(&profile_id).map_err(to_sql_error)?,
+        profile_name: row.get(2)?,
+        created_at: parse_time(&created_at)?,
+        rolled_back_at: rolled_back_at.as_deref().map(parse_time).transpose()?,
+        entries: Vec::new(),
+    })
+}
+
+fn row_to_entry(row: &rusqlite::Row<'_>) -> Result<ApplyBatchEntry, rusqlite::Error> {
+    let project_id: String = row.get(0)?;
+    let status: String = row.get(2)?;
+    let operations: i64 = row.get(3)?;
+    let backup_id: Option<String> = row.get(4)?;
+    Ok(ApplyBatchEntry {
+        project_id: Uuid::parse_str(&project_id).map_err(to_sql_error)?,
+        project_name: row.get(1)?,
+        status: BatchProjectStatus::parse(&status).ok_or_else(|| {
+            rusqlite::Error::InvalidColumnType(2, status.clone(), rusqlite::types::Type::Text)
+        })?,
+        operations: usize::try_from(operations).unwrap_or(0),
+        backup_id: backup_id
+            .as_deref()
+            .map(Uuid::parse_str)
+            .transpose()
+            .map_err(to_sql_error)?,
+        error: row.get(5)?,
+    })
+}
@@ -0,0 +1,214 @@
+//! Profile assignment rule storage
+//!
+//! Backs the table created in migration v19. A rule names the profile a
+//! newly discovered or added project should get when its conditions match;
+//! see [`crate::profile::rules`] for how projects are matched.
+
+use crate::storage::db::DatabaseError;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+const RULE_COLUMNS: &str =
+    "id, name, profile_id, priority, conditions, auto_apply, enabled, created_at";
+
+/// What a project must look like for a rule to match
+///
+/// Every non-empty list must have at least one match; values within a list
+/// are alternatives. Comparisons are case-insensitive except path globs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleConditions {
+    /// Globs matched against the project's absolute path, e.g. `~/work/**`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub path_globs: Vec<String>,
+    /// Languages with code in the project, e.g. `Rust`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub languages: Vec<String>,
+    /// Dependency manifests at the project root, e.g. `Cargo.toml`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependency_files: Vec<String>,
+    /// Host of the `origin` remote, e.g. `github.com`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub git_hosts: Vec<String>,
+    /// Owner or organization of the `origin` remote
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub git_orgs: Vec<String>,
+    /// Entries of the project's metadata `platforms`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub platforms: Vec<String>,
+}
+
+impl RuleConditions {
+    /// Whether no condition is set
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.path_globs.is_empty()
+            && self.languages.is_empty()
+            && self.dependency_files.is_empty()
+            && self.git_hosts.is_empty()
+            && self.git_orgs.is_empty()
+            && self.platforms.is_empty()
+    }
+}
+
+/// A rule assigning a profile to matching projects
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssignmentRule {
+    pub id: Uuid,
+    pub name: String,
+    pub profile_id: Uuid,
+    /// Rules with a higher priority win when several match
+    pub priority: i64,
+    pub conditions: RuleConditions,
+    /// Apply the profile right away instead of only proposing it
+    pub auto_apply: bool,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AssignmentRule {
+    /// A new enabled rule that proposes `profile_id`
+    #[must_use]
+    pub fn new(name: String, profile_id: Uuid, conditions: RuleConditions) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name,
+            profile_id,
+            priority: 0,
+            conditions,
+            auto_apply: false,
+            enabled: true,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Assignment rule storage operations
+pub struct AssignmentRuleStore<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> AssignmentRuleStore<'a> {
+    /// Create a new assignment rule store
+    #[must_use]
+    pub fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    /// Store a new rule
+    ///
+    /// # Errors
+    /// Returns an error if the rule cannot be stored, e.g. its profile does not exist
+    pub fn create(&self, rule: &AssignmentRule) -> Result<(), DatabaseError> {
+        self.conn.execute(
+            r"
+            INSERT INTO assignment_rules
+                (id, name, profile_id, priority, conditions, auto_apply, enabled, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            ",
+            params![
+                rule.id.to_string(),
+                rule.name,
+                rule.profile_id.to_string(),
+                rule.priority,
+                conditions_json(&rule.conditions)?,
+                rule.auto_apply,
+                rule.enabled,
+                rule.created_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Get a rule by ID
+    ///
+    /// # Errors
+    /// Returns an error if the query fails
+    pub fn get(&self, id: Uuid) -> Result<Option<AssignmentRule>, DatabaseError> {
+        let sql = format!("SELECT {RULE_COLUMNS} FROM assignment_rules WHERE id = ?1");
+        self.conn
+            .query_row(&sql, params![id.to_string()], row_to_rule)
+            .optional()
+            .map_err(DatabaseError::from)
+    }
+
+    /// List all rules, highest priority first
+    ///
+    /// # Errors
+    /// Returns an error if the query fails
+    pub fn list(&self) -> Result<Vec<AssignmentRule>, DatabaseError> {
+        let sql = format!(
+            "SELECT {RULE_COLUMNS} FROM assignment_rules ORDER BY priority DESC, created_at"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map([], row_to_rule)?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(DatabaseError::from)
+    }
+
+    /// Update a rule
+    ///
+    /// # Errors
+    /// Returns an error if the update fails
+    pub fn update(&self, rule: &AssignmentRule) -> Result<(), DatabaseError> {
+        self.conn.execute(
+            r"
+            UPDATE assignment_rules
+            SET name = ?1, profile_id = ?2, priority = ?3, conditions = ?4,
+                auto_apply = ?5, enabled = ?6
+            WHERE id = ?7
+            ",
+            params![
+                rule.name,
+                rule.profile_id.to_string(),
+                rule.priority,
+                conditions_json(&rule.conditions)?,
+                rule.auto_apply,
+                rule.enabled,
+                rule.id.to_string(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Delete a rule
+    ///
+    /// # Errors
+    /// Returns an error if the delete fails
+    pub fn delete(&self, id: Uuid) -> Result<bool, DatabaseError> {
+        let deleted = self.conn.execute(
+            "DELETE FROM assignment_rules WHERE id = ?1",
+            params![id.to_string()],
+        )?;
+        Ok(deleted > 0)
+    }
+}
+
+fn conditions_json(conditions: &RuleConditions) -> Result<String, DatabaseError> {
+    serde_json::to_string(conditions)
+        .map_err(|e| DatabaseError::Migration(format!("Failed to serialize conditions: {e}")))
+}
+
+fn to_sql_error(e: impl std::error::Error + Send + Sync + 'static) -> rusqlite::Error {
+    rusqlite::Error::ToSqlConversionFailure(Box::new(e))
+}
+
+fn row_to_rule(row: &rusqlite::Row<'_>) -> Result<AssignmentRule, rusqlite::Error> {
+    let id: String = row.get(0)?;
+    let profile_id: String = row.get(2)?;
+    let conditions: String = row.get(4)?;
+    let created_at: String = row.get(7)?;
+    Ok(AssignmentRule {
+        id: Uuid::parse_str(&id).map_err(to_sql_error)?,
+        name: row.get(1)?,
+        profile_id: Uuid::parse_str(&profile_id).map_err(to_sql_error)?,
+        priority: row.get(3)?,
+        conditions: serde_json::from_str(&conditions).map_err(to_sql_error)?,
+        auto_apply: row.get(5)?,
+        enabled: row.get(6)?,
+        created_at: DateTime::parse_from_rfc3339(&created_at)
+            .map_err(to_sql_error)?
+            .with_timezone(&Utc),
+    })
+}
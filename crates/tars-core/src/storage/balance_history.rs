@@ -0,0 +1,274 @@
+//! Balance snapshot history for API keys.
+//!
+//! Backs the `balance_snapshots` table created in migration v14. Every
+//! successful balance fetch appends one row so the desktop can chart spend
+//! over time; the raw provider payload stays in `api_keys.balance_json`.
+//! History is capped per key ([`MAX_SNAPSHOTS_PER_KEY`]) and pruned on insert,
+//! and rows go away with their key via `ON DELETE CASCADE`.
+
+use super::db::DatabaseError;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+/// Snapshots kept per key; roughly a year of daily validations.
+pub const MAX_SNAPSHOTS_PER_KEY: usize = 365;
+
+/// A normalised balance reading for one key.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BalanceSnapshot {
+    pub id: i64,
+    pub api_key_id: i64,
+    pub provider_id: String,
+    pub currency: String,
+    pub amount: f64,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Whether `amount` has dropped below the user's warning `threshold`.
+/// No threshold means no warning.
+#[must_use]
+pub fn is_below_threshold(amount: f64, threshold: Option<f64>) -> bool {
+    threshold.is_some_and(|t| amount < t)
+}
+
+pub struct BalanceHistoryStore<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> BalanceHistoryStore<'a> {
+    #[must_use]
+    pub fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    /// Append a snapshot and prune the key's history down to
+    /// [`MAX_SNAPSHOTS_PER_KEY`] rows, oldest first.
+    ///
+    /// # Errors
+    /// Returns an error if the amount is not finite or the insert fails.
+    pub fn record(
+        &self,
+        api_key_id: i64,
+        provider_id: &str,
+        currency: &str,
+        amount: f64,
+        recorded_at: DateTime<Utc>,
+    ) -> Result<i64, DatabaseError> {
+        if !amount.is_finite() {
+            return Err(DatabaseError::Migration(format!(
+                "Balance amount must be finite, got {amount}"
+            )));
+        }
+
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            r"
+            INSERT INTO balance_snapshots
+                (api_key_id, provider_id, currency, amount, recorded_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            ",
+            params![
+                api_key_id,
+                provider_id,
+                currency,
+                amount,
+                recorded_at.to_rfc3339()
+            ],
+        )?;
+        let id = tx.last_insert_rowid();
+        let keep = i64::try_from(MAX_SNAPSHOTS_PER_KEY).unwrap_or(i64::MAX);
+        tx.execute(
+            r"
+            DELETE FROM balance_snapshots
+            WHERE api_key_id = ?1 AND id NOT IN (
+                SELECT id FROM balance_snapshots
+                WHERE api_key_id = ?1
+                ORDER BY recorded_at DESC, id DESC
+                LIMIT ?2
+            )
+            ",
+            params![api_key_id, keep],
+        )?;
+        tx.commit()?;
+        Ok(id)
+    }
+
+    /// The most recent `limit` snapshots for a key, oldest first (chart
+    /// order).
+    ///
+    /// # Errors
+    /// Returns an error if the query fails or a timestamp is malformed.
+    pub fn list_for_key(
+        &self,
+        api_key_id: i64,
+        limit: usize,
+    ) -> Result<Vec<BalanceSnapshot>, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            r"
+            SELECT id, api_key_id, provider_id, currency, amount, recorded_at
+            FROM balance_snapshots
+            WHERE api_key_id = ?1
+            ORDER BY recorded_at DESC, id DESC
+            LIMIT ?2
+            ",
+        )?;
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        let rows = stmt.query_map(params![api_key_id, limit], row_to_snapshot)?;
+        let mut snapshots = rows.collect::<Result<Vec<_>, _>>()?;
+        snapshots.reverse();
+        Ok(snapshots)
+    }
+
+    /// The newest snapshot for a key, if any.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails or a timestamp is malformed.
+    pub fn latest(&self, api_key_id: i64) -> Result<Option<BalanceSnapshot>, DatabaseError> {
+        self.conn
+            .query_row(
+                r"
+                SELECT id, api_key_id, provider_id, currency, amount, recorded_at
+                FROM balance_snapshots
+                WHERE api_key_id = ?1
+                ORDER BY recorded_at DESC, id DESC
+                LIMIT 1
+                ",
+                params![api_key_id],
+                row_to_snapshot,
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+}
+
+fn row_to_snapshot(row: &rusqlite::Row<'_>) -> Result<BalanceSnapshot, rusqlite::Error> {
+    let recorded_at: String = row.get(5)?;
+    let recorded_at = DateTime::parse_from_rfc3339(&recorded_at)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(5, rusqlite::types::Type::Text, Box::new(e))
+        })?;
+    Ok(BalanceSnapshot {
+        id: row.get(0)?,
+        api_key_id: row.get(1)?,
+        provider_id: row.get(2)?,
+        currency: row.get(3)?,
+        amount: row.get(4)?,
+        recorded_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::db::Database;
+    use chrono::Duration;
+
+    /// Insert a key row directly; the encrypted columns are irrelevant here
+    /// and going through `ApiKeyStore::save` would need the OS keychain.
+    fn insert_key(conn: &Connection, label: &str) -> i64 {
+        conn.execute(
+            "INSERT INTO api_keys (provider_id, label, encrypted_key, nonce, created_at, updated_at)
+             VALUES ('openrouter', ?1, 'x', 'x', '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')",
+            params![label],
+        )
+        .unwrap();
+        conn.last_insert_rowid()
+    }
+
+    fn t0() -> DateTime<Utc> {
+        "2026-05-01T00:00:00Z".parse().unwrap()
+    }
+
+    #[test]
+    fn record_and_list_in_chronological_order() {
+        let db = Database::in_memory().unwrap();
+        let key = insert_key(db.connection(), "work");
+        let store = BalanceHistoryStore::new(db.connection());
+
+        store.record(key, "openrouter", "USD", 20.0, t0()).unwrap();
+        store
+            .record(key, "openrouter", "USD", 12.5, t0() + Duration::days(2))
+            .unwrap();
+        store
+            .record(key, "openrouter", "USD", 17.0, t0() + Duration::days(1))
+            .unwrap();
+
+        let amounts: Vec<f64> = store
+            .list_for_key(key, 10)
+            .unwrap()
+            .iter()
+            .map(|s| s.amount)
+            .collect();
+        assert_eq!(amounts, vec![20.0, 17.0, 12.5]);
+
+        let recent: Vec<f64> = store
+            .list_for_key(key, 2)
+            .unwrap()
+            .iter()
+            .map(|s| s.amount)
+            .collect();
+        assert_eq!(recent, vec![17.0, 12.5]);
+
+        let latest = store.latest(key).unwrap().unwrap();
+        assert!((latest.amount - 12.5).abs() < f64::EPSILON);
+        assert_eq!(latest.currency, "USD");
+        assert_eq!(latest.recorded_at, t0() + Duration::days(2));
+    }
+
+    #[test]
+    fn history_is_per_key_and_pruned() {
+        let db = Database::in_memory().unwrap();
+        let a = insert_key(db.connection(), "a");
+        let b = insert_key(db.connection(), "b");
+        let store = BalanceHistoryStore::new(db.connection());
+
+        for i in 0..=MAX_SNAPSHOTS_PER_KEY {
+            let t = t0() + Duration::hours(i64::try_from(i).unwrap());
+            store.record(a, "openrouter", "USD", 1.0, t).unwrap();
+        }
+        store.record(b, "openrouter", "USD", 9.0, t0()).unwrap();
+
+        let history = store.list_for_key(a, usize::MAX).unwrap();
+        assert_eq!(history.len(), MAX_SNAPSHOTS_PER_KEY);
+        assert_eq!(
+            history[0].recorded_at,
+            t0() + Duration::hours(1),
+            "oldest snapshot pruned"
+        );
+        assert_eq!(store.list_for_key(b, usize::MAX).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn snapshots_cascade_with_key() {
+        let db = Database::in_memory().unwrap();
+        let key = insert_key(db.connection(), "work");
+        let store = BalanceHistoryStore::new(db.connection());
+        store.record(key, "openrouter", "USD", 5.0, t0()).unwrap();
+
+        db.connection()
+            .execute("DELETE FROM api_keys WHERE id = ?1", params![key])
+            .unwrap();
+        assert!(store.latest(key).unwrap().is_none());
+    }
+
+    #[test]
+    fn record_rejects_non_finite_amount() {
+        let db = Database::in_memory().unwrap();
+        let key = insert_key(db.connection(), "work");
+        let store = BalanceHistoryStore::new(db.connection());
+        assert!(store
+            .record(key, "openrouter", "USD", f64::NAN, t0())
+            .is_err());
+        assert!(store.latest(key).unwrap().is_none());
+    }
+
+    #[test]
+    fn threshold_check() {
+        assert!(!is_below_threshold(3.0, None));
+        assert!(is_below_threshold(3.0, Some(5.0)));
+        assert!(!is_below_threshold(5.0, Some(5.0)));
+        assert!(!is_below_threshold(8.0, Some(5.0)));
+    }
+}
@@ -0,0 +1,450 @@
+//! User-defined OpenAI-compatible providers.
+//!
+//! Hosted vendors are a closed set in `tars_providers::ProviderId`; anything
+//! else that speaks the `OpenAI` `/models` dialect (Ollama, LM Studio, vLLM,
+//! a `LiteLLM` gateway) is registered here instead. Each row is addressed
+//! elsewhere by the provider id string `custom:<slug>` (see
+//! [`CustomProvider::provider_id`]), so [`super::ApiKeyStore`] and
+//! [`super::ModelCache`] treat custom providers exactly like built-in ones.
+
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tars_providers::{custom_provider_slug, AuthStyle, CUSTOM_PROVIDER_PREFIX};
+use thiserror::Error;
+
+use super::db::DatabaseError;
+
+/// Maximum allowed length for a slug
+pub const MAX_SLUG_LEN: usize = 64;
+
+/// Input validation errors for custom provider definitions
+#[derive(Error, Debug)]
+pub enum CustomProviderValidationError {
+    #[error("Slug must be 1-{MAX_SLUG_LEN} lowercase letters, digits, '-', '_' or '.'")]
+    InvalidSlug,
+
+    #[error("Display name is empty")]
+    EmptyDisplayName,
+
+    #[error("Base URL must start with http:// or https://")]
+    InvalidBaseUrl,
+
+    #[error("Header name is empty or contains invalid characters")]
+    InvalidHeaderName,
+}
+
+/// Split an auth style into its `auth_style` / `auth_header` columns.
+fn auth_to_columns(auth: &AuthStyle) -> (&'static str, Option<&str>) {
+    match auth {
+        AuthStyle::Bearer => ("bearer", None),
+        AuthStyle::Header { name } => ("header", Some(name.as_str())),
+        AuthStyle::None => ("none", None),
+    }
+}
+
+fn auth_from_columns(style: &str, header: Option<String>) -> Result<AuthStyle, DatabaseError> {
+    match (style, header) {
+        ("bearer", _) => Ok(AuthStyle::Bearer),
+        ("none", _) => Ok(AuthStyle::None),
+        ("header", Some(name)) => Ok(AuthStyle::Header { name }),
+        (other, _) => Err(DatabaseError::Migration(format!(
+            "Bad custom provider auth style: {other}"
+        ))),
+    }
+}
+
+/// A stored custom provider definition.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomProvider {
+    pub id: i64,
+    pub slug: String,
+    pub display_name: String,
+    pub base_url: String,
+    pub auth_style: AuthStyle,
+    /// Path appended to `base_url` for model discovery; `None` = `/models`.
+    pub models_path: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl CustomProvider {
+    /// The `custom:<slug>` string used as `provider_id` in `api_keys` and
+    /// `provider_models`.
+    #[must_use]
+    pub fn provider_id(&self) -> String {
+        format!("{CUSTOM_PROVIDER_PREFIX}{}", self.slug)
+    }
+}
+
+/// Input for creating or updating a custom provider.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CustomProviderInput {
+    pub slug: String,
+    pub display_name: String,
+    pub base_url: String,
+    pub auth_style: AuthStyle,
+    #[serde(default)]
+    pub models_path: Option<String>,
+}
+
+/// Validate a custom provider definition.
+///
+/// # Errors
+/// Returns a [`CustomProviderValidationError`] describing the first invalid
+/// field.
+pub fn validate_input(input: &CustomProviderInput) -> Result<(), CustomProviderValidationError> {
+    let slug_ok = !input.slug.is_empty()
+        && input.slug.len() <= MAX_SLUG_LEN
+        && input
+            .slug
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '_' | '.'));
+    if !slug_ok {
+        return Err(CustomProviderValidationError::InvalidSlug);
+    }
+    if input.display_name.trim().is_empty() {
+        return Err(CustomProviderValidationError::EmptyDisplayName);
+    }
+    let url = input.base_url.trim();
+    let has_host = url
+        .strip_prefix("http://")
+        .or_else(|| url.strip_prefix("https://"))
+        .is_some_and(|rest| !rest.is_empty());
+    if !has_host {
+        return Err(CustomProviderValidationError::InvalidBaseUrl);
+    }
+    if let AuthStyle::Header { name } = &input.auth_style {
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'));
+        if !valid {
+            return Err(CustomProviderValidationError::InvalidHeaderName);
+        }
+    }
+    Ok(())
+}
+
+const COLUMNS: &str =
+    "id, slug, display_name, base_url, auth_style, auth_header, models_path, created_at, updated_at";
+
+fn parse_datetime(value: &str) -> Result<DateTime<Utc>, DatabaseError> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|e| DatabaseError::Migration(format!("Bad custom provider timestamp: {e}")))
+}
+
+fn row_to_provider(row: &rusqlite::Row<'_>) -> Result<CustomProvider, rusqlite::Error> {
+    let to_sql_err = |e: DatabaseError| rusqlite::Error::ToSqlConversionFailure(Box::new(e));
+    let auth_style: String = row.get(4)?;
+    let created_at: String = row.get(7)?;
+    let updated_at: String = row.get(8)?;
+    Ok(CustomProvider {
+        id: row.get(0)?,
+        slug: row.get(1)?,
+        display_name: row.get(2)?,
+        base_url: row.get(3)?,
+        auth_style: auth_from_columns(&auth_style, row.get(5)?).map_err(to_sql_err)?,
+        models_path: row.get(6)?,
+        created_at: parse_datetime(&created_at).map_err(to_sql_err)?,
+        updated_at: parse_datetime(&updated_at).map_err(to_sql_err)?,
+    })
+}
+
+/// Normalise optional free-text fields: trim, and treat blank as unset.
+fn normalise_path(path: Option<&str>) -> Option<&str> {
+    path.map(str::trim).filter(|p| !p.is_empty())
+}
+
+/// Custom provider storage operations
+pub struct CustomProviderStore<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> CustomProviderStore<'a> {
+    #[must_use]
+    pub fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    /// Register a new custom provider.
+    ///
+    /// # Errors
+    /// Returns an error if the input fails validation or the insert fails
+    /// (e.g. duplicate slug).
+    pub fn create(&self, input: &CustomProviderInput) -> Result<CustomProvider, DatabaseError> {
+        validate_input(input)
+            .map_err(|e| DatabaseError::Migration(format!("Invalid custom provider: {e}")))?;
+        let (style, header) = auth_to_columns(&input.auth_style);
+        let now = Utc::now().to_rfc3339();
+        self.conn.execute(
+            r"
+            INSERT INTO custom_providers
+                (slug, display_name, base_url, auth_style, auth_header, models_path,
+                 created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)
+            ",
+            params![
+                input.slug,
+                input.display_name.trim(),
+                input.base_url.trim(),
+                style,
+                header,
+                normalise_path(input.models_path.as_deref()),
+                now
+            ],
+        )?;
+        self.get(&input.slug)?
+            .ok_or_else(|| DatabaseError::Migration("custom provider vanished after insert".into()))
+    }
+
+    /// Replace the connection settings of an existing provider (matched by
+    /// slug). Keys and cached models are untouched.
+    ///
+    /// Returns `None` if no provider has that slug.
+    ///
+    /// # Errors
+    /// Returns an error if the input fails validation or the update fails.
+    pub fn update(
+        &self,
+        input: &CustomProviderInput,
+    ) -> Result<Option<CustomProvider>, DatabaseError> {
+        validate_input(input)
+            .map_err(|e| DatabaseError::Migration(format!("Invalid custom provider: {e}")))?;
+        let (style, header) = auth_to_columns(&input.auth_style);
+        let now = Utc::now().to_rfc3339();
+        let updated = self.conn.execute(
+            r"
+            UPDATE custom_providers
+            SET display_name = ?2, base_url = ?3, auth_style = ?4, auth_header = ?5,
+                models_path = ?6, updated_at = ?7
+            WHERE slug = ?1
+            ",
+            params![
+                input.slug,
+                input.display_name.trim(),
+                input.base_url.trim(),
+                style,
+                header,
+                normalise_path(input.models_path.as_deref()),
+                now
+            ],
+        )?;
+        if updated == 0 {
+            return Ok(None);
+        }
+        self.get(&input.slug)
+    }
+
+    /// List all custom providers, ordered by display name.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub fn list(&self) -> Result<Vec<CustomProvider>, DatabaseError> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {COLUMNS} FROM custom_providers ORDER BY display_name COLLATE NOCASE, slug"
+        ))?;
+        let rows = stmt.query_map([], row_to_provider)?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    /// Get a provider by slug.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub fn get(&self, slug: &str) -> Result<Option<CustomProvider>, DatabaseError> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {COLUMNS} FROM custom_providers WHERE slug = ?1"
+        ))?;
+        stmt.query_row(params![slug], row_to_provider)
+            .optional()
+            .map_err(Into::into)
+    }
+
+    /// Get a provider by its `custom:<slug>` provider id string. Returns
+    /// `None` for built-in provider ids.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    pub fn get_by_provider_id(
+        &self,
+        provider_id: &str,
+    ) -> Result<Option<CustomProvider>, DatabaseError> {
+        match custom_provider_slug(provider_id) {
+            Some(slug) => self.get(slug),
+            None => Ok(None),
+        }
+    }
+
+    /// Remove a provider together with its stored keys and cached models,
+    /// which would otherwise be orphaned under an unresolvable provider id.
+    ///
+    /// # Errors
+    /// Returns an error if the transaction fails.
+    pub fn delete(&self, slug: &str) -> Result<bool, DatabaseError> {
+        let provider_id = format!("{CUSTOM_PROVIDER_PREFIX}{slug}");
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM api_keys WHERE provider_id = ?1",
+            params![provider_id],
+        )?;
+        tx.execute(
+            "DELETE FROM provider_models WHERE provider_id = ?1",
+            params![provider_id],
+        )?;
+        let deleted = tx.execute(
+            "DELETE FROM custom_providers WHERE slug = ?1",
+            params![slug],
+        )?;
+        tx.commit()?;
+        Ok(deleted > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::db::Database;
+    use crate::storage::model_cache::{ModelCache, ModelRow};
+
+    fn input(slug: &str) -> CustomProviderInput {
+        CustomProviderInput {
+            slug: slug.to_string(),
+            display_name: "Local Ollama".to_string(),
+            base_url: "http://localhost:11434/v1".to_string(),
+            auth_style: AuthStyle::None,
+            models_path: None,
+        }
+    }
+
+    #[test]
+    fn validate_input_rules() {
+        assert!(validate_input(&input("ollama")).is_ok());
+        assert!(validate_input(&input("gw-1.internal_a")).is_ok());
+        for bad in ["", "Ollama", "has space", "a/b"] {
+            assert!(
+                matches!(
+                    validate_input(&input(bad)),
+                    Err(CustomProviderValidationError::InvalidSlug)
+                ),
+                "{bad:?} should be rejected"
+            );
+        }
+
+        let mut i = input("x");
+        i.display_name = "  ".into();
+        assert!(matches!(
+            validate_input(&i),
+            Err(CustomProviderValidationError::EmptyDisplayName)
+        ));
+
+        let mut i = input("x");
+        i.base_url = "localhost:11434".into();
+        assert!(matches!(
+            validate_input(&i),
+            Err(CustomProviderValidationError::InvalidBaseUrl)
+        ));
+
+        let mut i = input("x");
+        i.auth_style = AuthStyle::Header {
+            name: "bad header".into(),
+        };
+        assert!(matches!(
+            validate_input(&i),
+            Err(CustomProviderValidationError::InvalidHeaderName)
+        ));
+    }
+
+    #[test]
+    fn create_get_update_roundtrip() {
+        let db = Database::in_memory().unwrap();
+        let store = CustomProviderStore::new(db.connection());
+
+        let created = store.create(&input("ollama")).unwrap();
+        assert_eq!(created.provider_id(), "custom:ollama");
+        assert_eq!(created.auth_style, AuthStyle::None);
+        assert_eq!(created.models_path, None);
+
+        let mut changed = input("ollama");
+        changed.base_url = "https://gw.internal/v1".into();
+        changed.auth_style = AuthStyle::Header {
+            name: "x-api-key".into(),
+        };
+        changed.models_path = Some(" /v1/models ".into());
+        let updated = store.update(&changed).unwrap().unwrap();
+        assert_eq!(updated.base_url, "https://gw.internal/v1");
+        assert_eq!(
+            updated.auth_style,
+            AuthStyle::Header {
+                name: "x-api-key".into()
+            }
+        );
+        assert_eq!(updated.models_path.as_deref(), Some("/v1/models"));
+
+        let fetched = store.get_by_provider_id("custom:ollama").unwrap().unwrap();
+        assert_eq!(fetched, updated);
+        assert!(store.get_by_provider_id("openai").unwrap().is_none());
+        assert!(store.update(&input("missing")).unwrap().is_none());
+    }
+
+    #[test]
+    fn duplicate_slug_rejected() {
+        let db = Database::in_memory().unwrap();
+        let store = CustomProviderStore::new(db.connection());
+        store.create(&input("ollama")).unwrap();
+        assert!(store.create(&input("ollama")).is_err());
+    }
+
+    #[test]
+    fn list_orders_by_display_name() {
+        let db = Database::in_memory().unwrap();
+        let store = CustomProviderStore::new(db.connection());
+        let mut b = input("vllm");
+        b.display_name = "vLLM".into();
+        store.create(&b).unwrap();
+        let mut a = input("lmstudio");
+        a.display_name = "LM Studio".into();
+        store.create(&a).unwrap();
+
+        let slugs: Vec<_> = store.list().unwrap().into_iter().map(|p| p.slug).collect();
+        assert_eq!(slugs, vec!["lmstudio", "vllm"]);
+    }
+
+    #[test]
+    fn delete_removes_cached_models() {
+        let db = Database::in_memory().unwrap();
+        let store = CustomProviderStore::new(db.connection());
+        let provider = store.create(&input("ollama")).unwrap();
+
+        let cache = ModelCache::new(db.connection());
+        cache
+            .upsert_all(
+                &provider.provider_id(),
+                &[ModelRow {
+                    model_id: "llama3.2".into(),
+                    display_name: None,
+                    context_window: None,
+                    input_price: None,
+                    output_price: None,
+                }],
+                Utc::now(),
+            )
+            .unwrap();
+        assert_eq!(
+            cache
+                .list_for_provider(&provider.provider_id())
+                .unwrap()
+                .len(),
+            1
+        );
+
+        assert!(store.delete("ollama").unwrap());
+        assert!(store.get("ollama").unwrap().is_none());
+        assert!(cache
+            .list_for_provider(&provider.provider_id())
+            .unwrap()
+            .is_empty());
+        assert!(!store.delete("ollama").unwrap());
+    }
+}
@@ -0,0 +1,435 @@
+//! Validation history and scheduling for stored API keys.
+//!
+//! Backs the `key_validations` table created in migration v16. Every
+//! validation verdict — from the on-demand check or the background schedule —
+//! appends one row, so a key that used to work and now gets rejected (revoked,
+//! expired, out of quota) can be told apart from one that never worked.
+//! History is capped per key ([`MAX_VALIDATIONS_PER_KEY`]) and pruned on
+//! insert; rows go away with their key via `ON DELETE CASCADE`.
+//!
+//! The background schedule ([`ValidationSchedule`]) lives in `app_settings`
+//! as JSON next to the timestamp of the last completed run.
+
+use super::db::DatabaseError;
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+/// Validations kept per key; a year of daily runs.
+pub const MAX_VALIDATIONS_PER_KEY: usize = 365;
+
+const SETTING_SCHEDULE: &str = "key_validation_schedule";
+const SETTING_LAST_RUN: &str = "key_validation_last_run";
+
+/// One validation verdict for a key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyValidation {
+    pub id: i64,
+    pub api_key_id: i64,
+    pub provider_id: String,
+    pub validated_at: DateTime<Utc>,
+    pub valid: bool,
+    pub message: Option<String>,
+    pub latency_ms: u64,
+}
+
+/// A key whose latest verdict is a rejection although it validated
+/// successfully at some earlier point.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailingKey {
+    pub api_key_id: i64,
+    pub provider_id: String,
+    pub label: String,
+    /// Last successful validation before the current failure streak
+    pub last_valid_at: DateTime<Utc>,
+    /// First rejection of the current streak
+    pub failing_since: DateTime<Utc>,
+    pub consecutive_failures: u32,
+    pub last_message: Option<String>,
+}
+
+/// How the background validator runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ValidationSchedule {
+    pub enabled: bool,
+    /// Hours between runs; clamped to at least 1.
+    pub interval_hours: u32,
+    /// Keys of the same provider validated at once; clamped to at least 1.
+    pub per_provider_concurrency: usize,
+}
+
+impl Default for ValidationSchedule {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_hours: 24,
+            per_provider_concurrency: 2,
+        }
+    }
+}
+
+impl ValidationSchedule {
+    #[must_use]
+    pub fn interval(&self) -> Duration {
+        Duration::hours(i64::from(self.interval_hours.max(1)))
+    }
+
+    /// Whether a run is due at `now` given the last completed run.
+    #[must_use]
+    pub fn is_due(&self, last_run: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+        self.enabled && last_run.map_or(true, |at| now.signed_duration_since(at) >= self.interval())
+    }
+}
+
+pub struct KeyValidationStore<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> KeyValidationStore<'a> {
+    #[must_use]
+    pub fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    /// Append a verdict and prune the key's history down to
+    /// [`MAX_VALIDATIONS_PER_KEY`] rows, oldest first.
+    ///
+    /// # Errors
+    /// Returns an error if the insert fails.
+    pub fn record(
+        &self,
+        api_key_id: i64,
+        provider_id: &str,
+        valid: bool,
+        message: Option<&str>,
+        latency_ms: u64,
+        validated_at: DateTime<Utc>,
+    ) -> Result<i64, DatabaseError> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            r"
+            INSERT INTO key_validations
+                (api_key_id, provider_id, validated_at, valid, message, latency_ms)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            ",
+            params![
+                api_key_id,
+                provider_id,
+                validated_at.to_rfc3339(),
+                valid,
+                message,
+                i64::try_from(latency_ms).unwrap_or(i64::MAX)
+            ],
+        )?;
+        let id = tx.last_insert_rowid();
+        let keep = i64::try_from(MAX_VALIDATIONS_PER_KEY).unwrap_or(i64::MAX);
+        tx.execute(
+            r"
+            DELETE FROM key_validations
+            WHERE api_key_id = ?1 AND id NOT IN (
+                SELECT id FROM key_validations
+                WHERE api_key_id = ?1
+                ORDER BY validated_at DESC, id DESC
+                LIMIT ?2
+            )
+            ",
+            params![api_key_id, keep],
+        )?;
+        tx.commit()?;
+        Ok(id)
+    }
+
+    /// The most recent `limit` verdicts for a key, newest first.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails or a timestamp is malformed.
+    pub fn list_for_key(
+        &self,
+        api_key_id: i64,
+        limit: usize,
+    ) -> Result<Vec<KeyValidation>, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            r"
+            SELECT id, api_key_id, provider_id, validated_at, valid, message, latency_ms
+            FROM key_validations
+            WHERE api_key_id = ?1
+            ORDER BY validated_at DESC, id DESC
+            LIMIT ?2
+            ",
+        )?;
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        let rows = stmt.query_map(params![api_key_id, limit], row_to_validation)?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    /// Keys that validated successfully before but whose latest verdicts
+    /// are rejections, most recently broken first.
+    ///
+    /// Keys that never validated are left out: those are typos, not
+    /// regressions, and the key list already flags them.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails or a timestamp is malformed.
+    pub fn failing_keys(&self) -> Result<Vec<FailingKey>, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            r"
+            SELECT v.id, v.api_key_id, v.provider_id, v.validated_at, v.valid,
+                   v.message, v.latency_ms, k.label
+            FROM key_validations v
+            JOIN api_keys k ON k.id = v.api_key_id
+            ORDER BY v.api_key_id, v.validated_at DESC, v.id DESC
+            ",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row_to_validation(row)?, row.get(7)?)))?;
+
+        // Newest-first history per key, in key order.
+        let mut by_key: Vec<(String, Vec<KeyValidation>)> = Vec::new();
+        for row in rows {
+            let (v, label): (KeyValidation, String) = row?;
+            match by_key.last_mut() {
+                Some((_, history)) if history[0].api_key_id == v.api_key_id => history.push(v),
+                _ => by_key.push((label, vec![v])),
+            }
+        }
+
+        let mut failing: Vec<FailingKey> = by_key
+            .into_iter()
+            .filter_map(|(label, history)| failing_streak(label, &history))
+            .collect();
+        failing.sort_by_key(|f| std::cmp::Reverse(f.failing_since));
+        Ok(failing)
+    }
+
+    /// The saved background schedule, or the default when none is saved or
+    /// the stored JSON no longer parses.
+    ///
+    /// # Errors
+    /// Returns an error if the settings query fails.
+    pub fn schedule(&self) -> Result<ValidationSchedule, DatabaseError> {
+        Ok(self
+            .setting(SETTING_SCHEDULE)?
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default())
+    }
+
+    /// Persist the background schedule.
+    ///
+    /// # Errors
+    /// Returns an error if the settings write fails.
+    pub fn set_schedule(&self, schedule: &ValidationSchedule) -> Result<(), DatabaseError> {
+        let json = serde_json::to_string(schedule)
+            .map_err(|e| DatabaseError::Migration(format!("Bad validation schedule: {e}")))?;
+        self.set_setting(SETTING_SCHEDULE, &json)
+    }
+
+    /// When the last scheduled run finished.
+    ///
+    /// # Errors
+    /// Returns an error if the settings query fails.
+    pub fn last_run(&self) -> Result<Option<DateTime<Utc>>, DatabaseError> {
+        Ok(self
+            .setting(SETTING_LAST_RUN)?
+            .and_then(|at| DateTime::parse_from_rfc3339(&at).ok())
+            .map(|at| at.with_timezone(&Utc)))
+    }
+
+    /// Record that a scheduled run finished at `at`.
+    ///
+    /// # Errors
+    /// Returns an error if the settings write fails.
+    pub fn set_last_run(&self, at: DateTime<Utc>) -> Result<(), DatabaseError> {
+        self.set_setting(SETTING_LAST_RUN, &at.to_rfc3339())
+    }
+
+    fn setting(&self, key: &str) -> Result<Option<String>, DatabaseError> {
+        self.conn
+            .query_row(
+                "SELECT value FROM app_settings WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    fn set_setting(&self, key: &str, value: &str) -> Result<(), DatabaseError> {
+        self.conn.execute(
+            r"
+            INSERT INTO app_settings (key, value, updated_at)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT(key) DO UPDATE SET
+                value = excluded.value,
+                updated_at = excluded.updated_at
+            ",
+            params![key, value, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+}
+
+/// The current failure streak of a newest-first history, if it follows an
+/// earlier successful validation.
+fn failing_streak(label: String, history: &[KeyValidation]) -> Option<FailingKey> {
+    let streak = history.iter().take_while(|v| !v.valid).count();
+    let last_pass = history.get(streak)?;
+    if streak == 0 {
+        return None;
+    }
+    let newest = &history[0];
+    Some(FailingKey {
+        api_key_id: newest.api_key_id,
+        provider_id: newest.provider_id.clone(),
+        label,
+        last_valid_at: last_pass.validated_at,
+        failing_since: history[streak - 1].validated_at,
+        consecutive_failures: u32::try_from(streak).unwrap_or(u32::MAX),
+        last_message: newest.message.clone(),
+    })
+}
+
+fn row_to_validation(row: &rusqlite::Row<'_>) -> Result<KeyValidation, rusqlite::Error> {
+    let validated_at: String = row.get(3)?;
+    let validated_at = DateTime::parse_from_rfc3339(&validated_at)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, Box::new(e))
+        })?;
+    let latency_ms: i64 = row.get(6)?;
+    Ok(KeyValidation {
+        id: row.get(0)?,
+        api_key_id: row.get(1)?,
+        provider_id: row.get(2)?,
+        validated_at,
+        valid: row.get(4)?,
+        message: row.get(5)?,
+        latency_ms: u64::try_from(latency_ms).unwrap_or(0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::db::Database;
+
+    /// Insert a key row directly; the encrypted columns are irrelevant here
+    /// and going through `ApiKeyStore::save` would need the OS keychain.
+    fn insert_key(conn: &Connection, label: &str) -> i64 {
+        conn.execute(
+            "INSERT INTO api_keys (provider_id, label, encrypted_key, nonce, created_at, updated_at)
+             VALUES ('openai', ?1, 'x', 'x', '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')",
+            params![label],
+        )
+        .unwrap();
+        conn.last_insert_rowid()
+    }
+
+    fn t(hours: i64) -> DateTime<Utc> {
+        "2026-05-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap() + Duration::hours(hours)
+    }
+
+    fn record(store: &KeyValidationStore<'_>, key: i64, valid: bool, hours: i64) {
+        let message = (!valid).then_some("Key rejected by OpenAI (HTTP 401)");
+        store
+            .record(key, "openai", valid, message, 120, t(hours))
+            .unwrap();
+    }
+
+    #[test]
+    fn record_and_list_newest_first() {
+        let db = Database::in_memory().unwrap();
+        let key = insert_key(db.connection(), "work");
+        let store = KeyValidationStore::new(db.connection());
+        record(&store, key, true, 0);
+        record(&store, key, false, 2);
+        record(&store, key, true, 1);
+
+        let history = store.list_for_key(key, 10).unwrap();
+        let valid: Vec<bool> = history.iter().map(|v| v.valid).collect();
+        assert_eq!(valid, vec![false, true, true]);
+        assert_eq!(history[0].latency_ms, 120);
+        assert!(history[0].message.is_some());
+        assert_eq!(store.list_for_key(key, 1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn history_is_pruned_and_cascades() {
+        let db = Database::in_memory().unwrap();
+        let key = insert_key(db.connection(), "work");
+        let store = KeyValidationStore::new(db.connection());
+        for i in 0..=MAX_VALIDATIONS_PER_KEY {
+            record(&store, key, true, i64::try_from(i).unwrap());
+        }
+        let history = store.list_for_key(key, usize::MAX).unwrap();
+        assert_eq!(history.len(), MAX_VALIDATIONS_PER_KEY);
+        assert_eq!(history.last().unwrap().validated_at, t(1), "oldest pruned");
+
+        db.connection()
+            .execute("DELETE FROM api_keys WHERE id = ?1", params![key])
+            .unwrap();
+        assert!(store.list_for_key(key, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn failing_keys_only_reports_regressions() {
+        let db = Database::in_memory().unwrap();
+        let conn = db.connection();
+        let broke = insert_key(conn, "broke");
+        let healthy = insert_key(conn, "healthy");
+        let never = insert_key(conn, "never worked");
+        let recovered = insert_key(conn, "recovered");
+        let store = KeyValidationStore::new(conn);
+
+        record(&store, broke, true, 0);
+        record(&store, broke, true, 1);
+        record(&store, broke, false, 2);
+        record(&store, broke, false, 3);
+
+        record(&store, healthy, true, 0);
+
+        record(&store, never, false, 0);
+        record(&store, never, false, 1);
+
+        record(&store, recovered, false, 0);
+        record(&store, recovered, true, 1);
+
+        let failing = store.failing_keys().unwrap();
+        assert_eq!(failing.len(), 1);
+        let f = &failing[0];
+        assert_eq!(f.api_key_id, broke);
+        assert_eq!(f.label, "broke");
+        assert_eq!(f.consecutive_failures, 2);
+        assert_eq!(f.failing_since, t(2));
+        assert_eq!(f.last_valid_at, t(1));
+        assert!(f.last_message.is_some());
+    }
+
+    #[test]
+    fn schedule_roundtrip_and_due_check() {
+        let db = Database::in_memory().unwrap();
+        let store = KeyValidationStore::new(db.connection());
+        assert_eq!(store.schedule().unwrap(), ValidationSchedule::default());
+
+        let schedule = ValidationSchedule {
+            enabled: true,
+            interval_hours: 6,
+            per_provider_concurrency: 4,
+        };
+        store.set_schedule(&schedule).unwrap();
+        assert_eq!(store.schedule().unwrap(), schedule);
+
+        assert!(store.last_run().unwrap().is_none());
+        store.set_last_run(t(0)).unwrap();
+        let last = store.last_run().unwrap();
+        assert_eq!(last, Some(t(0)));
+
+        assert!(!schedule.is_due(last, t(5)));
+        assert!(schedule.is_due(last, t(6)));
+        assert!(schedule.is_due(None, t(0)));
+        let disabled = ValidationSchedule {
+            enabled: false,
+            ..schedule
+        };
+        assert!(!disabled.is_due(None, t(0)));
+    }
+}
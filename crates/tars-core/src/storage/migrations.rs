@@ -4,7 +4,7 @@ use rusqlite::Connection;
 
 use super::db::DatabaseError;
 
-const CURRENT_VERSION: i32 = 12;
+const CURRENT_VERSION: i32 = 22;
 
 /// Run all pending migrations
 ///
@@ -61,6 +61,46 @@ pub fn run_migrations(conn: &Connection) -> Result<(), DatabaseError> {
         migrate_v12(conn)?;
     }
 
+    if version < 13 {
+        migrate_v13(conn)?;
+    }
+
+    if version < 14 {
+        migrate_v14(conn)?;
+    }
+
+    if version < 15 {
+        migrate_v15(conn)?;
+    }
+
+    if version < 16 {
+        migrate_v16(conn)?;
+    }
+
+    if version < 17 {
+        migrate_v17(conn)?;
+    }
+
+    if version < 18 {
+        migrate_v18(conn)?;
+    }
+
+    if version < 19 {
+        migrate_v19(conn)?;
+    }
+
+    if version < 20 {
+        migrate_v20(conn)?;
+    }
+
+    if version < 21 {
+        migrate_v21(conn)?;
+    }
+
+    if version < 22 {
+        migrate_v22(conn)?;
+    }
+
     conn.pragma_update(None, "user_version", CURRENT_VERSION)?;
     Ok(())
 }
@@ -511,6 +551,278 @@ fn migrate_v12(conn: &Connection) -> Result<(), DatabaseError> {
     Ok(())
 }
 
+fn migrate_v13(conn: &Connection) -> Result<(), DatabaseError> {
+    // User-defined OpenAI-compatible providers (Ollama, LM Studio, vLLM,
+    // gateways). Keys and cached models for these reuse `api_keys` and
+    // `provider_models`, keyed by the provider id string `custom:<slug>`.
+    // `auth_style` is 'bearer' | 'header' | 'none'; `auth_header` holds the
+    // header name when it is 'header'. NULL `models_path` means `/models`.
+    conn.execute_batch(
+        r"
+        CREATE TABLE IF NOT EXISTS custom_providers (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            slug TEXT NOT NULL UNIQUE,
+            display_name TEXT NOT NULL,
+            base_url TEXT NOT NULL,
+            auth_style TEXT NOT NULL DEFAULT 'bearer',
+            auth_header TEXT,
+            models_path TEXT,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        );
+        ",
+    )?;
+
+    Ok(())
+}
+
+fn migrate_v14(conn: &Connection) -> Result<(), DatabaseError> {
+    // Balance history: one row per successful balance fetch so the desktop
+    // can chart spend over time. `api_keys.balance_json` keeps holding the
+    // latest raw payload; snapshots store only the normalised amount.
+    // `balance_threshold` is in the key's balance currency; NULL disables the
+    // low-balance warning.
+    conn.execute_batch(
+        r"
+        ALTER TABLE api_keys ADD COLUMN balance_threshold REAL;
+
+        CREATE TABLE IF NOT EXISTS balance_snapshots (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            api_key_id INTEGER NOT NULL REFERENCES api_keys(id) ON DELETE CASCADE,
+            provider_id TEXT NOT NULL,
+            currency TEXT NOT NULL,
+            amount REAL NOT NULL,
+            recorded_at TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_balance_snapshots_key_time
+            ON balance_snapshots(api_key_id, recorded_at);
+        ",
+    )
+    .map_err(|e| DatabaseError::Migration(format!("v14 balance history migration failed: {e}")))?;
+
+    Ok(())
+}
+
+fn migrate_v15(conn: &Connection) -> Result<(), DatabaseError> {
+    // Prompt-caching and long-context (>200k prompt tokens) rates published
+    // by LiteLLM, all USD per 1M tokens. NULL means the source does not
+    // publish that dimension for the model.
+    conn.execute_batch(
+        r"
+        ALTER TABLE provider_models ADD COLUMN cache_read_price REAL;
+        ALTER TABLE provider_models ADD COLUMN cache_write_price REAL;
+        ALTER TABLE provider_models ADD COLUMN input_price_above_200k REAL;
+        ALTER TABLE provider_models ADD COLUMN output_price_above_200k REAL;
+        ALTER TABLE provider_models ADD COLUMN cache_read_price_above_200k REAL;
+        ALTER TABLE provider_models ADD COLUMN cache_write_price_above_200k REAL;
+        ",
+    )
+    .map_err(|e| DatabaseError::Migration(format!("v15 tiered pricing migration failed: {e}")))?;
+
+    Ok(())
+}
+
+fn migrate_v16(conn: &Connection) -> Result<(), DatabaseError> {
+    // Key validation history: one row per validation verdict (manual or
+    // scheduled). `api_keys.last_valid` keeps holding only the latest result.
+    conn.execute_batch(
+        r"
+        CREATE TABLE IF NOT EXISTS key_validations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            api_key_id INTEGER NOT NULL REFERENCES api_keys(id) ON DELETE CASCADE,
+            provider_id TEXT NOT NULL,
+            validated_at TEXT NOT NULL,
+            valid INTEGER NOT NULL,
+            message TEXT,
+            latency_ms INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_key_validations_key_time
+            ON key_validations(api_key_id, validated_at);
+        ",
+    )
+    .map_err(|e| {
+        DatabaseError::Migration(format!("v16 key validation history migration failed: {e}"))
+    })?;
+
+    Ok(())
+}
+
+fn migrate_v17(conn: &Connection) -> Result<(), DatabaseError> {
+    // Profile revision history. File contents live once in
+    // `profile_revision_blobs`, keyed by their SHA256; each revision lists
+    // the blobs making up `~/.tars/profiles/<id>/` at that point next to the
+    // profile row it was recorded from.
+    conn.execute_batch(
+        r"
+        CREATE TABLE IF NOT EXISTS profile_revision_blobs (
+            hash TEXT PRIMARY KEY,
+            content BLOB NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS profile_revisions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            profile_id TEXT NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+            hash TEXT NOT NULL,
+            data TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_profile_revisions_profile
+            ON profile_revisions(profile_id, id);
+        CREATE INDEX IF NOT EXISTS idx_profile_revisions_hash
+            ON profile_revisions(hash);
+
+        CREATE TABLE IF NOT EXISTS profile_revision_files (
+            revision_id INTEGER NOT NULL REFERENCES profile_revisions(id) ON DELETE CASCADE,
+            path TEXT NOT NULL,
+            blob_hash TEXT NOT NULL REFERENCES profile_revision_blobs(hash),
+            PRIMARY KEY (revision_id, path)
+        );
+        CREATE INDEX IF NOT EXISTS idx_profile_revision_files_blob
+            ON profile_revision_files(blob_hash);
+        ",
+    )
+    .map_err(|e| {
+        DatabaseError::Migration(format!("v17 profile revisions migration failed: {e}"))
+    })?;
+
+    Ok(())
+}
+
+fn migrate_v18(conn: &Connection) -> Result<(), DatabaseError> {
+    // Git repositories shared as profile sources. Each profile directory in
+    // a repository is linked to one local profile; `synced_hash` is the
+    // content hash both sides agreed on at the last pull, so upstream and
+    // local edits can be told apart.
+    conn.execute_batch(
+        r"
+        CREATE TABLE IF NOT EXISTS profile_repos (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL UNIQUE,
+            url TEXT NOT NULL,
+            branch TEXT NOT NULL,
+            head_commit TEXT,
+            last_synced_at TEXT,
+            created_at TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS profile_repo_links (
+            repo_id TEXT NOT NULL REFERENCES profile_repos(id) ON DELETE CASCADE,
+            path TEXT NOT NULL,
+            profile_id TEXT NOT NULL UNIQUE REFERENCES profiles(id) ON DELETE CASCADE,
+            synced_hash TEXT,
+            PRIMARY KEY (repo_id, path)
+        );
+        ",
+    )
+    .map_err(|e| DatabaseError::Migration(format!("v18 profile repos migration failed: {e}")))?;
+
+    Ok(())
+}
+
+fn migrate_v19(conn: &Connection) -> Result<(), DatabaseError> {
+    // Rules that pick a profile for newly added projects. `conditions` is a
+    // JSON object of match lists; higher `priority` wins when several match.
+    conn.execute_batch(
+        r"
+        CREATE TABLE IF NOT EXISTS assignment_rules (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            profile_id TEXT NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+            priority INTEGER NOT NULL DEFAULT 0,
+            conditions TEXT NOT NULL,
+            auto_apply INTEGER NOT NULL DEFAULT 0,
+            enabled INTEGER NOT NULL DEFAULT 1,
+            created_at TEXT NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_assignment_rules_priority
+            ON assignment_rules(priority DESC);
+        ",
+    )
+    .map_err(|e| DatabaseError::Migration(format!("v19 assignment rules migration failed: {e}")))?;
+
+    Ok(())
+}
+
+fn migrate_v20(conn: &Connection) -> Result<(), DatabaseError> {
+    // A batch applies one profile to every project it is assigned to. Each
+    // project still gets its own backup; the batch ties them together so a
+    // single rollback restores all of them.
+    conn.execute_batch(
+        r"
+        CREATE TABLE IF NOT EXISTS apply_batches (
+            id TEXT PRIMARY KEY,
+            profile_id TEXT NOT NULL,
+            profile_name TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            rolled_back_at TEXT
+        );
+
+        CREATE TABLE IF NOT EXISTS apply_batch_projects (
+            batch_id TEXT NOT NULL REFERENCES apply_batches(id) ON DELETE CASCADE,
+            project_id TEXT NOT NULL,
+            project_name TEXT NOT NULL,
+            status TEXT NOT NULL,
+            operations INTEGER NOT NULL DEFAULT 0,
+            backup_id TEXT,
+            error TEXT,
+            PRIMARY KEY (batch_id, project_id)
+        );
+        ",
+    )
+    .map_err(|e| DatabaseError::Migration(format!("v20 apply batches migration failed: {e}")))?;
+
+    Ok(())
+}
+
+fn migrate_v21(conn: &Connection) -> Result<(), DatabaseError> {
+    // The bundle a copy deployment was materialized from, file by file. It is
+    // the common base for classifying drift per file and for three-way merges
+    // between the library source and the deployed copy.
+    conn.execute_batch(
+        r"
+        CREATE TABLE IF NOT EXISTS skill_deployment_files (
+            deployment_id INTEGER NOT NULL REFERENCES skill_deployments(id) ON DELETE CASCADE,
+            path TEXT NOT NULL,
+            sha256 TEXT NOT NULL,
+            content BLOB NOT NULL,
+            PRIMARY KEY (deployment_id, path)
+        );
+        ",
+    )
+    .map_err(|e| {
+        DatabaseError::Migration(format!("v21 skill deployment files migration failed: {e}"))
+    })?;
+
+    Ok(())
+}
+
+fn migrate_v22(conn: &Connection) -> Result<(), DatabaseError> {
+    // Where a skill installed from git came from, so upstream commits can be
+    // checked and the install moved (or pinned) to another commit.
+    conn.execute_batch(
+        r"
+        CREATE TABLE IF NOT EXISTS skill_origins (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            skill_name TEXT NOT NULL,
+            install_path TEXT NOT NULL UNIQUE,
+            repo_url TEXT NOT NULL,
+            git_ref TEXT,
+            commit_sha TEXT NOT NULL,
+            subpath TEXT NOT NULL DEFAULT '',
+            pinned INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_skill_origins_name ON skill_origins(skill_name);
+        ",
+    )
+    .map_err(|e| DatabaseError::Migration(format!("v22 skill origins migration failed: {e}")))?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -841,6 +1153,205 @@ mod tests {
         assert!(dup.is_err(), "duplicate key should violate PK");
     }
 
+    #[test]
+    fn v13_creates_custom_providers_table() {
+        let conn = fresh_conn();
+        let cols = table_columns(&conn, "custom_providers");
+        for expected in [
+            "id",
+            "slug",
+            "display_name",
+            "base_url",
+            "auth_style",
+            "auth_header",
+            "models_path",
+            "created_at",
+            "updated_at",
+        ] {
+            assert!(
+                cols.contains(&expected.to_string()),
+                "missing col {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn v14_adds_balance_threshold_and_snapshots() {
+        let conn = fresh_conn();
+        assert!(table_columns(&conn, "api_keys").contains(&"balance_threshold".to_string()));
+        let cols = table_columns(&conn, "balance_snapshots");
+        for expected in [
+            "id",
+            "api_key_id",
+            "provider_id",
+            "currency",
+            "amount",
+            "recorded_at",
+        ] {
+            assert!(
+                cols.contains(&expected.to_string()),
+                "missing col {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn v15_adds_cache_and_tier_price_columns() {
+        let conn = fresh_conn();
+        let cols = table_columns(&conn, "provider_models");
+        for expected in [
+            "cache_read_price",
+            "cache_write_price",
+            "input_price_above_200k",
+            "output_price_above_200k",
+            "cache_read_price_above_200k",
+            "cache_write_price_above_200k",
+        ] {
+            assert!(
+                cols.contains(&expected.to_string()),
+                "missing col {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn v16_creates_key_validations_table() {
+        let conn = fresh_conn();
+        let cols = table_columns(&conn, "key_validations");
+        for expected in [
+            "id",
+            "api_key_id",
+            "provider_id",
+            "validated_at",
+            "valid",
+            "message",
+            "latency_ms",
+        ] {
+            assert!(
+                cols.contains(&expected.to_string()),
+                "missing col {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn v17_creates_profile_revision_tables() {
+        let conn = fresh_conn();
+        for (table, expected) in [
+            ("profile_revision_blobs", &["hash", "content"][..]),
+            (
+                "profile_revisions",
+                &["id", "profile_id", "hash", "data", "created_at"][..],
+            ),
+            (
+                "profile_revision_files",
+                &["revision_id", "path", "blob_hash"][..],
+            ),
+        ] {
+            let cols = table_columns(&conn, table);
+            for col in expected {
+                assert!(cols.contains(&(*col).to_string()), "missing {table}.{col}");
+            }
+        }
+    }
+
+    #[test]
+    fn v18_creates_profile_repo_tables() {
+        let conn = fresh_conn();
+        for (table, expected) in [
+            (
+                "profile_repos",
+                &[
+                    "id",
+                    "name",
+                    "url",
+                    "branch",
+                    "head_commit",
+                    "last_synced_at",
+                ][..],
+            ),
+            (
+                "profile_repo_links",
+                &["repo_id", "path", "profile_id", "synced_hash"][..],
+            ),
+        ] {
+            let cols = table_columns(&conn, table);
+            for col in expected {
+                assert!(cols.contains(&(*col).to_string()), "missing {table}.{col}");
+            }
+        }
+    }
+
+    #[test]
+    fn v19_creates_assignment_rules_table() {
+        let conn = fresh_conn();
+        let cols = table_columns(&conn, "assignment_rules");
+        for col in [
+            "id",
+            "name",
+            "profile_id",
+            "priority",
+            "conditions",
+            "auto_apply",
+            "enabled",
+        ] {
+            assert!(cols.contains(&col.to_string()), "missing {col}");
+        }
+    }
+
+    #[test]
+    fn v20_creates_apply_batch_tables() {
+        let conn = fresh_conn();
+        let cols = table_columns(&conn, "apply_batches");
+        for col in [
+            "id",
+            "profile_id",
+            "profile_name",
+            "created_at",
+            "rolled_back_at",
+        ] {
+            assert!(cols.contains(&col.to_string()), "missing {col}");
+        }
+        let cols = table_columns(&conn, "apply_batch_projects");
+        for col in [
+            "batch_id",
+            "project_id",
+            "project_name",
+            "status",
+            "operations",
+            "backup_id",
+            "error",
+        ] {
+            assert!(cols.contains(&col.to_string()), "missing {col}");
+        }
+    }
+
+    #[test]
+    fn v21_creates_skill_deployment_files() {
+        let conn = fresh_conn();
+        let cols = table_columns(&conn, "skill_deployment_files");
+        for col in ["deployment_id", "path", "sha256", "content"] {
+            assert!(cols.contains(&col.to_string()), "missing {col}");
+        }
+    }
+
+    #[test]
+    fn v22_creates_skill_origins() {
+        let conn = fresh_conn();
+        let cols = table_columns(&conn, "skill_origins");
+        for col in [
+            "skill_name",
+            "install_path",
+            "repo_url",
+            "git_ref",
+            "commit_sha",
+            "subpath",
+            "pinned",
+        ] {
+            assert!(cols.contains(&col.to_string()), "missing {col}");
+        }
+    }
+
     #[test]
     fn migrations_are_idempotent() {
         let conn = Connection::open_in_memory().unwrap();
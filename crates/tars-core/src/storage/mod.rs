@@ -1,32 +1,49 @@
 //! Storage layer (`SQLite` + file bundles)
 
 pub mod api_keys;
+pub mod apply_batches;
+pub mod assignment_rules;
 pub mod backups;
+pub mod balance_history;
+pub mod custom_providers;
 pub mod db;
 pub mod developer;
+pub mod key_validations;
 pub mod metadata;
 pub mod migrations;
 pub mod model_cache;
 pub mod plugin_subscriptions;
 pub mod plugin_versions;
+pub mod profile_repos;
+pub mod profile_revisions;
 pub mod profiles;
 pub mod projects;
 pub mod secrets;
 pub mod skill_library;
 
 pub use api_keys::ApiKeyStore;
+pub use apply_batches::{ApplyBatch, ApplyBatchEntry, ApplyBatchStore, BatchProjectStatus};
+pub use assignment_rules::{AssignmentRule, AssignmentRuleStore, RuleConditions};
 pub use backups::BackupStore;
+pub use balance_history::{BalanceHistoryStore, BalanceSnapshot};
+pub use custom_providers::{CustomProvider, CustomProviderInput, CustomProviderStore};
 pub use db::Database;
 pub use developer::DeveloperStore;
+pub use key_validations::{FailingKey, KeyValidation, KeyValidationStore, ValidationSchedule};
 pub use metadata::MetadataStore;
 pub use model_cache::{CachedModel, ModelCache, ModelRow};
 pub use plugin_subscriptions::{
     PluginSubscription, PluginSubscriptionInput, PluginSubscriptionStore,
 };
 pub use plugin_versions::PluginVersionStore;
+pub use profile_repos::{ProfileRepo, ProfileRepoLink, ProfileRepoStore};
+pub use profile_revisions::{
+    ProfileRevision, ProfileRevisionStore, RevisionFiles, RevisionSnapshot,
+};
 pub use profiles::ProfileStore;
 pub use projects::ProjectStore;
 pub use secrets::SecretStore;
 pub use skill_library::{
-    SkillDeployment, SkillDeploymentInput, SkillDeploymentStore, SkillSource, SkillSourceStore,
+    SkillDeployment, SkillDeploymentInput, SkillDeploymentStore, SkillOrigin, SkillOriginInput,
+    SkillOriginStore, SkillSource, SkillSourceStore,
 };
@@ -0,0 +1,262 @@
+//! Git profile repository storage
+//!
+//! Backs the tables created in migration v18. A repository row records where
+//! a team's profiles are shared; each link ties a profile directory in the
+//! repository to the local profile it was pulled into or pushed from.
+
+use crate::storage::db::DatabaseError;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+const REPO_COLUMNS: &str = "id, name, url, branch, head_commit, last_synced_at, created_at";
+
+/// A registered profile repository
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileRepo {
+    pub id: Uuid,
+    pub name: String,
+    /// Remote URL or local path, as passed to `git clone`
+    pub url: String,
+    /// Branch profiles are pulled from
+    pub branch: String,
+    /// Upstream commit of the last pull
+    pub head_commit: Option<String>,
+    pub last_synced_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ProfileRepo {
+    /// A new repository record
+    #[must_use]
+    pub fn new(name: String, url: String, branch: String) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name,
+            url,
+            branch,
+            head_commit: None,
+            last_synced_at: None,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// A profile directory in a repository linked to a local profile
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileRepoLink {
+    pub repo_id: Uuid,
+    /// Directory of the profile in the repository
+    pub path: String,
+    pub profile_id: Uuid,
+    /// Content hash both sides had at the last sync; `None` until the
+    /// profile has been seen upstream
+    pub synced_hash: Option<String>,
+}
+
+/// Profile repository storage operations
+pub struct ProfileRepoStore<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> ProfileRepoStore<'a> {
+    /// Create a new profile repository store
+    #[must_use]
+    pub fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    /// Register a repository
+    ///
+    /// # Errors
+    /// Returns an error if the repository cannot be stored, e.g. the name is taken
+    pub fn create(&self, repo: &ProfileRepo) -> Result<(), DatabaseError> {
+        self.conn.execute(
+            r"
+            INSERT INTO profile_repos
+                (id, name, url, branch, head_commit, last_synced_at, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            ",
+            params![
+                repo.id.to_string(),
+                repo.name,
+                repo.url,
+                repo.branch,
+                repo.head_commit,
+                repo.last_synced_at.map(|t| t.to_rfc3339()),
+                repo.created_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Get a repository by ID
+    ///
+    /// # Errors
+    /// Returns an error if the query fails
+    pub fn get(&self, id: Uuid) -> Result<Option<ProfileRepo>, DatabaseError> {
+        let sql = format!("SELECT {REPO_COLUMNS} FROM profile_repos WHERE id = ?1");
+        self.conn
+            .query_row(&sql, params![id.to_string()], row_to_repo)
+            .optional()
+            .map_err(DatabaseError::from)
+    }
+
+    /// Get a repository by name
+    ///
+    /// # Errors
+    /// Returns an error if the query fails
+    pub fn get_by_name(&self, name: &str) -> Result<Option<ProfileRepo>, DatabaseError> {
+        let sql = format!("SELECT {REPO_COLUMNS} FROM profile_repos WHERE name = ?1");
+        self.conn
+            .query_row(&sql, params![name], row_to_repo)
+            .optional()
+            .map_err(DatabaseError::from)
+    }
+
+    /// List all repositories by name
+    ///
+    /// # Errors
+    /// Returns an error if the query fails
+    pub fn list(&self) -> Result<Vec<ProfileRepo>, DatabaseError> {
+        let sql = format!("SELECT {REPO_COLUMNS} FROM profile_repos ORDER BY name");
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map([], row_to_repo)?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(DatabaseError::from)
+    }
+
+    /// Record the upstream commit of a pull
+    ///
+    /// # Errors
+    /// Returns an error if the update fails
+    pub fn set_head(&self, id: Uuid, commit: Option<&str>) -> Result<(), DatabaseError> {
+        self.conn.execute(
+            "UPDATE profile_repos SET head_commit = ?1, last_synced_at = ?2 WHERE id = ?3",
+            params![commit, Utc::now().to_rfc3339(), id.to_string()],
+        )?;
+        Ok(())
+    }
+
+    /// Delete a repository and its links; linked profiles are kept
+    ///
+    /// # Errors
+    /// Returns an error if the delete fails
+    pub fn delete(&self, id: Uuid) -> Result<bool, DatabaseError> {
+        let deleted = self.conn.execute(
+            "DELETE FROM profile_repos WHERE id = ?1",
+            params![id.to_string()],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    /// List a repository's links by path
+    ///
+    /// # Errors
+    /// Returns an error if the query fails
+    pub fn links(&self, repo_id: Uuid) -> Result<Vec<ProfileRepoLink>, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            r"
+            SELECT repo_id, path, profile_id, synced_hash
+            FROM profile_repo_links WHERE repo_id = ?1 ORDER BY path
+            ",
+        )?;
+        let rows = stmt.query_map(params![repo_id.to_string()], row_to_link)?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(DatabaseError::from)
+    }
+
+    /// Get the link of a local profile, if it belongs to a repository
+    ///
+    /// # Errors
+    /// Returns an error if the query fails
+    pub fn link_for_profile(
+        &self,
+        profile_id: Uuid,
+    ) -> Result<Option<ProfileRepoLink>, DatabaseError> {
+        self.conn
+            .query_row(
+                r"
+                SELECT repo_id, path, profile_id, synced_hash
+                FROM profile_repo_links WHERE profile_id = ?1
+                ",
+                params![profile_id.to_string()],
+                row_to_link,
+            )
+            .optional()
+            .map_err(DatabaseError::from)
+    }
+
+    /// Create or replace the link for a repository path
+    ///
+    /// # Errors
+    /// Returns an error if the link cannot be stored, e.g. the profile is
+    /// already linked elsewhere
+    pub fn set_link(&self, link: &ProfileRepoLink) -> Result<(), DatabaseError> {
+        self.conn.execute(
+            r"
+            INSERT INTO profile_repo_links (repo_id, path, profile_id, synced_hash)
+            VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT(repo_id, path) DO UPDATE SET
+                profile_id = excluded.profile_id,
+                synced_hash = excluded.synced_hash
+            ",
+            params![
+                link.repo_id.to_string(),
+                link.path,
+                link.profile_id.to_string(),
+                link.synced_hash,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Remove the link for a repository path
+    ///
+    /// # Errors
+    /// Returns an error if the delete fails
+    pub fn unlink(&self, repo_id: Uuid, path: &str) -> Result<bool, DatabaseError> {
+        let deleted = self.conn.execute(
+            "DELETE FROM profile_repo_links WHERE repo_id = ?1 AND path = ?2",
+            params![repo_id.to_string(), path],
+        )?;
+        Ok(deleted > 0)
+    }
+}
+
+fn parse_uuid(value: &str) -> Result<Uuid, rusqlite::Error> {
+    Uuid::parse_str(value).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
+}
+
+fn parse_time(value: &str) -> Result<DateTime<Utc>, rusqlite::Error> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
+}
+
+fn row_to_repo(row: &rusqlite::Row<'_>) -> Result<ProfileRepo, rusqlite::Error> {
+    let id: String = row.get(0)?;
+    let last_synced_at: Option<String> = row.get(5)?;
+    let created_at: String = row.get(6)?;
+    Ok(ProfileRepo {
+        id: parse_uuid(&id)?,
+        name: row.get(1)?,
+        url: row.get(2)?,
+        branch: row.get(3)?,
+        head_commit: row.get(4)?,
+        last_synced_at: last_synced_at.as_deref().map(parse_time).transpose()?,
+        created_at: parse_time(&created_at)?,
+    })
+}
+
+fn row_to_link(row: &rusqlite::Row<'_>) -> Result<ProfileRepoLink, rusqlite::Error> {
+    let repo_id: String = row.get(0)?;
+    let profile_id: String = row.get(2)?;
+    Ok(ProfileRepoLink {
+        repo_id: parse_uuid(&repo_id)?,
+        path: row.get(1)?,
+        profile_id: parse_uuid(&profile_id)?,
+        synced_hash: row.get(3)?,
+    })
+}
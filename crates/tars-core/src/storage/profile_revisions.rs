@@ -0,0 +1,263 @@
+//! Profile revision history storage
+//!
+//! Backs the tables created in migration v17. A revision pairs the profile
+//! row with the files of its storage directory at the time it was recorded.
+//! File contents are stored once per distinct SHA256 in
+//! `profile_revision_blobs`, and the revision hash covers both the profile
+//! (minus `updated_at`) and every file, so identical states share a hash.
+
+use crate::profile::Profile;
+use crate::storage::db::DatabaseError;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+const REVISION_COLUMNS: &str = "r.id, r.profile_id, r.hash, r.created_at, \
+     (SELECT COUNT(*) FROM profile_revision_files f WHERE f.revision_id = r.id)";
+
+/// Files of a profile's storage directory, keyed by `/`-separated relative path
+pub type RevisionFiles = BTreeMap<String, Vec<u8>>;
+
+/// A recorded profile revision
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileRevision {
+    /// Sequence number, increasing with time
+    pub id: i64,
+    pub profile_id: Uuid,
+    /// Content hash of the profile and its files
+    pub hash: String,
+    pub created_at: DateTime<Utc>,
+    pub file_count: usize,
+}
+
+impl ProfileRevision {
+    /// Abbreviated hash for display
+    #[must_use]
+    pub fn short_hash(&self) -> &str {
+        &self.hash[..self.hash.len().min(12)]
+    }
+}
+
+/// The full content of a revision
+#[derive(Debug, Clone)]
+pub struct RevisionSnapshot {
+    pub revision: ProfileRevision,
+    pub profile: Profile,
+    pub files: RevisionFiles,
+}
+
+/// Profile revision storage operations
+pub struct ProfileRevisionStore<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> ProfileRevisionStore<'a> {
+    /// Create a new revision store
+    #[must_use]
+    pub fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    /// Record a revision unless the profile's latest revision has the same hash
+    ///
+    /// Returns the new revision, or `None` when nothing changed.
+    ///
+    /// # Errors
+    /// Returns an error if the revision cannot be stored
+    pub fn record(
+        &self,
+        profile: &Profile,
+        files: &RevisionFiles,
+    ) -> Result<Option<ProfileRevision>, DatabaseError> {
+        let hash = revision_hash(profile, files)?;
+        if self
+            .latest(profile.id)?
+            .is_some_and(|latest| latest.hash == hash)
+        {
+            return Ok(None);
+        }
+
+        let data = serde_json::to_string(profile)
+            .map_err(|e| DatabaseError::Migration(format!("Failed to serialize profile: {e}")))?;
+        let created_at = Utc::now();
+
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            r"
+            INSERT INTO profile_revisions (profile_id, hash, data, created_at)
+            VALUES (?1, ?2, ?3, ?4)
+            ",
+            params![profile.id.to_string(), hash, data, created_at.to_rfc3339()],
+        )?;
+        let id = tx.last_insert_rowid();
+
+        for (path, content) in files {
+            let blob_hash = format!("{:x}", Sha256::digest(content));
+            tx.execute(
+                "INSERT OR IGNORE INTO profile_revision_blobs (hash, content) VALUES (?1, ?2)",
+                params![blob_hash, content],
+            )?;
+            tx.execute(
+                r"
+                INSERT INTO profile_revision_files (revision_id, path, blob_hash)
+                VALUES (?1, ?2, ?3)
+                ",
+                params![id, path, blob_hash],
+            )?;
+        }
+        tx.commit()?;
+
+        Ok(Some(ProfileRevision {
+            id,
+            profile_id: profile.id,
+            hash,
+            created_at,
+            file_count: files.len(),
+        }))
+    }
+
+    /// List a profile's revisions, newest first
+    ///
+    /// # Errors
+    /// Returns an error if the revisions cannot be listed
+    pub fn list(&self, profile_id: Uuid) -> Result<Vec<ProfileRevision>, DatabaseError> {
+        let sql = format!(
+            "SELECT {REVISION_COLUMNS} FROM profile_revisions r \
+             WHERE r.profile_id = ?1 ORDER BY r.id DESC"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![profile_id.to_string()], row_to_revision)?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(DatabaseError::from)
+    }
+
+    /// Get a profile's most recent revision
+    ///
+    /// # Errors
+    /// Returns an error if the query fails
+    pub fn latest(&self, profile_id: Uuid) -> Result<Option<ProfileRevision>, DatabaseError> {
+        let sql = format!(
+            "SELECT {REVISION_COLUMNS} FROM profile_revisions r \
+             WHERE r.profile_id = ?1 ORDER BY r.id DESC LIMIT 1"
+        );
+        self.conn
+            .query_row(&sql, params![profile_id.to_string()], row_to_revision)
+            .optional()
+            .map_err(DatabaseError::from)
+    }
+
+    /// Find revisions whose hash starts with `prefix`, across all profiles
+    ///
+    /// Returns the newest revision for each distinct matching hash, so a
+    /// state that was reverted to appears once.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails
+    pub fn find(&self, prefix: &str) -> Result<Vec<ProfileRevision>, DatabaseError> {
+        if prefix.is_empty() || !prefix.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Ok(Vec::new());
+        }
+        let sql = format!(
+            "SELECT {REVISION_COLUMNS} FROM profile_revisions r \
+             WHERE r.id IN ( \
+                 SELECT MAX(id) FROM profile_revisions \
+                 WHERE hash LIKE ?1 || '%' GROUP BY profile_id, hash \
+             ) ORDER BY r.id DESC"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![prefix.to_ascii_lowercase()], row_to_revision)?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(DatabaseError::from)
+    }
+
+    /// Load the profile and files of a revision
+    ///
+    /// # Errors
+    /// Returns an error if the revision's data cannot be read
+    pub fn snapshot(&self, revision: &ProfileRevision) -> Result<RevisionSnapshot, DatabaseError> {
+        let data: String = self.conn.query_row(
+            "SELECT data FROM profile_revisions WHERE id = ?1",
+            params![revision.id],
+            |row| row.get(0),
+        )?;
+        let profile: Profile = serde_json::from_str(&data)
+            .map_err(|e| DatabaseError::Migration(format!("Failed to parse profile: {e}")))?;
+
+        let mut stmt = self.conn.prepare(
+            r"
+            SELECT f.path, b.content
+            FROM profile_revision_files f
+            JOIN profile_revision_blobs b ON b.hash = f.blob_hash
+            WHERE f.revision_id = ?1
+            ",
+        )?;
+        let files = stmt
+            .query_map(params![revision.id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<RevisionFiles, _>>()?;
+
+        Ok(RevisionSnapshot {
+            revision: revision.clone(),
+            profile,
+            files,
+        })
+    }
+
+    /// Delete blobs no revision refers to any more
+    ///
+    /// # Errors
+    /// Returns an error if the delete fails
+    pub fn prune_blobs(&self) -> Result<usize, DatabaseError> {
+        Ok(self.conn.execute(
+            r"
+            DELETE FROM profile_revision_blobs
+            WHERE hash NOT IN (SELECT blob_hash FROM profile_revision_files)
+            ",
+            [],
+        )?)
+    }
+}
+
+/// Content hash of a profile state
+///
+/// Covers the serialized profile without `updated_at`, then each file's path
+/// and SHA256 in path order.
+///
+/// # Errors
+/// Returns an error if the profile cannot be serialized
+pub fn revision_hash(profile: &Profile, files: &RevisionFiles) -> Result<String, DatabaseError> {
+    // Going through `Value` sorts object keys, including HashMap fields
+    let mut value = serde_json::to_value(profile)
+        .map_err(|e| DatabaseError::Migration(format!("Failed to serialize profile: {e}")))?;
+    if let Some(object) = value.as_object_mut() {
+        object.remove("updated_at");
+    }
+
+    let mut hasher = Sha256::new();
+    hasher.update(value.to_string().as_bytes());
+    for (path, content) in files {
+        hasher.update(b"\n");
+        hasher.update(path.as_bytes());
+        hasher.update(b"\0");
+        hasher.update(format!("{:x}", Sha256::digest(content)).as_bytes());
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn row_to_revision(row: &rusqlite::Row<'_>) -> Result<ProfileRevision, rusqlite::Error> {
+    let profile_id: String = row.get(1)?;
+    let created_at: String = row.get(3)?;
+    let file_count: i64 = row.get(4)?;
+    Ok(ProfileRevision {
+        id: row.get(0)?,
+        profile_id: Uuid::parse_str(&profile_id)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?,
+        hash: row.get(2)?,
+        created_at: DateTime::parse_from_rfc3339(&created_at)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?
+            .with_timezone(&Utc),
+        file_count: usize::try_from(file_count).unwrap_or(0),
+    })
+}
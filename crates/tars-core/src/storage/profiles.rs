@@ -1,7 +1,9 @@
 //! Profile storage operations (CRUD)
 
-use crate::profile::Profile;
+use crate::profile::history::read_profile_files;
+use crate::profile::{Profile, StorageError};
 use crate::storage::db::DatabaseError;
+use crate::storage::profile_revisions::{ProfileRevision, ProfileRevisionStore, RevisionFiles};
 use chrono::{DateTime, Utc};
 use rusqlite::{params, Connection};
 use uuid::Uuid;
@@ -40,6 +42,7 @@ impl<'a> ProfileStore<'a> {
             ],
         )?;
 
+        self.record_revision(profile)?;
         Ok(())
     }
 
@@ -104,16 +107,42 @@ impl<'a> ProfileStore<'a> {
     /// # Errors
     /// Returns an error if the profiles cannot be listed
     pub fn list(&self) -> Result<Vec<ProfileSummary>, DatabaseError> {
-        let mut stmt = self.conn.prepare(
+        self.query_summaries(
             r"
             SELECT id, name, description, created_at, updated_at,
                    COALESCE(json_array_length(data, '$.tool_refs'), 0) as tool_count
             FROM profiles
             ORDER BY name
             ",
-        )?;
+            [],
+        )
+    }
 
-        let rows = stmt.query_map([], |row| {
+    /// List the profiles whose `extends` includes `parent`
+    ///
+    /// # Errors
+    /// Returns an error if the profiles cannot be listed
+    pub fn list_extending(&self, parent: Uuid) -> Result<Vec<ProfileSummary>, DatabaseError> {
+        self.query_summaries(
+            r"
+            SELECT DISTINCT p.id, p.name, p.description, p.created_at, p.updated_at,
+                   COALESCE(json_array_length(p.data, '$.tool_refs'), 0) as tool_count
+            FROM profiles p, json_each(p.data, '$.extends') e
+            WHERE e.value = ?1
+            ORDER BY p.name
+            ",
+            params![parent.to_string()],
+        )
+    }
+
+    fn query_summaries<P: rusqlite::Params>(
+        &self,
+        sql: &str,
+        query_params: P,
+    ) -> Result<Vec<ProfileSummary>, DatabaseError> {
+        let mut stmt = self.conn.prepare(sql)?;
+
+        let rows = stmt.query_map(query_params, |row| {
             let id_str: String = row.get(0)?;
             let name: String = row.get(1)?;
             let description: Option<String> = row.get(2)?;
@@ -186,9 +215,34 @@ impl<'a> ProfileStore<'a> {
             )));
         }
 
+        self.record_revision(profile)?;
         Ok(())
     }
 
+    /// Record a revision of a stored profile and its storage directory
+    ///
+    /// Called by [`Self::create`] and [`Self::update`]; call it directly after
+    /// changing a profile's stored tool files without touching its row. Does
+    /// nothing if the state matches the latest revision.
+    ///
+    /// # Errors
+    /// Returns an error if the files cannot be read or the revision stored
+    pub fn record_revision(
+        &self,
+        profile: &Profile,
+    ) -> Result<Option<ProfileRevision>, DatabaseError> {
+        let files = match read_profile_files(profile.id) {
+            Ok(files) => files,
+            Err(StorageError::NoHomeDir) => RevisionFiles::new(),
+            Err(e) => {
+                return Err(DatabaseError::Migration(format!(
+                    "Failed to snapshot profile files: {e}"
+                )))
+            }
+        };
+        ProfileRevisionStore::new(self.conn).record(profile, &files)
+    }
+
     /// Delete a profile
     ///
     /// # Errors
@@ -200,6 +254,8 @@ impl<'a> ProfileStore<'a> {
             ",
             params![id.to_string()],
         )?;
+        // Revisions go with the profile; drop the file contents only they used
+        ProfileRevisionStore::new(self.conn).prune_blobs()?;
 
         Ok(deleted > 0)
     }
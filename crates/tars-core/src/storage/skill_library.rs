@@ -5,11 +5,15 @@
 //! skill has been materialized — per agent (Claude / Codex) and scope
 //! (user / project) — so TARS can reconcile the on/off state of every
 //! (skill × agent × scope) target. A deployment row's presence is the
-//! "on" state; its absence is "off".
+//! "on" state; its absence is "off". `skill_origins` record the repository,
+//! ref and commit a skill installed from git came from.
+
+use std::collections::BTreeMap;
 
 use chrono::{DateTime, Utc};
 use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 use super::db::DatabaseError;
 
@@ -62,6 +66,38 @@ pub struct SkillDeploymentInput {
     pub sha256: Option<String>,
 }
 
+/// The git origin of an installed skill bundle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillOrigin {
+    pub id: i64,
+    pub skill_name: String,
+    /// Absolute path of the installed bundle directory.
+    pub install_path: String,
+    pub repo_url: String,
+    /// Branch or tag followed by updates; `None` follows the remote `HEAD`.
+    pub git_ref: Option<String>,
+    /// Commit the installed bundle was taken from.
+    pub commit_sha: String,
+    /// Bundle directory within the repository (`""` for the repository root).
+    pub subpath: String,
+    /// Pinned origins stay on `commit_sha` until explicitly moved.
+    pub pinned: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Input for recording where an installed bundle came from.
+#[derive(Debug, Clone)]
+pub struct SkillOriginInput {
+    pub skill_name: String,
+    pub install_path: String,
+    pub repo_url: String,
+    pub git_ref: Option<String>,
+    pub commit_sha: String,
+    pub subpath: String,
+    pub pinned: bool,
+}
+
 fn parse_datetime(value: &str) -> Result<DateTime<Utc>, DatabaseError> {
     DateTime::parse_from_rfc3339(value)
         .map(|dt| dt.with_timezone(&Utc))
@@ -103,6 +139,25 @@ fn row_to_deployment(row: &rusqlite::Row<'_>) -> Result<SkillDeployment, rusqlit
     })
 }
 
+fn row_to_origin(row: &rusqlite::Row<'_>) -> Result<SkillOrigin, rusqlite::Error> {
+    let created_at: String = row.get(8)?;
+    let updated_at: String = row.get(9)?;
+    Ok(SkillOrigin {
+        id: row.get(0)?,
+        skill_name: row.get(1)?,
+        install_path: row.get(2)?,
+        repo_url: row.get(3)?,
+        git_ref: row.get(4)?,
+        commit_sha: row.get(5)?,
+        subpath: row.get(6)?,
+        pinned: row.get(7)?,
+        created_at: parse_datetime(&created_at)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?,
+        updated_at: parse_datetime(&updated_at)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?,
+    })
+}
+
 /// Store for registered library source directories.
 pub struct SkillSourceStore<'a> {
     conn: &'a Connection,
@@ -310,6 +365,43 @@ impl<'a> SkillDeploymentStore<'a> {
         Ok(())
     }
 
+    /// Replace the deploy-time bundle recorded for a copy deployment.
+    ///
+    /// `files` maps `/`-separated paths relative to the skill directory to
+    /// their contents; it is the common base for drift classification.
+    pub fn set_base_files(
+        &self,
+        id: i64,
+        files: &BTreeMap<String, Vec<u8>>,
+    ) -> Result<(), DatabaseError> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM skill_deployment_files WHERE deployment_id = ?1",
+            params![id],
+        )?;
+        for (path, content) in files {
+            let sha256 = hex::encode(Sha256::digest(content));
+            tx.execute(
+                "INSERT INTO skill_deployment_files (deployment_id, path, sha256, content) \
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![id, path, sha256, content],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// The deploy-time bundle of a copy deployment, if one was recorded.
+    pub fn base_files(&self, id: i64) -> Result<Option<BTreeMap<String, Vec<u8>>>, DatabaseError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT path, content FROM skill_deployment_files WHERE deployment_id = ?1")?;
+        let files = stmt
+            .query_map(params![id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<BTreeMap<String, Vec<u8>>, _>>()?;
+        Ok((!files.is_empty()).then_some(files))
+    }
+
     /// Set (or clear, with `None`) the muting middle-state for a deployment.
     pub fn set_mute_state(&self, id: i64, mute_state: Option<&str>) -> Result<(), DatabaseError> {
         let now = Utc::now().to_rfc3339();
@@ -321,6 +413,104 @@ impl<'a> SkillDeploymentStore<'a> {
     }
 }
 
+const ORIGIN_COLUMNS: &str = "id, skill_name, install_path, repo_url, git_ref, commit_sha, \
+     subpath, pinned, created_at, updated_at";
+
+/// Store for the git origins of installed skills.
+pub struct SkillOriginStore<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> SkillOriginStore<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    /// Record the origin of a bundle, replacing any earlier record for the
+    /// same install path.
+    pub fn upsert(&self, input: &SkillOriginInput) -> Result<SkillOrigin, DatabaseError> {
+        let now = Utc::now().to_rfc3339();
+        self.conn.execute(
+            r"
+            INSERT INTO skill_origins (
+                skill_name, install_path, repo_url, git_ref, commit_sha,
+                subpath, pinned, created_at, updated_at
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8)
+            ON CONFLICT(install_path) DO UPDATE SET
+                skill_name = excluded.skill_name,
+                repo_url = excluded.repo_url,
+                git_ref = excluded.git_ref,
+                commit_sha = excluded.commit_sha,
+                subpath = excluded.subpath,
+                pinned = excluded.pinned,
+                updated_at = excluded.updated_at
+            ",
+            params![
+                input.skill_name,
+                input.install_path,
+                input.repo_url,
+                input.git_ref,
+                input.commit_sha,
+                input.subpath,
+                input.pinned,
+                now,
+            ],
+        )?;
+        self.get_by_path(&input.install_path)?
+            .ok_or_else(|| DatabaseError::Migration("skill origin vanished after insert".into()))
+    }
+
+    pub fn list(&self) -> Result<Vec<SkillOrigin>, DatabaseError> {
+        let sql = format!("SELECT {ORIGIN_COLUMNS} FROM skill_origins ORDER BY skill_name ASC");
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map([], row_to_origin)?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(DatabaseError::from)
+    }
+
+    pub fn get_by_path(&self, install_path: &str) -> Result<Option<SkillOrigin>, DatabaseError> {
+        let sql = format!("SELECT {ORIGIN_COLUMNS} FROM skill_origins WHERE install_path = ?1");
+        let mut stmt = self.conn.prepare(&sql)?;
+        stmt.query_row(params![install_path], row_to_origin)
+            .optional()
+            .map_err(DatabaseError::from)
+    }
+
+    /// All origins recorded for a skill name (one per install location).
+    pub fn list_by_name(&self, skill_name: &str) -> Result<Vec<SkillOrigin>, DatabaseError> {
+        let sql = format!(
+            "SELECT {ORIGIN_COLUMNS} FROM skill_origins WHERE skill_name = ?1 ORDER BY id ASC"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![skill_name], row_to_origin)?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(DatabaseError::from)
+    }
+
+    /// Move an origin to another commit, setting or clearing its pin.
+    pub fn update_commit(
+        &self,
+        id: i64,
+        commit_sha: &str,
+        pinned: bool,
+    ) -> Result<(), DatabaseError> {
+        let now = Utc::now().to_rfc3339();
+        self.conn.execute(
+            "UPDATE skill_origins SET commit_sha = ?2, pinned = ?3, updated_at = ?4 WHERE id = ?1",
+            params![id, commit_sha, pinned, now],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete(&self, id: i64) -> Result<bool, DatabaseError> {
+        let count = self
+            .conn
+            .execute("DELETE FROM skill_origins WHERE id = ?1", params![id])?;
+        Ok(count > 0)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -365,6 +555,33 @@ mod tests {
         assert!(store.list().unwrap().is_empty());
     }
 
+    #[test]
+    fn origin_upsert_follows_install_path() {
+        let conn = conn();
+        let store = SkillOriginStore::new(&conn);
+        let mut input = SkillOriginInput {
+            skill_name: "taste".into(),
+            install_path: "/u/.agents/skills/taste".into(),
+            repo_url: "https://github.com/o/taste-skill".into(),
+            git_ref: Some("main".into()),
+            commit_sha: "a".repeat(40),
+            subpath: String::new(),
+            pinned: false,
+        };
+        let first = store.upsert(&input).unwrap();
+        input.commit_sha = "b".repeat(40);
+        let second = store.upsert(&input).unwrap();
+        assert_eq!(first.id, second.id);
+        assert_eq!(store.list_by_name("taste").unwrap().len(), 1);
+
+        store
+            .update_commit(second.id, &"c".repeat(40), true)
+            .unwrap();
+        let pinned = store.get_by_path(&input.install_path).unwrap().unwrap();
+        assert!(pinned.pinned);
+        assert_eq!(pinned.commit_sha, "c".repeat(40));
+    }
+
     #[test]
     fn deployment_toggle_by_target() {
         let conn = conn();
@@ -0,0 +1,529 @@
+//! Dollar estimates for Claude Code usage.
+//!
+//! Joins the per-model token counts in [`ClaudeUsageStats`] with the
+//! Anthropic rows of the pricing cache. Prices go through
+//! [`effective_price_for`] so user overrides win over fetched `LiteLLM` data.
+//!
+//! Three caveats shape the numbers:
+//!
+//! - Cache reads and writes use the cached `LiteLLM` cache rates. Models
+//!   without them fall back to the input rate scaled by Anthropic's published
+//!   multipliers ([`CACHE_READ_MULTIPLIER`], [`CACHE_WRITE_MULTIPLIER`]).
+//! - The stats cache only keeps lifetime totals, not per-request prompt
+//!   sizes, so the long-context (>200k) tier cannot be attributed and every
+//!   token is priced at the base tier.
+//! - `dailyModelTokens` only records one token total per model per day. Daily
+//!   cost spreads each model's lifetime cost over its lifetime input + output
+//!   tokens and applies that blended rate to the day's total, so daily
+//!   figures are estimates that add up to the per-model totals.
+
+use std::collections::{BTreeMap, HashMap};
+use std::hash::BuildHasher;
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+use super::stats::{ClaudeUsageStats, ModelUsage};
+use crate::pricing::{effective_price_for, EffectivePrice, PriceColumns};
+use crate::storage::db::DatabaseError;
+
+/// Provider whose cached prices apply to Claude Code models
+pub const PRICING_PROVIDER_ID: &str = "anthropic";
+
+/// Cache reads are billed at 10% of the input rate
+pub const CACHE_READ_MULTIPLIER: f64 = 0.1;
+
+/// Cache writes (5-minute TTL) are billed at 125% of the input rate
+pub const CACHE_WRITE_MULTIPLIER: f64 = 1.25;
+
+const TOKENS_PER_MILLION: f64 = 1_000_000.0;
+
+/// Effective per-million-token price for one model
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelPrice {
+    pub input: f64,
+    pub output: f64,
+    pub cache_read: f64,
+    pub cache_write: f64,
+    /// True when either cache rate was derived from the input rate because
+    /// no explicit cache price is cached for the model
+    pub cache_rates_estimated: bool,
+    pub is_overridden: bool,
+}
+
+impl ModelPrice {
+    /// Resolve a model's price, or `None` without both input and output
+    /// rates.
+    fn from_effective(p: &EffectivePrice) -> Option<Self> {
+        let (input, output) = (p.input?, p.output?);
+        Some(Self {
+            input,
+            output,
+            cache_read: p.cache_read.unwrap_or(input * CACHE_READ_MULTIPLIER),
+            cache_write: p.cache_write.unwrap_or(input * CACHE_WRITE_MULTIPLIER),
+            cache_rates_estimated: p.cache_read.is_none() || p.cache_write.is_none(),
+            is_overridden: p.is_overridden,
+        })
+    }
+}
+
+/// Cost breakdown for one model over the whole stats window
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelCost {
+    pub model_id: String,
+    pub usage: ModelUsage,
+    /// `None` when no price is cached for the model; all costs are then 0
+    pub price: Option<ModelPrice>,
+    pub input_cost: f64,
+    pub output_cost: f64,
+    pub cache_read_cost: f64,
+    pub cache_creation_cost: f64,
+    pub total_cost: f64,
+}
+
+/// Estimated cost for one day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyCost {
+    pub date: String,
+    pub total_cost: f64,
+    pub cost_by_model: BTreeMap<String, f64>,
+}
+
+/// Cost estimate for everything in a stats cache
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CostReport {
+    pub total_cost: f64,
+    /// Sorted by descending total cost, then model id
+    pub models: Vec<ModelCost>,
+    /// Sorted by date
+    pub daily: Vec<DailyCost>,
+    /// Models with usage but no cached price, sorted
+    pub unpriced_models: Vec<String>,
+}
+
+/// Load effective prices for every cached Anthropic model that has both an
+/// input and an output price.
+///
+/// # Errors
+/// Returns the underlying `DatabaseError` on query failure.
+pub fn load_anthropic_prices(
+    conn: &Connection,
+) -> Result<HashMap<String, ModelPrice>, DatabaseError> {
+    let mut stmt = conn.prepare(
+        r"
+        SELECT model_id, input_price, output_price, cache_read_price, cache_write_price,
+               input_price_above_200k, output_price_above_200k,
+               cache_read_price_above_200k, cache_write_price_above_200k,
+               price_override_json
+        FROM provider_models
+        WHERE provider_id = ?1
+        ",
+    )?;
+    let rows = stmt.query_map(params![PRICING_PROVIDER_ID], |row| {
+        let fetched = PriceColumns {
+            input: row.get(1)?,
+            output: row.get(2)?,
+            cache_read: row.get(3)?,
+            cache_write: row.get(4)?,
+            input_above_200k: row.get(5)?,
+            output_above_200k: row.get(6)?,
+            cache_read_above_200k: row.get(7)?,
+            cache_write_above_200k: row.get(8)?,
+        };
+        Ok((
+            row.get::<_, String>(0)?,
+            fetched,
+            row.get::<_, Option<String>>(9)?,
+        ))
+    })?;
+
+    let mut prices = HashMap::new();
+    for row in rows {
+        let (model_id, fetched, override_json) = row?;
+        let effective = effective_price_for(fetched, override_json.as_deref());
+        if let Some(price) = ModelPrice::from_effective(&effective) {
+            prices.insert(model_id, price);
+        }
+    }
+    Ok(prices)
+}
+
+/// Find the price for a model id as Claude Code reports it.
+///
+/// Exact matches win; otherwise a trailing `-YYYYMMDD` snapshot date is
+/// ignored on either side, so `claude-sonnet-4-5-20250929` finds a
+/// `claude-sonnet-4-5` entry and vice versa.
+fn price_for<'a, S: BuildHasher>(
+    prices: &'a HashMap<String, ModelPrice, S>,
+    model_id: &str,
+) -> Option<&'a ModelPrice> {
+    if let Some(p) = prices.get(model_id) {
+        return Some(p);
+    }
+    let base = strip_date_suffix(model_id);
+    prices
+        .iter()
+        .filter(|(id, _)| strip_date_suffix(id) == base)
+        .min_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, p)| p)
+}
+
+fn strip_date_suffix(model_id: &str) -> &str {
+    match model_id.rsplit_once('-') {
+        Some((base, date)) if date.len() == 8 && date.bytes().all(|b| b.is_ascii_digit()) => base,
+        _ => model_id,
+    }
+}
+
+#[allow(clippy::cast_precision_loss)] // token counts stay far below 2^52
+fn tokens_cost(tokens: u64, per_million: f64) -> f64 {
+    tokens as f64 * per_million / TOKENS_PER_MILLION
+}
+
+fn model_cost(model_id: &str, usage: &ModelUsage, price: Option<ModelPrice>) -> ModelCost {
+    let (input_cost, output_cost, cache_read_cost, cache_creation_cost) = match price {
+        Some(p) => (
+            tokens_cost(usage.input_tokens, p.input),
+            tokens_cost(usage.output_tokens, p.output),
+            tokens_cost(usage.cache_read_input_tokens, p.cache_read),
+            tokens_cost(usage.cache_creation_input_tokens, p.cache_write),
+        ),
+        None => (0.0, 0.0, 0.0, 0.0),
+    };
+    ModelCost {
+        model_id: model_id.to_string(),
+        usage: usage.clone(),
+        price,
+        input_cost,
+        output_cost,
+        cache_read_cost,
+        cache_creation_cost,
+        total_cost: input_cost + output_cost + cache_read_cost + cache_creation_cost,
+    }
+}
+
+/// Per-token rate used to spread a model's cost over its daily totals.
+#[allow(clippy::cast_precision_loss)]
+fn blended_rate(cost: &ModelCost) -> Option<f64> {
+    let price = cost.price?;
+    let tokens = cost.usage.input_tokens + cost.usage.output_tokens;
+    if tokens > 0 {
+        return Some(cost.total_cost / tokens as f64);
+    }
+    // Model appears in daily data only: fall back to the mean of the input
+    // and output rates.
+    Some((price.input + price.output) / 2.0 / TOKENS_PER_MILLION)
+}
+
+/// Estimate the cost of everything recorded in `stats`.
+pub fn compute_cost<S: BuildHasher>(
+    stats: &ClaudeUsageStats,
+    prices: &HashMap<String, ModelPrice, S>,
+) -> CostReport {
+    let mut by_model: HashMap<String, ModelCost> = stats
+        .model_usage
+        .iter()
+        .map(|(id, usage)| {
+            (
+                id.clone(),
+                model_cost(id, usage, price_for(prices, id).copied()),
+            )
+        })
+        .collect();
+
+    // Models seen only in the daily series still need a price entry so
+    // their days can be costed.
+    for day in &stats.daily_model_tokens {
+        for id in day.tokens_by_model.keys() {
+            by_model.entry(id.clone()).or_insert_with(|| {
+                model_cost(id, &ModelUsage::default(), price_for(prices, id).copied())
+            });
+        }
+    }
+
+    let rates: HashMap<&str, f64> = by_model
+        .values()
+        .filter_map(|c| blended_rate(c).map(|r| (c.model_id.as_str(), r)))
+        .collect();
+
+    let mut daily: Vec<DailyCost> = stats
+        .daily_model_tokens
+        .iter()
+        .map(|day| {
+            let cost_by_model: BTreeMap<String, f64> = day
+                .tokens_by_model
+                .iter()
+                .filter_map(|(id, &tokens)| {
+                    rates.get(id.as_str()).map(|rate| {
+                        #[allow(clippy::cast_precision_loss)]
+                        let cost = tokens as f64 * rate;
+                        (id.clone(), cost)
+                    })
+                })
+                .collect();
+            DailyCost {
+                date: day.date.clone(),
+                total_cost: cost_by_model.values().sum(),
+                cost_by_model,
+            }
+        })
+        .collect();
+    daily.sort_by(|a, b| a.date.cmp(&b.date));
+
+    let mut unpriced_models: Vec<String> = by_model
+        .values()
+        .filter(|c| c.price.is_none())
+        .map(|c| c.model_id.clone())
+        .collect();
+    unpriced_models.sort();
+
+    let mut models: Vec<ModelCost> = by_model
+        .into_values()
+        .filter(|c| c.usage != ModelUsage::default())
+        .collect();
+    models.sort_by(|a, b| {
+        b.total_cost
+            .total_cmp(&a.total_cost)
+            .then_with(|| a.model_id.cmp(&b.model_id))
+    });
+
+    CostReport {
+        total_cost: models.iter().map(|m| m.total_cost).sum(),
+        models,
+        daily,
+        unpriced_models,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::db::Database;
+    use crate::storage::model_cache::{ModelCache, ModelRow};
+    use crate::usage::stats::DailyModelTokens;
+    use chrono::Utc;
+
+    const SONNET: &str = "claude-sonnet-4-5-20250929";
+    const HAIKU: &str = "claude-haiku-4-5-20251001";
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    fn price(input: f64, output: f64) -> ModelPrice {
+        ModelPrice {
+            input,
+            output,
+            cache_read: input * CACHE_READ_MULTIPLIER,
+            cache_write: input * CACHE_WRITE_MULTIPLIER,
+            cache_rates_estimated: true,
+            is_overridden: false,
+        }
+    }
+
+    fn stats(usage: &[(&str, ModelUsage)], daily: &[(&str, &[(&str, u64)])]) -> ClaudeUsageStats {
+        ClaudeUsageStats {
+            total_sessions: 0,
+            total_messages: 0,
+            first_session_date: None,
+            last_computed_date: None,
+            daily_activity: Vec::new(),
+            daily_model_tokens: daily
+                .iter()
+                .map(|(date, tokens)| DailyModelTokens {
+                    date: (*date).to_string(),
+                    tokens_by_model: tokens.iter().map(|(m, t)| ((*m).to_string(), *t)).collect(),
+                })
+                .collect(),
+            model_usage: usage
+                .iter()
+                .map(|(m, u)| ((*m).to_string(), u.clone()))
+                .collect(),
+            hour_counts: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn prices_every_token_class() {
+        let usage = ModelUsage {
+            input_tokens: 1_000_000,
+            output_tokens: 200_000,
+            cache_read_input_tokens: 10_000_000,
+            cache_creation_input_tokens: 400_000,
+        };
+        let prices = HashMap::from([(SONNET.to_string(), price(3.0, 15.0))]);
+        let report = compute_cost(&stats(&[(SONNET, usage)], &[]), &prices);
+
+        let m = &report.models[0];
+        assert!(approx(m.input_cost, 3.0));
+        assert!(approx(m.output_cost, 3.0));
+        assert!(approx(m.cache_read_cost, 3.0), "10M reads at $0.30/M");
+        assert!(approx(m.cache_creation_cost, 1.5), "400k writes at $3.75/M");
+        assert!(approx(m.total_cost, 10.5));
+        assert!(approx(report.total_cost, 10.5));
+        assert!(report.unpriced_models.is_empty());
+    }
+
+    #[test]
+    fn explicit_cache_rates_replace_multipliers() {
+        let usage = ModelUsage {
+            cache_read_input_tokens: 10_000_000,
+            cache_creation_input_tokens: 1_000_000,
+            ..ModelUsage::default()
+        };
+        let prices = HashMap::from([(
+            SONNET.to_string(),
+            ModelPrice {
+                cache_read: 0.5,
+                cache_write: 6.0,
+                cache_rates_estimated: false,
+                ..price(3.0, 15.0)
+            },
+        )]);
+        let report = compute_cost(&stats(&[(SONNET, usage)], &[]), &prices);
+
+        let m = &report.models[0];
+        assert!(approx(m.cache_read_cost, 5.0));
+        assert!(approx(m.cache_creation_cost, 6.0));
+    }
+
+    #[test]
+    fn daily_cost_spreads_blended_rate_and_sums_to_total() {
+        let usage = ModelUsage {
+            input_tokens: 600_000,
+            output_tokens: 400_000,
+            cache_read_input_tokens: 0,
+            cache_creation_input_tokens: 0,
+        };
+        // $1.80 input + $6.00 output over 1M tokens.
+        let prices = HashMap::from([(SONNET.to_string(), price(3.0, 15.0))]);
+        let days: &[(&str, &[(&str, u64)])] = &[
+            ("2026-05-02", &[(SONNET, 750_000)]),
+            ("2026-05-01", &[(SONNET, 250_000)]),
+        ];
+        let report = compute_cost(&stats(&[(SONNET, usage)], days), &prices);
+
+        assert_eq!(report.daily[0].date, "2026-05-01");
+        assert!(approx(report.daily[0].total_cost, 1.95));
+        assert!(approx(report.daily[1].total_cost, 5.85));
+        let daily_sum: f64 = report.daily.iter().map(|d| d.total_cost).sum();
+        assert!(approx(daily_sum, report.total_cost));
+    }
+
+    #[test]
+    fn unpriced_models_are_reported_not_costed() {
+        let usage = ModelUsage {
+            input_tokens: 10,
+            ..ModelUsage::default()
+        };
+        let report = compute_cost(
+            &stats(
+                &[("mystery-model", usage)],
+                &[("2026-05-01", &[("mystery-model", 10)])],
+            ),
+            &HashMap::new(),
+        );
+        assert_eq!(report.unpriced_models, vec!["mystery-model".to_string()]);
+        assert!(approx(report.total_cost, 0.0));
+        assert!(report.daily[0].cost_by_model.is_empty());
+    }
+
+    #[test]
+    fn daily_only_model_uses_mean_rate() {
+        let prices = HashMap::from([(HAIKU.to_string(), price(1.0, 5.0))]);
+        let report = compute_cost(
+            &stats(&[], &[("2026-05-01", &[(HAIKU, 1_000_000)])]),
+            &prices,
+        );
+        assert!(report.models.is_empty(), "no lifetime usage to list");
+        assert!(approx(report.daily[0].total_cost, 3.0));
+    }
+
+    #[test]
+    fn price_lookup_ignores_snapshot_date() {
+        let prices = HashMap::from([("claude-sonnet-4-5".to_string(), price(3.0, 15.0))]);
+        assert!(price_for(&prices, SONNET).is_some());
+
+        let prices = HashMap::from([(SONNET.to_string(), price(3.0, 15.0))]);
+        assert!(price_for(&prices, "claude-sonnet-4-5").is_some());
+        assert!(price_for(&prices, "claude-opus-4-5").is_none());
+        assert_eq!(strip_date_suffix("gpt-4o"), "gpt-4o");
+    }
+
+    #[test]
+    fn load_prices_applies_overrides() {
+        let db = Database::in_memory().unwrap();
+        let conn = db.connection();
+        ModelCache::new(conn)
+            .upsert_all(
+                PRICING_PROVIDER_ID,
+                &[
+                    ModelRow {
+                        model_id: SONNET.into(),
+                        display_name: None,
+                        context_window: None,
+                        input_price: Some(3.0),
+                        output_price: Some(15.0),
+                    },
+                    ModelRow {
+                        model_id: HAIKU.into(),
+                        display_name: None,
+                        context_window: None,
+                        input_price: None,
+                        output_price: None,
+                    },
+                ],
+                Utc::now(),
+            )
+            .unwrap();
+        conn.execute(
+            "UPDATE provider_models SET price_override_json = ?1 WHERE model_id = ?2",
+            params![r#"{"input": 2.0}"#, SONNET],
+        )
+        .unwrap();
+
+        let prices = load_anthropic_prices(conn).unwrap();
+        assert_eq!(prices.len(), 1, "unpriced rows are skipped");
+        let p = prices[SONNET];
+        assert!(approx(p.input, 2.0));
+        assert!(approx(p.output, 15.0));
+        assert!(p.is_overridden);
+        // No cache rates cached: derived from the overridden input rate.
+        assert!(approx(p.cache_read, 0.2));
+        assert!(p.cache_rates_estimated);
+    }
+
+    #[test]
+    fn load_prices_reads_cache_columns() {
+        let db = Database::in_memory().unwrap();
+        let conn = db.connection();
+        ModelCache::new(conn)
+            .upsert_all(
+                PRICING_PROVIDER_ID,
+                &[ModelRow {
+                    model_id: SONNET.into(),
+                    display_name: None,
+                    context_window: None,
+                    input_price: Some(3.0),
+                    output_price: Some(15.0),
+                }],
+                Utc::now(),
+            )
+            .unwrap();
+        conn.execute(
+            "UPDATE provider_models SET cache_read_price = 0.3, cache_write_price = 3.75
+             WHERE model_id = ?1",
+            params![SONNET],
+        )
+        .unwrap();
+
+        let p = load_anthropic_prices(conn).unwrap()[SONNET];
+        assert!(approx(p.cache_read, 0.3));
+        assert!(approx(p.cache_write, 3.75));
+        assert!(!p.cache_rates_estimated);
+    }
+}
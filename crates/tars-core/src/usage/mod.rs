@@ -0,0 +1,18 @@
+//! Claude Code usage statistics and cost attribution.
+//!
+//! - [`stats`]: read `~/.claude/stats-cache.json`, the aggregate Claude Code
+//!   maintains of sessions, messages and per-model token counts.
+//! - [`cost`]: join those token counts with the pricing cache
+//!   ([`crate::pricing`]) to estimate spend per model, per day and in total.
+
+pub mod cost;
+pub mod stats;
+
+pub use cost::{
+    compute_cost, load_anthropic_prices, CostReport, DailyCost, ModelCost, ModelPrice,
+    PRICING_PROVIDER_ID,
+};
+pub use stats::{
+    load_stats_cache, parse_stats_cache, stats_cache_path, ClaudeUsageStats, DailyActivity,
+    DailyModelTokens, ModelUsage, UsageStatsError,
+};
@@ -0,0 +1,252 @@
+//! Parser for Claude Code's `~/.claude/stats-cache.json`.
+//!
+//! The file is written by Claude Code itself; every field is optional on
+//! read so older or partial caches still load. The public types serialize in
+//! camelCase, matching the file and what the desktop frontend consumes.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum UsageStatsError {
+    #[error("Claude Code stats file not found at {0}. Have you used Claude Code yet?")]
+    NotFound(PathBuf),
+    #[error("Failed to read stats file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to parse stats file: {0}")]
+    Parse(#[from] serde_json::Error),
+}
+
+/// Location of the stats cache under a home directory
+pub fn stats_cache_path(home: &Path) -> PathBuf {
+    home.join(".claude").join("stats-cache.json")
+}
+
+/// Daily activity from Claude Code stats
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyActivity {
+    pub date: String,
+    pub message_count: u64,
+    pub session_count: u64,
+    pub tool_call_count: u64,
+}
+
+/// Daily token usage by model
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyModelTokens {
+    pub date: String,
+    pub tokens_by_model: HashMap<String, u64>,
+}
+
+/// Lifetime usage stats for a model
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+#[allow(clippy::struct_field_names)] // Matches external JSON format from Claude
+pub struct ModelUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_input_tokens: u64,
+    pub cache_creation_input_tokens: u64,
+}
+
+/// Claude Code usage statistics from ~/.claude/stats-cache.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeUsageStats {
+    pub total_sessions: u64,
+    pub total_messages: u64,
+    pub first_session_date: Option<String>,
+    pub last_computed_date: Option<String>,
+    pub daily_activity: Vec<DailyActivity>,
+    pub daily_model_tokens: Vec<DailyModelTokens>,
+    pub model_usage: HashMap<String, ModelUsage>,
+    pub hour_counts: HashMap<String, u64>,
+}
+
+/// Raw stats cache format (matches the JSON file structure)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawStatsCache {
+    #[serde(default)]
+    #[allow(dead_code)]
+    version: u32,
+    last_computed_date: Option<String>,
+    #[serde(default)]
+    daily_activity: Vec<RawDailyActivity>,
+    #[serde(default)]
+    daily_model_tokens: Vec<RawDailyModelTokens>,
+    #[serde(default)]
+    model_usage: HashMap<String, RawModelUsage>,
+    #[serde(default)]
+    total_sessions: u64,
+    #[serde(default)]
+    total_messages: u64,
+    first_session_date: Option<String>,
+    #[serde(default)]
+    hour_counts: HashMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawDailyActivity {
+    date: String,
+    #[serde(default)]
+    message_count: u64,
+    #[serde(default)]
+    session_count: u64,
+    #[serde(default)]
+    tool_call_count: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawDailyModelTokens {
+    date: String,
+    #[serde(default)]
+    tokens_by_model: HashMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+#[allow(clippy::struct_field_names)] // Matches external JSON format from Claude
+struct RawModelUsage {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+    #[serde(default)]
+    cache_read_input_tokens: u64,
+    #[serde(default)]
+    cache_creation_input_tokens: u64,
+}
+
+/// Parse the contents of a stats cache file.
+pub fn parse_stats_cache(content: &str) -> Result<ClaudeUsageStats, UsageStatsError> {
+    let raw: RawStatsCache = serde_json::from_str(content)?;
+
+    let daily_activity = raw
+        .daily_activity
+        .into_iter()
+        .map(|d| DailyActivity {
+            date: d.date,
+            message_count: d.message_count,
+            session_count: d.session_count,
+            tool_call_count: d.tool_call_count,
+        })
+        .collect();
+
+    let daily_model_tokens = raw
+        .daily_model_tokens
+        .into_iter()
+        .map(|d| DailyModelTokens {
+            date: d.date,
+            tokens_by_model: d.tokens_by_model,
+        })
+        .collect();
+
+    let model_usage = raw
+        .model_usage
+        .into_iter()
+        .map(|(k, v)| {
+            (
+                k,
+                ModelUsage {
+                    input_tokens: v.input_tokens,
+                    output_tokens: v.output_tokens,
+                    cache_read_input_tokens: v.cache_read_input_tokens,
+                    cache_creation_input_tokens: v.cache_creation_input_tokens,
+                },
+            )
+        })
+        .collect();
+
+    Ok(ClaudeUsageStats {
+        total_sessions: raw.total_sessions,
+        total_messages: raw.total_messages,
+        first_session_date: raw.first_session_date,
+        last_computed_date: raw.last_computed_date,
+        daily_activity,
+        daily_model_tokens,
+        model_usage,
+        hour_counts: raw.hour_counts,
+    })
+}
+
+/// Read and parse the stats cache under `home`.
+pub fn load_stats_cache(home: &Path) -> Result<ClaudeUsageStats, UsageStatsError> {
+    let path = stats_cache_path(home);
+    if !path.exists() {
+        return Err(UsageStatsError::NotFound(path));
+    }
+    let content = std::fs::read_to_string(&path)?;
+    parse_stats_cache(&content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const SAMPLE: &str = r#"{
+        "version": 2,
+        "lastComputedDate": "2026-05-03",
+        "dailyActivity": [
+            {"date": "2026-05-01", "messageCount": 12, "sessionCount": 2, "toolCallCount": 30}
+        ],
+        "dailyModelTokens": [
+            {"date": "2026-05-01", "tokensByModel": {"claude-sonnet-4-5-20250929": 1500}}
+        ],
+        "modelUsage": {
+            "claude-sonnet-4-5-20250929": {
+                "inputTokens": 1000,
+                "outputTokens": 500,
+                "cacheReadInputTokens": 20000,
+                "cacheCreationInputTokens": 4000,
+                "webSearchRequests": 0
+            }
+        },
+        "totalSessions": 2,
+        "totalMessages": 12,
+        "firstSessionDate": "2026-05-01T09:00:00Z",
+        "hourCounts": {"9": 2}
+    }"#;
+
+    #[test]
+    fn parses_full_cache() {
+        let stats = parse_stats_cache(SAMPLE).unwrap();
+        assert_eq!(stats.total_sessions, 2);
+        assert_eq!(stats.last_computed_date.as_deref(), Some("2026-05-03"));
+        assert_eq!(stats.daily_activity[0].tool_call_count, 30);
+        assert_eq!(
+            stats.daily_model_tokens[0].tokens_by_model["claude-sonnet-4-5-20250929"],
+            1500
+        );
+        let usage = &stats.model_usage["claude-sonnet-4-5-20250929"];
+        assert_eq!(usage.cache_read_input_tokens, 20_000);
+        assert_eq!(usage.cache_creation_input_tokens, 4_000);
+    }
+
+    #[test]
+    fn missing_sections_default_to_empty() {
+        let stats = parse_stats_cache("{}").unwrap();
+        assert_eq!(stats.total_messages, 0);
+        assert!(stats.model_usage.is_empty());
+        assert!(stats.daily_model_tokens.is_empty());
+    }
+
+    #[test]
+    fn load_reports_missing_file() {
+        let home = TempDir::new().unwrap();
+        let err = load_stats_cache(home.path()).unwrap_err();
+        assert!(matches!(err, UsageStatsError::NotFound(_)));
+
+        std::fs::create_dir_all(home.path().join(".claude")).unwrap();
+        std::fs::write(stats_cache_path(home.path()), SAMPLE).unwrap();
+        assert_eq!(load_stats_cache(home.path()).unwrap().total_sessions, 2);
+    }
+}
@@ -1,11 +1,12 @@
 use std::fs;
-use tars_core::config::{HookConfig, HookDefinition, HookTrigger};
 use tars_core::export::export_as_codex_bundle;
 use tars_core::profile::storage::{
     copy_agent_to_profile, copy_command_to_profile, copy_skill_to_profile, ensure_profile_dir,
     profile_dir, store_mcp_server,
 };
-use tars_core::profile::{Profile, ToolRef, ToolType};
+use tars_core::profile::{
+    resolve_with, MergeStrategy, Profile, ResolvedProfile, ToolRef, ToolType,
+};
 use tempfile::TempDir;
 
 struct ProfileStorageGuard(uuid::Uuid);
@@ -104,21 +105,25 @@ fn test_export_as_codex_bundle_generates_plugin_marketplace_and_report() {
         name: "overlay-skill".to_string(),
         content: "---\nname: overlay-skill\ndescription: Overlay skill with an embedded hook\nhooks:\n  SessionStart:\n    - type: prompt\n      prompt: Warm up the session\n---\n\nStay focused.\n"
             .to_string(),
+        files: Vec::new(),
     });
 
     let hooks_dir = ensure_profile_dir(profile.id).expect("Failed to create profile storage dir");
     fs::write(
         hooks_dir.join("hooks.json"),
-        serde_json::to_string_pretty(&vec![HookConfig::new(
-            HookTrigger::PreToolUse,
-            HookDefinition::command("npm test"),
-        )])
-        .expect("Failed to serialize hooks"),
+        serde_json::json!({
+            "hooks": {
+                "PreToolUse": [
+                    {"matcher": "Bash", "hooks": [{"type": "command", "command": "npm test"}]}
+                ]
+            }
+        })
+        .to_string(),
     )
     .expect("Failed to write hooks");
 
     let result = export_as_codex_bundle(
-        &profile,
+        &ResolvedProfile::standalone(profile.clone()),
         output_dir.path(),
         "team-bundle",
         "1.2.3",
@@ -190,3 +195,59 @@ fn test_export_as_codex_bundle_generates_plugin_marketplace_and_report() {
             && finding.support == tars_core::tars_scanner::runtime::RuntimeSupport::Partial
     }));
 }
+
+fn write_hooks(profile: &Profile, hooks: &serde_json::Value) {
+    let dir = ensure_profile_dir(profile.id).expect("Failed to create profile storage dir");
+    fs::write(dir.join("hooks.json"), hooks.to_string()).expect("Failed to write hooks");
+}
+
+#[test]
+fn test_codex_export_hooks_follow_layer_merge_strategy() {
+    let output_dir = TempDir::new().expect("Failed to create output dir");
+
+    let base = Profile::new("Hooks Base".to_string());
+    let _base_guard = ProfileStorageGuard(base.id);
+    write_hooks(
+        &base,
+        &serde_json::json!({
+            "hooks": {
+                "PreToolUse": [{"matcher": "Bash", "hooks": [{"type": "command", "command": "lint"}]}],
+                "SessionStart": [{"hooks": [{"type": "command", "command": "warm"}]}]
+            }
+        }),
+    );
+
+    let mut child = Profile::new("Hooks Child".to_string());
+    child.extends = vec![base.id];
+    child
+        .adapters
+        .merge_strategies
+        .insert("hooks".to_string(), MergeStrategy::Replace);
+    let _child_guard = ProfileStorageGuard(child.id);
+    write_hooks(
+        &child,
+        &serde_json::json!({
+            "hooks": {"Stop": [{"hooks": [{"type": "command", "command": "notify"}]}]}
+        }),
+    );
+
+    let resolved = resolve_with(&child, |id| Ok((id == base.id).then(|| base.clone())))
+        .expect("Failed to resolve child");
+    let result = export_as_codex_bundle(
+        &resolved,
+        output_dir.path(),
+        "hooks-child",
+        "0.1.0",
+        "hooks-marketplace",
+    )
+    .expect("Codex export failed");
+
+    let hooks: Vec<_> = result
+        .report
+        .findings
+        .iter()
+        .filter(|f| f.artifact_kind == tars_core::export::CodexArtifactKind::Hook)
+        .map(|f| f.name.as_str())
+        .collect();
+    assert_eq!(hooks, vec!["Stop#0"]);
+}
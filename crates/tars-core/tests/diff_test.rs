@@ -3,11 +3,13 @@
 //! Tests for generating diff plans from profiles.
 
 use std::fs;
-use tars_core::diff::plan::{generate_plan, generate_text_diff};
-use tars_core::diff::{DiffPlan, FileOperation};
+use tars_core::diff::plan::{generate_plan, generate_plan_with_context, generate_text_diff};
+use tars_core::diff::{DiffPlan, FileOperation, WarningSeverity};
 use tars_core::profile::{
-    AgentOverlay, ClaudeMdOverlay, CommandOverlay, OverlayMode, Profile, RepoOverlays, SkillOverlay,
+    AgentOverlay, ClaudeMdOverlay, CommandOverlay, OverlayMode, Profile, RepoOverlays, SkillFile,
+    SkillOverlay, TemplateContext,
 };
+use tars_core::storage::metadata::{CustomField, ProjectMetadata};
 use tempfile::TempDir;
 use uuid::Uuid;
 
@@ -16,6 +18,7 @@ fn create_test_profile_with_skills(name: &str) -> Profile {
     profile.repo_overlays.skills.push(SkillOverlay {
         name: "test-skill".to_string(),
         content: "---\nname: test-skill\ndescription: Test\n---\n\nSkill content".to_string(),
+        files: Vec::new(),
     });
     profile
 }
@@ -347,10 +350,12 @@ fn test_plan_full_profile() {
             SkillOverlay {
                 name: "skill1".to_string(),
                 content: "skill1 content".to_string(),
+                files: Vec::new(),
             },
             SkillOverlay {
                 name: "skill2".to_string(),
                 content: "skill2 content".to_string(),
+                files: Vec::new(),
             },
         ],
         commands: vec![CommandOverlay {
@@ -365,6 +370,8 @@ fn test_plan_full_profile() {
             mode: OverlayMode::Replace,
             content: "# Instructions".to_string(),
         }),
+        hooks: None,
+        permissions: None,
     };
 
     let plan =
@@ -399,6 +406,7 @@ fn test_plan_rejects_path_traversal_skill() {
     profile.repo_overlays.skills.push(SkillOverlay {
         name: "../../../etc/passwd".to_string(),
         content: "malicious".to_string(),
+        files: Vec::new(),
     });
 
     let result = generate_plan(project_id, temp_dir.path(), &profile);
@@ -419,3 +427,86 @@ fn test_plan_rejects_path_traversal_command() {
     let result = generate_plan(project_id, temp_dir.path(), &profile);
     assert!(result.is_err());
 }
+
+#[test]
+fn test_plan_rejects_path_traversal_skill_file() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let project_id = Uuid::new_v4();
+
+    let mut profile = Profile::new("path-traversal".to_string());
+    profile.repo_overlays.skills.push(SkillOverlay {
+        name: "helper".to_string(),
+        content: "helper".to_string(),
+        files: vec![SkillFile::from_bytes(
+            "../../settings.json".to_string(),
+            b"malicious".to_vec(),
+        )],
+    });
+
+    let result = generate_plan(project_id, temp_dir.path(), &profile);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_plan_renders_template_variables() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    fs::write(
+        temp_dir.path().join("Cargo.toml"),
+        "[package]\nname = \"demo\"\n",
+    )
+    .unwrap();
+    let project_id = Uuid::new_v4();
+
+    let mut profile = Profile::new("templated".to_string());
+    profile.repo_overlays.claude_md = Some(ClaudeMdOverlay {
+        mode: OverlayMode::Replace,
+        content: "# {{ project_name }}\n\nRun `{{ test_command }}` before pushing.\n\
+                  Deploy with {{ deploy_command }} to {{ custom.region | eu }}.\n\
+                  Owner: {{ custom.owner }}\n"
+            .to_string(),
+    });
+    profile.repo_overlays.commands.push(CommandOverlay {
+        name: "ship".to_string(),
+        content: "Ship {{ project_name }} with {{ custom.owner }}".to_string(),
+    });
+
+    let metadata = ProjectMetadata {
+        deploy_command: Some("fly deploy".to_string()),
+        custom_fields: vec![CustomField {
+            key: "region".to_string(),
+            value: "us-east".to_string(),
+        }],
+        ..ProjectMetadata::default()
+    };
+    let context = TemplateContext::new(temp_dir.path())
+        .with_name("demo")
+        .with_metadata(metadata);
+    let plan = generate_plan_with_context(project_id, temp_dir.path(), &profile, &context)
+        .expect("Failed to generate plan");
+
+    let claude_md = plan
+        .operations
+        .iter()
+        .find_map(|op| match op {
+            FileOperation::Create { path, content } if path.ends_with("CLAUDE.md") => {
+                Some(String::from_utf8(content.clone()).unwrap())
+            }
+            _ => None,
+        })
+        .expect("CLAUDE.md should be created");
+    assert!(claude_md.starts_with("# demo\n"));
+    assert!(claude_md.contains("Run `cargo test` before pushing."));
+    assert!(claude_md.contains("Deploy with fly deploy to us-east."));
+    // Unset variables are left in place and reported once per overlay
+    assert!(claude_md.contains("Owner: {{ custom.owner }}"));
+
+    let warnings: Vec<_> = plan
+        .warnings
+        .iter()
+        .filter(|w| w.message.contains("custom.owner"))
+        .collect();
+    assert_eq!(warnings.len(), 2);
+    assert!(warnings
+        .iter()
+        .all(|w| w.severity == WarningSeverity::Warning));
+}
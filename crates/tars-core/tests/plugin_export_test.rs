@@ -5,7 +5,8 @@
 use std::fs;
 use tars_core::export::export_as_plugin;
 use tars_core::profile::{
-    AgentOverlay, ClaudeMdOverlay, CommandOverlay, OverlayMode, Profile, SkillOverlay,
+    AgentOverlay, ClaudeMdOverlay, CommandOverlay, OverlayMode, Profile, ResolvedProfile,
+    SkillOverlay,
 };
 use tempfile::TempDir;
 
@@ -20,6 +21,7 @@ fn create_full_profile(name: &str) -> Profile {
     profile.repo_overlays.skills.push(SkillOverlay {
         name: "test-skill".to_string(),
         content: "---\nname: test-skill\ndescription: Test skill\n---\n\nSkill content".to_string(),
+        files: Vec::new(),
     });
 
     profile.repo_overlays.commands.push(CommandOverlay {
@@ -50,7 +52,13 @@ fn test_export_creates_plugin_directory() {
     let output_dir = temp_dir.path().join("my-plugin");
 
     let profile = create_minimal_profile("test");
-    export_as_plugin(&profile, &output_dir, "my-plugin", "1.0.0").expect("Export failed");
+    export_as_plugin(
+        &ResolvedProfile::standalone(profile.clone()),
+        &output_dir,
+        "my-plugin",
+        "1.0.0",
+    )
+    .expect("Export failed");
 
     assert!(output_dir.exists(), "Output directory should exist");
     assert!(
@@ -65,7 +73,13 @@ fn test_export_creates_manifest() {
     let output_dir = temp_dir.path().join("my-plugin");
 
     let profile = create_minimal_profile("test");
-    export_as_plugin(&profile, &output_dir, "my-plugin", "1.0.0").expect("Export failed");
+    export_as_plugin(
+        &ResolvedProfile::standalone(profile.clone()),
+        &output_dir,
+        "my-plugin",
+        "1.0.0",
+    )
+    .expect("Export failed");
 
     let manifest_path = output_dir.join(".claude-plugin/plugin.json");
     assert!(manifest_path.exists(), "plugin.json should exist");
@@ -81,7 +95,13 @@ fn test_export_creates_subdirectories() {
     let output_dir = temp_dir.path().join("my-plugin");
 
     let profile = create_minimal_profile("test");
-    export_as_plugin(&profile, &output_dir, "my-plugin", "1.0.0").expect("Export failed");
+    export_as_plugin(
+        &ResolvedProfile::standalone(profile.clone()),
+        &output_dir,
+        "my-plugin",
+        "1.0.0",
+    )
+    .expect("Export failed");
 
     assert!(
         output_dir.join("commands").exists(),
@@ -109,7 +129,13 @@ fn test_manifest_includes_description() {
     let mut profile = create_minimal_profile("test");
     profile.description = Some("A helpful plugin".to_string());
 
-    export_as_plugin(&profile, &output_dir, "my-plugin", "1.0.0").expect("Export failed");
+    export_as_plugin(
+        &ResolvedProfile::standalone(profile.clone()),
+        &output_dir,
+        "my-plugin",
+        "1.0.0",
+    )
+    .expect("Export failed");
 
     let manifest_path = output_dir.join(".claude-plugin/plugin.json");
     let content = fs::read_to_string(manifest_path).expect("Failed to read manifest");
@@ -122,7 +148,13 @@ fn test_manifest_valid_json() {
     let output_dir = temp_dir.path().join("my-plugin");
 
     let profile = create_full_profile("full-test");
-    export_as_plugin(&profile, &output_dir, "full-plugin", "2.0.0").expect("Export failed");
+    export_as_plugin(
+        &ResolvedProfile::standalone(profile.clone()),
+        &output_dir,
+        "full-plugin",
+        "2.0.0",
+    )
+    .expect("Export failed");
 
     let manifest_path = output_dir.join(".claude-plugin/plugin.json");
     let content = fs::read_to_string(manifest_path).expect("Failed to read manifest");
@@ -141,7 +173,13 @@ fn test_export_with_semver_version() {
     let output_dir = temp_dir.path().join("my-plugin");
 
     let profile = create_minimal_profile("test");
-    export_as_plugin(&profile, &output_dir, "my-plugin", "1.2.3").expect("Export failed");
+    export_as_plugin(
+        &ResolvedProfile::standalone(profile.clone()),
+        &output_dir,
+        "my-plugin",
+        "1.2.3",
+    )
+    .expect("Export failed");
 
     let manifest_path = output_dir.join(".claude-plugin/plugin.json");
     let content = fs::read_to_string(manifest_path).expect("Failed to read manifest");
@@ -154,7 +192,13 @@ fn test_export_with_prerelease_version() {
     let output_dir = temp_dir.path().join("my-plugin");
 
     let profile = create_minimal_profile("test");
-    export_as_plugin(&profile, &output_dir, "my-plugin", "1.0.0-beta.1").expect("Export failed");
+    export_as_plugin(
+        &ResolvedProfile::standalone(profile.clone()),
+        &output_dir,
+        "my-plugin",
+        "1.0.0-beta.1",
+    )
+    .expect("Export failed");
 
     let manifest_path = output_dir.join(".claude-plugin/plugin.json");
     let content = fs::read_to_string(manifest_path).expect("Failed to read manifest");
@@ -171,7 +215,13 @@ fn test_export_with_kebab_case_name() {
     let output_dir = temp_dir.path().join("my-awesome-plugin");
 
     let profile = create_minimal_profile("test");
-    export_as_plugin(&profile, &output_dir, "my-awesome-plugin", "1.0.0").expect("Export failed");
+    export_as_plugin(
+        &ResolvedProfile::standalone(profile.clone()),
+        &output_dir,
+        "my-awesome-plugin",
+        "1.0.0",
+    )
+    .expect("Export failed");
 
     let manifest_path = output_dir.join(".claude-plugin/plugin.json");
     let content = fs::read_to_string(manifest_path).expect("Failed to read manifest");
@@ -184,7 +234,13 @@ fn test_export_with_scoped_name() {
     let output_dir = temp_dir.path().join("plugin");
 
     let profile = create_minimal_profile("test");
-    export_as_plugin(&profile, &output_dir, "@myorg/my-plugin", "1.0.0").expect("Export failed");
+    export_as_plugin(
+        &ResolvedProfile::standalone(profile.clone()),
+        &output_dir,
+        "@myorg/my-plugin",
+        "1.0.0",
+    )
+    .expect("Export failed");
 
     let manifest_path = output_dir.join(".claude-plugin/plugin.json");
     let content = fs::read_to_string(manifest_path).expect("Failed to read manifest");
@@ -203,8 +259,20 @@ fn test_export_is_idempotent() {
     let profile = create_full_profile("test");
 
     // Export twice
-    export_as_plugin(&profile, &output_dir, "my-plugin", "1.0.0").expect("First export failed");
-    export_as_plugin(&profile, &output_dir, "my-plugin", "1.0.0").expect("Second export failed");
+    export_as_plugin(
+        &ResolvedProfile::standalone(profile.clone()),
+        &output_dir,
+        "my-plugin",
+        "1.0.0",
+    )
+    .expect("First export failed");
+    export_as_plugin(
+        &ResolvedProfile::standalone(profile.clone()),
+        &output_dir,
+        "my-plugin",
+        "1.0.0",
+    )
+    .expect("Second export failed");
 
     // Should still have valid structure
     let manifest_path = output_dir.join(".claude-plugin/plugin.json");
@@ -223,7 +291,13 @@ fn test_export_with_empty_description() {
     let mut profile = create_minimal_profile("test");
     profile.description = None;
 
-    export_as_plugin(&profile, &output_dir, "my-plugin", "1.0.0").expect("Export failed");
+    export_as_plugin(
+        &ResolvedProfile::standalone(profile.clone()),
+        &output_dir,
+        "my-plugin",
+        "1.0.0",
+    )
+    .expect("Export failed");
 
     let manifest_path = output_dir.join(".claude-plugin/plugin.json");
     let content = fs::read_to_string(manifest_path).expect("Failed to read manifest");
@@ -237,7 +311,13 @@ fn test_export_to_nested_path() {
     let output_dir = temp_dir.path().join("deeply/nested/path/my-plugin");
 
     let profile = create_minimal_profile("test");
-    export_as_plugin(&profile, &output_dir, "my-plugin", "1.0.0").expect("Export failed");
+    export_as_plugin(
+        &ResolvedProfile::standalone(profile.clone()),
+        &output_dir,
+        "my-plugin",
+        "1.0.0",
+    )
+    .expect("Export failed");
 
     assert!(
         output_dir.join(".claude-plugin/plugin.json").exists(),
@@ -253,7 +333,13 @@ fn test_export_preserves_special_chars_in_description() {
     let mut profile = create_minimal_profile("test");
     profile.description = Some("A plugin with \"quotes\" and 'apostrophes'".to_string());
 
-    export_as_plugin(&profile, &output_dir, "my-plugin", "1.0.0").expect("Export failed");
+    export_as_plugin(
+        &ResolvedProfile::standalone(profile.clone()),
+        &output_dir,
+        "my-plugin",
+        "1.0.0",
+    )
+    .expect("Export failed");
 
     let manifest_path = output_dir.join(".claude-plugin/plugin.json");
     let content = fs::read_to_string(manifest_path).expect("Failed to read manifest");
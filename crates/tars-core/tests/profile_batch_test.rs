@@ -0,0 +1,112 @@
+//! Batch apply tests
+//!
+//! Applying a profile to every project assigned to it with one backup per
+//! project, and rolling the whole batch back by its ID.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use tars_core::profile::{apply_batch, plan_batch, rollback_batch, BatchError, CommandOverlay};
+use tars_core::storage::db::Database;
+use tars_core::storage::{
+    ApplyBatchStore, BackupStore, BatchProjectStatus, ProfileStore, ProjectStore,
+};
+use tars_core::{Profile, Project};
+use tempfile::TempDir;
+
+const COMMAND: &str = ".claude/commands/review.md";
+
+fn assigned_project(db: &Database, path: PathBuf, name: &str, profile: &Profile) -> Project {
+    let mut project = Project::new(path);
+    project.name = name.to_string();
+    project.assigned_profile_id = Some(profile.id);
+    ProjectStore::new(db.connection()).create(&project).unwrap();
+    project
+}
+
+fn read(root: &Path) -> Option<String> {
+    fs::read_to_string(root.join(COMMAND)).ok()
+}
+
+#[test]
+fn test_batch_apply_and_rollback() {
+    let db = Database::in_memory().unwrap();
+    let work = TempDir::new().unwrap();
+    let backups_dir = TempDir::new().unwrap();
+
+    let mut profile = Profile::new("shared".to_string());
+    profile.repo_overlays.commands.push(CommandOverlay {
+        name: "review".to_string(),
+        content: "Review the diff".to_string(),
+    });
+    ProfileStore::new(db.connection()).create(&profile).unwrap();
+
+    let fresh = work.path().join("fresh");
+    let stale = work.path().join("stale");
+    let current = work.path().join("current");
+    for dir in [&fresh, &stale, &current] {
+        fs::create_dir_all(dir.join(".claude/commands")).unwrap();
+    }
+    fs::write(stale.join(COMMAND), "Old review steps").unwrap();
+    fs::write(current.join(COMMAND), "Review the diff").unwrap();
+
+    assigned_project(&db, fresh.clone(), "fresh", &profile);
+    assigned_project(&db, stale.clone(), "stale", &profile);
+    assigned_project(&db, current.clone(), "current", &profile);
+    assigned_project(&db, work.path().join("gone"), "gone", &profile);
+    // Projects with another profile are left alone
+    ProjectStore::new(db.connection())
+        .create(&Project::new(work.path().to_path_buf()))
+        .unwrap();
+
+    let plan = plan_batch(db.connection(), profile.id).unwrap();
+    let names: Vec<_> = plan
+        .projects
+        .iter()
+        .map(|p| p.project.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["current", "fresh", "gone", "stale"]);
+    assert_eq!(plan.changed(), 2);
+    assert_eq!(plan.failed(), 1);
+    assert_eq!(plan.summary.creates, 1);
+    assert_eq!(plan.summary.modifies, 1);
+
+    let batch = apply_batch(db.connection(), &plan, backups_dir.path()).unwrap();
+    assert_eq!(batch.count(BatchProjectStatus::Applied), 2);
+    assert_eq!(batch.count(BatchProjectStatus::Unchanged), 1);
+    assert_eq!(batch.count(BatchProjectStatus::Failed), 1);
+    assert_eq!(read(&fresh).as_deref(), Some("Review the diff"));
+    assert_eq!(read(&stale).as_deref(), Some("Review the diff"));
+
+    let backups = BackupStore::new(db.connection());
+    for entry in &batch.entries {
+        match entry.status {
+            BatchProjectStatus::Applied => {
+                let backup = backups.get(entry.backup_id.unwrap()).unwrap().unwrap();
+                assert!(backup.archive_path.exists());
+            }
+            _ => assert!(entry.backup_id.is_none()),
+        }
+    }
+    let stored = ApplyBatchStore::new(db.connection())
+        .get(batch.id)
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored, batch);
+
+    let rollback = rollback_batch(db.connection(), batch.id).unwrap();
+    assert!(rollback.failures.is_empty());
+    assert_eq!(rollback.restored, vec!["fresh", "stale"]);
+    assert_eq!(read(&fresh), None);
+    assert_eq!(read(&stale).as_deref(), Some("Old review steps"));
+    assert_eq!(read(&current).as_deref(), Some("Review the diff"));
+
+    let stored = ApplyBatchStore::new(db.connection())
+        .get(batch.id)
+        .unwrap()
+        .unwrap();
+    assert!(stored.rolled_back_at.is_some());
+    assert!(matches!(
+        rollback_batch(db.connection(), batch.id),
+        Err(BatchError::AlreadyRolledBack(_))
+    ));
+}
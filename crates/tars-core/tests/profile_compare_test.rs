@@ -0,0 +1,186 @@
+//! Profile comparison tests
+//!
+//! Tools are matched by kind and name across overlays and storage files;
+//! plugins, adapters and overlay settings are compared as settings.
+
+use tars_core::profile::{
+    compare_profiles, AgentOverlay, ClaudeMdOverlay, CommandOverlay, CompareToolKind,
+    MergeStrategy, OverlayMode, PluginRef, Profile, ToolKey,
+};
+use tars_core::storage::profile_revisions::RevisionFiles;
+use tars_scanner::types::Scope;
+
+fn files(entries: &[(&str, &str)]) -> RevisionFiles {
+    entries
+        .iter()
+        .map(|(path, content)| ((*path).to_string(), content.as_bytes().to_vec()))
+        .collect()
+}
+
+fn plugin(id: &str, enabled: bool) -> PluginRef {
+    PluginRef {
+        id: id.to_string(),
+        marketplace: Some("tools".to_string()),
+        scope: Scope::User,
+        enabled,
+        version_ref: None,
+    }
+}
+
+#[test]
+fn test_compare_profile_tools() {
+    let mut a = Profile::new("frontend".to_string());
+    let mut b = Profile::new("backend".to_string());
+
+    a.repo_overlays.commands.push(CommandOverlay {
+        name: "review".to_string(),
+        content: "Review the diff\nCheck the tests\n".to_string(),
+    });
+    b.repo_overlays.commands.push(CommandOverlay {
+        name: "review".to_string(),
+        content: "Review the diff\nCheck the migrations\n".to_string(),
+    });
+    a.repo_overlays.agents.push(AgentOverlay {
+        name: "designer".to_string(),
+        content: "Design".to_string(),
+    });
+
+    // The same MCP server formatted differently is not a difference
+    let a_files = files(&[
+        (
+            "mcp-servers/github.json",
+            r#"{"command":"npx","args":["gh"]}"#,
+        ),
+        ("skills/testing/SKILL.md", "Write tests"),
+        ("skills/testing/notes.md", "Prefer integration tests"),
+    ]);
+    let b_files = files(&[
+        (
+            "mcp-servers/github.json",
+            "{\n  \"args\": [\"gh\"],\n  \"command\": \"npx\"\n}",
+        ),
+        ("skills/testing/SKILL.md", "Write tests"),
+        ("skills/testing/notes.md", "Prefer unit tests"),
+        ("mcp-servers/postgres.json", r#"{"command":"pg-mcp"}"#),
+    ]);
+
+    let comparison = compare_profiles(&a, &a_files, &b, &b_files);
+    assert!(!comparison.is_identical());
+    assert_eq!(
+        comparison.only_in_a,
+        vec![ToolKey {
+            kind: CompareToolKind::Agent,
+            name: "designer".to_string()
+        }]
+    );
+    assert_eq!(
+        comparison.only_in_b,
+        vec![ToolKey {
+            kind: CompareToolKind::Mcp,
+            name: "postgres".to_string()
+        }]
+    );
+
+    let differing: Vec<_> = comparison
+        .differing
+        .iter()
+        .map(|d| (d.kind, d.name.as_str()))
+        .collect();
+    assert_eq!(
+        differing,
+        vec![
+            (CompareToolKind::Skill, "testing"),
+            (CompareToolKind::Command, "review")
+        ]
+    );
+    let review = &comparison.differing[1].diff;
+    assert!(review.contains("-Check the tests"));
+    assert!(review.contains("+Check the migrations"));
+    assert!(comparison.differing[0].diff.contains("+Prefer unit tests"));
+
+    assert!(compare_profiles(&a, &a_files, &a, &a_files).is_identical());
+}
+
+#[test]
+fn test_compare_profile_settings() {
+    let mut a = Profile::new("frontend".to_string());
+    let mut b = Profile::new("backend".to_string());
+
+    a.repo_overlays.claude_md = Some(ClaudeMdOverlay {
+        mode: OverlayMode::Append,
+        content: "Use pnpm".to_string(),
+    });
+    b.repo_overlays.claude_md = Some(ClaudeMdOverlay {
+        mode: OverlayMode::Replace,
+        content: "Use pnpm".to_string(),
+    });
+    a.plugin_set.plugins.push(plugin("lint", true));
+    a.plugin_set.plugins.push(plugin("format", true));
+    b.plugin_set.plugins.push(plugin("lint", false));
+    b.adapters
+        .merge_strategies
+        .insert("mcp".to_string(), MergeStrategy::Replace);
+
+    let none = RevisionFiles::new();
+    let comparison = compare_profiles(&a, &none, &b, &none);
+    assert!(comparison.only_in_a.is_empty());
+    assert!(comparison.differing.is_empty());
+
+    let plugins: Vec<_> = comparison
+        .plugins
+        .iter()
+        .map(|p| (p.setting.as_str(), p.a.as_deref(), p.b.as_deref()))
+        .collect();
+    assert_eq!(
+        plugins,
+        vec![
+            ("plugin format@tools", Some("user scope, enabled"), None),
+            (
+                "plugin lint@tools",
+                Some("user scope, enabled"),
+                Some("user scope, disabled")
+            ),
+        ]
+    );
+    assert_eq!(comparison.adapters.len(), 1);
+    assert_eq!(comparison.adapters[0].setting, "merge strategy mcp");
+    assert_eq!(comparison.adapters[0].a, None);
+    assert_eq!(comparison.adapters[0].b.as_deref(), Some("replace"));
+
+    // Same CLAUDE.md content, different mode
+    assert_eq!(comparison.overlays.len(), 1);
+    assert_eq!(comparison.overlays[0].setting, "CLAUDE.md mode");
+    assert_eq!(comparison.overlays[0].a.as_deref(), Some("append"));
+    assert_eq!(comparison.overlays[0].b.as_deref(), Some("replace"));
+}
+
+#[test]
+fn test_compare_stored_hooks() {
+    let a = Profile::new("frontend".to_string());
+    let b = Profile::new("backend".to_string());
+    let a_files = files(&[(
+        "hooks.json",
+        r#"{"hooks":{"Stop":[{"hooks":[{"type":"command","command":"echo a"}]}]}}"#,
+    )]);
+    let b_files = files(&[(
+        "hooks.json",
+        r#"{"hooks":{"Stop":[{"hooks":[{"type":"command","command":"echo b"}]}]}}"#,
+    )]);
+
+    let comparison = compare_profiles(&a, &a_files, &b, &b_files);
+    assert!(comparison.only_in_a.is_empty());
+    assert!(comparison.only_in_b.is_empty());
+    assert_eq!(comparison.overlays.len(), 1);
+    let hooks = &comparison.overlays[0];
+    assert_eq!(hooks.setting, "stored hooks");
+    let diff = hooks.diff.as_deref().unwrap();
+    assert!(diff.contains("echo a"));
+    assert!(diff.contains("echo b"));
+
+    // Hooks stored on one side only
+    let comparison = compare_profiles(&a, &a_files, &b, &RevisionFiles::new());
+    assert_eq!(comparison.overlays[0].setting, "stored hooks");
+    assert!(comparison.overlays[0].a.is_some());
+    assert_eq!(comparison.overlays[0].b, None);
+    assert!(compare_profiles(&a, &a_files, &b, &a_files).is_identical());
+}
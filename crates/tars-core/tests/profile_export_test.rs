@@ -315,3 +315,237 @@ fn test_all_tool_types_roundtrip() {
         .iter()
         .any(|t| t.tool_type == ToolType::Hook));
 }
+
+// ============================================================================
+// Version 2 bundles
+// ============================================================================
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use tars_core::profile::{BundledToolKind, ClaudeMdOverlay, OverlayMode, EXPORT_FORMAT_VERSION};
+
+/// Lay out a profile storage directory the way `profile::storage` does.
+fn seed_profile_storage(dir: &Path) {
+    let skill = dir.join("skills").join("my-skill");
+    fs::create_dir_all(skill.join("scripts")).unwrap();
+    fs::write(
+        skill.join("SKILL.md"),
+        "---\nname: my-skill\n---\nDo things.\n",
+    )
+    .unwrap();
+    fs::write(skill.join("scripts").join("run.sh"), "#!/bin/sh\necho hi\n").unwrap();
+    fs::create_dir_all(dir.join("agents")).unwrap();
+    fs::write(dir.join("agents").join("test-agent.md"), "# Agent\n").unwrap();
+    fs::create_dir_all(dir.join("mcp-servers")).unwrap();
+    fs::write(
+        dir.join("mcp-servers").join("context7.json"),
+        r#"{"command": "npx", "args": ["-y", "@upstash/context7-mcp"]}"#,
+    )
+    .unwrap();
+    // Generated plugin output is not part of a bundle.
+    fs::create_dir_all(dir.join("plugin")).unwrap();
+    fs::write(dir.join("plugin").join("plugin.json"), "{}").unwrap();
+}
+
+fn export_seeded_bundle(dir: &Path) -> (Profile, std::path::PathBuf) {
+    let storage = dir.join("storage");
+    seed_profile_storage(&storage);
+    let mut profile = create_profile_with_tools();
+    profile.repo_overlays.claude_md = Some(ClaudeMdOverlay {
+        mode: OverlayMode::Append,
+        content: "## Team rules\n".to_string(),
+    });
+    let bundle = dir.join("profile.tars-profile.zip");
+    export::export_bundle(&profile, &storage, &bundle).expect("Failed to export bundle");
+    (profile, bundle)
+}
+
+#[test]
+fn test_bundle_roundtrip_carries_tool_content() {
+    let dir = tempdir().expect("Failed to create temp dir");
+    let (original, bundle) = export_seeded_bundle(dir.path());
+
+    let preview = export::preview_import(&bundle).expect("Failed to preview");
+    assert_eq!(preview.version, EXPORT_FORMAT_VERSION);
+    assert_eq!(preview.file_count, 4, "plugin/ output must be excluded");
+    assert!(preview.content_hash.is_some());
+
+    let profiles_base = dir.path().join("profiles");
+    let imported = export::import_bundle(&bundle, &profiles_base).expect("Failed to import");
+    assert_ne!(imported.id, original.id);
+    assert_eq!(imported.tool_refs.len(), 3);
+    assert_eq!(
+        imported.repo_overlays.claude_md.map(|o| o.content),
+        Some("## Team rules\n".to_string())
+    );
+
+    let stored = profiles_base.join(imported.id.to_string());
+    assert_eq!(
+        fs::read_to_string(stored.join("skills/my-skill/scripts/run.sh")).unwrap(),
+        "#!/bin/sh\necho hi\n"
+    );
+    assert!(stored.join("agents/test-agent.md").is_file());
+    assert!(stored.join("mcp-servers/context7.json").is_file());
+    assert!(!stored.join("plugin").exists());
+}
+
+#[test]
+fn test_bundle_roundtrip_carries_stored_hooks() {
+    let dir = tempdir().expect("Failed to create temp dir");
+    let storage = dir.path().join("storage");
+    seed_profile_storage(&storage);
+    let hooks = r#"{"hooks":{"PreToolUse":[{"matcher":"Bash","hooks":[{"type":"command","command":"echo hi"}]}]}}"#;
+    fs::write(storage.join("hooks.json"), hooks).unwrap();
+    let bundle = dir.path().join("profile.tars-profile.zip");
+    export::export_bundle(&create_profile_with_tools(), &storage, &bundle)
+        .expect("Failed to export bundle");
+
+    let preview = export::preview_import(&bundle).expect("Failed to preview");
+    assert_eq!(preview.file_count, 5);
+
+    let mut archive = zip::ZipArchive::new(fs::File::open(&bundle).unwrap()).unwrap();
+    let manifest: export::BundleManifest =
+        serde_json::from_reader(archive.by_name("manifest.json").unwrap()).unwrap();
+    let entry = manifest
+        .files
+        .iter()
+        .find(|f| f.path == "hooks.json")
+        .expect("hooks.json must be listed in the manifest");
+
+    let profiles_base = dir.path().join("profiles");
+    let imported = export::import_bundle(&bundle, &profiles_base).expect("Failed to import");
+    let stored = profiles_base
+        .join(imported.id.to_string())
+        .join("hooks.json");
+    assert_eq!(fs::read_to_string(&stored).unwrap(), hooks);
+    assert_eq!(
+        tars_core::profile::storage::compute_file_hash(&stored).unwrap(),
+        entry.sha256
+    );
+}
+
+/// Copy `source` to `dest`, replacing one entry's contents.
+fn rewrite_bundle(source: &Path, dest: &Path, entry: &str, contents: &[u8]) {
+    let mut archive = zip::ZipArchive::new(fs::File::open(source).unwrap()).unwrap();
+    let mut out = zip::ZipWriter::new(fs::File::create(dest).unwrap());
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i).unwrap();
+        let name = file.name().to_string();
+        let mut data = Vec::new();
+        std::io::Read::read_to_end(&mut file, &mut data).unwrap();
+        out.start_file(name.clone(), zip::write::FileOptions::default())
+            .unwrap();
+        out.write_all(if name == entry { contents } else { &data })
+            .unwrap();
+    }
+    if archive.by_name(entry).is_err() {
+        out.start_file(entry, zip::write::FileOptions::default())
+            .unwrap();
+        out.write_all(contents).unwrap();
+    }
+    out.finish().unwrap();
+}
+
+#[test]
+fn test_bundle_with_tampered_file_is_rejected() {
+    let dir = tempdir().expect("Failed to create temp dir");
+    let (_, bundle) = export_seeded_bundle(dir.path());
+    let tampered = dir.path().join("tampered.zip");
+    rewrite_bundle(
+        &bundle,
+        &tampered,
+        "files/agents/test-agent.md",
+        b"# Evil\n",
+    );
+
+    let err = export::preview_import(&tampered).unwrap_err();
+    assert!(matches!(err, export::ExportError::Integrity(_)), "{err}");
+
+    let profiles_base = dir.path().join("profiles");
+    assert!(export::import_bundle(&tampered, &profiles_base).is_err());
+    assert!(!profiles_base.exists(), "nothing may be written");
+}
+
+#[test]
+fn test_bundle_with_traversal_path_is_rejected() {
+    let dir = tempdir().expect("Failed to create temp dir");
+    let (_, bundle) = export_seeded_bundle(dir.path());
+    let hostile = dir.path().join("hostile.zip");
+    rewrite_bundle(
+        &bundle,
+        &hostile,
+        "files/skills/../../escape.sh",
+        b"rm -rf /\n",
+    );
+
+    let err = export::import_bundle(&hostile, &dir.path().join("profiles")).unwrap_err();
+    assert!(
+        matches!(err, export::ExportError::InvalidBundle(_)),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_preview_reports_conflicts_with_local_tools() {
+    let dir = tempdir().expect("Failed to create temp dir");
+    let (_, bundle) = export_seeded_bundle(dir.path());
+
+    let home = dir.path().join("home");
+    // Same skill, byte for byte.
+    seed_profile_storage(&home.join("stash"));
+    let claude = home.join(".claude");
+    fs::create_dir_all(claude.join("skills")).unwrap();
+    fs::rename(
+        home.join("stash/skills/my-skill"),
+        claude.join("skills/my-skill"),
+    )
+    .unwrap();
+    // Same agent name, different content.
+    fs::create_dir_all(claude.join("agents")).unwrap();
+    fs::write(claude.join("agents/test-agent.md"), "# Local agent\n").unwrap();
+    // Same MCP server config, different key order.
+    fs::write(
+        home.join(".claude.json"),
+        r#"{"mcpServers": {"context7": {"args": ["-y", "@upstash/context7-mcp"], "command": "npx"}}}"#,
+    )
+    .unwrap();
+
+    let preview = export::preview_import_with_conflicts(&bundle, &home).expect("preview");
+    let mut found: Vec<_> = preview
+        .conflicts
+        .iter()
+        .map(|c| (c.kind, c.name.as_str(), c.identical))
+        .collect();
+    found.sort_by_key(|(_, name, _)| *name);
+    assert_eq!(
+        found,
+        vec![
+            (BundledToolKind::McpServer, "context7", true),
+            (BundledToolKind::Skill, "my-skill", true),
+            (BundledToolKind::Agent, "test-agent", false),
+        ]
+    );
+
+    // Plain previews do not look at the local machine.
+    assert!(export::preview_import(&bundle)
+        .unwrap()
+        .conflicts
+        .is_empty());
+}
+
+#[test]
+fn test_json_export_stays_version_one_and_is_importable() {
+    let dir = tempdir().expect("Failed to create temp dir");
+    let export_path = dir.path().join("profile.tars-profile.json");
+    export::export_profile(&create_profile_with_tools(), &export_path).unwrap();
+
+    let json = fs::read_to_string(&export_path).unwrap();
+    assert!(
+        !json.contains("repo_overlays"),
+        "v1 JSON stays reference-only"
+    );
+    let preview = export::preview_import_with_conflicts(&export_path, dir.path()).unwrap();
+    assert_eq!(preview.version, 1);
+    assert_eq!(preview.file_count, 0);
+}
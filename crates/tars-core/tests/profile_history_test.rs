@@ -0,0 +1,222 @@
+//! Profile revision history tests
+//!
+//! Tests for recording, diffing and restoring profile revisions.
+
+use std::fs;
+use tars_core::profile::history::{read_revision_files, restore_revision_files};
+use tars_core::profile::{
+    diff_revisions, find_revision, FileChangeKind, HistoryError, Profile, SkillOverlay,
+};
+use tars_core::storage::db::Database;
+use tars_core::storage::profile_revisions::revision_hash;
+use tars_core::storage::{ProfileRevisionStore, ProfileStore, RevisionFiles};
+use tempfile::TempDir;
+
+fn files(entries: &[(&str, &[u8])]) -> RevisionFiles {
+    entries
+        .iter()
+        .map(|(path, content)| ((*path).to_string(), content.to_vec()))
+        .collect()
+}
+
+fn blob_count(db: &Database) -> i64 {
+    db.connection()
+        .query_row("SELECT COUNT(*) FROM profile_revision_blobs", [], |row| {
+            row.get(0)
+        })
+        .unwrap()
+}
+
+#[test]
+fn test_create_and_update_record_revisions() {
+    let db = Database::in_memory().unwrap();
+    let store = ProfileStore::new(db.connection());
+    let revisions = ProfileRevisionStore::new(db.connection());
+
+    let mut profile = Profile::new("history".to_string());
+    store.create(&profile).unwrap();
+    assert_eq!(revisions.list(profile.id).unwrap().len(), 1);
+
+    // Touching only the timestamp is not a change
+    profile.updated_at = chrono::Utc::now();
+    store.update(&profile).unwrap();
+    assert_eq!(revisions.list(profile.id).unwrap().len(), 1);
+
+    profile.description = Some("edited".to_string());
+    store.update(&profile).unwrap();
+    let history = revisions.list(profile.id).unwrap();
+    assert_eq!(history.len(), 2);
+    assert!(history[0].id > history[1].id);
+    assert_ne!(history[0].hash, history[1].hash);
+
+    let latest = revisions.snapshot(&history[0]).unwrap();
+    assert_eq!(latest.profile.description.as_deref(), Some("edited"));
+}
+
+#[test]
+fn test_revision_hash_ignores_updated_at() {
+    let mut profile = Profile::new("hash".to_string());
+    let stored = files(&[("agents/a.md", b"agent")]);
+    let before = revision_hash(&profile, &stored).unwrap();
+    profile.updated_at += chrono::Duration::hours(1);
+    assert_eq!(revision_hash(&profile, &stored).unwrap(), before);
+
+    let changed = files(&[("agents/a.md", b"agent v2")]);
+    assert_ne!(revision_hash(&profile, &changed).unwrap(), before);
+}
+
+#[test]
+fn test_revision_files_are_content_addressed() {
+    let db = Database::in_memory().unwrap();
+    let store = ProfileStore::new(db.connection());
+    let revisions = ProfileRevisionStore::new(db.connection());
+
+    let profile = Profile::new("blobs".to_string());
+    store.create(&profile).unwrap();
+
+    let first = files(&[("skills/a/SKILL.md", b"same"), ("agents/b.md", b"same")]);
+    let second = files(&[("skills/a/SKILL.md", b"same"), ("agents/b.md", b"new")]);
+    let rev1 = revisions.record(&profile, &first).unwrap().unwrap();
+    assert!(revisions.record(&profile, &first).unwrap().is_none());
+    let rev2 = revisions.record(&profile, &second).unwrap().unwrap();
+
+    assert_eq!(blob_count(&db), 2);
+    assert_eq!(rev1.file_count, 2);
+    assert_eq!(revisions.snapshot(&rev1).unwrap().files, first);
+    assert_eq!(revisions.snapshot(&rev2).unwrap().files, second);
+
+    let found = find_revision(&revisions, &rev2.hash[..8]).unwrap();
+    assert_eq!(found.id, rev2.id);
+    assert!(matches!(
+        find_revision(&revisions, "not-hex"),
+        Err(HistoryError::NotFound(_))
+    ));
+}
+
+#[test]
+fn test_diff_between_revisions() {
+    let db = Database::in_memory().unwrap();
+    let store = ProfileStore::new(db.connection());
+    let revisions = ProfileRevisionStore::new(db.connection());
+
+    let mut profile = Profile::new("diff".to_string());
+    store.create(&profile).unwrap();
+    let old_files = files(&[
+        ("agents/keep.md", b"line one\nline two\n"),
+        ("agents/gone.md", b"bye"),
+        ("skills/pdf/logo.png", &[0xff, 0xfe]),
+    ]);
+    let rev_a = revisions.record(&profile, &old_files).unwrap().unwrap();
+
+    profile.repo_overlays.skills.push(SkillOverlay {
+        name: "review".to_string(),
+        content: "Review code".to_string(),
+        files: Vec::new(),
+    });
+    let new_files = files(&[
+        ("agents/keep.md", b"line one\nline 2\n"),
+        ("commands/new.md", b"hi"),
+        ("skills/pdf/logo.png", &[0xff, 0xfd]),
+    ]);
+    let rev_b = revisions.record(&profile, &new_files).unwrap().unwrap();
+
+    let diff = diff_revisions(
+        &revisions.snapshot(&rev_a).unwrap(),
+        &revisions.snapshot(&rev_b).unwrap(),
+    );
+    assert!(diff.profile.as_deref().unwrap().contains('+'));
+    assert!(diff.profile.as_deref().unwrap().contains("Review code"));
+
+    let changes: Vec<_> = diff
+        .files
+        .iter()
+        .map(|f| (f.path.as_str(), f.kind))
+        .collect();
+    assert_eq!(
+        changes,
+        vec![
+            ("agents/gone.md", FileChangeKind::Removed),
+            ("agents/keep.md", FileChangeKind::Modified),
+            ("commands/new.md", FileChangeKind::Added),
+            ("skills/pdf/logo.png", FileChangeKind::Modified),
+        ]
+    );
+    let keep = diff.files[1].diff.as_deref().unwrap();
+    assert!(keep.contains("-line two"));
+    assert!(keep.contains("+line 2"));
+    assert!(diff.files[3].diff.is_none());
+
+    let same = revisions.snapshot(&rev_b).unwrap();
+    assert!(diff_revisions(&same, &same).is_empty());
+}
+
+#[test]
+fn test_restore_revision_files() {
+    let db = Database::in_memory().unwrap();
+    let store = ProfileStore::new(db.connection());
+    let revisions = ProfileRevisionStore::new(db.connection());
+    let dir = TempDir::new().unwrap();
+
+    let profile = Profile::new("restore".to_string());
+    store.create(&profile).unwrap();
+
+    fs::create_dir_all(dir.path().join("skills/a")).unwrap();
+    fs::write(dir.path().join("skills/a/SKILL.md"), "v1").unwrap();
+    fs::create_dir_all(dir.path().join("plugin")).unwrap();
+    fs::write(dir.path().join("plugin/plugin.json"), "{}").unwrap();
+
+    let stored = read_revision_files(dir.path()).unwrap();
+    assert_eq!(stored.keys().collect::<Vec<_>>(), vec!["skills/a/SKILL.md"]);
+    let rev = revisions.record(&profile, &stored).unwrap().unwrap();
+
+    fs::write(dir.path().join("skills/a/SKILL.md"), "v2").unwrap();
+    fs::create_dir_all(dir.path().join("agents")).unwrap();
+    fs::write(dir.path().join("agents/extra.md"), "extra").unwrap();
+
+    let snapshot = revisions.snapshot(&rev).unwrap();
+    restore_revision_files(&snapshot, dir.path()).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(dir.path().join("skills/a/SKILL.md")).unwrap(),
+        "v1"
+    );
+    assert!(!dir.path().join("agents").exists());
+    // Generated plugin output is untouched
+    assert!(dir.path().join("plugin/plugin.json").exists());
+
+    let mut unsafe_snapshot = snapshot;
+    unsafe_snapshot
+        .files
+        .insert("../escape.md".to_string(), b"x".to_vec());
+    assert!(matches!(
+        restore_revision_files(&unsafe_snapshot, dir.path()),
+        Err(HistoryError::InvalidPath(_))
+    ));
+}
+
+#[test]
+fn test_delete_removes_history_and_unused_blobs() {
+    let db = Database::in_memory().unwrap();
+    let store = ProfileStore::new(db.connection());
+    let revisions = ProfileRevisionStore::new(db.connection());
+
+    let kept = Profile::new("kept".to_string());
+    let dropped = Profile::new("dropped".to_string());
+    store.create(&kept).unwrap();
+    store.create(&dropped).unwrap();
+    revisions
+        .record(&kept, &files(&[("agents/shared.md", b"shared")]))
+        .unwrap();
+    revisions
+        .record(
+            &dropped,
+            &files(&[("agents/shared.md", b"shared"), ("agents/own.md", b"own")]),
+        )
+        .unwrap();
+    assert_eq!(blob_count(&db), 2);
+
+    store.delete(dropped.id).unwrap();
+    assert!(revisions.list(dropped.id).unwrap().is_empty());
+    assert_eq!(blob_count(&db), 1);
+    assert_eq!(revisions.list(kept.id).unwrap().len(), 2);
+}
@@ -0,0 +1,180 @@
+//! Profile lock file tests
+//!
+//! Writing `.claude/tars.lock` on apply, verifying a project against it and
+//! reproducing the locked revision after the profile changed.
+
+use std::fs;
+use std::path::Path;
+use tars_core::apply::apply_operations;
+use tars_core::diff::plan::generate_plan;
+use tars_core::diff::FileOperation;
+use tars_core::profile::storage::{ensure_profile_dir, profile_dir};
+use tars_core::profile::{
+    lock_plan, locked_profile, preview_lock_plan, read_lock, resolve_profile, verify_lock,
+    ClaudeMdOverlay, CommandOverlay, LockError, LockedSource, OverlayMode, Profile, ProfileLock,
+    LOCK_FILE,
+};
+use tars_core::storage::db::Database;
+use tars_core::storage::{ProfileRevisionStore, ProfileStore};
+use tars_core::Backup;
+use tempfile::TempDir;
+use uuid::Uuid;
+
+fn create_profile(db: &Database, review: &str) -> Profile {
+    let mut profile = Profile::new("team".to_string());
+    profile.repo_overlays.claude_md = Some(ClaudeMdOverlay {
+        mode: OverlayMode::Replace,
+        content: "# Team rules".to_string(),
+    });
+    profile.repo_overlays.commands.push(CommandOverlay {
+        name: "review".to_string(),
+        content: review.to_string(),
+    });
+    ProfileStore::new(db.connection()).create(&profile).unwrap();
+    profile
+}
+
+/// Plan, lock and apply `profile` the way `tars profile apply --lock` does
+fn apply_locked(db: &Database, project: &Path, profile: &Profile) -> ProfileLock {
+    let resolved = resolve_profile(&ProfileStore::new(db.connection()), profile).unwrap();
+    let mut plan = generate_plan(Uuid::new_v4(), project, &resolved.profile).unwrap();
+    let lock = lock_plan(db.connection(), project, &resolved, &mut plan).unwrap();
+    let mut backup = Backup::new(plan.project_id, project.join("backup.json"));
+    apply_operations(&plan, project, &mut backup).unwrap();
+    lock
+}
+
+#[test]
+fn test_apply_writes_lock_that_verifies() {
+    let db = Database::in_memory().unwrap();
+    let project = TempDir::new().unwrap();
+    let profile = create_profile(&db, "Review $ARGUMENTS");
+
+    let lock = apply_locked(&db, project.path(), &profile);
+    assert_eq!(lock.profile, "team");
+    assert_eq!(lock.profile_id, profile.id);
+    assert!(lock.revision.is_some());
+    assert_eq!(
+        lock.files.keys().collect::<Vec<_>>(),
+        vec![".claude/commands/review.md", "CLAUDE.md"]
+    );
+    assert_eq!(read_lock(project.path()).unwrap(), lock);
+
+    let verification = verify_lock(project.path()).unwrap();
+    assert!(verification.is_clean());
+
+    // Re-applying an unchanged profile leaves the lock untouched
+    let resolved = resolve_profile(&ProfileStore::new(db.connection()), &profile).unwrap();
+    let mut plan = generate_plan(Uuid::new_v4(), project.path(), &resolved.profile).unwrap();
+    lock_plan(db.connection(), project.path(), &resolved, &mut plan).unwrap();
+    assert!(plan.is_empty());
+}
+
+#[test]
+fn test_verify_reports_modified_and_missing_files() {
+    let db = Database::in_memory().unwrap();
+    let project = TempDir::new().unwrap();
+    let profile = create_profile(&db, "Review $ARGUMENTS");
+    apply_locked(&db, project.path(), &profile);
+
+    fs::write(project.path().join("CLAUDE.md"), "# Edited").unwrap();
+    fs::remove_file(project.path().join(".claude/commands/review.md")).unwrap();
+
+    let verification = verify_lock(project.path()).unwrap();
+    assert!(!verification.is_clean());
+    let mismatches: Vec<_> = verification
+        .mismatches
+        .iter()
+        .map(|m| (m.path.as_str(), m.actual.is_some()))
+        .collect();
+    assert_eq!(
+        mismatches,
+        vec![(".claude/commands/review.md", false), ("CLAUDE.md", true)]
+    );
+}
+
+#[test]
+fn test_locked_revision_is_reproduced_after_profile_changes() {
+    let db = Database::in_memory().unwrap();
+    let project = TempDir::new().unwrap();
+    let mut profile = create_profile(&db, "Review v1");
+    let lock = apply_locked(&db, project.path(), &profile);
+
+    // The profile moves on; a fresh checkout only has the lock
+    profile.repo_overlays.commands[0].content = "Review v2".to_string();
+    ProfileStore::new(db.connection()).update(&profile).unwrap();
+    fs::remove_file(project.path().join("CLAUDE.md")).unwrap();
+    fs::remove_file(project.path().join(".claude/commands/review.md")).unwrap();
+
+    let locked = locked_profile(db.connection(), &lock).unwrap();
+    assert_eq!(locked.source, LockedSource::Revision);
+
+    let mut plan = generate_plan(Uuid::new_v4(), project.path(), &locked.resolved.profile).unwrap();
+    assert!(plan.operations.iter().any(|op| matches!(
+        op,
+        FileOperation::Create { content, .. } if content == b"Review v1"
+    )));
+    let mut relocked = lock.clone();
+    relocked.files.clear();
+    relocked.record_plan(project.path(), &plan).unwrap();
+    relocked.add_to_plan(project.path(), &mut plan).unwrap();
+    assert_eq!(relocked, lock);
+
+    let mut backup = Backup::new(plan.project_id, project.path().join("backup.json"));
+    apply_operations(&plan, project.path(), &mut backup).unwrap();
+    assert!(verify_lock(project.path()).unwrap().is_clean());
+}
+
+#[test]
+fn test_read_lock_rejects_paths_outside_project() {
+    let project = TempDir::new().unwrap();
+    fs::create_dir_all(project.path().join(".claude")).unwrap();
+
+    assert!(matches!(
+        read_lock(project.path()),
+        Err(LockError::NotFound(_))
+    ));
+
+    let mut lock = ProfileLock::new(&Profile::new("team".to_string()), None, "empty".into());
+    lock.files
+        .insert("../outside.md".to_string(), "0".repeat(64));
+    fs::write(project.path().join(LOCK_FILE), lock.to_bytes()).unwrap();
+    assert!(matches!(
+        read_lock(project.path()),
+        Err(LockError::InvalidPath(_))
+    ));
+}
+
+#[test]
+fn test_preview_lock_names_revision_without_recording_it() {
+    struct StorageGuard(Uuid);
+    impl Drop for StorageGuard {
+        fn drop(&mut self) {
+            if let Ok(path) = profile_dir(self.0) {
+                let _ = fs::remove_dir_all(path);
+            }
+        }
+    }
+
+    let db = Database::in_memory().unwrap();
+    let project = TempDir::new().unwrap();
+    let profile = create_profile(&db, "Review $ARGUMENTS");
+    let _guard = StorageGuard(profile.id);
+    let revisions = ProfileRevisionStore::new(db.connection());
+    assert_eq!(revisions.list(profile.id).unwrap().len(), 1);
+
+    // Stored files change without a revision being recorded
+    let storage = ensure_profile_dir(profile.id).unwrap();
+    fs::write(storage.join("notes.md"), "Unrecorded").unwrap();
+
+    let resolved = resolve_profile(&ProfileStore::new(db.connection()), &profile).unwrap();
+    let mut preview = generate_plan(Uuid::new_v4(), project.path(), &resolved.profile).unwrap();
+    let previewed =
+        preview_lock_plan(db.connection(), project.path(), &resolved, &mut preview).unwrap();
+    assert_eq!(revisions.list(profile.id).unwrap().len(), 1);
+    assert!(!project.path().join(LOCK_FILE).exists());
+
+    let locked = apply_locked(&db, project.path(), &profile);
+    assert_eq!(revisions.list(profile.id).unwrap().len(), 2);
+    assert_eq!(previewed, locked);
+}
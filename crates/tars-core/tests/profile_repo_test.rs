@@ -0,0 +1,356 @@
+//! Git profile repository tests
+//!
+//! Two machines share profiles through a local bare repository.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tars_core::profile::{Profile, RepoProfileState, RepoStatus, RepoSync, RepoWorkspace};
+use tars_core::storage::db::Database;
+use tars_core::storage::{ProfileRepo, ProfileStore};
+use tempfile::TempDir;
+
+fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .expect("git should run");
+    assert!(
+        output.status.success(),
+        "git {args:?}: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+struct Machine {
+    db: Database,
+    workspace: RepoWorkspace,
+    profiles_base: std::path::PathBuf,
+}
+
+impl Machine {
+    fn new(root: &Path, name: &str) -> Self {
+        let profiles_base = root.join(name).join("profiles");
+        Self {
+            db: Database::in_memory().unwrap(),
+            workspace: RepoWorkspace::new(root.join(name).join("repos"), profiles_base.clone()),
+            profiles_base,
+        }
+    }
+
+    fn sync(&self) -> RepoSync<'_> {
+        RepoSync::new(self.db.connection(), self.workspace.clone())
+    }
+
+    fn profiles(&self) -> ProfileStore<'_> {
+        ProfileStore::new(self.db.connection())
+    }
+
+    fn states(&self, repo: &ProfileRepo) -> Vec<(String, RepoProfileState)> {
+        let status: RepoStatus = self.sync().status(repo).unwrap();
+        status
+            .profiles
+            .into_iter()
+            .map(|p| (p.name, p.state))
+            .collect()
+    }
+
+    fn edit_description(&self, name: &str, description: &str) -> Profile {
+        let mut profile = self.profiles().get_by_name(name).unwrap().unwrap();
+        profile.description = Some(description.to_string());
+        self.profiles().update(&profile).unwrap();
+        profile
+    }
+}
+
+fn bare_repo(root: &Path) -> String {
+    let bare = root.join("team.git");
+    fs::create_dir_all(&bare).unwrap();
+    git(&bare, &["init", "-q", "--bare", "--initial-branch=main"]);
+    bare.display().to_string()
+}
+
+#[test]
+fn test_share_profiles_through_bare_repo() {
+    let root = TempDir::new().unwrap();
+    let url = bare_repo(root.path());
+    let alice = Machine::new(root.path(), "alice");
+    let bob = Machine::new(root.path(), "bob");
+
+    // Alice publishes a profile to the empty repository
+    let repo_a = alice.sync().add("team", &url, Some("main")).unwrap();
+    assert_eq!(repo_a.branch, "main");
+    let backend = Profile::new("backend".to_string());
+    let agents = alice
+        .profiles_base
+        .join(backend.id.to_string())
+        .join("agents");
+    fs::create_dir_all(&agents).unwrap();
+    fs::write(agents.join("reviewer.md"), "Review carefully").unwrap();
+    alice.profiles().create(&backend).unwrap();
+
+    let pushed = alice
+        .sync()
+        .push(
+            &repo_a,
+            std::slice::from_ref(&backend),
+            "main",
+            "Add backend profile",
+        )
+        .unwrap();
+    assert!(pushed.commit.is_some());
+    assert_eq!(
+        alice.states(&repo_a),
+        vec![("backend".to_string(), RepoProfileState::UpToDate)]
+    );
+
+    // Bob picks it up, with its files
+    let repo_b = bob.sync().add("team", &url, None).unwrap();
+    assert_eq!(repo_b.branch, "main");
+    assert_eq!(
+        bob.states(&repo_b),
+        vec![("backend".to_string(), RepoProfileState::New)]
+    );
+    let pulled = bob.sync().pull(&repo_b, false).unwrap();
+    assert_eq!(pulled.created, vec!["backend"]);
+    let bob_backend = bob.profiles().get_by_name("backend").unwrap().unwrap();
+    assert_eq!(
+        fs::read_to_string(
+            bob.profiles_base
+                .join(bob_backend.id.to_string())
+                .join("agents/reviewer.md")
+        )
+        .unwrap(),
+        "Review carefully"
+    );
+
+    // Bob proposes an edit on a branch; it stays local until merged
+    let edited = bob.edit_description("backend", "Backend services");
+    let proposal = bob
+        .sync()
+        .push(
+            &repo_b,
+            &[edited],
+            "tars/backend-description",
+            "Describe backend",
+        )
+        .unwrap();
+    let commit = proposal.commit.unwrap();
+    assert_eq!(
+        git(
+            Path::new(&url),
+            &["rev-parse", "refs/heads/tars/backend-description"]
+        ),
+        commit
+    );
+    assert_eq!(
+        bob.states(&repo_b),
+        vec![("backend".to_string(), RepoProfileState::LocalChanged)]
+    );
+    assert_eq!(
+        alice.states(&repo_a),
+        vec![("backend".to_string(), RepoProfileState::UpToDate)]
+    );
+
+    // Once merged, Alice sees the upstream change and Bob is in sync
+    git(
+        Path::new(&url),
+        &[
+            "update-ref",
+            "refs/heads/main",
+            "refs/heads/tars/backend-description",
+        ],
+    );
+    let status = alice.sync().status(&repo_a).unwrap();
+    assert!(status.has_upstream_changes());
+    assert_eq!(status.profiles[0].state, RepoProfileState::UpstreamChanged);
+    let pulled = alice.sync().pull(&repo_a, false).unwrap();
+    assert_eq!(pulled.updated, vec!["backend"]);
+    let alice_backend = alice.profiles().get_by_name("backend").unwrap().unwrap();
+    assert_eq!(alice_backend.id, backend.id);
+    assert_eq!(
+        alice_backend.description.as_deref(),
+        Some("Backend services")
+    );
+    assert_eq!(
+        bob.states(&repo_b),
+        vec![("backend".to_string(), RepoProfileState::UpToDate)]
+    );
+}
+
+#[test]
+fn test_diverged_profiles_need_force() {
+    let root = TempDir::new().unwrap();
+    let url = bare_repo(root.path());
+    let alice = Machine::new(root.path(), "alice");
+    let bob = Machine::new(root.path(), "bob");
+
+    let repo_a = alice.sync().add("team", &url, None).unwrap();
+    let shared = Profile::new("shared".to_string());
+    alice.profiles().create(&shared).unwrap();
+    alice
+        .sync()
+        .push(&repo_a, &[shared], "main", "Add shared")
+        .unwrap();
+
+    let repo_b = bob.sync().add("team", &url, None).unwrap();
+    bob.sync().pull(&repo_b, false).unwrap();
+
+    // Both edit; Bob publishes directly
+    alice.edit_description("shared", "alice");
+    let bobs = bob.edit_description("shared", "bob");
+    bob.sync()
+        .push(&repo_b, &[bobs], "main", "Bob's description")
+        .unwrap();
+
+    let status = alice.sync().status(&repo_a).unwrap();
+    assert_eq!(status.profiles[0].state, RepoProfileState::Diverged);
+    assert!(status.has_local_changes());
+
+    let pulled = alice.sync().pull(&repo_a, false).unwrap();
+    assert_eq!(pulled.skipped.len(), 1);
+    assert!(pulled.updated.is_empty());
+
+    let pulled = alice.sync().pull(&repo_a, true).unwrap();
+    assert_eq!(pulled.updated, vec!["shared"]);
+    let profile = alice.profiles().get_by_name("shared").unwrap().unwrap();
+    assert_eq!(profile.description.as_deref(), Some("bob"));
+}
+
+#[test]
+fn test_stored_hooks_travel_through_repo() {
+    let root = TempDir::new().unwrap();
+    let url = bare_repo(root.path());
+    let alice = Machine::new(root.path(), "alice");
+    let bob = Machine::new(root.path(), "bob");
+
+    let repo_a = alice.sync().add("team", &url, None).unwrap();
+    let hooked = Profile::new("hooked".to_string());
+    alice.profiles().create(&hooked).unwrap();
+    alice
+        .sync()
+        .push(&repo_a, std::slice::from_ref(&hooked), "main", "Add hooked")
+        .unwrap();
+    let repo_b = bob.sync().add("team", &url, None).unwrap();
+    bob.sync().pull(&repo_b, false).unwrap();
+
+    // Editing only hooks.json is a local change that can be pushed
+    let hooks = r#"{"hooks":{"Stop":[{"hooks":[{"type":"command","command":"echo done"}]}]}}"#;
+    let alice_dir = alice.profiles_base.join(hooked.id.to_string());
+    fs::create_dir_all(&alice_dir).unwrap();
+    fs::write(alice_dir.join("hooks.json"), hooks).unwrap();
+    assert_eq!(
+        alice.states(&repo_a),
+        vec![("hooked".to_string(), RepoProfileState::LocalChanged)]
+    );
+    alice
+        .sync()
+        .push(&repo_a, &[hooked], "main", "Add hooks")
+        .unwrap();
+
+    // Bob sees it upstream and pulls it into his storage
+    assert_eq!(
+        bob.states(&repo_b),
+        vec![("hooked".to_string(), RepoProfileState::UpstreamChanged)]
+    );
+    let pulled = bob.sync().pull(&repo_b, false).unwrap();
+    assert_eq!(pulled.updated, vec!["hooked"]);
+    let bob_hooked = bob.profiles().get_by_name("hooked").unwrap().unwrap();
+    assert_eq!(
+        fs::read_to_string(
+            bob.profiles_base
+                .join(bob_hooked.id.to_string())
+                .join("hooks.json")
+        )
+        .unwrap(),
+        hooks
+    );
+    assert_eq!(
+        bob.states(&repo_b),
+        vec![("hooked".to_string(), RepoProfileState::UpToDate)]
+    );
+}
+
+#[test]
+fn test_push_refuses_another_profiles_directory() {
+    let root = TempDir::new().unwrap();
+    let url = bare_repo(root.path());
+    let alice = Machine::new(root.path(), "alice");
+    let bob = Machine::new(root.path(), "bob");
+
+    let repo_a = alice.sync().add("team", &url, None).unwrap();
+    let ours = Profile::new("tooling".to_string());
+    alice.profiles().create(&ours).unwrap();
+    alice
+        .sync()
+        .push(&repo_a, &[ours], "main", "Add tooling")
+        .unwrap();
+
+    // Bob has an unrelated local profile with the same name
+    let repo_b = bob.sync().add("team", &url, None).unwrap();
+    let theirs = Profile::new("tooling".to_string());
+    bob.profiles().create(&theirs).unwrap();
+    assert!(bob
+        .sync()
+        .push(&repo_b, &[theirs], "tars/tooling", "Add tooling")
+        .is_err());
+
+    let pulled = bob.sync().pull(&repo_b, false).unwrap();
+    assert_eq!(pulled.skipped.len(), 1);
+    assert!(pulled.created.is_empty());
+}
+
+#[test]
+fn test_push_never_touches_an_enclosing_repository() {
+    let root = TempDir::new().unwrap();
+    let url = bare_repo(root.path());
+    let alice = Machine::new(root.path(), "alice");
+
+    // Alice's TARS data lives inside a repository of her own that also
+    // tracks the team remote
+    let outer = root.path().join("alice");
+    fs::create_dir_all(&outer).unwrap();
+    git(&outer, &["init", "-q", "--initial-branch=work"]);
+    git(&outer, &["remote", "add", "origin", &url]);
+    fs::write(outer.join("notes.txt"), "mine").unwrap();
+    git(&outer, &["add", "notes.txt"]);
+    git(
+        &outer,
+        &[
+            "-c",
+            "user.name=Alice",
+            "-c",
+            "user.email=alice@localhost",
+            "commit",
+            "-q",
+            "-m",
+            "Notes",
+        ],
+    );
+    fs::write(outer.join("scratch.txt"), "untracked").unwrap();
+
+    let repo = alice.sync().add("team", &url, None).unwrap();
+    let tooling = Profile::new("tooling".to_string());
+    alice.profiles().create(&tooling).unwrap();
+    alice
+        .sync()
+        .push(&repo, std::slice::from_ref(&tooling), "main", "Add tooling")
+        .unwrap();
+    let head = git(&outer, &["rev-parse", "HEAD"]);
+    let branches = git(&outer, &["branch", "--list"]);
+
+    // The clone loses its .git, so git would find Alice's repository
+    let checkout = alice.workspace.checkout(&repo);
+    fs::remove_dir_all(checkout.join(".git")).unwrap();
+
+    assert!(alice
+        .sync()
+        .push(&repo, &[tooling], "tars/tooling", "Update tooling")
+        .is_err());
+    assert_eq!(git(&outer, &["rev-parse", "HEAD"]), head);
+    assert_eq!(git(&outer, &["branch", "--list"]), branches);
+    assert_eq!(git(&outer, &["symbolic-ref", "--short", "HEAD"]), "work");
+    assert!(outer.join("scratch.txt").exists());
+}
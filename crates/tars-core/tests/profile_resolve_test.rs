@@ -0,0 +1,228 @@
+//! Profile inheritance tests
+//!
+//! Tests for layering `extends` parents into a resolved profile.
+
+use std::collections::HashMap;
+use tars_core::profile::{
+    resolve_profile, resolve_with, ArtifactKind, ClaudeMdOverlay, MergeStrategy, OverlayMode,
+    PermissionsOverlay, Profile, ResolveError, SkillOverlay, ToolRef, ToolType,
+};
+use tars_core::storage::db::Database;
+use tars_core::storage::profiles::ProfileStore;
+use uuid::Uuid;
+
+fn skill(name: &str, content: &str) -> SkillOverlay {
+    SkillOverlay {
+        name: name.to_string(),
+        content: content.to_string(),
+        files: Vec::new(),
+    }
+}
+
+fn tool(name: &str, tool_type: ToolType) -> ToolRef {
+    ToolRef {
+        name: name.to_string(),
+        tool_type,
+        source_scope: None,
+        permissions: None,
+        source_ref: None,
+    }
+}
+
+fn profile(name: &str, extends: &[&Profile]) -> Profile {
+    let mut profile = Profile::new(name.to_string());
+    profile.extends = extends.iter().map(|p| p.id).collect();
+    profile
+}
+
+fn lookup(profiles: &[&Profile]) -> HashMap<Uuid, Profile> {
+    profiles.iter().map(|p| (p.id, (*p).clone())).collect()
+}
+
+fn resolve(leaf: &Profile, all: &HashMap<Uuid, Profile>) -> Result<Vec<String>, ResolveError> {
+    let resolved = resolve_with(leaf, |id| Ok(all.get(&id).cloned()))?;
+    Ok(resolved.layers.into_iter().map(|l| l.name).collect())
+}
+
+#[test]
+fn test_later_layers_override_by_name() {
+    let mut base = profile("base", &[]);
+    base.repo_overlays.skills = vec![skill("lint", "base lint"), skill("docs", "base docs")];
+    base.tool_refs = vec![tool("fmt", ToolType::Skill), tool("fmt", ToolType::Agent)];
+
+    let mut team = profile("team", &[&base]);
+    team.repo_overlays.skills = vec![skill("lint", "team lint"), skill("review", "team")];
+    team.tool_refs = vec![tool("fmt", ToolType::Skill)];
+
+    let all = lookup(&[&base]);
+    let resolved = resolve_with(&team, |id| Ok(all.get(&id).cloned())).unwrap();
+
+    let skills: Vec<_> = resolved
+        .profile
+        .repo_overlays
+        .skills
+        .iter()
+        .map(|s| (s.name.as_str(), s.content.as_str()))
+        .collect();
+    assert_eq!(
+        skills,
+        vec![
+            ("lint", "team lint"),
+            ("docs", "base docs"),
+            ("review", "team")
+        ]
+    );
+    assert_eq!(resolved.profile.id, team.id);
+    assert_eq!(resolved.profile.tool_refs.len(), 2);
+
+    let lint = resolved.origin(ArtifactKind::Skills, None, "lint").unwrap();
+    assert_eq!(lint.source.name, "team");
+    assert_eq!(lint.earlier.len(), 1);
+    assert_eq!(lint.earlier[0].name, "base");
+
+    // Same name, different tool type: tracked separately
+    let skill_fmt = tool("fmt", ToolType::Skill);
+    let agent_fmt = tool("fmt", ToolType::Agent);
+    assert_eq!(resolved.tool_owner(&skill_fmt), team.id);
+    assert_eq!(resolved.tool_owner(&agent_fmt), base.id);
+}
+
+#[test]
+fn test_layer_merge_strategies() {
+    let mut base = profile("base", &[]);
+    base.repo_overlays.skills = vec![skill("lint", "base lint"), skill("docs", "base docs")];
+    base.repo_overlays.permissions = Some(PermissionsOverlay {
+        allow: vec!["Bash(ls:*)".to_string()],
+        ..PermissionsOverlay::default()
+    });
+
+    let mut skip = profile("skip", &[&base]);
+    skip.adapters
+        .merge_strategies
+        .insert("skills".to_string(), MergeStrategy::Skip);
+    skip.repo_overlays.skills = vec![skill("lint", "skip lint"), skill("new", "skip new")];
+    skip.repo_overlays.permissions = Some(PermissionsOverlay {
+        allow: vec!["Bash(cargo test:*)".to_string()],
+        ..PermissionsOverlay::default()
+    });
+
+    let mut replace = profile("replace", &[&base]);
+    replace
+        .adapters
+        .merge_strategies
+        .insert("skills".to_string(), MergeStrategy::Replace);
+    replace.repo_overlays.skills = vec![skill("only", "replace only")];
+
+    let all = lookup(&[&base]);
+
+    let resolved = resolve_with(&skip, |id| Ok(all.get(&id).cloned())).unwrap();
+    let overlays = &resolved.profile.repo_overlays;
+    assert_eq!(overlays.skills.len(), 3);
+    assert_eq!(overlays.skills[0].content, "base lint");
+    assert_eq!(overlays.skills[2].name, "new");
+    assert_eq!(
+        overlays.permissions.as_ref().unwrap().allow,
+        vec!["Bash(ls:*)", "Bash(cargo test:*)"]
+    );
+
+    let resolved = resolve_with(&replace, |id| Ok(all.get(&id).cloned())).unwrap();
+    let names: Vec<_> = resolved
+        .profile
+        .repo_overlays
+        .skills
+        .iter()
+        .map(|s| s.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["only"]);
+    assert!(resolved
+        .origin(ArtifactKind::Skills, None, "lint")
+        .is_none());
+    // Kinds the layer does not define are still inherited
+    assert!(resolved.profile.repo_overlays.permissions.is_some());
+}
+
+#[test]
+fn test_claude_md_layers() {
+    let mut base = profile("base", &[]);
+    base.repo_overlays.claude_md = Some(ClaudeMdOverlay {
+        mode: OverlayMode::Replace,
+        content: "# Base".to_string(),
+    });
+    let mut child = profile("child", &[&base]);
+    child.repo_overlays.claude_md = Some(ClaudeMdOverlay {
+        mode: OverlayMode::Append,
+        content: "# Child".to_string(),
+    });
+
+    let all = lookup(&[&base]);
+    let resolved = resolve_with(&child, |id| Ok(all.get(&id).cloned())).unwrap();
+    let claude_md = resolved.profile.repo_overlays.claude_md.unwrap();
+    assert!(claude_md.content.contains("# Base"));
+    assert!(claude_md.content.contains("# Child"));
+}
+
+#[test]
+fn test_diamond_is_linearized_once() {
+    let root = profile("root", &[]);
+    let left = profile("left", &[&root]);
+    let right = profile("right", &[&root]);
+    let leaf = profile("leaf", &[&left, &right]);
+
+    let all = lookup(&[&root, &left, &right]);
+    assert_eq!(
+        resolve(&leaf, &all).unwrap(),
+        vec!["root", "left", "right", "leaf"]
+    );
+}
+
+#[test]
+fn test_cycle_is_rejected() {
+    let mut a = profile("a", &[]);
+    let b = profile("b", &[&a]);
+    a.extends = vec![b.id];
+
+    let all = lookup(&[&a, &b]);
+    match resolve(&a, &all) {
+        Err(ResolveError::Cycle(path)) => assert_eq!(path, "a -> b -> a"),
+        other => panic!("expected cycle, got {other:?}"),
+    }
+
+    let mut selfish = profile("self", &[]);
+    selfish.extends = vec![selfish.id];
+    assert!(matches!(
+        resolve(&selfish, &lookup(&[&selfish])),
+        Err(ResolveError::Cycle(_))
+    ));
+}
+
+#[test]
+fn test_missing_parent_is_rejected() {
+    let mut orphan = profile("orphan", &[]);
+    let missing = Uuid::new_v4();
+    orphan.extends = vec![missing];
+
+    match resolve(&orphan, &HashMap::new()) {
+        Err(ResolveError::MissingParent(id)) => assert_eq!(id, missing),
+        other => panic!("expected missing parent, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_resolve_from_store() {
+    let db = Database::in_memory().unwrap();
+    let store = ProfileStore::new(db.connection());
+
+    let mut base = profile("base", &[]);
+    base.repo_overlays.skills = vec![skill("lint", "base")];
+    let child = profile("child", &[&base]);
+    store.create(&base).unwrap();
+    store.create(&child).unwrap();
+
+    let resolved = resolve_profile(&store, &child).unwrap();
+    assert_eq!(resolved.profile.repo_overlays.skills.len(), 1);
+
+    let children = store.list_extending(base.id).unwrap();
+    assert_eq!(children.len(), 1);
+    assert_eq!(children[0].name, "child");
+    assert!(store.list_extending(child.id).unwrap().is_empty());
+}
@@ -0,0 +1,222 @@
+//! Profile assignment rule tests
+//!
+//! Matching projects on their path, languages, dependency files, git remote
+//! and metadata, and applying the proposed profile with a backup.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tars_core::profile::{
+    apply_proposal, matching_rules, propose_assignment, validate_rule, CommandOverlay, Profile,
+    ProjectFacts, RuleError,
+};
+use tars_core::storage::db::Database;
+use tars_core::storage::metadata::ProjectMetadata;
+use tars_core::storage::{
+    AssignmentRule, AssignmentRuleStore, BackupStore, MetadataStore, ProfileStore, ProjectStore,
+    RuleConditions,
+};
+use tars_core::Project;
+use tempfile::TempDir;
+
+fn rust_project(root: &Path) {
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname = \"api\"\n\n[dependencies]\nserde = \"1\"\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("src/main.rs"),
+        "fn main() {\n    println!(\"hi\");\n}\n",
+    )
+    .unwrap();
+}
+
+fn set_origin(root: &Path, url: &str) {
+    for args in [&["init", "-q"][..], &["remote", "add", "origin", url][..]] {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(root)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+}
+
+fn rust_profile(db: &Database) -> Profile {
+    let mut profile = Profile::new("rust".to_string());
+    profile.repo_overlays.commands.push(CommandOverlay {
+        name: "clippy".to_string(),
+        content: "Run cargo clippy".to_string(),
+    });
+    ProfileStore::new(db.connection()).create(&profile).unwrap();
+    profile
+}
+
+fn rule(name: &str, profile: &Profile, conditions: RuleConditions) -> AssignmentRule {
+    AssignmentRule::new(name.to_string(), profile.id, conditions)
+}
+
+#[test]
+fn test_facts_and_conditions() {
+    let dir = TempDir::new().unwrap();
+    rust_project(dir.path());
+    set_origin(dir.path(), "git@github.com:acme/api.git");
+    let metadata = ProjectMetadata {
+        platforms: vec!["web".to_string()],
+        ..Default::default()
+    };
+
+    let facts = ProjectFacts::detect(dir.path(), Some(&metadata));
+    assert!(facts.languages.contains(&"Rust".to_string()));
+    assert_eq!(facts.dependency_files, vec!["Cargo.toml"]);
+    assert_eq!(facts.git_remote.as_ref().unwrap().host, "github.com");
+    assert_eq!(
+        facts.git_remote.as_ref().unwrap().org.as_deref(),
+        Some("acme")
+    );
+
+    let db = Database::in_memory().unwrap();
+    let profile = rust_profile(&db);
+    let glob = format!("{}/**", dir.path().parent().unwrap().display());
+    let matching = rule(
+        "acme rust",
+        &profile,
+        RuleConditions {
+            path_globs: vec![glob],
+            languages: vec!["rust".to_string()],
+            dependency_files: vec!["Cargo.toml".to_string()],
+            git_hosts: vec!["GitHub.com".to_string()],
+            git_orgs: vec!["acme".to_string()],
+            platforms: vec!["web".to_string()],
+        },
+    );
+    let other_org = rule(
+        "other org",
+        &profile,
+        RuleConditions {
+            git_orgs: vec!["initech".to_string()],
+            ..Default::default()
+        },
+    );
+    let mut disabled = rule(
+        "disabled",
+        &profile,
+        RuleConditions {
+            languages: vec!["Rust".to_string()],
+            ..Default::default()
+        },
+    );
+    disabled.enabled = false;
+    disabled.priority = 10;
+
+    let matches = matching_rules(&[other_org, disabled, matching], &facts);
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].rule.name, "acme rust");
+    assert_eq!(matches[0].reasons.len(), 6);
+    assert!(matches[0].reasons.contains(&"git org acme".to_string()));
+}
+
+#[test]
+fn test_rules_need_valid_conditions() {
+    let db = Database::in_memory().unwrap();
+    let profile = rust_profile(&db);
+
+    let empty = rule("empty", &profile, RuleConditions::default());
+    assert!(matches!(
+        validate_rule(&empty),
+        Err(RuleError::NoConditions)
+    ));
+
+    let bad_glob = rule(
+        "bad glob",
+        &profile,
+        RuleConditions {
+            path_globs: vec!["/work/[".to_string()],
+            ..Default::default()
+        },
+    );
+    assert!(matches!(
+        validate_rule(&bad_glob),
+        Err(RuleError::InvalidGlob { .. })
+    ));
+}
+
+#[test]
+fn test_highest_priority_rule_is_proposed_and_applied() {
+    let db = Database::in_memory().unwrap();
+    let dir = TempDir::new().unwrap();
+    let backups_dir = TempDir::new().unwrap();
+    rust_project(dir.path());
+
+    let profile = rust_profile(&db);
+    let fallback = Profile::new("fallback".to_string());
+    ProfileStore::new(db.connection())
+        .create(&fallback)
+        .unwrap();
+
+    let rules = AssignmentRuleStore::new(db.connection());
+    rules
+        .create(&rule(
+            "any cargo",
+            &fallback,
+            RuleConditions {
+                dependency_files: vec!["Cargo.toml".to_string()],
+                ..Default::default()
+            },
+        ))
+        .unwrap();
+    let mut preferred = rule(
+        "rust",
+        &profile,
+        RuleConditions {
+            languages: vec!["Rust".to_string()],
+            ..Default::default()
+        },
+    );
+    preferred.priority = 5;
+    preferred.auto_apply = true;
+    rules.create(&preferred).unwrap();
+    assert_eq!(rules.list().unwrap()[0].name, "rust");
+
+    let projects = ProjectStore::new(db.connection());
+    let mut project = Project::new(dir.path().to_path_buf());
+    projects.create(&project).unwrap();
+    MetadataStore::new(db.connection())
+        .save(project.id, &ProjectMetadata::default())
+        .unwrap();
+
+    let proposal = propose_assignment(db.connection(), &project)
+        .unwrap()
+        .unwrap();
+    assert_eq!(proposal.profile_id, profile.id);
+    assert!(proposal.rule.auto_apply);
+    assert_eq!(proposal.plan.operations.len(), 1);
+
+    let applied = apply_proposal(
+        db.connection(),
+        &mut project,
+        &proposal,
+        backups_dir.path(),
+        None,
+    )
+    .unwrap();
+    let backup = applied.backup.unwrap();
+    assert!(applied.assignment.plugin_errors.is_empty());
+    assert_eq!(
+        fs::read_to_string(dir.path().join(".claude/commands/clippy.md")).unwrap(),
+        "Run cargo clippy"
+    );
+    assert!(BackupStore::new(db.connection())
+        .get(backup.id)
+        .unwrap()
+        .is_some());
+    let stored = projects.get(project.id).unwrap().unwrap();
+    assert_eq!(stored.assigned_profile_id, Some(profile.id));
+
+    // Assigned projects get no further proposals
+    assert!(propose_assignment(db.connection(), &stored)
+        .unwrap()
+        .is_none());
+}
@@ -0,0 +1,110 @@
+//! Source tracking tests for MCP servers and plugins
+//!
+//! MCP servers copied from a shared config file track only their own entry;
+//! plugin entries follow the installed version from the version store.
+
+use serde_json::json;
+use std::fs;
+use tars_core::profile::{
+    check_plugin_updates, check_profile_updates, create_mcp_source_ref, record_plugin_version,
+    PluginRef, Profile, SourceMode, ToolRef, ToolType,
+};
+use tars_core::storage::db::Database;
+use tars_core::storage::PluginVersionStore;
+use tars_scanner::types::Scope;
+use tempfile::TempDir;
+
+fn write_config(path: &std::path::Path, servers: &serde_json::Value) {
+    let config = json!({ "numStartups": 3, "mcpServers": servers });
+    fs::write(path, serde_json::to_string_pretty(&config).unwrap()).unwrap();
+}
+
+#[test]
+fn test_mcp_server_tracks_its_own_entry() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join(".mcp.json");
+    let github = json!({ "command": "npx", "args": ["github-mcp"] });
+    write_config(
+        &config_path,
+        &json!({ "github": github, "db": { "command": "db-mcp" } }),
+    );
+
+    let source_ref =
+        create_mcp_source_ref(config_path.clone(), "github", SourceMode::Track).unwrap();
+    assert_eq!(source_ref.entry.as_deref(), Some("github"));
+    assert!(create_mcp_source_ref(config_path.clone(), "missing", SourceMode::Track).is_err());
+
+    let mut profile = Profile::new("mcp".to_string());
+    profile.tool_refs.push(ToolRef {
+        name: "github".to_string(),
+        tool_type: ToolType::Mcp,
+        source_scope: Some(Scope::Project),
+        permissions: None,
+        source_ref: Some(source_ref),
+    });
+
+    // Other servers and unrelated keys changing is not an update
+    write_config(
+        &config_path,
+        &json!({ "db": { "command": "db-mcp", "args": ["--ro"] }, "github": github }),
+    );
+    let check = check_profile_updates(&profile).unwrap();
+    assert_eq!(check.total_checked, 1);
+    assert!(!check.has_updates());
+
+    write_config(
+        &config_path,
+        &json!({ "github": { "command": "npx", "args": ["github-mcp@2"] } }),
+    );
+    let check = check_profile_updates(&profile).unwrap();
+    assert_eq!(check.update_count(), 1);
+    assert_eq!(check.updates[0].name, "github");
+
+    write_config(&config_path, &json!({ "db": { "command": "db-mcp" } }));
+    let check = check_profile_updates(&profile).unwrap();
+    assert_eq!(check.missing_sources, vec!["github"]);
+}
+
+#[test]
+fn test_plugin_follows_installed_version() {
+    let db = Database::in_memory().unwrap();
+    let versions = PluginVersionStore::new(db.connection());
+
+    let mut profile = Profile::new("plugins".to_string());
+    for id in ["lint", "format", "unseen"] {
+        profile.plugin_set.plugins.push(PluginRef {
+            id: id.to_string(),
+            marketplace: Some("tools".to_string()),
+            scope: Scope::User,
+            enabled: true,
+            version_ref: None,
+        });
+    }
+    assert_eq!(profile.plugin_set.plugins[0].version_key(), "lint@tools");
+
+    versions.track_version("lint@tools", "1.0.0").unwrap();
+    versions.track_version("format@tools", "2.0.0").unwrap();
+    let plugins = &mut profile.plugin_set.plugins;
+    assert!(record_plugin_version(db.connection(), &mut plugins[0], SourceMode::Track).unwrap());
+    assert!(record_plugin_version(db.connection(), &mut plugins[1], SourceMode::Pin).unwrap());
+    assert!(!record_plugin_version(db.connection(), &mut plugins[2], SourceMode::Track).unwrap());
+    assert!(plugins[2].version_ref.is_none());
+    assert!(check_plugin_updates(db.connection(), &profile)
+        .unwrap()
+        .is_empty());
+
+    // Only the tracked plugin reports its new version
+    versions.track_version("lint@tools", "1.1.0").unwrap();
+    versions.track_version("format@tools", "2.1.0").unwrap();
+    let updates = check_plugin_updates(db.connection(), &profile).unwrap();
+    assert_eq!(updates.len(), 1);
+    assert_eq!(updates[0].plugin_id, "lint");
+    assert_eq!(updates[0].recorded_version, "1.0.0");
+    assert_eq!(updates[0].installed_version, "1.1.0");
+
+    let lint = &mut profile.plugin_set.plugins[0];
+    record_plugin_version(db.connection(), lint, SourceMode::Track).unwrap();
+    assert!(check_plugin_updates(db.connection(), &profile)
+        .unwrap()
+        .is_empty());
+}
@@ -2,12 +2,28 @@
 //!
 //! Tests for profile-project assignment and synchronization.
 
+use std::fs;
 use std::path::PathBuf;
-use tars_core::profile::{Profile, ToolPermissions, ToolRef, ToolType};
+use tars_core::profile::storage::{copy_skill_to_profile, profile_dir, profile_plugin_dir};
+use tars_core::profile::sync::apply_profile_to_project;
+use tars_core::profile::{
+    regenerate_profile_plugin, resolve_profile, Profile, ToolPermissions, ToolRef, ToolType,
+};
 use tars_core::project::{LocalOverrides, Project};
 use tars_core::storage::db::Database;
 use tars_core::storage::profiles::ProfileStore;
 use tars_core::storage::projects::ProjectStore;
+use tempfile::TempDir;
+
+struct ProfileStorageGuard(uuid::Uuid);
+
+impl Drop for ProfileStorageGuard {
+    fn drop(&mut self) {
+        if let Ok(path) = profile_dir(self.0) {
+            let _ = fs::remove_dir_all(path);
+        }
+    }
+}
 
 fn create_test_profile(name: &str) -> Profile {
     let mut profile = Profile::new(name.to_string());
@@ -467,3 +483,53 @@ fn test_project_with_all_override_types() {
     assert_eq!(retrieved.local_overrides.total_count(), 4);
     assert!(!retrieved.local_overrides.is_empty());
 }
+
+#[test]
+fn test_child_profile_carries_parent_tools() {
+    let db = Database::in_memory().expect("Failed to create database");
+    let profile_store = ProfileStore::new(db.connection());
+    let source_dir = TempDir::new().expect("Failed to create source dir");
+    let project_dir = TempDir::new().expect("Failed to create project dir");
+
+    let mut base = Profile::new("sync-base".to_string());
+    let _base_guard = ProfileStorageGuard(base.id);
+    let skill_dir = source_dir.path().join("base-skill");
+    fs::create_dir_all(&skill_dir).expect("Failed to create skill dir");
+    fs::write(
+        skill_dir.join("SKILL.md"),
+        "---\nname: base-skill\ndescription: Inherited skill\n---\n\nFrom the base.\n",
+    )
+    .expect("Failed to write skill");
+    copy_skill_to_profile(base.id, "base-skill", &skill_dir).expect("Failed to store skill");
+    base.tool_refs = vec![ToolRef {
+        name: "base-skill".to_string(),
+        tool_type: ToolType::Skill,
+        source_scope: None,
+        permissions: None,
+        source_ref: None,
+    }];
+
+    let mut child = Profile::new("sync-child".to_string());
+    child.extends = vec![base.id];
+    let _child_guard = ProfileStorageGuard(child.id);
+
+    profile_store.create(&base).expect("Failed to create base");
+    profile_store
+        .create(&child)
+        .expect("Failed to create child");
+
+    // Assigning the child applies the parent's skill
+    let resolved = resolve_profile(&profile_store, &child).expect("Failed to resolve child");
+    let applied =
+        apply_profile_to_project(&resolved, project_dir.path()).expect("Failed to apply child");
+    assert_eq!(applied.skills_applied, 1);
+    assert!(project_dir
+        .path()
+        .join(".claude/skills/base-skill/SKILL.md")
+        .exists());
+
+    // Updating the parent regenerates the child's plugin too
+    regenerate_profile_plugin(db.connection(), &base).expect("Failed to regenerate base");
+    let child_plugin = profile_plugin_dir(child.id).expect("Failed to locate child plugin");
+    assert!(child_plugin.join("skills/base-skill/SKILL.md").exists());
+}
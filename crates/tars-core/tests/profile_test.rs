@@ -28,6 +28,7 @@ fn create_full_profile(name: &str) -> Profile {
             marketplace: Some("test-marketplace".to_string()),
             scope: Scope::User,
             enabled: true,
+            version_ref: None,
         }],
     };
 
@@ -37,6 +38,7 @@ fn create_full_profile(name: &str) -> Profile {
         skills: vec![SkillOverlay {
             name: "test-skill".to_string(),
             content: "---\nname: test-skill\ndescription: Test\n---\n\nSkill content".to_string(),
+            files: Vec::new(),
         }],
         commands: vec![CommandOverlay {
             name: "test-cmd".to_string(),
@@ -50,6 +52,8 @@ fn create_full_profile(name: &str) -> Profile {
             mode: OverlayMode::Append,
             content: "# Additional Instructions".to_string(),
         }),
+        hooks: None,
+        permissions: None,
     };
 
     // Add user overlays
@@ -57,6 +61,7 @@ fn create_full_profile(name: &str) -> Profile {
         skills: vec![SkillOverlay {
             name: "user-skill".to_string(),
             content: "User skill content".to_string(),
+            files: Vec::new(),
         }],
         commands: vec![],
     };
@@ -232,6 +237,7 @@ fn test_update_profile_overlays() {
     profile.repo_overlays.skills.push(SkillOverlay {
         name: "new-skill".to_string(),
         content: "New skill content".to_string(),
+        files: Vec::new(),
     });
     profile.updated_at = chrono::Utc::now();
 
@@ -103,6 +103,7 @@ fn create_modifying_profile() -> Profile {
         name: "new-skill".to_string(),
         content: "---\nname: new-skill\ndescription: New skill\n---\n\nNew skill content\n"
             .to_string(),
+        files: Vec::new(),
     });
 
     // Modify existing skill (by adding one with same name)
@@ -111,6 +112,7 @@ fn create_modifying_profile() -> Profile {
         content:
             "---\nname: existing-skill\ndescription: Modified skill\n---\n\nModified skill content\n"
                 .to_string(),
+        files: Vec::new(),
     });
 
     // Add new command
@@ -186,6 +188,7 @@ fn test_rollback_removes_newly_created_files() {
     profile.repo_overlays.skills.push(SkillOverlay {
         name: "brand-new-skill".to_string(),
         content: "New skill content".to_string(),
+        files: Vec::new(),
     });
 
     let plan = generate_plan(project_id, project_path, &profile).expect("Failed to generate plan");
@@ -271,6 +274,7 @@ fn test_rollback_preserves_untouched_files() {
     profile.repo_overlays.skills.push(SkillOverlay {
         name: "test-skill".to_string(),
         content: "Skill content".to_string(),
+        files: Vec::new(),
     });
 
     let plan = generate_plan(project_id, project_path, &profile).expect("Failed to generate plan");
@@ -392,6 +396,7 @@ fn test_rollback_with_nested_directories() {
     profile.repo_overlays.skills.push(SkillOverlay {
         name: "another/nested/skill".to_string(),
         content: "New nested skill".to_string(),
+        files: Vec::new(),
     });
 
     // This should fail due to path traversal protection (slashes in name)
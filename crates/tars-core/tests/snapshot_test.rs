@@ -0,0 +1,190 @@
+//! Profile snapshot tests
+//!
+//! Tests that snapshotting a project and applying the snapshot elsewhere
+//! reproduces skills, MCP servers, hooks and permissions.
+
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+use tars_core::apply::apply_operations;
+use tars_core::backup::Backup;
+use tars_core::diff::plan::generate_plan;
+use tars_core::profile::snapshot::snapshot_from_project;
+use tars_core::profile::{FileEncoding, McpLocation};
+use tempfile::TempDir;
+use uuid::Uuid;
+
+const PNG_HEADER: &[u8] = &[0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a, 0xff];
+
+fn write(path: &Path, content: &[u8]) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+fn read_json(path: &Path) -> Value {
+    serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+fn create_source_project(root: &Path) {
+    let skill = root.join(".claude/skills/pdf");
+    write(
+        &skill.join("SKILL.md"),
+        b"---\nname: pdf\n---\n\nUse the scripts.",
+    );
+    write(&skill.join("scripts/extract.py"), b"print('extract')\n");
+    write(&skill.join("references/forms.md"), b"# Forms\n");
+    write(&skill.join("assets/logo.png"), PNG_HEADER);
+
+    write(
+        &root.join(".mcp.json"),
+        json!({
+            "mcpServers": {
+                "files": {"command": "npx", "args": ["-y", "fs-server"], "env": {"ROOT": "."}},
+                "docs": {"type": "http", "url": "https://docs.example/mcp",
+                         "headers": {"Authorization": "Bearer ${DOCS_TOKEN}"}}
+            }
+        })
+        .to_string()
+        .as_bytes(),
+    );
+
+    write(
+        &root.join(".claude/settings.json"),
+        json!({
+            "hooks": {
+                "PostToolUse": [{
+                    "matcher": "Edit|Write",
+                    "hooks": [{"type": "command", "command": "cargo fmt", "timeout": 30}]
+                }]
+            },
+            "permissions": {
+                "allow": ["Bash(cargo test:*)"],
+                "deny": ["Read(./.env)"],
+                "ask": ["Bash(git push:*)"],
+                "defaultMode": "acceptEdits"
+            }
+        })
+        .to_string()
+        .as_bytes(),
+    );
+}
+
+#[test]
+fn test_snapshot_captures_whole_skill_tree() {
+    let source = TempDir::new().unwrap();
+    create_source_project(source.path());
+
+    let profile = snapshot_from_project(source.path(), "snap".to_string()).unwrap();
+
+    let skill = &profile.repo_overlays.skills[0];
+    let paths: Vec<_> = skill.files.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            "assets/logo.png",
+            "references/forms.md",
+            "scripts/extract.py"
+        ]
+    );
+    assert_eq!(skill.files[0].encoding, FileEncoding::Base64);
+    assert_eq!(skill.files[0].bytes().unwrap(), PNG_HEADER);
+    assert_eq!(skill.files[2].encoding, FileEncoding::Utf8);
+    assert_eq!(skill.files[2].content, "print('extract')\n");
+}
+
+#[test]
+fn test_snapshot_captures_mcp_hooks_and_permissions() {
+    let source = TempDir::new().unwrap();
+    create_source_project(source.path());
+
+    let profile = snapshot_from_project(source.path(), "snap".to_string()).unwrap();
+    let overlays = &profile.repo_overlays;
+
+    let mut servers = overlays.mcp_servers.clone();
+    servers.sort_by(|a, b| a.name.cmp(&b.name));
+    assert_eq!(servers.len(), 2);
+    assert_eq!(servers[0].name, "docs");
+    assert_eq!(servers[0].transport, "http");
+    assert_eq!(
+        servers[0].headers.get("Authorization").map(String::as_str),
+        Some("Bearer ${DOCS_TOKEN}")
+    );
+    assert_eq!(servers[1].transport, "stdio");
+    assert_eq!(servers[1].args, vec!["-y", "fs-server"]);
+    assert_eq!(profile.adapters.mcp_location, McpLocation::ProjectRoot);
+
+    let hooks = overlays.hooks.as_ref().expect("hooks captured");
+    assert_eq!(hooks.events["PostToolUse"][0]["hooks"][0]["timeout"], 30);
+
+    let permissions = overlays.permissions.as_ref().expect("permissions captured");
+    assert_eq!(permissions.allow, vec!["Bash(cargo test:*)"]);
+    assert_eq!(permissions.deny, vec!["Read(./.env)"]);
+    assert_eq!(permissions.ask, vec!["Bash(git push:*)"]);
+    assert_eq!(permissions.default_mode.as_deref(), Some("acceptEdits"));
+}
+
+#[test]
+fn test_snapshot_applies_losslessly_to_empty_project() {
+    let source = TempDir::new().unwrap();
+    let target = TempDir::new().unwrap();
+    create_source_project(source.path());
+
+    let profile = snapshot_from_project(source.path(), "snap".to_string()).unwrap();
+    let plan = generate_plan(Uuid::new_v4(), target.path(), &profile).unwrap();
+    let mut backup = Backup::new(plan.project_id, target.path().join("backup.json"));
+    apply_operations(&plan, target.path(), &mut backup).unwrap();
+
+    for file in [
+        ".claude/skills/pdf/SKILL.md",
+        ".claude/skills/pdf/scripts/extract.py",
+        ".claude/skills/pdf/references/forms.md",
+        ".claude/skills/pdf/assets/logo.png",
+    ] {
+        assert_eq!(
+            fs::read(target.path().join(file)).unwrap(),
+            fs::read(source.path().join(file)).unwrap(),
+            "{file} differs"
+        );
+    }
+    assert_eq!(
+        read_json(&target.path().join(".mcp.json")),
+        read_json(&source.path().join(".mcp.json"))
+    );
+    assert_eq!(
+        read_json(&target.path().join(".claude/settings.json")),
+        read_json(&source.path().join(".claude/settings.json"))
+    );
+
+    // Re-planning against the result is a no-op
+    let replan = generate_plan(Uuid::new_v4(), target.path(), &profile).unwrap();
+    assert!(replan.is_empty());
+}
+
+#[test]
+fn test_plan_merges_settings_with_existing_rules() {
+    let source = TempDir::new().unwrap();
+    let target = TempDir::new().unwrap();
+    create_source_project(source.path());
+    write(
+        &target.path().join(".claude/settings.json"),
+        json!({
+            "model": "opus",
+            "permissions": {"allow": ["Bash(ls:*)", "Bash(cargo test:*)"]}
+        })
+        .to_string()
+        .as_bytes(),
+    );
+
+    let profile = snapshot_from_project(source.path(), "snap".to_string()).unwrap();
+    let plan = generate_plan(Uuid::new_v4(), target.path(), &profile).unwrap();
+    let mut backup = Backup::new(plan.project_id, target.path().join("backup.json"));
+    apply_operations(&plan, target.path(), &mut backup).unwrap();
+
+    let settings = read_json(&target.path().join(".claude/settings.json"));
+    assert_eq!(settings["model"], "opus");
+    assert_eq!(
+        settings["permissions"]["allow"],
+        json!(["Bash(ls:*)", "Bash(cargo test:*)"])
+    );
+    assert_eq!(settings["hooks"]["PostToolUse"][0]["matcher"], "Edit|Write");
+}
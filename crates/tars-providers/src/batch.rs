@@ -0,0 +1,162 @@
+//! Concurrent key validation across providers.
+//!
+//! [`validate_keys`] runs many `validate_key` calls at once while capping how
+//! many hit the same provider simultaneously, so a bulk re-validation does
+//! not trip vendor rate limits. Each outcome carries the measured latency.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::{error::ProviderError, provider::Provider, types::ValidationResult};
+
+/// One key to validate.
+pub struct ValidationJob {
+    /// Caller-side identifier echoed back in the outcome (e.g. the DB row id)
+    pub id: i64,
+    /// Concurrency group; jobs sharing it share the per-provider limit.
+    /// Usually the stored provider id, so each custom provider gets its own.
+    pub provider_id: String,
+    pub key: String,
+    pub provider: Box<dyn Provider>,
+}
+
+/// Result of one [`ValidationJob`].
+#[derive(Debug)]
+pub struct ValidationOutcome {
+    pub id: i64,
+    pub provider_id: String,
+    pub result: Result<ValidationResult, ProviderError>,
+    /// Wall time of the `validate_key` call, excluding time spent waiting
+    /// for a concurrency slot
+    pub latency: Duration,
+}
+
+/// Validate every job concurrently, at most `per_provider_limit` (minimum 1)
+/// at a time per `provider_id`. Outcomes come back in job order.
+pub async fn validate_keys(
+    jobs: Vec<ValidationJob>,
+    per_provider_limit: usize,
+) -> Vec<ValidationOutcome> {
+    let limit = per_provider_limit.max(1);
+    let mut semaphores: HashMap<String, Arc<Semaphore>> = HashMap::new();
+    let mut set = JoinSet::new();
+
+    for (index, job) in jobs.into_iter().enumerate() {
+        let semaphore = Arc::clone(
+            semaphores
+                .entry(job.provider_id.clone())
+                .or_insert_with(|| Arc::new(Semaphore::new(limit))),
+        );
+        set.spawn(async move {
+            // The semaphore is never closed, so acquiring cannot fail.
+            let _permit = semaphore.acquire_owned().await.ok();
+            let started = Instant::now();
+            let result = job.provider.validate_key(&job.key).await;
+            (
+                index,
+                ValidationOutcome {
+                    id: job.id,
+                    provider_id: job.provider_id,
+                    result,
+                    latency: started.elapsed(),
+                },
+            )
+        });
+    }
+
+    let mut outcomes = Vec::with_capacity(set.len());
+    while let Some(joined) = set.join_next().await {
+        // A panicking provider loses only its own outcome.
+        if let Ok(outcome) = joined {
+            outcomes.push(outcome);
+        }
+    }
+    outcomes.sort_by_key(|(index, _)| *index);
+    outcomes.into_iter().map(|(_, outcome)| outcome).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::OpenAiProvider;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn job(id: i64, provider_id: &str, key: &str, server: &MockServer) -> ValidationJob {
+        ValidationJob {
+            id,
+            provider_id: provider_id.to_string(),
+            key: key.to_string(),
+            provider: Box::new(OpenAiProvider::with_base_url(server.uri())),
+        }
+    }
+
+    #[tokio::test]
+    async fn returns_outcomes_in_job_order_with_latency() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .and(header("authorization", "Bearer sk-bad"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"data": []})))
+            .mount(&server)
+            .await;
+
+        let outcomes = validate_keys(
+            vec![
+                job(1, "openai", "sk-good", &server),
+                job(2, "openai", "sk-bad", &server),
+                job(3, "other", "sk-good", &server),
+            ],
+            2,
+        )
+        .await;
+
+        let ids: Vec<i64> = outcomes.iter().map(|o| o.id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+        assert!(outcomes[0].result.as_ref().unwrap().valid);
+        assert!(!outcomes[1].result.as_ref().unwrap().valid);
+        assert_eq!(outcomes[2].provider_id, "other");
+        assert!(outcomes.iter().all(|o| o.latency > Duration::ZERO));
+    }
+
+    #[tokio::test]
+    async fn per_provider_limit_serialises_calls() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"data": []}))
+                    .set_delay(Duration::from_millis(60)),
+            )
+            .mount(&server)
+            .await;
+
+        let jobs = (0..3).map(|i| job(i, "openai", "sk", &server)).collect();
+        let started = Instant::now();
+        let outcomes = validate_keys(jobs, 1).await;
+        let serial = started.elapsed();
+        assert_eq!(outcomes.len(), 3);
+        assert!(
+            serial >= Duration::from_millis(180),
+            "limit of 1 must run the three calls back to back"
+        );
+
+        // Separate providers do not wait on each other.
+        let jobs = (0..3)
+            .map(|i| job(i, &format!("p{i}"), "sk", &server))
+            .collect();
+        let started = Instant::now();
+        validate_keys(jobs, 1).await;
+        assert!(started.elapsed() < serial);
+    }
+}
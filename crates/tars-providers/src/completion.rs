@@ -0,0 +1,131 @@
+//! Shared plumbing for [`crate::Provider::complete`] implementations.
+//!
+//! `OpenAI` and `DeepSeek` speak the same chat-completions format, so the
+//! request/response handling lives here; Anthropic and Gemini parse their
+//! own responses but share the error mapping and latency measurement.
+
+use std::time::Instant;
+
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::Deserialize;
+
+use crate::{
+    error::ProviderError,
+    http::HttpClient,
+    types::{Completion, TokenUsage},
+};
+
+/// Milliseconds since `started`, saturating.
+pub(crate) fn elapsed_ms(started: Instant) -> u64 {
+    u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX)
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorEnvelope {
+    error: ErrorBody,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorBody {
+    message: String,
+}
+
+/// Map a non-success completion response to an error.
+///
+/// 401/403 become `Unauthorized`. Anything else keeps the provider's own
+/// message — every supported vendor uses `{"error": {"message": ...}}` — so
+/// "model not found" or "no access to this model" reach the user verbatim.
+pub(crate) async fn error_for(provider: &str, resp: Response) -> ProviderError {
+    let status = resp.status();
+    if matches!(status, StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN) {
+        return ProviderError::Unauthorized {
+            status: status.as_u16(),
+        };
+    }
+    let detail = resp
+        .text()
+        .await
+        .ok()
+        .and_then(|body| serde_json::from_str::<ErrorEnvelope>(&body).ok())
+        .map(|e| e.error.message);
+    match detail {
+        Some(message) => ProviderError::Http(format!("{provider} returned {status}: {message}")),
+        None => ProviderError::Http(format!("{provider} returned {status}")),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    model: String,
+    choices: Vec<ChatChoice>,
+    usage: ChatUsage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatMessage {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatUsage {
+    prompt_tokens: u64,
+    completion_tokens: u64,
+    /// `OpenAI`: cached prompt tokens, included in `prompt_tokens`
+    #[serde(default)]
+    prompt_tokens_details: Option<PromptTokensDetails>,
+    /// `DeepSeek`: cache hits, included in `prompt_tokens`
+    #[serde(default)]
+    prompt_cache_hit_tokens: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PromptTokensDetails {
+    #[serde(default)]
+    cached_tokens: u64,
+}
+
+/// Send an OpenAI-format chat-completions request (auth and JSON body
+/// already attached) and parse the reply.
+pub(crate) async fn chat_completion(
+    client: &HttpClient,
+    request: RequestBuilder,
+    provider: &str,
+) -> Result<Completion, ProviderError> {
+    let started = Instant::now();
+    let resp = client.send_billable(request).await?;
+    let latency_ms = elapsed_ms(started);
+    if !resp.status().is_success() {
+        return Err(error_for(provider, resp).await);
+    }
+
+    let parsed: ChatResponse = resp.json().await.map_err(ProviderError::from)?;
+    let cached = parsed
+        .usage
+        .prompt_tokens_details
+        .map(|d| d.cached_tokens)
+        .or(parsed.usage.prompt_cache_hit_tokens)
+        .unwrap_or(0);
+    let text = parsed
+        .choices
+        .into_iter()
+        .next()
+        .and_then(|c| c.message.content)
+        .unwrap_or_default();
+    Ok(Completion {
+        model: parsed.model,
+        text,
+        usage: TokenUsage {
+            input_tokens: parsed.usage.prompt_tokens.saturating_sub(cached),
+            output_tokens: parsed.usage.completion_tokens,
+            cache_read_tokens: cached,
+            cache_write_tokens: 0,
+        },
+        latency_ms,
+    })
+}
@@ -1,5 +1,7 @@
 //! Provider error types
 
+use std::time::Duration;
+
 use thiserror::Error;
 
 /// Errors returned by provider operations
@@ -11,8 +13,10 @@ pub enum ProviderError {
     #[error("Unauthorized (HTTP {status})")]
     Unauthorized { status: u16 },
 
-    #[error("Rate limited")]
-    RateLimited,
+    /// HTTP 429 after retries ran out. `retry_after` is the server's
+    /// `Retry-After` hint, when it sent one.
+    #[error("Rate limited{}", retry_after.map(|d| format!(" (retry after {}s)", d.as_secs())).unwrap_or_default())]
+    RateLimited { retry_after: Option<Duration> },
 
     #[error("Unknown provider: {0}")]
     UnknownProvider(String),
@@ -44,7 +48,7 @@ impl From<reqwest::Error> for ProviderError {
                 };
             }
             if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
-                return Self::RateLimited;
+                return Self::RateLimited { retry_after: None };
             }
         }
         // Strip the URL before stringifying — reqwest's Display includes the
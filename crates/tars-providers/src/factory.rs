@@ -7,18 +7,22 @@ use crate::{
     provider::Provider,
     providers::{
         AnthropicProvider, BraveSearchProvider, DeepseekProvider, ElevenLabsProvider,
-        GeminiProvider, GroqProvider, MistralProvider, OpenAiProvider, OpenRouterProvider,
-        PerplexityProvider, XAiProvider,
+        GeminiProvider, GroqProvider, MistralProvider, OpenAiCompatibleProvider, OpenAiProvider,
+        OpenRouterProvider, PerplexityProvider, XAiProvider,
     },
-    types::ProviderId,
+    types::{CustomProviderConfig, ProviderId},
 };
 
 /// Return a boxed `Provider` implementation for the given id.
 ///
 /// Each call builds a fresh HTTP client; providers are cheap to construct.
+///
+/// Returns `None` for `ProviderId::Custom`, which has no endpoint of its
+/// own — callers holding a `custom:<slug>` id should use [`custom_provider`]
+/// with the stored configuration.
 #[must_use]
-pub fn provider_for(id: ProviderId) -> Box<dyn Provider> {
-    match id {
+pub fn provider_for(id: ProviderId) -> Option<Box<dyn Provider>> {
+    let provider: Box<dyn Provider> = match id {
         ProviderId::OpenAi => Box::new(OpenAiProvider::new()),
         ProviderId::Anthropic => Box::new(AnthropicProvider::new()),
         ProviderId::Gemini => Box::new(GeminiProvider::new()),
@@ -30,7 +34,15 @@ pub fn provider_for(id: ProviderId) -> Box<dyn Provider> {
         ProviderId::XAi => Box::new(XAiProvider::new()),
         ProviderId::OpenRouter => Box::new(OpenRouterProvider::new()),
         ProviderId::Perplexity => Box::new(PerplexityProvider::new()),
-    }
+        ProviderId::Custom => return None,
+    };
+    Some(provider)
+}
+
+/// Return a boxed `Provider` for a user-defined OpenAI-compatible endpoint.
+#[must_use]
+pub fn custom_provider(config: CustomProviderConfig) -> Box<dyn Provider> {
+    Box::new(OpenAiCompatibleProvider::new(config))
 }
 
 #[cfg(test)]
@@ -40,8 +52,13 @@ mod tests {
     #[test]
     fn factory_covers_all_providers() {
         for &id in ProviderId::ALL {
-            let p = provider_for(id);
+            let p = provider_for(id).unwrap();
             assert_eq!(p.id(), id);
         }
     }
+
+    #[test]
+    fn custom_provider_needs_its_stored_config() {
+        assert!(provider_for(ProviderId::Custom).is_none());
+    }
 }
@@ -0,0 +1,410 @@
+//! Shared HTTP request layer for provider implementations.
+//!
+//! Every provider talks to its vendor through an [`HttpClient`], which owns
+//! the `reqwest::Client` (fixed timeouts, user agent, optional HTTPS-only
+//! enforcement) and a [`RetryPolicy`]. [`HttpClient::send`] retries
+//! transient failures — HTTP 429, 502/503/504 and connection errors — with
+//! bounded exponential backoff and jitter, honouring a server-sent
+//! `Retry-After` header when present.
+//!
+//! Requests that cost money (completions) go through
+//! [`HttpClient::send_billable`] instead, which retries only 429: a 5xx or a
+//! dropped connection may arrive after the vendor accepted — and billed — the
+//! request, so retrying could charge twice.
+//!
+//! When retries run out on a 429, the caller gets
+//! [`ProviderError::RateLimited`] carrying the server's suggested delay, so
+//! bulk operations can pace themselves. Exhausted 5xx responses are handed
+//! back unchanged so each provider keeps its own status-to-error mapping.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+
+use crate::error::ProviderError;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const USER_AGENT: &str = "tars/0.4";
+
+/// How [`HttpClient::send`] retries transient failures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total attempts including the first one; `1` disables retries.
+    pub max_attempts: u32,
+    /// Backoff before the second attempt; doubles on each further attempt.
+    pub base_delay: Duration,
+    /// Upper bound for a single wait. A `Retry-After` longer than this is
+    /// not waited out: the error is returned immediately instead.
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Send each request exactly once.
+    #[must_use]
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Backoff before attempt `attempt + 1` (1-based `attempt`), using
+    /// "equal jitter": half the exponential delay is fixed, the other half
+    /// random, so waits stay bounded but never collapse to zero.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exp = self
+            .base_delay
+            .saturating_mul(1_u32 << attempt.saturating_sub(1).min(16));
+        let capped = exp.min(self.max_delay);
+        let half = capped / 2;
+        half + jitter(half)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+/// `reqwest::Client` plus retry policy shared by all providers.
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+    client: Client,
+    retry: RetryPolicy,
+}
+
+impl HttpClient {
+    /// Client with the standard timeouts and the default [`RetryPolicy`].
+    ///
+    /// # Panics
+    /// Panics only if the underlying TLS stack fails to initialize, which is
+    /// treated as a non-recoverable environment error.
+    #[must_use]
+    pub fn new(https_only: bool) -> Self {
+        let client = Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .connect_timeout(CONNECT_TIMEOUT)
+            .user_agent(USER_AGENT)
+            .https_only(https_only)
+            .build()
+            .expect("reqwest client builds");
+        Self {
+            client,
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    /// Replace the retry policy.
+    #[must_use]
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    #[must_use]
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry
+    }
+
+    /// Start a GET request; pass the finished builder to [`Self::send`].
+    pub fn get(&self, url: &str) -> RequestBuilder {
+        self.client.get(url)
+    }
+
+    /// Start a POST request; pass the finished builder to [`Self::send`].
+    pub fn post(&self, url: &str) -> RequestBuilder {
+        self.client.post(url)
+    }
+
+    /// Send `request`, retrying transient failures per the retry policy.
+    ///
+    /// Requests whose body cannot be cloned (streams) are sent once.
+    ///
+    /// # Errors
+    /// - [`ProviderError::RateLimited`] when the final attempt got HTTP 429,
+    ///   or the server asked for a wait longer than the policy allows.
+    /// - The mapped `reqwest` error when the request could not be sent.
+    pub async fn send(&self, request: RequestBuilder) -> Result<Response, ProviderError> {
+        self.send_with(request, true).await
+    }
+
+    /// Send a billable request, retrying only HTTP 429.
+    ///
+    /// A rate-limited request was rejected before any work was done, so it
+    /// is safe to repeat. Server errors and connection failures are handed
+    /// back after a single attempt, since the vendor may already have
+    /// charged for the request.
+    ///
+    /// # Errors
+    /// As for [`Self::send`].
+    pub async fn send_billable(&self, request: RequestBuilder) -> Result<Response, ProviderError> {
+        self.send_with(request, false).await
+    }
+
+    async fn send_with(
+        &self,
+        request: RequestBuilder,
+        retry_unsafe: bool,
+    ) -> Result<Response, ProviderError> {
+        let mut attempt = 1;
+        loop {
+            let Some(this_try) = request.try_clone() else {
+                return request.send().await.map_err(ProviderError::from);
+            };
+            let can_retry = attempt < self.retry.max_attempts;
+            let delay = match this_try.send().await {
+                Ok(resp) => {
+                    let status = resp.status();
+                    let retryable = if retry_unsafe {
+                        is_retryable_status(status)
+                    } else {
+                        status == StatusCode::TOO_MANY_REQUESTS
+                    };
+                    if !retryable {
+                        return Ok(resp);
+                    }
+                    let retry_after = retry_after(resp.headers(), Utc::now());
+                    let too_long = retry_after.is_some_and(|d| d > self.retry.max_delay);
+                    if !can_retry || too_long {
+                        if status == StatusCode::TOO_MANY_REQUESTS {
+                            return Err(ProviderError::RateLimited { retry_after });
+                        }
+                        return Ok(resp);
+                    }
+                    retry_after.unwrap_or_else(|| self.retry.backoff(attempt))
+                }
+                Err(e) if retry_unsafe && can_retry && e.is_connect() => {
+                    self.retry.backoff(attempt)
+                }
+                Err(e) => return Err(ProviderError::from(e)),
+            };
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Parse a `Retry-After` header: either delay-seconds or an HTTP-date.
+/// Dates in the past yield a zero delay.
+fn retry_after(headers: &HeaderMap, now: DateTime<Utc>) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = DateTime::parse_from_rfc2822(value)
+        .ok()?
+        .with_timezone(&Utc);
+    Some((at - now).to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Pseudo-random duration in `[0, max]`. `RandomState` is seeded per
+/// instance, which is plenty for spreading out retries without pulling in
+/// an RNG crate.
+fn jitter(max: Duration) -> Duration {
+    let nanos = u64::try_from(max.as_nanos()).unwrap_or(u64::MAX);
+    if nanos == 0 {
+        return Duration::ZERO;
+    }
+    let random = RandomState::new().build_hasher().finish();
+    Duration::from_nanos(random % nanos.saturating_add(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn fast_client(max_attempts: u32) -> HttpClient {
+        HttpClient::new(false).with_retry_policy(RetryPolicy {
+            max_attempts,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_secs(2),
+        })
+    }
+
+    fn headers(value: &str) -> HeaderMap {
+        let mut h = HeaderMap::new();
+        h.insert(RETRY_AFTER, HeaderValue::from_str(value).unwrap());
+        h
+    }
+
+    #[test]
+    fn retry_after_parses_seconds_and_dates() {
+        let now: DateTime<Utc> = "2026-05-01T12:00:00Z".parse().unwrap();
+        assert_eq!(
+            retry_after(&headers("7"), now),
+            Some(Duration::from_secs(7))
+        );
+        assert_eq!(
+            retry_after(&headers("Fri, 01 May 2026 12:00:30 GMT"), now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            retry_after(&headers("Fri, 01 May 2026 11:00:00 GMT"), now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(retry_after(&headers("soon"), now), None);
+        assert_eq!(retry_after(&HeaderMap::new(), now), None);
+    }
+
+    #[test]
+    fn backoff_is_bounded_and_grows() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(500),
+        };
+        for attempt in 1..=10 {
+            let d = policy.backoff(attempt);
+            assert!(d <= policy.max_delay, "attempt {attempt}: {d:?}");
+        }
+        // Equal jitter keeps at least half the exponential delay.
+        assert!(policy.backoff(1) >= Duration::from_millis(50));
+        assert!(policy.backoff(3) >= Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn retries_until_success() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/flaky"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/flaky"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let client = fast_client(3);
+        let url = format!("{}/flaky", server.uri());
+        let resp = client.send(client.get(&url)).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn exhausted_rate_limit_reports_retry_after() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/limited"))
+            .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "0"))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let client = fast_client(2);
+        let url = format!("{}/limited", server.uri());
+        let err = client.send(client.get(&url)).await.unwrap_err();
+        assert!(matches!(
+            err,
+            ProviderError::RateLimited {
+                retry_after: Some(d)
+            } if d.is_zero()
+        ));
+    }
+
+    #[tokio::test]
+    async fn long_retry_after_is_not_waited_out() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/limited"))
+            .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "3600"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = fast_client(5);
+        let url = format!("{}/limited", server.uri());
+        let err = client.send(client.get(&url)).await.unwrap_err();
+        assert!(matches!(
+            err,
+            ProviderError::RateLimited { retry_after: Some(d) } if d == Duration::from_secs(3600)
+        ));
+    }
+
+    #[tokio::test]
+    async fn exhausted_server_error_returns_last_response() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/down"))
+            .respond_with(ResponseTemplate::new(502))
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let client = fast_client(3);
+        let url = format!("{}/down", server.uri());
+        let resp = client.send(client.get(&url)).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_GATEWAY);
+    }
+
+    #[tokio::test]
+    async fn client_errors_are_not_retried() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/nope"))
+            .respond_with(ResponseTemplate::new(401))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = fast_client(3);
+        let url = format!("{}/nope", server.uri());
+        let resp = client.send(client.get(&url)).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn billable_requests_retry_only_rate_limits() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/down"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/limited"))
+            .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "0"))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/limited"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let client = fast_client(3);
+        let down = format!("{}/down", server.uri());
+        let resp = client.send_billable(client.post(&down)).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let limited = format!("{}/limited", server.uri());
+        let resp = client.send_billable(client.post(&limited)).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+}
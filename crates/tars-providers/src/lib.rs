@@ -2,18 +2,31 @@
 //!
 //! Provides a uniform `Provider` trait that each supported AI provider
 //! implements, a static metadata registry, and concrete HTTP-backed
-//! implementations for `OpenAI`, Anthropic, Gemini, and `DeepSeek`.
+//! implementations for the hosted vendors in [`ProviderId::ALL`] plus a
+//! generic client for user-defined OpenAI-compatible endpoints. All HTTP goes
+//! through [`http::HttpClient`], which retries rate limits and transient
+//! server errors.
 
+pub mod batch;
+mod completion;
 pub mod error;
 pub mod factory;
+pub mod http;
 pub mod provider;
 pub mod providers;
 pub mod registry;
 pub mod types;
 
+pub use batch::{validate_keys, ValidationJob, ValidationOutcome};
 pub use error::ProviderError;
-pub use factory::provider_for;
+pub use factory::{custom_provider, provider_for};
+pub use http::{HttpClient, RetryPolicy};
 pub use provider::Provider;
-pub use providers::{AnthropicProvider, DeepseekProvider, GeminiProvider, OpenAiProvider};
+pub use providers::{
+    AnthropicProvider, DeepseekProvider, GeminiProvider, OpenAiCompatibleProvider, OpenAiProvider,
+};
 pub use registry::{all_metadata, metadata_for};
-pub use types::{Balance, ModelInfo, ProviderId, ProviderMetadata, ValidationResult};
+pub use types::{
+    custom_provider_slug, AuthStyle, Balance, Completion, CustomProviderConfig, ModelInfo,
+    ProviderId, ProviderMetadata, TokenUsage, ValidationResult, CUSTOM_PROVIDER_PREFIX,
+};
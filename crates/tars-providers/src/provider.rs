@@ -4,13 +4,14 @@ use async_trait::async_trait;
 
 use crate::{
     error::ProviderError,
-    types::{Balance, ModelInfo, ProviderId, ProviderMetadata, ValidationResult},
+    types::{Balance, Completion, ModelInfo, ProviderId, ProviderMetadata, ValidationResult},
 };
 
 /// Common interface for AI providers.
 ///
 /// Implementations live in their own modules (one per provider) and handle
-/// auth-check, model discovery, and optional balance queries.
+/// auth-check, model discovery, optional balance queries, and an optional
+/// one-shot completion used to test a key against a specific model.
 #[async_trait]
 pub trait Provider: Send + Sync {
     /// Stable identifier
@@ -42,4 +43,28 @@ pub trait Provider: Send + Sync {
     /// # Errors
     /// Returns a `ProviderError` on network or auth failure.
     async fn get_balance(&self, key: &str) -> Result<Option<Balance>, ProviderError>;
+
+    /// Send a single user message to `model` and return the reply with its
+    /// token usage and latency.
+    ///
+    /// Meant for end-to-end checks of a key/model pair, not for chat: there
+    /// is no system prompt, history, or streaming. The default returns
+    /// `ProviderError::Unsupported`; providers that implement it set
+    /// `ProviderMetadata::supports_completion`.
+    ///
+    /// # Errors
+    /// Returns `ProviderError::Unauthorized` if the key is rejected,
+    /// `ProviderError::Http` carrying the provider's message for model or
+    /// request errors (unknown model, no access), or other variants for
+    /// network / parse failures.
+    async fn complete(
+        &self,
+        key: &str,
+        model: &str,
+        prompt: &str,
+        max_tokens: u32,
+    ) -> Result<Completion, ProviderError> {
+        let _ = (key, model, prompt, max_tokens);
+        Err(ProviderError::Unsupported)
+    }
 }
@@ -1,28 +1,31 @@
 //! Anthropic provider implementation.
 //!
 //! Uses `GET /v1/models` (requires `anthropic-version` header) for both key
-//! validation and model discovery.
+//! validation and model discovery; `complete` uses `POST /v1/messages`.
+
+use std::time::Instant;
 
 use async_trait::async_trait;
-use reqwest::{Client, StatusCode};
+use reqwest::StatusCode;
 use serde::Deserialize;
-use std::time::Duration;
 
 use crate::{
+    completion::{elapsed_ms, error_for},
     error::ProviderError,
+    http::HttpClient,
     provider::Provider,
     registry::metadata_for,
-    types::{Balance, ModelInfo, ProviderId, ProviderMetadata, ValidationResult},
+    types::{
+        Balance, Completion, ModelInfo, ProviderId, ProviderMetadata, TokenUsage, ValidationResult,
+    },
 };
 
 const DEFAULT_BASE_URL: &str = "https://api.anthropic.com";
 // Anthropic's GA API version string. See https://docs.anthropic.com/en/api/versioning
 const API_VERSION: &str = "2023-06-01";
-const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
-const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct AnthropicProvider {
-    client: Client,
+    client: HttpClient,
     base_url: String,
 }
 
@@ -34,7 +37,7 @@ impl AnthropicProvider {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            client: build_client(true),
+            client: HttpClient::new(true),
             base_url: DEFAULT_BASE_URL.to_string(),
         }
     }
@@ -47,22 +50,12 @@ impl AnthropicProvider {
     #[must_use]
     pub fn with_base_url(base_url: String) -> Self {
         Self {
-            client: build_client(false),
+            client: HttpClient::new(false),
             base_url,
         }
     }
 }
 
-fn build_client(https_only: bool) -> Client {
-    Client::builder()
-        .timeout(REQUEST_TIMEOUT)
-        .connect_timeout(CONNECT_TIMEOUT)
-        .user_agent("tars/0.4")
-        .https_only(https_only)
-        .build()
-        .expect("reqwest client builds")
-}
-
 impl Default for AnthropicProvider {
     fn default() -> Self {
         Self::new()
@@ -80,6 +73,33 @@ struct ModelDto {
     display_name: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct MessageResponse {
+    model: String,
+    content: Vec<ContentBlock>,
+    usage: MessageUsage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContentBlock {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    text: Option<String>,
+}
+
+/// Anthropic reports cache reads and writes separately from `input_tokens`.
+#[derive(Debug, Deserialize)]
+#[allow(clippy::struct_field_names)] // Matches Anthropic's JSON field names
+struct MessageUsage {
+    input_tokens: u64,
+    output_tokens: u64,
+    #[serde(default)]
+    cache_read_input_tokens: Option<u64>,
+    #[serde(default)]
+    cache_creation_input_tokens: Option<u64>,
+}
+
 #[async_trait]
 impl Provider for AnthropicProvider {
     fn id(&self) -> ProviderId {
@@ -94,13 +114,14 @@ impl Provider for AnthropicProvider {
         let url = format!("{}/v1/models", self.base_url);
         let resp = self
             .client
-            .get(&url)
-            .header("x-api-key", key)
-            .header("anthropic-version", API_VERSION)
-            .query(&[("limit", "1000")])
-            .send()
-            .await
-            .map_err(ProviderError::from)?;
+            .send(
+                self.client
+                    .get(&url)
+                    .header("x-api-key", key)
+                    .header("anthropic-version", API_VERSION)
+                    .query(&[("limit", "1000")]),
+            )
+            .await?;
 
         let status = resp.status();
         match status {
@@ -127,13 +148,14 @@ impl Provider for AnthropicProvider {
         let url = format!("{}/v1/models", self.base_url);
         let resp = self
             .client
-            .get(&url)
-            .header("x-api-key", key)
-            .header("anthropic-version", API_VERSION)
-            .query(&[("limit", "1000")])
-            .send()
-            .await
-            .map_err(ProviderError::from)?;
+            .send(
+                self.client
+                    .get(&url)
+                    .header("x-api-key", key)
+                    .header("anthropic-version", API_VERSION)
+                    .query(&[("limit", "1000")]),
+            )
+            .await?;
 
         match resp.status() {
             s if s.is_success() => {
@@ -160,12 +182,61 @@ impl Provider for AnthropicProvider {
     async fn get_balance(&self, _key: &str) -> Result<Option<Balance>, ProviderError> {
         Ok(None)
     }
+
+    async fn complete(
+        &self,
+        key: &str,
+        model: &str,
+        prompt: &str,
+        max_tokens: u32,
+    ) -> Result<Completion, ProviderError> {
+        let url = format!("{}/v1/messages", self.base_url);
+        let body = serde_json::json!({
+            "model": model,
+            "max_tokens": max_tokens,
+            "messages": [{"role": "user", "content": prompt}],
+        });
+        let started = Instant::now();
+        let resp = self
+            .client
+            .send_billable(
+                self.client
+                    .post(&url)
+                    .header("x-api-key", key)
+                    .header("anthropic-version", API_VERSION)
+                    .json(&body),
+            )
+            .await?;
+        let latency_ms = elapsed_ms(started);
+        if !resp.status().is_success() {
+            return Err(error_for("Anthropic", resp).await);
+        }
+
+        let parsed: MessageResponse = resp.json().await.map_err(ProviderError::from)?;
+        let text = parsed
+            .content
+            .into_iter()
+            .filter(|block| block.kind == "text")
+            .filter_map(|block| block.text)
+            .collect::<String>();
+        Ok(Completion {
+            model: parsed.model,
+            text,
+            usage: TokenUsage {
+                input_tokens: parsed.usage.input_tokens,
+                output_tokens: parsed.usage.output_tokens,
+                cache_read_tokens: parsed.usage.cache_read_input_tokens.unwrap_or(0),
+                cache_write_tokens: parsed.usage.cache_creation_input_tokens.unwrap_or(0),
+            },
+            latency_ms,
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use wiremock::matchers::{header, method, path};
+    use wiremock::matchers::{body_partial_json, header, method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
     fn provider(server: &MockServer) -> AnthropicProvider {
@@ -256,4 +327,70 @@ mod tests {
         assert_eq!(p.id(), ProviderId::Anthropic);
         assert_eq!(p.metadata().display_name, "Anthropic");
     }
+
+    #[tokio::test]
+    async fn complete_joins_text_blocks_and_reports_cache_usage() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(header("x-api-key", "sk-ant-good"))
+            .and(header("anthropic-version", API_VERSION))
+            .and(body_partial_json(serde_json::json!({
+                "model": "claude-sonnet-4-5",
+                "max_tokens": 32
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "model": "claude-sonnet-4-5-20250929",
+                "content": [
+                    {"type": "text", "text": "po"},
+                    {"type": "text", "text": "ng"}
+                ],
+                "usage": {
+                    "input_tokens": 12,
+                    "output_tokens": 4,
+                    "cache_read_input_tokens": 2048,
+                    "cache_creation_input_tokens": null
+                }
+            })))
+            .mount(&server)
+            .await;
+
+        let completion = provider(&server)
+            .complete("sk-ant-good", "claude-sonnet-4-5", "ping", 32)
+            .await
+            .unwrap();
+        assert_eq!(completion.text, "pong");
+        assert_eq!(completion.model, "claude-sonnet-4-5-20250929");
+        assert_eq!(
+            completion.usage,
+            TokenUsage {
+                input_tokens: 12,
+                output_tokens: 4,
+                cache_read_tokens: 2048,
+                cache_write_tokens: 0,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn complete_surfaces_not_found_model() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+                "type": "error",
+                "error": {"type": "not_found_error", "message": "model: claude-nope"}
+            })))
+            .mount(&server)
+            .await;
+
+        let err = provider(&server)
+            .complete("sk-ant-good", "claude-nope", "ping", 32)
+            .await
+            .unwrap_err();
+        match err {
+            ProviderError::Http(msg) => assert!(msg.contains("model: claude-nope"), "{msg}"),
+            other => panic!("expected Http, got {other:?}"),
+        }
+    }
 }
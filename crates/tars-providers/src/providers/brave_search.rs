@@ -5,22 +5,20 @@
 //! header. No model list, no balance.
 
 use async_trait::async_trait;
-use reqwest::{Client, StatusCode};
-use std::time::Duration;
+use reqwest::StatusCode;
 
 use crate::{
     error::ProviderError,
+    http::HttpClient,
     provider::Provider,
     registry::metadata_for,
     types::{Balance, ModelInfo, ProviderId, ProviderMetadata, ValidationResult},
 };
 
 const DEFAULT_BASE_URL: &str = "https://api.search.brave.com";
-const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
-const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct BraveSearchProvider {
-    client: Client,
+    client: HttpClient,
     base_url: String,
 }
 
@@ -28,7 +26,7 @@ impl BraveSearchProvider {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            client: build_client(true),
+            client: HttpClient::new(true),
             base_url: DEFAULT_BASE_URL.to_string(),
         }
     }
@@ -36,22 +34,12 @@ impl BraveSearchProvider {
     #[must_use]
     pub fn with_base_url(base_url: String) -> Self {
         Self {
-            client: build_client(false),
+            client: HttpClient::new(false),
             base_url,
         }
     }
 }
 
-fn build_client(https_only: bool) -> Client {
-    Client::builder()
-        .timeout(REQUEST_TIMEOUT)
-        .connect_timeout(CONNECT_TIMEOUT)
-        .user_agent("tars/0.4")
-        .https_only(https_only)
-        .build()
-        .expect("reqwest client builds")
-}
-
 impl Default for BraveSearchProvider {
     fn default() -> Self {
         Self::new()
@@ -72,13 +60,14 @@ impl Provider for BraveSearchProvider {
         let url = format!("{}/res/v1/web/search", self.base_url);
         let resp = self
             .client
-            .get(&url)
-            .header("X-Subscription-Token", key)
-            .header("Accept", "application/json")
-            .query(&[("q", "t"), ("count", "1")])
-            .send()
-            .await
-            .map_err(ProviderError::from)?;
+            .send(
+                self.client
+                    .get(&url)
+                    .header("X-Subscription-Token", key)
+                    .header("Accept", "application/json")
+                    .query(&[("q", "t"), ("count", "1")]),
+            )
+            .await?;
 
         let status = resp.status();
         match status {
@@ -2,26 +2,25 @@
 //!
 //! Uses `GET /user/balance` for both key validation and balance (the balance
 //! endpoint doubles as an auth check). Model discovery is via the `OpenAI`-
-//! compatible `GET /v1/models`.
+//! compatible `GET /v1/models`, and `complete` uses `POST /chat/completions`.
 
 use async_trait::async_trait;
-use reqwest::{Client, StatusCode};
+use reqwest::StatusCode;
 use serde::Deserialize;
-use std::time::Duration;
 
 use crate::{
+    completion::chat_completion,
     error::ProviderError,
+    http::HttpClient,
     provider::Provider,
     registry::metadata_for,
-    types::{Balance, ModelInfo, ProviderId, ProviderMetadata, ValidationResult},
+    types::{Balance, Completion, ModelInfo, ProviderId, ProviderMetadata, ValidationResult},
 };
 
 const DEFAULT_BASE_URL: &str = "https://api.deepseek.com";
-const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
-const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct DeepseekProvider {
-    client: Client,
+    client: HttpClient,
     base_url: String,
 }
 
@@ -33,7 +32,7 @@ impl DeepseekProvider {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            client: build_client(true),
+            client: HttpClient::new(true),
             base_url: DEFAULT_BASE_URL.to_string(),
         }
     }
@@ -46,22 +45,12 @@ impl DeepseekProvider {
     #[must_use]
     pub fn with_base_url(base_url: String) -> Self {
         Self {
-            client: build_client(false),
+            client: HttpClient::new(false),
             base_url,
         }
     }
 }
 
-fn build_client(https_only: bool) -> Client {
-    Client::builder()
-        .timeout(REQUEST_TIMEOUT)
-        .connect_timeout(CONNECT_TIMEOUT)
-        .user_agent("tars/0.4")
-        .https_only(https_only)
-        .build()
-        .expect("reqwest client builds")
-}
-
 impl Default for DeepseekProvider {
     fn default() -> Self {
         Self::new()
@@ -103,11 +92,8 @@ impl Provider for DeepseekProvider {
         let url = format!("{}/user/balance", self.base_url);
         let resp = self
             .client
-            .get(&url)
-            .bearer_auth(key)
-            .send()
-            .await
-            .map_err(ProviderError::from)?;
+            .send(self.client.get(&url).bearer_auth(key))
+            .await?;
 
         let status = resp.status();
         match status {
@@ -130,11 +116,8 @@ impl Provider for DeepseekProvider {
         let url = format!("{}/v1/models", self.base_url);
         let resp = self
             .client
-            .get(&url)
-            .bearer_auth(key)
-            .send()
-            .await
-            .map_err(ProviderError::from)?;
+            .send(self.client.get(&url).bearer_auth(key))
+            .await?;
 
         match resp.status() {
             s if s.is_success() => {
@@ -162,11 +145,8 @@ impl Provider for DeepseekProvider {
         let url = format!("{}/user/balance", self.base_url);
         let resp = self
             .client
-            .get(&url)
-            .bearer_auth(key)
-            .send()
-            .await
-            .map_err(ProviderError::from)?;
+            .send(self.client.get(&url).bearer_auth(key))
+            .await?;
 
         match resp.status() {
             s if s.is_success() => {
@@ -198,6 +178,23 @@ impl Provider for DeepseekProvider {
             other => Err(ProviderError::Http(format!("DeepSeek returned {other}"))),
         }
     }
+
+    async fn complete(
+        &self,
+        key: &str,
+        model: &str,
+        prompt: &str,
+        max_tokens: u32,
+    ) -> Result<Completion, ProviderError> {
+        let url = format!("{}/chat/completions", self.base_url);
+        let body = serde_json::json!({
+            "model": model,
+            "messages": [{"role": "user", "content": prompt}],
+            "max_tokens": max_tokens,
+        });
+        let request = self.client.post(&url).bearer_auth(key).json(&body);
+        chat_completion(&self.client, request, "DeepSeek").await
+    }
 }
 
 #[cfg(test)]
@@ -329,4 +326,33 @@ mod tests {
         assert_eq!(p.metadata().display_name, "DeepSeek");
         assert!(p.metadata().supports_balance);
     }
+
+    #[tokio::test]
+    async fn complete_splits_cache_hits_from_input() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .and(header("authorization", "Bearer sk-good"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "model": "deepseek-chat",
+                "choices": [{"index": 0, "message": {"role": "assistant", "content": "pong"}}],
+                "usage": {
+                    "prompt_tokens": 100,
+                    "completion_tokens": 2,
+                    "prompt_cache_hit_tokens": 64,
+                    "prompt_cache_miss_tokens": 36
+                }
+            })))
+            .mount(&server)
+            .await;
+
+        let completion = provider(&server)
+            .complete("sk-good", "deepseek-chat", "ping", 8)
+            .await
+            .unwrap();
+        assert_eq!(completion.text, "pong");
+        assert_eq!(completion.usage.input_tokens, 36);
+        assert_eq!(completion.usage.cache_read_tokens, 64);
+        assert_eq!(completion.usage.output_tokens, 2);
+    }
 }
@@ -1,25 +1,26 @@
 //! `ElevenLabs` provider.
 //!
-//! Simple-storage provider: auth-check only via `GET /v1/user` with the
-//! `xi-api-key` header. No model list, no balance.
+//! Auth check via `GET /v1/user` with the `xi-api-key` header. No model
+//! list. Balance is the remaining character quota for the current billing
+//! period from `GET /v1/user/subscription` (`character_limit -
+//! character_count`), reported with the pseudo-currency `characters`.
 
 use async_trait::async_trait;
-use reqwest::{Client, StatusCode};
-use std::time::Duration;
+use reqwest::StatusCode;
+use serde::Deserialize;
 
 use crate::{
     error::ProviderError,
+    http::HttpClient,
     provider::Provider,
     registry::metadata_for,
     types::{Balance, ModelInfo, ProviderId, ProviderMetadata, ValidationResult},
 };
 
 const DEFAULT_BASE_URL: &str = "https://api.elevenlabs.io";
-const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
-const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct ElevenLabsProvider {
-    client: Client,
+    client: HttpClient,
     base_url: String,
 }
 
@@ -27,7 +28,7 @@ impl ElevenLabsProvider {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            client: build_client(true),
+            client: HttpClient::new(true),
             base_url: DEFAULT_BASE_URL.to_string(),
         }
     }
@@ -35,28 +36,27 @@ impl ElevenLabsProvider {
     #[must_use]
     pub fn with_base_url(base_url: String) -> Self {
         Self {
-            client: build_client(false),
+            client: HttpClient::new(false),
             base_url,
         }
     }
 }
 
-fn build_client(https_only: bool) -> Client {
-    Client::builder()
-        .timeout(REQUEST_TIMEOUT)
-        .connect_timeout(CONNECT_TIMEOUT)
-        .user_agent("tars/0.4")
-        .https_only(https_only)
-        .build()
-        .expect("reqwest client builds")
-}
-
 impl Default for ElevenLabsProvider {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// Currency label used for character-quota balances.
+pub const CHARACTERS_CURRENCY: &str = "characters";
+
+#[derive(Debug, Deserialize)]
+struct SubscriptionResponse {
+    character_count: f64,
+    character_limit: f64,
+}
+
 #[async_trait]
 impl Provider for ElevenLabsProvider {
     fn id(&self) -> ProviderId {
@@ -71,11 +71,8 @@ impl Provider for ElevenLabsProvider {
         let url = format!("{}/v1/user", self.base_url);
         let resp = self
             .client
-            .get(&url)
-            .header("xi-api-key", key)
-            .send()
-            .await
-            .map_err(ProviderError::from)?;
+            .send(self.client.get(&url).header("xi-api-key", key))
+            .await?;
 
         let status = resp.status();
         match status {
@@ -98,8 +95,30 @@ impl Provider for ElevenLabsProvider {
         Err(ProviderError::Unsupported)
     }
 
-    async fn get_balance(&self, _key: &str) -> Result<Option<Balance>, ProviderError> {
-        Ok(None)
+    async fn get_balance(&self, key: &str) -> Result<Option<Balance>, ProviderError> {
+        let url = format!("{}/v1/user/subscription", self.base_url);
+        let resp = self
+            .client
+            .send(self.client.get(&url).header("xi-api-key", key))
+            .await?;
+
+        match resp.status() {
+            s if s.is_success() => {
+                let raw: serde_json::Value = resp.json().await.map_err(ProviderError::from)?;
+                let parsed: SubscriptionResponse = serde_json::from_value(raw.clone())
+                    .map_err(|e| ProviderError::Parse(e.to_string()))?;
+                let remaining = (parsed.character_limit - parsed.character_count).max(0.0);
+                Ok(Some(Balance {
+                    currency: CHARACTERS_CURRENCY.to_string(),
+                    amount: remaining,
+                    raw,
+                }))
+            }
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(ProviderError::Unauthorized {
+                status: resp.status().as_u16(),
+            }),
+            other => Err(ProviderError::Http(format!("ElevenLabs returned {other}"))),
+        }
     }
 }
 
@@ -164,9 +183,40 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn get_balance_returns_none() {
+    async fn get_balance_reports_remaining_characters() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/user/subscription"))
+            .and(header("xi-api-key", "sk_good"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "tier": "creator",
+                "character_count": 12_500,
+                "character_limit": 100_000
+            })))
+            .mount(&server)
+            .await;
+
+        let bal = provider(&server)
+            .get_balance("sk_good")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(bal.currency, "characters");
+        assert!((bal.amount - 87_500.0).abs() < f64::EPSILON);
+        assert_eq!(bal.raw["tier"], "creator");
+    }
+
+    #[tokio::test]
+    async fn get_balance_unauthorized() {
         let server = MockServer::start().await;
-        assert!(provider(&server).get_balance("x").await.unwrap().is_none());
+        Mock::given(method("GET"))
+            .and(path("/v1/user/subscription"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        let err = provider(&server).get_balance("bad").await.unwrap_err();
+        assert!(matches!(err, ProviderError::Unauthorized { status: 401 }));
     }
 
     #[test]
@@ -175,6 +225,6 @@ mod tests {
         assert_eq!(p.id(), ProviderId::ElevenLabs);
         assert_eq!(p.metadata().display_name, "ElevenLabs");
         assert!(!p.metadata().supports_models);
-        assert!(!p.metadata().supports_balance);
+        assert!(p.metadata().supports_balance);
     }
 }
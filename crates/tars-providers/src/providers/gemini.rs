@@ -2,26 +2,30 @@
 //!
 //! Auth check and model discovery share `GET /v1beta/models`, with the API
 //! key passed via the `x-goog-api-key` header (not the `?key=` query param)
-//! so it never lands in the request URL or error messages.
+//! so it never lands in the request URL or error messages. `complete` uses
+//! `POST /v1beta/models/{model}:generateContent` with the same header.
+
+use std::time::Instant;
 
 use async_trait::async_trait;
-use reqwest::{Client, StatusCode};
+use reqwest::{StatusCode, Url};
 use serde::Deserialize;
-use std::time::Duration;
 
 use crate::{
+    completion::{elapsed_ms, error_for},
     error::ProviderError,
+    http::HttpClient,
     provider::Provider,
     registry::metadata_for,
-    types::{Balance, ModelInfo, ProviderId, ProviderMetadata, ValidationResult},
+    types::{
+        Balance, Completion, ModelInfo, ProviderId, ProviderMetadata, TokenUsage, ValidationResult,
+    },
 };
 
 const DEFAULT_BASE_URL: &str = "https://generativelanguage.googleapis.com";
-const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
-const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct GeminiProvider {
-    client: Client,
+    client: HttpClient,
     base_url: String,
 }
 
@@ -33,7 +37,7 @@ impl GeminiProvider {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            client: build_client(true),
+            client: HttpClient::new(true),
             base_url: DEFAULT_BASE_URL.to_string(),
         }
     }
@@ -46,22 +50,12 @@ impl GeminiProvider {
     #[must_use]
     pub fn with_base_url(base_url: String) -> Self {
         Self {
-            client: build_client(false),
+            client: HttpClient::new(false),
             base_url,
         }
     }
 }
 
-fn build_client(https_only: bool) -> Client {
-    Client::builder()
-        .timeout(REQUEST_TIMEOUT)
-        .connect_timeout(CONNECT_TIMEOUT)
-        .user_agent("tars/0.4")
-        .https_only(https_only)
-        .build()
-        .expect("reqwest client builds")
-}
-
 impl Default for GeminiProvider {
     fn default() -> Self {
         Self::new()
@@ -86,6 +80,64 @@ fn strip_models_prefix(name: &str) -> String {
     name.strip_prefix("models/").unwrap_or(name).to_string()
 }
 
+/// `{base_url}/v1beta/models/{model}:generateContent`, with the model id
+/// percent-encoded as a single path segment.
+fn generate_content_url(base_url: &str, model: &str) -> Result<Url, ProviderError> {
+    let invalid = || ProviderError::Http(format!("invalid Gemini base URL: {base_url}"));
+    let mut url = Url::parse(base_url).map_err(|_| invalid())?;
+    url.path_segments_mut()
+        .map_err(|()| invalid())?
+        .pop_if_empty()
+        .extend(["v1beta", "models", &format!("{model}:generateContent")]);
+    Ok(url)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerateResponse {
+    #[serde(default)]
+    candidates: Vec<Candidate>,
+    #[serde(default)]
+    usage_metadata: UsageMetadata,
+    #[serde(default)]
+    model_version: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Candidate {
+    /// Missing when the candidate was blocked by a safety filter
+    #[serde(default)]
+    content: Option<CandidateContent>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CandidateContent {
+    #[serde(default)]
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Part {
+    #[serde(default)]
+    text: Option<String>,
+}
+
+/// `promptTokenCount` includes cached tokens; thinking tokens are billed as
+/// output but reported separately from `candidatesTokenCount`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[allow(clippy::struct_field_names)] // Matches Gemini's JSON field names
+struct UsageMetadata {
+    #[serde(default)]
+    prompt_token_count: u64,
+    #[serde(default)]
+    candidates_token_count: u64,
+    #[serde(default)]
+    thoughts_token_count: u64,
+    #[serde(default)]
+    cached_content_token_count: u64,
+}
+
 #[async_trait]
 impl Provider for GeminiProvider {
     fn id(&self) -> ProviderId {
@@ -105,12 +157,13 @@ impl Provider for GeminiProvider {
         // Tauri error surface.
         let resp = self
             .client
-            .get(&url)
-            .header("x-goog-api-key", key)
-            .query(&[("pageSize", "1000")])
-            .send()
-            .await
-            .map_err(ProviderError::from)?;
+            .send(
+                self.client
+                    .get(&url)
+                    .header("x-goog-api-key", key)
+                    .query(&[("pageSize", "1000")]),
+            )
+            .await?;
 
         let status = resp.status();
         match status {
@@ -134,12 +187,13 @@ impl Provider for GeminiProvider {
         let url = format!("{}/v1beta/models", self.base_url);
         let resp = self
             .client
-            .get(&url)
-            .header("x-goog-api-key", key)
-            .query(&[("pageSize", "1000")])
-            .send()
-            .await
-            .map_err(ProviderError::from)?;
+            .send(
+                self.client
+                    .get(&url)
+                    .header("x-goog-api-key", key)
+                    .query(&[("pageSize", "1000")]),
+            )
+            .await?;
 
         match resp.status() {
             s if s.is_success() => {
@@ -166,12 +220,64 @@ impl Provider for GeminiProvider {
     async fn get_balance(&self, _key: &str) -> Result<Option<Balance>, ProviderError> {
         Ok(None)
     }
+
+    async fn complete(
+        &self,
+        key: &str,
+        model: &str,
+        prompt: &str,
+        max_tokens: u32,
+    ) -> Result<Completion, ProviderError> {
+        let model = model.strip_prefix("models/").unwrap_or(model);
+        let url = generate_content_url(&self.base_url, model)?;
+        let body = serde_json::json!({
+            "contents": [{"role": "user", "parts": [{"text": prompt}]}],
+            "generationConfig": {"maxOutputTokens": max_tokens},
+        });
+        let started = Instant::now();
+        let resp = self
+            .client
+            .send_billable(
+                self.client
+                    .post(url.as_str())
+                    .header("x-goog-api-key", key)
+                    .json(&body),
+            )
+            .await?;
+        let latency_ms = elapsed_ms(started);
+        if !resp.status().is_success() {
+            return Err(error_for("Gemini", resp).await);
+        }
+
+        let parsed: GenerateResponse = resp.json().await.map_err(ProviderError::from)?;
+        let usage = &parsed.usage_metadata;
+        let text = parsed
+            .candidates
+            .into_iter()
+            .next()
+            .and_then(|c| c.content)
+            .map(|c| c.parts.into_iter().filter_map(|p| p.text).collect())
+            .unwrap_or_default();
+        Ok(Completion {
+            model: parsed.model_version.unwrap_or_else(|| model.to_string()),
+            text,
+            usage: TokenUsage {
+                input_tokens: usage
+                    .prompt_token_count
+                    .saturating_sub(usage.cached_content_token_count),
+                output_tokens: usage.candidates_token_count + usage.thoughts_token_count,
+                cache_read_tokens: usage.cached_content_token_count,
+                cache_write_tokens: 0,
+            },
+            latency_ms,
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::matchers::{body_partial_json, header, method, path, query_param};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
     fn provider(server: &MockServer) -> GeminiProvider {
@@ -280,4 +386,63 @@ mod tests {
         assert_eq!(p.id(), ProviderId::Gemini);
         assert_eq!(p.metadata().display_name, "Google Gemini");
     }
+
+    #[tokio::test]
+    async fn complete_counts_thinking_tokens_as_output() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1beta/models/gemini-2.5-flash:generateContent"))
+            .and(header("x-goog-api-key", "AIza-good"))
+            .and(body_partial_json(serde_json::json!({
+                "generationConfig": {"maxOutputTokens": 64}
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "candidates": [{"content": {"role": "model", "parts": [{"text": "pong"}]}}],
+                "usageMetadata": {
+                    "promptTokenCount": 10,
+                    "candidatesTokenCount": 2,
+                    "thoughtsTokenCount": 30
+                },
+                "modelVersion": "gemini-2.5-flash"
+            })))
+            .mount(&server)
+            .await;
+
+        let completion = provider(&server)
+            .complete("AIza-good", "models/gemini-2.5-flash", "ping", 64)
+            .await
+            .unwrap();
+        assert_eq!(completion.text, "pong");
+        assert_eq!(completion.usage.input_tokens, 10);
+        assert_eq!(completion.usage.output_tokens, 32);
+    }
+
+    #[tokio::test]
+    async fn complete_keeps_key_out_of_error_message() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1beta/models/gemini-nope:generateContent"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+                "error": {"code": 404, "message": "models/gemini-nope is not found", "status": "NOT_FOUND"}
+            })))
+            .mount(&server)
+            .await;
+
+        let err = provider(&server)
+            .complete("AIza-secret", "gemini-nope", "ping", 64)
+            .await
+            .unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("is not found"), "{msg}");
+        assert!(!msg.contains("AIza-secret"));
+    }
+
+    #[test]
+    fn generate_content_url_encodes_the_model() {
+        let url = generate_content_url("https://example.test/", "gemini/../x?y").unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://example.test/v1beta/models/gemini%2F..%2Fx%3Fy:generateContent"
+        );
+    }
 }
@@ -1,26 +1,25 @@
 //! Groq provider.
 //!
-//! Simple-storage provider: auth-check only via `GET /openai/v1/models`
-//! (OpenAI-compatible endpoint) with `Authorization: Bearer`. No model list
-//! surfaced, no balance.
+//! Auth check and model discovery share the OpenAI-compatible
+//! `GET /openai/v1/models` endpoint with `Authorization: Bearer`. Groq adds a
+//! `context_window` field to each model entry, which we surface. No balance.
 
 use async_trait::async_trait;
-use reqwest::{Client, StatusCode};
-use std::time::Duration;
+use reqwest::StatusCode;
+use serde::Deserialize;
 
 use crate::{
     error::ProviderError,
+    http::HttpClient,
     provider::Provider,
     registry::metadata_for,
     types::{Balance, ModelInfo, ProviderId, ProviderMetadata, ValidationResult},
 };
 
 const DEFAULT_BASE_URL: &str = "https://api.groq.com";
-const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
-const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct GroqProvider {
-    client: Client,
+    client: HttpClient,
     base_url: String,
 }
 
@@ -28,7 +27,7 @@ impl GroqProvider {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            client: build_client(true),
+            client: HttpClient::new(true),
             base_url: DEFAULT_BASE_URL.to_string(),
         }
     }
@@ -36,28 +35,30 @@ impl GroqProvider {
     #[must_use]
     pub fn with_base_url(base_url: String) -> Self {
         Self {
-            client: build_client(false),
+            client: HttpClient::new(false),
             base_url,
         }
     }
 }
 
-fn build_client(https_only: bool) -> Client {
-    Client::builder()
-        .timeout(REQUEST_TIMEOUT)
-        .connect_timeout(CONNECT_TIMEOUT)
-        .user_agent("tars/0.4")
-        .https_only(https_only)
-        .build()
-        .expect("reqwest client builds")
-}
-
 impl Default for GroqProvider {
     fn default() -> Self {
         Self::new()
     }
 }
 
+#[derive(Debug, Deserialize)]
+struct ModelsResponse {
+    data: Vec<ModelDto>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelDto {
+    id: String,
+    #[serde(default)]
+    context_window: Option<u32>,
+}
+
 #[async_trait]
 impl Provider for GroqProvider {
     fn id(&self) -> ProviderId {
@@ -72,11 +73,8 @@ impl Provider for GroqProvider {
         let url = format!("{}/openai/v1/models", self.base_url);
         let resp = self
             .client
-            .get(&url)
-            .bearer_auth(key)
-            .send()
-            .await
-            .map_err(ProviderError::from)?;
+            .send(self.client.get(&url).bearer_auth(key))
+            .await?;
 
         let status = resp.status();
         match status {
@@ -92,8 +90,33 @@ impl Provider for GroqProvider {
         }
     }
 
-    async fn list_models(&self, _key: &str) -> Result<Vec<ModelInfo>, ProviderError> {
-        Err(ProviderError::Unsupported)
+    async fn list_models(&self, key: &str) -> Result<Vec<ModelInfo>, ProviderError> {
+        let url = format!("{}/openai/v1/models", self.base_url);
+        let resp = self
+            .client
+            .send(self.client.get(&url).bearer_auth(key))
+            .await?;
+
+        match resp.status() {
+            s if s.is_success() => {
+                let parsed: ModelsResponse = resp.json().await.map_err(ProviderError::from)?;
+                Ok(parsed
+                    .data
+                    .into_iter()
+                    .map(|m| ModelInfo {
+                        id: m.id,
+                        display_name: None,
+                        context_window: m.context_window,
+                        input_price_per_million: None,
+                        output_price_per_million: None,
+                    })
+                    .collect())
+            }
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(ProviderError::Unauthorized {
+                status: resp.status().as_u16(),
+            }),
+            other => Err(ProviderError::Http(format!("Groq returned {other}"))),
+        }
     }
 
     async fn get_balance(&self, _key: &str) -> Result<Option<Balance>, ProviderError> {
@@ -155,10 +178,45 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn list_models_returns_unsupported() {
+    async fn list_models_parses_context_window() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/openai/v1/models"))
+            .and(header("authorization", "Bearer gsk_good"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "object": "list",
+                "data": [
+                    {
+                        "id": "llama-3.3-70b-versatile",
+                        "object": "model",
+                        "owned_by": "Meta",
+                        "active": true,
+                        "context_window": 131_072
+                    },
+                    {"id": "whisper-large-v3", "object": "model"}
+                ]
+            })))
+            .mount(&server)
+            .await;
+
+        let models = provider(&server).list_models("gsk_good").await.unwrap();
+        assert_eq!(models.len(), 2);
+        assert_eq!(models[0].id, "llama-3.3-70b-versatile");
+        assert_eq!(models[0].context_window, Some(131_072));
+        assert_eq!(models[1].context_window, None);
+    }
+
+    #[tokio::test]
+    async fn list_models_returns_unauthorized_on_401() {
         let server = MockServer::start().await;
-        let err = provider(&server).list_models("x").await.unwrap_err();
-        assert!(matches!(err, ProviderError::Unsupported));
+        Mock::given(method("GET"))
+            .and(path("/openai/v1/models"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        let err = provider(&server).list_models("gsk_bad").await.unwrap_err();
+        assert!(matches!(err, ProviderError::Unauthorized { status: 401 }));
     }
 
     #[tokio::test]
@@ -172,7 +230,7 @@ mod tests {
         let p = GroqProvider::new();
         assert_eq!(p.id(), ProviderId::Groq);
         assert_eq!(p.metadata().display_name, "Groq");
-        assert!(!p.metadata().supports_models);
+        assert!(p.metadata().supports_models);
         assert!(!p.metadata().supports_balance);
     }
 }
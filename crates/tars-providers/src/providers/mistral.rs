@@ -1,25 +1,25 @@
 //! Mistral provider.
 //!
-//! Simple-storage provider: auth-check only via `GET /v1/models` with
-//! `Authorization: Bearer`. No model list surfaced, no balance.
+//! Auth check and model discovery share `GET /v1/models` with
+//! `Authorization: Bearer`. Mistral's model cards carry a human-readable
+//! `name` and a `max_context_length`, both surfaced. No balance.
 
 use async_trait::async_trait;
-use reqwest::{Client, StatusCode};
-use std::time::Duration;
+use reqwest::StatusCode;
+use serde::Deserialize;
 
 use crate::{
     error::ProviderError,
+    http::HttpClient,
     provider::Provider,
     registry::metadata_for,
     types::{Balance, ModelInfo, ProviderId, ProviderMetadata, ValidationResult},
 };
 
 const DEFAULT_BASE_URL: &str = "https://api.mistral.ai";
-const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
-const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct MistralProvider {
-    client: Client,
+    client: HttpClient,
     base_url: String,
 }
 
@@ -27,7 +27,7 @@ impl MistralProvider {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            client: build_client(true),
+            client: HttpClient::new(true),
             base_url: DEFAULT_BASE_URL.to_string(),
         }
     }
@@ -35,28 +35,32 @@ impl MistralProvider {
     #[must_use]
     pub fn with_base_url(base_url: String) -> Self {
         Self {
-            client: build_client(false),
+            client: HttpClient::new(false),
             base_url,
         }
     }
 }
 
-fn build_client(https_only: bool) -> Client {
-    Client::builder()
-        .timeout(REQUEST_TIMEOUT)
-        .connect_timeout(CONNECT_TIMEOUT)
-        .user_agent("tars/0.4")
-        .https_only(https_only)
-        .build()
-        .expect("reqwest client builds")
-}
-
 impl Default for MistralProvider {
     fn default() -> Self {
         Self::new()
     }
 }
 
+#[derive(Debug, Deserialize)]
+struct ModelsResponse {
+    data: Vec<ModelDto>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelDto {
+    id: String,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    max_context_length: Option<u32>,
+}
+
 #[async_trait]
 impl Provider for MistralProvider {
     fn id(&self) -> ProviderId {
@@ -71,11 +75,8 @@ impl Provider for MistralProvider {
         let url = format!("{}/v1/models", self.base_url);
         let resp = self
             .client
-            .get(&url)
-            .bearer_auth(key)
-            .send()
-            .await
-            .map_err(ProviderError::from)?;
+            .send(self.client.get(&url).bearer_auth(key))
+            .await?;
 
         let status = resp.status();
         match status {
@@ -94,8 +95,33 @@ impl Provider for MistralProvider {
         }
     }
 
-    async fn list_models(&self, _key: &str) -> Result<Vec<ModelInfo>, ProviderError> {
-        Err(ProviderError::Unsupported)
+    async fn list_models(&self, key: &str) -> Result<Vec<ModelInfo>, ProviderError> {
+        let url = format!("{}/v1/models", self.base_url);
+        let resp = self
+            .client
+            .send(self.client.get(&url).bearer_auth(key))
+            .await?;
+
+        match resp.status() {
+            s if s.is_success() => {
+                let parsed: ModelsResponse = resp.json().await.map_err(ProviderError::from)?;
+                Ok(parsed
+                    .data
+                    .into_iter()
+                    .map(|m| ModelInfo {
+                        id: m.id,
+                        display_name: m.name,
+                        context_window: m.max_context_length,
+                        input_price_per_million: None,
+                        output_price_per_million: None,
+                    })
+                    .collect())
+            }
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(ProviderError::Unauthorized {
+                status: resp.status().as_u16(),
+            }),
+            other => Err(ProviderError::Http(format!("Mistral returned {other}"))),
+        }
     }
 
     async fn get_balance(&self, _key: &str) -> Result<Option<Balance>, ProviderError> {
@@ -157,10 +183,48 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn list_models_returns_unsupported() {
+    async fn list_models_parses_name_and_context_length() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .and(header("authorization", "Bearer good"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "object": "list",
+                "data": [
+                    {
+                        "id": "mistral-large-latest",
+                        "object": "model",
+                        "name": "mistral-large-2411",
+                        "max_context_length": 131_072
+                    },
+                    {"id": "codestral-latest", "object": "model", "name": null}
+                ]
+            })))
+            .mount(&server)
+            .await;
+
+        let models = provider(&server).list_models("good").await.unwrap();
+        assert_eq!(models.len(), 2);
+        assert_eq!(
+            models[0].display_name.as_deref(),
+            Some("mistral-large-2411")
+        );
+        assert_eq!(models[0].context_window, Some(131_072));
+        assert_eq!(models[1].display_name, None);
+        assert_eq!(models[1].context_window, None);
+    }
+
+    #[tokio::test]
+    async fn list_models_returns_unauthorized_on_401() {
         let server = MockServer::start().await;
-        let err = provider(&server).list_models("x").await.unwrap_err();
-        assert!(matches!(err, ProviderError::Unsupported));
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        let err = provider(&server).list_models("bad").await.unwrap_err();
+        assert!(matches!(err, ProviderError::Unauthorized { status: 401 }));
     }
 
     #[tokio::test]
@@ -174,7 +238,7 @@ mod tests {
         let p = MistralProvider::new();
         assert_eq!(p.id(), ProviderId::Mistral);
         assert_eq!(p.metadata().display_name, "Mistral");
-        assert!(!p.metadata().supports_models);
+        assert!(p.metadata().supports_models);
         assert!(!p.metadata().supports_balance);
     }
 }
@@ -1,7 +1,7 @@
 //! Concrete `Provider` implementations, one per supported vendor.
 //!
-//! Each submodule is self-contained: its struct owns a `reqwest::Client` and
-//! a configurable `base_url` (so tests can point at a `wiremock::MockServer`).
+//! Each submodule is self-contained: its struct owns a shared
+//! [`crate::http::HttpClient`] and a configurable `base_url` (so tests can point at a `wiremock::MockServer`).
 //! Default base URLs match each vendor's production API.
 
 pub mod anthropic;
@@ -12,6 +12,7 @@ pub mod gemini;
 pub mod groq;
 pub mod mistral;
 pub mod openai;
+pub mod openai_compatible;
 pub mod openrouter;
 pub mod perplexity;
 pub mod xai;
@@ -24,6 +25,7 @@ pub use gemini::GeminiProvider;
 pub use groq::GroqProvider;
 pub use mistral::MistralProvider;
 pub use openai::OpenAiProvider;
+pub use openai_compatible::OpenAiCompatibleProvider;
 pub use openrouter::OpenRouterProvider;
 pub use perplexity::PerplexityProvider;
 pub use xai::XAiProvider;
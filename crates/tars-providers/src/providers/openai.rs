@@ -3,26 +3,26 @@
 //! Auth check and model discovery share the same endpoint: `GET /v1/models`.
 //! A 200 response means the key is valid; a 401/403 means invalid (surfaced as
 //! `ValidationResult { valid: false }` from `validate_key` and as
-//! `ProviderError::Unauthorized` from `list_models`).
+//! `ProviderError::Unauthorized` from `list_models`). `complete` posts a single
+//! user message to `POST /v1/chat/completions`.
 
 use async_trait::async_trait;
-use reqwest::{Client, StatusCode};
+use reqwest::StatusCode;
 use serde::Deserialize;
-use std::time::Duration;
 
 use crate::{
+    completion::chat_completion,
     error::ProviderError,
+    http::HttpClient,
     provider::Provider,
     registry::metadata_for,
-    types::{Balance, ModelInfo, ProviderId, ProviderMetadata, ValidationResult},
+    types::{Balance, Completion, ModelInfo, ProviderId, ProviderMetadata, ValidationResult},
 };
 
 const DEFAULT_BASE_URL: &str = "https://api.openai.com";
-const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
-const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct OpenAiProvider {
-    client: Client,
+    client: HttpClient,
     base_url: String,
 }
 
@@ -35,7 +35,7 @@ impl OpenAiProvider {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            client: build_client(true),
+            client: HttpClient::new(true),
             base_url: DEFAULT_BASE_URL.to_string(),
         }
     }
@@ -48,22 +48,12 @@ impl OpenAiProvider {
     #[must_use]
     pub fn with_base_url(base_url: String) -> Self {
         Self {
-            client: build_client(false),
+            client: HttpClient::new(false),
             base_url,
         }
     }
 }
 
-fn build_client(https_only: bool) -> Client {
-    Client::builder()
-        .timeout(REQUEST_TIMEOUT)
-        .connect_timeout(CONNECT_TIMEOUT)
-        .user_agent("tars/0.4")
-        .https_only(https_only)
-        .build()
-        .expect("reqwest client builds")
-}
-
 impl Default for OpenAiProvider {
     fn default() -> Self {
         Self::new()
@@ -94,11 +84,8 @@ impl Provider for OpenAiProvider {
         let url = format!("{}/v1/models", self.base_url);
         let resp = self
             .client
-            .get(&url)
-            .bearer_auth(key)
-            .send()
-            .await
-            .map_err(ProviderError::from)?;
+            .send(self.client.get(&url).bearer_auth(key))
+            .await?;
 
         match resp.status() {
             s if s.is_success() => Ok(ValidationResult {
@@ -117,11 +104,8 @@ impl Provider for OpenAiProvider {
         let url = format!("{}/v1/models", self.base_url);
         let resp = self
             .client
-            .get(&url)
-            .bearer_auth(key)
-            .send()
-            .await
-            .map_err(ProviderError::from)?;
+            .send(self.client.get(&url).bearer_auth(key))
+            .await?;
 
         match resp.status() {
             s if s.is_success() => {
@@ -148,12 +132,31 @@ impl Provider for OpenAiProvider {
     async fn get_balance(&self, _key: &str) -> Result<Option<Balance>, ProviderError> {
         Ok(None)
     }
+
+    async fn complete(
+        &self,
+        key: &str,
+        model: &str,
+        prompt: &str,
+        max_tokens: u32,
+    ) -> Result<Completion, ProviderError> {
+        let url = format!("{}/v1/chat/completions", self.base_url);
+        // `max_completion_tokens` supersedes `max_tokens`, which reasoning
+        // models reject.
+        let body = serde_json::json!({
+            "model": model,
+            "messages": [{"role": "user", "content": prompt}],
+            "max_completion_tokens": max_tokens,
+        });
+        let request = self.client.post(&url).bearer_auth(key).json(&body);
+        chat_completion(&self.client, request, "OpenAI").await
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use wiremock::matchers::{header, method, path};
+    use wiremock::matchers::{body_partial_json, header, method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
     fn provider(server: &MockServer) -> OpenAiProvider {
@@ -207,6 +210,28 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn validate_key_retries_then_reports_rate_limit() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "0"))
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let err = provider(&server).validate_key("sk-x").await.unwrap_err();
+        assert!(
+            matches!(
+                err,
+                ProviderError::RateLimited {
+                    retry_after: Some(_)
+                }
+            ),
+            "expected RateLimited, got {err:?}"
+        );
+    }
+
     #[tokio::test]
     async fn list_models_parses_data_array() {
         let server = MockServer::start().await;
@@ -258,4 +283,78 @@ mod tests {
         assert_eq!(p.id(), ProviderId::OpenAi);
         assert_eq!(p.metadata().display_name, "OpenAI");
     }
+
+    #[tokio::test]
+    async fn complete_returns_text_usage_and_latency() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .and(header("authorization", "Bearer sk-good"))
+            .and(body_partial_json(serde_json::json!({
+                "model": "gpt-4o-mini",
+                "max_completion_tokens": 16,
+                "messages": [{"role": "user", "content": "ping"}]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "model": "gpt-4o-mini-2024-07-18",
+                "choices": [{"index": 0, "message": {"role": "assistant", "content": "pong"}}],
+                "usage": {
+                    "prompt_tokens": 1200,
+                    "completion_tokens": 3,
+                    "prompt_tokens_details": {"cached_tokens": 1024}
+                }
+            })))
+            .mount(&server)
+            .await;
+
+        let completion = provider(&server)
+            .complete("sk-good", "gpt-4o-mini", "ping", 16)
+            .await
+            .unwrap();
+        assert_eq!(completion.text, "pong");
+        assert_eq!(completion.model, "gpt-4o-mini-2024-07-18");
+        assert_eq!(
+            completion.usage.input_tokens, 176,
+            "cached tokens split out"
+        );
+        assert_eq!(completion.usage.cache_read_tokens, 1024);
+        assert_eq!(completion.usage.output_tokens, 3);
+    }
+
+    #[tokio::test]
+    async fn complete_surfaces_model_error_message() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+                "error": {"message": "The model `gpt-9` does not exist or you do not have access to it."}
+            })))
+            .mount(&server)
+            .await;
+
+        let err = provider(&server)
+            .complete("sk-good", "gpt-9", "ping", 16)
+            .await
+            .unwrap_err();
+        match err {
+            ProviderError::Http(msg) => assert!(msg.contains("does not exist"), "{msg}"),
+            other => panic!("expected Http, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn complete_returns_unauthorized_on_401() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        let err = provider(&server)
+            .complete("sk-bad", "gpt-4o-mini", "ping", 16)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ProviderError::Unauthorized { status: 401 }));
+    }
 }
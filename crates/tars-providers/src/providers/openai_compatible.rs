@@ -0,0 +1,327 @@
+//! Generic provider for user-defined OpenAI-compatible endpoints.
+//!
+//! Covers local runtimes (Ollama, LM Studio, vLLM) and self-hosted gateways
+//! (`LiteLLM` proxy, internal routers). Everything is driven by a
+//! [`CustomProviderConfig`]: auth check and model discovery share
+//! `GET {base_url}{models_path}`, with the key sent according to
+//! [`AuthStyle`]. The `data[]` entries are parsed leniently — only `id` is
+//! required, and a context window is picked up from whichever of
+//! `context_window` (Groq-style), `context_length` (`OpenRouter`, LM Studio)
+//! or `max_model_len` (vLLM) the server reports. No balance.
+//!
+//! Unlike the hosted providers, HTTPS is not enforced: local runtimes almost
+//! always listen on plain `http://localhost`.
+
+use async_trait::async_trait;
+use reqwest::{RequestBuilder, StatusCode};
+use serde::Deserialize;
+
+use crate::{
+    error::ProviderError,
+    http::HttpClient,
+    provider::Provider,
+    registry::metadata_for,
+    types::{
+        AuthStyle, Balance, CustomProviderConfig, ModelInfo, ProviderId, ProviderMetadata,
+        ValidationResult,
+    },
+};
+
+/// Models path used when the config leaves it unset.
+pub const DEFAULT_MODELS_PATH: &str = "/models";
+
+pub struct OpenAiCompatibleProvider {
+    client: HttpClient,
+    config: CustomProviderConfig,
+}
+
+impl OpenAiCompatibleProvider {
+    /// Construct from a stored custom provider configuration.
+    ///
+    /// # Panics
+    /// Panics only if the underlying TLS stack fails to initialize.
+    #[must_use]
+    pub fn new(config: CustomProviderConfig) -> Self {
+        Self {
+            client: HttpClient::new(false),
+            config,
+        }
+    }
+
+    /// User-facing name from the configuration (the static
+    /// [`Provider::metadata`] only carries the generic "Custom" label).
+    #[must_use]
+    pub fn display_name(&self) -> &str {
+        &self.config.display_name
+    }
+
+    fn models_url(&self) -> Result<String, ProviderError> {
+        let base = self.config.base_url.trim().trim_end_matches('/');
+        if base.is_empty() {
+            return Err(ProviderError::UnknownProvider(
+                "custom provider has no base URL configured".to_string(),
+            ));
+        }
+        let path = self
+            .config
+            .models_path
+            .as_deref()
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .unwrap_or(DEFAULT_MODELS_PATH);
+        if path.starts_with('/') {
+            Ok(format!("{base}{path}"))
+        } else {
+            Ok(format!("{base}/{path}"))
+        }
+    }
+
+    fn authorize(&self, request: RequestBuilder, key: &str) -> RequestBuilder {
+        match &self.config.auth {
+            AuthStyle::Bearer => request.bearer_auth(key),
+            AuthStyle::Header { name } => request.header(name.as_str(), key),
+            AuthStyle::None => request,
+        }
+    }
+
+    async fn get_models(&self, key: &str) -> Result<reqwest::Response, ProviderError> {
+        let url = self.models_url()?;
+        self.client
+            .send(self.authorize(self.client.get(&url), key))
+            .await
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelsResponse {
+    data: Vec<ModelDto>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelDto {
+    id: String,
+    #[serde(default, alias = "context_length", alias = "max_model_len")]
+    context_window: Option<u32>,
+}
+
+#[async_trait]
+impl Provider for OpenAiCompatibleProvider {
+    fn id(&self) -> ProviderId {
+        ProviderId::Custom
+    }
+
+    fn metadata(&self) -> &'static ProviderMetadata {
+        metadata_for(ProviderId::Custom)
+    }
+
+    async fn validate_key(&self, key: &str) -> Result<ValidationResult, ProviderError> {
+        let resp = self.get_models(key).await?;
+        match resp.status() {
+            s if s.is_success() => Ok(ValidationResult {
+                valid: true,
+                message: None,
+            }),
+            status @ (StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN) => Ok(ValidationResult {
+                valid: false,
+                message: Some(format!(
+                    "Key rejected by {} (HTTP {})",
+                    self.config.display_name,
+                    status.as_u16()
+                )),
+            }),
+            other => Err(ProviderError::Http(format!(
+                "{} returned {other}",
+                self.config.display_name
+            ))),
+        }
+    }
+
+    async fn list_models(&self, key: &str) -> Result<Vec<ModelInfo>, ProviderError> {
+        let resp = self.get_models(key).await?;
+        match resp.status() {
+            s if s.is_success() => {
+                let parsed: ModelsResponse = resp.json().await.map_err(ProviderError::from)?;
+                Ok(parsed
+                    .data
+                    .into_iter()
+                    .map(|m| ModelInfo {
+                        id: m.id,
+                        display_name: None,
+                        context_window: m.context_window,
+                        input_price_per_million: None,
+                        output_price_per_million: None,
+                    })
+                    .collect())
+            }
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(ProviderError::Unauthorized {
+                status: resp.status().as_u16(),
+            }),
+            other => Err(ProviderError::Http(format!(
+                "{} returned {other}",
+                self.config.display_name
+            ))),
+        }
+    }
+
+    async fn get_balance(&self, _key: &str) -> Result<Option<Balance>, ProviderError> {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn config(
+        base_url: String,
+        auth: AuthStyle,
+        models_path: Option<&str>,
+    ) -> CustomProviderConfig {
+        CustomProviderConfig {
+            display_name: "Gateway".into(),
+            base_url,
+            auth,
+            models_path: models_path.map(str::to_string),
+        }
+    }
+
+    fn provider(server: &MockServer, auth: AuthStyle) -> OpenAiCompatibleProvider {
+        OpenAiCompatibleProvider::new(config(format!("{}/v1/", server.uri()), auth, None))
+    }
+
+    #[tokio::test]
+    async fn validate_key_sends_bearer_to_default_models_path() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .and(header("authorization", "Bearer sk-gw"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": []
+            })))
+            .mount(&server)
+            .await;
+
+        let r = provider(&server, AuthStyle::Bearer)
+            .validate_key("sk-gw")
+            .await
+            .unwrap();
+        assert!(r.valid);
+    }
+
+    #[tokio::test]
+    async fn validate_key_uses_named_header() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .and(header("api-key", "azure-key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": []
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        let auth = AuthStyle::Header {
+            name: "api-key".into(),
+        };
+        let r = provider(&server, auth)
+            .validate_key("azure-key")
+            .await
+            .unwrap();
+        assert!(r.valid);
+    }
+
+    #[tokio::test]
+    async fn validate_key_invalid_on_401_names_provider() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        let r = provider(&server, AuthStyle::Bearer)
+            .validate_key("bad")
+            .await
+            .unwrap();
+        assert!(!r.valid);
+        assert!(r.message.unwrap().contains("Gateway"));
+    }
+
+    #[tokio::test]
+    async fn list_models_honours_custom_path_and_context_aliases() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v0/models"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "object": "list",
+                "data": [
+                    {"id": "llama3.2:latest", "object": "model", "owned_by": "library"},
+                    {"id": "qwen2.5-coder", "max_model_len": 32_768},
+                    {"id": "phi-4", "context_length": 16_384}
+                ]
+            })))
+            .mount(&server)
+            .await;
+
+        let p = OpenAiCompatibleProvider::new(config(
+            server.uri(),
+            AuthStyle::None,
+            Some("api/v0/models"),
+        ));
+        let models = p.list_models("").await.unwrap();
+        let got: Vec<_> = models
+            .iter()
+            .map(|m| (m.id.as_str(), m.context_window))
+            .collect();
+        assert_eq!(
+            got,
+            vec![
+                ("llama3.2:latest", None),
+                ("qwen2.5-coder", Some(32_768)),
+                ("phi-4", Some(16_384)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn list_models_returns_unauthorized_on_403() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(403))
+            .mount(&server)
+            .await;
+
+        let err = provider(&server, AuthStyle::Bearer)
+            .list_models("x")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ProviderError::Unauthorized { status: 403 }));
+    }
+
+    #[tokio::test]
+    async fn empty_base_url_is_reported_not_sent() {
+        let p = OpenAiCompatibleProvider::new(config(String::new(), AuthStyle::Bearer, None));
+        let err = p.list_models("x").await.unwrap_err();
+        assert!(matches!(err, ProviderError::UnknownProvider(_)));
+    }
+
+    #[test]
+    fn metadata_is_generic_custom_entry() {
+        let p = OpenAiCompatibleProvider::new(config(
+            "http://localhost:11434/v1".into(),
+            AuthStyle::None,
+            None,
+        ));
+        assert_eq!(p.id(), ProviderId::Custom);
+        assert_eq!(p.display_name(), "Gateway");
+        assert!(p.metadata().supports_models);
+        assert!(!p.metadata().supports_balance);
+    }
+}
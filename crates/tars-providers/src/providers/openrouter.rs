@@ -1,26 +1,37 @@
 //! `OpenRouter` provider.
 //!
-//! Simple-storage provider: auth-check only via `GET /api/v1/auth/key` with
-//! `Authorization: Bearer`. This endpoint returns rate-limit/credit info
-//! about the key itself, but we only use it as an auth check here.
+//! Auth check uses `GET /api/v1/auth/key` with `Authorization: Bearer`.
+//!
+//! Model discovery uses `GET /api/v1/models`, which is the richest catalogue
+//! of any provider we support: each entry carries a display name, the
+//! `context_length`, and `pricing.prompt` / `pricing.completion` as decimal
+//! strings in USD per token. Prices are converted to per-1M-token; negative
+//! sentinels (used by routers such as `openrouter/auto` whose price depends
+//! on the model picked at request time) are dropped.
+//!
+//! Balance combines two endpoints. `GET /api/v1/credits` reports the
+//! account's `total_credits` and `total_usage`; it needs a management-capable
+//! key, so a 401/403 there just means "not available" rather than a bad key.
+//! `GET /api/v1/key` reports the key's own `limit` and `limit_remaining`
+//! (null for keys without a spending cap). The reported amount is the smaller
+//! of the two remainders, since either one can stop requests.
 
 use async_trait::async_trait;
-use reqwest::{Client, StatusCode};
-use std::time::Duration;
+use reqwest::StatusCode;
+use serde::Deserialize;
 
 use crate::{
     error::ProviderError,
+    http::HttpClient,
     provider::Provider,
     registry::metadata_for,
     types::{Balance, ModelInfo, ProviderId, ProviderMetadata, ValidationResult},
 };
 
 const DEFAULT_BASE_URL: &str = "https://openrouter.ai";
-const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
-const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct OpenRouterProvider {
-    client: Client,
+    client: HttpClient,
     base_url: String,
 }
 
@@ -28,7 +39,7 @@ impl OpenRouterProvider {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            client: build_client(true),
+            client: HttpClient::new(true),
             base_url: DEFAULT_BASE_URL.to_string(),
         }
     }
@@ -36,28 +47,75 @@ impl OpenRouterProvider {
     #[must_use]
     pub fn with_base_url(base_url: String) -> Self {
         Self {
-            client: build_client(false),
+            client: HttpClient::new(false),
             base_url,
         }
     }
 }
 
-fn build_client(https_only: bool) -> Client {
-    Client::builder()
-        .timeout(REQUEST_TIMEOUT)
-        .connect_timeout(CONNECT_TIMEOUT)
-        .user_agent("tars/0.4")
-        .https_only(https_only)
-        .build()
-        .expect("reqwest client builds")
-}
-
 impl Default for OpenRouterProvider {
     fn default() -> Self {
         Self::new()
     }
 }
 
+#[derive(Debug, Deserialize)]
+struct ModelsResponse {
+    data: Vec<ModelDto>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelDto {
+    id: String,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    context_length: Option<u32>,
+    #[serde(default)]
+    pricing: Option<PricingDto>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PricingDto {
+    #[serde(default)]
+    prompt: Option<String>,
+    #[serde(default)]
+    completion: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DataEnvelope<T> {
+    data: T,
+}
+
+#[derive(Debug, Deserialize)]
+struct CreditsDto {
+    total_credits: f64,
+    total_usage: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyDto {
+    #[serde(default)]
+    limit_remaining: Option<f64>,
+}
+
+/// Remaining USD given the account credits and the key's own cap; `None`
+/// when neither side reports a limit.
+fn remaining_credit(credits: Option<&CreditsDto>, key: &KeyDto) -> Option<f64> {
+    let account = credits.map(|c| c.total_credits - c.total_usage);
+    match (account, key.limit_remaining) {
+        (Some(a), Some(k)) => Some(a.min(k)),
+        (a, k) => a.or(k),
+    }
+}
+
+/// Convert an `OpenRouter` per-token price string into USD per 1M tokens.
+fn per_million(raw: Option<&str>) -> Option<f64> {
+    let per_token: f64 = raw?.trim().parse().ok()?;
+    (per_token.is_finite() && per_token >= 0.0).then_some(per_token * 1_000_000.0)
+}
+
 #[async_trait]
 impl Provider for OpenRouterProvider {
     fn id(&self) -> ProviderId {
@@ -72,11 +130,8 @@ impl Provider for OpenRouterProvider {
         let url = format!("{}/api/v1/auth/key", self.base_url);
         let resp = self
             .client
-            .get(&url)
-            .bearer_auth(key)
-            .send()
-            .await
-            .map_err(ProviderError::from)?;
+            .send(self.client.get(&url).bearer_auth(key))
+            .await?;
 
         let status = resp.status();
         match status {
@@ -95,12 +150,85 @@ impl Provider for OpenRouterProvider {
         }
     }
 
-    async fn list_models(&self, _key: &str) -> Result<Vec<ModelInfo>, ProviderError> {
-        Err(ProviderError::Unsupported)
+    async fn list_models(&self, key: &str) -> Result<Vec<ModelInfo>, ProviderError> {
+        let url = format!("{}/api/v1/models", self.base_url);
+        let resp = self
+            .client
+            .send(self.client.get(&url).bearer_auth(key))
+            .await?;
+
+        match resp.status() {
+            s if s.is_success() => {
+                let parsed: ModelsResponse = resp.json().await.map_err(ProviderError::from)?;
+                Ok(parsed
+                    .data
+                    .into_iter()
+                    .map(|m| {
+                        let pricing = m.pricing.as_ref();
+                        ModelInfo {
+                            input_price_per_million: per_million(
+                                pricing.and_then(|p| p.prompt.as_deref()),
+                            ),
+                            output_price_per_million: per_million(
+                                pricing.and_then(|p| p.completion.as_deref()),
+                            ),
+                            id: m.id,
+                            display_name: m.name,
+                            context_window: m.context_length,
+                        }
+                    })
+                    .collect())
+            }
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(ProviderError::Unauthorized {
+                status: resp.status().as_u16(),
+            }),
+            other => Err(ProviderError::Http(format!("OpenRouter returned {other}"))),
+        }
     }
 
-    async fn get_balance(&self, _key: &str) -> Result<Option<Balance>, ProviderError> {
-        Ok(None)
+    async fn get_balance(&self, key: &str) -> Result<Option<Balance>, ProviderError> {
+        let key_url = format!("{}/api/v1/key", self.base_url);
+        let resp = self
+            .client
+            .send(self.client.get(&key_url).bearer_auth(key))
+            .await?;
+        let key_raw: serde_json::Value = match resp.status() {
+            s if s.is_success() => resp.json().await.map_err(ProviderError::from)?,
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                return Err(ProviderError::Unauthorized {
+                    status: resp.status().as_u16(),
+                });
+            }
+            other => return Err(ProviderError::Http(format!("OpenRouter returned {other}"))),
+        };
+        let key_info: DataEnvelope<KeyDto> = serde_json::from_value(key_raw.clone())
+            .map_err(|e| ProviderError::Parse(e.to_string()))?;
+
+        let credits_url = format!("{}/api/v1/credits", self.base_url);
+        let resp = self
+            .client
+            .send(self.client.get(&credits_url).bearer_auth(key))
+            .await?;
+        let credits_raw: Option<serde_json::Value> = match resp.status() {
+            s if s.is_success() => Some(resp.json().await.map_err(ProviderError::from)?),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => None,
+            other => return Err(ProviderError::Http(format!("OpenRouter returned {other}"))),
+        };
+        let credits = credits_raw
+            .clone()
+            .map(serde_json::from_value::<DataEnvelope<CreditsDto>>)
+            .transpose()
+            .map_err(|e| ProviderError::Parse(e.to_string()))?;
+
+        let Some(amount) = remaining_credit(credits.as_ref().map(|c| &c.data), &key_info.data)
+        else {
+            return Ok(None);
+        };
+        Ok(Some(Balance {
+            currency: "USD".to_string(),
+            amount,
+            raw: serde_json::json!({ "credits": credits_raw, "key": key_raw }),
+        }))
     }
 }
 
@@ -158,24 +286,174 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn list_models_returns_unsupported() {
+    async fn list_models_parses_pricing_and_context_length() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/models"))
+            .and(header("authorization", "Bearer sk-or-good"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [
+                    {
+                        "id": "anthropic/claude-sonnet-4",
+                        "name": "Anthropic: Claude Sonnet 4",
+                        "context_length": 200_000,
+                        "pricing": {
+                            "prompt": "0.000003",
+                            "completion": "0.000015",
+                            "request": "0"
+                        }
+                    },
+                    {
+                        "id": "openrouter/auto",
+                        "name": "Auto Router",
+                        "context_length": 2_000_000,
+                        "pricing": { "prompt": "-1", "completion": "-1" }
+                    },
+                    {"id": "bare/model"}
+                ]
+            })))
+            .mount(&server)
+            .await;
+
+        let models = provider(&server).list_models("sk-or-good").await.unwrap();
+        assert_eq!(models.len(), 3);
+
+        let sonnet = &models[0];
+        assert_eq!(sonnet.id, "anthropic/claude-sonnet-4");
+        assert_eq!(
+            sonnet.display_name.as_deref(),
+            Some("Anthropic: Claude Sonnet 4")
+        );
+        assert_eq!(sonnet.context_window, Some(200_000));
+        assert!((sonnet.input_price_per_million.unwrap() - 3.0).abs() < 1e-9);
+        assert!((sonnet.output_price_per_million.unwrap() - 15.0).abs() < 1e-9);
+
+        let auto = &models[1];
+        assert_eq!(
+            auto.input_price_per_million, None,
+            "negative sentinel dropped"
+        );
+        assert_eq!(auto.output_price_per_million, None);
+
+        let bare = &models[2];
+        assert_eq!(bare.display_name, None);
+        assert_eq!(bare.context_window, None);
+        assert_eq!(bare.input_price_per_million, None);
+    }
+
+    #[tokio::test]
+    async fn list_models_returns_unauthorized_on_401() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/models"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        let err = provider(&server)
+            .list_models("sk-or-bad")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ProviderError::Unauthorized { status: 401 }));
+    }
+
+    #[test]
+    fn per_million_rejects_garbage() {
+        assert_eq!(per_million(None), None);
+        assert_eq!(per_million(Some("abc")), None);
+        assert_eq!(per_million(Some("NaN")), None);
+        assert_eq!(per_million(Some("0")), Some(0.0));
+    }
+
+    #[tokio::test]
+    async fn get_balance_takes_smaller_of_account_and_key_remainder() {
         let server = MockServer::start().await;
-        let err = provider(&server).list_models("x").await.unwrap_err();
-        assert!(matches!(err, ProviderError::Unsupported));
+        Mock::given(method("GET"))
+            .and(path("/api/v1/key"))
+            .and(header("authorization", "Bearer sk-or-good"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": { "limit": 10.0, "limit_remaining": 4.5, "usage": 5.5 }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/credits"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": { "total_credits": 25.0, "total_usage": 12.0 }
+            })))
+            .mount(&server)
+            .await;
+
+        let bal = provider(&server)
+            .get_balance("sk-or-good")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(bal.currency, "USD");
+        assert!((bal.amount - 4.5).abs() < 1e-9);
+        assert_eq!(bal.raw["credits"]["data"]["total_credits"], 25.0);
+        assert_eq!(bal.raw["key"]["data"]["limit"], 10.0);
     }
 
     #[tokio::test]
-    async fn get_balance_returns_none() {
+    async fn get_balance_falls_back_to_key_limit_without_credits_access() {
         let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": { "limit": 5.0, "limit_remaining": 3.25, "usage": 1.75 }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/credits"))
+            .respond_with(ResponseTemplate::new(403))
+            .mount(&server)
+            .await;
+
+        let bal = provider(&server).get_balance("x").await.unwrap().unwrap();
+        assert!((bal.amount - 3.25).abs() < 1e-9);
+        assert!(bal.raw["credits"].is_null());
+    }
+
+    #[tokio::test]
+    async fn get_balance_none_when_uncapped_and_no_credits_access() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": { "limit": null, "limit_remaining": null, "usage": 0.5 }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/credits"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
         assert!(provider(&server).get_balance("x").await.unwrap().is_none());
     }
 
+    #[tokio::test]
+    async fn get_balance_unauthorized_key() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/key"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        let err = provider(&server).get_balance("bad").await.unwrap_err();
+        assert!(matches!(err, ProviderError::Unauthorized { status: 401 }));
+    }
+
     #[test]
     fn metadata_matches_registry() {
         let p = OpenRouterProvider::new();
         assert_eq!(p.id(), ProviderId::OpenRouter);
         assert_eq!(p.metadata().display_name, "OpenRouter");
-        assert!(!p.metadata().supports_models);
-        assert!(!p.metadata().supports_balance);
+        assert!(p.metadata().supports_models);
+        assert!(p.metadata().supports_balance);
     }
 }
@@ -5,6 +5,10 @@
 //! therefore store the key but return `ProviderError::Unsupported` from
 //! `validate_key` to signal "unverifiable". Callers should surface this to
 //! the user as a badge rather than a hard error.
+//!
+//! There is no model-listing endpoint either, so `list_models` returns the
+//! Sonar family from Perplexity's published model table. Prices are left
+//! empty and filled in from `LiteLLM` by the pricing refresh.
 
 use async_trait::async_trait;
 
@@ -15,6 +19,15 @@ use crate::{
     types::{Balance, ModelInfo, ProviderId, ProviderMetadata, ValidationResult},
 };
 
+/// `(model id, context window)` for each model in Perplexity's model table.
+const KNOWN_MODELS: &[(&str, u32)] = &[
+    ("sonar", 128_000),
+    ("sonar-pro", 200_000),
+    ("sonar-reasoning", 128_000),
+    ("sonar-reasoning-pro", 128_000),
+    ("sonar-deep-research", 128_000),
+];
+
 #[derive(Default)]
 pub struct PerplexityProvider;
 
@@ -40,7 +53,16 @@ impl Provider for PerplexityProvider {
     }
 
     async fn list_models(&self, _key: &str) -> Result<Vec<ModelInfo>, ProviderError> {
-        Err(ProviderError::Unsupported)
+        Ok(KNOWN_MODELS
+            .iter()
+            .map(|&(id, context_window)| ModelInfo {
+                id: id.to_string(),
+                display_name: None,
+                context_window: Some(context_window),
+                input_price_per_million: None,
+                output_price_per_million: None,
+            })
+            .collect())
     }
 
     async fn get_balance(&self, _key: &str) -> Result<Option<Balance>, ProviderError> {
@@ -63,10 +85,13 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn list_models_returns_unsupported() {
+    async fn list_models_returns_sonar_family() {
         let p = PerplexityProvider::new();
-        let err = p.list_models("x").await.unwrap_err();
-        assert!(matches!(err, ProviderError::Unsupported));
+        let models = p.list_models("x").await.unwrap();
+        assert_eq!(models.len(), KNOWN_MODELS.len());
+        let pro = models.iter().find(|m| m.id == "sonar-pro").unwrap();
+        assert_eq!(pro.context_window, Some(200_000));
+        assert!(models.iter().all(|m| m.id.starts_with("sonar")));
     }
 
     #[tokio::test]
@@ -80,7 +105,7 @@ mod tests {
         let p = PerplexityProvider::new();
         assert_eq!(p.id(), ProviderId::Perplexity);
         assert_eq!(p.metadata().display_name, "Perplexity");
-        assert!(!p.metadata().supports_models);
+        assert!(p.metadata().supports_models);
         assert!(!p.metadata().supports_balance);
     }
 }
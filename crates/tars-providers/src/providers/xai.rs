@@ -1,27 +1,26 @@
 //! xAI (Grok) provider.
 //!
-//! Simple-storage provider: auth-check only via `GET /v1/api-key` with
-//! `Authorization: Bearer`. This xAI-specific endpoint returns metadata
-//! about the key itself (name, owner, redacted id). No model list surfaced,
-//! no balance.
+//! Auth check uses `GET /v1/api-key` with `Authorization: Bearer`. This
+//! xAI-specific endpoint returns metadata about the key itself (name, owner,
+//! redacted id). Model discovery uses the OpenAI-compatible `GET /v1/models`,
+//! which carries ids only. No balance.
 
 use async_trait::async_trait;
-use reqwest::{Client, StatusCode};
-use std::time::Duration;
+use reqwest::StatusCode;
+use serde::Deserialize;
 
 use crate::{
     error::ProviderError,
+    http::HttpClient,
     provider::Provider,
     registry::metadata_for,
     types::{Balance, ModelInfo, ProviderId, ProviderMetadata, ValidationResult},
 };
 
 const DEFAULT_BASE_URL: &str = "https://api.x.ai";
-const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
-const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct XAiProvider {
-    client: Client,
+    client: HttpClient,
     base_url: String,
 }
 
@@ -29,7 +28,7 @@ impl XAiProvider {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            client: build_client(true),
+            client: HttpClient::new(true),
             base_url: DEFAULT_BASE_URL.to_string(),
         }
     }
@@ -37,28 +36,28 @@ impl XAiProvider {
     #[must_use]
     pub fn with_base_url(base_url: String) -> Self {
         Self {
-            client: build_client(false),
+            client: HttpClient::new(false),
             base_url,
         }
     }
 }
 
-fn build_client(https_only: bool) -> Client {
-    Client::builder()
-        .timeout(REQUEST_TIMEOUT)
-        .connect_timeout(CONNECT_TIMEOUT)
-        .user_agent("tars/0.4")
-        .https_only(https_only)
-        .build()
-        .expect("reqwest client builds")
-}
-
 impl Default for XAiProvider {
     fn default() -> Self {
         Self::new()
     }
 }
 
+#[derive(Debug, Deserialize)]
+struct ModelsResponse {
+    data: Vec<ModelDto>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelDto {
+    id: String,
+}
+
 #[async_trait]
 impl Provider for XAiProvider {
     fn id(&self) -> ProviderId {
@@ -73,11 +72,8 @@ impl Provider for XAiProvider {
         let url = format!("{}/v1/api-key", self.base_url);
         let resp = self
             .client
-            .get(&url)
-            .bearer_auth(key)
-            .send()
-            .await
-            .map_err(ProviderError::from)?;
+            .send(self.client.get(&url).bearer_auth(key))
+            .await?;
 
         let status = resp.status();
         match status {
@@ -93,8 +89,33 @@ impl Provider for XAiProvider {
         }
     }
 
-    async fn list_models(&self, _key: &str) -> Result<Vec<ModelInfo>, ProviderError> {
-        Err(ProviderError::Unsupported)
+    async fn list_models(&self, key: &str) -> Result<Vec<ModelInfo>, ProviderError> {
+        let url = format!("{}/v1/models", self.base_url);
+        let resp = self
+            .client
+            .send(self.client.get(&url).bearer_auth(key))
+            .await?;
+
+        match resp.status() {
+            s if s.is_success() => {
+                let parsed: ModelsResponse = resp.json().await.map_err(ProviderError::from)?;
+                Ok(parsed
+                    .data
+                    .into_iter()
+                    .map(|m| ModelInfo {
+                        id: m.id,
+                        display_name: None,
+                        context_window: None,
+                        input_price_per_million: None,
+                        output_price_per_million: None,
+                    })
+                    .collect())
+            }
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(ProviderError::Unauthorized {
+                status: resp.status().as_u16(),
+            }),
+            other => Err(ProviderError::Http(format!("xAI returned {other}"))),
+        }
     }
 
     async fn get_balance(&self, _key: &str) -> Result<Option<Balance>, ProviderError> {
@@ -157,10 +178,37 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn list_models_returns_unsupported() {
+    async fn list_models_parses_data_array() {
         let server = MockServer::start().await;
-        let err = provider(&server).list_models("x").await.unwrap_err();
-        assert!(matches!(err, ProviderError::Unsupported));
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .and(header("authorization", "Bearer xai-good"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "object": "list",
+                "data": [
+                    {"id": "grok-4", "object": "model", "owned_by": "xai"},
+                    {"id": "grok-3-mini", "object": "model", "owned_by": "xai"}
+                ]
+            })))
+            .mount(&server)
+            .await;
+
+        let models = provider(&server).list_models("xai-good").await.unwrap();
+        let ids: Vec<_> = models.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, vec!["grok-4", "grok-3-mini"]);
+    }
+
+    #[tokio::test]
+    async fn list_models_returns_unauthorized_on_401() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        let err = provider(&server).list_models("xai-bad").await.unwrap_err();
+        assert!(matches!(err, ProviderError::Unauthorized { status: 401 }));
     }
 
     #[tokio::test]
@@ -174,7 +222,7 @@ mod tests {
         let p = XAiProvider::new();
         assert_eq!(p.id(), ProviderId::XAi);
         assert_eq!(p.metadata().display_name, "xAI");
-        assert!(!p.metadata().supports_models);
+        assert!(p.metadata().supports_models);
         assert!(!p.metadata().supports_balance);
     }
 }
@@ -9,6 +9,7 @@ const OPENAI: ProviderMetadata = ProviderMetadata {
     key_format_hint: "sk-...",
     supports_models: true,
     supports_balance: false,
+    supports_completion: true,
 };
 
 const ANTHROPIC: ProviderMetadata = ProviderMetadata {
@@ -18,6 +19,7 @@ const ANTHROPIC: ProviderMetadata = ProviderMetadata {
     key_format_hint: "sk-ant-...",
     supports_models: true,
     supports_balance: false,
+    supports_completion: true,
 };
 
 const GEMINI: ProviderMetadata = ProviderMetadata {
@@ -27,6 +29,7 @@ const GEMINI: ProviderMetadata = ProviderMetadata {
     key_format_hint: "AIza...",
     supports_models: true,
     supports_balance: false,
+    supports_completion: true,
 };
 
 const DEEPSEEK: ProviderMetadata = ProviderMetadata {
@@ -36,6 +39,7 @@ const DEEPSEEK: ProviderMetadata = ProviderMetadata {
     key_format_hint: "sk-...",
     supports_models: true,
     supports_balance: true,
+    supports_completion: true,
 };
 
 const BRAVE_SEARCH: ProviderMetadata = ProviderMetadata {
@@ -45,6 +49,7 @@ const BRAVE_SEARCH: ProviderMetadata = ProviderMetadata {
     key_format_hint: "BSA...",
     supports_models: false,
     supports_balance: false,
+    supports_completion: false,
 };
 
 const ELEVENLABS: ProviderMetadata = ProviderMetadata {
@@ -53,7 +58,8 @@ const ELEVENLABS: ProviderMetadata = ProviderMetadata {
     docs_url: "https://elevenlabs.io/app/settings/api-keys",
     key_format_hint: "sk_...",
     supports_models: false,
-    supports_balance: false,
+    supports_balance: true,
+    supports_completion: false,
 };
 
 const GROQ: ProviderMetadata = ProviderMetadata {
@@ -61,8 +67,9 @@ const GROQ: ProviderMetadata = ProviderMetadata {
     display_name: "Groq",
     docs_url: "https://console.groq.com/keys",
     key_format_hint: "gsk_...",
-    supports_models: false,
+    supports_models: true,
     supports_balance: false,
+    supports_completion: false,
 };
 
 const MISTRAL: ProviderMetadata = ProviderMetadata {
@@ -70,8 +77,9 @@ const MISTRAL: ProviderMetadata = ProviderMetadata {
     display_name: "Mistral",
     docs_url: "https://console.mistral.ai/api-keys",
     key_format_hint: "...",
-    supports_models: false,
+    supports_models: true,
     supports_balance: false,
+    supports_completion: false,
 };
 
 const XAI: ProviderMetadata = ProviderMetadata {
@@ -79,8 +87,9 @@ const XAI: ProviderMetadata = ProviderMetadata {
     display_name: "xAI",
     docs_url: "https://console.x.ai",
     key_format_hint: "xai-...",
-    supports_models: false,
+    supports_models: true,
     supports_balance: false,
+    supports_completion: false,
 };
 
 const OPENROUTER: ProviderMetadata = ProviderMetadata {
@@ -88,8 +97,9 @@ const OPENROUTER: ProviderMetadata = ProviderMetadata {
     display_name: "OpenRouter",
     docs_url: "https://openrouter.ai/keys",
     key_format_hint: "sk-or-...",
-    supports_models: false,
-    supports_balance: false,
+    supports_models: true,
+    supports_balance: true,
+    supports_completion: false,
 };
 
 const PERPLEXITY: ProviderMetadata = ProviderMetadata {
@@ -97,8 +107,21 @@ const PERPLEXITY: ProviderMetadata = ProviderMetadata {
     display_name: "Perplexity",
     docs_url: "https://www.perplexity.ai/settings/api",
     key_format_hint: "pplx-...",
-    supports_models: false,
+    supports_models: true,
+    supports_balance: false,
+    supports_completion: false,
+};
+
+/// Shared metadata for every user-defined OpenAI-compatible provider. The
+/// per-instance display name and base URL live in [`crate::CustomProviderConfig`].
+const CUSTOM: ProviderMetadata = ProviderMetadata {
+    id: ProviderId::Custom,
+    display_name: "Custom (OpenAI-compatible)",
+    docs_url: "https://platform.openai.com/docs/api-reference/models/list",
+    key_format_hint: "...",
+    supports_models: true,
     supports_balance: false,
+    supports_completion: false,
 };
 
 /// Get static metadata for a provider
@@ -116,6 +139,7 @@ pub const fn metadata_for(id: ProviderId) -> &'static ProviderMetadata {
         ProviderId::XAi => &XAI,
         ProviderId::OpenRouter => &OPENROUTER,
         ProviderId::Perplexity => &PERPLEXITY,
+        ProviderId::Custom => &CUSTOM,
     }
 }
 
@@ -146,13 +170,38 @@ mod tests {
     }
 
     #[test]
-    fn deepseek_is_only_provider_with_balance() {
+    fn balance_providers() {
         let with_balance: Vec<_> = all_metadata()
             .iter()
             .filter(|m| m.supports_balance)
             .map(|m| m.id)
             .collect();
-        assert_eq!(with_balance, vec![ProviderId::Deepseek]);
+        assert_eq!(
+            with_balance,
+            vec![
+                ProviderId::Deepseek,
+                ProviderId::ElevenLabs,
+                ProviderId::OpenRouter
+            ]
+        );
+    }
+
+    #[test]
+    fn completion_providers() {
+        let with_completion: Vec<_> = all_metadata()
+            .iter()
+            .filter(|m| m.supports_completion)
+            .map(|m| m.id)
+            .collect();
+        assert_eq!(
+            with_completion,
+            vec![
+                ProviderId::OpenAi,
+                ProviderId::Anthropic,
+                ProviderId::Gemini,
+                ProviderId::Deepseek
+            ]
+        );
     }
 
     #[test]
@@ -162,6 +211,11 @@ mod tests {
             ProviderId::Anthropic,
             ProviderId::Gemini,
             ProviderId::Deepseek,
+            ProviderId::Groq,
+            ProviderId::Mistral,
+            ProviderId::XAi,
+            ProviderId::OpenRouter,
+            ProviderId::Perplexity,
         ];
         for id in model_providers {
             assert!(
@@ -172,26 +226,14 @@ mod tests {
     }
 
     #[test]
-    fn simple_storage_providers_skip_model_and_balance() {
-        let simple = [
-            ProviderId::BraveSearch,
-            ProviderId::ElevenLabs,
-            ProviderId::Groq,
-            ProviderId::Mistral,
-            ProviderId::XAi,
-            ProviderId::OpenRouter,
-            ProviderId::Perplexity,
-        ];
+    fn simple_storage_providers_skip_model_discovery() {
+        let simple = [ProviderId::BraveSearch, ProviderId::ElevenLabs];
         for id in simple {
             let m = metadata_for(id);
             assert!(
                 !m.supports_models,
                 "{id:?} simple-storage must not claim model discovery"
             );
-            assert!(
-                !m.supports_balance,
-                "{id:?} simple-storage must not claim balance"
-            );
             assert!(!m.display_name.is_empty());
             assert!(!m.docs_url.is_empty());
             assert!(!m.key_format_hint.is_empty());
@@ -35,6 +35,32 @@ pub struct ValidationResult {
     pub message: Option<String>,
 }
 
+/// Token counts reported for one completion.
+///
+/// `input_tokens` excludes prompt tokens served from or written to the
+/// prompt cache, so each field maps to exactly one price column.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    #[serde(default)]
+    pub cache_read_tokens: u64,
+    #[serde(default)]
+    pub cache_write_tokens: u64,
+}
+
+/// Result of a one-shot completion (see [`crate::Provider::complete`])
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Completion {
+    /// Model that served the request, as reported by the provider (may be a
+    /// dated snapshot of the requested alias)
+    pub model: String,
+    pub text: String,
+    pub usage: TokenUsage,
+    /// Wall time of the HTTP round trip, including retries
+    pub latency_ms: u64,
+}
+
 /// Stable identifier for each supported provider
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
@@ -55,6 +81,50 @@ pub enum ProviderId {
     #[serde(rename = "openrouter")]
     OpenRouter,
     Perplexity,
+    /// A user-defined OpenAI-compatible endpoint (Ollama, LM Studio, vLLM,
+    /// self-hosted gateways). Not part of [`ProviderId::ALL`] and never
+    /// returned by [`ProviderId::parse`]: each configured instance is
+    /// addressed by a `custom:<slug>` string (see [`custom_provider_slug`])
+    /// and built with [`crate::factory::custom_provider`].
+    Custom,
+}
+
+/// Prefix marking a `provider_id` string as a user-defined custom provider.
+pub const CUSTOM_PROVIDER_PREFIX: &str = "custom:";
+
+/// Extract the slug from a `custom:<slug>` provider id string.
+///
+/// Returns `None` for built-in provider ids and for an empty slug.
+#[must_use]
+pub fn custom_provider_slug(provider_id: &str) -> Option<&str> {
+    provider_id
+        .strip_prefix(CUSTOM_PROVIDER_PREFIX)
+        .filter(|slug| !slug.is_empty())
+}
+
+/// How an OpenAI-compatible endpoint expects the API key to be sent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum AuthStyle {
+    /// `Authorization: Bearer <key>` (`OpenAI`, vLLM, `LiteLLM` proxy)
+    Bearer,
+    /// The raw key in a named header, e.g. `api-key` or `x-api-key`
+    Header { name: String },
+    /// No credentials sent (local Ollama / LM Studio)
+    None,
+}
+
+/// Connection settings for a user-defined OpenAI-compatible provider
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomProviderConfig {
+    pub display_name: String,
+    /// Base URL as it would be given to an `OpenAI` SDK, typically ending in
+    /// `/v1` (e.g. `http://localhost:11434/v1`).
+    pub base_url: String,
+    pub auth: AuthStyle,
+    /// Path appended to `base_url` for model discovery. `None` means the
+    /// `OpenAI` default, `/models`.
+    pub models_path: Option<String>,
 }
 
 impl ProviderId {
@@ -88,6 +158,7 @@ impl ProviderId {
             ProviderId::XAi => "xai",
             ProviderId::OpenRouter => "openrouter",
             ProviderId::Perplexity => "perplexity",
+            ProviderId::Custom => "custom",
         }
     }
 
@@ -120,6 +191,8 @@ pub struct ProviderMetadata {
     pub key_format_hint: &'static str,
     pub supports_models: bool,
     pub supports_balance: bool,
+    /// Implements [`crate::Provider::complete`]
+    pub supports_completion: bool,
 }
 
 #[cfg(test)]
@@ -185,6 +258,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn custom_is_not_parsed_or_listed() {
+        assert_eq!(ProviderId::parse("custom"), None);
+        assert!(!ProviderId::ALL.contains(&ProviderId::Custom));
+    }
+
+    #[test]
+    fn custom_provider_slug_strips_prefix() {
+        assert_eq!(custom_provider_slug("custom:ollama"), Some("ollama"));
+        assert_eq!(custom_provider_slug("custom:"), None);
+        assert_eq!(custom_provider_slug("openai"), None);
+    }
+
+    #[test]
+    fn auth_style_serialization_is_tagged() {
+        let json = serde_json::to_string(&AuthStyle::Header {
+            name: "api-key".into(),
+        })
+        .unwrap();
+        assert_eq!(json, r#"{"kind":"header","name":"api-key"}"#);
+        let json = serde_json::to_string(&AuthStyle::Bearer).unwrap();
+        assert_eq!(json, r#"{"kind":"bearer"}"#);
+    }
+
     #[test]
     fn validation_result_omits_none_message() {
         let r = ValidationResult {